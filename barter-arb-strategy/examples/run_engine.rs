@@ -17,6 +17,33 @@
 //!   POLY_MAKER_ADDRESS=0x...
 //!   SUPABASE_URL=...
 //!   SUPABASE_ANON_KEY=...
+//!   RECORD_OPPORTUNITIES=true  (optional, table from SUPABASE_OPPORTUNITIES_TABLE)
+//!   RECORD_SNAPSHOTS=true      (optional, orderbook events + opportunities to SNAPSHOT_DIR;
+//!                               rotate/compress/prune with RECORDER_ROTATE_MB,
+//!                               RECORDER_ROTATE_MINUTES, RECORDER_COMPRESSION=gzip|zstd,
+//!                               RECORDER_RETENTION; RECORDER_FORMAT=parquet with
+//!                               the `parquet` feature)
+//!   MARK_INVALID_PAIRS=true    (optional, requires SUPABASE_SERVICE_KEY)
+//!   REVALIDATE_AFTER_SCANS=20  (optional, with MARK_INVALID_PAIRS flag pairs whose
+//!                              prices contradict their inverse flag for this many scans)
+//!   PAIR_REFRESH_SECS=300      (optional, re-query pairs while running)
+//!   PAIR_SOURCE=rest|postgres  (optional, default rest; postgres needs DATABASE_URL
+//!                              and the `postgres` feature; writes still use Supabase)
+//!   DRY_RUN=true               (optional, log and record would-be orders without sending;
+//!                              never cancels exchange orders, at startup or shutdown)
+//!   STATE_SNAPSHOT_PATH=./state.json (optional, persist positions and capital across
+//!                              restarts; saved every STATE_SNAPSHOT_SECS, default 30)
+//!   CANCEL_UNKNOWN_ORDERS=true (optional, cancel open orders found at startup instead
+//!                              of adopting them)
+//!   RECONCILE_INTERVAL_SECS=300 (optional, re-check positions against the exchanges
+//!                              while running)
+//!   METRICS_PORT=9100          (optional, serve Prometheus metrics on /metrics)
+//!   POLY_SUBSCRIBE_NO=true     (optional, also subscribe Polymarket NO token books
+//!                              instead of deriving NO asks from YES bids)
+//!   KALSHI_SUBCENT_TICKERS=KX-A,KX-B (optional, price orders on these Kalshi markets in
+//!                              sub-cent ticks)
+//!   BALANCE_POLL_MS=2000       (optional, how often both clients poll balances)
+//!   ORDER_POLL_MS=1000         (optional, how often both clients poll order status)
 //!
 //!   cargo run -p barter-arb-strategy --example run_engine
 
@@ -24,29 +51,41 @@ use barter::engine::{Engine, state::builder::EngineStateBuilder, state::trading:
 use barter::execution::builder::ExecutionBuilder;
 use barter::system::builder::{AuditMode, EngineFeedMode, SystemBuild};
 use barter_arb_strategy::{
-    ArbitrageConfig, ArbitrageRiskManager, DatabaseQuerier, MarketPairFilters,
-    PredictionArbitrageStrategy,
-    recorder::OrderbookRecorder,
-    state::{ArbitrageGlobalData, ArbitrageInstrumentData},
+    ArbitrageConfig, ArbitrageRiskManager, CircuitBreaker, CorrelatedPair, DailyPnlTracker,
+    DatabaseError, DatabaseQuerier, MarketPairFilters, Metrics, PairRefresher, PairSource, PositionReconciler,
+    PredictionArbitrageStrategy, ReconcileClient, StatePersistence, StateSnapshot,
+    DEFAULT_SHUTDOWN_TIMEOUT, graceful_shutdown, reconcile_startup,
+    recorder::{DEFAULT_FLUSH_INTERVAL, DEFAULT_RECORDER_CAPACITY, OrderbookRecorder},
+    state::{
+        ArbitrageGlobalData, PositionMode, instrument_min_quantities, instrument_tick_sizes,
+        market_data_lookup,
+    },
 };
 use barter_data::{
     event::{DataKind, MarketEvent},
-    exchange::{kalshi::Kalshi, polymarket::Polymarket},
-    streams::{Streams, reconnect},
-    subscription::book::OrderBooksL2,
+    exchange::{
+        kalshi::{Kalshi, KalshiServer},
+        polymarket::Polymarket,
+    },
+    streams::{Streams, consumer::MarketStreamResult, reconnect},
+    subscription::{book::OrderBooksL2, status::MarketStatuses},
 };
 use barter_execution::client::{
-    kalshi::KalshiExecutionConfig,
-    polymarket::{PolymarketExecutionConfig, http::PolymarketHttpClient},
+    ExecutionClient,
+    kalshi::{self, KalshiExecution, KalshiExecutionConfig},
+    polymarket::{
+        self, PolymarketExecution, PolymarketExecutionConfig, http::PolymarketHttpClient,
+    },
 };
 use barter_instrument::{
     Underlying,
-    asset::Asset,
+    asset::{Asset, name::AssetNameExchange},
     exchange::ExchangeId,
     index::IndexedInstruments,
+    kalshi::tick,
+    polymarket::tick as polymarket_tick,
     instrument::{
-        Instrument, InstrumentIndex,
-        name::InstrumentNameInternal,
+        Instrument,
         spec::{
             InstrumentSpec, InstrumentSpecNotional, InstrumentSpecPrice, InstrumentSpecQuantity,
             OrderQuantityUnits,
@@ -58,18 +97,54 @@ use barter_instrument::instrument::market_data::kind::{
 };
 use futures::StreamExt;
 use rust_decimal_macros::dec;
-use std::collections::HashMap;
-use std::time::Duration;
+use std::{collections::HashMap, sync::Arc, time::Duration};
 use tracing::{error, info, warn};
 
+/// Pair backend selected via `PAIR_SOURCE`.
+#[derive(Clone)]
+enum PairBackend {
+    Rest(DatabaseQuerier),
+    #[cfg(feature = "postgres")]
+    Postgres(barter_arb_strategy::PostgresPairSource),
+}
+
+impl PairBackend {
+    async fn from_env(db: &DatabaseQuerier) -> Self {
+        match std::env::var("PAIR_SOURCE").as_deref() {
+            Ok("rest") | Err(_) => PairBackend::Rest(db.clone()),
+            #[cfg(feature = "postgres")]
+            Ok("postgres") => PairBackend::Postgres(
+                barter_arb_strategy::PostgresPairSource::from_env()
+                    .await
+                    .expect("Postgres connection failed"),
+            ),
+            Ok(other) => panic!("Unsupported PAIR_SOURCE: {other}"),
+        }
+    }
+}
+
+impl PairSource for PairBackend {
+    async fn get_correlated_pairs(
+        &self,
+        filters: MarketPairFilters,
+    ) -> Result<Vec<CorrelatedPair>, DatabaseError> {
+        match self {
+            PairBackend::Rest(db) => db.get_correlated_pairs(filters).await,
+            #[cfg(feature = "postgres")]
+            PairBackend::Postgres(db) => db.get_correlated_pairs(filters).await,
+        }
+    }
+}
+
 #[tokio::main]
 async fn main() {
     init_logging();
     dotenv();
 
-    // Step 1: Fetch correlated pairs from Supabase
+    // Step 1: Fetch correlated pairs (Supabase REST or direct Postgres)
     info!("Fetching correlated market pairs...");
     let db = DatabaseQuerier::from_env().expect("Database connection failed");
+    let pair_source = PairBackend::from_env(&db).await;
     let filters = MarketPairFilters {
         min_similarity: Some(dec!(0.85)),
         min_confidence: Some(dec!(0.85)),
@@ -78,8 +153,8 @@ async fn main() {
         ..Default::default()
     };
 
-    let pairs = db
-        .get_correlated_pairs(filters)
+    let pairs = pair_source
+        .get_correlated_pairs(filters.clone())
         .await
         .expect("Failed to fetch pairs");
 
@@ -89,61 +164,87 @@ async fn main() {
     }
     info!("Found {} correlated pairs", pairs.len());
 
+    let poly_private_key = env("POLYMARKET_PRIVATE_KEY");
+
+    // Always derive fresh API credentials from private key
+    info!("Deriving Polymarket API credentials from private key...");
+    let poly_creds = PolymarketHttpClient::derive_api_credentials(&poly_private_key)
+        .await
+        .expect("Failed to derive Polymarket API credentials");
+    info!("Polymarket wallet address: {}", poly_creds.wallet_address);
+
+    // Polymarket tick and minimum order sizes, from each market's metadata
+    let poly_http = PolymarketHttpClient::new(poly_creds.clone());
+    let mut poly_markets = HashMap::new();
+    for pair in &pairs {
+        match poly_http.fetch_market(&pair.polymarket_condition_id).await {
+            Ok(meta) => {
+                poly_markets.insert(pair.polymarket_condition_id.clone(), meta);
+            }
+            Err(e) => warn!(
+                condition = %pair.polymarket_condition_id,
+                "Failed to fetch Polymarket market metadata, using default tick and order sizes: {}",
+                e
+            ),
+        }
+    }
+
     // Step 2: Build IndexedInstruments from pairs
     // Each pair generates 4 instruments: Kalshi YES/NO, Polymarket YES/NO
-    let spec = InstrumentSpec::new(
-        InstrumentSpecPrice::new(dec!(0.01), dec!(0.01)),
-        InstrumentSpecQuantity::new(OrderQuantityUnits::Contract, dec!(1), dec!(1)),
-        InstrumentSpecNotional::new(dec!(0.01)),
-    );
+    // Instrument specs carry each market's tick and minimum order sizes through to the
+    // execution clients
+    let spec = |tick_size, min_quantity| {
+        InstrumentSpec::new(
+            InstrumentSpecPrice::new(tick_size, tick_size),
+            InstrumentSpecQuantity::new(OrderQuantityUnits::Contract, min_quantity, dec!(1)),
+            InstrumentSpecNotional::new(dec!(0.01)),
+        )
+    };
+    let kalshi_subcent_tickers: Vec<String> = std::env::var("KALSHI_SUBCENT_TICKERS")
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|ticker| !ticker.is_empty())
+        .map(str::to_ascii_uppercase)
+        .collect();
 
     let mut builder = IndexedInstruments::builder();
     for pair in &pairs {
-        // Kalshi YES
-        let kalshi_yes_name = format!("{}_yes", pair.kalshi_ticker);
-        builder = builder.add_instrument(Instrument::spot(
-            ExchangeId::Kalshi,
-            format!("kalshi_{}", kalshi_yes_name),
-            kalshi_yes_name.as_str(),
-            Underlying::new(Asset::from(kalshi_yes_name.as_str()), Asset::from("usd")),
-            Some(spec.clone()),
-        ));
-
-        // Kalshi NO
-        let kalshi_no_name = format!("{}_no", pair.kalshi_ticker);
-        builder = builder.add_instrument(Instrument::spot(
-            ExchangeId::Kalshi,
-            format!("kalshi_{}", kalshi_no_name),
-            kalshi_no_name.as_str(),
-            Underlying::new(Asset::from(kalshi_no_name.as_str()), Asset::from("usd")),
-            Some(spec.clone()),
-        ));
-
-        // Polymarket YES (token_id as name_exchange)
-        let poly_yes_prefix = &pair.polymarket_yes_token[..8.min(pair.polymarket_yes_token.len())];
-        builder = builder.add_instrument(Instrument::spot(
-            ExchangeId::Polymarket,
-            format!("poly_{}", poly_yes_prefix),
-            pair.polymarket_yes_token.as_str(),
-            Underlying::new(
-                Asset::from(pair.polymarket_yes_token.as_str()),
-                Asset::from("usdc"),
-            ),
-            Some(spec.clone()),
-        ));
-
-        // Polymarket NO
-        let poly_no_prefix = &pair.polymarket_no_token[..8.min(pair.polymarket_no_token.len())];
-        builder = builder.add_instrument(Instrument::spot(
-            ExchangeId::Polymarket,
-            format!("poly_{}", poly_no_prefix),
-            pair.polymarket_no_token.as_str(),
-            Underlying::new(
-                Asset::from(pair.polymarket_no_token.as_str()),
-                Asset::from("usdc"),
-            ),
-            Some(spec.clone()),
-        ));
+        let kalshi_tick_size =
+            if kalshi_subcent_tickers.contains(&pair.kalshi_ticker.to_ascii_uppercase()) {
+                tick::SUBCENT_TICK_SIZE
+            } else {
+                tick::DEFAULT_TICK_SIZE
+            };
+        for key in pair.instrument_keys() {
+            // name_exchange is what the execution clients trade: "{ticker}_{yes|no}" or token_id
+            let name = key.to_instrument_name();
+            let (name_internal, quote, tick_size, min_quantity) = match key.exchange {
+                ExchangeId::Kalshi => (
+                    format!("kalshi_{}", name),
+                    kalshi::DEFAULT_QUOTE_ASSET,
+                    kalshi_tick_size,
+                    dec!(1),
+                ),
+                _ => {
+                    let meta = poly_markets.get(&pair.polymarket_condition_id);
+                    (
+                        format!("poly_{}", &name[..8.min(name.len())]),
+                        polymarket::DEFAULT_QUOTE_ASSET,
+                        meta.and_then(|meta| meta.tick_size)
+                            .unwrap_or(polymarket_tick::DEFAULT_TICK_SIZE),
+                        meta.and_then(|meta| meta.min_order_size).unwrap_or(dec!(1)),
+                    )
+                }
+            };
+            builder = builder.add_instrument(Instrument::spot(
+                key.exchange,
+                name_internal,
+                name.as_str(),
+                Underlying::new(Asset::from(name.as_str()), Asset::from(quote)),
+                Some(spec(tick_size, min_quantity)),
+            ));
+        }
     }
 
     let indexed = builder.build();
@@ -154,7 +255,9 @@ async fn main() {
     );
 
     // Step 3: Build data streams
-    // We subscribe to YES orderbooks only; NO prices are derived (1 - YES).
+    // We subscribe to YES orderbooks; NO prices are derived (1 - YES) unless a real NO
+    // book is available. With POLY_SUBSCRIBE_NO=true the Polymarket NO tokens are
+    // subscribed too, and the strategy prefers their asks over the derived ones.
     info!("Building market data streams...");
     let kalshi_subs: Vec<_> = pairs
         .iter()
@@ -172,19 +275,34 @@ async fn main() {
         })
         .collect();
 
+    let poly_subscribe_no = std::env::var("POLY_SUBSCRIBE_NO").unwrap_or_default() == "true";
     let polymarket_subs: Vec<_> = pairs
         .iter()
-        .map(|pair| {
-            (
-                Polymarket,
-                pair.polymarket_yes_token.as_str(),
-                "USDC",
-                MarketDataInstrumentKind::Prediction(MarketDataPredictionContract {
-                    outcome: Outcome::Yes,
-                    expiry: pair.expiry,
-                }),
-                OrderBooksL2,
-            )
+        .flat_map(|pair| {
+            let yes = (pair.polymarket_yes_token.as_str(), Outcome::Yes);
+            let no = (pair.polymarket_no_token.as_str(), Outcome::No);
+            std::iter::once(yes)
+                .chain(poly_subscribe_no.then_some(no))
+                .map(|(token, outcome)| {
+                    (
+                        Polymarket,
+                        token,
+                        "USDC",
+                        MarketDataInstrumentKind::Prediction(MarketDataPredictionContract {
+                            outcome,
+                            expiry: pair.expiry,
+                        }),
+                        OrderBooksL2,
+                    )
+                })
+        })
+        .collect();
+
+    // Kalshi lifecycle events, so settled and determined markets are suspended
+    let kalshi_status_subs: Vec<_> = kalshi_subs
+        .iter()
+        .map(|(exchange, base, quote, kind, _)| {
+            (*exchange, *base, *quote, kind.clone(), MarketStatuses)
         })
         .collect();
 
@@ -195,47 +313,32 @@ async fn main() {
         .await
         .expect("Failed to init data streams");
 
-    let raw_stream = streams.select_all();
+    let status_streams = Streams::<MarketStatuses>::builder()
+        .subscribe(kalshi_status_subs)
+        .init()
+        .await
+        .expect("Failed to init market status streams");
+
+    let raw_stream = futures::stream::select(
+        streams.select_all().map(MarketStreamResult::<_, DataKind>::from),
+        status_streams.select_all().map(MarketStreamResult::<_, DataKind>::from),
+    );
 
     // Build lookup map: (ExchangeId, subscription_base_lowercase) -> InstrumentIndex
     // The subscription base is the kalshi ticker or polymarket token_id.
-    // AssetNameInternal lowercases its input, so we lowercase the lookup keys too.
-    let mut instrument_lookup: HashMap<(ExchangeId, String), InstrumentIndex> = HashMap::new();
-    for pair in &pairs {
-        // Kalshi YES: subscription base = kalshi_ticker, instrument name_internal = "kalshi_{ticker}_yes"
-        let kalshi_name_internal =
-            InstrumentNameInternal::new(format!("kalshi_{}_yes", pair.kalshi_ticker));
-        if let Ok(idx) = indexed.find_instrument_index(ExchangeId::Kalshi, &kalshi_name_internal) {
-            instrument_lookup.insert(
-                (ExchangeId::Kalshi, pair.kalshi_ticker.to_lowercase()),
-                idx,
-            );
-        }
-
-        // Polymarket YES: subscription base = token_id, instrument name_internal = "poly_{prefix}"
-        let poly_prefix = &pair.polymarket_yes_token[..8.min(pair.polymarket_yes_token.len())];
-        let poly_name_internal = InstrumentNameInternal::new(format!("poly_{}", poly_prefix));
-        if let Ok(idx) =
-            indexed.find_instrument_index(ExchangeId::Polymarket, &poly_name_internal)
-        {
-            instrument_lookup.insert(
-                (
-                    ExchangeId::Polymarket,
-                    pair.polymarket_yes_token.to_lowercase(),
-                ),
-                idx,
-            );
-        }
-    }
-
-    // Optional: Set up orderbook snapshot recording
-    let recorder = OrderbookRecorder::from_env();
-    if recorder.is_some() {
-        info!("Orderbook snapshot recording enabled");
-    }
-    let recorder = std::sync::Arc::new(std::sync::Mutex::new(recorder));
+    // AssetNameInternal lowercases its input, so the lookup keys are lowercased too.
+    let instrument_lookup = market_data_lookup(&pairs, &indexed);
+
+    // Optional: Set up orderbook event recording
+    // File I/O runs on a writer task; the stream only pushes into a bounded channel
+    let (recorder, recorder_writer) = OrderbookRecorder::from_env()
+        .map(|recorder| {
+            info!("Orderbook event recording enabled");
+            recorder.spawn(DEFAULT_RECORDER_CAPACITY, DEFAULT_FLUSH_INTERVAL)
+        })
+        .unzip();
 
-    // Adapt raw stream: filter errors, map instrument keys to InstrumentIndex, wrap kind in DataKind
+    // Adapt raw stream: filter errors, map instrument keys to InstrumentIndex
     let recorder_tap = recorder.clone();
     let market_stream = raw_stream.filter_map(move |event| {
         let result = match event {
@@ -245,21 +348,34 @@ async fn main() {
             reconnect::Event::Item(Ok(market_event)) => {
                 let base: &str = market_event.instrument.base.as_ref();
                 let key = (market_event.exchange, base.to_owned());
+
+                // Settled pairs are suspended, so stop streaming their Kalshi markets
+                if let (ExchangeId::Kalshi, DataKind::MarketStatus(status)) =
+                    (market_event.exchange, &market_event.kind)
+                {
+                    if status.is_terminal() {
+                        Kalshi::unsubscribe_markets([base]);
+                    }
+                }
                 if let Some(&idx) = instrument_lookup.get(&key) {
-                    // Tap: record snapshot if recorder is enabled
-                    if let Ok(mut guard) = recorder_tap.lock() {
-                        if let Some(rec) = guard.as_mut() {
-                            if let barter_data::subscription::book::OrderBookEvent::Snapshot(ref book) = market_event.kind {
-                                rec.on_orderbook_update(&key.1, book);
-                            }
-                        }
+                    // Tap: record snapshots and updates if recorder is enabled
+                    if let (Some(rec), DataKind::OrderBook(book)) =
+                        (&recorder_tap, &market_event.kind)
+                    {
+                        rec.on_orderbook_update(
+                            market_event.exchange,
+                            &key.1,
+                            market_event.time_exchange,
+                            market_event.time_received,
+                            book,
+                        );
                     }
                     Some(reconnect::Event::Item(MarketEvent {
                         time_exchange: market_event.time_exchange,
                         time_received: market_event.time_received,
                         exchange: market_event.exchange,
                         instrument: idx,
-                        kind: DataKind::OrderBook(market_event.kind),
+                        kind: market_event.kind,
                     }))
                 } else {
                     warn!(?key, "No instrument index found for market event");
@@ -277,41 +393,46 @@ async fn main() {
     // Step 4: Build execution clients
     info!("Building execution clients...");
     let kalshi_pem = load_kalshi_pem();
+    let balance_poll_interval_ms = env_or("BALANCE_POLL_MS", 2000);
+    let order_poll_interval_ms = env_or("ORDER_POLL_MS", 1000);
     let kalshi_config = KalshiExecutionConfig {
         api_key: env("KALSHI_API_KEY"),
         private_key_pem: kalshi_pem,
-        demo: std::env::var("KALSHI_DEMO")
-            .or_else(|_| std::env::var("KALSHI_USE_DEMO"))
-            .unwrap_or_default() == "true",
-        poll_interval_ms: 2000,
+        // Same server selection as the Kalshi market data streams
+        demo: Kalshi::server() == KalshiServer::Demo,
+        balance_poll_interval_ms,
+        order_poll_interval_ms,
+        quote_asset: AssetNameExchange::from(kalshi::DEFAULT_QUOTE_ASSET),
+        rate_limit: kalshi::http::KalshiRateLimit::default(),
+        tick_sizes: instrument_tick_sizes(&indexed, ExchangeId::Kalshi),
     };
 
-    let poly_private_key = env("POLYMARKET_PRIVATE_KEY");
-
-    // Always derive fresh API credentials from private key
-    info!("Deriving Polymarket API credentials from private key...");
-    let poly_creds = PolymarketHttpClient::derive_api_credentials(&poly_private_key)
-        .await
-        .expect("Failed to derive Polymarket API credentials");
-    info!("Polymarket wallet address: {}", poly_creds.wallet_address);
-
     let poly_config = PolymarketExecutionConfig {
         api_key: poly_creds.api_key,
         api_secret: poly_creds.api_secret,
         api_passphrase: poly_creds.api_passphrase,
         private_key_hex: poly_private_key,
         maker_address: poly_creds.wallet_address,
-        poll_interval_ms: 2000,
+        balance_poll_interval_ms,
+        order_poll_interval_ms,
+        quote_asset: AssetNameExchange::from(polymarket::DEFAULT_QUOTE_ASSET),
         neg_risk: std::env::var("POLY_NEG_RISK").unwrap_or_default() == "true",
+        min_order_value: polymarket::DEFAULT_MIN_ORDER_VALUE,
+        tick_sizes: instrument_tick_sizes(&indexed, ExchangeId::Polymarket),
+        min_order_sizes: instrument_min_quantities(&indexed, ExchangeId::Polymarket),
     };
 
+    // Standalone clients for startup reconciliation
+    let kalshi_client = KalshiExecution::new(kalshi_config.clone());
+    let poly_client = PolymarketExecution::new(poly_config.clone());
+
     let execution = ExecutionBuilder::new(&indexed)
-        .add_live::<barter_execution::client::kalshi::KalshiExecution>(
+        .add_live::<KalshiExecution>(
             kalshi_config,
             Duration::from_secs(10),
         )
         .expect("Failed to add Kalshi execution")
-        .add_live::<barter_execution::client::polymarket::PolymarketExecution>(
+        .add_live::<PolymarketExecution>(
             poly_config,
             Duration::from_secs(10),
         )
@@ -323,30 +444,230 @@ async fn main() {
         min_spread_threshold: dec!(0.02),
         max_position_per_market: 500,
         max_total_capital: dec!(5000),
+        record_opportunities: std::env::var("RECORD_OPPORTUNITIES").unwrap_or_default() == "true",
+        mark_invalid_pairs: std::env::var("MARK_INVALID_PAIRS").unwrap_or_default() == "true",
+        revalidate_after_anomalous_scans: std::env::var("REVALIDATE_AFTER_SCANS")
+            .ok()
+            .and_then(|v| v.parse().ok()),
+        dry_run: std::env::var("DRY_RUN").unwrap_or_default() == "true",
+        cancel_unknown_orders: std::env::var("CANCEL_UNKNOWN_ORDERS").unwrap_or_default()
+            == "true",
+        reconcile_interval: std::env::var("RECONCILE_INTERVAL_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .map(Duration::from_secs),
         ..Default::default()
     };
+    // A dry run never touches exchange orders, including those of an earlier session
+    let dry_run = config.dry_run;
+    let cancel_unknown_orders = config.cancel_unknown_orders && !dry_run;
+    let reconcile_interval = config.reconcile_interval;
+    let record_opportunities = config.record_opportunities;
+    let mark_invalid_pairs = config.mark_invalid_pairs;
+    let circuit_breaker = CircuitBreaker::from_config(&config);
+    if dry_run {
+        warn!("Dry run enabled: orders are logged and recorded but not sent or cancelled");
+    }
 
-    let strategy = PredictionArbitrageStrategy::with_instruments(
+    // Shared with the risk manager, so the day's realized P&L survives a restart
+    let daily_pnl = DailyPnlTracker::default();
+    let mut strategy = PredictionArbitrageStrategy::with_instruments(
         barter_execution::order::id::StrategyId::new("pred-arb"),
         config,
-        pairs,
+        pairs.clone(),
         &indexed,
-    );
+    )
+    .with_daily_pnl(daily_pnl.clone());
+
+    // Restore positions, capital, suspended pairs and daily P&L from the previous session
+    let persistence = StatePersistence::from_env();
+    let snapshot = match persistence.as_ref().map(StatePersistence::load) {
+        Some(Err(e)) => {
+            error!("Failed to load state snapshot: {}", e);
+            return;
+        }
+        Some(Ok(snapshot)) => snapshot.unwrap_or_default(),
+        None => StateSnapshot::default(),
+    };
+    strategy.restore(&snapshot.strategy);
+    if let Some(persistence) = &persistence {
+        info!(path = %persistence.path().display(), "State persistence enabled");
+        strategy = strategy.with_persistence(persistence.clone());
+    }
+
+    // Prometheus metrics exporter
+    let metrics = match std::env::var("METRICS_PORT").ok().map(|port| port.parse::<u16>()) {
+        Some(Ok(port)) => match tokio::net::TcpListener::bind(("0.0.0.0", port)).await {
+            Ok(listener) => {
+                let metrics = Metrics::new();
+                tokio::spawn(metrics.clone().serve(listener));
+                strategy = strategy.with_metrics(metrics.clone());
+                Some(metrics)
+            }
+            Err(e) => {
+                error!(port, "Failed to bind metrics exporter: {}", e);
+                return;
+            }
+        },
+        Some(Err(e)) => {
+            error!("Invalid METRICS_PORT: {}", e);
+            return;
+        }
+        None => None,
+    };
+
+    // Database writes happen off the engine thread
+    let db_writer = db.clone();
+
+    // Record detected opportunities
+    if record_opportunities {
+        let (opp_tx, mut opp_rx) = tokio::sync::mpsc::unbounded_channel();
+        strategy = strategy.with_opportunity_sink(opp_tx);
+        let db = db_writer.clone();
+        let recorder = recorder.clone();
+        tokio::spawn(db.clone().run_opportunity_flusher());
+        tokio::spawn(async move {
+            while let Some((opp, rejection)) = opp_rx.recv().await {
+                // Detections land in the same timeline as the orderbook events
+                if let Some(rec) = &recorder {
+                    rec.record_opportunity(&opp, rejection);
+                }
+                if let Err(e) = db.insert_opportunity(&opp, rejection).await {
+                    warn!("Failed to record opportunities: {}", e);
+                }
+            }
+        });
+    }
+
+    // Record paired positions as they change and log a periodic summary
+    let (position_tx, mut position_rx) = tokio::sync::mpsc::unbounded_channel();
+    strategy = strategy.with_position_sink(position_tx);
+    let position_recorder = recorder.clone();
+    tokio::spawn(async move {
+        let mut positions = HashMap::new();
+        let mut summary = tokio::time::interval(Duration::from_secs(60));
+        loop {
+            tokio::select! {
+                Some((ticker, position)) = position_rx.recv() => {
+                    if let Some(rec) = &position_recorder {
+                        rec.record_position(&ticker, &position);
+                    }
+                    positions.insert(ticker, position);
+                }
+                _ = summary.tick() => {
+                    for (ticker, position) in &positions {
+                        info!(
+                            pair = %ticker,
+                            matched = %position.matched_quantity(),
+                            net_exposure = %position.net_exposure(),
+                            locked_profit = %position.locked_profit(),
+                            "Arbitrage position"
+                        );
+                    }
+                }
+            }
+        }
+    });
+
+    // Periodically re-query pairs and apply changes to the running strategy
+    if let Some(secs) = std::env::var("PAIR_REFRESH_SECS").ok().and_then(|v| v.parse().ok()) {
+        let (update_tx, update_rx) = tokio::sync::mpsc::unbounded_channel();
+        strategy = strategy.with_pair_updates(update_rx);
+        let refresher = PairRefresher::new(
+            pair_source,
+            filters,
+            Duration::from_secs(secs),
+            pairs.clone(),
+            update_tx,
+        );
+        tokio::spawn(refresher.run());
+    }
 
-    // Step 6: Build risk manager
+    // Mark pairs suspended at runtime invalid, and flag inconsistent ones for revalidation
+    if mark_invalid_pairs {
+        let (invalid_tx, mut invalid_rx) = tokio::sync::mpsc::unbounded_channel();
+        strategy = strategy.with_invalidation_sink(invalid_tx);
+        let db = db_writer.clone();
+        tokio::spawn(async move {
+            while let Some(invalidation) = invalid_rx.recv().await {
+                let result = if invalidation.revalidate {
+                    db.flag_pair_for_revalidation(invalidation.pair_id, &invalidation.reason)
+                        .await
+                } else {
+                    db.mark_pair_invalid(invalidation.pair_id, &invalidation.reason)
+                        .await
+                };
+                if let Err(e) = result {
+                    warn!(pair = %invalidation.kalshi_ticker, "Failed to mark pair invalid: {}", e);
+                }
+            }
+        });
+    }
+
+    // Step 6: Build risk manager (kill-switch disables trading on daily loss breach)
+    let (kill_switch_tx, mut kill_switch_rx) = tokio::sync::mpsc::unbounded_channel();
     let risk = ArbitrageRiskManager {
         max_total_capital: dec!(5000),
         max_order_notional: dec!(500),
-    };
+        max_kalshi_capital: dec!(2500),
+        max_polymarket_capital: dec!(2500),
+        max_open_pairs: 5,
+        max_order_contracts: dec!(1000),
+        max_daily_loss: dec!(250),
+        daily_pnl,
+        ..Default::default()
+    }
+    .with_pairs(&pairs, &indexed)
+    .with_trading_state_tx(kill_switch_tx);
+    // Pairs added by the refresher count against max_open_pairs
+    strategy = strategy.with_pair_instruments(risk.instrument_pairs.clone());
 
     // Step 7: Build engine state
-    let global_data = ArbitrageGlobalData::default();
-    let state = EngineStateBuilder::new(&indexed, global_data, |_| {
-        ArbitrageInstrumentData::default()
+    let global_data = ArbitrageGlobalData {
+        circuit_breaker,
+        ..snapshot.global.clone()
+    }
+    .with_exchanges(&indexed)
+    .with_pairs(&pairs, &indexed);
+    let global_data = match metrics {
+        Some(metrics) => global_data.with_metrics(metrics),
+        None => global_data,
+    };
+    // Every instrument is a pair leg held to settlement, so value it at its locked profit
+    let mut state = EngineStateBuilder::new(&indexed, global_data, |instrument| {
+        snapshot
+            .instrument_data(instrument)
+            .with_position_mode(PositionMode::HeldToSettlement)
     })
     .trading_state(TradingState::Enabled)
     .build();
 
+    // Reconcile positions and resting orders left by a previous session before trading
+    let clients: [&dyn ReconcileClient; 2] = [&kalshi_client, &poly_client];
+    match reconcile_startup(&mut state, &indexed, &clients, cancel_unknown_orders).await {
+        Ok(report) if report.is_clean() => info!("Startup reconciliation found no discrepancies"),
+        Ok(report) => {
+            for discrepancy in &report.discrepancies {
+                warn!(?discrepancy, "Startup reconciliation discrepancy");
+            }
+        }
+        Err(e) => {
+            error!("Startup reconciliation failed: {}", e);
+            return;
+        }
+    }
+
+    // Correct positions drifting from the exchanges' while running
+    if let Some(interval) = reconcile_interval {
+        let reconciler = PositionReconciler::new(
+            vec![Arc::new(kalshi_client.clone()), Arc::new(poly_client.clone())],
+            indexed.clone(),
+            interval,
+            execution.account_channel.tx.tx.clone(),
+        );
+        tokio::spawn(reconciler.run());
+    }
+
     // Step 8: Construct engine
     let clock = barter::engine::clock::LiveClock;
     let engine = Engine::new(
@@ -371,9 +692,48 @@ async fn main() {
     match system_build.init().await {
         Ok(system) => {
             info!("Engine running. Press Ctrl+C to stop.");
-            let _ = tokio::signal::ctrl_c().await;
+            loop {
+                tokio::select! {
+                    _ = tokio::signal::ctrl_c() => break,
+                    Some(trading_state) = kill_switch_rx.recv() => {
+                        warn!(?trading_state, "Risk manager kill-switch changed trading state");
+                        system.trading_state(trading_state);
+                    }
+                }
+            }
             info!("Shutting down...");
-            drop(system);
+            match system.shutdown().await {
+                Ok((engine, _audit)) => {
+                    if let Some(persistence) = &persistence {
+                        let snapshot = StateSnapshot::capture(
+                            &engine.state,
+                            &engine.strategy,
+                            chrono::Utc::now(),
+                        );
+                        match persistence.save(&snapshot) {
+                            Ok(()) => info!("Saved state snapshot"),
+                            Err(e) => warn!("Failed to save state snapshot: {}", e),
+                        }
+                    }
+                }
+                Err(e) => error!("Engine task failed during shutdown: {:?}", e),
+            }
+            // Cancel orders left working, unless dry running, and write out queued recordings
+            let cancel_clients: &[&dyn ReconcileClient] = if dry_run { &[] } else { &clients };
+            let report = graceful_shutdown(
+                cancel_clients,
+                recorder.zip(recorder_writer),
+                DEFAULT_SHUTDOWN_TIMEOUT,
+            )
+            .await;
+            if !report.is_clean() {
+                warn!(?report, "Shutdown did not complete cleanly");
+            }
+            if record_opportunities {
+                if let Err(e) = db_writer.flush_opportunities().await {
+                    warn!("Failed to flush recorded opportunities: {}", e);
+                }
+            }
         }
         Err(e) => {
             error!("Failed to initialize system: {:?}", e);
@@ -385,6 +745,13 @@ fn env(key: &str) -> String {
     std::env::var(key).unwrap_or_else(|_| panic!("{} not set", key))
 }
 
+fn env_or(key: &str, default: u64) -> u64 {
+    std::env::var(key)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(default)
+}
+
 fn load_kalshi_pem() -> String {
     if let Ok(path) = std::env::var("KALSHI_PRIVATE_KEY_PATH") {
         // Try the path as-is first, then resolve relative to parent dirs
@@ -2,12 +2,16 @@
 
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
 /// Configuration for the prediction market arbitrage strategy.
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct ArbitrageConfig {
     /// Minimum spread after fees to trigger trade (e.g., 0.02 = 2%)
     pub min_spread_threshold: Decimal,
+    /// Minimum expected profit in dollars across all contracts of an opportunity
+    #[serde(default)]
+    pub min_total_profit: Decimal,
     /// Maximum position size per market (in contracts)
     pub max_position_per_market: u32,
     /// Maximum total capital deployed across all positions
@@ -16,16 +20,129 @@ pub struct ArbitrageConfig {
     pub min_order_value: MinOrderValues,
     /// Maximum days until market expiry to consider
     pub max_days_to_expiry: Option<u32>,
+    /// Record detected opportunities to the database for offline analysis
+    #[serde(default)]
+    pub record_opportunities: bool,
+    /// Also record opportunities rejected by filters (requires `record_opportunities`)
+    #[serde(default)]
+    pub record_rejected_opportunities: bool,
+    /// Adverse-move buffer added to each leg's assumed cost, in basis points
+    #[serde(default)]
+    pub slippage_buffer_bps: u32,
+    /// Mark pairs invalid in the database when they are suspended at runtime
+    /// (requires a key with write access)
+    #[serde(default)]
+    pub mark_invalid_pairs: bool,
+    /// Suspend a pair once its orderbooks have been missing for this many seconds
+    #[serde(default = "default_missing_book_timeout_secs")]
+    pub missing_book_timeout_secs: u64,
+    /// Use the inverse flag inferred from live mid prices instead of the stored
+    /// one when they disagree (see [`CorrelatedPair::infer_inverse`](crate::CorrelatedPair::infer_inverse))
+    #[serde(default)]
+    pub prefer_inferred_inverse: bool,
+    /// Suspend a pair and flag it for re-validation once this many consecutive scans
+    /// imply the opposite of its stored inverse flag (flagging requires
+    /// `mark_invalid_pairs`, `None` = never)
+    #[serde(default)]
+    pub revalidate_after_anomalous_scans: Option<u32>,
+    /// Trip the circuit breaker after this many consecutive failed orders on one exchange
+    #[serde(default = "default_max_consecutive_order_failures")]
+    pub max_consecutive_order_failures: u32,
+    /// Automatically reset a tripped circuit breaker after this many seconds
+    /// (`None` = manual reset only)
+    #[serde(default)]
+    pub circuit_breaker_reset_secs: Option<u64>,
+    /// Re-evaluate every pair on each scan, even if none of its orderbooks
+    /// changed since the last one
+    #[serde(default)]
+    pub rescan_unchanged_pairs: bool,
+    /// Log and record the order pairs that would be sent instead of sending them
+    #[serde(default)]
+    pub dry_run: bool,
+    /// At startup, cancel open orders the engine doesn't know about instead of
+    /// adopting them, see [`reconcile_startup`](crate::reconcile::reconcile_startup)
+    #[serde(default)]
+    pub cancel_unknown_orders: bool,
+    /// Re-check positions against the exchanges on this interval while running,
+    /// see [`PositionReconciler`](crate::reconcile::PositionReconciler)
+    /// (`None` = only at startup)
+    #[serde(default)]
+    pub reconcile_interval: Option<Duration>,
+    /// Once one leg of an opportunity has filled, how long to wait for the other
+    /// leg before flattening the filled one
+    #[serde(default = "default_leg_confirm_timeout")]
+    pub leg_confirm_timeout: Duration,
+    /// Send Polymarket legs fill-and-kill rather than fill-or-kill, so thin books fill
+    /// partially rather than not at all, leaving the other leg to be reconciled
+    #[serde(default)]
+    pub polymarket_fill_and_kill: bool,
+    /// Maximum orderbook levels to walk on each side of a single opportunity
+    /// (`None` = walk while profitable)
+    #[serde(default)]
+    pub max_walk_levels: Option<usize>,
+    /// Maximum contracts to size a single opportunity at, unlike
+    /// `max_position_per_market` which bounds the cumulative position
+    #[serde(default)]
+    pub max_contracts_per_opportunity: Option<u32>,
+    /// Only fire when an exponential moving average of the pair's edge, with this
+    /// time constant, is above `min_spread_threshold`, filtering momentarily crossed
+    /// books however often pairs are scanned (`None` = act on the latest scan alone)
+    #[serde(default)]
+    pub min_edge_persistence: Option<Duration>,
+    /// Minimum contracts both legs must offer within `book_depth_band` of their
+    /// best ask, filtering books too thin to trust (`None` = no depth check)
+    #[serde(default)]
+    pub min_book_depth: Option<Decimal>,
+    /// Price band from the best ask used by `min_book_depth` (e.g. 0.02 = 2c)
+    #[serde(default = "default_book_depth_band")]
+    pub book_depth_band: Decimal,
+}
+
+fn default_missing_book_timeout_secs() -> u64 {
+    3600
+}
+
+fn default_max_consecutive_order_failures() -> u32 {
+    3
+}
+
+fn default_leg_confirm_timeout() -> Duration {
+    Duration::from_secs(5)
+}
+
+fn default_book_depth_band() -> Decimal {
+    Decimal::new(2, 2)
 }
 
 impl Default for ArbitrageConfig {
     fn default() -> Self {
         Self {
             min_spread_threshold: Decimal::new(2, 2), // 2%
+            min_total_profit: Decimal::ZERO,
             max_position_per_market: 1000,
             max_total_capital: Decimal::new(10000, 0), // $10,000
             min_order_value: MinOrderValues::default(),
             max_days_to_expiry: Some(90),
+            record_opportunities: false,
+            record_rejected_opportunities: false,
+            slippage_buffer_bps: 0,
+            mark_invalid_pairs: false,
+            missing_book_timeout_secs: default_missing_book_timeout_secs(),
+            prefer_inferred_inverse: false,
+            revalidate_after_anomalous_scans: None,
+            max_consecutive_order_failures: default_max_consecutive_order_failures(),
+            circuit_breaker_reset_secs: None,
+            rescan_unchanged_pairs: false,
+            dry_run: false,
+            cancel_unknown_orders: false,
+            reconcile_interval: None,
+            leg_confirm_timeout: default_leg_confirm_timeout(),
+            polymarket_fill_and_kill: false,
+            max_walk_levels: None,
+            max_contracts_per_opportunity: None,
+            min_edge_persistence: None,
+            min_book_depth: None,
+            book_depth_band: default_book_depth_band(),
         }
     }
 }
@@ -56,8 +173,29 @@ mod tests {
     fn test_default_config() {
         let config = ArbitrageConfig::default();
         assert_eq!(config.min_spread_threshold, Decimal::new(2, 2));
+        assert_eq!(config.min_total_profit, Decimal::ZERO);
         assert_eq!(config.max_position_per_market, 1000);
         assert_eq!(config.max_total_capital, Decimal::new(10000, 0));
+        assert!(!config.record_opportunities);
+        assert!(!config.record_rejected_opportunities);
+        assert_eq!(config.slippage_buffer_bps, 0);
+        assert!(!config.mark_invalid_pairs);
+        assert_eq!(config.missing_book_timeout_secs, 3600);
+        assert!(!config.prefer_inferred_inverse);
+        assert_eq!(config.max_consecutive_order_failures, 3);
+        assert_eq!(config.circuit_breaker_reset_secs, None);
+        assert!(!config.rescan_unchanged_pairs);
+        assert!(!config.dry_run);
+        assert!(!config.cancel_unknown_orders);
+        assert_eq!(config.reconcile_interval, None);
+        assert_eq!(config.leg_confirm_timeout, Duration::from_secs(5));
+        assert!(!config.polymarket_fill_and_kill);
+        assert_eq!(config.max_walk_levels, None);
+        assert_eq!(config.max_contracts_per_opportunity, None);
+        assert_eq!(config.min_edge_persistence, None);
+        assert_eq!(config.revalidate_after_anomalous_scans, None);
+        assert_eq!(config.min_book_depth, None);
+        assert_eq!(config.book_depth_band, Decimal::new(2, 2));
     }
 
     #[test]
@@ -1,9 +1,34 @@
 //! Market pair correlation management for arbitrage detection.
 
+use barter_data::books::OrderBook;
+use barter_execution::client::polymarket::model::PolymarketMarketMeta;
 use barter_instrument::exchange::ExchangeId;
 use chrono::{DateTime, Utc};
+use indexmap::IndexMap;
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use smol_str::SmolStr;
+use thiserror::Error;
+
+/// Minimum difference between the direct and inverse mid-price distances for
+/// [`CorrelatedPair::infer_inverse`] to make a call (0.15).
+pub const INVERSE_INFERENCE_MARGIN: Decimal = Decimal::from_parts(15, 0, 0, false, 2);
+
+const SECONDS_PER_HOUR: i64 = 60 * 60;
+const SECONDS_PER_DAY: i64 = 24 * SECONDS_PER_HOUR;
+
+/// Polymarket market metadata that contradicts a [`CorrelatedPair`].
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum PairMetaError {
+    #[error("metadata is for condition {found}, pair has {expected}")]
+    Condition { expected: SmolStr, found: String },
+    #[error("{outcome} token mismatch: pair has {expected}, market has {found}")]
+    Token {
+        outcome: Outcome,
+        expected: SmolStr,
+        found: String,
+    },
+}
 
 /// A pair of markets that ask the same question on different platforms.
 ///
@@ -26,6 +51,9 @@ pub struct CorrelatedPair {
     /// Whether Kalshi YES/NO are inverted relative to Polymarket
     /// When true: Polymarket YES = Kalshi NO
     pub inverse: bool,
+    /// Market pairs table row id, if loaded from the database
+    #[serde(default)]
+    pub db_id: Option<i64>,
 }
 
 impl CorrelatedPair {
@@ -47,18 +75,194 @@ impl CorrelatedPair {
             description: description.into(),
             expiry,
             inverse,
+            db_id: None,
+        }
+    }
+
+    /// Attach the market pairs table row id this pair was loaded from.
+    pub fn with_db_id(mut self, db_id: i64) -> Self {
+        self.db_id = Some(db_id);
+        self
+    }
+
+    /// Check the pair against Polymarket metadata resolved from its condition id (see
+    /// `PolymarketHttpClient::fetch_market`), filling in missing token ids.
+    ///
+    /// Token ids already set must match the market's, so stale pairs are caught rather
+    /// than subscribed to the wrong books.
+    pub fn apply_polymarket_meta(
+        &mut self,
+        meta: &PolymarketMarketMeta,
+    ) -> Result<(), PairMetaError> {
+        if !self.polymarket_condition_id.eq_ignore_ascii_case(&meta.condition_id) {
+            return Err(PairMetaError::Condition {
+                expected: self.polymarket_condition_id.clone(),
+                found: meta.condition_id.clone(),
+            });
+        }
+
+        for (outcome, token, found) in [
+            (Outcome::Yes, &self.polymarket_yes_token, &meta.yes_token),
+            (Outcome::No, &self.polymarket_no_token, &meta.no_token),
+        ] {
+            if !token.is_empty() && token != found {
+                return Err(PairMetaError::Token {
+                    outcome,
+                    expected: token.clone(),
+                    found: found.clone(),
+                });
+            }
         }
+
+        self.polymarket_yes_token = SmolStr::new(&meta.yes_token);
+        self.polymarket_no_token = SmolStr::new(&meta.no_token);
+        Ok(())
     }
 
-    /// Get the number of days until this market expires.
+    /// Get the number of whole days until this market expires.
+    ///
+    /// Rounds down, so a market expiring later today is 0 days away and one that
+    /// expired earlier today is -1.
     pub fn days_to_expiry(&self) -> i64 {
-        (self.expiry - Utc::now()).num_days()
+        (self.expiry - Utc::now()).num_seconds().div_euclid(SECONDS_PER_DAY)
+    }
+
+    /// Get the number of whole hours until this market expires, rounding down like
+    /// [`Self::days_to_expiry`].
+    pub fn hours_to_expiry(&self) -> i64 {
+        (self.expiry - Utc::now()).num_seconds().div_euclid(SECONDS_PER_HOUR)
     }
 
     /// Check if the market has expired.
     pub fn is_expired(&self) -> bool {
         self.expiry <= Utc::now()
     }
+
+    /// Check the market is still trading and, if `max_days_to_expiry` is set, is at
+    /// most that many whole days from expiry (see [`Self::days_to_expiry`]).
+    ///
+    /// A market expiring later today is 0 days away and within any window, while one
+    /// whose close time passed earlier today is outside every window.
+    pub fn is_within_trading_window(&self, max_days_to_expiry: Option<u32>) -> bool {
+        !self.is_expired()
+            && max_days_to_expiry
+                .is_none_or(|max_days| self.days_to_expiry() <= i64::from(max_days))
+    }
+
+    /// Keys of the pair's four instruments, in Kalshi YES/NO, Polymarket YES/NO order.
+    pub fn instrument_keys(&self) -> [PredictionMarketKey; 4] {
+        [
+            PredictionMarketKey::kalshi_yes(self.kalshi_ticker.clone()),
+            PredictionMarketKey::kalshi_no(self.kalshi_ticker.clone()),
+            PredictionMarketKey::polymarket_yes(self.polymarket_yes_token.clone()),
+            PredictionMarketKey::polymarket_no(self.polymarket_no_token.clone()),
+        ]
+    }
+
+    /// Resolve an engine instrument name to one of this pair's keys.
+    pub fn instrument_key(&self, exchange: ExchangeId, name: &str) -> Option<PredictionMarketKey> {
+        self.instrument_keys()
+            .into_iter()
+            .find(|key| key.exchange == exchange && key.to_instrument_name() == name)
+    }
+
+    /// Infer whether the pair is inverse from current YES orderbook mid prices.
+    ///
+    /// Correlated markets should price the same outcome similarly, so if the
+    /// Polymarket YES mid is much closer to the Kalshi NO mid (1 - Kalshi YES
+    /// mid) than to the Kalshi YES mid, the pair is inverse. Returns `None` if
+    /// either mid is unavailable or neither reading is better by at least
+    /// [`INVERSE_INFERENCE_MARGIN`] (e.g. both markets near 50c).
+    pub fn infer_inverse(
+        &self,
+        poly_yes_book: &OrderBook,
+        kalshi_yes_book: &OrderBook,
+    ) -> Option<bool> {
+        let poly_mid = poly_yes_book.mid_price()?;
+        let kalshi_mid = kalshi_yes_book.mid_price()?;
+
+        let direct_distance = (poly_mid - kalshi_mid).abs();
+        let inverse_distance = (poly_mid - (Decimal::ONE - kalshi_mid)).abs();
+
+        if inverse_distance + INVERSE_INFERENCE_MARGIN <= direct_distance {
+            Some(true)
+        } else if direct_distance + INVERSE_INFERENCE_MARGIN <= inverse_distance {
+            Some(false)
+        } else {
+            None
+        }
+    }
+}
+
+/// One Polymarket market correlated with one or more Kalshi markets.
+///
+/// Bucketed Kalshi markets (e.g. price thresholds) can map onto a single
+/// Polymarket question. Every leg trades against the same Polymarket book, so
+/// detection picks the best Kalshi leg per direction rather than all of them.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct CorrelatedGroup {
+    /// Polymarket condition ID shared by every pair
+    pub polymarket_condition_id: SmolStr,
+    /// Polymarket YES token ID shared by every pair
+    pub polymarket_yes_token: SmolStr,
+    /// Polymarket NO token ID shared by every pair
+    pub polymarket_no_token: SmolStr,
+    /// One pair per Kalshi ticker
+    pub pairs: Vec<CorrelatedPair>,
+}
+
+impl CorrelatedGroup {
+    /// Create a group from its first pair.
+    pub fn new(pair: CorrelatedPair) -> Self {
+        Self {
+            polymarket_condition_id: pair.polymarket_condition_id.clone(),
+            polymarket_yes_token: pair.polymarket_yes_token.clone(),
+            polymarket_no_token: pair.polymarket_no_token.clone(),
+            pairs: vec![pair],
+        }
+    }
+
+    /// Add a Kalshi leg. Returns false (and leaves the group unchanged) if the
+    /// pair is for a different Polymarket market or its ticker is already present.
+    pub fn push(&mut self, pair: CorrelatedPair) -> bool {
+        if pair.polymarket_yes_token != self.polymarket_yes_token
+            || self
+                .pairs
+                .iter()
+                .any(|existing| existing.kalshi_ticker == pair.kalshi_ticker)
+        {
+            return false;
+        }
+
+        self.pairs.push(pair);
+        true
+    }
+
+    /// Group pairs by Polymarket market, preserving first-seen order.
+    pub fn from_pairs(pairs: impl IntoIterator<Item = CorrelatedPair>) -> Vec<Self> {
+        let mut groups: IndexMap<SmolStr, CorrelatedGroup> = IndexMap::new();
+        for pair in pairs {
+            match groups.get_mut(&pair.polymarket_yes_token) {
+                Some(group) => {
+                    group.push(pair);
+                }
+                None => {
+                    groups.insert(pair.polymarket_yes_token.clone(), Self::new(pair));
+                }
+            }
+        }
+        groups.into_values().collect()
+    }
+
+    /// Kalshi tickers of every leg.
+    pub fn kalshi_tickers(&self) -> impl Iterator<Item = &SmolStr> {
+        self.pairs.iter().map(|pair| &pair.kalshi_ticker)
+    }
+
+    /// Whether the group has more than one Kalshi leg.
+    pub fn is_many_to_one(&self) -> bool {
+        self.pairs.len() > 1
+    }
 }
 
 /// Unique identifier for a prediction market instrument.
@@ -104,6 +308,39 @@ impl PredictionMarketKey {
     pub fn polymarket_no(token_id: impl Into<SmolStr>) -> Self {
         Self::new(ExchangeId::Polymarket, token_id, Outcome::No)
     }
+
+    /// Engine instrument name (`name_exchange`) for this key.
+    ///
+    /// Kalshi uses one ticker for both outcomes, so the outcome is appended
+    /// (`{ticker}_yes` / `{ticker}_no`), matching the Kalshi execution client.
+    /// Polymarket outcomes are separate tokens, so the name is the token ID.
+    pub fn to_instrument_name(&self) -> SmolStr {
+        match self.exchange {
+            ExchangeId::Kalshi => SmolStr::new(format!("{}_{}", self.market_id, self.outcome)),
+            _ => self.market_id.clone(),
+        }
+    }
+
+    /// Resolve a key from an engine instrument name (inverse of
+    /// [`Self::to_instrument_name`]).
+    ///
+    /// Returns `None` for names without an outcome suffix on Kalshi, and for
+    /// Polymarket names: bare token IDs don't say which outcome they are, so
+    /// they resolve only against a pair (see [`CorrelatedPair::instrument_key`]).
+    pub fn from_instrument_name(exchange: ExchangeId, name: &str) -> Option<Self> {
+        match exchange {
+            ExchangeId::Kalshi => {
+                let (ticker, outcome) = name.rsplit_once('_')?;
+                let outcome = match outcome {
+                    "yes" => Outcome::Yes,
+                    "no" => Outcome::No,
+                    _ => return None,
+                };
+                (!ticker.is_empty()).then(|| Self::new(exchange, ticker, outcome))
+            }
+            _ => None,
+        }
+    }
 }
 
 impl std::fmt::Display for PredictionMarketKey {
@@ -142,6 +379,8 @@ impl Outcome {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use barter_data::books::Level;
+    use rust_decimal_macros::dec;
 
     #[test]
     fn test_correlated_pair_creation() {
@@ -160,6 +399,45 @@ mod tests {
         assert_eq!(pair.polymarket_no_token.as_str(), "0xno_token");
     }
 
+    fn expiring_in(remaining: chrono::Duration) -> CorrelatedPair {
+        CorrelatedPair::new(
+            "KXTEST",
+            "0xcondition",
+            "0xyes",
+            "0xno",
+            "Test market",
+            Utc::now() + remaining,
+            false,
+        )
+    }
+
+    #[test]
+    fn test_intraday_expiry() {
+        // Expires in 6 hours: still trading, 0 whole days away
+        let open = expiring_in(chrono::Duration::hours(6));
+        assert_eq!(open.days_to_expiry(), 0);
+        assert!((5..=6).contains(&open.hours_to_expiry()));
+        assert!(!open.is_expired());
+        assert!(open.is_within_trading_window(Some(0)));
+        assert!(open.is_within_trading_window(None));
+
+        // Closed 2 hours ago: same calendar day, but no longer tradable
+        let closed = expiring_in(chrono::Duration::hours(-2));
+        assert_eq!(closed.days_to_expiry(), -1);
+        assert!((-3..=-2).contains(&closed.hours_to_expiry()));
+        assert!(closed.is_expired());
+        assert!(!closed.is_within_trading_window(Some(90)));
+        assert!(!closed.is_within_trading_window(None));
+    }
+
+    #[test]
+    fn test_trading_window_uses_whole_days() {
+        let pair = expiring_in(chrono::Duration::days(90) + chrono::Duration::hours(6));
+        assert_eq!(pair.days_to_expiry(), 90);
+        assert!(pair.is_within_trading_window(Some(90)));
+        assert!(!pair.is_within_trading_window(Some(89)));
+    }
+
     #[test]
     fn test_prediction_market_key() {
         let key = PredictionMarketKey::kalshi_yes("KXBTC-25JAN31-T100000");
@@ -168,9 +446,206 @@ mod tests {
         assert_eq!(key.to_string(), "Kalshi|KXBTC-25JAN31-T100000|yes");
     }
 
+    fn book(bid: Decimal, ask: Decimal) -> OrderBook {
+        OrderBook::new(
+            1,
+            None,
+            vec![Level::new(bid, dec!(100))],
+            vec![Level::new(ask, dec!(100))],
+        )
+    }
+
+    fn pair(inverse: bool) -> CorrelatedPair {
+        CorrelatedPair::new(
+            "KXTEST",
+            "0xcondition",
+            "0xyes",
+            "0xno",
+            "Test market",
+            Utc::now() + chrono::Duration::days(30),
+            inverse,
+        )
+    }
+
+    #[test]
+    fn test_infer_inverse_clearly_inverse() {
+        // Poly YES ~0.71, Kalshi YES ~0.29 → Poly YES matches Kalshi NO
+        let poly = book(dec!(0.70), dec!(0.72));
+        let kalshi = book(dec!(0.28), dec!(0.30));
+
+        assert_eq!(pair(false).infer_inverse(&poly, &kalshi), Some(true));
+    }
+
+    #[test]
+    fn test_infer_inverse_clearly_direct() {
+        // Poly YES ~0.71, Kalshi YES ~0.73
+        let poly = book(dec!(0.70), dec!(0.72));
+        let kalshi = book(dec!(0.72), dec!(0.74));
+
+        assert_eq!(pair(true).infer_inverse(&poly, &kalshi), Some(false));
+    }
+
+    #[test]
+    fn test_infer_inverse_ambiguous() {
+        // Near 50c both readings fit equally well
+        let poly = book(dec!(0.49), dec!(0.51));
+        let kalshi = book(dec!(0.50), dec!(0.54));
+        assert_eq!(pair(false).infer_inverse(&poly, &kalshi), None);
+
+        // No mid price without any levels
+        let empty = OrderBook::new(1, None, Vec::<Level>::new(), Vec::<Level>::new());
+        assert_eq!(pair(false).infer_inverse(&empty, &kalshi), None);
+    }
+
+    fn kalshi_leg(ticker: &str, yes_token: &str) -> CorrelatedPair {
+        CorrelatedPair::new(
+            ticker,
+            "0xcondition",
+            yes_token,
+            "0xno",
+            "Test market",
+            Utc::now() + chrono::Duration::days(30),
+            false,
+        )
+    }
+
+    #[test]
+    fn test_group_from_pairs() {
+        let groups = CorrelatedGroup::from_pairs([
+            kalshi_leg("KXBTC-T100", "0xyes"),
+            kalshi_leg("KXETH", "0xother"),
+            kalshi_leg("KXBTC-T105", "0xyes"),
+            kalshi_leg("KXBTC-T100", "0xyes"),
+        ]);
+
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].polymarket_yes_token, "0xyes");
+        assert!(groups[0].is_many_to_one());
+        assert_eq!(
+            groups[0].kalshi_tickers().collect::<Vec<_>>(),
+            vec!["KXBTC-T100", "KXBTC-T105"]
+        );
+        assert_eq!(groups[1].polymarket_yes_token, "0xother");
+        assert!(!groups[1].is_many_to_one());
+    }
+
+    #[test]
+    fn test_group_rejects_other_polymarket_market() {
+        let mut group = CorrelatedGroup::new(kalshi_leg("KXA", "0xyes"));
+        assert!(!group.push(kalshi_leg("KXB", "0xother")));
+        assert!(group.push(kalshi_leg("KXB", "0xyes")));
+        assert_eq!(group.pairs.len(), 2);
+    }
+
+    #[test]
+    fn test_kalshi_instrument_name_round_trip() {
+        for key in [
+            PredictionMarketKey::kalshi_yes("KXBTC-25JAN31-T100000"),
+            PredictionMarketKey::kalshi_no("KXBTC-25JAN31-T100000"),
+            // Underscores in the ticker itself are preserved
+            PredictionMarketKey::kalshi_no("KX_ODD_TICKER"),
+        ] {
+            let name = key.to_instrument_name();
+            assert_eq!(
+                PredictionMarketKey::from_instrument_name(ExchangeId::Kalshi, &name),
+                Some(key)
+            );
+        }
+
+        assert_eq!(
+            PredictionMarketKey::kalshi_yes("KXTEST").to_instrument_name(),
+            "KXTEST_yes"
+        );
+        assert_eq!(
+            PredictionMarketKey::kalshi_no("KXTEST").to_instrument_name(),
+            "KXTEST_no"
+        );
+        assert_eq!(
+            PredictionMarketKey::from_instrument_name(ExchangeId::Kalshi, "KXTEST"),
+            None
+        );
+        assert_eq!(
+            PredictionMarketKey::from_instrument_name(ExchangeId::Kalshi, "_yes"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_polymarket_instrument_name_round_trip() {
+        let pair = kalshi_leg("KXTEST", "0xyes");
+
+        assert_eq!(
+            PredictionMarketKey::polymarket_yes("0xyes").to_instrument_name(),
+            "0xyes"
+        );
+        // Token IDs alone don't identify the outcome
+        assert_eq!(
+            PredictionMarketKey::from_instrument_name(ExchangeId::Polymarket, "0xyes"),
+            None
+        );
+
+        for key in pair.instrument_keys() {
+            let name = key.to_instrument_name();
+            assert_eq!(pair.instrument_key(key.exchange, &name), Some(key));
+        }
+        assert_eq!(
+            pair.instrument_key(ExchangeId::Polymarket, "0xno"),
+            Some(PredictionMarketKey::polymarket_no("0xno"))
+        );
+        assert_eq!(pair.instrument_key(ExchangeId::Kalshi, "0xno"), None);
+    }
+
     #[test]
     fn test_outcome_inverse() {
         assert_eq!(Outcome::Yes.inverse(), Outcome::No);
         assert_eq!(Outcome::No.inverse(), Outcome::Yes);
     }
+
+    fn market_meta(yes: &str, no: &str) -> PolymarketMarketMeta {
+        PolymarketMarketMeta {
+            condition_id: "0xcondition".to_string(),
+            question: "Will BTC be above $100k on Jan 31?".to_string(),
+            yes_token: yes.to_string(),
+            no_token: no.to_string(),
+            neg_risk: false,
+            tick_size: Some(dec!(0.01)),
+            min_order_size: Some(dec!(5)),
+            closed: false,
+        }
+    }
+
+    #[test]
+    fn test_apply_polymarket_meta() {
+        let mut pair = CorrelatedPair::new(
+            "KXBTC-25JAN31-T100000",
+            "0xCONDITION",
+            "",
+            "",
+            "Will BTC be above $100k on Jan 31?",
+            Utc::now(),
+            false,
+        );
+
+        // Missing token ids are filled in
+        assert_eq!(pair.apply_polymarket_meta(&market_meta("0xyes", "0xno")), Ok(()));
+        assert_eq!(pair.polymarket_yes_token, "0xyes");
+        assert_eq!(pair.polymarket_no_token, "0xno");
+
+        // Stale token ids are rejected and left untouched
+        assert_eq!(
+            pair.apply_polymarket_meta(&market_meta("0xyes", "0xother")),
+            Err(PairMetaError::Token {
+                outcome: Outcome::No,
+                expected: SmolStr::new("0xno"),
+                found: "0xother".to_string(),
+            })
+        );
+        assert_eq!(pair.polymarket_no_token, "0xno");
+
+        pair.polymarket_condition_id = SmolStr::new("0xelsewhere");
+        assert!(matches!(
+            pair.apply_polymarket_meta(&market_meta("0xyes", "0xno")),
+            Err(PairMetaError::Condition { .. })
+        ));
+    }
 }
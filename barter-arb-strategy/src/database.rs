@@ -3,16 +3,34 @@
 //! This module provides a Rust implementation of the same database queries
 //! used by the TypeScript arbitrage bot, enabling the barter strategy to
 //! fetch market pairs directly.
+//!
+//! Detected opportunities can also be recorded to a `detected_opportunities`
+//! table via [`DatabaseQuerier::insert_opportunity`], which batches rows
+//! before flushing to avoid hammering the REST API on busy markets.
 
-use crate::correlation::CorrelatedPair;
+use crate::{
+    correlation::CorrelatedPair,
+    opportunity::{ArbitrageDirection, ArbitrageOpportunity},
+};
+use barter_instrument::exchange::ExchangeId;
 use chrono::{DateTime, Utc};
 use reqwest::Client;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use smol_str::SmolStr;
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
 use thiserror::Error;
 use tracing::{debug, warn};
 
+/// Default table that detected opportunities are inserted into.
+pub const DEFAULT_OPPORTUNITIES_TABLE: &str = "detected_opportunities";
+
+/// Default table that market pairs are stored in.
+pub const DEFAULT_MARKET_PAIRS_TABLE: &str = "market_pairs";
+
 /// Errors that can occur when querying the database.
 #[derive(Debug, Error)]
 pub enum DatabaseError {
@@ -27,9 +45,22 @@ pub enum DatabaseError {
 
     #[error("Missing configuration: {0}")]
     Config(String),
+
+    #[cfg(feature = "postgres")]
+    #[error("Postgres query failed: {0}")]
+    Postgres(#[from] sqlx::Error),
 }
 
 /// Filters for querying market pairs.
+///
+/// `min_similarity`, `min_confidence`, `limit`, `offset` and `valid_only` are sent
+/// to the `get_market_pairs_with_volume` RPC and applied server-side.
+///
+/// `min_polymarket_volume`, `min_kalshi_volume`, `max_days_to_expiry` and
+/// `ticker_prefix` are not accepted by the RPC, so they are applied client-side
+/// after deserialization (see [`MarketPairFilters::matches`]). Note that `limit`
+/// applies before client-side filtering, so fewer than `limit` records may be
+/// returned. Records missing a field that is filtered on are excluded.
 #[derive(Debug, Clone, Default)]
 pub struct MarketPairFilters {
     /// Minimum similarity score (0.0-1.0)
@@ -42,6 +73,14 @@ pub struct MarketPairFilters {
     pub offset: Option<u32>,
     /// Only return validated pairs
     pub valid_only: Option<bool>,
+    /// Minimum Polymarket volume (client-side)
+    pub min_polymarket_volume: Option<Decimal>,
+    /// Minimum Kalshi volume (client-side)
+    pub min_kalshi_volume: Option<Decimal>,
+    /// Maximum days until Kalshi expiry; expired markets are excluded (client-side)
+    pub max_days_to_expiry: Option<i64>,
+    /// Only return pairs whose Kalshi ticker starts with this prefix (client-side)
+    pub ticker_prefix: Option<String>,
 }
 
 impl MarketPairFilters {
@@ -53,6 +92,7 @@ impl MarketPairFilters {
             limit: Some(100),
             offset: Some(0),
             valid_only: Some(true),
+            ..Default::default()
         }
     }
 
@@ -64,8 +104,63 @@ impl MarketPairFilters {
             limit: Some(20),
             offset: Some(0),
             valid_only: Some(true),
+            ..Default::default()
+        }
+    }
+
+    /// Fill unset query parameters with the defaults from [`Self::default_filters`].
+    pub fn with_query_defaults(self) -> Self {
+        let defaults = Self::default_filters();
+        Self {
+            min_similarity: self.min_similarity.or(defaults.min_similarity),
+            min_confidence: self.min_confidence.or(defaults.min_confidence),
+            limit: self.limit.or(defaults.limit),
+            offset: self.offset.or(defaults.offset),
+            valid_only: self.valid_only.or(defaults.valid_only),
+            ..self
         }
     }
+
+    /// Check whether a record passes the client-side filters.
+    pub fn matches(&self, record: &MarketPairRecord) -> bool {
+        self.matches_at(record, Utc::now())
+    }
+
+    /// Check whether a record passes the client-side filters at the given time.
+    pub fn matches_at(&self, record: &MarketPairRecord, now: DateTime<Utc>) -> bool {
+        let meets_min = |volume: Option<Decimal>, min: Option<Decimal>| match min {
+            Some(min) => volume.is_some_and(|volume| volume >= min),
+            None => true,
+        };
+
+        if !meets_min(record.polymarket_volume, self.min_polymarket_volume)
+            || !meets_min(record.kalshi_volume, self.min_kalshi_volume)
+        {
+            return false;
+        }
+
+        if let Some(max_days) = self.max_days_to_expiry {
+            let Some(expiry) = record.kalshi_expiry else {
+                return false;
+            };
+            if expiry <= now || (expiry - now).num_days() > max_days {
+                return false;
+            }
+        }
+
+        if let Some(prefix) = &self.ticker_prefix {
+            if !record.kalshi_ticker.starts_with(prefix.as_str()) {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Retain only records that pass the client-side filters.
+    pub fn apply(&self, records: Vec<MarketPairRecord>) -> Vec<MarketPairRecord> {
+        records.into_iter().filter(|record| self.matches(record)).collect()
+    }
 }
 
 /// Raw market pair record from the database.
@@ -139,7 +234,8 @@ impl MarketPairRecord {
             self.polymarket_question.as_deref().unwrap_or(""),
             expiry,
             self.inverse.unwrap_or(false),
-        ))
+        )
+        .with_db_id(self.id))
     }
 
     /// Check if this is a valid, usable market pair.
@@ -177,6 +273,29 @@ fn parse_token_id(value: &Option<String>, index: usize) -> Option<SmolStr> {
     None
 }
 
+/// Convert market pair records to CorrelatedPairs.
+///
+/// Skips records that are invalid or can't be converted (missing token IDs, etc.).
+pub fn records_to_pairs(records: Vec<MarketPairRecord>) -> Vec<CorrelatedPair> {
+    let pairs: Vec<CorrelatedPair> = records
+        .into_iter()
+        .filter_map(|record| {
+            if !record.is_valid() {
+                warn!(
+                    "Skipping invalid market pair: kalshi={}, valid={:?}",
+                    record.kalshi_ticker, record.valid
+                );
+                return None;
+            }
+            record.to_correlated_pair()
+        })
+        .collect();
+
+    debug!("Converted {} records to correlated pairs", pairs.len());
+
+    pairs
+}
+
 /// RPC request parameters for get_market_pairs_with_volume.
 #[derive(Debug, Serialize)]
 struct RpcParams {
@@ -195,15 +314,162 @@ struct SupabaseError {
     code: Option<String>,
 }
 
+/// Build a [`DatabaseError`] from a non-success response.
+async fn response_error(response: reqwest::Response) -> DatabaseError {
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+
+    // Try to parse as Supabase error
+    if let Ok(err) = serde_json::from_str::<SupabaseError>(&body) {
+        return DatabaseError::Database {
+            message: err.message,
+            code: err.code,
+        };
+    }
+
+    DatabaseError::Database {
+        message: format!("HTTP {}: {}", status, body),
+        code: None,
+    }
+}
+
+/// Prefix of the `validation_result` of pairs flagged by
+/// [`DatabaseQuerier::flag_pair_for_revalidation`], so the validator can find them.
+pub const REVALIDATION_PREFIX: &str = "needs_revalidation";
+
+/// PATCH body marking a market pair invalid.
+#[derive(Debug, Serialize)]
+struct InvalidatePairBody<'a> {
+    valid: bool,
+    validation_result: &'a str,
+}
+
+/// Row inserted into the detected opportunities table.
+///
+/// One row per detected opportunity, with `rejection_reason` populated when the
+/// opportunity was detected but filtered out before order generation.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct OpportunityRecord {
+    pub kalshi_ticker: String,
+    pub polymarket_condition_id: String,
+    pub direction: ArbitrageDirection,
+    pub yes_exchange: ExchangeId,
+    pub yes_market_id: String,
+    pub yes_price: Decimal,
+    pub no_exchange: ExchangeId,
+    pub no_market_id: String,
+    pub no_price: Decimal,
+    pub total_cost: Decimal,
+    pub max_contracts: u32,
+    pub expected_profit: Decimal,
+    pub profit_per_contract: Decimal,
+    pub total_fees: Decimal,
+    #[serde(default)]
+    pub rejection_reason: Option<String>,
+    pub detected_at: DateTime<Utc>,
+}
+
+impl OpportunityRecord {
+    /// Build a record from a detected opportunity and optional rejection reason.
+    pub fn new(opportunity: &ArbitrageOpportunity, rejection: Option<&str>) -> Self {
+        Self {
+            kalshi_ticker: opportunity.pair.kalshi_ticker.to_string(),
+            polymarket_condition_id: opportunity.pair.polymarket_condition_id.to_string(),
+            direction: opportunity.direction,
+            yes_exchange: opportunity.yes_side.exchange,
+            yes_market_id: opportunity.yes_side.instrument.market_id.to_string(),
+            yes_price: opportunity.avg_yes_price,
+            no_exchange: opportunity.no_side.exchange,
+            no_market_id: opportunity.no_side.instrument.market_id.to_string(),
+            no_price: opportunity.avg_no_price,
+            total_cost: opportunity.total_cost,
+            max_contracts: opportunity.max_contracts,
+            expected_profit: opportunity.expected_profit,
+            profit_per_contract: opportunity.profit_per_contract(),
+            total_fees: opportunity.total_fees,
+            rejection_reason: rejection.map(str::to_string),
+            detected_at: Utc::now(),
+        }
+    }
+}
+
+/// Batching parameters for opportunity inserts.
+///
+/// Rows are flushed once `max_rows` have accumulated, or once the oldest
+/// buffered row is older than `max_age` (checked on each insert, and periodically by
+/// [`DatabaseQuerier::run_opportunity_flusher`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OpportunityBatchConfig {
+    /// Flush once this many rows are buffered.
+    pub max_rows: usize,
+    /// Flush once the oldest buffered row is older than this.
+    pub max_age: Duration,
+}
+
+impl Default for OpportunityBatchConfig {
+    fn default() -> Self {
+        Self {
+            max_rows: 50,
+            max_age: Duration::from_secs(10),
+        }
+    }
+}
+
+/// Buffered opportunity rows awaiting a flush.
+#[derive(Debug, Default)]
+struct OpportunityBuffer {
+    rows: Vec<OpportunityRecord>,
+    oldest: Option<Instant>,
+}
+
+impl OpportunityBuffer {
+    /// Push a row, returning the drained batch if a flush trigger was hit.
+    fn push(
+        &mut self,
+        row: OpportunityRecord,
+        config: &OpportunityBatchConfig,
+        now: Instant,
+    ) -> Option<Vec<OpportunityRecord>> {
+        let oldest = *self.oldest.get_or_insert(now);
+        self.rows.push(row);
+
+        let size_trigger = self.rows.len() >= config.max_rows;
+        let time_trigger = now.duration_since(oldest) >= config.max_age;
+
+        (size_trigger || time_trigger).then(|| self.drain())
+    }
+
+    /// Drain the buffer if its oldest row is at least `max_age` old.
+    fn drain_expired(
+        &mut self,
+        config: &OpportunityBatchConfig,
+        now: Instant,
+    ) -> Option<Vec<OpportunityRecord>> {
+        let oldest = self.oldest?;
+        (now.duration_since(oldest) >= config.max_age).then(|| self.drain())
+    }
+
+    fn drain(&mut self) -> Vec<OpportunityRecord> {
+        self.oldest = None;
+        std::mem::take(&mut self.rows)
+    }
+}
+
 /// Database querier for Supabase.
 ///
 /// Fetches correlated market pairs using the same RPC function
 /// as the TypeScript implementation.
+///
+/// Clones share the same opportunity insert buffer.
 #[derive(Debug, Clone)]
 pub struct DatabaseQuerier {
     client: Client,
     base_url: String,
     api_key: String,
+    market_pairs_table: String,
+    opportunities_table: String,
+    opportunity_batch: OpportunityBatchConfig,
+    opportunity_buffer: Arc<Mutex<OpportunityBuffer>>,
 }
 
 impl DatabaseQuerier {
@@ -217,12 +483,37 @@ impl DatabaseQuerier {
             client: Client::new(),
             base_url: supabase_url.into(),
             api_key: api_key.into(),
+            market_pairs_table: DEFAULT_MARKET_PAIRS_TABLE.to_string(),
+            opportunities_table: DEFAULT_OPPORTUNITIES_TABLE.to_string(),
+            opportunity_batch: OpportunityBatchConfig::default(),
+            opportunity_buffer: Arc::new(Mutex::new(OpportunityBuffer::default())),
         }
     }
 
+    /// Set the table market pairs are stored in (used by [`Self::mark_pair_invalid`]).
+    pub fn with_market_pairs_table(mut self, table: impl Into<String>) -> Self {
+        self.market_pairs_table = table.into();
+        self
+    }
+
+    /// Set the table detected opportunities are inserted into.
+    pub fn with_opportunities_table(mut self, table: impl Into<String>) -> Self {
+        self.opportunities_table = table.into();
+        self
+    }
+
+    /// Set the batching parameters for opportunity inserts.
+    pub fn with_opportunity_batch(mut self, batch: OpportunityBatchConfig) -> Self {
+        self.opportunity_batch = batch;
+        self
+    }
+
     /// Create from environment variables.
     ///
     /// Reads `SUPABASE_URL` and `SUPABASE_SERVICE_KEY` (or `SUPABASE_ANON_KEY`).
+    /// The opportunities and market pairs tables default to [`DEFAULT_OPPORTUNITIES_TABLE`]
+    /// and [`DEFAULT_MARKET_PAIRS_TABLE`], and can be overridden with
+    /// `SUPABASE_OPPORTUNITIES_TABLE` and `SUPABASE_MARKET_PAIRS_TABLE`.
     pub fn from_env() -> Result<Self, DatabaseError> {
         let url = std::env::var("SUPABASE_URL")
             .map_err(|_| DatabaseError::Config("SUPABASE_URL not set".into()))?;
@@ -233,7 +524,20 @@ impl DatabaseQuerier {
                 DatabaseError::Config("SUPABASE_SERVICE_KEY or SUPABASE_ANON_KEY not set".into())
             })?;
 
-        Ok(Self::new(url, key))
+        let mut querier = Self::new(url, key);
+
+        if let Ok(table) = std::env::var("SUPABASE_OPPORTUNITIES_TABLE") {
+            if !table.is_empty() {
+                querier = querier.with_opportunities_table(table);
+            }
+        }
+        if let Ok(table) = std::env::var("SUPABASE_MARKET_PAIRS_TABLE") {
+            if !table.is_empty() {
+                querier = querier.with_market_pairs_table(table);
+            }
+        }
+
+        Ok(querier)
     }
 
     /// Query correlated market pairs from the database.
@@ -243,13 +547,7 @@ impl DatabaseQuerier {
         &self,
         filters: MarketPairFilters,
     ) -> Result<Vec<MarketPairRecord>, DatabaseError> {
-        let filters = MarketPairFilters {
-            min_similarity: filters.min_similarity.or(Some(Decimal::new(70, 2))),
-            min_confidence: filters.min_confidence.or(Some(Decimal::new(70, 2))),
-            limit: filters.limit.or(Some(100)),
-            offset: filters.offset.or(Some(0)),
-            valid_only: filters.valid_only.or(Some(true)),
-        };
+        let filters = filters.with_query_defaults();
 
         let params = RpcParams {
             min_similarity: filters.min_similarity.unwrap(),
@@ -274,28 +572,21 @@ impl DatabaseQuerier {
             .await?;
 
         if !response.status().is_success() {
-            let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-
-            // Try to parse as Supabase error
-            if let Ok(err) = serde_json::from_str::<SupabaseError>(&body) {
-                return Err(DatabaseError::Database {
-                    message: err.message,
-                    code: err.code,
-                });
-            }
-
-            return Err(DatabaseError::Database {
-                message: format!("HTTP {}: {}", status, body),
-                code: None,
-            });
+            return Err(response_error(response).await);
         }
 
         let records: Vec<MarketPairRecord> = response.json().await.map_err(|e| {
             DatabaseError::Parse(format!("Failed to parse market pairs: {}", e))
         })?;
 
-        debug!("Fetched {} market pairs", records.len());
+        let fetched = records.len();
+        let records = filters.apply(records);
+
+        debug!(
+            "Fetched {} market pairs ({} after client-side filters)",
+            fetched,
+            records.len()
+        );
 
         Ok(records)
     }
@@ -313,30 +604,413 @@ impl DatabaseQuerier {
         filters: MarketPairFilters,
     ) -> Result<Vec<CorrelatedPair>, DatabaseError> {
         let records = self.get_market_pairs(filters).await?;
+        Ok(records_to_pairs(records))
+    }
 
-        let pairs: Vec<CorrelatedPair> = records
-            .into_iter()
-            .filter_map(|record| {
-                if !record.is_valid() {
-                    warn!(
-                        "Skipping invalid market pair: kalshi={}, valid={:?}",
-                        record.kalshi_ticker, record.valid
-                    );
-                    return None;
-                }
-                record.to_correlated_pair()
+    /// Mark a market pair invalid after it failed validation at runtime.
+    ///
+    /// Sets `valid = false` and `validation_result = reason` on the row with `id`.
+    /// Requires a key with write access (e.g. the service key).
+    pub async fn mark_pair_invalid(&self, id: i64, reason: &str) -> Result<(), DatabaseError> {
+        let url = format!(
+            "{}/rest/v1/{}?id=eq.{}",
+            self.base_url, self.market_pairs_table, id
+        );
+
+        debug!(id, reason, "Marking market pair invalid");
+
+        let response = self
+            .client
+            .patch(&url)
+            .header("apikey", &self.api_key)
+            .header("Authorization", format!("Bearer {}", self.api_key))
+            .header("Content-Type", "application/json")
+            .header("Prefer", "return=minimal")
+            .json(&InvalidatePairBody {
+                valid: false,
+                validation_result: reason,
             })
-            .collect();
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(response_error(response).await);
+        }
+
+        Ok(())
+    }
+
+    /// Flag a market pair whose live pricing looks inconsistent for re-validation.
+    ///
+    /// Like [`Self::mark_pair_invalid`] the pair stops being loaded, but its
+    /// `validation_result` is `"{REVALIDATION_PREFIX}: {reason}"`, telling the validator
+    /// to check the pair again rather than treating it as known bad.
+    pub async fn flag_pair_for_revalidation(
+        &self,
+        id: i64,
+        reason: &str,
+    ) -> Result<(), DatabaseError> {
+        self.mark_pair_invalid(id, &format!("{REVALIDATION_PREFIX}: {reason}"))
+            .await
+    }
+
+    /// Record a detected opportunity to the opportunities table.
+    ///
+    /// Rows are buffered and only sent once the batch size or age trigger in
+    /// [`OpportunityBatchConfig`] is hit. A failed flush drops the batch.
+    pub async fn insert_opportunity(
+        &self,
+        opportunity: &ArbitrageOpportunity,
+        rejection: Option<&str>,
+    ) -> Result<(), DatabaseError> {
+        let row = OpportunityRecord::new(opportunity, rejection);
+
+        let batch = self
+            .opportunity_buffer
+            .lock()
+            .expect("opportunity buffer lock poisoned")
+            .push(row, &self.opportunity_batch, Instant::now());
+
+        match batch {
+            Some(rows) => self.insert_opportunity_rows(&rows).await,
+            None => Ok(()),
+        }
+    }
+
+    /// Flush any buffered opportunity rows regardless of batch triggers.
+    pub async fn flush_opportunities(&self) -> Result<(), DatabaseError> {
+        let rows = self
+            .opportunity_buffer
+            .lock()
+            .expect("opportunity buffer lock poisoned")
+            .drain();
+
+        if rows.is_empty() {
+            return Ok(());
+        }
+
+        self.insert_opportunity_rows(&rows).await
+    }
+
+    /// Flush buffered opportunity rows that reached the batch age, checking every
+    /// `max_age`, until every other clone of this querier is dropped.
+    ///
+    /// Without it, rows buffered before a quiet period wait for the next insert.
+    pub async fn run_opportunity_flusher(self) {
+        let mut interval = tokio::time::interval(self.opportunity_batch.max_age);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
 
-        debug!("Converted {} records to correlated pairs", pairs.len());
+            if Arc::strong_count(&self.opportunity_buffer) == 1 {
+                debug!("Opportunity buffer no longer shared, stopping flusher");
+                return;
+            }
+
+            let rows = self
+                .opportunity_buffer
+                .lock()
+                .expect("opportunity buffer lock poisoned")
+                .drain_expired(&self.opportunity_batch, Instant::now());
+
+            if let Some(rows) = rows {
+                if let Err(e) = self.insert_opportunity_rows(&rows).await {
+                    warn!("Failed to flush recorded opportunities: {}", e);
+                }
+            }
+        }
+    }
 
-        Ok(pairs)
+    /// Number of opportunity rows currently buffered.
+    pub fn pending_opportunities(&self) -> usize {
+        self.opportunity_buffer
+            .lock()
+            .expect("opportunity buffer lock poisoned")
+            .rows
+            .len()
+    }
+
+    /// Insert a batch of opportunity rows using the REST insert endpoint.
+    async fn insert_opportunity_rows(
+        &self,
+        rows: &[OpportunityRecord],
+    ) -> Result<(), DatabaseError> {
+        let url = format!("{}/rest/v1/{}", self.base_url, self.opportunities_table);
+
+        debug!(rows = rows.len(), %url, "Flushing detected opportunities");
+
+        let response = self
+            .client
+            .post(&url)
+            .header("apikey", &self.api_key)
+            .header("Authorization", format!("Bearer {}", self.api_key))
+            .header("Content-Type", "application/json")
+            .header("Prefer", "return=minimal")
+            .json(rows)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(response_error(response).await);
+        }
+
+        Ok(())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{correlation::Outcome, opportunity::OrderSide};
+    use rust_decimal_macros::dec;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    /// Requests received by the mock endpoint: (request line + headers, JSON body).
+    type Received = Arc<Mutex<Vec<(String, serde_json::Value)>>>;
+
+    /// Minimal HTTP server that records each request and replies `201 Created`.
+    async fn mock_endpoint() -> (String, Received) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let received = Received::default();
+
+        let log = Arc::clone(&received);
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let log = Arc::clone(&log);
+                tokio::spawn(async move {
+                    let mut buf = Vec::new();
+                    let mut chunk = [0u8; 4096];
+                    loop {
+                        let Some(header_end) =
+                            buf.windows(4).position(|w| w == b"\r\n\r\n").map(|i| i + 4)
+                        else {
+                            match socket.read(&mut chunk).await {
+                                Ok(0) | Err(_) => return,
+                                Ok(n) => buf.extend_from_slice(&chunk[..n]),
+                            }
+                            continue;
+                        };
+
+                        let head = String::from_utf8_lossy(&buf[..header_end]).to_string();
+                        let content_length = head
+                            .lines()
+                            .find_map(|line| {
+                                let (name, value) = line.split_once(':')?;
+                                name.eq_ignore_ascii_case("content-length")
+                                    .then(|| value.trim().parse::<usize>().ok())?
+                            })
+                            .unwrap_or(0);
+
+                        while buf.len() < header_end + content_length {
+                            match socket.read(&mut chunk).await {
+                                Ok(0) | Err(_) => return,
+                                Ok(n) => buf.extend_from_slice(&chunk[..n]),
+                            }
+                        }
+
+                        let body = &buf[header_end..header_end + content_length];
+                        let json = serde_json::from_slice(body).unwrap_or(serde_json::Value::Null);
+                        log.lock().unwrap().push((head, json));
+
+                        buf.drain(..header_end + content_length);
+                        let response = b"HTTP/1.1 201 Created\r\ncontent-length: 0\r\n\r\n";
+                        if socket.write_all(response).await.is_err() {
+                            return;
+                        }
+                    }
+                });
+            }
+        });
+
+        (base_url, received)
+    }
+
+    fn test_opportunity() -> ArbitrageOpportunity {
+        let pair = CorrelatedPair::new(
+            "KXTEST-25JAN31",
+            "0xcondition",
+            "0xyes_token",
+            "0xno_token",
+            "Test market",
+            Utc::now() + chrono::Duration::days(30),
+            false,
+        );
+
+        ArbitrageOpportunity {
+            pair,
+            direction: ArbitrageDirection::YesPolyNoKalshi,
+            yes_side: OrderSide::poly("0xyes_token", Outcome::Yes, dec!(0.40), 100),
+            no_side: OrderSide::kalshi("KXTEST-25JAN31", Outcome::No, dec!(0.54), 100),
+            total_cost: dec!(0.96),
+            avg_yes_price: dec!(0.40),
+            avg_no_price: dec!(0.54),
+            max_contracts: 100,
+            expected_profit: dec!(4.00),
+            total_fees: dec!(2.00),
+        }
+    }
+
+    #[test]
+    fn test_opportunity_record_from_opportunity() {
+        let record = OpportunityRecord::new(&test_opportunity(), Some("below_threshold"));
+
+        assert_eq!(record.kalshi_ticker, "KXTEST-25JAN31");
+        assert_eq!(record.yes_exchange, ExchangeId::Polymarket);
+        assert_eq!(record.yes_market_id, "0xyes_token");
+        assert_eq!(record.no_exchange, ExchangeId::Kalshi);
+        assert_eq!(record.profit_per_contract, dec!(0.04));
+        assert_eq!(record.rejection_reason.as_deref(), Some("below_threshold"));
+    }
+
+    #[tokio::test]
+    async fn test_insert_opportunity_flushes_on_batch_size() {
+        let (base_url, received) = mock_endpoint().await;
+        let db = DatabaseQuerier::new(base_url, "key")
+            .with_opportunities_table("opps")
+            .with_opportunity_batch(OpportunityBatchConfig {
+                max_rows: 3,
+                max_age: Duration::from_secs(3600),
+            });
+        let opp = test_opportunity();
+
+        db.insert_opportunity(&opp, None).await.unwrap();
+        db.insert_opportunity(&opp, Some("position_limit")).await.unwrap();
+        assert!(received.lock().unwrap().is_empty());
+        assert_eq!(db.pending_opportunities(), 2);
+
+        db.insert_opportunity(&opp, None).await.unwrap();
+        assert_eq!(db.pending_opportunities(), 0);
+
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 1);
+        let (head, body) = &received[0];
+        assert!(head.starts_with("POST /rest/v1/opps "));
+        let rows = body.as_array().unwrap();
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[1]["rejection_reason"], "position_limit");
+        assert_eq!(rows[0]["kalshi_ticker"], "KXTEST-25JAN31");
+    }
+
+    #[tokio::test]
+    async fn test_insert_opportunity_flushes_on_batch_age() {
+        let (base_url, received) = mock_endpoint().await;
+        let db = DatabaseQuerier::new(base_url, "key").with_opportunity_batch(
+            OpportunityBatchConfig {
+                max_rows: 100,
+                max_age: Duration::from_millis(50),
+            },
+        );
+        let opp = test_opportunity();
+
+        db.insert_opportunity(&opp, None).await.unwrap();
+        assert!(received.lock().unwrap().is_empty());
+
+        tokio::time::sleep(Duration::from_millis(80)).await;
+        db.insert_opportunity(&opp, None).await.unwrap();
+
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 1);
+        assert!(received[0]
+            .0
+            .starts_with(&format!("POST /rest/v1/{DEFAULT_OPPORTUNITIES_TABLE} ")));
+        assert_eq!(received[0].1.as_array().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_opportunity_flusher_flushes_aged_rows_without_inserts() {
+        let (base_url, received) = mock_endpoint().await;
+        let db = DatabaseQuerier::new(base_url, "key").with_opportunity_batch(
+            OpportunityBatchConfig {
+                max_rows: 100,
+                max_age: Duration::from_millis(50),
+            },
+        );
+        let flusher = tokio::spawn(db.clone().run_opportunity_flusher());
+
+        db.insert_opportunity(&test_opportunity(), None).await.unwrap();
+        assert!(received.lock().unwrap().is_empty());
+
+        // No further inserts, the flusher sends the row once it ages out
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        assert_eq!(db.pending_opportunities(), 0);
+        assert_eq!(received.lock().unwrap().len(), 1);
+
+        // Stops once it holds the last reference to the buffer
+        drop(db);
+        tokio::time::timeout(Duration::from_secs(1), flusher)
+            .await
+            .unwrap()
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_flush_opportunities_drains_buffer() {
+        let (base_url, received) = mock_endpoint().await;
+        let db = DatabaseQuerier::new(base_url, "key");
+
+        db.flush_opportunities().await.unwrap();
+        assert!(received.lock().unwrap().is_empty());
+
+        db.insert_opportunity(&test_opportunity(), None).await.unwrap();
+        db.flush_opportunities().await.unwrap();
+
+        assert_eq!(db.pending_opportunities(), 0);
+        assert_eq!(received.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_mark_pair_invalid_patch() {
+        let (base_url, received) = mock_endpoint().await;
+        let db = DatabaseQuerier::new(base_url, "secret-key");
+
+        db.mark_pair_invalid(42, "orderbooks missing for 3600s").await.unwrap();
+
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 1);
+        let (head, body) = &received[0];
+        let head = head.to_ascii_lowercase();
+
+        assert!(head.starts_with("patch /rest/v1/market_pairs?id=eq.42 "));
+        assert!(head.contains("apikey: secret-key\r\n"));
+        assert!(head.contains("authorization: bearer secret-key\r\n"));
+        assert!(head.contains("content-type: application/json\r\n"));
+        assert!(head.contains("prefer: return=minimal\r\n"));
+        assert_eq!(
+            body,
+            &serde_json::json!({
+                "valid": false,
+                "validation_result": "orderbooks missing for 3600s",
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_flag_pair_for_revalidation_patch() {
+        let (base_url, received) = mock_endpoint().await;
+        let db = DatabaseQuerier::new(base_url, "secret-key");
+
+        db.flag_pair_for_revalidation(42, "prices imply inverse=true for 3 scans")
+            .await
+            .unwrap();
+
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 1);
+        let (head, body) = &received[0];
+
+        assert!(head.to_ascii_lowercase().starts_with("patch /rest/v1/market_pairs?id=eq.42 "));
+        assert_eq!(
+            body,
+            &serde_json::json!({
+                "valid": false,
+                "validation_result": "needs_revalidation: prices imply inverse=true for 3 scans",
+            })
+        );
+    }
 
     #[test]
     fn test_parse_token_id_json_array() {
@@ -376,6 +1050,113 @@ mod tests {
         assert_eq!(filters.valid_only, Some(true));
     }
 
+    fn filter_record(
+        ticker: &str,
+        polymarket_volume: Option<Decimal>,
+        kalshi_volume: Option<Decimal>,
+        kalshi_expiry: Option<DateTime<Utc>>,
+    ) -> MarketPairRecord {
+        MarketPairRecord {
+            id: 1,
+            polymarket_id: "poly123".to_string(),
+            polymarket_condition_id: Some("0xcondition".to_string()),
+            polymarket_yes_token_id: None,
+            kalshi_ticker: ticker.to_string(),
+            similarity_score: Decimal::new(95, 2),
+            confidence_score: Decimal::new(90, 2),
+            evaluation_id: 1,
+            llm_notes: None,
+            discovered_at: Utc::now(),
+            last_verified_at: None,
+            verified_at: None,
+            valid: Some(true),
+            validation_result: None,
+            inverse: None,
+            polymarket_volume,
+            kalshi_volume,
+            polymarket_question: None,
+            kalshi_expiry,
+        }
+    }
+
+    #[test]
+    fn test_market_pair_filters_min_volumes() {
+        let poly_filter = MarketPairFilters {
+            min_polymarket_volume: Some(dec!(1000)),
+            ..Default::default()
+        };
+        assert!(poly_filter.matches(&filter_record("KXA", Some(dec!(1000)), None, None)));
+        assert!(!poly_filter.matches(&filter_record("KXA", Some(dec!(999)), None, None)));
+        assert!(!poly_filter.matches(&filter_record("KXA", None, Some(dec!(5000)), None)));
+
+        let kalshi_filter = MarketPairFilters {
+            min_kalshi_volume: Some(dec!(500)),
+            ..Default::default()
+        };
+        assert!(kalshi_filter.matches(&filter_record("KXA", None, Some(dec!(600)), None)));
+        assert!(!kalshi_filter.matches(&filter_record("KXA", Some(dec!(5000)), Some(dec!(100)), None)));
+        assert!(!kalshi_filter.matches(&filter_record("KXA", None, None, None)));
+    }
+
+    #[test]
+    fn test_market_pair_filters_max_days_to_expiry() {
+        let now = Utc::now();
+        let filters = MarketPairFilters {
+            max_days_to_expiry: Some(30),
+            ..Default::default()
+        };
+
+        let soon = filter_record("KXA", None, None, Some(now + chrono::Duration::days(10)));
+        let late = filter_record("KXA", None, None, Some(now + chrono::Duration::days(45)));
+        let expired = filter_record("KXA", None, None, Some(now - chrono::Duration::days(1)));
+        let unknown = filter_record("KXA", None, None, None);
+
+        assert!(filters.matches_at(&soon, now));
+        assert!(!filters.matches_at(&late, now));
+        assert!(!filters.matches_at(&expired, now));
+        assert!(!filters.matches_at(&unknown, now));
+    }
+
+    #[test]
+    fn test_market_pair_filters_ticker_prefix() {
+        let filters = MarketPairFilters {
+            ticker_prefix: Some("KXBTC".to_string()),
+            ..Default::default()
+        };
+
+        assert!(filters.matches(&filter_record("KXBTC-25JAN31", None, None, None)));
+        assert!(!filters.matches(&filter_record("KXETH-25JAN31", None, None, None)));
+    }
+
+    #[test]
+    fn test_market_pair_filters_combined() {
+        let now = Utc::now();
+        let expiry = Some(now + chrono::Duration::days(5));
+        let filters = MarketPairFilters {
+            min_polymarket_volume: Some(dec!(1000)),
+            min_kalshi_volume: Some(dec!(500)),
+            max_days_to_expiry: Some(7),
+            ticker_prefix: Some("KXBTC".to_string()),
+            ..MarketPairFilters::default_filters()
+        };
+
+        let records = vec![
+            filter_record("KXBTC-A", Some(dec!(2000)), Some(dec!(800)), expiry),
+            filter_record("KXETH-B", Some(dec!(2000)), Some(dec!(800)), expiry),
+            filter_record("KXBTC-C", Some(dec!(10)), Some(dec!(800)), expiry),
+            filter_record("KXBTC-D", Some(dec!(2000)), Some(dec!(10)), expiry),
+            filter_record("KXBTC-E", Some(dec!(2000)), Some(dec!(800)), Some(now + chrono::Duration::days(20))),
+        ];
+
+        let filtered = filters.apply(records);
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(filtered[0].kalshi_ticker, "KXBTC-A");
+
+        // No client-side filters set: everything passes
+        let unfiltered = MarketPairFilters::default_filters();
+        assert!(unfiltered.matches(&filter_record("KXETH-B", None, None, None)));
+    }
+
     #[test]
     fn test_market_pair_record_to_correlated_pair() {
         let record = MarketPairRecord {
@@ -406,5 +1187,6 @@ mod tests {
         assert_eq!(pair.kalshi_ticker.as_str(), "KXTEST-25JAN31");
         assert_eq!(pair.polymarket_condition_id.as_str(), "0xcondition");
         assert_eq!(pair.description, "Will X happen?");
+        assert_eq!(pair.db_id, Some(1));
     }
 }
@@ -2,7 +2,12 @@
 //!
 //! Uses rust_decimal for exact precision in financial calculations.
 
-use rust_decimal::Decimal;
+use crate::opportunity::ArbitrageOpportunity;
+use barter_instrument::{exchange::ExchangeId, kalshi};
+use rust_decimal::{Decimal, MathematicalOps};
+
+/// Kalshi taker fee rate in basis points of profit potential (700 = 7%).
+pub const KALSHI_TAKER_FEE_BPS: u32 = 700;
 
 /// Fee calculator for prediction market platforms.
 pub struct FeeCalculator;
@@ -10,7 +15,8 @@ pub struct FeeCalculator;
 impl FeeCalculator {
     /// Kalshi taker fee: 7% of profit potential.
     ///
-    /// Formula: 0.07 * contracts * price * (1 - price)
+    /// Formula: 0.07 * contracts * price * (1 - price), rounded up to the next cent
+    /// as Kalshi bills it, see [`kalshi::fee::taker_fee`].
     ///
     /// This is based on the maximum profit potential of a binary contract,
     /// where buying at price P means max profit is (1 - P) if YES wins.
@@ -22,9 +28,16 @@ impl FeeCalculator {
     /// # Returns
     /// Fee amount in dollars
     pub fn kalshi_taker_fee(price: Decimal, contracts: u32) -> Decimal {
-        let c = Decimal::from(contracts);
-        let fee_rate = Decimal::new(7, 2); // 0.07
-        fee_rate * c * price * (Decimal::ONE - price)
+        kalshi::fee::taker_fee(price, Decimal::from(contracts))
+    }
+
+    /// Kalshi taker fee at a rate of `fee_bps` of profit potential.
+    ///
+    /// Formula: contracts * price * (1 - price) * (fee_bps / 10000), rounded up to the
+    /// next cent as [`Self::kalshi_taker_fee`].
+    pub fn kalshi_taker_fee_bps(price: Decimal, contracts: u32, fee_bps: u32) -> Decimal {
+        let fee_rate = Decimal::new(fee_bps as i64, 4);
+        kalshi::fee::taker_fee_at_rate(fee_rate, price, Decimal::from(contracts))
     }
 
     /// Kalshi maker fee: 0% (makers pay no fees).
@@ -49,6 +62,48 @@ impl FeeCalculator {
         c * price * bps
     }
 
+    /// Taker fee for buying `contracts` at `price` on `exchange`.
+    ///
+    /// Exchanges other than Kalshi and Polymarket are fee free.
+    pub fn taker_fee(
+        exchange: ExchangeId,
+        price: Decimal,
+        contracts: u32,
+        poly_fee_bps: u32,
+        kalshi_fee_bps: u32,
+    ) -> Decimal {
+        match exchange {
+            ExchangeId::Kalshi => Self::kalshi_taker_fee_bps(price, contracts, kalshi_fee_bps),
+            ExchangeId::Polymarket => Self::polymarket_taker_fee(price, contracts, poly_fee_bps),
+            _ => Decimal::ZERO,
+        }
+    }
+
+    /// Total taker fees for both legs of `opportunity`, each bought at its
+    /// average price for [`ArbitrageOpportunity::max_contracts`].
+    ///
+    /// Matches the depth walk's `total_fees` when each leg fills at one price.
+    /// Across several Kalshi levels it is an upper bound, since the Kalshi fee
+    /// is concave in price.
+    pub fn opportunity_fees(
+        opportunity: &ArbitrageOpportunity,
+        poly_fee_bps: u32,
+        kalshi_fee_bps: u32,
+    ) -> Decimal {
+        [&opportunity.yes_side, &opportunity.no_side]
+            .into_iter()
+            .map(|side| {
+                Self::taker_fee(
+                    side.exchange,
+                    side.price,
+                    opportunity.max_contracts,
+                    poly_fee_bps,
+                    kalshi_fee_bps,
+                )
+            })
+            .sum()
+    }
+
     /// Polymarket maker rebate (negative fee = credit).
     ///
     /// # Arguments
@@ -90,20 +145,17 @@ impl FeeCalculator {
         // Gross spread
         let gross_profit = (sell_price - buy_price) * c;
 
-        // Calculate fees based on which side is which platform
-        let (buy_fee, sell_fee) = if buy_is_kalshi {
-            // Buying on Kalshi (taker), selling on Polymarket (taker)
-            let kalshi_fee = Self::kalshi_taker_fee(buy_price, contracts);
-            let poly_fee = Self::polymarket_taker_fee(sell_price, contracts, poly_fee_bps);
-            (kalshi_fee, poly_fee)
+        // Both sides pay taker fees on their own platform
+        let (buy_exchange, sell_exchange) = if buy_is_kalshi {
+            (ExchangeId::Kalshi, ExchangeId::Polymarket)
         } else {
-            // Buying on Polymarket (taker), selling on Kalshi (taker)
-            let poly_fee = Self::polymarket_taker_fee(buy_price, contracts, poly_fee_bps);
-            let kalshi_fee = Self::kalshi_taker_fee(sell_price, contracts);
-            (poly_fee, kalshi_fee)
+            (ExchangeId::Polymarket, ExchangeId::Kalshi)
+        };
+        let fee = |exchange, price| {
+            Self::taker_fee(exchange, price, contracts, poly_fee_bps, KALSHI_TAKER_FEE_BPS)
         };
 
-        gross_profit - buy_fee - sell_fee
+        gross_profit - fee(buy_exchange, buy_price) - fee(sell_exchange, sell_price)
     }
 
     /// Calculate the minimum spread required to break even after fees.
@@ -125,11 +177,56 @@ impl FeeCalculator {
         // Total fees as percentage of trade value
         kalshi_fee_per_dollar + poly_fee_per_dollar
     }
+
+    /// Maximum combined `yes_price + no_price` at which buying both legs still
+    /// nets a profit after taker fees, for setting price alerts on a pair.
+    ///
+    /// Fees are evaluated with the combined price split evenly across the legs,
+    /// so with `h` the price of each leg the boundary solves
+    /// `2h + fee_yes(h) + fee_no(h) = 1`, ignoring the cent rounding of Kalshi fees.
+    pub fn breakeven_combined_price(
+        yes_exchange: ExchangeId,
+        no_exchange: ExchangeId,
+        poly_fee_bps: u32,
+        kalshi_fee_bps: u32,
+    ) -> Decimal {
+        // Per contract, a Polymarket leg costs rate * h and a Kalshi leg rate * h * (1 - h),
+        // giving -quadratic * h^2 + linear * h - 1 = 0
+        let (quadratic, linear) = [yes_exchange, no_exchange].into_iter().fold(
+            (Decimal::ZERO, Decimal::TWO),
+            |(quadratic, linear), exchange| match exchange {
+                ExchangeId::Kalshi => {
+                    let rate = Decimal::new(kalshi_fee_bps as i64, 4);
+                    (quadratic + rate, linear + rate)
+                }
+                ExchangeId::Polymarket => {
+                    (quadratic, linear + Decimal::new(poly_fee_bps as i64, 4))
+                }
+                _ => (quadratic, linear),
+            },
+        );
+
+        let leg_price = if quadratic.is_zero() {
+            Decimal::ONE / linear
+        } else {
+            // Smaller root, the one within (0, 1)
+            let discriminant = linear * linear - Decimal::from(4) * quadratic;
+            let root = discriminant.sqrt().unwrap_or(Decimal::ZERO);
+            (linear - root) / (Decimal::TWO * quadratic)
+        };
+
+        Decimal::TWO * leg_price
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{
+        correlation::{CorrelatedPair, Outcome},
+        opportunity::{ArbitrageDirection, OrderSide},
+    };
+    use chrono::{Duration, Utc};
     use rust_decimal_macros::dec;
 
     #[test]
@@ -169,8 +266,8 @@ mod tests {
         // Buy on Polymarket at 40c, sell on Kalshi at 45c, 100 contracts
         // Gross profit = (0.45 - 0.40) * 100 = $5.00
         // Polymarket fee = 100 * 0.40 * 0.0050 = $0.20 (50 bps)
-        // Kalshi fee = 0.07 * 100 * 0.45 * 0.55 = $1.7325
-        // Net = 5.00 - 0.20 - 1.7325 = $3.0675
+        // Kalshi fee = 0.07 * 100 * 0.45 * 0.55 = $1.7325, billed as $1.74
+        // Net = 5.00 - 0.20 - 1.74 = $3.06
         let profit = FeeCalculator::calculate_net_profit(
             dec!(0.40), // buy price (Polymarket)
             dec!(0.45), // sell price (Kalshi)
@@ -179,7 +276,7 @@ mod tests {
             50,    // Polymarket fee bps
         );
 
-        assert_eq!(profit, dec!(3.0675));
+        assert_eq!(profit, dec!(3.06));
     }
 
     #[test]
@@ -200,6 +297,69 @@ mod tests {
         assert_eq!(profit, dec!(3.095));
     }
 
+    fn opportunity(
+        direction: ArbitrageDirection,
+        yes_side: OrderSide,
+        no_side: OrderSide,
+    ) -> ArbitrageOpportunity {
+        let contracts = yes_side.available_size;
+        ArbitrageOpportunity {
+            pair: CorrelatedPair::new(
+                "KXTEST",
+                "0xcondition",
+                "0xyes",
+                "0xno",
+                "Test market",
+                Utc::now() + Duration::days(30),
+                false,
+            ),
+            direction,
+            avg_yes_price: yes_side.price,
+            avg_no_price: no_side.price,
+            yes_side,
+            no_side,
+            total_cost: Decimal::ZERO,
+            max_contracts: contracts,
+            expected_profit: Decimal::ZERO,
+            total_fees: Decimal::ZERO,
+        }
+    }
+
+    #[test]
+    fn test_opportunity_fees_kalshi_yes_poly_no() {
+        // Kalshi YES at 40c: 0.07 * 100 * 0.40 * 0.60 = 1.68
+        // Polymarket NO at 55c: 100 * 0.55 * 0.0050 = 0.275
+        let opp = opportunity(
+            ArbitrageDirection::YesKalshiNoPoly,
+            OrderSide::kalshi("KXTEST", Outcome::Yes, dec!(0.40), 100),
+            OrderSide::poly("0xno", Outcome::No, dec!(0.55), 100),
+        );
+
+        let fees = FeeCalculator::opportunity_fees(&opp, 50, KALSHI_TAKER_FEE_BPS);
+        assert_eq!(fees, dec!(1.955));
+    }
+
+    #[test]
+    fn test_opportunity_fees_poly_yes_kalshi_no() {
+        // Polymarket YES at 40c: 100 * 0.40 * 0.0050 = 0.20
+        // Kalshi NO at 54c: 0.07 * 100 * 0.54 * 0.46 = 1.7388, billed as 1.74
+        let opp = opportunity(
+            ArbitrageDirection::YesPolyNoKalshi,
+            OrderSide::poly("0xyes", Outcome::Yes, dec!(0.40), 100),
+            OrderSide::kalshi("KXTEST", Outcome::No, dec!(0.54), 100),
+        );
+
+        assert_eq!(
+            FeeCalculator::opportunity_fees(&opp, 50, KALSHI_TAKER_FEE_BPS),
+            dec!(1.94)
+        );
+
+        // Rates are taken from the arguments
+        assert_eq!(FeeCalculator::opportunity_fees(&opp, 0, 0), Decimal::ZERO);
+        // 0.035 * 100 * 0.54 * 0.46 = 0.8694, billed as 0.87
+        assert_eq!(FeeCalculator::opportunity_fees(&opp, 100, 350), dec!(1.27));
+    }
+
     #[test]
     fn test_minimum_breakeven_spread() {
         // At 50c price with 50bps Polymarket fee
@@ -209,4 +369,77 @@ mod tests {
         let spread = FeeCalculator::minimum_breakeven_spread(dec!(0.50), 50);
         assert_eq!(spread, dec!(0.0225));
     }
+
+    /// Net profit per contract buying both legs at half of `combined` each, over enough
+    /// contracts that rounding the Kalshi fee up to a cent is negligible.
+    fn net_at_even_split(
+        yes_exchange: ExchangeId,
+        no_exchange: ExchangeId,
+        combined: Decimal,
+    ) -> Decimal {
+        const CONTRACTS: u32 = 1_000_000;
+        let leg = combined / Decimal::TWO;
+        let fee = |exchange| {
+            FeeCalculator::taker_fee(exchange, leg, CONTRACTS, 50, KALSHI_TAKER_FEE_BPS)
+                / Decimal::from(CONTRACTS)
+        };
+        Decimal::ONE - combined - fee(yes_exchange) - fee(no_exchange)
+    }
+
+    #[test]
+    fn test_breakeven_combined_price_kalshi_poly() {
+        // h = (2.075 - sqrt(2.075^2 - 4 * 0.07)) / (2 * 0.07) ~= 0.49003
+        let kalshi_yes = FeeCalculator::breakeven_combined_price(
+            ExchangeId::Kalshi,
+            ExchangeId::Polymarket,
+            50,
+            KALSHI_TAKER_FEE_BPS,
+        );
+        assert_eq!(kalshi_yes.round_dp(4), dec!(0.9801));
+
+        // Even split makes the boundary independent of which leg is on which exchange
+        let poly_yes = FeeCalculator::breakeven_combined_price(
+            ExchangeId::Polymarket,
+            ExchangeId::Kalshi,
+            50,
+            KALSHI_TAKER_FEE_BPS,
+        );
+        assert_eq!(poly_yes, kalshi_yes);
+
+        // Just below the boundary nets positive, just above nets negative
+        let net = |combined| {
+            net_at_even_split(ExchangeId::Kalshi, ExchangeId::Polymarket, combined)
+        };
+        assert!(net(kalshi_yes - dec!(0.0001)) > Decimal::ZERO);
+        assert!(net(kalshi_yes + dec!(0.0001)) < Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_breakeven_combined_price_kalshi_side_lower() {
+        let breakeven = |yes, no| {
+            FeeCalculator::breakeven_combined_price(yes, no, 50, KALSHI_TAKER_FEE_BPS)
+        };
+
+        // Polymarket only: 1 / 2.01 per leg
+        let poly_only = breakeven(ExchangeId::Polymarket, ExchangeId::Polymarket);
+        assert_eq!(poly_only.round_dp(4), dec!(0.9950));
+
+        // Each Kalshi leg's 7% fee pulls the boundary further down
+        let one_kalshi = breakeven(ExchangeId::Kalshi, ExchangeId::Polymarket);
+        let both_kalshi = breakeven(ExchangeId::Kalshi, ExchangeId::Kalshi);
+        assert!(one_kalshi < poly_only);
+        assert!(both_kalshi < one_kalshi);
+        assert_eq!(both_kalshi.round_dp(4), dec!(0.9650));
+
+        // Fee free legs break even at $1
+        assert_eq!(
+            FeeCalculator::breakeven_combined_price(
+                ExchangeId::Kalshi,
+                ExchangeId::Polymarket,
+                0,
+                0
+            ),
+            Decimal::ONE
+        );
+    }
 }
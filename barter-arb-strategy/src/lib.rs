@@ -65,7 +65,9 @@
 //!
 //! # Market Model (Delta-Neutral)
 //!
-//! Each correlated pair uses 2 YES orderbooks; NO asks are derived from YES bids:
+//! Each correlated pair uses 2 YES orderbooks; NO asks are derived from YES bids unless a
+//! real NO book is subscribed (a Kalshi NO instrument or the Polymarket NO token), in which
+//! case its asks are used instead:
 //!
 //! ```text
 //! Buy YES on Platform A + Buy NO on Platform B = guaranteed $1 payout
@@ -77,23 +79,63 @@
 //! - Buy Kalshi YES + Buy Polymarket NO
 //!
 //! With `inverse` flag, Kalshi YES/NO perspective is swapped before checking.
+//!
+//! Several Kalshi tickers may map to the same Polymarket market (a
+//! [`CorrelatedGroup`]); only the best Kalshi leg per direction is traded.
 
 pub mod config;
 pub mod correlation;
 pub mod database;
 pub mod fees;
+pub mod metrics;
 pub mod opportunity;
+pub mod persistence;
+#[cfg(feature = "postgres")]
+pub mod postgres;
+pub mod reconcile;
 pub mod recorder;
+pub mod refresh;
+pub mod replay;
 pub mod risk;
+pub mod shutdown;
+pub mod source;
 pub mod state;
 pub mod strategy;
 
 // Re-exports for convenience
 pub use config::{ArbitrageConfig, MinOrderValues};
-pub use correlation::{CorrelatedPair, Outcome, PredictionMarketKey};
-pub use database::{DatabaseError, DatabaseQuerier, MarketPairFilters, MarketPairRecord};
-pub use fees::FeeCalculator;
-pub use opportunity::{ArbitrageDirection, ArbitrageOpportunity, OrderSide};
-pub use state::{ArbitrageEngineState, ArbitrageGlobalData, ArbitrageInstrumentData, OrderbookLookup};
-pub use risk::ArbitrageRiskManager;
-pub use strategy::PredictionArbitrageStrategy;
+pub use correlation::{CorrelatedGroup, CorrelatedPair, Outcome, PredictionMarketKey};
+pub use database::{
+    DatabaseError, DatabaseQuerier, MarketPairFilters, MarketPairRecord, OpportunityBatchConfig,
+    OpportunityRecord,
+};
+pub use fees::{FeeCalculator, KALSHI_TAKER_FEE_BPS};
+pub use metrics::Metrics;
+pub use opportunity::{
+    ArbitrageDirection, ArbitrageOpportunity, DirectionEvaluation, OpportunityScan, OrderSide,
+    PairEvaluation, PairSkip, RejectionReason, SkipReason,
+};
+pub use state::{
+    ArbLeg, ArbPosition, ArbPositions, ArbitrageEngineState, ArbitrageGlobalData,
+    ArbitrageInstrumentData, MarketDataLookup, OrderbookLookup, PositionMode,
+    instrument_min_quantities, instrument_tick_sizes, market_data_lookup,
+};
+pub use persistence::{PersistenceError, StatePersistence, StateSnapshot};
+pub use reconcile::{
+    reconcile_startup, Discrepancy, PositionReconciler, ReconcileClient, ReconcileError,
+    ReconciliationReport,
+};
+pub use refresh::{PairRefresher, PairUpdate, PairUpdateOutcome};
+pub use replay::{ReplayConfig, ReplaySpeed, ReplayStream};
+pub use risk::{
+    ArbitrageRiskManager, BreakerTrip, CircuitBreaker, DailyPnl, DailyPnlTracker,
+    OrderRateLimiter, PairInstruments, RateLimit, RiskRefusal,
+};
+pub use shutdown::{graceful_shutdown, ShutdownReport, DEFAULT_SHUTDOWN_TIMEOUT};
+pub use source::PairSource;
+#[cfg(feature = "postgres")]
+pub use postgres::PostgresPairSource;
+pub use strategy::{
+    OpportunityEvents, OpportunitySink, PairInvalidation, PairInvalidationSink, PositionSink,
+    PredictionArbitrageStrategy, StrategySnapshot,
+};
@@ -0,0 +1,337 @@
+//! Prometheus metrics for live operation.
+//!
+//! [`Metrics`] is a cheaply cloneable handle to a registry of:
+//!
+//! | Metric | Type | Labels |
+//! |---|---|---|
+//! | `arb_orderbook_events_total` | counter | `exchange` |
+//! | `arb_book_age_seconds` | gauge (oldest book) | `exchange` |
+//! | `arb_opportunities_detected_total` | counter | |
+//! | `arb_opportunities_filtered_total` | counter | `reason` |
+//! | `arb_orders_generated_total` | counter | `exchange` |
+//! | `arb_order_fills_total` | counter | `exchange` |
+//! | `arb_orders_rejected_total` | counter | `exchange` |
+//! | `arb_deployed_capital` | gauge | |
+//! | `arb_pair_pnl` | gauge (locked profit) | `pair` |
+//! | `arb_pair_edge_ema` | gauge (smoothed edge) | `pair` |
+//! | `arb_event_to_order_latency_seconds` | histogram | |
+//!
+//! The strategy ([`PredictionArbitrageStrategy::with_metrics`]) and the global
+//! state processor ([`ArbitrageGlobalData::with_metrics`]) update it; updates
+//! are atomic increments on pre-registered metric families. [`Metrics::serve`]
+//! exposes the text format on `GET /metrics`.
+//!
+//! [`PredictionArbitrageStrategy::with_metrics`]: crate::PredictionArbitrageStrategy::with_metrics
+//! [`ArbitrageGlobalData::with_metrics`]: crate::ArbitrageGlobalData::with_metrics
+
+use barter_instrument::exchange::ExchangeId;
+use bytes::Bytes;
+use chrono::TimeDelta;
+use http_body_util::Full;
+use hyper::{body::Incoming, service::service_fn, Method, Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use prometheus::{
+    Encoder, Gauge, GaugeVec, Histogram, HistogramOpts, IntCounter, IntCounterVec, Opts, Registry,
+    TextEncoder,
+};
+use rust_decimal::{prelude::ToPrimitive, Decimal};
+use std::{convert::Infallible, sync::Arc};
+use tokio::net::TcpListener;
+use tracing::{debug, info};
+
+/// Event-to-order latency buckets, in seconds.
+const LATENCY_BUCKETS: &[f64] = &[0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0];
+
+/// Handle to the strategy's Prometheus metrics.
+#[derive(Clone)]
+pub struct Metrics {
+    inner: Arc<MetricsInner>,
+}
+
+struct MetricsInner {
+    registry: Registry,
+    orderbook_events: IntCounterVec,
+    book_age: GaugeVec,
+    opportunities_detected: IntCounter,
+    opportunities_filtered: IntCounterVec,
+    orders_generated: IntCounterVec,
+    order_fills: IntCounterVec,
+    orders_rejected: IntCounterVec,
+    deployed_capital: Gauge,
+    pair_pnl: GaugeVec,
+    pair_edge: GaugeVec,
+    event_to_order_latency: Histogram,
+}
+
+impl std::fmt::Debug for Metrics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Metrics").finish_non_exhaustive()
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Metrics {
+    /// Create a handle with every metric registered.
+    pub fn new() -> Self {
+        let registry = Registry::new();
+
+        fn register<M: prometheus::core::Collector + Clone + 'static>(
+            registry: &Registry,
+            metric: prometheus::Result<M>,
+        ) -> M {
+            let metric = metric.expect("metric options are valid");
+            registry
+                .register(Box::new(metric.clone()))
+                .expect("metric names are unique");
+            metric
+        }
+
+        let inner = MetricsInner {
+            orderbook_events: register(
+                &registry,
+                IntCounterVec::new(
+                    Opts::new("arb_orderbook_events_total", "Orderbook events received"),
+                    &["exchange"],
+                ),
+            ),
+            book_age: register(
+                &registry,
+                GaugeVec::new(
+                    Opts::new("arb_book_age_seconds", "Age of the stalest orderbook"),
+                    &["exchange"],
+                ),
+            ),
+            opportunities_detected: register(
+                &registry,
+                IntCounter::new("arb_opportunities_detected_total", "Opportunities detected"),
+            ),
+            opportunities_filtered: register(
+                &registry,
+                IntCounterVec::new(
+                    Opts::new(
+                        "arb_opportunities_filtered_total",
+                        "Opportunities rejected by a filter",
+                    ),
+                    &["reason"],
+                ),
+            ),
+            orders_generated: register(
+                &registry,
+                IntCounterVec::new(
+                    Opts::new(
+                        "arb_orders_generated_total",
+                        "Orders generated by the strategy, before risk checks",
+                    ),
+                    &["exchange"],
+                ),
+            ),
+            order_fills: register(
+                &registry,
+                IntCounterVec::new(
+                    Opts::new("arb_order_fills_total", "Order fills received"),
+                    &["exchange"],
+                ),
+            ),
+            orders_rejected: register(
+                &registry,
+                IntCounterVec::new(
+                    Opts::new(
+                        "arb_orders_rejected_total",
+                        "Orders rejected by the exchange",
+                    ),
+                    &["exchange"],
+                ),
+            ),
+            deployed_capital: register(
+                &registry,
+                Gauge::new(
+                    "arb_deployed_capital",
+                    "Capital deployed across all positions",
+                ),
+            ),
+            pair_pnl: register(
+                &registry,
+                GaugeVec::new(
+                    Opts::new(
+                        "arb_pair_pnl",
+                        "Profit locked in by each pair's matched legs",
+                    ),
+                    &["pair"],
+                ),
+            ),
+            pair_edge: register(
+                &registry,
+                GaugeVec::new(
+                    Opts::new(
+                        "arb_pair_edge_ema",
+                        "Smoothed top-of-book edge of each pair",
+                    ),
+                    &["pair"],
+                ),
+            ),
+            event_to_order_latency: register(
+                &registry,
+                Histogram::with_opts(
+                    HistogramOpts::new(
+                        "arb_event_to_order_latency_seconds",
+                        "Time from the latest orderbook update of an opportunity to its orders",
+                    )
+                    .buckets(LATENCY_BUCKETS.to_vec()),
+                ),
+            ),
+            registry,
+        };
+
+        Self {
+            inner: Arc::new(inner),
+        }
+    }
+
+    pub fn orderbook_event(&self, exchange: ExchangeId) {
+        self.inner
+            .orderbook_events
+            .with_label_values(&[exchange.as_str()])
+            .inc();
+    }
+
+    pub fn set_book_age(&self, exchange: ExchangeId, age: TimeDelta) {
+        self.inner
+            .book_age
+            .with_label_values(&[exchange.as_str()])
+            .set(seconds(age));
+    }
+
+    /// Count a detected opportunity, and the filter that rejected it if any.
+    pub fn opportunity(&self, rejection: Option<&str>) {
+        self.inner.opportunities_detected.inc();
+        if let Some(reason) = rejection {
+            self.inner
+                .opportunities_filtered
+                .with_label_values(&[reason])
+                .inc();
+        }
+    }
+
+    pub fn order_generated(&self, exchange: ExchangeId) {
+        self.inner
+            .orders_generated
+            .with_label_values(&[exchange.as_str()])
+            .inc();
+    }
+
+    pub fn order_filled(&self, exchange: ExchangeId) {
+        self.inner
+            .order_fills
+            .with_label_values(&[exchange.as_str()])
+            .inc();
+    }
+
+    pub fn order_rejected(&self, exchange: ExchangeId) {
+        self.inner
+            .orders_rejected
+            .with_label_values(&[exchange.as_str()])
+            .inc();
+    }
+
+    pub fn set_deployed_capital(&self, deployed: Decimal) {
+        self.inner
+            .deployed_capital
+            .set(deployed.to_f64().unwrap_or_default());
+    }
+
+    pub fn set_pair_pnl(&self, kalshi_ticker: &str, pnl: Decimal) {
+        self.inner
+            .pair_pnl
+            .with_label_values(&[kalshi_ticker])
+            .set(pnl.to_f64().unwrap_or_default());
+    }
+
+    pub fn set_pair_edge(&self, kalshi_ticker: &str, edge: Decimal) {
+        self.inner
+            .pair_edge
+            .with_label_values(&[kalshi_ticker])
+            .set(edge.to_f64().unwrap_or_default());
+    }
+
+    pub fn observe_event_to_order(&self, latency: TimeDelta) {
+        self.inner.event_to_order_latency.observe(seconds(latency));
+    }
+
+    /// Render every metric in the Prometheus text format.
+    pub fn render(&self) -> String {
+        let mut buffer = Vec::new();
+        TextEncoder::new()
+            .encode(&self.inner.registry.gather(), &mut buffer)
+            .expect("text encoding into a Vec can't fail");
+        String::from_utf8(buffer).expect("text format is utf8")
+    }
+
+    /// Serve `GET /metrics` on `listener` until the task is dropped.
+    pub async fn serve(self, listener: TcpListener) -> std::io::Result<()> {
+        info!(addr = %listener.local_addr()?, "Serving Prometheus metrics");
+        loop {
+            let (stream, _) = listener.accept().await?;
+            let metrics = self.clone();
+            tokio::spawn(async move {
+                let service = service_fn(move |request| {
+                    let response = metrics.respond(&request);
+                    async move { Ok::<_, Infallible>(response) }
+                });
+                if let Err(e) = hyper::server::conn::http1::Builder::new()
+                    .serve_connection(TokioIo::new(stream), service)
+                    .await
+                {
+                    debug!("Metrics connection error: {}", e);
+                }
+            });
+        }
+    }
+
+    fn respond(&self, request: &Request<Incoming>) -> Response<Full<Bytes>> {
+        if request.method() != Method::GET || request.uri().path() != "/metrics" {
+            let mut response = Response::new(Full::new(Bytes::from_static(b"not found")));
+            *response.status_mut() = StatusCode::NOT_FOUND;
+            return response;
+        }
+
+        let mut response = Response::new(Full::new(Bytes::from(self.render())));
+        response.headers_mut().insert(
+            hyper::header::CONTENT_TYPE,
+            hyper::header::HeaderValue::from_static("text/plain; version=0.0.4"),
+        );
+        response
+    }
+}
+
+fn seconds(delta: TimeDelta) -> f64 {
+    delta
+        .num_microseconds()
+        .map_or(f64::MAX, |micros| micros as f64 / 1e6)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_render_includes_labels() {
+        let metrics = Metrics::new();
+        metrics.orderbook_event(ExchangeId::Kalshi);
+        metrics.opportunity(Some("below_threshold"));
+        metrics.set_pair_pnl("KXTEST", dec!(1.25));
+        metrics.observe_event_to_order(TimeDelta::milliseconds(3));
+
+        let text = metrics.render();
+        assert!(text.contains(r#"arb_orderbook_events_total{exchange="kalshi"} 1"#));
+        assert!(text.contains("arb_opportunities_detected_total 1"));
+        assert!(text.contains(r#"arb_opportunities_filtered_total{reason="below_threshold"} 1"#));
+        assert!(text.contains(r#"arb_pair_pnl{pair="KXTEST"} 1.25"#));
+        assert!(text.contains(r#"arb_event_to_order_latency_seconds_bucket{le="0.005"} 1"#));
+    }
+}
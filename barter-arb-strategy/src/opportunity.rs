@@ -7,6 +7,10 @@ use crate::correlation::{CorrelatedPair, Outcome, PredictionMarketKey};
 use barter_instrument::exchange::ExchangeId;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use smol_str::SmolStr;
+
+/// Days per year used to annualise returns.
+const DAYS_PER_YEAR: i64 = 365;
 
 /// Direction of the delta-neutral arbitrage trade.
 ///
@@ -37,6 +41,12 @@ impl ArbitrageDirection {
     }
 }
 
+impl std::fmt::Display for ArbitrageDirection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "yes@{}/no@{}", self.yes_exchange().as_str(), self.no_exchange().as_str())
+    }
+}
+
 /// One side of a delta-neutral arbitrage order.
 ///
 /// Both sides are always BUY orders.
@@ -130,6 +140,67 @@ impl ArbitrageOpportunity {
         }
     }
 
+    /// Capital committed by buying both legs at their average prices.
+    pub fn capital_required(&self) -> Decimal {
+        (self.avg_yes_price + self.avg_no_price) * Decimal::from(self.max_contracts)
+    }
+
+    /// Payout at resolution, $1.00 per contract whichever outcome resolves YES.
+    pub fn settlement_payout(&self) -> Decimal {
+        Decimal::from(self.max_contracts)
+    }
+
+    /// Guaranteed profit at resolution: the settlement payout less the total cost,
+    /// fees included, of both legs.
+    ///
+    /// Nothing is realised when the legs fill (see [`Self::expected_profit_immediate`]),
+    /// the profit is only paid out at expiry, so compare opportunities by
+    /// [`Self::annualized_return`] rather than by this alone.
+    pub fn expected_profit_at_settlement(&self) -> Decimal {
+        (Decimal::ONE - self.total_cost) * Decimal::from(self.max_contracts)
+    }
+
+    /// Profit realised as soon as both legs fill, before resolution.
+    ///
+    /// Always zero: both legs are bought and held to expiry, so the whole edge is
+    /// [`Self::expected_profit_at_settlement`].
+    pub fn expected_profit_immediate(&self) -> Decimal {
+        Decimal::ZERO
+    }
+
+    /// Settlement profit as a fraction of the capital, fees included, locked until expiry.
+    ///
+    /// eg/ 0.02 for a $2 profit on $100 of capital.
+    pub fn return_on_capital(&self) -> Decimal {
+        let capital = self.total_cost * Decimal::from(self.max_contracts);
+        if capital > Decimal::ZERO {
+            self.expected_profit_at_settlement() / capital
+        } else {
+            Decimal::ZERO
+        }
+    }
+
+    /// [`Self::return_on_capital`] annualised (simple, not compounded) over the
+    /// `days_to_expiry` the capital is locked for.
+    ///
+    /// eg/ a $0.02 profit on $0.98 of capital (~2.04%) over 30 days is ~24.8%
+    /// annualised, while $0.01 on $0.99 (~1.01%) over 7 days is ~52.7%.
+    /// Returns `None` for markets expiring within a day (see
+    /// [`CorrelatedPair::days_to_expiry`]).
+    pub fn annualized_return(&self, days_to_expiry: i64) -> Option<Decimal> {
+        (days_to_expiry > 0).then(|| {
+            self.return_on_capital() * Decimal::from(DAYS_PER_YEAR) / Decimal::from(days_to_expiry)
+        })
+    }
+
+    /// One-line human readable summary of the pair, direction, size, cost and profit.
+    ///
+    /// eg/ `KXBTC-25JAN31 yes@polymarket/no@kalshi 100 x 0.96 (yes 0.40 + no 0.54),
+    /// fees 2.00, profit 4.00`
+    pub fn summary(&self) -> String {
+        self.to_string()
+    }
+
     /// Check if this opportunity is profitable after fees.
     pub fn is_profitable(&self) -> bool {
         self.expected_profit > Decimal::ZERO
@@ -141,6 +212,185 @@ impl ArbitrageOpportunity {
     }
 }
 
+impl std::fmt::Display for ArbitrageOpportunity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} {} x {} (yes {} + no {}), fees {}, profit {}",
+            self.pair.kalshi_ticker,
+            self.direction,
+            self.max_contracts,
+            self.total_cost,
+            self.avg_yes_price,
+            self.avg_no_price,
+            self.total_fees,
+            self.expected_profit
+        )
+    }
+}
+
+/// Why an arbitrage direction was not acted on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum RejectionReason {
+    /// One of the books has no asks to buy from
+    NoLiquidity,
+    /// Cost per contract (prices + fees) is at least $1.00 at the top of book
+    Unprofitable,
+    /// Profit per contract below `min_spread_threshold`
+    BelowThreshold,
+    /// Expected profit across all contracts below `min_total_profit`
+    BelowMinProfit,
+    /// Size exceeds `max_position_per_market`
+    PositionLimit,
+    /// One of the legs is below the platform minimum order value
+    MinOrderValue,
+    /// The pair's smoothed edge is below `min_spread_threshold`
+    /// (see `min_edge_persistence`)
+    EdgeNotPersistent,
+    /// One of the legs has less than `min_book_depth` contracts near its best ask
+    ThinBook,
+    /// Not enough of `max_total_capital` left after funding higher-edge opportunities
+    InsufficientCapital,
+}
+
+impl RejectionReason {
+    /// Stable name used when recording rejected opportunities.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RejectionReason::NoLiquidity => "no_liquidity",
+            RejectionReason::Unprofitable => "unprofitable",
+            RejectionReason::BelowThreshold => "below_threshold",
+            RejectionReason::BelowMinProfit => "below_min_profit",
+            RejectionReason::PositionLimit => "position_limit",
+            RejectionReason::MinOrderValue => "min_order_value",
+            RejectionReason::EdgeNotPersistent => "edge_not_persistent",
+            RejectionReason::ThinBook => "thin_book",
+            RejectionReason::InsufficientCapital => "insufficient_capital",
+        }
+    }
+}
+
+/// Why a pair produced no orders in a scan.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub enum SkipReason {
+    /// The pair is suspended, for the given reason (eg/ "expired")
+    Suspended(String),
+    /// The pair expires after `max_days_to_expiry`
+    OutsideTradingWindow,
+    /// No Polymarket YES orderbook
+    MissingPolymarketBook,
+    /// No Kalshi YES orderbook
+    MissingKalshiBook,
+    /// A book was left stale by its market closing or pausing
+    StaleBook,
+    /// An instrument to be ordered is not mapped to an engine instrument index
+    IndexNotMapped(PredictionMarketKey),
+    /// Every direction failed a filter, with the reason of the closest direction
+    Rejected(RejectionReason),
+}
+
+impl SkipReason {
+    /// Stable name for logs and metrics.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SkipReason::Suspended(_) => "suspended",
+            SkipReason::OutsideTradingWindow => "outside_trading_window",
+            SkipReason::MissingPolymarketBook => "missing_polymarket_book",
+            SkipReason::MissingKalshiBook => "missing_kalshi_book",
+            SkipReason::StaleBook => "stale_book",
+            SkipReason::IndexNotMapped(_) => "index_not_mapped",
+            SkipReason::Rejected(reason) => reason.as_str(),
+        }
+    }
+}
+
+/// A pair skipped during a scan, and why.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct PairSkip {
+    pub kalshi_ticker: SmolStr,
+    pub reason: SkipReason,
+}
+
+/// Opportunities detected in a scan, alongside the pairs that produced none.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct OpportunityScan {
+    /// Best opportunity per direction for each group, as from `detect_opportunities`
+    pub opportunities: Vec<ArbitrageOpportunity>,
+    /// Pairs that would not trade
+    pub skipped: Vec<PairSkip>,
+}
+
+impl OpportunityScan {
+    /// Reason `kalshi_ticker` was skipped, if it was.
+    pub fn skip_reason(&self, kalshi_ticker: &str) -> Option<&SkipReason> {
+        self.skipped
+            .iter()
+            .find(|skip| skip.kalshi_ticker == kalshi_ticker)
+            .map(|skip| &skip.reason)
+    }
+}
+
+/// Breakdown of one arbitrage direction for a pair.
+///
+/// Prices, fees and size come from the depth walk and are zero if no level was
+/// profitable; `top_of_book_cost` is always populated when both books have asks.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct DirectionEvaluation {
+    /// Direction evaluated
+    pub direction: ArbitrageDirection,
+    /// Cost per contract at the best level, including fees and slippage buffer
+    pub top_of_book_cost: Option<Decimal>,
+    /// Weighted average YES price from depth walk
+    pub avg_yes_price: Decimal,
+    /// Weighted average NO price from depth walk
+    pub avg_no_price: Decimal,
+    /// Total cost per contract: avg_yes + avg_no + fees/contract
+    pub total_cost: Decimal,
+    /// Total fees across both sides
+    pub total_fees: Decimal,
+    /// Maximum contracts fillable at profitable levels
+    pub max_contracts: u32,
+    /// Expected profit in dollars
+    pub expected_profit: Decimal,
+    /// Opportunity found by the depth walk (present even if rejected by a filter)
+    pub opportunity: Option<ArbitrageOpportunity>,
+    /// First filter the direction fails, if any
+    pub rejection: Option<RejectionReason>,
+}
+
+impl DirectionEvaluation {
+    /// Whether the strategy would trade this direction (ignoring risk checks).
+    pub fn is_actionable(&self) -> bool {
+        self.opportunity.is_some() && self.rejection.is_none()
+    }
+}
+
+/// Breakdown of both arbitrage directions for a pair.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PairEvaluation {
+    /// The correlated market pair
+    pub pair: CorrelatedPair,
+    /// Buy YES on Polymarket + Buy NO on Kalshi
+    pub yes_poly_no_kalshi: DirectionEvaluation,
+    /// Buy YES on Kalshi + Buy NO on Polymarket
+    pub yes_kalshi_no_poly: DirectionEvaluation,
+}
+
+impl PairEvaluation {
+    /// Both directions, in [`ArbitrageDirection`] order.
+    pub fn directions(&self) -> [&DirectionEvaluation; 2] {
+        [&self.yes_poly_no_kalshi, &self.yes_kalshi_no_poly]
+    }
+
+    /// The most profitable actionable direction, if any.
+    pub fn best(&self) -> Option<&DirectionEvaluation> {
+        self.directions()
+            .into_iter()
+            .filter(|eval| eval.is_actionable())
+            .max_by_key(|eval| eval.expected_profit)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -198,6 +448,100 @@ mod tests {
         assert!(!opp.meets_threshold(dec!(0.05)));
     }
 
+    #[test]
+    fn test_opportunity_summary() {
+        let opp = ArbitrageOpportunity {
+            pair: test_pair(),
+            direction: ArbitrageDirection::YesPolyNoKalshi,
+            yes_side: OrderSide::poly("0xyes_token", Outcome::Yes, dec!(0.40), 100),
+            no_side: OrderSide::kalshi("KXBTC-25JAN31-T100000", Outcome::No, dec!(0.54), 100),
+            total_cost: dec!(0.96),
+            avg_yes_price: dec!(0.40),
+            avg_no_price: dec!(0.54),
+            max_contracts: 100,
+            expected_profit: dec!(4.00),
+            total_fees: dec!(2.00),
+        };
+
+        let summary = opp.summary();
+        assert_eq!(summary, opp.to_string());
+        assert!(summary.contains("KXBTC-25JAN31-T100000"));
+        assert!(summary.contains("yes@polymarket/no@kalshi"));
+        assert!(summary.contains("profit 4.00"));
+        assert_eq!(
+            summary,
+            "KXBTC-25JAN31-T100000 yes@polymarket/no@kalshi 100 x 0.96 (yes 0.40 + no 0.54), \
+             fees 2.00, profit 4.00"
+        );
+    }
+
+    #[test]
+    fn test_annualized_return() {
+        fn opportunity(total_cost: Decimal) -> ArbitrageOpportunity {
+            ArbitrageOpportunity {
+                pair: test_pair(),
+                direction: ArbitrageDirection::YesPolyNoKalshi,
+                yes_side: OrderSide::poly("0xyes_token", Outcome::Yes, dec!(0.40), 100),
+                no_side: OrderSide::kalshi("KXBTC-25JAN31-T100000", Outcome::No, dec!(0.50), 100),
+                total_cost,
+                avg_yes_price: dec!(0.40),
+                avg_no_price: dec!(0.50),
+                max_contracts: 100,
+                expected_profit: (Decimal::ONE - total_cost) * dec!(100),
+                total_fees: (total_cost - dec!(0.90)) * dec!(100),
+            }
+        }
+
+        struct TestCase {
+            total_cost: Decimal,
+            days_to_expiry: i64,
+            expected: Option<Decimal>,
+        }
+
+        let tests = vec![
+            TestCase {
+                // TC0: 25% locked for a fifth of a year
+                total_cost: dec!(0.80),
+                days_to_expiry: 73,
+                expected: Some(dec!(1.25)),
+            },
+            TestCase {
+                // TC1: ~2.04% over 30 days
+                total_cost: dec!(0.98),
+                days_to_expiry: 30,
+                expected: Some(dec!(0.2483)),
+            },
+            TestCase {
+                // TC2: ~1.01% over 7 days beats ~2.04% over 30 days
+                total_cost: dec!(0.99),
+                days_to_expiry: 7,
+                expected: Some(dec!(0.5267)),
+            },
+            TestCase {
+                // TC3: expiring within a day
+                total_cost: dec!(0.98),
+                days_to_expiry: 0,
+                expected: None,
+            },
+        ];
+
+        for (index, test) in tests.into_iter().enumerate() {
+            let opp = opportunity(test.total_cost);
+            assert_eq!(opp.settlement_payout(), dec!(100), "TC{index} failed");
+            assert_eq!(opp.expected_profit_immediate(), Decimal::ZERO, "TC{index} failed");
+            assert_eq!(
+                opp.expected_profit_at_settlement(),
+                opp.expected_profit,
+                "TC{index} failed"
+            );
+
+            let actual = opp
+                .annualized_return(test.days_to_expiry)
+                .map(|annualized| annualized.round_dp(4));
+            assert_eq!(actual, test.expected, "TC{index} failed");
+        }
+    }
+
     #[test]
     fn test_unprofitable_opportunity() {
         let opp = ArbitrageOpportunity {
@@ -0,0 +1,518 @@
+//! Persist engine and strategy state across restarts.
+//!
+//! A [`StateSnapshot`] holds the [`ArbitrageGlobalData`], every
+//! [`ArbitrageInstrumentData`] keyed by internal instrument name (so a different
+//! instrument index order after a restart doesn't corrupt it) and the strategy's
+//! [`StrategySnapshot`]. [`StatePersistence`] writes it to a JSON file
+//! periodically and on shutdown, and loads it on startup. Periodic snapshots are
+//! written on a background thread, keeping file I/O off the engine's hot path.
+//!
+//! ```rust,ignore
+//! let persistence = StatePersistence::new("./state.json");
+//! let snapshot = persistence.load()?.unwrap_or_default();
+//!
+//! let global = snapshot.global.clone().with_exchanges(&indexed);
+//! let state = EngineStateBuilder::new(&indexed, global, |instrument| {
+//!     snapshot
+//!         .instrument_data(instrument)
+//!         .with_position_mode(PositionMode::HeldToSettlement)
+//! })
+//! .build();
+//! strategy.restore(&snapshot.strategy);
+//! ```
+
+use crate::{
+    state::{ArbitrageEngineState, ArbitrageGlobalData, ArbitrageInstrumentData},
+    strategy::{PredictionArbitrageStrategy, StrategySnapshot},
+};
+use barter_instrument::{
+    asset::AssetIndex,
+    exchange::{ExchangeId, ExchangeIndex},
+    instrument::{name::InstrumentNameInternal, Instrument, InstrumentIndex},
+    Keyed,
+};
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+use std::{
+    cell::{Cell, OnceCell},
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{mpsc, Arc, Mutex},
+};
+use thiserror::Error;
+use tracing::{info, warn};
+
+/// Version of the snapshot file format. Bump on incompatible changes.
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// Default interval between periodic snapshots.
+pub const DEFAULT_SNAPSHOT_INTERVAL: TimeDelta = TimeDelta::seconds(30);
+
+/// Instrument as passed to the `EngineStateBuilder` instrument data closure.
+type EngineInstrument =
+    Keyed<InstrumentIndex, Instrument<Keyed<ExchangeIndex, ExchangeId>, AssetIndex>>;
+
+/// Errors saving or loading a [`StateSnapshot`].
+#[derive(Debug, Error)]
+pub enum PersistenceError {
+    #[error("Snapshot I/O failed: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Failed to (de)serialize snapshot: {0}")]
+    Serde(#[from] serde_json::Error),
+
+    #[error("Snapshot version {found} does not match supported version {expected}")]
+    VersionMismatch { found: u32, expected: u32 },
+}
+
+/// Serialized engine and strategy state.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StateSnapshot {
+    /// File format version, see [`SNAPSHOT_VERSION`]
+    pub version: u32,
+    /// When the snapshot was taken
+    pub time: DateTime<Utc>,
+    /// Global capital and balance state
+    pub global: ArbitrageGlobalData,
+    /// Per-instrument positions and cost basis, keyed by internal instrument name
+    pub instruments: HashMap<InstrumentNameInternal, ArbitrageInstrumentData>,
+    /// Strategy suspension, order id and daily P&L state
+    pub strategy: StrategySnapshot,
+}
+
+impl Default for StateSnapshot {
+    fn default() -> Self {
+        Self {
+            version: SNAPSHOT_VERSION,
+            time: DateTime::<Utc>::MIN_UTC,
+            global: ArbitrageGlobalData::default(),
+            instruments: HashMap::new(),
+            strategy: StrategySnapshot::default(),
+        }
+    }
+}
+
+impl StateSnapshot {
+    /// Capture the current engine and strategy state.
+    ///
+    /// Orderbooks are not persisted: they are stale after a restart and get
+    /// re-seeded by the market data snapshots.
+    pub fn capture(
+        state: &ArbitrageEngineState,
+        strategy: &PredictionArbitrageStrategy,
+        time: DateTime<Utc>,
+    ) -> Self {
+        let instruments = state
+            .instruments
+            .0
+            .iter()
+            .map(|(name, instrument)| {
+                let data = ArbitrageInstrumentData {
+                    orderbook: None,
+                    ..instrument.data.clone()
+                };
+                (name.clone(), data)
+            })
+            .collect();
+
+        Self {
+            version: SNAPSHOT_VERSION,
+            time,
+            global: state.global.clone(),
+            instruments,
+            strategy: strategy.snapshot(),
+        }
+    }
+
+    /// Restored data for an instrument, or the default if it wasn't in the snapshot.
+    ///
+    /// Matches the `EngineStateBuilder` instrument data closure.
+    pub fn instrument_data(&self, instrument: &EngineInstrument) -> ArbitrageInstrumentData {
+        self.instruments
+            .get(&instrument.value.name_internal)
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+/// Saves and loads [`StateSnapshot`]s at a file path.
+#[derive(Debug, Clone)]
+pub struct StatePersistence {
+    path: PathBuf,
+    interval: TimeDelta,
+    last_saved: Cell<Option<DateTime<Utc>>>,
+    /// Time of the newest snapshot written by any clone, held while writing so an
+    /// older snapshot never replaces a newer one
+    written: Arc<Mutex<Option<DateTime<Utc>>>>,
+    /// Background thread writing snapshots from [`Self::save_if_due`], spawned on
+    /// first use
+    writer: OnceCell<mpsc::Sender<StateSnapshot>>,
+}
+
+impl StatePersistence {
+    /// Persist to `path`, saving at most every [`DEFAULT_SNAPSHOT_INTERVAL`].
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            interval: DEFAULT_SNAPSHOT_INTERVAL,
+            last_saved: Cell::new(None),
+            written: Arc::default(),
+            writer: OnceCell::new(),
+        }
+    }
+
+    /// Build from `STATE_SNAPSHOT_PATH` (and optional `STATE_SNAPSHOT_SECS`),
+    /// returning `None` if persistence isn't configured.
+    pub fn from_env() -> Option<Self> {
+        let path = std::env::var("STATE_SNAPSHOT_PATH").ok()?;
+        let persistence = Self::new(path);
+
+        let interval = std::env::var("STATE_SNAPSHOT_SECS").ok().and_then(|v| {
+            v.parse::<i64>()
+                .inspect_err(|e| warn!(%e, "Ignoring STATE_SNAPSHOT_SECS"))
+                .ok()
+        });
+        Some(match interval {
+            Some(secs) => persistence.with_interval(TimeDelta::seconds(secs)),
+            None => persistence,
+        })
+    }
+
+    /// Set the minimum interval between periodic snapshots.
+    pub fn with_interval(mut self, interval: TimeDelta) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Snapshot file path.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Write `snapshot`, replacing the previous file atomically via a rename.
+    ///
+    /// A snapshot older than one already written is skipped.
+    pub fn save(&self, snapshot: &StateSnapshot) -> Result<(), PersistenceError> {
+        write_snapshot(&self.path, &self.written, snapshot)?;
+        self.last_saved.set(Some(snapshot.time));
+        Ok(())
+    }
+
+    /// Capture the state if the interval has elapsed since the last save, handing it
+    /// to the background writer thread.
+    ///
+    /// Returns whether a snapshot was captured. Write failures are logged by the
+    /// writer thread.
+    pub fn save_if_due(
+        &self,
+        state: &ArbitrageEngineState,
+        strategy: &PredictionArbitrageStrategy,
+        now: DateTime<Utc>,
+    ) -> bool {
+        if self
+            .last_saved
+            .get()
+            .is_some_and(|last| now - last < self.interval)
+        {
+            return false;
+        }
+        self.last_saved.set(Some(now));
+
+        let snapshot = StateSnapshot::capture(state, strategy, now);
+        if self.writer().send(snapshot).is_err() {
+            warn!(path = %self.path.display(), "Snapshot writer stopped, snapshot dropped");
+        }
+        true
+    }
+
+    fn writer(&self) -> &mpsc::Sender<StateSnapshot> {
+        self.writer.get_or_init(|| {
+            let (tx, rx) = mpsc::channel::<StateSnapshot>();
+            let path = self.path.clone();
+            let written = Arc::clone(&self.written);
+            std::thread::spawn(move || {
+                for snapshot in rx {
+                    if let Err(e) = write_snapshot(&path, &written, &snapshot) {
+                        warn!(path = %path.display(), "Failed to save state snapshot: {}", e);
+                    }
+                }
+            });
+            tx
+        })
+    }
+
+    /// Load the snapshot, returning `None` if no file exists yet.
+    ///
+    /// Refuses snapshots written with a different [`SNAPSHOT_VERSION`].
+    pub fn load(&self) -> Result<Option<StateSnapshot>, PersistenceError> {
+        let bytes = match std::fs::read(&self.path) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+
+        #[derive(Deserialize)]
+        struct Versioned {
+            version: u32,
+        }
+        let Versioned { version } = serde_json::from_slice(&bytes)?;
+        if version != SNAPSHOT_VERSION {
+            return Err(PersistenceError::VersionMismatch {
+                found: version,
+                expected: SNAPSHOT_VERSION,
+            });
+        }
+
+        let snapshot: StateSnapshot = serde_json::from_slice(&bytes)?;
+        info!(
+            path = %self.path.display(),
+            time = %snapshot.time,
+            instruments = snapshot.instruments.len(),
+            "Loaded state snapshot"
+        );
+        Ok(Some(snapshot))
+    }
+}
+
+/// Write `snapshot` to `path` via a temporary file, unless `written` records a newer
+/// snapshot.
+fn write_snapshot(
+    path: &Path,
+    written: &Mutex<Option<DateTime<Utc>>>,
+    snapshot: &StateSnapshot,
+) -> Result<(), PersistenceError> {
+    let mut written = written
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    if written.is_some_and(|time| time > snapshot.time) {
+        return Ok(());
+    }
+
+    let tmp = path.with_extension("tmp");
+    std::fs::write(&tmp, serde_json::to_vec_pretty(snapshot)?)?;
+    std::fs::rename(&tmp, path)?;
+    *written = Some(snapshot.time);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::ArbitrageConfig, correlation::CorrelatedPair};
+    use barter::engine::state::builder::EngineStateBuilder;
+    use barter_execution::order::id::StrategyId;
+    use barter_instrument::{asset::Asset, index::IndexedInstruments, Underlying};
+    use rust_decimal_macros::dec;
+
+    fn pair() -> CorrelatedPair {
+        CorrelatedPair::new(
+            "KXTEST",
+            "0xcond",
+            "0xyes",
+            "0xno",
+            "Test market",
+            Utc::now() + chrono::Duration::days(30),
+            false,
+        )
+    }
+
+    fn indexed(names: &[&str]) -> IndexedInstruments {
+        let mut builder = IndexedInstruments::builder();
+        for name in names {
+            builder = builder.add_instrument(Instrument::spot(
+                ExchangeId::Kalshi,
+                format!("kalshi_{name}"),
+                *name,
+                Underlying::new(Asset::from(*name), Asset::from("usd")),
+                None,
+            ));
+        }
+        builder.build()
+    }
+
+    fn strategy(indexed: &IndexedInstruments) -> PredictionArbitrageStrategy {
+        PredictionArbitrageStrategy::with_instruments(
+            StrategyId::new("test-arb"),
+            ArbitrageConfig::default(),
+            vec![pair()],
+            indexed,
+        )
+    }
+
+    fn snapshot_path(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("arb-persistence-{}-{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir.join("state.json")
+    }
+
+    #[test]
+    fn test_snapshot_restores_positions_and_capital() {
+        let path = snapshot_path("restore");
+        let before = indexed(&["a", "b"]);
+        let mut state = EngineStateBuilder::new(&before, ArbitrageGlobalData::default(), |_| {
+            ArbitrageInstrumentData::default()
+        })
+        .build();
+        state.global.total_deployed = dec!(42.50);
+        let b = state
+            .instruments
+            .instrument_mut(&InstrumentNameInternal::new("kalshi_b"));
+        b.data.position = 10;
+        b.data.cost_basis = dec!(4.20);
+        b.data.avg_entry = Some(dec!(0.42));
+
+        let strategy_before = strategy(&before);
+        strategy_before.suspend_pair(&pair(), "expired");
+        let persistence = StatePersistence::new(&path);
+        persistence
+            .save(&StateSnapshot::capture(
+                &state,
+                &strategy_before,
+                Utc::now(),
+            ))
+            .unwrap();
+
+        // Restart with the instruments in a different index order
+        let after = indexed(&["b", "a"]);
+        let snapshot = persistence.load().unwrap().unwrap();
+        let restored = EngineStateBuilder::new(&after, snapshot.global.clone(), |instrument| {
+            snapshot.instrument_data(instrument)
+        })
+        .build();
+        let strategy_after = strategy(&after);
+        strategy_after.restore(&snapshot.strategy);
+
+        assert_eq!(restored.global.total_deployed, dec!(42.50));
+        let b = restored
+            .instruments
+            .instrument(&InstrumentNameInternal::new("kalshi_b"));
+        assert_eq!(b.data.position, 10);
+        assert_eq!(b.data.cost_basis, dec!(4.20));
+        assert_eq!(b.data.avg_entry, Some(dec!(0.42)));
+        let a = restored
+            .instruments
+            .instrument(&InstrumentNameInternal::new("kalshi_a"));
+        assert_eq!(a.data.position, 0);
+        assert_eq!(
+            strategy_after.suspension_reason("KXTEST").as_deref(),
+            Some("expired")
+        );
+
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_load_refuses_version_mismatch() {
+        let path = snapshot_path("version");
+        let snapshot = StateSnapshot {
+            version: SNAPSHOT_VERSION + 1,
+            ..Default::default()
+        };
+        std::fs::write(&path, serde_json::to_vec(&snapshot).unwrap()).unwrap();
+
+        let result = StatePersistence::new(&path).load();
+        assert!(matches!(
+            result,
+            Err(PersistenceError::VersionMismatch { found, expected })
+                if found == SNAPSHOT_VERSION + 1 && expected == SNAPSHOT_VERSION
+        ));
+
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_load_missing_file_is_none() {
+        let persistence = StatePersistence::new(snapshot_path("missing").join("absent.json"));
+        assert!(persistence.load().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_save_if_due_respects_interval() {
+        let path = snapshot_path("interval");
+        let indexed = indexed(&["a"]);
+        let state = EngineStateBuilder::new(&indexed, ArbitrageGlobalData::default(), |_| {
+            ArbitrageInstrumentData::default()
+        })
+        .build();
+        let strategy = strategy(&indexed);
+        let persistence = StatePersistence::new(&path).with_interval(TimeDelta::seconds(30));
+
+        let now = Utc::now();
+        assert!(persistence.save_if_due(&state, &strategy, now));
+        assert!(!persistence.save_if_due(&state, &strategy, now + TimeDelta::seconds(10)));
+        assert!(persistence.save_if_due(&state, &strategy, now + TimeDelta::seconds(30)));
+
+        // Written in the background
+        let written = now + TimeDelta::seconds(30);
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+        while persistence
+            .load()
+            .ok()
+            .flatten()
+            .map(|snapshot| snapshot.time)
+            != Some(written)
+        {
+            assert!(std::time::Instant::now() < deadline, "snapshot not written");
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+
+        // A stale snapshot never replaces a newer one
+        persistence
+            .save(&StateSnapshot::capture(&state, &strategy, now))
+            .unwrap();
+        assert_eq!(persistence.load().unwrap().unwrap().time, written);
+
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_daily_loss_survives_restart() {
+        use crate::risk::{ArbitrageRiskManager, DailyPnlTracker};
+
+        let path = snapshot_path("daily-pnl");
+        let indexed = indexed(&["a"]);
+        let name = InstrumentNameInternal::new("kalshi_a");
+        let risk = |daily_pnl| ArbitrageRiskManager {
+            max_daily_loss: dec!(150),
+            daily_pnl,
+            ..Default::default()
+        };
+
+        let daily_pnl = DailyPnlTracker::default();
+        let risk_before = risk(daily_pnl.clone());
+        let strategy_before = strategy(&indexed).with_daily_pnl(daily_pnl);
+        let mut state = EngineStateBuilder::new(&indexed, ArbitrageGlobalData::default(), |_| {
+            ArbitrageInstrumentData::default()
+        })
+        .build();
+        let now = Utc::now();
+        assert!(!risk_before.daily_loss_breached(&state, now));
+        state.instruments.instrument_mut(&name).data.realized_pnl = dec!(-100);
+        assert!(!risk_before.daily_loss_breached(&state, now));
+
+        let persistence = StatePersistence::new(&path);
+        persistence
+            .save(&StateSnapshot::capture(&state, &strategy_before, now))
+            .unwrap();
+
+        // Restart mid-day, then lose another 60
+        let snapshot = persistence.load().unwrap().unwrap();
+        let mut restored =
+            EngineStateBuilder::new(&indexed, snapshot.global.clone(), |instrument| {
+                snapshot.instrument_data(instrument)
+            })
+            .build();
+        let daily_pnl = DailyPnlTracker::default();
+        let risk_after = risk(daily_pnl.clone());
+        strategy(&indexed)
+            .with_daily_pnl(daily_pnl)
+            .restore(&snapshot.strategy);
+        restored.instruments.instrument_mut(&name).data.realized_pnl = dec!(-160);
+
+        assert!(risk_after.daily_loss_breached(&restored, now));
+        assert_eq!(risk_after.daily_pnl.get().realized, dec!(-160));
+
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
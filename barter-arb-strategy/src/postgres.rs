@@ -0,0 +1,303 @@
+//! Direct Postgres backend for fetching correlated market pairs.
+//!
+//! Alternative to the Supabase REST API for deployments that can reach the
+//! database directly. Calls the same `get_market_pairs_with_volume` function
+//! with the same parameters and defaults as [`DatabaseQuerier::get_market_pairs`],
+//! then applies the same client-side filters.
+//!
+//! Requires the `postgres` feature.
+//!
+//! [`DatabaseQuerier::get_market_pairs`]: crate::database::DatabaseQuerier::get_market_pairs
+
+use crate::{
+    correlation::CorrelatedPair,
+    database::{records_to_pairs, DatabaseError, MarketPairFilters, MarketPairRecord},
+    source::PairSource,
+};
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use sqlx::{
+    postgres::{PgPoolOptions, PgRow},
+    FromRow, PgPool, Row,
+};
+use std::future::Future;
+use tracing::debug;
+
+/// Default database function returning market pairs with volume.
+pub const DEFAULT_PAIRS_FUNCTION: &str = "get_market_pairs_with_volume";
+
+/// Maximum connections held by pools created via [`PostgresPairSource::connect`].
+const MAX_CONNECTIONS: u32 = 4;
+
+/// Row returned by the market pairs function.
+///
+/// Mirrors [`MarketPairRecord`], but tolerates a NULL `evaluation_id`, which
+/// PostgREST never returns for the same rows because of its JSON defaults.
+#[derive(Debug, Clone)]
+pub struct MarketPairRow {
+    pub id: i64,
+    pub polymarket_id: String,
+    pub polymarket_condition_id: Option<String>,
+    pub polymarket_yes_token_id: Option<String>,
+    pub kalshi_ticker: String,
+    pub similarity_score: Decimal,
+    pub confidence_score: Decimal,
+    pub evaluation_id: Option<i64>,
+    pub llm_notes: Option<String>,
+    pub discovered_at: DateTime<Utc>,
+    pub last_verified_at: Option<DateTime<Utc>>,
+    pub verified_at: Option<DateTime<Utc>>,
+    pub valid: Option<bool>,
+    pub validation_result: Option<String>,
+    pub inverse: Option<bool>,
+    pub polymarket_volume: Option<Decimal>,
+    pub kalshi_volume: Option<Decimal>,
+    pub polymarket_question: Option<String>,
+    pub kalshi_expiry: Option<DateTime<Utc>>,
+}
+
+impl FromRow<'_, PgRow> for MarketPairRow {
+    fn from_row(row: &PgRow) -> Result<Self, sqlx::Error> {
+        Ok(Self {
+            id: row.try_get("id")?,
+            polymarket_id: row.try_get("polymarket_id")?,
+            polymarket_condition_id: row.try_get("polymarket_condition_id")?,
+            polymarket_yes_token_id: row.try_get("polymarket_yes_token_id")?,
+            kalshi_ticker: row.try_get("kalshi_ticker")?,
+            similarity_score: row.try_get("similarity_score")?,
+            confidence_score: row.try_get("confidence_score")?,
+            evaluation_id: row.try_get("evaluation_id")?,
+            llm_notes: row.try_get("llm_notes")?,
+            discovered_at: row.try_get("discovered_at")?,
+            last_verified_at: row.try_get("last_verified_at")?,
+            verified_at: row.try_get("verified_at")?,
+            valid: row.try_get("valid")?,
+            validation_result: row.try_get("validation_result")?,
+            inverse: row.try_get("inverse")?,
+            polymarket_volume: row.try_get("polymarket_volume")?,
+            kalshi_volume: row.try_get("kalshi_volume")?,
+            polymarket_question: row.try_get("polymarket_question")?,
+            kalshi_expiry: row.try_get("kalshi_expiry")?,
+        })
+    }
+}
+
+impl From<MarketPairRow> for MarketPairRecord {
+    fn from(row: MarketPairRow) -> Self {
+        Self {
+            id: row.id,
+            polymarket_id: row.polymarket_id,
+            polymarket_condition_id: row.polymarket_condition_id,
+            polymarket_yes_token_id: row.polymarket_yes_token_id,
+            kalshi_ticker: row.kalshi_ticker,
+            similarity_score: row.similarity_score,
+            confidence_score: row.confidence_score,
+            evaluation_id: row.evaluation_id.unwrap_or_default(),
+            llm_notes: row.llm_notes,
+            discovered_at: row.discovered_at,
+            last_verified_at: row.last_verified_at,
+            verified_at: row.verified_at,
+            valid: row.valid,
+            validation_result: row.validation_result,
+            inverse: row.inverse,
+            polymarket_volume: row.polymarket_volume,
+            kalshi_volume: row.kalshi_volume,
+            polymarket_question: row.polymarket_question,
+            kalshi_expiry: row.kalshi_expiry,
+        }
+    }
+}
+
+/// Pair source querying Postgres directly.
+#[derive(Debug, Clone)]
+pub struct PostgresPairSource {
+    pool: PgPool,
+    function: String,
+}
+
+impl PostgresPairSource {
+    /// Create a source from an existing connection pool.
+    pub fn from_pool(pool: PgPool) -> Self {
+        Self {
+            pool,
+            function: DEFAULT_PAIRS_FUNCTION.to_string(),
+        }
+    }
+
+    /// Connect to the database at the given URL.
+    pub async fn connect(database_url: &str) -> Result<Self, DatabaseError> {
+        let pool = PgPoolOptions::new()
+            .max_connections(MAX_CONNECTIONS)
+            .connect(database_url)
+            .await?;
+
+        Ok(Self::from_pool(pool))
+    }
+
+    /// Connect using the `DATABASE_URL` environment variable.
+    pub async fn from_env() -> Result<Self, DatabaseError> {
+        let database_url = std::env::var("DATABASE_URL")
+            .map_err(|_| DatabaseError::Config("DATABASE_URL not set".to_string()))?;
+
+        Self::connect(&database_url).await
+    }
+
+    /// Use a different function to fetch market pairs (optionally schema-qualified).
+    ///
+    /// The name is interpolated into SQL, so only ASCII alphanumerics, `_` and
+    /// `.` are accepted.
+    pub fn with_function(mut self, function: impl Into<String>) -> Result<Self, DatabaseError> {
+        let function = function.into();
+        if !is_valid_identifier(&function) {
+            return Err(DatabaseError::Config(format!(
+                "Invalid pairs function name: {}",
+                function
+            )));
+        }
+        self.function = function;
+        Ok(self)
+    }
+
+    /// Underlying connection pool.
+    pub fn pool(&self) -> &PgPool {
+        &self.pool
+    }
+
+    /// Get market pairs with optional filters.
+    pub async fn get_market_pairs(
+        &self,
+        filters: MarketPairFilters,
+    ) -> Result<Vec<MarketPairRecord>, DatabaseError> {
+        let filters = filters.with_query_defaults();
+
+        let sql = format!(
+            "SELECT * FROM {}(min_similarity => $1, min_confidence => $2, \
+             row_limit => $3, row_offset => $4, valid_only => $5)",
+            self.function
+        );
+
+        debug!("Fetching market pairs via {}", self.function);
+
+        let rows: Vec<MarketPairRow> = sqlx::query_as(&sql)
+            .bind(filters.min_similarity.unwrap())
+            .bind(filters.min_confidence.unwrap())
+            .bind(filters.limit.unwrap() as i32)
+            .bind(filters.offset.unwrap() as i32)
+            .bind(filters.valid_only.unwrap())
+            .fetch_all(&self.pool)
+            .await?;
+
+        let fetched = rows.len();
+        let records = filters.apply(rows.into_iter().map(MarketPairRecord::from).collect());
+
+        debug!(
+            "Fetched {} market pairs ({} after client-side filters)",
+            fetched,
+            records.len()
+        );
+
+        Ok(records)
+    }
+
+    /// Get market pairs and convert to CorrelatedPair.
+    pub async fn get_correlated_pairs(
+        &self,
+        filters: MarketPairFilters,
+    ) -> Result<Vec<CorrelatedPair>, DatabaseError> {
+        let records = self.get_market_pairs(filters).await?;
+        Ok(records_to_pairs(records))
+    }
+}
+
+impl PairSource for PostgresPairSource {
+    fn get_correlated_pairs(
+        &self,
+        filters: MarketPairFilters,
+    ) -> impl Future<Output = Result<Vec<CorrelatedPair>, DatabaseError>> + Send {
+        PostgresPairSource::get_correlated_pairs(self, filters)
+    }
+}
+
+/// Whether a (possibly schema-qualified) name is safe to interpolate into SQL.
+fn is_valid_identifier(name: &str) -> bool {
+    !name.is_empty()
+        && name.split('.').all(|part| {
+            !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn row() -> MarketPairRow {
+        MarketPairRow {
+            id: 42,
+            polymarket_id: "poly-1".to_string(),
+            polymarket_condition_id: Some("0xcondition".to_string()),
+            polymarket_yes_token_id: Some(r#"["yes_token", "no_token"]"#.to_string()),
+            kalshi_ticker: "KXTEST-25JAN31".to_string(),
+            similarity_score: dec!(0.91),
+            confidence_score: dec!(0.88),
+            evaluation_id: Some(7),
+            llm_notes: None,
+            discovered_at: Utc::now(),
+            last_verified_at: None,
+            verified_at: None,
+            valid: Some(true),
+            validation_result: None,
+            inverse: Some(true),
+            polymarket_volume: Some(dec!(15000)),
+            kalshi_volume: None,
+            polymarket_question: Some("Will it happen?".to_string()),
+            kalshi_expiry: Some(Utc::now() + chrono::Duration::days(10)),
+        }
+    }
+
+    #[test]
+    fn test_row_to_record() {
+        let row = row();
+        let record = MarketPairRecord::from(row.clone());
+
+        assert_eq!(record.id, 42);
+        assert_eq!(record.kalshi_ticker, "KXTEST-25JAN31");
+        assert_eq!(record.similarity_score, dec!(0.91));
+        assert_eq!(record.evaluation_id, 7);
+        assert_eq!(record.inverse, Some(true));
+        assert_eq!(record.polymarket_volume, Some(dec!(15000)));
+        assert_eq!(record.kalshi_expiry, row.kalshi_expiry);
+        assert_eq!(record.yes_token_id().as_deref(), Some("yes_token"));
+        assert_eq!(record.no_token_id().as_deref(), Some("no_token"));
+    }
+
+    #[test]
+    fn test_row_to_record_null_evaluation_id() {
+        let record = MarketPairRecord::from(MarketPairRow {
+            evaluation_id: None,
+            ..row()
+        });
+
+        assert_eq!(record.evaluation_id, 0);
+    }
+
+    #[test]
+    fn test_row_to_correlated_pair() {
+        let pair = MarketPairRecord::from(row()).to_correlated_pair().unwrap();
+
+        assert_eq!(pair.db_id, Some(42));
+        assert!(pair.inverse);
+        assert_eq!(pair.polymarket_condition_id, "0xcondition");
+    }
+
+    #[test]
+    fn test_is_valid_identifier() {
+        assert!(is_valid_identifier("get_market_pairs_with_volume"));
+        assert!(is_valid_identifier(
+            "test_schema.get_market_pairs_with_volume"
+        ));
+        assert!(!is_valid_identifier(""));
+        assert!(!is_valid_identifier("schema."));
+        assert!(!is_valid_identifier("pairs(); DROP TABLE market_pairs; --"));
+    }
+}
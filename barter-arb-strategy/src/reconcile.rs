@@ -0,0 +1,1029 @@
+//! Reconcile engine state with the exchanges at startup.
+//!
+//! After a restart the engine assumes flat positions and no working orders,
+//! which is untrue if the previous run left inventory or resting orders behind.
+//! [`reconcile_startup`] fetches positions and open orders from each
+//! [`ReconcileClient`], seeds [`ArbitrageInstrumentData`] positions from the
+//! exchange's view, and either adopts unknown open orders into the engine's
+//! order state or cancels them (`cancel_unknown_orders`).
+//!
+//! Every difference between local and exchange state is listed in the returned
+//! [`ReconciliationReport`].
+//!
+//! While running, a [`PositionReconciler`] repeats the position check on an
+//! interval, feeding exchange positions to the engine as account events so
+//! missed fills or manual trades don't leave local positions drifting.
+//!
+//! [`ArbitrageInstrumentData`]: crate::state::ArbitrageInstrumentData
+//!
+//! ```rust,ignore
+//! let report = reconcile_startup(
+//!     &mut state,
+//!     &indexed,
+//!     &[&kalshi_client as &dyn ReconcileClient, &polymarket_client],
+//!     config.cancel_unknown_orders,
+//! )
+//! .await?;
+//! ```
+
+use crate::state::ArbitrageEngineState;
+use async_trait::async_trait;
+use barter::execution::AccountStreamEvent;
+use barter_execution::{
+    client::{
+        kalshi::KalshiExecution, polymarket::PolymarketExecution, ExecutionClient,
+        InstrumentPosition,
+    },
+    error::UnindexedClientError,
+    order::{
+        id::ClientOrderId,
+        request::{OrderRequestCancel, RequestCancel},
+        state::{ActiveOrderState, Open},
+        Order, OrderEvent, OrderKey,
+    },
+    AccountEvent,
+};
+use barter_instrument::{
+    exchange::{ExchangeId, ExchangeIndex},
+    index::IndexedInstruments,
+    instrument::{name::InstrumentNameExchange, InstrumentIndex},
+};
+use barter_integration::snapshot::Snapshot;
+use rust_decimal::Decimal;
+use std::{collections::HashSet, sync::Arc, time::Duration};
+use thiserror::Error;
+use tokio::sync::mpsc;
+use tracing::{debug, info, warn};
+
+/// Open order as reported by an exchange.
+pub type ExchangeOpenOrder = Order<ExchangeId, InstrumentNameExchange, Open>;
+
+/// Errors fetching exchange state during reconciliation.
+#[derive(Debug, Error)]
+pub enum ReconcileError {
+    #[error("failed to fetch {exchange} positions: {source}")]
+    Positions {
+        exchange: ExchangeId,
+        source: UnindexedClientError,
+    },
+    #[error("failed to fetch {exchange} open orders: {source}")]
+    OpenOrders {
+        exchange: ExchangeId,
+        source: UnindexedClientError,
+    },
+}
+
+/// Exchange account queries needed to reconcile at startup.
+#[async_trait]
+pub trait ReconcileClient: Sync {
+    /// Exchange this client trades on.
+    fn exchange(&self) -> ExchangeId;
+
+    /// Fetch non-flat positions, keyed by exchange instrument name.
+    async fn fetch_positions(&self) -> Result<Vec<InstrumentPosition>, UnindexedClientError>;
+
+    /// Fetch resting orders.
+    async fn fetch_open_orders(&self) -> Result<Vec<ExchangeOpenOrder>, UnindexedClientError>;
+
+    /// Cancel a resting order, returning an error description on failure.
+    async fn cancel_order(&self, order: &ExchangeOpenOrder) -> Result<(), String>;
+
+    /// Cancel every resting order, returning how many cancels succeeded.
+    ///
+    /// Failed cancels are logged and skipped, so callers should re-check
+    /// [`Self::fetch_open_orders`] to confirm nothing is left working.
+    async fn cancel_all(&self) -> Result<usize, UnindexedClientError> {
+        let mut cancelled = 0;
+        for order in self.fetch_open_orders().await? {
+            match self.cancel_order(&order).await {
+                Ok(()) => cancelled += 1,
+                Err(error) => warn!(
+                    exchange = %self.exchange(),
+                    cid = %order.key.cid,
+                    %error,
+                    "Failed to cancel resting order"
+                ),
+            }
+        }
+        Ok(cancelled)
+    }
+}
+
+/// Cancel `order` through an [`ExecutionClient`], flattening the response.
+async fn cancel_with<Client: ExecutionClient>(
+    client: &Client,
+    order: &ExchangeOpenOrder,
+) -> Result<(), String> {
+    let request = OrderRequestCancel {
+        key: OrderKey {
+            exchange: order.key.exchange,
+            instrument: &order.key.instrument,
+            strategy: order.key.strategy.clone(),
+            cid: order.key.cid.clone(),
+        },
+        state: RequestCancel {
+            id: Some(order.state.id.clone()),
+        },
+    };
+
+    match client.cancel_order(request).await {
+        Some(OrderEvent { state: Ok(_), .. }) => Ok(()),
+        Some(OrderEvent {
+            state: Err(error), ..
+        }) => Err(error.to_string()),
+        None => Err("no cancel response".to_string()),
+    }
+}
+
+#[async_trait]
+impl ReconcileClient for KalshiExecution {
+    fn exchange(&self) -> ExchangeId {
+        ExchangeId::Kalshi
+    }
+
+    async fn fetch_positions(&self) -> Result<Vec<InstrumentPosition>, UnindexedClientError> {
+        KalshiExecution::fetch_positions(self).await
+    }
+
+    async fn fetch_open_orders(&self) -> Result<Vec<ExchangeOpenOrder>, UnindexedClientError> {
+        ExecutionClient::fetch_open_orders(self).await
+    }
+
+    async fn cancel_order(&self, order: &ExchangeOpenOrder) -> Result<(), String> {
+        cancel_with(self, order).await
+    }
+}
+
+#[async_trait]
+impl ReconcileClient for PolymarketExecution {
+    fn exchange(&self) -> ExchangeId {
+        ExchangeId::Polymarket
+    }
+
+    async fn fetch_positions(&self) -> Result<Vec<InstrumentPosition>, UnindexedClientError> {
+        PolymarketExecution::fetch_positions(self).await
+    }
+
+    async fn fetch_open_orders(&self) -> Result<Vec<ExchangeOpenOrder>, UnindexedClientError> {
+        ExecutionClient::fetch_open_orders(self).await
+    }
+
+    async fn cancel_order(&self, order: &ExchangeOpenOrder) -> Result<(), String> {
+        cancel_with(self, order).await
+    }
+}
+
+/// A difference between local and exchange state found during reconciliation.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Discrepancy {
+    /// Local position differed from the exchange's; local state was overwritten.
+    PositionMismatch {
+        exchange: ExchangeId,
+        instrument: InstrumentNameExchange,
+        local: i32,
+        exchange_quantity: Decimal,
+    },
+    /// Exchange reported a position or order on an instrument the engine doesn't trade.
+    UnknownInstrument {
+        exchange: ExchangeId,
+        instrument: InstrumentNameExchange,
+    },
+    /// Unknown open order added to the engine's order state.
+    OrderAdopted {
+        exchange: ExchangeId,
+        instrument: InstrumentNameExchange,
+        cid: ClientOrderId,
+    },
+    /// Unknown open order cancelled on the exchange.
+    OrderCancelled {
+        exchange: ExchangeId,
+        instrument: InstrumentNameExchange,
+        cid: ClientOrderId,
+    },
+    /// Unknown open order that could not be cancelled.
+    CancelFailed {
+        exchange: ExchangeId,
+        instrument: InstrumentNameExchange,
+        cid: ClientOrderId,
+        error: String,
+    },
+}
+
+/// Outcome of [`reconcile_startup`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReconciliationReport {
+    /// Instruments whose position was seeded from an exchange
+    pub positions_seeded: usize,
+    /// Open orders reported by the exchanges
+    pub open_orders: usize,
+    pub discrepancies: Vec<Discrepancy>,
+}
+
+impl ReconciliationReport {
+    /// Whether local state already matched the exchanges.
+    pub fn is_clean(&self) -> bool {
+        self.discrepancies.is_empty()
+    }
+}
+
+/// Seed positions and resolve open orders from each client's exchange.
+///
+/// Positions on an exchange replace the local position of the matching
+/// instrument; local positions the exchange doesn't report are flattened.
+/// `total_deployed` is recomputed from the seeded cost bases. Open orders not
+/// already tracked are cancelled when `cancel_unknown` is set, otherwise
+/// adopted as open orders so the engine manages them.
+pub async fn reconcile_startup(
+    state: &mut ArbitrageEngineState,
+    indexed: &IndexedInstruments,
+    clients: &[&dyn ReconcileClient],
+    cancel_unknown: bool,
+) -> Result<ReconciliationReport, ReconcileError> {
+    let mut report = ReconciliationReport::default();
+
+    for client in clients {
+        let exchange = client.exchange();
+        let positions = client
+            .fetch_positions()
+            .await
+            .map_err(|source| ReconcileError::Positions { exchange, source })?;
+        let open_orders = client
+            .fetch_open_orders()
+            .await
+            .map_err(|source| ReconcileError::OpenOrders { exchange, source })?;
+
+        seed_positions(state, indexed, exchange, &positions, &mut report);
+
+        report.open_orders += open_orders.len();
+        for order in &open_orders {
+            resolve_open_order(state, indexed, *client, order, cancel_unknown, &mut report).await;
+        }
+    }
+
+    state.global.total_deployed = state
+        .instruments
+        .0
+        .values()
+        .map(|instrument| instrument.data.cost_basis)
+        .sum();
+
+    info!(
+        positions_seeded = report.positions_seeded,
+        open_orders = report.open_orders,
+        discrepancies = report.discrepancies.len(),
+        total_deployed = %state.global.total_deployed,
+        "Startup reconciliation complete"
+    );
+
+    Ok(report)
+}
+
+/// Task that re-fetches exchange positions and open orders on an interval,
+/// correcting the engine's positions while it runs.
+///
+/// Each traded instrument is sent to the engine as an
+/// [`AccountEventKind::PositionSnapshot`], flat if the exchange reports no
+/// position on it. [`ArbitrageInstrumentData`] overwrites its local position and
+/// logs a warning when consecutive snapshots report the same differing quantity with
+/// no fills in between, so fills racing a snapshot are not counted twice. Open orders
+/// are fetched to log those resting on instruments the engine doesn't trade.
+///
+/// `total_deployed` tracks capital through fills and is not corrected.
+///
+/// [`AccountEventKind::PositionSnapshot`]: barter_execution::AccountEventKind::PositionSnapshot
+/// [`ArbitrageInstrumentData`]: crate::state::ArbitrageInstrumentData
+pub struct PositionReconciler {
+    clients: Vec<Arc<dyn ReconcileClient + Send>>,
+    indexed: IndexedInstruments,
+    interval: Duration,
+    tx: mpsc::UnboundedSender<AccountStreamEvent>,
+}
+
+impl std::fmt::Debug for PositionReconciler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PositionReconciler")
+            .field(
+                "clients",
+                &self
+                    .clients
+                    .iter()
+                    .map(|client| client.exchange())
+                    .collect::<Vec<_>>(),
+            )
+            .field("interval", &self.interval)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PositionReconciler {
+    /// Create a reconciler sending position snapshots to the engine's account
+    /// channel `tx`.
+    pub fn new(
+        clients: Vec<Arc<dyn ReconcileClient + Send>>,
+        indexed: IndexedInstruments,
+        interval: Duration,
+        tx: mpsc::UnboundedSender<AccountStreamEvent>,
+    ) -> Self {
+        Self {
+            clients,
+            indexed,
+            interval,
+            tx,
+        }
+    }
+
+    /// Fetch every client's positions and open orders once and send the
+    /// positions to the engine.
+    ///
+    /// A client that fails to report is skipped and its error collected, so one
+    /// exchange being unreachable doesn't stop the others being reconciled.
+    /// Returns the number of position snapshots sent and the errors.
+    pub async fn reconcile(&self) -> (usize, Vec<ReconcileError>) {
+        let mut sent = 0;
+        let mut errors = Vec::new();
+
+        for client in &self.clients {
+            if self.tx.is_closed() {
+                break;
+            }
+            match self.reconcile_client(client.as_ref()).await {
+                Ok(client_sent) => sent += client_sent,
+                Err(error) => errors.push(error),
+            }
+        }
+
+        (sent, errors)
+    }
+
+    /// Reconcile `client`'s exchange, returning the number of position snapshots sent.
+    async fn reconcile_client(
+        &self,
+        client: &(dyn ReconcileClient + Send),
+    ) -> Result<usize, ReconcileError> {
+        let exchange = client.exchange();
+        let positions = client
+            .fetch_positions()
+            .await
+            .map_err(|source| ReconcileError::Positions { exchange, source })?;
+        let open_orders = client
+            .fetch_open_orders()
+            .await
+            .map_err(|source| ReconcileError::OpenOrders { exchange, source })?;
+
+        for order in &open_orders {
+            if find_instrument(&self.indexed, exchange, &order.key.instrument).is_none() {
+                warn!(
+                    %exchange,
+                    instrument = %order.key.instrument,
+                    cid = %order.key.cid,
+                    "Open order on untraded instrument"
+                );
+            }
+        }
+
+        let mut sent = 0;
+        for instrument in self.indexed.instruments() {
+            if instrument.value.exchange.value != exchange {
+                continue;
+            }
+            let reported = positions
+                .iter()
+                .find(|position| position.instrument == instrument.value.name_exchange);
+            let (quantity, cost_basis) = reported
+                .map(|position| (position.quantity, position.cost_basis))
+                .unwrap_or_default();
+
+            let event = AccountEvent::new(
+                instrument.value.exchange.key,
+                Snapshot(InstrumentPosition {
+                    instrument: instrument.key,
+                    quantity,
+                    cost_basis,
+                }),
+            );
+            if self.tx.send(AccountStreamEvent::Item(event)).is_err() {
+                return Ok(sent);
+            }
+            sent += 1;
+        }
+
+        for position in &positions {
+            if find_instrument(&self.indexed, exchange, &position.instrument).is_none() {
+                warn!(
+                    %exchange,
+                    instrument = %position.instrument,
+                    "Position on untraded instrument"
+                );
+            }
+        }
+        debug!(
+            %exchange,
+            positions = positions.len(),
+            open_orders = open_orders.len(),
+            "Reconciled exchange positions"
+        );
+
+        Ok(sent)
+    }
+
+    /// Reconcile on the configured interval until the engine's account channel
+    /// is closed.
+    pub async fn run(self) {
+        let mut interval = tokio::time::interval(self.interval);
+        // First tick completes immediately; startup reconciliation already ran
+        interval.tick().await;
+
+        loop {
+            interval.tick().await;
+
+            if self.tx.is_closed() {
+                debug!("Account channel closed, stopping position reconciler");
+                return;
+            }
+
+            let (_, errors) = self.reconcile().await;
+            for e in errors {
+                warn!("Scheduled reconciliation failed: {}", e);
+            }
+        }
+    }
+}
+
+/// Resolve the engine instrument traded on `exchange` as `name`.
+fn find_instrument(
+    indexed: &IndexedInstruments,
+    exchange: ExchangeId,
+    name: &InstrumentNameExchange,
+) -> Option<(ExchangeIndex, InstrumentIndex)> {
+    indexed.instruments().iter().find_map(|instrument| {
+        (instrument.value.exchange.value == exchange && instrument.value.name_exchange == *name)
+            .then_some((instrument.value.exchange.key, instrument.key))
+    })
+}
+
+fn seed_positions(
+    state: &mut ArbitrageEngineState,
+    indexed: &IndexedInstruments,
+    exchange: ExchangeId,
+    positions: &[InstrumentPosition],
+    report: &mut ReconciliationReport,
+) {
+    let mut seeded = HashSet::new();
+
+    for position in positions {
+        let Some((_, index)) = find_instrument(indexed, exchange, &position.instrument) else {
+            warn!(%exchange, instrument = %position.instrument, "Position on untraded instrument");
+            report.discrepancies.push(Discrepancy::UnknownInstrument {
+                exchange,
+                instrument: position.instrument.clone(),
+            });
+            continue;
+        };
+
+        let data = &mut state.instruments.instrument_index_mut(&index).data;
+        if Decimal::from(data.position) != position.quantity {
+            report.discrepancies.push(Discrepancy::PositionMismatch {
+                exchange,
+                instrument: position.instrument.clone(),
+                local: data.position,
+                exchange_quantity: position.quantity,
+            });
+        }
+        data.set_position(position.quantity, position.cost_basis);
+        seeded.insert(index);
+        report.positions_seeded += 1;
+    }
+
+    // Local positions the exchange no longer reports were closed or settled
+    for instrument in indexed.instruments() {
+        if instrument.value.exchange.value != exchange || seeded.contains(&instrument.key) {
+            continue;
+        }
+        let data = &mut state.instruments.instrument_index_mut(&instrument.key).data;
+        if data.position != 0 {
+            report.discrepancies.push(Discrepancy::PositionMismatch {
+                exchange,
+                instrument: instrument.value.name_exchange.clone(),
+                local: data.position,
+                exchange_quantity: Decimal::ZERO,
+            });
+            data.position = 0;
+            data.avg_entry = None;
+            data.cost_basis = Decimal::ZERO;
+        }
+    }
+}
+
+async fn resolve_open_order(
+    state: &mut ArbitrageEngineState,
+    indexed: &IndexedInstruments,
+    client: &dyn ReconcileClient,
+    order: &ExchangeOpenOrder,
+    cancel_unknown: bool,
+    report: &mut ReconciliationReport,
+) {
+    let exchange = order.key.exchange;
+    let instrument = order.key.instrument.clone();
+    let cid = order.key.cid.clone();
+    let indexes = find_instrument(indexed, exchange, &instrument);
+
+    if let Some((_, index)) = indexes {
+        let orders = &state.instruments.instrument_index(&index).orders.0;
+        if orders.contains_key(&cid) {
+            return;
+        }
+    } else {
+        report.discrepancies.push(Discrepancy::UnknownInstrument {
+            exchange,
+            instrument: instrument.clone(),
+        });
+    }
+
+    match indexes {
+        Some((exchange_index, index)) if !cancel_unknown => {
+            info!(%exchange, %instrument, cid = %cid, "Adopting unknown open order");
+            state
+                .instruments
+                .instrument_index_mut(&index)
+                .orders
+                .0
+                .insert(
+                    cid.clone(),
+                    Order {
+                        key: OrderKey {
+                            exchange: exchange_index,
+                            instrument: index,
+                            strategy: order.key.strategy.clone(),
+                            cid: cid.clone(),
+                        },
+                        side: order.side,
+                        price: order.price,
+                        quantity: order.quantity,
+                        kind: order.kind,
+                        time_in_force: order.time_in_force,
+                        state: ActiveOrderState::Open(order.state.clone()),
+                    },
+                );
+            report.discrepancies.push(Discrepancy::OrderAdopted {
+                exchange,
+                instrument,
+                cid,
+            });
+        }
+        // Orders on untraded instruments can't be managed, so they're left alone
+        // unless cancelling is enabled
+        None if !cancel_unknown => {}
+        _ => match client.cancel_order(order).await {
+            Ok(()) => {
+                info!(%exchange, %instrument, cid = %cid, "Cancelled unknown open order");
+                report.discrepancies.push(Discrepancy::OrderCancelled {
+                    exchange,
+                    instrument,
+                    cid,
+                });
+            }
+            Err(error) => {
+                warn!(%exchange, %instrument, cid = %cid, %error, "Failed to cancel unknown order");
+                report.discrepancies.push(Discrepancy::CancelFailed {
+                    exchange,
+                    instrument,
+                    cid,
+                    error,
+                });
+            }
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::ArbitrageInstrumentData;
+    use barter::engine::state::builder::EngineStateBuilder;
+    use barter_execution::order::{
+        id::{OrderId, StrategyId},
+        OrderKind, TimeInForce,
+    };
+    use barter_instrument::{asset::Asset, instrument::Instrument, Side, Underlying};
+    use chrono::Utc;
+    use rust_decimal_macros::dec;
+    use std::sync::Mutex;
+
+    struct MockClient {
+        exchange: ExchangeId,
+        positions: Vec<InstrumentPosition>,
+        open_orders: Vec<ExchangeOpenOrder>,
+        cancelled: Mutex<Vec<ClientOrderId>>,
+        unreachable: bool,
+    }
+
+    impl MockClient {
+        fn new(exchange: ExchangeId) -> Self {
+            Self {
+                exchange,
+                positions: Vec::new(),
+                open_orders: Vec::new(),
+                cancelled: Mutex::new(Vec::new()),
+                unreachable: false,
+            }
+        }
+    }
+
+    #[async_trait]
+    impl ReconcileClient for MockClient {
+        fn exchange(&self) -> ExchangeId {
+            self.exchange
+        }
+
+        async fn fetch_positions(&self) -> Result<Vec<InstrumentPosition>, UnindexedClientError> {
+            if self.unreachable {
+                return Err(UnindexedClientError::Connectivity(
+                    barter_execution::error::ConnectivityError::Timeout,
+                ));
+            }
+            Ok(self.positions.clone())
+        }
+
+        async fn fetch_open_orders(&self) -> Result<Vec<ExchangeOpenOrder>, UnindexedClientError> {
+            Ok(self.open_orders.clone())
+        }
+
+        async fn cancel_order(&self, order: &ExchangeOpenOrder) -> Result<(), String> {
+            self.cancelled.lock().unwrap().push(order.key.cid.clone());
+            Ok(())
+        }
+    }
+
+    fn indexed() -> IndexedInstruments {
+        let instrument = |exchange, internal: &str, name: &str| {
+            Instrument::spot(
+                exchange,
+                internal,
+                name,
+                Underlying::new(Asset::from(name), Asset::from("usd")),
+                None,
+            )
+        };
+        IndexedInstruments::builder()
+            .add_instrument(instrument(
+                ExchangeId::Kalshi,
+                "kalshi_kxtest_yes",
+                "KXTEST_yes",
+            ))
+            .add_instrument(instrument(
+                ExchangeId::Kalshi,
+                "kalshi_kxtest_no",
+                "KXTEST_no",
+            ))
+            .add_instrument(instrument(
+                ExchangeId::Polymarket,
+                "polymarket_0xyes",
+                "0xyes",
+            ))
+            .build()
+    }
+
+    fn state(indexed: &IndexedInstruments) -> ArbitrageEngineState {
+        EngineStateBuilder::new(indexed, Default::default(), |_| {
+            ArbitrageInstrumentData::default()
+        })
+        .build()
+    }
+
+    fn position(instrument: &str, quantity: Decimal, cost_basis: Decimal) -> InstrumentPosition {
+        InstrumentPosition {
+            instrument: InstrumentNameExchange::from(instrument),
+            quantity,
+            cost_basis,
+        }
+    }
+
+    fn open_order(exchange: ExchangeId, instrument: &str, cid: &str) -> ExchangeOpenOrder {
+        Order {
+            key: OrderKey {
+                exchange,
+                instrument: InstrumentNameExchange::from(instrument),
+                strategy: StrategyId::new("pred-arb"),
+                cid: ClientOrderId::new(cid),
+            },
+            side: Side::Buy,
+            price: dec!(0.40),
+            quantity: dec!(10),
+            kind: OrderKind::Limit,
+            time_in_force: TimeInForce::GoodUntilCancelled { post_only: false },
+            state: Open {
+                id: OrderId::new(format!("{cid}-id")),
+                time_exchange: Utc::now(),
+                filled_quantity: Decimal::ZERO,
+            },
+        }
+    }
+
+    fn data<'a>(state: &'a ArbitrageEngineState, name: &str) -> &'a ArbitrageInstrumentData {
+        &state.instruments.instrument(&name.into()).data
+    }
+
+    #[tokio::test]
+    async fn test_positions_seeded_from_exchanges() {
+        let indexed = indexed();
+        let mut state = state(&indexed);
+        // Stale local position the exchange no longer reports
+        state
+            .instruments
+            .instrument_mut(&"kalshi_kxtest_no".into())
+            .data
+            .position = 5;
+
+        let mut kalshi = MockClient::new(ExchangeId::Kalshi);
+        kalshi.positions = vec![
+            position("KXTEST_yes", dec!(10), dec!(4.50)),
+            position("KXOTHER_yes", dec!(3), dec!(1.20)),
+        ];
+        let mut polymarket = MockClient::new(ExchangeId::Polymarket);
+        polymarket.positions = vec![position("0xyes", dec!(20), dec!(10.00))];
+
+        let report = reconcile_startup(&mut state, &indexed, &[&kalshi, &polymarket], false)
+            .await
+            .unwrap();
+
+        let yes = data(&state, "kalshi_kxtest_yes");
+        assert_eq!(yes.position, 10);
+        assert_eq!(yes.cost_basis, dec!(4.50));
+        assert_eq!(yes.avg_entry, Some(dec!(0.45)));
+        assert_eq!(data(&state, "kalshi_kxtest_no").position, 0);
+        assert_eq!(data(&state, "polymarket_0xyes").position, 20);
+        assert_eq!(state.global.total_deployed, dec!(14.50));
+
+        assert_eq!(report.positions_seeded, 2);
+        assert_eq!(
+            report.discrepancies,
+            vec![
+                Discrepancy::PositionMismatch {
+                    exchange: ExchangeId::Kalshi,
+                    instrument: InstrumentNameExchange::from("KXTEST_yes"),
+                    local: 0,
+                    exchange_quantity: dec!(10),
+                },
+                Discrepancy::UnknownInstrument {
+                    exchange: ExchangeId::Kalshi,
+                    instrument: InstrumentNameExchange::from("KXOTHER_yes"),
+                },
+                Discrepancy::PositionMismatch {
+                    exchange: ExchangeId::Kalshi,
+                    instrument: InstrumentNameExchange::from("KXTEST_no"),
+                    local: 5,
+                    exchange_quantity: Decimal::ZERO,
+                },
+                Discrepancy::PositionMismatch {
+                    exchange: ExchangeId::Polymarket,
+                    instrument: InstrumentNameExchange::from("0xyes"),
+                    local: 0,
+                    exchange_quantity: dec!(20),
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unknown_orders_adopted_by_default() {
+        let indexed = indexed();
+        let mut state = state(&indexed);
+        let mut kalshi = MockClient::new(ExchangeId::Kalshi);
+        kalshi.open_orders = vec![open_order(ExchangeId::Kalshi, "KXTEST_yes", "arb-1-yes")];
+
+        let report = reconcile_startup(&mut state, &indexed, &[&kalshi], false)
+            .await
+            .unwrap();
+
+        let orders = &state
+            .instruments
+            .instrument(&"kalshi_kxtest_yes".into())
+            .orders
+            .0;
+        let adopted = orders.get(&ClientOrderId::new("arb-1-yes")).unwrap();
+        assert!(matches!(adopted.state, ActiveOrderState::Open(_)));
+        assert_eq!(adopted.quantity, dec!(10));
+        assert!(kalshi.cancelled.lock().unwrap().is_empty());
+        assert_eq!(report.open_orders, 1);
+        assert!(matches!(
+            report.discrepancies.as_slice(),
+            [Discrepancy::OrderAdopted { .. }]
+        ));
+
+        // A second pass finds the order already tracked
+        let report = reconcile_startup(&mut state, &indexed, &[&kalshi], false)
+            .await
+            .unwrap();
+        assert!(report.is_clean());
+    }
+
+    #[tokio::test]
+    async fn test_unknown_orders_cancelled_when_configured() {
+        let indexed = indexed();
+        let mut state = state(&indexed);
+        let mut polymarket = MockClient::new(ExchangeId::Polymarket);
+        polymarket.open_orders = vec![
+            open_order(ExchangeId::Polymarket, "0xyes", "arb-2-no"),
+            open_order(ExchangeId::Polymarket, "0xuntraded", "manual"),
+        ];
+
+        let report = reconcile_startup(&mut state, &indexed, &[&polymarket], true)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            *polymarket.cancelled.lock().unwrap(),
+            vec![ClientOrderId::new("arb-2-no"), ClientOrderId::new("manual")]
+        );
+        assert!(state
+            .instruments
+            .instrument(&"polymarket_0xyes".into())
+            .orders
+            .0
+            .is_empty());
+        assert_eq!(
+            report.discrepancies,
+            vec![
+                Discrepancy::OrderCancelled {
+                    exchange: ExchangeId::Polymarket,
+                    instrument: InstrumentNameExchange::from("0xyes"),
+                    cid: ClientOrderId::new("arb-2-no"),
+                },
+                Discrepancy::UnknownInstrument {
+                    exchange: ExchangeId::Polymarket,
+                    instrument: InstrumentNameExchange::from("0xuntraded"),
+                },
+                Discrepancy::OrderCancelled {
+                    exchange: ExchangeId::Polymarket,
+                    instrument: InstrumentNameExchange::from("0xuntraded"),
+                    cid: ClientOrderId::new("manual"),
+                },
+            ]
+        );
+    }
+
+    #[derive(Clone, Default)]
+    struct CapturedLogs(Arc<Mutex<Vec<u8>>>);
+
+    impl std::io::Write for CapturedLogs {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Apply the events received on `rx` to `state`, returning the captured logs.
+    fn apply_events(
+        state: &mut ArbitrageEngineState,
+        rx: &mut mpsc::UnboundedReceiver<AccountStreamEvent>,
+    ) -> String {
+        let logs = CapturedLogs::default();
+        let writer = logs.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(move || writer.clone())
+            .with_ansi(false)
+            .finish();
+        tracing::subscriber::with_default(subscriber, || {
+            while let Ok(AccountStreamEvent::Item(event)) = rx.try_recv() {
+                state.update_from_account(&event);
+            }
+        });
+        let logs = logs.0.lock().unwrap().clone();
+        String::from_utf8(logs).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_scheduled_reconcile_overwrites_confirmed_divergent_position() {
+        let indexed = indexed();
+        let mut state = state(&indexed);
+        // Engine missed fills: the exchange holds 8 contracts, the engine thinks 5
+        state
+            .instruments
+            .instrument_mut(&"kalshi_kxtest_yes".into())
+            .data
+            .set_position(dec!(5), dec!(2.00));
+
+        let mut kalshi = MockClient::new(ExchangeId::Kalshi);
+        kalshi.positions = vec![position("KXTEST_yes", dec!(8), dec!(3.20))];
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let reconciler = PositionReconciler::new(
+            vec![Arc::new(kalshi)],
+            indexed.clone(),
+            Duration::from_secs(60),
+            tx,
+        );
+
+        // One snapshot per traded Kalshi instrument, including the flat NO leg
+        let (sent, errors) = reconciler.reconcile().await;
+        assert_eq!(sent, 2);
+        assert!(errors.is_empty());
+
+        // A first mismatch may be a fill racing the snapshot, so is not applied
+        let logs = apply_events(&mut state, &mut rx);
+        assert_eq!(data(&state, "kalshi_kxtest_yes").position, 5);
+        assert!(logs.contains("awaiting the next snapshot"), "{logs}");
+        assert!(!logs.contains("Position diverged"), "{logs}");
+
+        // The same mismatch again, with no fills in between, is drift
+        reconciler.reconcile().await;
+        let logs = apply_events(&mut state, &mut rx);
+
+        let yes = data(&state, "kalshi_kxtest_yes");
+        assert_eq!(yes.position, 8);
+        assert_eq!(yes.cost_basis, dec!(3.20));
+        assert_eq!(yes.avg_entry, Some(dec!(0.40)));
+        assert_eq!(data(&state, "kalshi_kxtest_no").position, 0);
+
+        let warnings = logs
+            .lines()
+            .filter(|line| line.contains("WARN") && line.contains("Position diverged"))
+            .collect::<Vec<_>>();
+        assert_eq!(warnings.len(), 1, "{logs}");
+        assert!(warnings[0].contains("local=5"), "{logs}");
+    }
+
+    #[tokio::test]
+    async fn test_scheduled_reconcile_ignores_fill_racing_snapshot() {
+        use barter_execution::{
+            trade::{AssetFees, Trade, TradeId},
+            AccountEventKind,
+        };
+
+        let indexed = indexed();
+        let mut state = state(&indexed);
+        let mut kalshi = MockClient::new(ExchangeId::Kalshi);
+        kalshi.positions = vec![position("KXTEST_yes", dec!(3), dec!(1.20))];
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let reconciler = PositionReconciler::new(
+            vec![Arc::new(kalshi)],
+            indexed.clone(),
+            Duration::from_secs(60),
+            tx,
+        );
+
+        // The snapshot already includes a fill the engine has yet to process
+        reconciler.reconcile().await;
+        apply_events(&mut state, &mut rx);
+        assert_eq!(data(&state, "kalshi_kxtest_yes").position, 0);
+
+        let (exchange, instrument) =
+            find_instrument(&indexed, ExchangeId::Kalshi, &"KXTEST_yes".into()).unwrap();
+        state.update_from_account(&AccountEvent::new(
+            exchange,
+            AccountEventKind::Trade(Trade {
+                id: TradeId::new("fill-1"),
+                order_id: OrderId::new("order-1"),
+                instrument,
+                strategy: StrategyId::new("arb"),
+                time_exchange: Utc::now(),
+                side: Side::Buy,
+                price: dec!(0.40),
+                quantity: dec!(3),
+                fees: AssetFees::quote_fees(Decimal::ZERO),
+            }),
+        ));
+
+        // The next snapshot matches, so the fill is counted once
+        reconciler.reconcile().await;
+        let logs = apply_events(&mut state, &mut rx);
+        assert_eq!(data(&state, "kalshi_kxtest_yes").position, 3);
+        assert!(!logs.contains("Position diverged"), "{logs}");
+    }
+
+    #[tokio::test]
+    async fn test_scheduled_reconcile_continues_past_unreachable_exchange() {
+        let indexed = indexed();
+        let mut state = state(&indexed);
+
+        let mut kalshi = MockClient::new(ExchangeId::Kalshi);
+        kalshi.unreachable = true;
+        let mut polymarket = MockClient::new(ExchangeId::Polymarket);
+        polymarket.positions = vec![position("0xyes", dec!(4), dec!(2.00))];
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let reconciler = PositionReconciler::new(
+            vec![Arc::new(kalshi), Arc::new(polymarket)],
+            indexed.clone(),
+            Duration::from_secs(60),
+            tx,
+        );
+
+        // Kalshi failing still reconciles Polymarket
+        let (sent, errors) = reconciler.reconcile().await;
+        assert_eq!(sent, 1);
+        assert!(matches!(
+            errors.as_slice(),
+            [ReconcileError::Positions {
+                exchange: ExchangeId::Kalshi,
+                ..
+            }]
+        ));
+
+        reconciler.reconcile().await;
+        apply_events(&mut state, &mut rx);
+        assert_eq!(data(&state, "polymarket_0xyes").position, 4);
+    }
+}
@@ -0,0 +1,634 @@
+//! Parquet output for the orderbook recorder.
+//!
+//! Writes one row per orderbook level so recordings can be queried directly by
+//! columnar tools. Events without levels (e.g. an empty snapshot) are written
+//! as a single row with null level columns, so the timeline stays complete.
+//! The `event_row` column numbers the rows of each event from zero, so
+//! identical consecutive events are read back as separate records.
+//! Opportunity records are not written; use the JSONL format to capture them.
+//!
+//! Requires the `parquet` feature.
+
+use super::{BookEventType, Compression, RecordedEvent};
+use arrow_array::{
+    builder::{
+        Decimal128Builder, StringBuilder, TimestampMicrosecondBuilder, UInt32Builder, UInt64Builder,
+    },
+    Array, Decimal128Array, RecordBatch, StringArray, TimestampMicrosecondArray, UInt32Array,
+    UInt64Array,
+};
+use arrow_schema::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use barter_data::books::Level;
+use barter_instrument::exchange::ExchangeId;
+use chrono::{DateTime, Utc};
+use parquet::{
+    arrow::{arrow_reader::ParquetRecordBatchReaderBuilder, ArrowWriter},
+    basic::{Compression as ParquetCompression, GzipLevel, ZstdLevel},
+    errors::ParquetError,
+    file::properties::WriterProperties,
+};
+use rust_decimal::Decimal;
+use smol_str::SmolStr;
+use std::{fs::File, path::Path, sync::Arc};
+
+/// Scale of the decimal `price` and `size` columns.
+pub const DECIMAL_SCALE: i8 = 8;
+
+/// Precision of the decimal `price` and `size` columns.
+const DECIMAL_PRECISION: u8 = 38;
+
+/// Arrow schema of recorder Parquet files.
+pub fn schema() -> SchemaRef {
+    let timestamp = DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into()));
+    let decimal = DataType::Decimal128(DECIMAL_PRECISION, DECIMAL_SCALE);
+
+    Arc::new(Schema::new(vec![
+        Field::new("exchange", DataType::Utf8, false),
+        Field::new("instrument", DataType::Utf8, false),
+        Field::new("event_type", DataType::Utf8, false),
+        Field::new("side", DataType::Utf8, true),
+        Field::new("price", decimal.clone(), true),
+        Field::new("size", decimal, true),
+        Field::new("level", DataType::UInt32, true),
+        Field::new("event_row", DataType::UInt32, false),
+        Field::new("sequence", DataType::UInt64, false),
+        Field::new("time_exchange", timestamp.clone(), false),
+        Field::new("time_received", timestamp, false),
+    ]))
+}
+
+/// Writes orderbook records to a Parquet file, one row group per
+/// `row_group_rows` rows.
+pub(super) struct ParquetSink {
+    writer: ArrowWriter<File>,
+    rows: RowBuffer,
+    row_group_rows: usize,
+}
+
+impl ParquetSink {
+    /// Create a Parquet file at `path`, compressing column chunks as configured.
+    pub(super) fn create(
+        path: &Path,
+        compression: Compression,
+        row_group_rows: usize,
+    ) -> Result<Self, ParquetError> {
+        let row_group_rows = row_group_rows.max(1);
+        let compression = match compression {
+            Compression::None => ParquetCompression::UNCOMPRESSED,
+            Compression::Gzip => ParquetCompression::GZIP(GzipLevel::default()),
+            Compression::Zstd => ParquetCompression::ZSTD(ZstdLevel::default()),
+        };
+        let properties = WriterProperties::builder()
+            .set_compression(compression)
+            .set_max_row_group_row_count(Some(row_group_rows))
+            .build();
+
+        let writer = ArrowWriter::try_new(File::create(path)?, schema(), Some(properties))?;
+
+        Ok(Self {
+            writer,
+            rows: RowBuffer::new(),
+            row_group_rows,
+        })
+    }
+
+    /// Buffer the rows for one record, writing a row group once enough are buffered.
+    pub(super) fn write(&mut self, record: &RecordedEvent) -> Result<(), ParquetError> {
+        let RecordedEvent::OrderBook {
+            exchange,
+            instrument,
+            event_type,
+            sequence,
+            time_exchange,
+            time_received,
+            bids,
+            asks,
+        } = record
+        else {
+            return Ok(());
+        };
+
+        let event = RowEvent {
+            exchange: *exchange,
+            instrument,
+            event_type: *event_type,
+            sequence: *sequence,
+            time_exchange: *time_exchange,
+            time_received: *time_received,
+        };
+
+        let levels = bids
+            .iter()
+            .enumerate()
+            .map(|(index, level)| (Side::Bid, index, level))
+            .chain(
+                asks.iter()
+                    .enumerate()
+                    .map(|(index, level)| (Side::Ask, index, level)),
+            );
+
+        let mut event_row = 0;
+        for (side, index, level) in levels {
+            self.rows
+                .push(&event, event_row, Some((side, index as u32, level)));
+            event_row += 1;
+        }
+        if event_row == 0 {
+            self.rows.push(&event, 0, None);
+        }
+
+        if self.rows.len >= self.row_group_rows {
+            self.flush_row_group()?;
+        }
+
+        Ok(())
+    }
+
+    /// Bytes written to the file so far, plus the uncompressed size of rows
+    /// still buffered for the next row group.
+    pub(super) fn bytes_written(&self) -> u64 {
+        (self.writer.bytes_written() + self.rows.bytes) as u64
+    }
+
+    /// Write buffered rows and the file footer.
+    pub(super) fn close(mut self) -> Result<(), ParquetError> {
+        self.flush_row_group()?;
+        self.writer.close()?;
+        Ok(())
+    }
+
+    fn flush_row_group(&mut self) -> Result<(), ParquetError> {
+        if self.rows.len == 0 {
+            return Ok(());
+        }
+
+        let batch = self.rows.finish()?;
+        self.writer.write(&batch)?;
+        self.writer.flush()
+    }
+}
+
+/// Read orderbook records from a recorder Parquet file.
+///
+/// Rows are regrouped into one record per event, starting a new record at
+/// each `event_row` of zero.
+pub fn read_parquet_recording(path: impl AsRef<Path>) -> Result<Vec<RecordedEvent>, ParquetError> {
+    let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(path)?)?.build()?;
+
+    let mut records = Vec::new();
+    for batch in reader {
+        let batch = batch?;
+        let columns = Columns::new(&batch)?;
+        for row in 0..batch.num_rows() {
+            columns.append_row(row, &mut records)?;
+        }
+    }
+
+    Ok(records)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Side {
+    Bid,
+    Ask,
+}
+
+impl Side {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Side::Bid => "bid",
+            Side::Ask => "ask",
+        }
+    }
+}
+
+fn event_type_str(event_type: BookEventType) -> &'static str {
+    match event_type {
+        BookEventType::Snapshot => "snapshot",
+        BookEventType::Update => "update",
+    }
+}
+
+/// Fields shared by every row of one orderbook event.
+struct RowEvent<'a> {
+    exchange: ExchangeId,
+    instrument: &'a str,
+    event_type: BookEventType,
+    sequence: u64,
+    time_exchange: DateTime<Utc>,
+    time_received: DateTime<Utc>,
+}
+
+/// Column builders for the rows of the next row group.
+struct RowBuffer {
+    exchange: StringBuilder,
+    instrument: StringBuilder,
+    event_type: StringBuilder,
+    side: StringBuilder,
+    price: Decimal128Builder,
+    size: Decimal128Builder,
+    level: UInt32Builder,
+    event_row: UInt32Builder,
+    sequence: UInt64Builder,
+    time_exchange: TimestampMicrosecondBuilder,
+    time_received: TimestampMicrosecondBuilder,
+    len: usize,
+    /// Approximate uncompressed size of the buffered rows.
+    bytes: usize,
+}
+
+impl RowBuffer {
+    fn new() -> Self {
+        Self {
+            exchange: StringBuilder::new(),
+            instrument: StringBuilder::new(),
+            event_type: StringBuilder::new(),
+            side: StringBuilder::new(),
+            price: decimal_builder(),
+            size: decimal_builder(),
+            level: UInt32Builder::new(),
+            event_row: UInt32Builder::new(),
+            sequence: UInt64Builder::new(),
+            time_exchange: TimestampMicrosecondBuilder::new().with_timezone("UTC"),
+            time_received: TimestampMicrosecondBuilder::new().with_timezone("UTC"),
+            len: 0,
+            bytes: 0,
+        }
+    }
+
+    fn push(&mut self, event: &RowEvent<'_>, event_row: u32, level: Option<(Side, u32, &Level)>) {
+        self.exchange.append_value(event.exchange.as_str());
+        self.instrument.append_value(event.instrument);
+        self.event_type
+            .append_value(event_type_str(event.event_type));
+        self.event_row.append_value(event_row);
+        self.sequence.append_value(event.sequence);
+        self.time_exchange
+            .append_value(event.time_exchange.timestamp_micros());
+        self.time_received
+            .append_value(event.time_received.timestamp_micros());
+
+        self.side
+            .append_option(level.map(|(side, _, _)| side.as_str()));
+        self.level.append_option(level.map(|(_, index, _)| index));
+        self.price
+            .append_option(level.map(|(_, _, level)| to_decimal128(level.price)));
+        self.size
+            .append_option(level.map(|(_, _, level)| to_decimal128(level.amount)));
+
+        self.len += 1;
+        self.bytes += event.exchange.as_str().len()
+            + event.instrument.len()
+            + event_type_str(event.event_type).len()
+            + level.map_or(0, |(side, _, _)| side.as_str().len() + 2 * 16 + 4)
+            + 4
+            + 3 * 8;
+    }
+
+    /// Drain the buffered rows into a record batch.
+    fn finish(&mut self) -> Result<RecordBatch, ParquetError> {
+        self.len = 0;
+        self.bytes = 0;
+        let batch = RecordBatch::try_new(
+            schema(),
+            vec![
+                Arc::new(self.exchange.finish()),
+                Arc::new(self.instrument.finish()),
+                Arc::new(self.event_type.finish()),
+                Arc::new(self.side.finish()),
+                Arc::new(self.price.finish()),
+                Arc::new(self.size.finish()),
+                Arc::new(self.level.finish()),
+                Arc::new(self.event_row.finish()),
+                Arc::new(self.sequence.finish()),
+                Arc::new(self.time_exchange.finish()),
+                Arc::new(self.time_received.finish()),
+            ],
+        )?;
+        Ok(batch)
+    }
+}
+
+fn decimal_builder() -> Decimal128Builder {
+    Decimal128Builder::new()
+        .with_precision_and_scale(DECIMAL_PRECISION, DECIMAL_SCALE)
+        .expect("valid decimal precision and scale")
+}
+
+/// Convert to a Decimal128 value at [`DECIMAL_SCALE`], rounding extra places.
+fn to_decimal128(value: Decimal) -> i128 {
+    let mut value = value.round_dp(DECIMAL_SCALE as u32);
+    value.rescale(DECIMAL_SCALE as u32);
+    value.mantissa()
+}
+
+fn from_decimal128(value: i128) -> Decimal {
+    Decimal::from_i128_with_scale(value, DECIMAL_SCALE as u32).normalize()
+}
+
+/// Typed columns of a record batch read back from a recorder Parquet file.
+struct Columns<'a> {
+    exchange: &'a StringArray,
+    instrument: &'a StringArray,
+    event_type: &'a StringArray,
+    side: &'a StringArray,
+    price: &'a Decimal128Array,
+    size: &'a Decimal128Array,
+    event_row: &'a UInt32Array,
+    sequence: &'a UInt64Array,
+    time_exchange: &'a TimestampMicrosecondArray,
+    time_received: &'a TimestampMicrosecondArray,
+}
+
+impl<'a> Columns<'a> {
+    fn new(batch: &'a RecordBatch) -> Result<Self, ParquetError> {
+        Ok(Self {
+            exchange: column(batch, "exchange")?,
+            instrument: column(batch, "instrument")?,
+            event_type: column(batch, "event_type")?,
+            side: column(batch, "side")?,
+            price: column(batch, "price")?,
+            size: column(batch, "size")?,
+            event_row: column(batch, "event_row")?,
+            sequence: column(batch, "sequence")?,
+            time_exchange: column(batch, "time_exchange")?,
+            time_received: column(batch, "time_received")?,
+        })
+    }
+
+    /// Start a record, without levels, for the event `row` belongs to.
+    fn event(&self, row: usize) -> Result<RecordedEvent, ParquetError> {
+        let exchange: ExchangeId = serde_json::from_value(self.exchange.value(row).into())
+            .map_err(|e| ParquetError::General(format!("invalid exchange in recording: {e}")))?;
+        let event_type = match self.event_type.value(row) {
+            "snapshot" => BookEventType::Snapshot,
+            "update" => BookEventType::Update,
+            other => {
+                return Err(ParquetError::General(format!(
+                    "invalid event type in recording: {other}"
+                )));
+            }
+        };
+
+        Ok(RecordedEvent::OrderBook {
+            exchange,
+            instrument: SmolStr::new(self.instrument.value(row)),
+            event_type,
+            sequence: self.sequence.value(row),
+            time_exchange: timestamp(self.time_exchange.value(row))?,
+            time_received: timestamp(self.time_received.value(row))?,
+            bids: Vec::new(),
+            asks: Vec::new(),
+        })
+    }
+
+    /// Add the level in `row` to the last record, or start a new record if the
+    /// row is the first of its event.
+    fn append_row(&self, row: usize, records: &mut Vec<RecordedEvent>) -> Result<(), ParquetError> {
+        if self.event_row.value(row) == 0 || records.is_empty() {
+            records.push(self.event(row)?);
+        }
+
+        if self.side.is_null(row) {
+            return Ok(());
+        }
+
+        let level = Level::new(
+            from_decimal128(self.price.value(row)),
+            from_decimal128(self.size.value(row)),
+        );
+        if let Some(RecordedEvent::OrderBook { bids, asks, .. }) = records.last_mut() {
+            match self.side.value(row) {
+                "bid" => bids.push(level),
+                "ask" => asks.push(level),
+                other => {
+                    return Err(ParquetError::General(format!(
+                        "invalid side in recording: {other}"
+                    )));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn column<'a, T: 'static>(batch: &'a RecordBatch, name: &str) -> Result<&'a T, ParquetError> {
+    batch
+        .column_by_name(name)
+        .and_then(|column| column.as_any().downcast_ref::<T>())
+        .ok_or_else(|| ParquetError::General(format!("missing or invalid column: {name}")))
+}
+
+fn timestamp(micros: i64) -> Result<DateTime<Utc>, ParquetError> {
+    DateTime::from_timestamp_micros(micros)
+        .ok_or_else(|| ParquetError::General(format!("invalid timestamp in recording: {micros}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+    use rust_decimal_macros::dec;
+
+    fn synthetic_records() -> Vec<RecordedEvent> {
+        let time = DateTime::from_timestamp_micros(1_760_000_000_123_456).unwrap();
+
+        (0..20u64)
+            .map(|i| RecordedEvent::OrderBook {
+                exchange: if i % 2 == 0 {
+                    ExchangeId::Kalshi
+                } else {
+                    ExchangeId::Polymarket
+                },
+                instrument: SmolStr::new(format!("MARKET{}", i % 3)),
+                event_type: if i == 0 {
+                    BookEventType::Snapshot
+                } else {
+                    BookEventType::Update
+                },
+                sequence: i,
+                time_exchange: time + chrono::Duration::milliseconds(i as i64),
+                time_received: time + chrono::Duration::milliseconds(i as i64 + 3),
+                bids: (0..10)
+                    .map(|l| Level::new(dec!(0.50) - Decimal::new(l, 2), Decimal::from(10 + l)))
+                    .collect(),
+                asks: (0..i as i64 % 10)
+                    .map(|l| Level::new(dec!(0.51) + Decimal::new(l, 2), dec!(2.5)))
+                    .collect(),
+            })
+            .chain(std::iter::once(RecordedEvent::OrderBook {
+                exchange: ExchangeId::Kalshi,
+                instrument: SmolStr::new("MARKET0"),
+                event_type: BookEventType::Snapshot,
+                sequence: 20,
+                time_exchange: time,
+                time_received: time,
+                bids: vec![],
+                asks: vec![],
+            }))
+            .collect()
+    }
+
+    #[test]
+    fn test_parquet_round_trip() {
+        let dir = std::env::temp_dir().join(format!("barter_test_parquet_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("recording.parquet");
+
+        let records = synthetic_records();
+        let level_rows: usize = records
+            .iter()
+            .map(|record| match record {
+                RecordedEvent::OrderBook { bids, asks, .. } => (bids.len() + asks.len()).max(1),
+                RecordedEvent::Opportunity { .. } | RecordedEvent::Position { .. } => 0,
+            })
+            .sum();
+        assert!(level_rows > 250);
+
+        let mut sink = ParquetSink::create(&path, Compression::Zstd, 64).unwrap();
+        for record in &records {
+            sink.write(record).unwrap();
+        }
+        sink.close().unwrap();
+
+        // Row groups are flushed on the configured row count
+        let metadata = SerializedFileReader::new(File::open(&path).unwrap())
+            .unwrap()
+            .metadata()
+            .clone();
+        assert_eq!(metadata.file_metadata().num_rows() as usize, level_rows);
+        assert!(metadata.num_row_groups() > 1);
+        assert!(metadata
+            .row_groups()
+            .iter()
+            .all(|group| group.num_rows() <= 64));
+
+        // Raw columns via the parquet reader
+        let builder = ParquetRecordBatchReaderBuilder::try_new(File::open(&path).unwrap()).unwrap();
+        assert_eq!(builder.schema().fields(), schema().fields());
+        let batch = builder
+            .with_batch_size(level_rows)
+            .build()
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap();
+        assert_eq!(batch.num_rows(), level_rows);
+
+        let columns = Columns::new(&batch).unwrap();
+        let level: &UInt32Array = column(&batch, "level").unwrap();
+        assert_eq!(columns.exchange.value(0), "kalshi");
+        assert_eq!(columns.instrument.value(0), "MARKET0");
+        assert_eq!(columns.event_type.value(0), "snapshot");
+        assert_eq!(columns.side.value(0), "bid");
+        assert_eq!(from_decimal128(columns.price.value(0)), dec!(0.50));
+        assert_eq!(from_decimal128(columns.size.value(0)), dec!(10));
+        assert_eq!(level.value(9), 9);
+        assert_eq!(from_decimal128(columns.price.value(9)), dec!(0.41));
+        assert_eq!(columns.sequence.value(0), 0);
+        assert_eq!(columns.time_exchange.value(0), 1_760_000_000_123_456);
+        assert_eq!(columns.time_received.value(0), 1_760_000_000_126_456);
+
+        // Empty snapshot is a single row with null level columns
+        let last = level_rows - 1;
+        assert!(columns.side.is_null(last));
+        assert!(columns.price.is_null(last));
+        assert!(level.is_null(last));
+
+        // And regrouped into the original records
+        let read = read_parquet_recording(&path).unwrap();
+        assert_eq!(read.len(), records.len());
+        for (read, original) in read.iter().zip(&records) {
+            match (read, original) {
+                (
+                    RecordedEvent::OrderBook {
+                        exchange,
+                        instrument,
+                        event_type,
+                        time_exchange,
+                        time_received,
+                        ..
+                    },
+                    RecordedEvent::OrderBook {
+                        exchange: original_exchange,
+                        instrument: original_instrument,
+                        event_type: original_event_type,
+                        time_exchange: original_time_exchange,
+                        time_received: original_time_received,
+                        ..
+                    },
+                ) => {
+                    assert_eq!(exchange, original_exchange);
+                    assert_eq!(instrument, original_instrument);
+                    assert_eq!(event_type, original_event_type);
+                    assert_eq!(time_exchange, original_time_exchange);
+                    assert_eq!(time_received, original_time_received);
+                }
+                other => panic!("expected orderbook records, got {other:?}"),
+            }
+            assert_eq!(read.book_event(), original.book_event());
+        }
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_parquet_keeps_identical_events_apart() {
+        let dir = std::env::temp_dir().join(format!("barter_test_parquet2_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("recording.parquet");
+
+        let time = DateTime::from_timestamp_micros(1_760_000_000_123_456).unwrap();
+        let event = |bids: Vec<Level>, asks: Vec<Level>| RecordedEvent::OrderBook {
+            exchange: ExchangeId::Kalshi,
+            instrument: SmolStr::new("MARKET0"),
+            event_type: BookEventType::Update,
+            sequence: 7,
+            time_exchange: time,
+            time_received: time,
+            bids,
+            asks,
+        };
+        let bid = Level::new(dec!(0.50), dec!(10));
+        let ask = Level::new(dec!(0.52), dec!(5));
+
+        // Same event fields throughout, distinguishable only by row order
+        let records = vec![
+            event(vec![bid], vec![ask]),
+            event(vec![bid], vec![ask]),
+            event(vec![bid], vec![]),
+            event(vec![], vec![ask]),
+            event(vec![], vec![]),
+            event(vec![], vec![]),
+        ];
+
+        let mut sink = ParquetSink::create(&path, Compression::None, 64).unwrap();
+        for record in &records {
+            sink.write(record).unwrap();
+        }
+        sink.close().unwrap();
+
+        let read = read_parquet_recording(&path).unwrap();
+        assert_eq!(
+            read.iter()
+                .map(RecordedEvent::book_event)
+                .collect::<Vec<_>>(),
+            records
+                .iter()
+                .map(RecordedEvent::book_event)
+                .collect::<Vec<_>>(),
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_decimal128_conversion() {
+        assert_eq!(from_decimal128(to_decimal128(dec!(0.4567))), dec!(0.4567));
+        assert_eq!(from_decimal128(to_decimal128(dec!(1234.5))), dec!(1234.5));
+        assert_eq!(to_decimal128(dec!(0.123456789)), 12_345_679);
+    }
+}
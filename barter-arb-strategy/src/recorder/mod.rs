@@ -0,0 +1,1445 @@
+//! Orderbook event recorder for debugging and replay.
+//!
+//! Writes every orderbook event (snapshots and updates, from both exchanges)
+//! and every detected opportunity to JSON-lines files, so a session can be
+//! replayed in the order it was observed. Enable via `RECORD_SNAPSHOTS=true`
+//! environment variable.
+//!
+//! Files can be rotated by size or age, compressed once closed and pruned to a
+//! retention count (see [`RecorderConfig`]). With the `parquet` feature,
+//! orderbook events can instead be written as columnar Parquet files
+//! ([`RecorderFormat::Parquet`]).
+//!
+//! In live use, [`OrderbookRecorder::spawn`] moves file I/O onto a writer task
+//! and returns a [`RecorderHandle`] that never blocks the market data stream.
+
+use crate::{opportunity::ArbitrageOpportunity, state::ArbPosition};
+use barter_data::{
+    books::{Level, OrderBook},
+    subscription::book::OrderBookEvent,
+};
+use barter_instrument::exchange::ExchangeId;
+use chrono::{DateTime, Utc};
+use flate2::{read::GzDecoder, write::GzEncoder};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use smol_str::SmolStr;
+use std::{
+    fs::File,
+    future::Future,
+    io::{BufRead, BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+use tokio::{
+    sync::{
+        mpsc::{self, error::TrySendError},
+        Notify,
+    },
+    task::JoinHandle,
+    time::MissedTickBehavior,
+};
+use tracing::{debug, error, info, warn};
+
+#[cfg(feature = "parquet")]
+pub mod columnar;
+
+/// Default capacity of the channel between [`RecorderHandle`]s and the writer task.
+pub const DEFAULT_RECORDER_CAPACITY: usize = 10_000;
+
+/// Default interval between flushes on the writer task.
+pub const DEFAULT_FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Default number of rows per Parquet row group.
+pub const DEFAULT_PARQUET_ROW_GROUP_ROWS: usize = 100_000;
+
+/// Zstd level used for closed recording files (0 selects the library default).
+const ZSTD_LEVEL: i32 = 0;
+
+/// Kind of orderbook event recorded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BookEventType {
+    Snapshot,
+    Update,
+}
+
+/// One line of a recording.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(tag = "record", rename_all = "snake_case")]
+pub enum RecordedEvent {
+    /// Orderbook snapshot or update as received from the exchange.
+    OrderBook {
+        /// Exchange the event came from
+        exchange: ExchangeId,
+        /// Exchange instrument name (Kalshi ticker or Polymarket token id)
+        instrument: SmolStr,
+        /// Snapshot or update
+        event_type: BookEventType,
+        /// Orderbook sequence number
+        sequence: u64,
+        /// Exchange timestamp of the event
+        time_exchange: DateTime<Utc>,
+        /// Local receipt timestamp of the event
+        time_received: DateTime<Utc>,
+        /// Bid levels (full side for snapshots, changed levels for updates)
+        bids: Vec<Level>,
+        /// Ask levels (full side for snapshots, changed levels for updates)
+        asks: Vec<Level>,
+    },
+    /// Opportunity detected by the strategy.
+    Opportunity {
+        /// Time the opportunity was recorded
+        time_detected: DateTime<Utc>,
+        /// Filter that rejected the opportunity, if any
+        rejection: Option<String>,
+        opportunity: Box<ArbitrageOpportunity>,
+    },
+    /// Paired YES/NO position after a change.
+    Position {
+        /// Time the position was recorded
+        time_recorded: DateTime<Utc>,
+        /// Kalshi ticker of the pair
+        kalshi_ticker: SmolStr,
+        /// Contracts held on both legs
+        matched_quantity: Decimal,
+        /// Naked contracts, positive when long the YES leg
+        net_exposure: Decimal,
+        /// Profit locked in by the matched contracts, net of fees
+        locked_profit: Decimal,
+        position: ArbPosition,
+    },
+}
+
+impl RecordedEvent {
+    /// Build an orderbook record from an [`OrderBookEvent`].
+    pub fn order_book(
+        exchange: ExchangeId,
+        instrument: &str,
+        time_exchange: DateTime<Utc>,
+        time_received: DateTime<Utc>,
+        event: &OrderBookEvent,
+    ) -> Self {
+        let (event_type, book) = match event {
+            OrderBookEvent::Snapshot(book) => (BookEventType::Snapshot, book),
+            OrderBookEvent::Update(book) => (BookEventType::Update, book),
+        };
+
+        RecordedEvent::OrderBook {
+            exchange,
+            instrument: SmolStr::new(instrument),
+            event_type,
+            sequence: book.sequence(),
+            time_exchange,
+            time_received,
+            bids: book.bids().levels().to_vec(),
+            asks: book.asks().levels().to_vec(),
+        }
+    }
+
+    /// Build an opportunity record, timestamped now.
+    pub fn opportunity(opportunity: &ArbitrageOpportunity, rejection: Option<&str>) -> Self {
+        RecordedEvent::Opportunity {
+            time_detected: Utc::now(),
+            rejection: rejection.map(str::to_string),
+            opportunity: Box::new(opportunity.clone()),
+        }
+    }
+
+    /// Build a position record, timestamped now.
+    pub fn position(kalshi_ticker: &str, position: &ArbPosition) -> Self {
+        RecordedEvent::Position {
+            time_recorded: Utc::now(),
+            kalshi_ticker: SmolStr::new(kalshi_ticker),
+            matched_quantity: position.matched_quantity(),
+            net_exposure: position.net_exposure(),
+            locked_profit: position.locked_profit(),
+            position: position.clone(),
+        }
+    }
+
+    /// Reconstruct the original [`OrderBookEvent`] for orderbook records.
+    pub fn book_event(&self) -> Option<OrderBookEvent> {
+        match self {
+            RecordedEvent::OrderBook {
+                event_type,
+                sequence,
+                bids,
+                asks,
+                ..
+            } => {
+                let book = OrderBook::new(*sequence, None, bids.clone(), asks.clone());
+                Some(match event_type {
+                    BookEventType::Snapshot => OrderBookEvent::Snapshot(book),
+                    BookEventType::Update => OrderBookEvent::Update(book),
+                })
+            }
+            RecordedEvent::Opportunity { .. } | RecordedEvent::Position { .. } => None,
+        }
+    }
+}
+
+/// Compression applied to recording files once they are closed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Compression {
+    #[default]
+    None,
+    Gzip,
+    Zstd,
+}
+
+impl Compression {
+    /// File extension appended to compressed files.
+    pub fn extension(&self) -> Option<&'static str> {
+        match self {
+            Compression::None => None,
+            Compression::Gzip => Some("gz"),
+            Compression::Zstd => Some("zst"),
+        }
+    }
+
+    /// Compression of a recording file, from its extension.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("gz") => Compression::Gzip,
+            Some("zst") => Compression::Zstd,
+            _ => Compression::None,
+        }
+    }
+}
+
+impl FromStr for Compression {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "" | "none" => Ok(Compression::None),
+            "gzip" | "gz" => Ok(Compression::Gzip),
+            "zstd" | "zst" => Ok(Compression::Zstd),
+            other => Err(format!("unknown recorder compression: {other}")),
+        }
+    }
+}
+
+/// File format of recordings.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecorderFormat {
+    /// One JSON object per line, including opportunities.
+    #[default]
+    Jsonl,
+    /// Columnar Parquet, one row per orderbook level (see [`columnar`]).
+    /// Opportunities are not recorded.
+    #[cfg(feature = "parquet")]
+    Parquet,
+}
+
+impl RecorderFormat {
+    /// File extension of recordings in this format (before any compression suffix).
+    pub fn extension(&self) -> &'static str {
+        match self {
+            RecorderFormat::Jsonl => "jsonl",
+            #[cfg(feature = "parquet")]
+            RecorderFormat::Parquet => "parquet",
+        }
+    }
+}
+
+impl FromStr for RecorderFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "" | "jsonl" | "json" => Ok(RecorderFormat::Jsonl),
+            #[cfg(feature = "parquet")]
+            "parquet" => Ok(RecorderFormat::Parquet),
+            #[cfg(not(feature = "parquet"))]
+            "parquet" => Err("parquet recordings require the `parquet` feature".to_string()),
+            other => Err(format!("unknown recorder format: {other}")),
+        }
+    }
+}
+
+/// Recorder output settings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecorderConfig {
+    /// Directory to write recording files.
+    pub output_dir: PathBuf,
+    /// File format of recordings.
+    pub format: RecorderFormat,
+    /// Rows per Parquet row group; buffered rows are written once this many
+    /// accumulate (Parquet format only).
+    pub parquet_row_group_rows: usize,
+    /// Start a new file once the current one would exceed this many bytes.
+    pub rotate_bytes: Option<u64>,
+    /// Start a new file once the current one has been open this long.
+    pub rotate_interval: Option<Duration>,
+    /// Compression applied to files once they are closed. Parquet files are
+    /// compressed per column chunk instead.
+    pub compression: Compression,
+    /// Maximum number of recording files kept in `output_dir`, including the
+    /// current one. Oldest files are deleted first.
+    pub retention: Option<usize>,
+}
+
+impl RecorderConfig {
+    /// Single uncompressed file in `output_dir`, no rotation or retention.
+    pub fn new(output_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            output_dir: output_dir.into(),
+            format: RecorderFormat::Jsonl,
+            parquet_row_group_rows: DEFAULT_PARQUET_ROW_GROUP_ROWS,
+            rotate_bytes: None,
+            rotate_interval: None,
+            compression: Compression::None,
+            retention: None,
+        }
+    }
+
+    /// Create from environment variables.
+    ///
+    /// Returns `Some` if `RECORD_SNAPSHOTS=true`, using:
+    /// - `SNAPSHOT_DIR` (default: `./snapshots`)
+    /// - `RECORDER_FORMAT`: `jsonl` (default) or `parquet`
+    /// - `RECORDER_PARQUET_ROW_GROUP_ROWS`: rows per Parquet row group
+    /// - `RECORDER_ROTATE_MB`: rotate after this many megabytes
+    /// - `RECORDER_ROTATE_MINUTES`: rotate after this many minutes
+    /// - `RECORDER_COMPRESSION`: `none` (default), `gzip` or `zstd`
+    /// - `RECORDER_RETENTION`: number of recording files to keep
+    pub fn from_env() -> Option<Self> {
+        let enabled = std::env::var("RECORD_SNAPSHOTS")
+            .map(|v| v == "true" || v == "1")
+            .unwrap_or(false);
+
+        if !enabled {
+            return None;
+        }
+
+        let dir = std::env::var("SNAPSHOT_DIR").unwrap_or_else(|_| "./snapshots".to_string());
+
+        let compression = std::env::var("RECORDER_COMPRESSION")
+            .ok()
+            .and_then(|v| {
+                v.parse()
+                    .inspect_err(|e| warn!(%e, "Ignoring RECORDER_COMPRESSION"))
+                    .ok()
+            })
+            .unwrap_or_default();
+
+        let format = std::env::var("RECORDER_FORMAT")
+            .ok()
+            .and_then(|v| {
+                v.parse()
+                    .inspect_err(|e| warn!(%e, "Ignoring RECORDER_FORMAT"))
+                    .ok()
+            })
+            .unwrap_or_default();
+
+        Some(Self {
+            output_dir: dir.into(),
+            format,
+            parquet_row_group_rows: env_parse("RECORDER_PARQUET_ROW_GROUP_ROWS")
+                .unwrap_or(DEFAULT_PARQUET_ROW_GROUP_ROWS),
+            rotate_bytes: env_parse::<u64>("RECORDER_ROTATE_MB").map(|mb| mb * 1024 * 1024),
+            rotate_interval: env_parse::<u64>("RECORDER_ROTATE_MINUTES")
+                .map(|minutes| Duration::from_secs(minutes * 60)),
+            compression,
+            retention: env_parse("RECORDER_RETENTION"),
+        })
+    }
+}
+
+/// Parse a non-zero numeric environment variable, warning on invalid values.
+fn env_parse<T>(key: &str) -> Option<T>
+where
+    T: FromStr + PartialEq + Default,
+{
+    let value = std::env::var(key).ok()?;
+    match value.parse::<T>() {
+        Ok(parsed) if parsed != T::default() => Some(parsed),
+        Ok(_) => None,
+        Err(_) => {
+            warn!(key, value, "Ignoring invalid recorder setting");
+            None
+        }
+    }
+}
+
+/// Open recording file.
+enum RecordWriter {
+    Jsonl(BufWriter<File>),
+    #[cfg(feature = "parquet")]
+    Parquet(Box<columnar::ParquetSink>),
+}
+
+/// Records orderbook events and opportunities to JSON-lines (or Parquet) files.
+///
+/// Files are named `recording_<UTC timestamp>_<index>.<format>` and rotated,
+/// compressed and pruned according to the [`RecorderConfig`]. Pruning runs
+/// inline; within a tokio runtime, closed files are compressed on the blocking
+/// pool so the writer task keeps draining records meanwhile.
+pub struct OrderbookRecorder {
+    config: RecorderConfig,
+    /// Path of the current recording file.
+    path: PathBuf,
+    /// Open recording file (None if it could not be created, or once closed).
+    writer: Option<RecordWriter>,
+    /// Bytes written to the current file.
+    file_bytes: u64,
+    /// Records written to the current file.
+    file_records: u64,
+    /// When the current file was opened.
+    opened_at: Instant,
+    /// Files opened by this recorder.
+    file_count: u64,
+    /// Total records written (for logging).
+    total_written: u64,
+    /// Closed files still being compressed on the blocking pool.
+    compressing: Vec<(PathBuf, JoinHandle<std::io::Result<PathBuf>>)>,
+}
+
+impl OrderbookRecorder {
+    /// Create a new recorder writing a single file in `output_dir`.
+    pub fn new(output_dir: impl Into<PathBuf>) -> Self {
+        Self::with_config(RecorderConfig::new(output_dir))
+    }
+
+    /// Create a new recorder with rotation, compression and retention settings.
+    pub fn with_config(config: RecorderConfig) -> Self {
+        let mut recorder = Self {
+            config,
+            path: PathBuf::new(),
+            writer: None,
+            file_bytes: 0,
+            file_records: 0,
+            opened_at: Instant::now(),
+            file_count: 0,
+            total_written: 0,
+            compressing: Vec::new(),
+        };
+        recorder.open();
+        info!(config = ?recorder.config, "OrderbookRecorder initialized");
+        recorder
+    }
+
+    /// Create from environment variables (see [`RecorderConfig::from_env`]).
+    pub fn from_env() -> Option<Self> {
+        RecorderConfig::from_env().map(Self::with_config)
+    }
+
+    /// Called on every orderbook event. Records the event with its exchange,
+    /// instrument and timestamps.
+    pub fn on_orderbook_update(
+        &mut self,
+        exchange: ExchangeId,
+        instrument: &str,
+        time_exchange: DateTime<Utc>,
+        time_received: DateTime<Utc>,
+        event: &OrderBookEvent,
+    ) {
+        self.record(&RecordedEvent::order_book(
+            exchange,
+            instrument,
+            time_exchange,
+            time_received,
+            event,
+        ));
+    }
+
+    /// Record a detected opportunity in the same timeline as the orderbook events.
+    pub fn record_opportunity(
+        &mut self,
+        opportunity: &ArbitrageOpportunity,
+        rejection: Option<&str>,
+    ) {
+        self.record(&RecordedEvent::opportunity(opportunity, rejection));
+    }
+
+    /// Append one record (buffered; see [`Self::flush`]).
+    ///
+    /// Rotates first if the record would take the current file past
+    /// [`RecorderConfig::rotate_bytes`], or the file is older than
+    /// [`RecorderConfig::rotate_interval`].
+    pub fn record(&mut self, record: &RecordedEvent) {
+        match self.writer {
+            None => {}
+            Some(RecordWriter::Jsonl(_)) => self.record_jsonl(record),
+            #[cfg(feature = "parquet")]
+            Some(RecordWriter::Parquet(_)) => self.record_parquet(record),
+        }
+    }
+
+    fn record_jsonl(&mut self, record: &RecordedEvent) {
+        let mut line = match serde_json::to_vec(record) {
+            Ok(line) => line,
+            Err(e) => {
+                error!(%e, "Failed to serialize record");
+                return;
+            }
+        };
+        line.push(b'\n');
+
+        if self.should_rotate(line.len() as u64) {
+            self.rotate();
+        }
+
+        let Some(RecordWriter::Jsonl(writer)) = self.writer.as_mut() else {
+            return;
+        };
+
+        match writer.write_all(&line) {
+            Ok(()) => {
+                self.file_bytes += line.len() as u64;
+                self.file_records += 1;
+                self.total_written += 1;
+            }
+            Err(e) => error!(path = ?self.path, %e, "Failed to write record"),
+        }
+    }
+
+    /// Parquet files only hold orderbook events. Rows buffered for the next row
+    /// group count towards [`RecorderConfig::rotate_bytes`] at their
+    /// uncompressed size.
+    #[cfg(feature = "parquet")]
+    fn record_parquet(&mut self, record: &RecordedEvent) {
+        if !matches!(record, RecordedEvent::OrderBook { .. }) {
+            return;
+        }
+
+        if self.should_rotate(0) {
+            self.rotate();
+        }
+
+        let Some(RecordWriter::Parquet(sink)) = self.writer.as_mut() else {
+            return;
+        };
+
+        match sink.write(record) {
+            Ok(()) => {
+                self.file_bytes = sink.bytes_written();
+                self.file_records += 1;
+                self.total_written += 1;
+            }
+            Err(e) => error!(path = ?self.path, %e, "Failed to write record"),
+        }
+    }
+
+    /// Flush buffered records to disk, rotating the current file if it has
+    /// been open longer than [`RecorderConfig::rotate_interval`].
+    ///
+    /// Parquet rows are only written in full row groups (or on close).
+    pub fn flush(&mut self) {
+        if self.writer.is_some() && self.should_rotate(0) {
+            self.rotate();
+        }
+
+        if let Some(RecordWriter::Jsonl(writer)) = self.writer.as_mut() {
+            if let Err(e) = writer.flush() {
+                error!(path = ?self.path, %e, "Failed to flush recording");
+            }
+        }
+    }
+
+    /// Flush and close the current file, compressing it if configured.
+    ///
+    /// Further records are ignored. Outside a tokio runtime [`Self::path`] then
+    /// points at the final (possibly compressed) file; within one, compression
+    /// completes in the background and [`Self::run_until`] waits for it.
+    pub fn close(&mut self) {
+        match self.writer.take() {
+            None => {}
+            Some(RecordWriter::Jsonl(mut writer)) => {
+                if let Err(e) = writer.flush() {
+                    error!(path = ?self.path, %e, "Failed to flush recording");
+                }
+                drop(writer);
+
+                if self.config.compression != Compression::None {
+                    self.compress();
+                }
+            }
+            #[cfg(feature = "parquet")]
+            Some(RecordWriter::Parquet(sink)) => {
+                if let Err(e) = sink.close() {
+                    error!(path = ?self.path, %e, "Failed to close recording");
+                }
+            }
+        }
+    }
+
+    /// Whether the current (non-empty) file should be closed before writing
+    /// `next_bytes` more.
+    fn should_rotate(&self, next_bytes: u64) -> bool {
+        if self.file_records == 0 {
+            return false;
+        }
+
+        let size_exceeded = self
+            .config
+            .rotate_bytes
+            .is_some_and(|max| self.file_bytes + next_bytes > max);
+        let age_exceeded = self
+            .config
+            .rotate_interval
+            .is_some_and(|max| self.opened_at.elapsed() >= max);
+
+        size_exceeded || age_exceeded
+    }
+
+    /// Compress the just-closed current file, on the blocking pool if running
+    /// within a tokio runtime.
+    fn compress(&mut self) {
+        let path = self.path.clone();
+        let compression = self.config.compression;
+
+        match tokio::runtime::Handle::try_current() {
+            Ok(runtime) => {
+                self.compressing.retain(|(_, task)| !task.is_finished());
+                let task = runtime.spawn_blocking({
+                    let path = path.clone();
+                    move || compress_file(&path, compression)
+                });
+                self.compressing.push((path, task));
+            }
+            Err(_) => match compress_file(&path, compression) {
+                Ok(path) => self.path = path,
+                Err(e) => error!(?path, %e, "Failed to compress recording"),
+            },
+        }
+    }
+
+    /// Wait for background compressions, pointing [`Self::path`] at the
+    /// compressed file if the last closed file was among them.
+    async fn finish_compression(&mut self) {
+        for (path, task) in std::mem::take(&mut self.compressing) {
+            match task.await {
+                Ok(Ok(target)) if path == self.path => self.path = target,
+                Ok(Ok(_)) => {}
+                Ok(Err(e)) => error!(?path, %e, "Failed to compress recording"),
+                Err(e) => error!(?path, %e, "Compression task failed"),
+            }
+        }
+    }
+
+    /// Close the current file and start a new one, pruning old files.
+    fn rotate(&mut self) {
+        let closed = self.path.clone();
+        self.close();
+        self.open();
+        debug!(?closed, path = ?self.path, "Rotated recording file");
+        self.enforce_retention();
+    }
+
+    /// Open a new timestamped recording file.
+    fn open(&mut self) {
+        self.file_count += 1;
+        let filename = format!(
+            "recording_{}_{:04}.{}",
+            Utc::now().format("%Y%m%dT%H%M%S%.3fZ"),
+            self.file_count,
+            self.config.format.extension()
+        );
+        self.path = self.config.output_dir.join(filename);
+        self.file_bytes = 0;
+        self.file_records = 0;
+        self.opened_at = Instant::now();
+
+        let writer =
+            std::fs::create_dir_all(&self.config.output_dir).and_then(|()| self.create_writer());
+
+        self.writer = match writer {
+            Ok(writer) => Some(writer),
+            Err(e) => {
+                error!(path = ?self.path, %e, "Failed to create recording file");
+                None
+            }
+        };
+    }
+
+    fn create_writer(&self) -> std::io::Result<RecordWriter> {
+        match self.config.format {
+            RecorderFormat::Jsonl => {
+                File::create(&self.path).map(|file| RecordWriter::Jsonl(BufWriter::new(file)))
+            }
+            #[cfg(feature = "parquet")]
+            RecorderFormat::Parquet => columnar::ParquetSink::create(
+                &self.path,
+                self.config.compression,
+                self.config.parquet_row_group_rows,
+            )
+            .map(|sink| RecordWriter::Parquet(Box::new(sink)))
+            .map_err(std::io::Error::other),
+        }
+    }
+
+    /// Delete the oldest recording files beyond [`RecorderConfig::retention`].
+    fn enforce_retention(&self) {
+        let Some(retention) = self.config.retention else {
+            return;
+        };
+
+        let mut files = match recording_files(&self.config.output_dir) {
+            Ok(files) => files,
+            Err(e) => {
+                error!(dir = ?self.config.output_dir, %e, "Failed to list recordings");
+                return;
+            }
+        };
+        files.retain(|file| *file != self.path);
+
+        // Files still being compressed count towards retention, but are pruned
+        // by a later rotation once their archive exists
+        let excess = (files.len() + 1).saturating_sub(retention.max(1));
+        for file in files.into_iter().take(excess) {
+            if self
+                .compressing
+                .iter()
+                .any(|(path, task)| *path == file && !task.is_finished())
+            {
+                continue;
+            }
+
+            match std::fs::remove_file(&file) {
+                Ok(()) => debug!(?file, "Deleted old recording"),
+                Err(e) => warn!(?file, %e, "Failed to delete old recording"),
+            }
+        }
+    }
+
+    /// Write records from `rx` until all senders are dropped, then close the
+    /// current file.
+    pub async fn run(
+        self,
+        rx: mpsc::Receiver<RecordedEvent>,
+        flush_interval: Duration,
+    ) -> OrderbookRecorder {
+        self.run_until(rx, flush_interval, std::future::pending()).await
+    }
+
+    /// Write records from `rx` until all senders are dropped or `stop`
+    /// completes, then close the current file.
+    ///
+    /// Records already queued when `stop` completes are still written; any
+    /// sent afterwards are dropped.
+    pub async fn run_until(
+        mut self,
+        mut rx: mpsc::Receiver<RecordedEvent>,
+        flush_interval: Duration,
+        stop: impl Future<Output = ()>,
+    ) -> OrderbookRecorder {
+        let mut flush = tokio::time::interval(flush_interval);
+        flush.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        tokio::pin!(stop);
+
+        loop {
+            tokio::select! {
+                record = rx.recv() => match record {
+                    Some(record) => self.record(&record),
+                    None => break,
+                },
+                _ = flush.tick() => self.flush(),
+                _ = &mut stop => {
+                    rx.close();
+                    while let Some(record) = rx.recv().await {
+                        self.record(&record);
+                    }
+                    break;
+                }
+            }
+        }
+
+        self.close();
+        self.finish_compression().await;
+        debug!(path = ?self.path, total = self.total_written, "Recorder writer stopped");
+        self
+    }
+
+    /// Move the recorder onto a spawned writer task, returning a non-blocking
+    /// [`RecorderHandle`] and the task's join handle.
+    ///
+    /// The task flushes every `flush_interval` and closes the current file once
+    /// all handles are dropped or [`RecorderHandle::close`] is called, then
+    /// returns the recorder. Must be called within a tokio runtime.
+    pub fn spawn(
+        self,
+        capacity: usize,
+        flush_interval: Duration,
+    ) -> (RecorderHandle, JoinHandle<OrderbookRecorder>) {
+        let (handle, rx) = RecorderHandle::channel(capacity);
+        let stop = Arc::clone(&handle.stop);
+        let task = tokio::spawn(self.run_until(rx, flush_interval, async move {
+            stop.notified().await
+        }));
+        (handle, task)
+    }
+
+    /// Get the recorder settings.
+    pub fn config(&self) -> &RecorderConfig {
+        &self.config
+    }
+
+    /// Get the output directory.
+    pub fn output_dir(&self) -> &Path {
+        &self.config.output_dir
+    }
+
+    /// Get the path of the current recording file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Get total records written.
+    pub fn total_written(&self) -> u64 {
+        self.total_written
+    }
+}
+
+/// Compress `path` into `<path>.<ext>` and delete the original.
+///
+/// The archive is written to `<path>.<ext>.tmp` and renamed into place, so a crash
+/// mid-compression never leaves a truncated archive among the recording files.
+fn compress_file(path: &Path, compression: Compression) -> std::io::Result<PathBuf> {
+    let Some(extension) = compression.extension() else {
+        return Ok(path.to_path_buf());
+    };
+
+    let mut target = path.as_os_str().to_owned();
+    target.push(".");
+    target.push(extension);
+    let target = PathBuf::from(target);
+    let mut partial = target.as_os_str().to_owned();
+    partial.push(".tmp");
+    let partial = PathBuf::from(partial);
+
+    let mut input = File::open(path)?;
+    let output = BufWriter::new(File::create(&partial)?);
+
+    match compression {
+        Compression::None => unreachable!("no extension for uncompressed files"),
+        Compression::Gzip => {
+            let mut encoder = GzEncoder::new(output, flate2::Compression::default());
+            std::io::copy(&mut input, &mut encoder)?;
+            encoder.finish()?.flush()?;
+        }
+        Compression::Zstd => {
+            let mut encoder = zstd::Encoder::new(output, ZSTD_LEVEL)?;
+            std::io::copy(&mut input, &mut encoder)?;
+            encoder.finish()?.flush()?;
+        }
+    }
+
+    std::fs::rename(&partial, &target)?;
+    std::fs::remove_file(path)?;
+    Ok(target)
+}
+
+/// List recording files in `dir`, oldest first.
+pub fn recording_files(dir: impl AsRef<Path>) -> std::io::Result<Vec<PathBuf>> {
+    let mut files = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| {
+                    name.starts_with("recording_")
+                        && [".jsonl", ".jsonl.gz", ".jsonl.zst", ".parquet"]
+                            .iter()
+                            .any(|suffix| name.ends_with(suffix))
+                })
+        })
+        .collect::<Vec<_>>();
+
+    // Names start with a UTC timestamp, so lexical order is chronological
+    files.sort();
+    Ok(files)
+}
+
+/// Cheap, cloneable handle for submitting records to a spawned recorder.
+///
+/// Never blocks: if the writer task falls behind and the channel is full, the
+/// record is dropped and counted (see [`RecorderHandle::dropped`]).
+#[derive(Debug, Clone)]
+pub struct RecorderHandle {
+    tx: mpsc::Sender<RecordedEvent>,
+    dropped: Arc<AtomicU64>,
+    stop: Arc<Notify>,
+}
+
+impl RecorderHandle {
+    /// Create a handle and the receiver a writer task consumes.
+    pub fn channel(capacity: usize) -> (Self, mpsc::Receiver<RecordedEvent>) {
+        let (tx, rx) = mpsc::channel(capacity);
+        let handle = Self {
+            tx,
+            dropped: Arc::new(AtomicU64::new(0)),
+            stop: Arc::new(Notify::new()),
+        };
+        (handle, rx)
+    }
+
+    /// Submit an orderbook event.
+    pub fn on_orderbook_update(
+        &self,
+        exchange: ExchangeId,
+        instrument: &str,
+        time_exchange: DateTime<Utc>,
+        time_received: DateTime<Utc>,
+        event: &OrderBookEvent,
+    ) {
+        self.send(RecordedEvent::order_book(
+            exchange,
+            instrument,
+            time_exchange,
+            time_received,
+            event,
+        ));
+    }
+
+    /// Submit a detected opportunity.
+    pub fn record_opportunity(&self, opportunity: &ArbitrageOpportunity, rejection: Option<&str>) {
+        self.send(RecordedEvent::opportunity(opportunity, rejection));
+    }
+
+    /// Submit a paired position.
+    pub fn record_position(&self, kalshi_ticker: &str, position: &ArbPosition) {
+        self.send(RecordedEvent::position(kalshi_ticker, position));
+    }
+
+    /// Submit a record, returning false if it was dropped.
+    pub fn send(&self, record: RecordedEvent) -> bool {
+        match self.tx.try_send(record) {
+            Ok(()) => true,
+            Err(TrySendError::Full(_)) => {
+                let dropped = self.dropped.fetch_add(1, Ordering::Relaxed) + 1;
+                if dropped.is_power_of_two() {
+                    warn!(dropped, "Recorder channel full, dropping records");
+                }
+                false
+            }
+            Err(TrySendError::Closed(_)) => {
+                self.dropped.fetch_add(1, Ordering::Relaxed);
+                false
+            }
+        }
+    }
+
+    /// Ask a writer task started by [`OrderbookRecorder::spawn`] to write the
+    /// records already queued, close its file and exit, even while other
+    /// handles are still alive.
+    pub fn close(&self) {
+        self.stop.notify_one();
+    }
+
+    /// Total records dropped because the writer task was not keeping up.
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+/// Read all records from a recording file, in the order they were written.
+///
+/// Gzip and zstd files are decompressed based on their extension. With the
+/// `parquet` feature, `.parquet` files are read via
+/// [`columnar::read_parquet_recording`].
+pub fn read_recording(path: impl AsRef<Path>) -> std::io::Result<Vec<RecordedEvent>> {
+    let path = path.as_ref();
+
+    #[cfg(feature = "parquet")]
+    if path.extension().is_some_and(|ext| ext == "parquet") {
+        return columnar::read_parquet_recording(path).map_err(std::io::Error::other);
+    }
+
+    let file = File::open(path)?;
+    let reader: Box<dyn BufRead> = match Compression::from_path(path) {
+        Compression::None => Box::new(BufReader::new(file)),
+        Compression::Gzip => Box::new(BufReader::new(GzDecoder::new(file))),
+        Compression::Zstd => Box::new(BufReader::new(zstd::Decoder::new(file)?)),
+    };
+
+    reader
+        .lines()
+        .filter(|line| !matches!(line, Ok(line) if line.trim().is_empty()))
+        .map(|line| Ok(serde_json::from_str(&line?)?))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        correlation::{CorrelatedPair, Outcome},
+        opportunity::{ArbitrageDirection, OrderSide},
+    };
+    use rust_decimal_macros::dec;
+    use std::fs;
+
+    fn test_opportunity() -> ArbitrageOpportunity {
+        ArbitrageOpportunity {
+            pair: CorrelatedPair::new(
+                "KXTEST",
+                "0xcondition",
+                "0xyes",
+                "0xno",
+                "Test market",
+                Utc::now() + chrono::Duration::days(30),
+                false,
+            ),
+            direction: ArbitrageDirection::YesPolyNoKalshi,
+            yes_side: OrderSide::poly("0xyes", Outcome::Yes, dec!(0.40), 10),
+            no_side: OrderSide::kalshi("KXTEST", Outcome::No, dec!(0.45), 10),
+            total_cost: dec!(0.87),
+            avg_yes_price: dec!(0.40),
+            avg_no_price: dec!(0.45),
+            max_contracts: 10,
+            expected_profit: dec!(1.30),
+            total_fees: dec!(0.20),
+        }
+    }
+
+    #[test]
+    fn test_recorder_writes_ordered_timeline() {
+        let dir =
+            std::env::temp_dir().join(format!("barter_test_recording_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+
+        let mut recorder = OrderbookRecorder::new(&dir);
+        let snapshot = OrderBook::new(
+            1,
+            None,
+            vec![Level::new(dec!(0.45), dec!(100))],
+            vec![Level::new(dec!(0.46), dec!(50))],
+        );
+        let update = OrderBook::new(2, None, vec![Level::new(dec!(0.45), dec!(80))], vec![]);
+        let time_exchange = Utc::now();
+        let time_received = time_exchange + chrono::Duration::milliseconds(5);
+
+        recorder.on_orderbook_update(
+            ExchangeId::Polymarket,
+            "0xyes",
+            time_exchange,
+            time_received,
+            &OrderBookEvent::Snapshot(snapshot.clone()),
+        );
+        recorder.on_orderbook_update(
+            ExchangeId::Kalshi,
+            "KXTEST",
+            time_exchange,
+            time_received,
+            &OrderBookEvent::Update(update.clone()),
+        );
+        recorder.record_opportunity(&test_opportunity(), Some("below_threshold"));
+        assert_eq!(recorder.total_written(), 3);
+
+        recorder.flush();
+        let records = read_recording(recorder.path()).unwrap();
+        assert_eq!(records.len(), 3);
+
+        match &records[0] {
+            RecordedEvent::OrderBook {
+                exchange,
+                instrument,
+                event_type,
+                sequence,
+                time_exchange: recorded_exchange,
+                time_received: recorded_received,
+                ..
+            } => {
+                assert_eq!(*exchange, ExchangeId::Polymarket);
+                assert_eq!(instrument, "0xyes");
+                assert_eq!(*event_type, BookEventType::Snapshot);
+                assert_eq!(*sequence, 1);
+                assert_eq!(*recorded_exchange, time_exchange);
+                assert_eq!(*recorded_received, time_received);
+            }
+            other => panic!("expected orderbook record, got {other:?}"),
+        }
+        assert_eq!(
+            records[0].book_event(),
+            Some(OrderBookEvent::Snapshot(snapshot))
+        );
+
+        match &records[1] {
+            RecordedEvent::OrderBook {
+                exchange,
+                event_type,
+                sequence,
+                ..
+            } => {
+                assert_eq!(*exchange, ExchangeId::Kalshi);
+                assert_eq!(*event_type, BookEventType::Update);
+                assert_eq!(*sequence, 2);
+            }
+            other => panic!("expected orderbook record, got {other:?}"),
+        }
+        assert_eq!(
+            records[1].book_event(),
+            Some(OrderBookEvent::Update(update))
+        );
+
+        match &records[2] {
+            RecordedEvent::Opportunity {
+                rejection,
+                opportunity,
+                ..
+            } => {
+                assert_eq!(rejection.as_deref(), Some("below_threshold"));
+                assert_eq!(opportunity.pair.kalshi_ticker, "KXTEST");
+                assert_eq!(opportunity.max_contracts, 10);
+            }
+            other => panic!("expected opportunity record, got {other:?}"),
+        }
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_recorder_one_line_per_event() {
+        let dir =
+            std::env::temp_dir().join(format!("barter_test_recording2_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+
+        let mut recorder = OrderbookRecorder::new(&dir);
+        let book = OrderBook::new(1, None, vec![Level::new(dec!(0.50), dec!(10))], vec![]);
+
+        for _ in 0..5 {
+            recorder.on_orderbook_update(
+                ExchangeId::Kalshi,
+                "KXTEST",
+                Utc::now(),
+                Utc::now(),
+                &OrderBookEvent::Update(book.clone()),
+            );
+        }
+
+        recorder.flush();
+        let content = fs::read_to_string(recorder.path()).unwrap();
+        assert_eq!(content.lines().count(), 5);
+        for line in content.lines() {
+            let value: serde_json::Value = serde_json::from_str(line).unwrap();
+            assert_eq!(value["record"], "order_book");
+            assert_eq!(value["event_type"], "update");
+            assert_eq!(value["exchange"], "kalshi");
+        }
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_recorder_handle_writes_all_events() {
+        let dir =
+            std::env::temp_dir().join(format!("barter_test_recording3_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+
+        let (handle, task) =
+            OrderbookRecorder::new(&dir).spawn(DEFAULT_RECORDER_CAPACITY, DEFAULT_FLUSH_INTERVAL);
+        let book = OrderBook::new(1, None, vec![Level::new(dec!(0.50), dec!(10))], vec![]);
+
+        // Producers on other tasks, as with the market stream and opportunity sink
+        let producers: Vec<_> = (0..4)
+            .map(|i| {
+                let handle = handle.clone();
+                let book = book.clone();
+                tokio::spawn(async move {
+                    for _ in 0..250 {
+                        handle.on_orderbook_update(
+                            ExchangeId::Kalshi,
+                            &format!("KXTEST{i}"),
+                            Utc::now(),
+                            Utc::now(),
+                            &OrderBookEvent::Update(book.clone()),
+                        );
+                        tokio::task::yield_now().await;
+                    }
+                })
+            })
+            .collect();
+        for producer in producers {
+            producer.await.unwrap();
+        }
+        handle.record_opportunity(&test_opportunity(), None);
+
+        assert_eq!(handle.dropped(), 0);
+        drop(handle);
+
+        // Writer flushes and returns once all handles are dropped
+        let recorder = task.await.unwrap();
+        assert_eq!(recorder.total_written(), 1001);
+
+        let records = read_recording(recorder.path()).unwrap();
+        assert_eq!(records.len(), 1001);
+        assert!(matches!(records[1000], RecordedEvent::Opportunity { .. }));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    fn write_books(recorder: &mut OrderbookRecorder, count: u64) {
+        for sequence in 0..count {
+            let book = OrderBook::new(
+                sequence,
+                None,
+                vec![Level::new(dec!(0.50), dec!(10))],
+                vec![Level::new(dec!(0.52), dec!(10))],
+            );
+            recorder.on_orderbook_update(
+                ExchangeId::Kalshi,
+                "KXTEST",
+                Utc::now(),
+                Utc::now(),
+                &OrderBookEvent::Snapshot(book),
+            );
+        }
+    }
+
+    fn sequences(files: &[PathBuf]) -> Vec<u64> {
+        files
+            .iter()
+            .flat_map(|file| read_recording(file).unwrap())
+            .map(|record| match record {
+                RecordedEvent::OrderBook { sequence, .. } => sequence,
+                other => panic!("expected orderbook record, got {other:?}"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_recorder_rotates_by_size_with_gzip() {
+        let dir =
+            std::env::temp_dir().join(format!("barter_test_recording4_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+
+        let mut recorder = OrderbookRecorder::with_config(RecorderConfig {
+            rotate_bytes: Some(1024),
+            compression: Compression::Gzip,
+            ..RecorderConfig::new(&dir)
+        });
+        write_books(&mut recorder, 50);
+        recorder.close();
+
+        let files = recording_files(&dir).unwrap();
+        assert!(files.len() > 2, "expected several rotations: {files:?}");
+        assert_eq!(recorder.path(), files.last().unwrap());
+        // No temporary or uncompressed files are left behind
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), files.len());
+
+        for file in &files {
+            let name = file.file_name().unwrap().to_str().unwrap();
+            assert!(name.starts_with("recording_"), "{name}");
+            assert!(name.ends_with(".jsonl.gz"), "{name}");
+            // recording_YYYYMMDDTHHMMSS.mmmZ_NNNN.jsonl.gz
+            let timestamp = &name["recording_".len()..name.len() - "_0000.jsonl.gz".len()];
+            assert!(
+                chrono::NaiveDateTime::parse_from_str(timestamp, "%Y%m%dT%H%M%S%.3fZ").is_ok(),
+                "{name}"
+            );
+        }
+        let names: Vec<_> = files.iter().map(|f| f.file_name().unwrap()).collect();
+        assert!(names.windows(2).all(|pair| pair[0] < pair[1]));
+
+        // Closed files decompress to valid JSONL, together holding every record in order
+        assert_eq!(sequences(&files), (0..50).collect::<Vec<_>>());
+        assert_eq!(recorder.total_written(), 50);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_spawned_recorder_compresses_in_background() {
+        let dir =
+            std::env::temp_dir().join(format!("barter_test_recording8_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+
+        let recorder = OrderbookRecorder::with_config(RecorderConfig {
+            rotate_bytes: Some(1024),
+            compression: Compression::Gzip,
+            ..RecorderConfig::new(&dir)
+        });
+        let (handle, task) = recorder.spawn(DEFAULT_RECORDER_CAPACITY, DEFAULT_FLUSH_INTERVAL);
+        let book = OrderBook::new(1, None, vec![Level::new(dec!(0.50), dec!(10))], vec![]);
+        for _ in 0..50 {
+            handle.on_orderbook_update(
+                ExchangeId::Kalshi,
+                "KXTEST",
+                Utc::now(),
+                Utc::now(),
+                &OrderBookEvent::Snapshot(book.clone()),
+            );
+        }
+        drop(handle);
+
+        // The writer waits for every background compression before returning
+        let recorder = task.await.unwrap();
+        let files = recording_files(&dir).unwrap();
+        assert!(files.len() > 2, "expected several rotations: {files:?}");
+        assert_eq!(recorder.path(), files.last().unwrap());
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), files.len());
+        assert!(files
+            .iter()
+            .all(|f| Compression::from_path(f) == Compression::Gzip));
+        assert_eq!(sequences(&files).len(), 50);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_recorder_retention_with_zstd() {
+        let dir =
+            std::env::temp_dir().join(format!("barter_test_recording5_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+
+        let mut recorder = OrderbookRecorder::with_config(RecorderConfig {
+            rotate_bytes: Some(512),
+            compression: Compression::Zstd,
+            retention: Some(3),
+            ..RecorderConfig::new(&dir)
+        });
+        write_books(&mut recorder, 50);
+
+        // Two closed (compressed) files plus the open one
+        let files = recording_files(&dir).unwrap();
+        assert_eq!(files.len(), 3);
+        assert_eq!(files[2], recorder.path());
+        assert!(files[..2]
+            .iter()
+            .all(|f| Compression::from_path(f) == Compression::Zstd));
+
+        recorder.close();
+        let files = recording_files(&dir).unwrap();
+        assert_eq!(files.len(), 3);
+
+        // Only the newest records survive, still contiguous and in order
+        let sequences = sequences(&files);
+        assert_eq!(*sequences.last().unwrap(), 49);
+        assert!(sequences.windows(2).all(|pair| pair[1] == pair[0] + 1));
+        assert!(sequences.len() < 50);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_recorder_rotates_by_time() {
+        let dir =
+            std::env::temp_dir().join(format!("barter_test_recording6_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+
+        let mut recorder = OrderbookRecorder::with_config(RecorderConfig {
+            rotate_interval: Some(Duration::from_millis(20)),
+            ..RecorderConfig::new(&dir)
+        });
+        write_books(&mut recorder, 3);
+        std::thread::sleep(Duration::from_millis(30));
+
+        // Flushing an expired file rotates it even without new records
+        recorder.flush();
+        write_books(&mut recorder, 2);
+        recorder.close();
+
+        let files = recording_files(&dir).unwrap();
+        assert_eq!(files.len(), 2);
+        assert_eq!(read_recording(&files[0]).unwrap().len(), 3);
+        assert_eq!(read_recording(&files[1]).unwrap().len(), 2);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(feature = "parquet")]
+    #[test]
+    fn test_recorder_parquet_format() {
+        let dir =
+            std::env::temp_dir().join(format!("barter_test_recording7_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+
+        let mut recorder = OrderbookRecorder::with_config(RecorderConfig {
+            format: RecorderFormat::Parquet,
+            parquet_row_group_rows: 16,
+            ..RecorderConfig::new(&dir)
+        });
+        write_books(&mut recorder, 30);
+        recorder.record_opportunity(&test_opportunity(), None);
+        recorder.close();
+
+        let files = recording_files(&dir).unwrap();
+        assert_eq!(files.len(), 1);
+        assert!(files[0].to_str().unwrap().ends_with(".parquet"));
+
+        // Opportunities are not part of the columnar output
+        assert_eq!(recorder.total_written(), 30);
+        assert_eq!(sequences(&files), (0..30).collect::<Vec<_>>());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(feature = "parquet")]
+    #[test]
+    fn test_recorder_parquet_rotates_on_buffered_rows() {
+        let dir =
+            std::env::temp_dir().join(format!("barter_test_recording9_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+
+        // No row group completes before the size limit is reached
+        let mut recorder = OrderbookRecorder::with_config(RecorderConfig {
+            format: RecorderFormat::Parquet,
+            rotate_bytes: Some(1024),
+            ..RecorderConfig::new(&dir)
+        });
+        write_books(&mut recorder, 30);
+        recorder.close();
+
+        let files = recording_files(&dir).unwrap();
+        assert!(files.len() > 2, "expected several rotations: {files:?}");
+        assert_eq!(sequences(&files), (0..30).collect::<Vec<_>>());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_compression_from_str() {
+        assert_eq!("gzip".parse::<Compression>(), Ok(Compression::Gzip));
+        assert_eq!("ZSTD".parse::<Compression>(), Ok(Compression::Zstd));
+        assert_eq!("none".parse::<Compression>(), Ok(Compression::None));
+        assert!("lz4".parse::<Compression>().is_err());
+
+        assert_eq!("jsonl".parse::<RecorderFormat>(), Ok(RecorderFormat::Jsonl));
+        #[cfg(feature = "parquet")]
+        assert_eq!(
+            "parquet".parse::<RecorderFormat>(),
+            Ok(RecorderFormat::Parquet)
+        );
+        #[cfg(not(feature = "parquet"))]
+        assert!("parquet".parse::<RecorderFormat>().is_err());
+    }
+
+    #[test]
+    fn test_recorder_handle_drops_when_full() {
+        // No writer task consuming: the channel fills and further records are dropped
+        let (handle, mut rx) = RecorderHandle::channel(2);
+        let book = OrderBook::new(1, None, vec![Level::new(dec!(0.50), dec!(10))], vec![]);
+        let event = OrderBookEvent::Snapshot(book);
+
+        for _ in 0..5 {
+            handle.on_orderbook_update(
+                ExchangeId::Kalshi,
+                "KXTEST",
+                Utc::now(),
+                Utc::now(),
+                &event,
+            );
+        }
+        assert_eq!(handle.dropped(), 3);
+
+        // Clones share the counter
+        let clone = handle.clone();
+        clone.record_opportunity(&test_opportunity(), None);
+        assert_eq!(handle.dropped(), 4);
+
+        // Once the consumer catches up, records are accepted again
+        rx.try_recv().unwrap();
+        assert!(handle.send(RecordedEvent::opportunity(&test_opportunity(), None)));
+        assert_eq!(handle.dropped(), 4);
+    }
+}
@@ -0,0 +1,241 @@
+//! Periodic pair refresh and diffing against the running strategy.
+//!
+//! Pairs are fetched once at startup; [`PairRefresher`] re-queries the database
+//! on an interval, diffs the result against the current pair set, and publishes
+//! [`PairUpdate`]s that the strategy applies via
+//! [`PredictionArbitrageStrategy::apply_pair_update`](crate::PredictionArbitrageStrategy::apply_pair_update).
+
+use crate::{
+    correlation::CorrelatedPair,
+    database::{DatabaseError, DatabaseQuerier, MarketPairFilters},
+    source::PairSource,
+};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tracing::{debug, info, warn};
+
+/// Change to the monitored pair set.
+#[derive(Debug, Clone)]
+pub enum PairUpdate {
+    /// Pair newly returned by the database.
+    Added(CorrelatedPair),
+    /// Pair no longer returned by the database.
+    Removed(CorrelatedPair),
+    /// Pair still returned, but with different tokens or inverse flag.
+    Changed {
+        previous: CorrelatedPair,
+        current: CorrelatedPair,
+    },
+}
+
+impl PairUpdate {
+    /// The pair this update applies to (the current version for `Changed`).
+    pub fn pair(&self) -> &CorrelatedPair {
+        match self {
+            PairUpdate::Added(pair) | PairUpdate::Removed(pair) => pair,
+            PairUpdate::Changed { current, .. } => current,
+        }
+    }
+}
+
+/// Result of the strategy applying a [`PairUpdate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PairUpdateOutcome {
+    /// Added pair is being monitored (its instruments already exist in the engine).
+    Monitoring,
+    /// Added pair needs new market data subscriptions (restart required).
+    RequiresSubscription,
+    /// Pair removed from monitoring.
+    Removed,
+    /// Existing pair updated in place.
+    Updated,
+    /// Update had no effect (already added, or unknown pair).
+    Ignored,
+}
+
+/// Whether two pairs refer to the same market pair (same Kalshi ticker and condition id).
+pub fn same_pair(a: &CorrelatedPair, b: &CorrelatedPair) -> bool {
+    a.kalshi_ticker == b.kalshi_ticker && a.polymarket_condition_id == b.polymarket_condition_id
+}
+
+/// Whether a pair's tradable definition differs.
+///
+/// Expiry is not compared, since pairs without a Kalshi expiry get a default
+/// relative to fetch time and would otherwise change on every refresh.
+fn pair_changed(previous: &CorrelatedPair, current: &CorrelatedPair) -> bool {
+    previous.inverse != current.inverse
+        || previous.polymarket_yes_token != current.polymarket_yes_token
+        || previous.polymarket_no_token != current.polymarket_no_token
+}
+
+/// Diff the current pair set against the latest pairs from the database.
+pub fn diff_pairs(current: &[CorrelatedPair], latest: &[CorrelatedPair]) -> Vec<PairUpdate> {
+    let mut updates = Vec::new();
+
+    for pair in latest {
+        match current.iter().find(|p| same_pair(p, pair)) {
+            None => updates.push(PairUpdate::Added(pair.clone())),
+            Some(previous) if pair_changed(previous, pair) => updates.push(PairUpdate::Changed {
+                previous: previous.clone(),
+                current: pair.clone(),
+            }),
+            Some(_) => {}
+        }
+    }
+
+    for pair in current {
+        if !latest.iter().any(|p| same_pair(p, pair)) {
+            updates.push(PairUpdate::Removed(pair.clone()));
+        }
+    }
+
+    updates
+}
+
+/// Task that re-queries a [`PairSource`] on an interval and publishes [`PairUpdate`]s.
+#[derive(Debug)]
+pub struct PairRefresher<Source = DatabaseQuerier> {
+    db: Source,
+    filters: MarketPairFilters,
+    interval: Duration,
+    current: Vec<CorrelatedPair>,
+    tx: mpsc::UnboundedSender<PairUpdate>,
+}
+
+impl<Source> PairRefresher<Source>
+where
+    Source: PairSource,
+{
+    /// Create a refresher starting from the pairs the strategy was built with.
+    pub fn new(
+        db: Source,
+        filters: MarketPairFilters,
+        interval: Duration,
+        current: Vec<CorrelatedPair>,
+        tx: mpsc::UnboundedSender<PairUpdate>,
+    ) -> Self {
+        Self {
+            db,
+            filters,
+            interval,
+            current,
+            tx,
+        }
+    }
+
+    /// Pairs as of the last refresh.
+    pub fn current(&self) -> &[CorrelatedPair] {
+        &self.current
+    }
+
+    /// Re-query the database once and publish the diff.
+    pub async fn refresh(&mut self) -> Result<Vec<PairUpdate>, DatabaseError> {
+        let latest = self.db.get_correlated_pairs(self.filters.clone()).await?;
+        let updates = diff_pairs(&self.current, &latest);
+        self.current = latest;
+
+        for update in &updates {
+            let _ = self.tx.send(update.clone());
+        }
+
+        Ok(updates)
+    }
+
+    /// Refresh on the configured interval until the receiver is dropped.
+    pub async fn run(mut self) {
+        let mut interval = tokio::time::interval(self.interval);
+        // First tick completes immediately; startup pairs are already current
+        interval.tick().await;
+
+        loop {
+            interval.tick().await;
+
+            if self.tx.is_closed() {
+                debug!("Pair update receiver dropped, stopping refresher");
+                return;
+            }
+
+            match self.refresh().await {
+                Ok(updates) if !updates.is_empty() => {
+                    info!(updates = updates.len(), "Published pair updates")
+                }
+                Ok(_) => debug!("Pair refresh found no changes"),
+                Err(e) => warn!("Pair refresh failed: {}", e),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn pair(ticker: &str, condition: &str, inverse: bool) -> CorrelatedPair {
+        CorrelatedPair::new(
+            ticker,
+            condition,
+            format!("{ticker}_yes_token"),
+            format!("{ticker}_no_token"),
+            "Test market",
+            Utc::now() + chrono::Duration::days(30),
+            inverse,
+        )
+    }
+
+    #[test]
+    fn test_diff_pairs_added() {
+        let current = vec![pair("KXA", "0xa", false)];
+        let latest = vec![pair("KXA", "0xa", false), pair("KXB", "0xb", false)];
+
+        let updates = diff_pairs(&current, &latest);
+        assert_eq!(updates.len(), 1);
+        assert!(matches!(&updates[0], PairUpdate::Added(p) if p.kalshi_ticker == "KXB"));
+    }
+
+    #[test]
+    fn test_diff_pairs_removed() {
+        let current = vec![pair("KXA", "0xa", false), pair("KXB", "0xb", false)];
+        let latest = vec![pair("KXB", "0xb", false)];
+
+        let updates = diff_pairs(&current, &latest);
+        assert_eq!(updates.len(), 1);
+        assert!(matches!(&updates[0], PairUpdate::Removed(p) if p.kalshi_ticker == "KXA"));
+    }
+
+    #[test]
+    fn test_diff_pairs_inverse_changed() {
+        let current = vec![pair("KXA", "0xa", false)];
+        let latest = vec![pair("KXA", "0xa", true)];
+
+        let updates = diff_pairs(&current, &latest);
+        assert_eq!(updates.len(), 1);
+        match &updates[0] {
+            PairUpdate::Changed { previous, current } => {
+                assert!(!previous.inverse);
+                assert!(current.inverse);
+            }
+            other => panic!("expected Changed, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_diff_pairs_same_ticker_different_condition() {
+        // Same Kalshi ticker mapped to a different Polymarket market is a new pair
+        let current = vec![pair("KXA", "0xa", false)];
+        let latest = vec![pair("KXA", "0xother", false)];
+
+        let updates = diff_pairs(&current, &latest);
+        assert_eq!(updates.len(), 2);
+        assert!(matches!(&updates[0], PairUpdate::Added(_)));
+        assert!(matches!(&updates[1], PairUpdate::Removed(_)));
+    }
+
+    #[test]
+    fn test_diff_pairs_expiry_drift_ignored() {
+        let mut later = pair("KXA", "0xa", false);
+        later.expiry += chrono::Duration::minutes(5);
+
+        assert!(diff_pairs(&[pair("KXA", "0xa", false)], &[later]).is_empty());
+    }
+}
@@ -0,0 +1,330 @@
+//! Replay recorded orderbook data for offline backtests.
+//!
+//! [`ReplayStream`] reads files written by the
+//! [`OrderbookRecorder`](crate::recorder::OrderbookRecorder), maps them onto engine
+//! instruments via a [`MarketDataLookup`] and yields the same
+//! `reconnect::Event<ExchangeId, MarketEvent<InstrumentIndex, DataKind>>` items the
+//! live stream does, so it can be fed to the engine in place of it.
+
+use crate::{
+    recorder::{read_recording, recording_files, RecordedEvent},
+    state::MarketDataLookup,
+};
+use barter_data::{
+    event::{DataKind, MarketEvent},
+    streams::reconnect,
+};
+use barter_instrument::{exchange::ExchangeId, instrument::InstrumentIndex};
+use chrono::{DateTime, Utc};
+use futures::Stream;
+use std::{
+    collections::VecDeque,
+    future::Future,
+    path::{Path, PathBuf},
+    pin::Pin,
+    task::{ready, Context, Poll},
+};
+use tokio::time::{Instant, Sleep};
+use tracing::{info, warn};
+
+/// Item yielded by [`ReplayStream`], matching the live market stream.
+pub type ReplayEvent = reconnect::Event<ExchangeId, MarketEvent<InstrumentIndex, DataKind>>;
+
+/// Pacing of a replay.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum ReplaySpeed {
+    /// Yield events as fast as they are polled.
+    #[default]
+    AsFastAsPossible,
+    /// Preserve recorded gaps between events, scaled by the multiplier
+    /// (2.0 replays twice as fast as real time).
+    ///
+    /// A non-finite or non-positive multiplier replays as fast as possible.
+    RealTime(f64),
+}
+
+impl ReplaySpeed {
+    fn multiplier(&self) -> Option<f64> {
+        match *self {
+            ReplaySpeed::RealTime(multiplier) if multiplier.is_finite() && multiplier > 0.0 => {
+                Some(multiplier)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Configuration for a [`ReplayStream`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReplayConfig {
+    /// Pacing of the replay
+    pub speed: ReplaySpeed,
+    /// Skip events received before this time (inclusive bound)
+    pub start: Option<DateTime<Utc>>,
+    /// Skip events received at or after this time (exclusive bound)
+    pub end: Option<DateTime<Utc>>,
+}
+
+impl ReplayConfig {
+    fn contains(&self, time: DateTime<Utc>) -> bool {
+        self.start.is_none_or(|start| time >= start) && self.end.is_none_or(|end| time < end)
+    }
+}
+
+/// Stream of recorded orderbook events, ordered by receipt time across exchanges.
+#[derive(Debug)]
+pub struct ReplayStream {
+    events: VecDeque<MarketEvent<InstrumentIndex, DataKind>>,
+    speed: ReplaySpeed,
+    /// Wall clock time and recorded time of the first yielded event
+    anchor: Option<(Instant, DateTime<Utc>)>,
+    sleep: Option<Pin<Box<Sleep>>>,
+}
+
+impl ReplayStream {
+    /// Build a replay from already loaded records.
+    ///
+    /// Opportunity records and orderbook records for instruments missing from
+    /// `lookup` are skipped.
+    pub fn new(
+        records: impl IntoIterator<Item = RecordedEvent>,
+        lookup: &MarketDataLookup,
+        config: ReplayConfig,
+    ) -> Self {
+        let mut unknown = 0usize;
+        let mut events: Vec<_> = records
+            .into_iter()
+            .filter_map(|record| {
+                let kind = record.book_event()?;
+                let RecordedEvent::OrderBook {
+                    exchange,
+                    instrument,
+                    time_exchange,
+                    time_received,
+                    ..
+                } = record
+                else {
+                    return None;
+                };
+                if !config.contains(time_received) {
+                    return None;
+                }
+
+                let Some(&index) = lookup.get(&(exchange, instrument.to_lowercase())) else {
+                    unknown += 1;
+                    return None;
+                };
+
+                Some(MarketEvent {
+                    time_exchange,
+                    time_received,
+                    exchange,
+                    instrument: index,
+                    kind: DataKind::OrderBook(kind),
+                })
+            })
+            .collect();
+
+        if unknown > 0 {
+            warn!(unknown, "Skipped recorded events for unknown instruments");
+        }
+
+        // Stable sort keeps file order for events received at the same instant
+        events.sort_by_key(|event| event.time_received);
+
+        Self {
+            events: events.into(),
+            speed: config.speed,
+            anchor: None,
+            sleep: None,
+        }
+    }
+
+    /// Build a replay from recording files, read in the given order.
+    pub fn from_files(
+        paths: impl IntoIterator<Item = impl AsRef<Path>>,
+        lookup: &MarketDataLookup,
+        config: ReplayConfig,
+    ) -> std::io::Result<Self> {
+        let mut records = Vec::new();
+        for path in paths {
+            records.extend(read_recording(path)?);
+        }
+        let replay = Self::new(records, lookup, config);
+        info!(events = replay.len(), "Loaded recording for replay");
+        Ok(replay)
+    }
+
+    /// Build a replay from every recording file in `dir`.
+    pub fn from_dir(
+        dir: impl AsRef<Path>,
+        lookup: &MarketDataLookup,
+        config: ReplayConfig,
+    ) -> std::io::Result<Self> {
+        let paths: Vec<PathBuf> = recording_files(dir)?;
+        Self::from_files(paths, lookup, config)
+    }
+
+    /// Number of events left to replay.
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Whether every event has been replayed.
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Wall clock deadline for an event received at `time`, if paced.
+    fn deadline(&mut self, time: DateTime<Utc>) -> Option<Instant> {
+        let multiplier = self.speed.multiplier()?;
+        let (wall, recorded) = *self.anchor.get_or_insert((Instant::now(), time));
+        let elapsed = (time - recorded).to_std().unwrap_or_default();
+        Some(wall + elapsed.div_f64(multiplier))
+    }
+}
+
+impl Stream for ReplayStream {
+    type Item = ReplayEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        let Some(time) = this.events.front().map(|event| event.time_received) else {
+            return Poll::Ready(None);
+        };
+
+        if this.sleep.is_none() {
+            if let Some(deadline) = this.deadline(time).filter(|&d| d > Instant::now()) {
+                this.sleep = Some(Box::pin(tokio::time::sleep_until(deadline)));
+            }
+        }
+        if let Some(sleep) = this.sleep.as_mut() {
+            ready!(sleep.as_mut().poll(cx));
+            this.sleep = None;
+        }
+
+        Poll::Ready(this.events.pop_front().map(reconnect::Event::Item))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.events.len(), Some(self.events.len()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recorder::BookEventType;
+    use barter_data::books::Level;
+    use chrono::TimeZone;
+    use futures::StreamExt;
+    use rust_decimal_macros::dec;
+    use smol_str::SmolStr;
+    use std::time::Duration;
+
+    fn record(exchange: ExchangeId, instrument: &str, millis: i64) -> RecordedEvent {
+        let time = Utc.timestamp_millis_opt(millis).unwrap();
+        RecordedEvent::OrderBook {
+            exchange,
+            instrument: SmolStr::new(instrument),
+            event_type: BookEventType::Snapshot,
+            sequence: 1,
+            time_exchange: time,
+            time_received: time,
+            bids: vec![Level::new(dec!(0.40), dec!(10))],
+            asks: vec![Level::new(dec!(0.45), dec!(10))],
+        }
+    }
+
+    fn lookup() -> MarketDataLookup {
+        MarketDataLookup::from([
+            (
+                (ExchangeId::Kalshi, "kxtest".to_string()),
+                InstrumentIndex(0),
+            ),
+            (
+                (ExchangeId::Polymarket, "0xyes".to_string()),
+                InstrumentIndex(2),
+            ),
+        ])
+    }
+
+    #[tokio::test]
+    async fn test_replay_orders_across_exchanges() {
+        let records = vec![
+            record(ExchangeId::Kalshi, "KXTEST", 3_000),
+            record(ExchangeId::Polymarket, "0xyes", 1_000),
+            record(ExchangeId::Polymarket, "0xunknown", 1_500),
+            record(ExchangeId::Kalshi, "KXTEST", 2_000),
+        ];
+        let replay = ReplayStream::new(records, &lookup(), ReplayConfig::default());
+        assert_eq!(replay.len(), 3);
+
+        let events: Vec<_> = replay
+            .map(|event| match event {
+                reconnect::Event::Item(event) => {
+                    (event.instrument, event.time_received.timestamp_millis())
+                }
+                reconnect::Event::Reconnecting(_) => panic!("unexpected reconnect"),
+            })
+            .collect()
+            .await;
+        assert_eq!(
+            events,
+            vec![
+                (InstrumentIndex(2), 1_000),
+                (InstrumentIndex(0), 2_000),
+                (InstrumentIndex(0), 3_000),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_replay_time_range() {
+        let records = (0..5).map(|i| record(ExchangeId::Kalshi, "KXTEST", i * 1_000));
+        let config = ReplayConfig {
+            start: Some(Utc.timestamp_millis_opt(1_000).unwrap()),
+            end: Some(Utc.timestamp_millis_opt(3_000).unwrap()),
+            ..Default::default()
+        };
+        let replay = ReplayStream::new(records, &lookup(), config);
+        let times: Vec<_> = replay
+            .filter_map(|event| async move {
+                match event {
+                    reconnect::Event::Item(event) => Some(event.time_received.timestamp_millis()),
+                    reconnect::Event::Reconnecting(_) => None,
+                }
+            })
+            .collect()
+            .await;
+        assert_eq!(times, vec![1_000, 2_000]);
+    }
+
+    #[tokio::test]
+    async fn test_replay_real_time_scaled() {
+        // 2s of recorded time at 20x should take ~100ms
+        let records = vec![
+            record(ExchangeId::Kalshi, "KXTEST", 0),
+            record(ExchangeId::Kalshi, "KXTEST", 2_000),
+        ];
+        let config = ReplayConfig {
+            speed: ReplaySpeed::RealTime(20.0),
+            ..Default::default()
+        };
+        let started = std::time::Instant::now();
+        let count = ReplayStream::new(records, &lookup(), config).count().await;
+        let elapsed = started.elapsed();
+
+        assert_eq!(count, 2);
+        assert!(elapsed >= Duration::from_millis(90), "{elapsed:?}");
+        assert!(elapsed < Duration::from_secs(1), "{elapsed:?}");
+    }
+
+    #[test]
+    fn test_replay_speed_multiplier() {
+        assert_eq!(ReplaySpeed::AsFastAsPossible.multiplier(), None);
+        assert_eq!(ReplaySpeed::RealTime(0.0).multiplier(), None);
+        assert_eq!(ReplaySpeed::RealTime(f64::NAN).multiplier(), None);
+        assert_eq!(ReplaySpeed::RealTime(2.0).multiplier(), Some(2.0));
+    }
+}
@@ -1,12 +1,71 @@
 //! Risk manager for prediction market arbitrage.
 //!
 //! Validates orders against maximum capital constraints before execution.
+//!
+//! Kalshi (USD) and Polymarket (USDC) are funded separately, so in addition to
+//! the total capital cap each exchange has its own deployed-notional cap.
+//!
+//! `max_open_pairs` caps how many distinct pairs can be in-position (or have
+//! working orders) at once, so a burst of opportunities can't fan capital out
+//! across dozens of markets.
+//!
+//! Fat-finger checks refuse opens priced outside `[min_price, max_price]`, with a
+//! non-positive or oversized quantity, or priced too far from the instrument's mid.
+//!
+//! A daily loss limit acts as a kill-switch: once realized losses since UTC
+//! midnight exceed `max_daily_loss`, all opens are vetoed until the next day.
+//! The `trading_state_tx` kill-switch disables trading on a breach and re-enables
+//! it at the following UTC midnight.
+//!
+//! Once an exchange has reported a balance, opens approved in one check can't
+//! spend more than its free balance.
+//!
+//! Opens touching an exchange whose latest balance snapshot is older than
+//! `max_balance_age`, or that has not reported a balance yet, are refused, since
+//! deployed capital can't be trusted.
+//!
+//! An [`OrderRateLimiter`] throttles opens per exchange with a token bucket, so a
+//! volatile minute can't trip exchange-side rate limits.
+//!
+//! A [`CircuitBreaker`] (kept in engine state, fed by account events) vetoes all
+//! opens once an exchange fails too many orders in a row, eg/ on an expired API
+//! key or exchange maintenance. Cancels are always allowed, as are the sells
+//! flattening a one-legged fill (see [`ArbLeg::flatten_cid`]), which bypass every
+//! limit since refusing them would leave the filled leg unhedged.
+//!
+//! Capital limits accumulate across the opens approved in a single check, and
+//! the legs of one opportunity (sharing a client order id prefix) are approved
+//! or refused together, so a cycle can never leave a one-legged position.
 
-use crate::state::ArbitrageEngineState;
+use crate::{
+    config::ArbitrageConfig,
+    correlation::CorrelatedPair,
+    state::{ArbLeg, ArbitrageEngineState, pair_instruments},
+};
+use barter::engine::state::{instrument::filter::InstrumentFilter, trading::TradingState};
 use barter::risk::{RiskApproved, RiskManager, RiskRefused};
-use barter_execution::order::request::{OrderRequestCancel, OrderRequestOpen};
-use barter_instrument::{exchange::ExchangeIndex, instrument::InstrumentIndex};
+use barter_execution::order::{
+    id::ClientOrderId,
+    request::{OrderRequestCancel, OrderRequestOpen, RequestOpen},
+};
+use barter_instrument::{
+    Side,
+    exchange::{ExchangeId, ExchangeIndex},
+    index::IndexedInstruments,
+    instrument::InstrumentIndex,
+};
+use chrono::{DateTime, NaiveDate, TimeDelta, Utc};
 use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use smol_str::SmolStr;
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex, RwLock},
+};
+use thiserror::Error;
+use tokio::sync::mpsc;
+use tracing::{error, info, warn};
 
 /// Risk manager that enforces max capital and per-order limits.
 #[derive(Debug, Clone)]
@@ -15,6 +74,41 @@ pub struct ArbitrageRiskManager {
     pub max_total_capital: Decimal,
     /// Maximum notional value per single order.
     pub max_order_notional: Decimal,
+    /// Maximum capital that can be deployed on Kalshi.
+    pub max_kalshi_capital: Decimal,
+    /// Maximum capital that can be deployed on Polymarket.
+    pub max_polymarket_capital: Decimal,
+    /// Maximum number of distinct pairs with positions or working orders.
+    pub max_open_pairs: usize,
+    /// Minimum order price (inclusive).
+    pub min_price: Decimal,
+    /// Maximum order price (inclusive).
+    pub max_price: Decimal,
+    /// Maximum contracts per single order.
+    pub max_order_contracts: Decimal,
+    /// Maximum deviation of the order price from the instrument mid, in percent.
+    ///
+    /// Skipped when the instrument has no orderbook of its own (eg/ NO legs priced
+    /// from the YES book).
+    pub max_mid_deviation_pct: Decimal,
+    /// Map from instrument to the pair (Kalshi ticker) it belongs to.
+    ///
+    /// Opens for instruments not in this map are not counted against `max_open_pairs`.
+    pub instrument_pairs: PairInstruments,
+    /// Maximum realized loss per UTC day before new opens are vetoed.
+    pub max_daily_loss: Decimal,
+    /// Rolling daily realized P&L accumulator.
+    pub daily_pnl: DailyPnlTracker,
+    /// Notified with `TradingState::Disabled` when the daily loss limit is breached,
+    /// and with `TradingState::Enabled` when the daily P&L resets at UTC midnight.
+    pub trading_state_tx: Option<mpsc::UnboundedSender<TradingState>>,
+    /// Per-exchange order rate limits, persisted across checks.
+    pub rate_limiter: OrderRateLimiter,
+    /// Maximum age of an exchange's balance snapshot before its opens are refused
+    /// (`None` disables the check).
+    ///
+    /// Exchanges that have never reported a balance are treated as stale.
+    pub max_balance_age: Option<TimeDelta>,
 }
 
 impl Default for ArbitrageRiskManager {
@@ -22,10 +116,783 @@ impl Default for ArbitrageRiskManager {
         Self {
             max_total_capital: Decimal::from(10_000),
             max_order_notional: Decimal::from(1_000),
+            max_kalshi_capital: Decimal::from(5_000),
+            max_polymarket_capital: Decimal::from(5_000),
+            max_open_pairs: 10,
+            min_price: Decimal::new(1, 2),
+            max_price: Decimal::new(99, 2),
+            max_order_contracts: Decimal::from(5_000),
+            max_mid_deviation_pct: Decimal::from(50),
+            instrument_pairs: PairInstruments::default(),
+            max_daily_loss: Decimal::from(500),
+            daily_pnl: DailyPnlTracker::default(),
+            trading_state_tx: None,
+            rate_limiter: OrderRateLimiter::default(),
+            max_balance_age: Some(TimeDelta::minutes(5)),
         }
     }
 }
 
+/// Reason an open order was refused by [`ArbitrageRiskManager`].
+///
+/// Rendered into the [`RiskRefused`] reason, so it shows up in engine audits.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum RiskRefusal {
+    #[error("Daily loss limit breached: realized={realized} max_loss={max_loss}")]
+    DailyLossLimit {
+        realized: Decimal,
+        max_loss: Decimal,
+    },
+
+    #[error("Order price {price} outside bounds [{min}, {max}]")]
+    PriceOutOfBounds {
+        price: Decimal,
+        min: Decimal,
+        max: Decimal,
+    },
+
+    #[error("Order quantity {quantity} must be positive")]
+    NonPositiveQuantity { quantity: Decimal },
+
+    #[error("Order quantity {quantity} exceeds max contracts {max}")]
+    OrderContracts { quantity: Decimal, max: Decimal },
+
+    #[error("Order price {price} deviates {deviation_pct}% from mid {mid}, max {max_pct}%")]
+    MidDeviation {
+        price: Decimal,
+        mid: Decimal,
+        deviation_pct: Decimal,
+        max_pct: Decimal,
+    },
+
+    #[error("Order notional {notional} exceeds max {max}")]
+    OrderNotional { notional: Decimal, max: Decimal },
+
+    #[error("Would exceed max capital: deployed={deployed} + order={order} > max={max}")]
+    TotalCapital {
+        deployed: Decimal,
+        order: Decimal,
+        max: Decimal,
+    },
+
+    #[error(
+        "Would exceed {} capital: deployed={deployed} + order={order} > max={max}",
+        exchange.as_str()
+    )]
+    ExchangeCapital {
+        exchange: ExchangeId,
+        deployed: Decimal,
+        order: Decimal,
+        max: Decimal,
+    },
+
+    #[error("Would exceed max open pairs: open={open} max={max}")]
+    MaxOpenPairs { open: usize, max: usize },
+
+    #[error(
+        "{} balance is stale: last update {age_secs}s ago > max={max_secs}s",
+        exchange.as_str()
+    )]
+    StaleBalance {
+        exchange: ExchangeId,
+        age_secs: i64,
+        max_secs: i64,
+    },
+
+    #[error("{} has not reported a balance yet", exchange.as_str())]
+    MissingBalance { exchange: ExchangeId },
+
+    #[error(
+        "Insufficient {} balance: spent={spent} + order={order} > balance={balance}",
+        exchange.as_str()
+    )]
+    InsufficientBalance {
+        exchange: ExchangeId,
+        balance: Decimal,
+        spent: Decimal,
+        order: Decimal,
+    },
+
+    #[error("Rate limited: {} order budget exhausted", exchange.as_str())]
+    RateLimited { exchange: ExchangeId },
+
+    #[error(
+        "Circuit breaker tripped: {failures} consecutive order failures on exchange {exchange}"
+    )]
+    CircuitBreaker {
+        exchange: ExchangeIndex,
+        failures: u32,
+    },
+
+    #[error("Linked leg {cid} refused: {reason}")]
+    LinkedLeg {
+        cid: ClientOrderId,
+        reason: Box<RiskRefusal>,
+    },
+}
+
+/// Why and when a [`CircuitBreaker`] tripped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BreakerTrip {
+    /// Exchange whose orders kept failing
+    pub exchange: ExchangeIndex,
+    /// Consecutive failures at the time of the trip
+    pub failures: u32,
+    /// Time the breaker tripped
+    pub time: DateTime<Utc>,
+}
+
+/// Kill-switch on consecutive order failures per exchange.
+///
+/// Once tripped it stays tripped until [`CircuitBreaker::reset`], or until
+/// `reset_after` has elapsed if set.
+#[derive(Debug, Clone)]
+pub struct CircuitBreaker {
+    /// Consecutive failures on one exchange that trip the breaker
+    pub max_consecutive_failures: u32,
+    /// Automatic reset delay (`None` = manual reset only)
+    pub reset_after: Option<TimeDelta>,
+    failures: HashMap<ExchangeIndex, u32>,
+    tripped: Option<BreakerTrip>,
+}
+
+impl Default for CircuitBreaker {
+    fn default() -> Self {
+        Self::new(3, None)
+    }
+}
+
+impl CircuitBreaker {
+    /// Create a breaker tripping after `max_consecutive_failures` on one exchange.
+    pub fn new(max_consecutive_failures: u32, reset_after: Option<TimeDelta>) -> Self {
+        Self {
+            max_consecutive_failures,
+            reset_after,
+            failures: HashMap::new(),
+            tripped: None,
+        }
+    }
+
+    /// Create a breaker with the thresholds from `config`.
+    pub fn from_config(config: &ArbitrageConfig) -> Self {
+        Self::new(
+            config.max_consecutive_order_failures,
+            config
+                .circuit_breaker_reset_secs
+                .map(|secs| TimeDelta::seconds(secs as i64)),
+        )
+    }
+
+    /// Record the outcome of an order on `exchange`.
+    ///
+    /// A success resets that exchange's failure count; a failure that reaches
+    /// `max_consecutive_failures` trips the breaker.
+    pub fn record_order_result(
+        &mut self,
+        exchange: ExchangeIndex,
+        success: bool,
+        now: DateTime<Utc>,
+    ) {
+        // Start counting afresh once a timed trip has expired
+        if self.tripped.is_some() && self.tripped(now).is_none() {
+            info!("Circuit breaker reset after cooldown");
+            self.reset();
+        }
+
+        if success {
+            self.failures.remove(&exchange);
+            return;
+        }
+
+        let failures = self.failures.entry(exchange).or_default();
+        *failures += 1;
+        let failures = *failures;
+        warn!(%exchange, failures, "Order failed");
+
+        if self.tripped.is_none() && failures >= self.max_consecutive_failures {
+            error!(
+                %exchange,
+                failures,
+                reset_after = ?self.reset_after,
+                "CIRCUIT BREAKER TRIPPED: refusing all new orders"
+            );
+            self.tripped = Some(BreakerTrip {
+                exchange,
+                failures,
+                time: now,
+            });
+        }
+    }
+
+    /// Active trip at `now`, if the breaker is tripped and not yet expired.
+    pub fn tripped(&self, now: DateTime<Utc>) -> Option<&BreakerTrip> {
+        self.tripped.as_ref().filter(|trip| {
+            self.reset_after
+                .is_none_or(|reset_after| now < trip.time + reset_after)
+        })
+    }
+
+    /// Clear the trip and all failure counts.
+    pub fn reset(&mut self) {
+        self.tripped = None;
+        self.failures.clear();
+    }
+
+    /// Current consecutive failure count for `exchange`.
+    pub fn consecutive_failures(&self, exchange: ExchangeIndex) -> u32 {
+        self.failures.get(&exchange).copied().unwrap_or(0)
+    }
+}
+
+/// Token bucket parameters for one exchange.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    /// Sustained orders per second the bucket refills at
+    pub orders_per_sec: f64,
+    /// Bucket capacity, ie/ the most orders that can be sent at once
+    pub burst: f64,
+}
+
+impl RateLimit {
+    pub fn new(orders_per_sec: f64, burst: f64) -> Self {
+        Self {
+            orders_per_sec,
+            burst,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct TokenBucket {
+    tokens: f64,
+    updated: DateTime<Utc>,
+}
+
+/// Per-exchange token bucket order rate limiter.
+///
+/// Exchanges without a [`RateLimit`] are unthrottled. Buckets start full.
+///
+/// Uses interior mutability since [`RiskManager::check`] takes `&self`.
+#[derive(Debug, Clone)]
+pub struct OrderRateLimiter {
+    limits: HashMap<ExchangeId, RateLimit>,
+    buckets: RefCell<HashMap<ExchangeId, TokenBucket>>,
+}
+
+impl Default for OrderRateLimiter {
+    /// 10 orders/sec with a burst of 10 on both Kalshi and Polymarket.
+    fn default() -> Self {
+        Self::unlimited()
+            .with_limit(ExchangeId::Kalshi, RateLimit::new(10.0, 10.0))
+            .with_limit(ExchangeId::Polymarket, RateLimit::new(10.0, 10.0))
+    }
+}
+
+impl OrderRateLimiter {
+    /// Limiter with no exchange throttled.
+    pub fn unlimited() -> Self {
+        Self {
+            limits: HashMap::new(),
+            buckets: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// Set the rate limit for `exchange`, resetting its bucket.
+    pub fn with_limit(mut self, exchange: ExchangeId, limit: RateLimit) -> Self {
+        self.limits.insert(exchange, limit);
+        self.buckets.get_mut().remove(&exchange);
+        self
+    }
+
+    /// Rate limit for `exchange`, if throttled.
+    pub fn limit(&self, exchange: ExchangeId) -> Option<RateLimit> {
+        self.limits.get(&exchange).copied()
+    }
+
+    /// Orders that could be sent on `exchange` at `now` (`None` if unthrottled).
+    pub fn available(&self, exchange: ExchangeId, now: DateTime<Utc>) -> Option<f64> {
+        let limit = self.limit(exchange)?;
+        Some(self.refilled(exchange, limit, now).tokens)
+    }
+
+    /// Take one token per order from each exchange's bucket, all or nothing.
+    ///
+    /// Returns the first exchange without enough tokens, leaving every bucket
+    /// untouched.
+    pub fn try_acquire(
+        &self,
+        orders: &HashMap<ExchangeId, u32>,
+        now: DateTime<Utc>,
+    ) -> Result<(), ExchangeId> {
+        let mut acquired = Vec::with_capacity(orders.len());
+        for (&exchange, &count) in orders {
+            let Some(limit) = self.limit(exchange) else {
+                continue;
+            };
+            let mut bucket = self.refilled(exchange, limit, now);
+            if bucket.tokens < f64::from(count) {
+                return Err(exchange);
+            }
+            bucket.tokens -= f64::from(count);
+            acquired.push((exchange, bucket));
+        }
+
+        self.buckets.borrow_mut().extend(acquired);
+        Ok(())
+    }
+
+    /// Bucket for `exchange` refilled up to `now`.
+    fn refilled(&self, exchange: ExchangeId, limit: RateLimit, now: DateTime<Utc>) -> TokenBucket {
+        match self.buckets.borrow().get(&exchange) {
+            None => TokenBucket {
+                tokens: limit.burst,
+                updated: now,
+            },
+            Some(bucket) => {
+                let elapsed = (now - bucket.updated)
+                    .to_std()
+                    .unwrap_or_default()
+                    .as_secs_f64();
+                TokenBucket {
+                    tokens: (bucket.tokens + elapsed * limit.orders_per_sec).min(limit.burst),
+                    updated: now.max(bucket.updated),
+                }
+            }
+        }
+    }
+}
+
+/// Running totals for one risk check, including opens approved earlier in the batch.
+#[derive(Debug, Clone)]
+struct CheckBudget {
+    /// Total deployed capital
+    deployed: Decimal,
+    /// Per-exchange deployed notional, populated lazily from engine state
+    exchanges: HashMap<ExchangeIndex, Decimal>,
+    /// Per-exchange notional of opens approved so far in this check
+    spent: HashMap<ExchangeIndex, Decimal>,
+    /// Pairs with positions, working orders or approved opens
+    open_pairs: HashSet<SmolStr>,
+}
+
+/// Instrument to pair (Kalshi ticker) map, shared with the strategy so pairs added at
+/// runtime count against `max_open_pairs`.
+///
+/// See [`PredictionArbitrageStrategy::with_pair_instruments`](crate::PredictionArbitrageStrategy::with_pair_instruments).
+#[derive(Debug, Clone, Default)]
+pub struct PairInstruments(Arc<RwLock<HashMap<InstrumentIndex, SmolStr>>>);
+
+impl PairInstruments {
+    /// Pair `instrument` belongs to, if any.
+    pub fn get(&self, instrument: &InstrumentIndex) -> Option<SmolStr> {
+        self.0
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .get(instrument)
+            .cloned()
+    }
+
+    /// Map each instrument to its pair, replacing any previous mapping.
+    pub fn extend(&self, instruments: impl IntoIterator<Item = (InstrumentIndex, SmolStr)>) {
+        self.0
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .extend(instruments);
+    }
+}
+
+impl From<HashMap<InstrumentIndex, SmolStr>> for PairInstruments {
+    fn from(instruments: HashMap<InstrumentIndex, SmolStr>) -> Self {
+        Self(Arc::new(RwLock::new(instruments)))
+    }
+}
+
+/// Snapshot of the daily realized P&L accumulator.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DailyPnl {
+    /// UTC day the accumulator is tracking.
+    pub day: Option<NaiveDate>,
+    /// Cumulative realized P&L at the start of `day`.
+    pub baseline: Decimal,
+    /// Realized P&L since the start of `day`.
+    pub realized: Decimal,
+    /// Whether the daily loss limit has been breached for `day`.
+    pub breached: bool,
+}
+
+/// Rolling daily realized P&L accumulator, reset at UTC midnight.
+///
+/// Shared with the strategy so it is persisted across restarts, see
+/// [`PredictionArbitrageStrategy::with_daily_pnl`](crate::PredictionArbitrageStrategy::with_daily_pnl).
+#[derive(Debug, Clone, Default)]
+pub struct DailyPnlTracker(Arc<Mutex<DailyPnl>>);
+
+impl DailyPnlTracker {
+    /// Current accumulator snapshot.
+    pub fn get(&self) -> DailyPnl {
+        *self.0.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Replace the accumulator, eg/ with one persisted before a restart.
+    pub fn set(&self, pnl: DailyPnl) {
+        *self.0.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = pnl;
+    }
+
+    /// Update from the cumulative realized P&L observed at `now`.
+    ///
+    /// Rolls the baseline over on a new UTC day. Returns `true` if this update
+    /// is the one that breached `max_daily_loss`.
+    pub fn update(&self, cumulative_realized: Decimal, now: DateTime<Utc>, max_daily_loss: Decimal) -> bool {
+        let mut pnl = self.get();
+        let today = now.date_naive();
+
+        if pnl.day != Some(today) {
+            if pnl.day.is_some() {
+                info!(%today, "Daily P&L accumulator reset");
+            }
+            pnl = DailyPnl {
+                day: Some(today),
+                baseline: cumulative_realized,
+                realized: Decimal::ZERO,
+                breached: false,
+            };
+        }
+
+        pnl.realized = cumulative_realized - pnl.baseline;
+
+        let newly_breached = !pnl.breached && pnl.realized <= -max_daily_loss;
+        pnl.breached |= newly_breached;
+
+        self.set(pnl);
+        newly_breached
+    }
+}
+
+/// Start of the UTC day following `now`, when the daily P&L accumulator resets.
+pub fn next_utc_midnight(now: DateTime<Utc>) -> DateTime<Utc> {
+    (now.date_naive() + TimeDelta::days(1))
+        .and_time(chrono::NaiveTime::MIN)
+        .and_utc()
+}
+
+impl ArbitrageRiskManager {
+    /// Map each pair's Kalshi and Polymarket instruments to the pair, so opens can
+    /// be counted against `max_open_pairs`.
+    ///
+    /// Uses the same instrument naming as
+    /// [`PredictionArbitrageStrategy::with_instruments`](crate::PredictionArbitrageStrategy::with_instruments).
+    pub fn with_pairs(self, pairs: &[CorrelatedPair], indexed: &IndexedInstruments) -> Self {
+        self.instrument_pairs.extend(pair_instruments(pairs, indexed));
+        self
+    }
+
+    /// Pairs with a non-zero position or working orders in engine state.
+    pub fn open_pairs(&self, state: &ArbitrageEngineState) -> HashSet<SmolStr> {
+        state
+            .instruments
+            .instruments(&InstrumentFilter::None)
+            .filter(|inst| inst.data.position != 0 || !inst.orders.0.is_empty())
+            .filter_map(|inst| self.instrument_pairs.get(&inst.key))
+            .collect()
+    }
+
+    /// Notify `tx` with `TradingState::Disabled` when the daily loss limit is breached,
+    /// and with `TradingState::Enabled` at the following UTC midnight.
+    ///
+    /// Disabled trading stops the risk checks that would notice the new day, so the
+    /// re-enable is scheduled on the tokio runtime the breach is detected on.
+    pub fn with_trading_state_tx(mut self, tx: mpsc::UnboundedSender<TradingState>) -> Self {
+        self.trading_state_tx = Some(tx);
+        self
+    }
+
+    /// Update the daily P&L accumulator, returning `true` if opens should be vetoed.
+    pub fn daily_loss_breached(&self, state: &ArbitrageEngineState, now: DateTime<Utc>) -> bool {
+        if self.daily_pnl.update(total_realized_pnl(state), now, self.max_daily_loss) {
+            let pnl = self.daily_pnl.get();
+            error!(
+                realized = %pnl.realized,
+                max_daily_loss = %self.max_daily_loss,
+                "Daily loss limit breached, disabling trading"
+            );
+            if let Some(tx) = &self.trading_state_tx {
+                let _ = tx.send(TradingState::Disabled);
+                Self::schedule_reenable(tx.clone(), now);
+            }
+        }
+
+        self.daily_pnl.get().breached
+    }
+
+    /// Send `TradingState::Enabled` on `tx` at the UTC midnight following `now`.
+    fn schedule_reenable(tx: mpsc::UnboundedSender<TradingState>, now: DateTime<Utc>) {
+        let reset = next_utc_midnight(now);
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            warn!(%reset, "No tokio runtime, trading stays disabled after the daily reset");
+            return;
+        };
+
+        let delay = (reset - now).to_std().unwrap_or_default();
+        runtime.spawn(async move {
+            tokio::time::sleep(delay).await;
+            info!(%reset, "Daily loss limit reset, re-enabling trading");
+            let _ = tx.send(TradingState::Enabled);
+        });
+    }
+
+    /// Capital cap for an exchange, if one applies.
+    pub fn exchange_capital_limit(&self, exchange: ExchangeId) -> Option<Decimal> {
+        match exchange {
+            ExchangeId::Kalshi => Some(self.max_kalshi_capital),
+            ExchangeId::Polymarket => Some(self.max_polymarket_capital),
+            _ => None,
+        }
+    }
+
+    /// Fat-finger checks on an open's price and quantity.
+    pub fn check_sanity(
+        &self,
+        state: &ArbitrageEngineState,
+        open: &OrderRequestOpen<ExchangeIndex, InstrumentIndex>,
+    ) -> Result<(), RiskRefusal> {
+        let RequestOpen {
+            price, quantity, ..
+        } = open.state;
+
+        if price < self.min_price || price > self.max_price {
+            return Err(RiskRefusal::PriceOutOfBounds {
+                price,
+                min: self.min_price,
+                max: self.max_price,
+            });
+        }
+
+        if quantity <= Decimal::ZERO {
+            return Err(RiskRefusal::NonPositiveQuantity { quantity });
+        }
+
+        if quantity > self.max_order_contracts {
+            return Err(RiskRefusal::OrderContracts {
+                quantity,
+                max: self.max_order_contracts,
+            });
+        }
+
+        let mid = state
+            .instruments
+            .instrument_index(&open.key.instrument)
+            .data
+            .mid_price();
+        if let Some(mid) = mid.filter(|mid| *mid > Decimal::ZERO) {
+            let deviation_pct = ((price - mid).abs() / mid * Decimal::ONE_HUNDRED).round_dp(2);
+            if deviation_pct > self.max_mid_deviation_pct {
+                return Err(RiskRefusal::MidDeviation {
+                    price,
+                    mid,
+                    deviation_pct,
+                    max_pct: self.max_mid_deviation_pct,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Refuse opens on `exchange` if its balance snapshot is older than `max_balance_age`,
+    /// or if it has not reported one yet.
+    pub fn check_balance_age(
+        &self,
+        state: &ArbitrageEngineState,
+        exchange: ExchangeIndex,
+        now: DateTime<Utc>,
+    ) -> Result<(), RiskRefusal> {
+        let (Some(max_age), Some(exchange)) = (self.max_balance_age, exchange_id(state, exchange))
+        else {
+            return Ok(());
+        };
+
+        match state.global.balance_age(exchange, now) {
+            Some(age) if age > max_age => Err(RiskRefusal::StaleBalance {
+                exchange,
+                age_secs: age.num_seconds(),
+                max_secs: max_age.num_seconds(),
+            }),
+            Some(_) => Ok(()),
+            None => Err(RiskRefusal::MissingBalance { exchange }),
+        }
+    }
+
+    /// Check one open against the limits, charging it to `budget` if it passes.
+    fn check_open(
+        &self,
+        state: &ArbitrageEngineState,
+        budget: &mut CheckBudget,
+        open: &OrderRequestOpen<ExchangeIndex, InstrumentIndex>,
+    ) -> Result<(), RiskRefusal> {
+        // Sells reduce inventory (eg/ flattening a one-legged fill) rather than deploy capital
+        if open.state.side == Side::Sell {
+            return Ok(());
+        }
+
+        self.check_sanity(state, open)?;
+        self.check_balance_age(state, open.key.exchange, Utc::now())?;
+
+        let notional = open.state.price * open.state.quantity;
+
+        if notional > self.max_order_notional {
+            return Err(RiskRefusal::OrderNotional {
+                notional,
+                max: self.max_order_notional,
+            });
+        }
+
+        if budget.deployed + notional > self.max_total_capital {
+            return Err(RiskRefusal::TotalCapital {
+                deployed: budget.deployed,
+                order: notional,
+                max: self.max_total_capital,
+            });
+        }
+
+        let pair = self.instrument_pairs.get(&open.key.instrument);
+        if let Some(pair) = &pair {
+            if !budget.open_pairs.contains(pair) && budget.open_pairs.len() >= self.max_open_pairs {
+                return Err(RiskRefusal::MaxOpenPairs {
+                    open: budget.open_pairs.len(),
+                    max: self.max_open_pairs,
+                });
+            }
+        }
+
+        let exchange = open.key.exchange;
+        if let Some((exchange_id, limit)) = exchange_id(state, exchange)
+            .and_then(|id| self.exchange_capital_limit(id).map(|limit| (id, limit)))
+        {
+            let exchange_deployed = *budget
+                .exchanges
+                .entry(exchange)
+                .or_insert_with(|| exchange_deployed(state, exchange));
+
+            if exchange_deployed + notional > limit {
+                return Err(RiskRefusal::ExchangeCapital {
+                    exchange: exchange_id,
+                    deployed: exchange_deployed,
+                    order: notional,
+                    max: limit,
+                });
+            }
+
+            budget.exchanges.insert(exchange, exchange_deployed + notional);
+        }
+
+        if let Some((exchange_id, balance)) = exchange_id(state, exchange)
+            .and_then(|id| state.global.exchange_balance(id).map(|balance| (id, balance)))
+        {
+            let spent = budget.spent.entry(exchange).or_default();
+            if *spent + notional > balance {
+                return Err(RiskRefusal::InsufficientBalance {
+                    exchange: exchange_id,
+                    balance,
+                    spent: *spent,
+                    order: notional,
+                });
+            }
+            *spent += notional;
+        }
+
+        budget.deployed += notional;
+        if let Some(pair) = pair {
+            budget.open_pairs.insert(pair);
+        }
+
+        Ok(())
+    }
+}
+
+/// Whether an open is a sell flattening a one-legged fill.
+fn is_flatten<T>(open: &OrderRequestOpen<ExchangeIndex, T>) -> bool {
+    open.state.side == Side::Sell && ArbLeg::is_flatten_cid(&open.key.cid)
+}
+
+/// Split opens into groups that must be approved together.
+///
+/// Consecutive opens sharing an [`ArbLeg::parse_cid`] group form one group; all
+/// other opens stand alone.
+fn linked_groups<T>(
+    opens: impl IntoIterator<Item = OrderRequestOpen<ExchangeIndex, T>>,
+) -> Vec<Vec<OrderRequestOpen<ExchangeIndex, T>>> {
+    let mut groups: Vec<Vec<OrderRequestOpen<ExchangeIndex, T>>> = Vec::new();
+    for open in opens {
+        let linked = groups
+            .last()
+            .and_then(|group| group.last())
+            .and_then(|last| ArbLeg::parse_cid(&last.key.cid))
+            .is_some_and(|(group, _)| {
+                ArbLeg::parse_cid(&open.key.cid).is_some_and(|(open_group, _)| open_group == group)
+            });
+
+        match groups.last_mut() {
+            Some(group) if linked => group.push(open),
+            _ => groups.push(vec![open]),
+        }
+    }
+    groups
+}
+
+impl ArbitrageRiskManager {
+    /// Take rate limit tokens for every leg of a group, refusing the first leg
+    /// on an exhausted exchange.
+    fn acquire_rate_limit(
+        &self,
+        state: &ArbitrageEngineState,
+        group: &[OrderRequestOpen<ExchangeIndex, InstrumentIndex>],
+        now: DateTime<Utc>,
+    ) -> Result<(), (ClientOrderId, RiskRefusal)> {
+        let mut orders: HashMap<ExchangeId, u32> = HashMap::new();
+        for open in group {
+            if let Some(id) = exchange_id(state, open.key.exchange) {
+                *orders.entry(id).or_default() += 1;
+            }
+        }
+
+        self.rate_limiter.try_acquire(&orders, now).map_err(|limited| {
+            let cid = group
+                .iter()
+                .find(|open| exchange_id(state, open.key.exchange) == Some(limited))
+                .map(|open| open.key.cid.clone())
+                .expect("limited exchange has a leg in the group");
+            (cid, RiskRefusal::RateLimited { exchange: limited })
+        })
+    }
+}
+
+/// Resolve the `ExchangeId` for an `ExchangeIndex` in engine state.
+fn exchange_id(state: &ArbitrageEngineState, exchange: ExchangeIndex) -> Option<ExchangeId> {
+    state
+        .connectivity
+        .exchanges
+        .get_index(exchange.index())
+        .map(|(id, _)| *id)
+}
+
+/// Cumulative realized P&L across all instruments.
+fn total_realized_pnl(state: &ArbitrageEngineState) -> Decimal {
+    state
+        .instruments
+        .instruments(&InstrumentFilter::None)
+        .map(|inst| inst.data.realized_pnl)
+        .sum()
+}
+
+/// Capital deployed on an exchange, summed from instrument cost bases.
+fn exchange_deployed(state: &ArbitrageEngineState, exchange: ExchangeIndex) -> Decimal {
+    state
+        .instruments
+        .instruments(&InstrumentFilter::exchanges([exchange]))
+        .map(|inst| inst.data.cost_basis)
+        .sum()
+}
+
 impl RiskManager<ExchangeIndex, InstrumentIndex> for ArbitrageRiskManager {
     type State = ArbitrageEngineState;
 
@@ -43,39 +910,1020 @@ impl RiskManager<ExchangeIndex, InstrumentIndex> for ArbitrageRiskManager {
         // Cancels always approved
         let approved_cancels: Vec<_> = cancels.into_iter().map(RiskApproved::new).collect();
 
-        let mut approved_opens = Vec::new();
+        // Flattening sells unwind a one-legged fill, so are never vetoed: refusing them
+        // would leave the filled leg unhedged
+        let (flattens, opens): (Vec<_>, Vec<_>) = opens.into_iter().partition(is_flatten);
+        let mut approved_opens: Vec<_> = flattens.into_iter().map(RiskApproved::new).collect();
         let mut refused_opens = Vec::new();
 
-        let deployed = state.global.total_deployed;
+        if self.daily_loss_breached(state, Utc::now()) {
+            let pnl = self.daily_pnl.get();
+            let refusal = RiskRefusal::DailyLossLimit {
+                realized: pnl.realized,
+                max_loss: self.max_daily_loss,
+            };
+            refused_opens.extend(
+                opens
+                    .into_iter()
+                    .map(|open| RiskRefused::new(open, refusal.to_string())),
+            );
+            return (approved_cancels, approved_opens, std::iter::empty(), refused_opens);
+        }
 
-        for open in opens {
-            let notional = open.state.price * open.state.quantity;
+        if let Some(trip) = state.global.circuit_breaker.tripped(Utc::now()) {
+            let refusal = RiskRefusal::CircuitBreaker {
+                exchange: trip.exchange,
+                failures: trip.failures,
+            };
+            refused_opens.extend(
+                opens
+                    .into_iter()
+                    .map(|open| RiskRefused::new(open, refusal.to_string())),
+            );
+            return (approved_cancels, approved_opens, std::iter::empty(), refused_opens);
+        }
 
-            if deployed + notional > self.max_total_capital {
-                refused_opens.push(RiskRefused::new(
-                    open,
-                    format!(
-                        "Would exceed max capital: deployed={} + order={} > max={}",
-                        deployed, notional, self.max_total_capital
-                    ),
-                ));
-                continue;
+        let mut budget = CheckBudget {
+            deployed: state.global.total_deployed,
+            exchanges: HashMap::new(),
+            spent: HashMap::new(),
+            open_pairs: self.open_pairs(state),
+        };
+
+        for group in linked_groups(opens) {
+            // Charge the whole group to a scratch budget, committing only if every leg passes
+            let mut scratch = budget.clone();
+            // Only charge the rate limiter for groups that pass every other check
+            let refused = group
+                .iter()
+                .find_map(|open| {
+                    self.check_open(state, &mut scratch, open)
+                        .err()
+                        .map(|refusal| (open.key.cid.clone(), refusal))
+                })
+                .or_else(|| self.acquire_rate_limit(state, &group, Utc::now()).err());
+
+            match refused {
+                None => {
+                    budget = scratch;
+                    approved_opens.extend(group.into_iter().map(RiskApproved::new));
+                }
+                Some((refused_cid, refusal)) => {
+                    for open in group {
+                        let reason = if open.key.cid == refused_cid {
+                            refusal.clone()
+                        } else {
+                            RiskRefusal::LinkedLeg {
+                                cid: refused_cid.clone(),
+                                reason: Box::new(refusal.clone()),
+                            }
+                        };
+                        refused_opens.push(RiskRefused::new(open, reason.to_string()));
+                    }
+                }
             }
+        }
 
-            if notional > self.max_order_notional {
-                refused_opens.push(RiskRefused::new(
-                    open,
-                    format!(
-                        "Order notional {} exceeds max {}",
-                        notional, self.max_order_notional
-                    ),
-                ));
-                continue;
+        (approved_cancels, approved_opens, std::iter::empty(), refused_opens)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{ArbitrageGlobalData, ArbitrageInstrumentData};
+    use barter::engine::state::builder::EngineStateBuilder;
+    use barter_data::books::{Level, OrderBook};
+    use barter_execution::order::{
+        OrderKey, OrderKind, TimeInForce,
+        id::{ClientOrderId, StrategyId},
+    };
+    use barter_instrument::{
+        Side, Underlying, asset::Asset, index::IndexedInstruments, instrument::Instrument,
+    };
+    use rust_decimal_macros::dec;
+
+    const KALSHI: ExchangeIndex = ExchangeIndex(0);
+    const POLYMARKET: ExchangeIndex = ExchangeIndex(1);
+    const KALSHI_YES: InstrumentIndex = InstrumentIndex(0);
+    const POLY_NO: InstrumentIndex = InstrumentIndex(1);
+
+    fn test_state() -> ArbitrageEngineState {
+        let indexed = IndexedInstruments::builder()
+            .add_instrument(Instrument::spot(
+                ExchangeId::Kalshi,
+                "kalshi_KXTEST_yes",
+                "KXTEST_yes",
+                Underlying::new(Asset::from("KXTEST_yes"), Asset::from("usd")),
+                None,
+            ))
+            .add_instrument(Instrument::spot(
+                ExchangeId::Polymarket,
+                "poly_0xno_tok",
+                "0xno_token",
+                Underlying::new(Asset::from("0xno_token"), Asset::from("usdc")),
+                None,
+            ))
+            .build();
+
+        // Fresh balances ample enough that only the limit under test applies
+        let now = Utc::now();
+        let global = ArbitrageGlobalData {
+            kalshi_balance: dec!(1000000),
+            polymarket_balance: dec!(1000000),
+            last_balance_update: HashMap::from([
+                (ExchangeId::Kalshi, now),
+                (ExchangeId::Polymarket, now),
+            ]),
+            ..ArbitrageGlobalData::default()
+        };
+
+        EngineStateBuilder::new(&indexed, global, |_| ArbitrageInstrumentData::default()).build()
+    }
+
+    fn open(
+        exchange: ExchangeIndex,
+        instrument: InstrumentIndex,
+        price: Decimal,
+        quantity: Decimal,
+    ) -> Open {
+        OrderRequestOpen {
+            key: OrderKey {
+                exchange,
+                instrument,
+                strategy: StrategyId::new("test-arb"),
+                cid: ClientOrderId::new(format!("{}_{}", exchange.0, instrument.0)),
+            },
+            state: RequestOpen {
+                side: Side::Buy,
+                price,
+                quantity,
+                kind: OrderKind::Limit,
+                time_in_force: TimeInForce::ImmediateOrCancel,
+            },
+        }
+    }
+
+    /// One leg of an opportunity, with a `{strategy}_{n}_{leg}` client order id.
+    fn leg(
+        exchange: ExchangeIndex,
+        instrument: InstrumentIndex,
+        price: Decimal,
+        quantity: Decimal,
+        cid: &str,
+    ) -> Open {
+        let mut open = open(exchange, instrument, price, quantity);
+        open.key.cid = ClientOrderId::new(cid);
+        open
+    }
+
+    fn risk(max_kalshi: Decimal, max_poly: Decimal) -> ArbitrageRiskManager {
+        ArbitrageRiskManager {
+            max_total_capital: dec!(10000),
+            max_order_notional: dec!(1000),
+            max_kalshi_capital: max_kalshi,
+            max_polymarket_capital: max_poly,
+            ..Default::default()
+        }
+    }
+
+    type Open = OrderRequestOpen<ExchangeIndex, InstrumentIndex>;
+
+    fn check(
+        risk: &ArbitrageRiskManager,
+        state: &ArbitrageEngineState,
+        opens: Vec<Open>,
+    ) -> (Vec<Open>, Vec<RiskRefused<Open>>) {
+        let (_, approved, _, refused) = risk.check(state, std::iter::empty(), opens);
+        (
+            approved.into_iter().map(|a| a.into_item()).collect(),
+            refused.into_iter().collect(),
+        )
+    }
+
+    #[test]
+    fn test_kalshi_leg_limited_polymarket_leg_approved() {
+        let mut state = test_state();
+        state.instruments.instrument_index_mut(&KALSHI_YES).data.cost_basis = dec!(80);
+
+        let risk = risk(dec!(100), dec!(1000));
+        let (approved, refused) = check(
+            &risk,
+            &state,
+            vec![
+                open(KALSHI, KALSHI_YES, dec!(0.40), dec!(100)),
+                open(POLYMARKET, POLY_NO, dec!(0.55), dec!(100)),
+            ],
+        );
+
+        assert_eq!(approved.len(), 1);
+        assert_eq!(approved[0].key.exchange, POLYMARKET);
+        assert_eq!(refused.len(), 1);
+        assert_eq!(refused[0].item.key.exchange, KALSHI);
+        assert!(refused[0].reason.contains("kalshi"));
+    }
+
+    #[test]
+    fn test_exchange_limit_accumulates_within_batch() {
+        let state = test_state();
+
+        let risk = risk(dec!(50), dec!(1000));
+        let (approved, refused) = check(
+            &risk,
+            &state,
+            vec![
+                open(KALSHI, KALSHI_YES, dec!(0.40), dec!(100)),
+                open(KALSHI, KALSHI_YES, dec!(0.40), dec!(100)),
+                open(POLYMARKET, POLY_NO, dec!(0.55), dec!(100)),
+            ],
+        );
+
+        assert_eq!(approved.len(), 2);
+        assert_eq!(refused.len(), 1);
+        assert_eq!(refused[0].item.key.exchange, KALSHI);
+    }
+
+    #[test]
+    fn test_within_exchange_limits_approved() {
+        let state = test_state();
+
+        let (approved, refused) = check(
+            &ArbitrageRiskManager::default(),
+            &state,
+            vec![
+                open(KALSHI, KALSHI_YES, dec!(0.40), dec!(100)),
+                open(POLYMARKET, POLY_NO, dec!(0.55), dec!(100)),
+            ],
+        );
+
+        assert_eq!(approved.len(), 2);
+        assert!(refused.is_empty());
+    }
+
+    #[test]
+    fn test_daily_loss_limit_vetoes_opens() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let risk = ArbitrageRiskManager {
+            max_daily_loss: dec!(100),
+            ..Default::default()
+        }
+        .with_trading_state_tx(tx);
+        let mut state = test_state();
+        let orders = || vec![open(KALSHI, KALSHI_YES, dec!(0.40), dec!(10))];
+
+        let (approved, refused) = check(&risk, &state, orders());
+        assert_eq!(approved.len(), 1);
+        assert!(refused.is_empty());
+
+        // Realize a loss that crosses the limit
+        state.instruments.instrument_index_mut(&KALSHI_YES).data.realized_pnl = dec!(-60);
+        state.instruments.instrument_index_mut(&POLY_NO).data.realized_pnl = dec!(-45);
+
+        let (approved, refused) = check(&risk, &state, orders());
+        assert!(approved.is_empty());
+        assert_eq!(refused.len(), 1);
+        assert!(refused[0].reason.contains("Daily loss limit"));
+        assert_eq!(rx.try_recv().unwrap(), TradingState::Disabled);
+
+        // Subsequent opens stay vetoed, even if P&L recovers, without re-notifying
+        state.instruments.instrument_index_mut(&POLY_NO).data.realized_pnl = dec!(0);
+        let (approved, _) = check(&risk, &state, orders());
+        assert!(approved.is_empty());
+        assert!(rx.try_recv().is_err());
+    }
+
+    /// A sell flattening the YES leg of opportunity `test-arb_1`.
+    fn flatten() -> Open {
+        let mut open = leg(KALSHI, KALSHI_YES, dec!(0.38), dec!(10), "");
+        open.key.cid = ArbLeg::Yes.flatten_cid("test-arb_1", 1);
+        open.state.side = Side::Sell;
+        open
+    }
+
+    #[test]
+    fn test_flatten_approved_despite_daily_loss() {
+        let risk = ArbitrageRiskManager {
+            max_daily_loss: dec!(100),
+            ..Default::default()
+        };
+        let mut state = test_state();
+        check(&risk, &state, vec![]);
+        state.instruments.instrument_index_mut(&KALSHI_YES).data.realized_pnl = dec!(-150);
+
+        let (approved, refused) = check(
+            &risk,
+            &state,
+            vec![open(KALSHI, KALSHI_YES, dec!(0.40), dec!(10)), flatten()],
+        );
+
+        assert_eq!(approved, vec![flatten()]);
+        assert_eq!(refused.len(), 1);
+        assert!(refused[0].reason.contains("Daily loss limit"));
+    }
+
+    #[test]
+    fn test_flatten_approved_despite_tripped_breaker_and_rate_limit() {
+        let mut state = test_state();
+        let now = Utc::now();
+        for _ in 0..3 {
+            state
+                .global
+                .circuit_breaker
+                .record_order_result(KALSHI, false, now);
+        }
+        // Without a balance the exchange would also be refused as stale
+        state.global.last_balance_update.remove(&ExchangeId::Kalshi);
+        let risk = ArbitrageRiskManager {
+            rate_limiter: OrderRateLimiter::unlimited()
+                .with_limit(ExchangeId::Kalshi, RateLimit::new(1.0, 1.0)),
+            ..Default::default()
+        };
+
+        let (approved, refused) = check(&risk, &state, vec![flatten(), flatten(), flatten()]);
+        assert_eq!(approved.len(), 3);
+        assert!(refused.is_empty());
+
+        // Other sells skip the balance check too, but not the breaker
+        let mut sell = open(KALSHI, KALSHI_YES, dec!(0.38), dec!(10));
+        sell.state.side = Side::Sell;
+        let (approved, refused) = check(&risk, &state, vec![sell.clone()]);
+        assert!(approved.is_empty());
+        assert!(refused[0].reason.contains("Circuit breaker"));
+
+        state.global.circuit_breaker = CircuitBreaker::default();
+        let (approved, refused) = check(&risk, &state, vec![sell]);
+        assert_eq!(approved.len(), 1);
+        assert!(refused.is_empty());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_trading_reenabled_at_utc_midnight() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let risk = ArbitrageRiskManager {
+            max_daily_loss: dec!(100),
+            ..Default::default()
+        }
+        .with_trading_state_tx(tx);
+        let mut state = test_state();
+        let now = DateTime::parse_from_rfc3339("2025-01-01T23:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(
+            next_utc_midnight(now),
+            DateTime::parse_from_rfc3339("2025-01-02T00:00:00Z").unwrap()
+        );
+
+        assert!(!risk.daily_loss_breached(&state, now));
+        state.instruments.instrument_index_mut(&KALSHI_YES).data.realized_pnl = dec!(-150);
+        assert!(risk.daily_loss_breached(&state, now));
+        assert_eq!(rx.recv().await, Some(TradingState::Disabled));
+
+        // Re-enabled once the hour to midnight has passed
+        let start = tokio::time::Instant::now();
+        assert_eq!(rx.recv().await, Some(TradingState::Enabled));
+        assert_eq!(start.elapsed(), std::time::Duration::from_secs(3600));
+
+        // The first check of the new day clears the breach
+        assert!(!risk.daily_loss_breached(&state, next_utc_midnight(now)));
+    }
+
+    #[test]
+    fn test_daily_pnl_resets_on_new_day() {
+        let tracker = DailyPnlTracker::default();
+        let day_one = DateTime::parse_from_rfc3339("2025-01-01T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let day_two = day_one + chrono::Duration::days(1);
+
+        assert!(!tracker.update(dec!(0), day_one, dec!(100)));
+        assert!(tracker.update(dec!(-150), day_one, dec!(100)));
+        assert!(tracker.get().breached);
+        assert_eq!(tracker.get().realized, dec!(-150));
+
+        // New UTC day: baseline rolls to the cumulative total and the breach clears
+        assert!(!tracker.update(dec!(-150), day_two, dec!(100)));
+        let pnl = tracker.get();
+        assert!(!pnl.breached);
+        assert_eq!(pnl.realized, Decimal::ZERO);
+        assert_eq!(pnl.baseline, dec!(-150));
+
+        // Further losses on the new day count from the new baseline
+        assert!(!tracker.update(dec!(-200), day_two, dec!(100)));
+        assert!(tracker.update(dec!(-260), day_two, dec!(100)));
+    }
+
+    #[test]
+    fn test_max_open_pairs_counts_existing_positions() {
+        let mut state = test_state();
+        state.instruments.instrument_index_mut(&KALSHI_YES).data.position = 10;
+
+        let risk = ArbitrageRiskManager {
+            max_open_pairs: 1,
+            instrument_pairs: HashMap::from([
+                (KALSHI_YES, SmolStr::new("KXA")),
+                (POLY_NO, SmolStr::new("KXB")),
+            ])
+            .into(),
+            ..Default::default()
+        };
+        assert_eq!(risk.open_pairs(&state), HashSet::from([SmolStr::new("KXA")]));
+
+        let (approved, refused) = check(
+            &risk,
+            &state,
+            vec![
+                open(KALSHI, KALSHI_YES, dec!(0.40), dec!(10)),
+                open(POLYMARKET, POLY_NO, dec!(0.55), dec!(10)),
+            ],
+        );
+
+        assert_eq!(approved.len(), 1);
+        assert_eq!(approved[0].key.instrument, KALSHI_YES);
+        assert_eq!(refused.len(), 1);
+        assert_eq!(refused[0].item.key.instrument, POLY_NO);
+    }
+
+    #[test]
+    fn test_single_order_over_notional_refused() {
+        let state = test_state();
+        let risk = ArbitrageRiskManager {
+            max_order_notional: dec!(50),
+            ..Default::default()
+        };
+
+        let (approved, refused) = check(
+            &risk,
+            &state,
+            vec![
+                open(KALSHI, KALSHI_YES, dec!(0.40), dec!(200)),
+                open(POLYMARKET, POLY_NO, dec!(0.40), dec!(100)),
+            ],
+        );
+
+        assert_eq!(approved.len(), 1);
+        assert_eq!(approved[0].key.instrument, POLY_NO);
+        assert_eq!(refused.len(), 1);
+        assert_eq!(
+            refused[0].reason,
+            RiskRefusal::OrderNotional {
+                notional: dec!(80.00),
+                max: dec!(50),
+            }
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn test_total_capital_accumulates_with_deployed() {
+        let mut state = test_state();
+        state.global.total_deployed = dec!(900);
+        let risk = ArbitrageRiskManager {
+            max_total_capital: dec!(1000),
+            ..Default::default()
+        };
+
+        // 40 + 40 fit in the remaining 100, the third order does not
+        let (approved, refused) = check(
+            &risk,
+            &state,
+            vec![
+                open(KALSHI, KALSHI_YES, dec!(0.40), dec!(100)),
+                open(POLYMARKET, POLY_NO, dec!(0.40), dec!(100)),
+                open(KALSHI, KALSHI_YES, dec!(0.40), dec!(100)),
+            ],
+        );
+
+        assert_eq!(approved.len(), 2);
+        assert_eq!(refused.len(), 1);
+        assert_eq!(
+            refused[0].reason,
+            RiskRefusal::TotalCapital {
+                deployed: dec!(980.00),
+                order: dec!(40.00),
+                max: dec!(1000),
+            }
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn test_sells_skip_capital_limits() {
+        let mut state = test_state();
+        state.global.total_deployed = dec!(1000);
+        let risk = ArbitrageRiskManager {
+            max_total_capital: dec!(1000),
+            ..Default::default()
+        };
+
+        let buy = open(KALSHI, KALSHI_YES, dec!(0.40), dec!(100));
+        let mut sell = open(KALSHI, KALSHI_YES, dec!(0.40), dec!(100));
+        sell.state.side = Side::Sell;
+        let (approved, refused) = check(&risk, &state, vec![buy, sell]);
+
+        assert_eq!(approved.len(), 1);
+        assert_eq!(approved[0].state.side, Side::Sell);
+        assert_eq!(refused.len(), 1);
+    }
+
+    #[test]
+    fn test_linked_legs_refused_together() {
+        let state = test_state();
+        let risk = risk(dec!(50), dec!(1000));
+
+        // The first opportunity fits; the second's Kalshi leg breaches the Kalshi cap,
+        // so its Polymarket leg must not be sent on its own
+        let (approved, refused) = check(
+            &risk,
+            &state,
+            vec![
+                leg(KALSHI, KALSHI_YES, dec!(0.40), dec!(100), "arb_1_yes"),
+                leg(POLYMARKET, POLY_NO, dec!(0.55), dec!(100), "arb_1_no"),
+                leg(POLYMARKET, POLY_NO, dec!(0.55), dec!(10), "arb_2_no"),
+                leg(KALSHI, KALSHI_YES, dec!(0.40), dec!(100), "arb_2_yes"),
+            ],
+        );
+
+        let approved: Vec<_> = approved.iter().map(|o| o.key.cid.0.as_str()).collect();
+        assert_eq!(approved, vec!["arb_1_yes", "arb_1_no"]);
+
+        assert_eq!(refused.len(), 2);
+        let kalshi_refusal = RiskRefusal::ExchangeCapital {
+            exchange: ExchangeId::Kalshi,
+            deployed: dec!(40.00),
+            order: dec!(40.00),
+            max: dec!(50),
+        };
+        assert_eq!(refused[0].item.key.cid.0, "arb_2_no");
+        assert_eq!(
+            refused[0].reason,
+            RiskRefusal::LinkedLeg {
+                cid: ClientOrderId::new("arb_2_yes"),
+                reason: Box::new(kalshi_refusal.clone()),
+            }
+            .to_string()
+        );
+        assert_eq!(refused[1].item.key.cid.0, "arb_2_yes");
+        assert_eq!(refused[1].reason, kalshi_refusal.to_string());
+    }
+
+    #[test]
+    fn test_refused_group_does_not_consume_budget() {
+        let state = test_state();
+        let risk = ArbitrageRiskManager {
+            max_total_capital: dec!(100),
+            ..Default::default()
+        };
+
+        // arb_1's first leg fits but its second does not; the capital it would have
+        // used stays available for arb_2
+        let (approved, refused) = check(
+            &risk,
+            &state,
+            vec![
+                leg(KALSHI, KALSHI_YES, dec!(0.50), dec!(100), "arb_1_yes"),
+                leg(POLYMARKET, POLY_NO, dec!(0.60), dec!(100), "arb_1_no"),
+                leg(KALSHI, KALSHI_YES, dec!(0.40), dec!(100), "arb_2_yes"),
+                leg(POLYMARKET, POLY_NO, dec!(0.50), dec!(100), "arb_2_no"),
+            ],
+        );
+
+        assert_eq!(refused.len(), 2);
+        assert!(refused.iter().all(|r| r.item.key.cid.0.starts_with("arb_1")));
+        assert_eq!(approved.len(), 2);
+        assert!(approved.iter().all(|o| o.key.cid.0.starts_with("arb_2")));
+    }
+
+    #[test]
+    fn test_linked_groups() {
+        let cids = |groups: Vec<Vec<Open>>| -> Vec<Vec<String>> {
+            groups
+                .into_iter()
+                .map(|g| g.into_iter().map(|o| o.key.cid.0.to_string()).collect())
+                .collect()
+        };
+        let opens = ["s_1_yes", "s_1_no", "manual", "s_2_no", "s_3_yes"]
+            .into_iter()
+            .map(|cid| leg(KALSHI, KALSHI_YES, dec!(0.40), dec!(1), cid));
+
+        assert_eq!(
+            cids(linked_groups(opens)),
+            vec![
+                vec!["s_1_yes", "s_1_no"],
+                vec!["manual"],
+                vec!["s_2_no"],
+                vec!["s_3_yes"],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_price_bounds_inclusive() {
+        let state = test_state();
+        let risk = ArbitrageRiskManager::default();
+
+        let (approved, refused) = check(
+            &risk,
+            &state,
+            vec![
+                open(KALSHI, KALSHI_YES, dec!(0.01), dec!(10)),
+                open(POLYMARKET, POLY_NO, dec!(0.99), dec!(10)),
+                open(KALSHI, KALSHI_YES, dec!(0), dec!(10)),
+                open(POLYMARKET, POLY_NO, dec!(1.50), dec!(10)),
+            ],
+        );
+
+        let approved: Vec<_> = approved.iter().map(|o| o.state.price).collect();
+        assert_eq!(approved, vec![dec!(0.01), dec!(0.99)]);
+        assert_eq!(refused.len(), 2);
+        assert_eq!(
+            refused[1].reason,
+            RiskRefusal::PriceOutOfBounds {
+                price: dec!(1.50),
+                min: dec!(0.01),
+                max: dec!(0.99),
             }
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn test_quantity_limits() {
+        let state = test_state();
+        let risk = ArbitrageRiskManager {
+            max_order_contracts: dec!(100),
+            ..Default::default()
+        };
+
+        let (approved, refused) = check(
+            &risk,
+            &state,
+            vec![
+                open(KALSHI, KALSHI_YES, dec!(0.40), dec!(100)),
+                open(KALSHI, KALSHI_YES, dec!(0.40), dec!(101)),
+                open(KALSHI, KALSHI_YES, dec!(0.40), dec!(0)),
+            ],
+        );
+
+        assert_eq!(approved.len(), 1);
+        assert_eq!(
+            refused[0].reason,
+            RiskRefusal::OrderContracts {
+                quantity: dec!(101),
+                max: dec!(100),
+            }
+            .to_string()
+        );
+        assert_eq!(
+            refused[1].reason,
+            RiskRefusal::NonPositiveQuantity { quantity: dec!(0) }.to_string()
+        );
+    }
+
+    #[test]
+    fn test_mid_deviation() {
+        let mut state = test_state();
+        let risk = ArbitrageRiskManager {
+            max_mid_deviation_pct: dec!(20),
+            ..Default::default()
+        };
+        let far = || vec![open(KALSHI, KALSHI_YES, dec!(0.80), dec!(10))];
+
+        // No book: deviation can't be measured, so the check is skipped
+        let (approved, _) = check(&risk, &state, far());
+        assert_eq!(approved.len(), 1);
+
+        // Mid 0.50: 0.80 deviates 60%
+        state
+            .instruments
+            .instrument_index_mut(&KALSHI_YES)
+            .data
+            .orderbook = Some(OrderBook::new(
+            1,
+            None,
+            vec![Level::new(dec!(0.48), dec!(100))],
+            vec![Level::new(dec!(0.52), dec!(100))],
+        ));
+        let (approved, refused) = check(&risk, &state, far());
+        assert!(approved.is_empty());
+        assert_eq!(
+            refused[0].reason,
+            RiskRefusal::MidDeviation {
+                price: dec!(0.80),
+                mid: dec!(0.50),
+                deviation_pct: dec!(60.00),
+                max_pct: dec!(20),
+            }
+            .to_string()
+        );
+
+        let (approved, _) = check(
+            &risk,
+            &state,
+            vec![open(KALSHI, KALSHI_YES, dec!(0.55), dec!(10))],
+        );
+        assert_eq!(approved.len(), 1);
+    }
+
+    #[test]
+    fn test_circuit_breaker_trips_after_consecutive_failures() {
+        let now = Utc::now();
+        let mut breaker = CircuitBreaker::new(3, None);
+
+        breaker.record_order_result(KALSHI, false, now);
+        breaker.record_order_result(KALSHI, false, now);
+        // Failures on another exchange are counted separately
+        breaker.record_order_result(POLYMARKET, false, now);
+        assert!(breaker.tripped(now).is_none());
 
-            approved_opens.push(RiskApproved::new(open));
+        breaker.record_order_result(KALSHI, false, now);
+        let trip = breaker.tripped(now).unwrap();
+        assert_eq!(trip.exchange, KALSHI);
+        assert_eq!(trip.failures, 3);
+
+        // Manual reset only
+        assert!(breaker.tripped(now + TimeDelta::days(1)).is_some());
+        breaker.reset();
+        assert!(breaker.tripped(now).is_none());
+        assert_eq!(breaker.consecutive_failures(POLYMARKET), 0);
+    }
+
+    #[test]
+    fn test_circuit_breaker_success_resets_count() {
+        let now = Utc::now();
+        let mut breaker = CircuitBreaker::new(3, None);
+
+        breaker.record_order_result(KALSHI, false, now);
+        breaker.record_order_result(KALSHI, false, now);
+        breaker.record_order_result(KALSHI, true, now);
+        assert_eq!(breaker.consecutive_failures(KALSHI), 0);
+
+        breaker.record_order_result(KALSHI, false, now);
+        breaker.record_order_result(KALSHI, false, now);
+        assert!(breaker.tripped(now).is_none());
+    }
+
+    #[test]
+    fn test_circuit_breaker_timed_reset() {
+        let now = Utc::now();
+        let mut breaker = CircuitBreaker::new(1, Some(TimeDelta::seconds(60)));
+
+        breaker.record_order_result(KALSHI, false, now);
+        assert!(breaker.tripped(now + TimeDelta::seconds(59)).is_some());
+        assert!(breaker.tripped(now + TimeDelta::seconds(60)).is_none());
+
+        // Counting restarts after the cooldown
+        breaker.record_order_result(KALSHI, true, now + TimeDelta::seconds(61));
+        assert_eq!(breaker.consecutive_failures(KALSHI), 0);
+    }
+
+    #[test]
+    fn test_circuit_breaker_refuses_opens() {
+        let mut state = test_state();
+        let now = Utc::now();
+        for _ in 0..3 {
+            state
+                .global
+                .circuit_breaker
+                .record_order_result(POLYMARKET, false, now);
         }
 
-        (approved_cancels, approved_opens, std::iter::empty(), refused_opens)
+        let (approved, refused) = check(
+            &ArbitrageRiskManager::default(),
+            &state,
+            vec![
+                open(KALSHI, KALSHI_YES, dec!(0.40), dec!(10)),
+                open(POLYMARKET, POLY_NO, dec!(0.55), dec!(10)),
+            ],
+        );
+
+        assert!(approved.is_empty());
+        assert_eq!(refused.len(), 2);
+        assert!(refused.iter().all(|r| r.reason
+            == RiskRefusal::CircuitBreaker {
+                exchange: POLYMARKET,
+                failures: 3,
+            }
+            .to_string()));
+    }
+
+    #[test]
+    fn test_rate_limit_refuses_excess_pairs() {
+        let state = test_state();
+        let risk = ArbitrageRiskManager {
+            rate_limiter: OrderRateLimiter::unlimited()
+                .with_limit(ExchangeId::Kalshi, RateLimit::new(4.0, 4.0)),
+            ..Default::default()
+        };
+
+        // Both legs of each pair go to Kalshi, so the 4 order budget covers 2 pairs
+        let opens = (1..=10)
+            .flat_map(|n| {
+                [
+                    leg(KALSHI, KALSHI_YES, dec!(0.40), dec!(1), &format!("arb_{n}_yes")),
+                    leg(KALSHI, KALSHI_YES, dec!(0.55), dec!(1), &format!("arb_{n}_no")),
+                ]
+            })
+            .collect();
+        let (approved, refused) = check(&risk, &state, opens);
+
+        let approved: Vec<_> = approved.iter().map(|o| o.key.cid.0.as_str()).collect();
+        assert_eq!(approved, vec!["arb_1_yes", "arb_1_no", "arb_2_yes", "arb_2_no"]);
+        assert_eq!(refused.len(), 16);
+
+        let limited = RiskRefusal::RateLimited {
+            exchange: ExchangeId::Kalshi,
+        };
+        assert_eq!(refused[0].item.key.cid.0, "arb_3_yes");
+        assert_eq!(refused[0].reason, limited.to_string());
+        assert_eq!(
+            refused[1].reason,
+            RiskRefusal::LinkedLeg {
+                cid: ClientOrderId::new("arb_3_yes"),
+                reason: Box::new(limited),
+            }
+            .to_string()
+        );
+
+        // Bucket state persists across checks
+        let (approved, _) = check(
+            &risk,
+            &state,
+            vec![leg(KALSHI, KALSHI_YES, dec!(0.40), dec!(1), "arb_11_yes")],
+        );
+        assert!(approved.is_empty());
+    }
+
+    #[test]
+    fn test_rate_limit_throttles_cross_exchange_pair_atomically() {
+        let state = test_state();
+        let risk = ArbitrageRiskManager {
+            rate_limiter: OrderRateLimiter::unlimited()
+                .with_limit(ExchangeId::Polymarket, RateLimit::new(1.0, 1.0)),
+            ..Default::default()
+        };
+
+        let (approved, refused) = check(
+            &risk,
+            &state,
+            vec![
+                leg(KALSHI, KALSHI_YES, dec!(0.40), dec!(1), "arb_1_yes"),
+                leg(POLYMARKET, POLY_NO, dec!(0.55), dec!(1), "arb_1_no"),
+                leg(KALSHI, KALSHI_YES, dec!(0.40), dec!(1), "arb_2_yes"),
+                leg(POLYMARKET, POLY_NO, dec!(0.55), dec!(1), "arb_2_no"),
+            ],
+        );
+
+        assert_eq!(approved.len(), 2);
+        assert!(approved.iter().all(|o| o.key.cid.0.starts_with("arb_1")));
+        assert_eq!(refused.len(), 2);
+        assert_eq!(refused[0].item.key.cid.0, "arb_2_yes");
+        assert_eq!(refused[1].item.key.cid.0, "arb_2_no");
+        assert_eq!(
+            refused[1].reason,
+            RiskRefusal::RateLimited {
+                exchange: ExchangeId::Polymarket,
+            }
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn test_rate_limiter_refills() {
+        let now = Utc::now();
+        let limiter = OrderRateLimiter::unlimited()
+            .with_limit(ExchangeId::Kalshi, RateLimit::new(4.0, 4.0));
+        let orders = |count| HashMap::from([(ExchangeId::Kalshi, count)]);
+
+        assert_eq!(limiter.try_acquire(&orders(4), now), Ok(()));
+        assert_eq!(limiter.try_acquire(&orders(1), now), Err(ExchangeId::Kalshi));
+
+        // Half a second refills 2 tokens
+        let later = now + TimeDelta::milliseconds(500);
+        assert_eq!(limiter.available(ExchangeId::Kalshi, later), Some(2.0));
+        assert_eq!(limiter.try_acquire(&orders(3), later), Err(ExchangeId::Kalshi));
+        assert_eq!(limiter.try_acquire(&orders(2), later), Ok(()));
+
+        // Refill is capped at the burst size
+        let much_later = now + TimeDelta::seconds(60);
+        assert_eq!(limiter.available(ExchangeId::Kalshi, much_later), Some(4.0));
+
+        // Unthrottled exchanges always pass
+        let poly = HashMap::from([(ExchangeId::Polymarket, 100)]);
+        assert_eq!(limiter.try_acquire(&poly, now), Ok(()));
+        assert_eq!(limiter.available(ExchangeId::Polymarket, now), None);
+    }
+
+    #[test]
+    fn test_stale_balance_refuses_exchange() {
+        let mut state = test_state();
+        let now = Utc::now();
+        state
+            .global
+            .last_balance_update
+            .insert(ExchangeId::Kalshi, now - TimeDelta::minutes(10));
+        state
+            .global
+            .last_balance_update
+            .insert(ExchangeId::Polymarket, now);
+        state.global.kalshi_balance = dec!(1000);
+        state.global.polymarket_balance = dec!(1000);
+        let risk = ArbitrageRiskManager::default();
+
+        // Polymarket-only orders still pass
+        let (approved, refused) = check(
+            &risk,
+            &state,
+            vec![leg(POLYMARKET, POLY_NO, dec!(0.55), dec!(10), "arb_1_no")],
+        );
+        assert_eq!(approved.len(), 1);
+        assert!(refused.is_empty());
+
+        // Pairs touching Kalshi are refused
+        let (approved, refused) = check(
+            &risk,
+            &state,
+            vec![
+                leg(KALSHI, KALSHI_YES, dec!(0.40), dec!(10), "arb_2_yes"),
+                leg(POLYMARKET, POLY_NO, dec!(0.55), dec!(10), "arb_2_no"),
+            ],
+        );
+        assert!(approved.is_empty());
+        assert_eq!(refused.len(), 2);
+        let stale = RiskRefusal::StaleBalance {
+            exchange: ExchangeId::Kalshi,
+            age_secs: 600,
+            max_secs: 300,
+        };
+        assert_eq!(refused[0].item.key.cid.0, "arb_2_yes");
+        assert_eq!(refused[0].reason, stale.to_string());
+        assert_eq!(refused[1].item.key.cid.0, "arb_2_no");
+        assert!(refused[1].reason.starts_with("Linked leg arb_2_yes refused"));
+    }
+
+    #[test]
+    fn test_missing_balance_treated_as_stale() {
+        let mut state = test_state();
+        state.global.last_balance_update.remove(&ExchangeId::Polymarket);
+        let risk = ArbitrageRiskManager::default();
+
+        let (approved, refused) = check(
+            &risk,
+            &state,
+            vec![leg(POLYMARKET, POLY_NO, dec!(0.55), dec!(10), "arb_1_no")],
+        );
+        assert!(approved.is_empty());
+        assert_eq!(
+            refused[0].reason,
+            RiskRefusal::MissingBalance {
+                exchange: ExchangeId::Polymarket
+            }
+            .to_string()
+        );
+
+        // Disabling the check lets the open through
+        let risk = ArbitrageRiskManager {
+            max_balance_age: None,
+            ..ArbitrageRiskManager::default()
+        };
+        let (approved, _) = check(
+            &risk,
+            &state,
+            vec![leg(POLYMARKET, POLY_NO, dec!(0.55), dec!(10), "arb_1_no")],
+        );
+        assert_eq!(approved.len(), 1);
+    }
+
+    #[test]
+    fn test_opens_limited_by_exchange_balance() {
+        let mut state = test_state();
+        let now = Utc::now();
+        state.global.last_balance_update.insert(ExchangeId::Kalshi, now);
+        state.global.kalshi_balance = dec!(50);
+
+        // Polymarket's ample balance leaves only its capital cap
+        let (approved, refused) = check(
+            &ArbitrageRiskManager::default(),
+            &state,
+            vec![
+                leg(KALSHI, KALSHI_YES, dec!(0.40), dec!(100), "arb_1_yes"),
+                leg(POLYMARKET, POLY_NO, dec!(0.55), dec!(100), "arb_1_no"),
+                leg(KALSHI, KALSHI_YES, dec!(0.40), dec!(100), "arb_2_yes"),
+                leg(POLYMARKET, POLY_NO, dec!(0.55), dec!(100), "arb_2_no"),
+            ],
+        );
+
+        assert_eq!(approved.len(), 2);
+        assert!(approved.iter().all(|o| o.key.cid.0.starts_with("arb_1")));
+        assert_eq!(refused.len(), 2);
+        assert_eq!(
+            refused[0].reason,
+            RiskRefusal::InsufficientBalance {
+                exchange: ExchangeId::Kalshi,
+                balance: dec!(50),
+                spent: dec!(40.00),
+                order: dec!(40.00),
+            }
+            .to_string()
+        );
     }
 }
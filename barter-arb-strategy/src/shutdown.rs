@@ -0,0 +1,307 @@
+//! Graceful shutdown: cancel working orders and flush the recorder.
+//!
+//! Stopping the engine leaves any resting orders working on the exchanges and
+//! drops records still queued for the recorder's writer task. After the engine
+//! has stopped, [`graceful_shutdown`] cancels every resting order through each
+//! [`ReconcileClient`], re-checking until the exchange reports none left, while
+//! closing the recorder so queued records are written and its file is flushed.
+//! Both are bounded by a single timeout.
+//!
+//! ```rust,ignore
+//! let report = graceful_shutdown(
+//!     &[&kalshi_client as &dyn ReconcileClient, &polymarket_client],
+//!     recorder,
+//!     DEFAULT_SHUTDOWN_TIMEOUT,
+//! )
+//! .await;
+//! ```
+
+use crate::{
+    reconcile::ReconcileClient,
+    recorder::{OrderbookRecorder, RecorderHandle},
+};
+use barter_instrument::exchange::ExchangeId;
+use futures::future::join_all;
+use std::time::Duration;
+use tokio::{
+    task::JoinHandle,
+    time::{sleep, timeout_at, Instant},
+};
+use tracing::{error, info, warn};
+
+/// Default time allowed for cancelling orders and closing the recorder.
+pub const DEFAULT_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Delay between re-checking an exchange for orders still working.
+const SETTLE_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Outcome of [`graceful_shutdown`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ShutdownReport {
+    /// Orders cancelled on each exchange.
+    pub cancelled: Vec<(ExchangeId, usize)>,
+    /// Exchanges that may still have working orders, with the reason.
+    pub unsettled: Vec<(ExchangeId, String)>,
+    /// Whether the recorder closed its file in time (`None` without a recorder).
+    pub recorder_closed: Option<bool>,
+}
+
+impl ShutdownReport {
+    /// True if no orders were left working and the recorder (if any) closed.
+    pub fn is_clean(&self) -> bool {
+        self.unsettled.is_empty() && self.recorder_closed != Some(false)
+    }
+}
+
+/// Cancel all resting orders on `clients` and close the `recorder` writer task
+/// spawned by [`OrderbookRecorder::spawn`], giving up after `timeout`.
+///
+/// Call once the engine has stopped, so no new orders are sent while cancelling.
+pub async fn graceful_shutdown(
+    clients: &[&dyn ReconcileClient],
+    recorder: Option<(RecorderHandle, JoinHandle<OrderbookRecorder>)>,
+    timeout: Duration,
+) -> ShutdownReport {
+    let deadline = Instant::now() + timeout;
+
+    let cancels = join_all(clients.iter().map(|client| async move {
+        let mut cancelled = 0;
+        let settled = timeout_at(deadline, cancel_until_settled(*client, &mut cancelled)).await;
+        (client.exchange(), cancelled, settled)
+    }));
+    let recorder = async move {
+        let (handle, writer) = recorder?;
+        handle.close();
+        Some(match timeout_at(deadline, writer).await {
+            Ok(Ok(recorder)) => {
+                info!(
+                    path = ?recorder.path(),
+                    total = recorder.total_written(),
+                    dropped = handle.dropped(),
+                    "Recorder closed"
+                );
+                true
+            }
+            Ok(Err(e)) => {
+                error!(%e, "Recorder writer task failed");
+                false
+            }
+            Err(_) => {
+                warn!("Timed out closing recorder");
+                false
+            }
+        })
+    };
+    let (cancels, recorder_closed) = tokio::join!(cancels, recorder);
+
+    let mut report = ShutdownReport {
+        recorder_closed,
+        ..ShutdownReport::default()
+    };
+    for (exchange, cancelled, settled) in cancels {
+        info!(%exchange, cancelled, "Cancelled resting orders");
+        report.cancelled.push((exchange, cancelled));
+
+        let reason = match settled {
+            Ok(Ok(())) => continue,
+            Ok(Err(reason)) => reason,
+            Err(_) => "timed out waiting for cancels to settle".to_string(),
+        };
+        warn!(%exchange, %reason, "Orders may still be working");
+        report.unsettled.push((exchange, reason));
+    }
+
+    report
+}
+
+/// Cancel `client`'s resting orders until it reports none left, counting
+/// successful cancels in `cancelled`.
+///
+/// Errors if open orders can no longer be fetched.
+async fn cancel_until_settled(
+    client: &dyn ReconcileClient,
+    cancelled: &mut usize,
+) -> Result<(), String> {
+    loop {
+        match client.cancel_all().await {
+            Ok(count) => *cancelled += count,
+            Err(e) => warn!(exchange = %client.exchange(), %e, "Failed to cancel resting orders"),
+        }
+
+        let open = client
+            .fetch_open_orders()
+            .await
+            .map_err(|e| format!("failed to fetch open orders: {e}"))?;
+        if open.is_empty() {
+            return Ok(());
+        }
+
+        sleep(SETTLE_POLL_INTERVAL).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        reconcile::ExchangeOpenOrder,
+        recorder::{read_recording, RecordedEvent, DEFAULT_FLUSH_INTERVAL},
+        ArbPosition,
+    };
+    use async_trait::async_trait;
+    use barter_execution::{
+        client::InstrumentPosition,
+        error::UnindexedClientError,
+        order::{
+            id::{ClientOrderId, OrderId, StrategyId},
+            state::Open,
+            Order, OrderKey, OrderKind, TimeInForce,
+        },
+    };
+    use barter_instrument::{instrument::name::InstrumentNameExchange, Side};
+    use chrono::Utc;
+    use rust_decimal::Decimal;
+    use rust_decimal_macros::dec;
+    use std::sync::Mutex;
+
+    /// Client whose open orders disappear once cancelled.
+    struct MockClient {
+        exchange: ExchangeId,
+        open_orders: Mutex<Vec<ExchangeOpenOrder>>,
+        cancelled: Mutex<Vec<ClientOrderId>>,
+    }
+
+    impl MockClient {
+        fn new(exchange: ExchangeId, cids: &[&str]) -> Self {
+            Self {
+                exchange,
+                open_orders: Mutex::new(cids.iter().map(|cid| open_order(exchange, cid)).collect()),
+                cancelled: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl ReconcileClient for MockClient {
+        fn exchange(&self) -> ExchangeId {
+            self.exchange
+        }
+
+        async fn fetch_positions(&self) -> Result<Vec<InstrumentPosition>, UnindexedClientError> {
+            Ok(Vec::new())
+        }
+
+        async fn fetch_open_orders(&self) -> Result<Vec<ExchangeOpenOrder>, UnindexedClientError> {
+            Ok(self.open_orders.lock().unwrap().clone())
+        }
+
+        async fn cancel_order(&self, order: &ExchangeOpenOrder) -> Result<(), String> {
+            self.open_orders
+                .lock()
+                .unwrap()
+                .retain(|open| open.key.cid != order.key.cid);
+            self.cancelled.lock().unwrap().push(order.key.cid.clone());
+            Ok(())
+        }
+    }
+
+    fn open_order(exchange: ExchangeId, cid: &str) -> ExchangeOpenOrder {
+        Order {
+            key: OrderKey {
+                exchange,
+                instrument: InstrumentNameExchange::from("KXTEST_yes"),
+                strategy: StrategyId::new("pred-arb"),
+                cid: ClientOrderId::new(cid),
+            },
+            side: Side::Buy,
+            price: dec!(0.40),
+            quantity: dec!(10),
+            kind: OrderKind::Limit,
+            time_in_force: TimeInForce::GoodUntilCancelled { post_only: false },
+            state: Open {
+                id: OrderId::new(format!("{cid}-id")),
+                time_exchange: Utc::now(),
+                filled_quantity: Decimal::ZERO,
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_cancels_orders_and_flushes_recorder() {
+        let dir = std::env::temp_dir().join(format!("barter_test_shutdown_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let kalshi = MockClient::new(ExchangeId::Kalshi, &["a", "b"]);
+        let polymarket = MockClient::new(ExchangeId::Polymarket, &["c"]);
+
+        // A second handle stays alive, as with the market stream tap
+        let (handle, writer) = OrderbookRecorder::new(&dir).spawn(16, DEFAULT_FLUSH_INTERVAL);
+        let stream_handle = handle.clone();
+        stream_handle.record_position("KXTEST", &ArbPosition::default());
+
+        let report = graceful_shutdown(
+            &[&kalshi as &dyn ReconcileClient, &polymarket],
+            Some((handle, writer)),
+            Duration::from_secs(5),
+        )
+        .await;
+
+        assert!(report.is_clean(), "{report:?}");
+        assert_eq!(
+            report.cancelled,
+            vec![(ExchangeId::Kalshi, 2), (ExchangeId::Polymarket, 1)]
+        );
+        assert_eq!(kalshi.cancelled.lock().unwrap().len(), 2);
+        assert_eq!(polymarket.cancelled.lock().unwrap().len(), 1);
+        assert_eq!(report.recorder_closed, Some(true));
+
+        let files = crate::recorder::recording_files(&dir).unwrap();
+        let records = read_recording(&files[0]).unwrap();
+        assert!(matches!(
+            records.as_slice(),
+            [RecordedEvent::Position { .. }]
+        ));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_reports_unsettled_orders_on_timeout() {
+        struct StuckClient;
+
+        #[async_trait]
+        impl ReconcileClient for StuckClient {
+            fn exchange(&self) -> ExchangeId {
+                ExchangeId::Polymarket
+            }
+
+            async fn fetch_positions(
+                &self,
+            ) -> Result<Vec<InstrumentPosition>, UnindexedClientError> {
+                Ok(Vec::new())
+            }
+
+            async fn fetch_open_orders(
+                &self,
+            ) -> Result<Vec<ExchangeOpenOrder>, UnindexedClientError> {
+                Ok(vec![open_order(ExchangeId::Polymarket, "stuck")])
+            }
+
+            async fn cancel_order(&self, _: &ExchangeOpenOrder) -> Result<(), String> {
+                Err("rejected".to_string())
+            }
+        }
+
+        let report = graceful_shutdown(
+            &[&StuckClient as &dyn ReconcileClient],
+            None,
+            Duration::from_millis(100),
+        )
+        .await;
+
+        assert!(!report.is_clean());
+        assert_eq!(report.cancelled, vec![(ExchangeId::Polymarket, 0)]);
+        assert_eq!(report.unsettled.len(), 1);
+        assert_eq!(report.recorder_closed, None);
+    }
+}
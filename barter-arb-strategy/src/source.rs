@@ -0,0 +1,32 @@
+//! Backends that supply correlated market pairs.
+//!
+//! [`PairSource`] abstracts over where pairs come from, so the refresher and
+//! examples can use either the Supabase REST API ([`DatabaseQuerier`]) or, with
+//! the `postgres` feature, a direct database connection
+//! ([`PostgresPairSource`](crate::postgres::PostgresPairSource)).
+
+use crate::{
+    correlation::CorrelatedPair,
+    database::{DatabaseError, DatabaseQuerier, MarketPairFilters},
+};
+use std::future::Future;
+
+/// Source of correlated market pairs.
+pub trait PairSource {
+    /// Fetch market pairs matching the filters and convert them to [`CorrelatedPair`]s.
+    ///
+    /// Invalid or unconvertible records are skipped.
+    fn get_correlated_pairs(
+        &self,
+        filters: MarketPairFilters,
+    ) -> impl Future<Output = Result<Vec<CorrelatedPair>, DatabaseError>> + Send;
+}
+
+impl PairSource for DatabaseQuerier {
+    fn get_correlated_pairs(
+        &self,
+        filters: MarketPairFilters,
+    ) -> impl Future<Output = Result<Vec<CorrelatedPair>, DatabaseError>> + Send {
+        DatabaseQuerier::get_correlated_pairs(self, filters)
+    }
+}
@@ -1,25 +1,41 @@
 //! Custom engine state for the arbitrage strategy.
 
-use crate::correlation::PredictionMarketKey;
+use crate::{
+    correlation::{CorrelatedPair, PredictionMarketKey},
+    metrics::Metrics,
+    risk::CircuitBreaker,
+};
 use barter::engine::{
     Processor,
     state::{EngineState, order::in_flight_recorder::InFlightRequestRecorder},
 };
 use barter_data::{
-    books::OrderBook,
+    books::{Level, OrderBook},
     event::{DataKind, MarketEvent},
-    subscription::book::OrderBookEvent,
+    subscription::{book::OrderBookEvent, status::MarketStatus},
 };
 use barter_execution::{
     AccountEvent, AccountEventKind,
-    order::request::{OrderRequestCancel, OrderRequestOpen},
+    order::{
+        id::{ClientOrderId, OrderId},
+        request::{OrderRequestCancel, OrderRequestOpen},
+        state::{ActiveOrderState, InactiveOrderState, OrderState},
+    },
+    trade::Trade,
+};
+use barter_instrument::{
+    Side,
+    exchange::{ExchangeId, ExchangeIndex},
+    index::IndexedInstruments,
+    instrument::{InstrumentIndex, name::InstrumentNameExchange},
 };
-use barter_instrument::Side;
 use rust_decimal::Decimal;
 use rust_decimal::prelude::ToPrimitive;
 use serde::{Deserialize, Serialize};
+use smol_str::SmolStr;
 use std::collections::HashMap;
-use tracing::info;
+use chrono::{DateTime, TimeDelta, Utc};
+use tracing::{debug, info, warn};
 
 /// Type alias for the arbitrage engine state.
 pub type ArbitrageEngineState = EngineState<ArbitrageGlobalData, ArbitrageInstrumentData>;
@@ -29,16 +45,84 @@ pub type ArbitrageEngineState = EngineState<ArbitrageGlobalData, ArbitrageInstru
 pub struct ArbitrageGlobalData {
     /// Total capital deployed across all positions
     pub total_deployed: Decimal,
-    /// Kalshi account balance
+    /// Kalshi free USD balance, from balance snapshots
     pub kalshi_balance: Decimal,
-    /// Polymarket account balance
+    /// Polymarket free USDC balance, from balance snapshots
     pub polymarket_balance: Decimal,
+    /// Kill-switch on consecutive order failures, fed by account events
+    #[serde(skip)]
+    pub circuit_breaker: CircuitBreaker,
+    /// Time of the latest balance snapshot per exchange
+    pub last_balance_update: HashMap<ExchangeId, DateTime<Utc>>,
+    /// Engine exchange index to `ExchangeId`, see [`Self::with_exchanges`]
+    #[serde(skip)]
+    pub exchange_ids: HashMap<ExchangeIndex, ExchangeId>,
+    /// Paired YES/NO leg fills per correlated pair, see [`Self::with_pairs`]
+    #[serde(default)]
+    pub arb_positions: ArbPositions,
+    /// Optional metrics updated from market and account events
+    #[serde(skip)]
+    pub metrics: Option<Metrics>,
 }
 
 impl ArbitrageGlobalData {
-    /// Get available capital (not yet deployed).
-    pub fn available_capital(&self) -> Decimal {
-        self.kalshi_balance + self.polymarket_balance - self.total_deployed
+    /// Resolve the engine's exchange indexes, so account events can be attributed
+    /// to an `ExchangeId`.
+    pub fn with_exchanges(mut self, indexed: &IndexedInstruments) -> Self {
+        self.exchange_ids = indexed
+            .exchanges()
+            .iter()
+            .map(|exchange| (exchange.key, exchange.value))
+            .collect();
+        self
+    }
+
+    /// Resolve the instruments of `pairs`, so trades can be attributed to
+    /// their pair's [`ArbPosition`].
+    pub fn with_pairs(mut self, pairs: &[CorrelatedPair], indexed: &IndexedInstruments) -> Self {
+        self.arb_positions.instrument_pairs = pair_instruments(pairs, indexed);
+        self
+    }
+
+    /// Age of the latest balance snapshot for `exchange` at `now`, if one was received.
+    pub fn balance_age(
+        &self,
+        exchange: ExchangeId,
+        now: DateTime<Utc>,
+    ) -> Option<chrono::TimeDelta> {
+        self.last_balance_update
+            .get(&exchange)
+            .map(|updated| now - *updated)
+    }
+
+    /// Free balance on `exchange`, if a balance snapshot has been received for it.
+    pub fn exchange_balance(&self, exchange: ExchangeId) -> Option<Decimal> {
+        if !self.last_balance_update.contains_key(&exchange) {
+            return None;
+        }
+        match exchange {
+            ExchangeId::Kalshi => Some(self.kalshi_balance),
+            ExchangeId::Polymarket => Some(self.polymarket_balance),
+            _ => None,
+        }
+    }
+
+    /// Count orderbook events, fills and rejections with `metrics`.
+    pub fn with_metrics(mut self, metrics: Metrics) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Free balance across both exchanges, once a balance snapshot has been received
+    /// for each.
+    ///
+    /// Free balances already exclude what was spent on positions, so deployed capital
+    /// is not subtracted again.
+    pub fn available_capital(&self) -> Option<Decimal> {
+        Some(
+            self.exchange_balance(ExchangeId::Kalshi)?
+                + self.exchange_balance(ExchangeId::Polymarket)?,
+        )
     }
 
     /// Reserve capital for a new position.
@@ -55,6 +139,413 @@ impl ArbitrageGlobalData {
     }
 }
 
+/// Leg of an arbitrage opportunity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum ArbLeg {
+    Yes,
+    No,
+}
+
+impl ArbLeg {
+    /// Client order id of this leg of opportunity `group`.
+    ///
+    /// Both legs of one opportunity share a `{strategy}_{n}` group, so their ids
+    /// are `{strategy}_{n}_yes` and `{strategy}_{n}_no`.
+    pub fn cid(&self, group: &str) -> ClientOrderId {
+        ClientOrderId::new(format!("{group}_{}", self.as_str()))
+    }
+
+    /// Opportunity group and leg of a strategy order, from a client order id
+    /// built by [`Self::cid`].
+    pub fn parse_cid(cid: &ClientOrderId) -> Option<(&str, Self)> {
+        match cid.0.rsplit_once('_') {
+            Some((group, "yes")) if !group.is_empty() => Some((group, ArbLeg::Yes)),
+            Some((group, "no")) if !group.is_empty() => Some((group, ArbLeg::No)),
+            _ => None,
+        }
+    }
+
+    /// Client order id of the `attempt`th order flattening this leg of `group`.
+    ///
+    /// Unique per attempt, as exchanges reject a reused client order id.
+    pub fn flatten_cid(&self, group: &str, attempt: usize) -> ClientOrderId {
+        self.cid(&format!("{group}_flatten{attempt}"))
+    }
+
+    /// Whether a client order id was built by [`Self::flatten_cid`].
+    pub fn is_flatten_cid(cid: &ClientOrderId) -> bool {
+        Self::parse_cid(cid)
+            .and_then(|(group, _)| group.rsplit_once('_'))
+            .is_some_and(|(_, attempt)| attempt.starts_with("flatten"))
+    }
+
+    /// Leg of a strategy order, see [`Self::parse_cid`].
+    pub fn from_cid(cid: &ClientOrderId) -> Option<Self> {
+        Self::parse_cid(cid).map(|(_, leg)| leg)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ArbLeg::Yes => "yes",
+            ArbLeg::No => "no",
+        }
+    }
+}
+
+/// YES and NO leg fills for one correlated pair.
+///
+/// Each matched YES/NO contract pays out $1 at settlement whatever the outcome;
+/// any unmatched quantity is naked exposure on one leg.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ArbPosition {
+    /// Contracts held on the YES leg
+    pub yes_quantity: Decimal,
+    /// Cost of the YES leg contracts held
+    pub yes_cost: Decimal,
+    /// Contracts held on the NO leg
+    pub no_quantity: Decimal,
+    /// Cost of the NO leg contracts held
+    pub no_cost: Decimal,
+    /// Fees paid across both legs
+    pub fees: Decimal,
+}
+
+impl ArbPosition {
+    /// Apply a fill on `leg`. Sells reduce the leg at its average cost.
+    pub fn apply_fill(
+        &mut self,
+        leg: ArbLeg,
+        side: Side,
+        price: Decimal,
+        quantity: Decimal,
+        fees: Decimal,
+    ) {
+        let (held, cost) = match leg {
+            ArbLeg::Yes => (&mut self.yes_quantity, &mut self.yes_cost),
+            ArbLeg::No => (&mut self.no_quantity, &mut self.no_cost),
+        };
+        match side {
+            Side::Buy => {
+                *held += quantity;
+                *cost += price * quantity;
+            }
+            Side::Sell => {
+                let sold = quantity.min(*held);
+                if !held.is_zero() {
+                    *cost -= *cost * sold / *held;
+                }
+                *held -= sold;
+            }
+        }
+        self.fees += fees;
+    }
+
+    /// Contracts held on both legs, paying $1 each at settlement.
+    pub fn matched_quantity(&self) -> Decimal {
+        self.yes_quantity.min(self.no_quantity)
+    }
+
+    /// YES leg contracts without a matching NO contract.
+    pub fn unmatched_yes(&self) -> Decimal {
+        self.yes_quantity - self.matched_quantity()
+    }
+
+    /// NO leg contracts without a matching YES contract.
+    pub fn unmatched_no(&self) -> Decimal {
+        self.no_quantity - self.matched_quantity()
+    }
+
+    /// Naked contracts: positive when long the YES leg, negative when long the NO leg.
+    pub fn net_exposure(&self) -> Decimal {
+        self.yes_quantity - self.no_quantity
+    }
+
+    /// Average YES price plus average NO price, if both legs are held.
+    pub fn blended_cost(&self) -> Option<Decimal> {
+        if self.yes_quantity.is_zero() || self.no_quantity.is_zero() {
+            return None;
+        }
+        Some(self.yes_cost / self.yes_quantity + self.no_cost / self.no_quantity)
+    }
+
+    /// Guaranteed settlement payout of the matched contracts.
+    pub fn expected_payout(&self) -> Decimal {
+        self.matched_quantity()
+    }
+
+    /// Profit locked in by the matched contracts, net of all fees paid.
+    pub fn locked_profit(&self) -> Decimal {
+        let matched = self.blended_cost().map_or(Decimal::ZERO, |blended| {
+            self.matched_quantity() * (Decimal::ONE - blended)
+        });
+        matched - self.fees
+    }
+
+    /// P&L of the position given its `yes` and `no` leg instrument data.
+    ///
+    /// When both legs are [`PositionMode::HeldToSettlement`] this is the
+    /// [`Self::locked_profit`], whatever the books do. Otherwise both legs are
+    /// marked to their mid prices, requiring a mid for any leg held.
+    pub fn pnl(
+        &self,
+        yes: &ArbitrageInstrumentData,
+        no: &ArbitrageInstrumentData,
+    ) -> Option<Decimal> {
+        if yes.position_mode.is_held_to_settlement() && no.position_mode.is_held_to_settlement() {
+            return Some(self.locked_profit());
+        }
+
+        let mark = |quantity: Decimal, cost: Decimal, data: &ArbitrageInstrumentData| {
+            if quantity.is_zero() {
+                return Some(-cost);
+            }
+            data.mid_price().map(|mid| mid * quantity - cost)
+        };
+        let yes_pnl = mark(self.yes_quantity, self.yes_cost, yes)?;
+        let no_pnl = mark(self.no_quantity, self.no_cost, no)?;
+        Some(yes_pnl + no_pnl - self.fees)
+    }
+}
+
+/// How long fills are held waiting for their order's leg, see [`ArbPositions`].
+pub const PENDING_FILL_TTL: TimeDelta = TimeDelta::minutes(10);
+
+/// A fill for an order whose leg isn't known yet.
+#[derive(Debug, Clone)]
+struct PendingFill {
+    instrument: InstrumentIndex,
+    side: Side,
+    price: Decimal,
+    quantity: Decimal,
+    fees: Decimal,
+    received: DateTime<Utc>,
+}
+
+/// A leg order reported fully filled, with fills still to be attributed to it.
+#[derive(Debug, Clone)]
+struct AwaitingFills {
+    leg: ArbLeg,
+    cid: ClientOrderId,
+    quantity: Decimal,
+    since: DateTime<Utc>,
+}
+
+/// [`ArbPosition`]s keyed by Kalshi ticker, built from trades.
+///
+/// Trades only carry the exchange order id, so each leg is learned from the
+/// order snapshot carrying both the exchange id and the client order id. Fills
+/// arriving before that snapshot are held until it does.
+///
+/// An order filled in full on submission is reported without its exchange id,
+/// so its fills are instead attributed by instrument, up to the order quantity.
+/// Fills not attributed within [`PENDING_FILL_TTL`] are dropped.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct ArbPositions {
+    positions: HashMap<SmolStr, ArbPosition>,
+    #[serde(skip)]
+    instrument_pairs: HashMap<InstrumentIndex, SmolStr>,
+    #[serde(skip)]
+    order_legs: HashMap<OrderId, (ArbLeg, ClientOrderId)>,
+    #[serde(skip)]
+    pending: HashMap<OrderId, Vec<PendingFill>>,
+    #[serde(skip)]
+    awaiting: HashMap<InstrumentIndex, Vec<AwaitingFills>>,
+    /// Filled quantity per leg order, see [`Self::filled_quantity`]
+    #[serde(skip)]
+    leg_fills: HashMap<ClientOrderId, Decimal>,
+}
+
+impl ArbPositions {
+    /// Position for the pair with `kalshi_ticker`.
+    pub fn get(&self, kalshi_ticker: &str) -> Option<&ArbPosition> {
+        self.positions.get(kalshi_ticker)
+    }
+
+    /// All positions, by Kalshi ticker.
+    pub fn iter(&self) -> impl Iterator<Item = (&SmolStr, &ArbPosition)> {
+        self.positions.iter()
+    }
+
+    /// Quantity filled so far on the leg order `cid`.
+    pub fn filled_quantity(&self, cid: &ClientOrderId) -> Decimal {
+        self.leg_fills.get(cid).copied().unwrap_or_default()
+    }
+
+    /// Number of exchange orders with fills not yet attributed to a leg.
+    pub fn pending_orders(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Record the leg of exchange order `id`, applying any fills already received.
+    pub fn link_order(&mut self, id: &OrderId, cid: &ClientOrderId) {
+        let pending = self.pending.remove(id).unwrap_or_default();
+        let Some(leg) = ArbLeg::from_cid(cid) else {
+            return;
+        };
+        self.order_legs.insert(id.clone(), (leg, cid.clone()));
+        for fill in pending {
+            self.apply_fill(leg, cid.clone(), fill);
+        }
+    }
+
+    /// Record leg order `cid` on `instrument` as fully filled with `quantity`, without
+    /// its exchange order id.
+    ///
+    /// Unlinked fills on `instrument` are attributed to it until `quantity` is reached,
+    /// now or as they arrive.
+    pub fn link_fully_filled(
+        &mut self,
+        cid: &ClientOrderId,
+        instrument: InstrumentIndex,
+        quantity: Decimal,
+        now: DateTime<Utc>,
+    ) {
+        let Some(leg) = ArbLeg::from_cid(cid) else {
+            return;
+        };
+        let pending_ids = self
+            .pending
+            .iter()
+            .filter(|(_, fills)| fills.iter().any(|fill| fill.instrument == instrument))
+            .map(|(id, _)| id.clone())
+            .collect::<Vec<_>>();
+        for id in pending_ids {
+            if self.filled_quantity(cid) >= quantity {
+                return;
+            }
+            self.link_order(&id, cid);
+        }
+
+        if self.filled_quantity(cid) < quantity {
+            self.awaiting.entry(instrument).or_default().push(AwaitingFills {
+                leg,
+                cid: cid.clone(),
+                quantity,
+                since: now,
+            });
+        }
+    }
+
+    /// Apply a trade to its pair's position, or hold it until its order is linked.
+    pub fn apply_trade<AssetKey>(&mut self, trade: &Trade<AssetKey, InstrumentIndex>) {
+        self.apply_trade_at(trade, Utc::now())
+    }
+
+    fn apply_trade_at<AssetKey>(
+        &mut self,
+        trade: &Trade<AssetKey, InstrumentIndex>,
+        now: DateTime<Utc>,
+    ) {
+        self.expire(now);
+        let fill = PendingFill {
+            instrument: trade.instrument,
+            side: trade.side,
+            price: trade.price,
+            quantity: trade.quantity.abs(),
+            fees: trade.fees.fees,
+            received: now,
+        };
+        if let Some((leg, cid)) = self.order_legs.get(&trade.order_id) {
+            let (leg, cid) = (*leg, cid.clone());
+            self.apply_fill(leg, cid, fill);
+            return;
+        }
+
+        // First fill of an order reported fully filled without its exchange id
+        if let Some(order) = self
+            .awaiting
+            .get(&trade.instrument)
+            .and_then(|awaiting| awaiting.first())
+        {
+            let (leg, cid) = (order.leg, order.cid.clone());
+            self.order_legs.insert(trade.order_id.clone(), (leg, cid.clone()));
+            self.apply_fill(leg, cid, fill);
+            return;
+        }
+
+        self.pending.entry(trade.order_id.clone()).or_default().push(fill);
+    }
+
+    /// Drop fills and fully filled orders left unattributed for [`PENDING_FILL_TTL`].
+    fn expire(&mut self, now: DateTime<Utc>) {
+        self.pending.retain(|id, fills| {
+            let expired = fills.iter().all(|fill| now - fill.received > PENDING_FILL_TTL);
+            if expired {
+                warn!(order_id = %id, "Dropping fills never linked to an arbitrage leg");
+            }
+            !expired
+        });
+        self.awaiting.retain(|_, awaiting| {
+            awaiting.retain(|order| now - order.since <= PENDING_FILL_TTL);
+            !awaiting.is_empty()
+        });
+    }
+
+    fn apply_fill(&mut self, leg: ArbLeg, cid: ClientOrderId, fill: PendingFill) {
+        *self.leg_fills.entry(cid).or_default() += fill.quantity;
+        if let Some(awaiting) = self.awaiting.get_mut(&fill.instrument) {
+            let leg_fills = &self.leg_fills;
+            awaiting.retain(|order| {
+                leg_fills.get(&order.cid).copied().unwrap_or_default() < order.quantity
+            });
+        }
+        let Some(ticker) = self.instrument_pairs.get(&fill.instrument) else {
+            debug!(instrument = ?fill.instrument, "Fill for an instrument outside any pair");
+            return;
+        };
+        self.positions.entry(ticker.clone()).or_default().apply_fill(
+            leg,
+            fill.side,
+            fill.price,
+            fill.quantity,
+            fill.fees,
+        );
+    }
+}
+
+/// Cumulative size of the `levels` priced within `band` of the best (first) level.
+///
+/// Levels must be sorted best first, as on either side of an [`OrderBook`], so
+/// this works for descending bids and ascending asks alike.
+pub fn depth_within(levels: &[Level], band: Decimal) -> Decimal {
+    let Some(best) = levels.first() else {
+        return Decimal::ZERO;
+    };
+    levels
+        .iter()
+        .take_while(|level| (level.price - best.price).abs() <= band)
+        .map(|level| level.amount)
+        .sum()
+}
+
+/// Whether applying `deltas` would change any of the `current` levels (a zero
+/// amount removes a level).
+fn changes_levels(current: &[Level], deltas: &[Level]) -> bool {
+    deltas.iter().any(|delta| {
+        match current.iter().find(|level| level.price == delta.price) {
+            Some(level) => level.amount != delta.amount,
+            None => !delta.amount.is_zero(),
+        }
+    })
+}
+
+/// How an instrument's position is valued.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum PositionMode {
+    /// Marked to the mid price of the orderbook.
+    #[default]
+    MarkToMarket,
+    /// A leg of a delta-neutral pair held until settlement, whose profit is
+    /// locked at `$1 - cost` per matched contract; mid price moves are noise.
+    HeldToSettlement,
+}
+
+impl PositionMode {
+    pub fn is_held_to_settlement(&self) -> bool {
+        matches!(self, PositionMode::HeldToSettlement)
+    }
+}
+
 /// Per-instrument data for the arbitrage strategy.
 #[derive(Debug, Default, Clone, Deserialize, Serialize)]
 pub struct ArbitrageInstrumentData {
@@ -66,12 +557,78 @@ pub struct ArbitrageInstrumentData {
     pub avg_entry: Option<Decimal>,
     /// Total cost basis for the position
     pub cost_basis: Decimal,
+    /// Cumulative realized P&L (closed quantity P&L net of fees)
+    #[serde(default)]
+    pub realized_pnl: Decimal,
+    /// Bumped whenever the orderbook's levels change; the strategy treats a pair
+    /// as dirty when any of its books' versions differ from its last scan
+    #[serde(skip)]
+    pub book_version: u64,
+    /// When the latest orderbook event for this instrument was received
+    #[serde(skip)]
+    pub book_updated: Option<DateTime<Utc>>,
+    /// Latest market status reported by the exchange, if any
+    #[serde(default)]
+    pub market_status: Option<MarketStatus>,
+    /// Set when the exchange reports the market is no longer open, eg/ a Kalshi pause,
+    /// which keeps the book's levels though they may not be tradable. Cleared by the next
+    /// orderbook event or an open status
+    #[serde(skip)]
+    pub book_stale: bool,
+    /// How the position is valued, see [`ArbPosition::pnl`]
+    #[serde(default)]
+    pub position_mode: PositionMode,
+    /// Exchange quantity of the latest position snapshot that differed from the local
+    /// position, cleared by any fill; a second matching snapshot confirms the drift
+    #[serde(skip)]
+    pub position_drift: Option<Decimal>,
 }
 
 impl ArbitrageInstrumentData {
+    /// Value the position with `mode`.
+    pub fn with_position_mode(mut self, mode: PositionMode) -> Self {
+        self.position_mode = mode;
+        self
+    }
+
     /// Update the orderbook for this instrument.
-    pub fn update_orderbook(&mut self, orderbook: OrderBook) {
-        self.orderbook = Some(orderbook);
+    ///
+    /// Snapshots replace the book. Updates are deltas merged level by level (zero
+    /// size removes a level); an update with an older sequence than the stored
+    /// book is ignored, as is any update before the first snapshot, since deltas
+    /// alone would build a partial book.
+    pub fn update_orderbook(&mut self, event: &OrderBookEvent) {
+        let changed = match (event, &mut self.orderbook) {
+            (OrderBookEvent::Snapshot(snapshot), current) => {
+                let changed = current.as_ref().is_none_or(|book| {
+                    book.bids() != snapshot.bids() || book.asks() != snapshot.asks()
+                });
+                *current = Some(snapshot.clone());
+                changed
+            }
+            (OrderBookEvent::Update(update), Some(book)) => {
+                if update.sequence() < book.sequence() {
+                    debug!(
+                        stored = book.sequence(),
+                        update = update.sequence(),
+                        "Ignoring out of order orderbook update"
+                    );
+                    return;
+                }
+                let changed = changes_levels(book.bids().levels(), update.bids().levels())
+                    || changes_levels(book.asks().levels(), update.asks().levels());
+                book.update(event);
+                changed
+            }
+            (OrderBookEvent::Update(_), None) => {
+                debug!("Ignoring orderbook update received before a snapshot");
+                return;
+            }
+        };
+
+        if changed {
+            self.book_version += 1;
+        }
     }
 
     /// Get the best bid price.
@@ -95,6 +652,29 @@ impl ArbitrageInstrumentData {
         self.orderbook.as_ref().and_then(|b| b.mid_price())
     }
 
+    /// Contracts offered within `band` of the best ask (zero without a book).
+    pub fn ask_depth_within(&self, band: Decimal) -> Decimal {
+        self.orderbook
+            .as_ref()
+            .map_or(Decimal::ZERO, |b| depth_within(b.asks().levels(), band))
+    }
+
+    /// Contracts bid within `band` of the best bid (zero without a book).
+    pub fn bid_depth_within(&self, band: Decimal) -> Decimal {
+        self.orderbook
+            .as_ref()
+            .map_or(Decimal::ZERO, |b| depth_within(b.bids().levels(), band))
+    }
+
+    /// Replace the position with `quantity` contracts costing `cost_basis`, as
+    /// reported by an exchange.
+    pub fn set_position(&mut self, quantity: Decimal, cost_basis: Decimal) {
+        // Contracts are whole units on both venues; round away any fractional dust
+        self.position = quantity.round().try_into().unwrap_or(i32::MAX);
+        self.cost_basis = cost_basis;
+        self.avg_entry = (quantity > Decimal::ZERO).then(|| cost_basis / quantity);
+    }
+
     /// Update position after a fill.
     pub fn update_position(&mut self, quantity: i32, price: Decimal) {
         if quantity == 0 {
@@ -118,6 +698,10 @@ impl ArbitrageInstrumentData {
             let reduce_by = quantity.abs().min(self.position.abs());
             let remaining = self.position.abs() - reduce_by;
 
+            let entry = self.avg_entry.unwrap_or(price);
+            let closed_pnl = (price - entry) * Decimal::from(reduce_by);
+            self.realized_pnl += if self.position > 0 { closed_pnl } else { -closed_pnl };
+
             if remaining == 0 {
                 // Position fully closed
                 if new_position == 0 {
@@ -140,7 +724,14 @@ impl ArbitrageInstrumentData {
     }
 
     /// Calculate unrealized P&L.
+    ///
+    /// Always zero for a [`PositionMode::HeldToSettlement`] leg, whose profit
+    /// only means something for the pair, see [`ArbPosition::pnl`].
     pub fn unrealized_pnl(&self) -> Option<Decimal> {
+        if self.position_mode.is_held_to_settlement() {
+            return Some(Decimal::ZERO);
+        }
+
         let current_price = self.mid_price()?;
         let avg_entry = self.avg_entry?;
 
@@ -162,7 +753,11 @@ impl InstrumentDataState for ArbitrageInstrumentData {
     type MarketEventKind = DataKind;
 
     fn price(&self) -> Option<Decimal> {
-        self.mid_price()
+        // Pricing a settlement leg at its entry keeps the engine's unrealised P&L flat
+        match self.position_mode {
+            PositionMode::MarkToMarket => self.mid_price(),
+            PositionMode::HeldToSettlement => self.avg_entry.or_else(|| self.mid_price()),
+        }
     }
 }
 
@@ -174,11 +769,13 @@ impl<InstrumentKey> Processor<&MarketEvent<InstrumentKey, DataKind>>
     fn process(&mut self, event: &MarketEvent<InstrumentKey, DataKind>) -> Self::Audit {
         match &event.kind {
             DataKind::OrderBook(book_event) => {
-                let book = match book_event {
-                    OrderBookEvent::Snapshot(book) => book.clone(),
-                    OrderBookEvent::Update(book) => book.clone(),
-                };
-                self.update_orderbook(book);
+                self.update_orderbook(book_event);
+                self.book_updated = Some(event.time_received);
+                self.book_stale = false;
+            }
+            DataKind::MarketStatus(status) => {
+                self.market_status = Some(*status);
+                self.book_stale = *status != MarketStatus::Open;
             }
             _ => {}
         }
@@ -213,6 +810,39 @@ where
                     "Trade fill received"
                 );
                 self.update_position(signed_qty, trade.price);
+                self.realized_pnl -= trade.fees.fees;
+                self.position_drift = None;
+            }
+            AccountEventKind::PositionSnapshot(snapshot) => {
+                // Only a quantity mismatch is drift; cost bases may differ by fee treatment
+                let position = &snapshot.0;
+                if Decimal::from(self.position) == position.quantity {
+                    self.position_drift = None;
+                    return;
+                }
+
+                // Fills racing the snapshot request would be double counted by an
+                // overwrite, so only a mismatch repeated without fills in between is drift
+                if self.position_drift != Some(position.quantity) {
+                    warn!(
+                        instrument = ?position.instrument,
+                        local = self.position,
+                        exchange_quantity = %position.quantity,
+                        "Position differs from exchange, awaiting the next snapshot"
+                    );
+                    self.position_drift = Some(position.quantity);
+                    return;
+                }
+
+                warn!(
+                    instrument = ?position.instrument,
+                    local = self.position,
+                    exchange_quantity = %position.quantity,
+                    exchange_cost_basis = %position.cost_basis,
+                    "Position diverged from exchange, overwriting local position"
+                );
+                self.set_position(position.quantity, position.cost_basis);
+                self.position_drift = None;
             }
             _ => {}
         }
@@ -245,24 +875,71 @@ where
     }
 }
 
-impl<InstrumentKey, Kind> Processor<&MarketEvent<InstrumentKey, Kind>>
-    for ArbitrageGlobalData
-{
+impl<InstrumentKey> Processor<&MarketEvent<InstrumentKey, DataKind>> for ArbitrageGlobalData {
     type Audit = ();
-    fn process(&mut self, _: &MarketEvent<InstrumentKey, Kind>) -> Self::Audit {}
+
+    fn process(&mut self, event: &MarketEvent<InstrumentKey, DataKind>) -> Self::Audit {
+        if let (Some(metrics), DataKind::OrderBook(_)) = (&self.metrics, &event.kind) {
+            metrics.orderbook_event(event.exchange);
+        }
+    }
 }
 
-impl<ExchangeKey, AssetKey, InstrumentKey> Processor<&AccountEvent<ExchangeKey, AssetKey, InstrumentKey>>
+impl<AssetKey> Processor<&AccountEvent<ExchangeIndex, AssetKey, InstrumentIndex>>
     for ArbitrageGlobalData
 {
     type Audit = ();
 
     fn process(
         &mut self,
-        event: &AccountEvent<ExchangeKey, AssetKey, InstrumentKey>,
+        event: &AccountEvent<ExchangeIndex, AssetKey, InstrumentIndex>,
     ) -> Self::Audit {
         match &event.kind {
+            AccountEventKind::OrderSnapshot(snapshot) => {
+                let order = &snapshot.0;
+                match &order.state {
+                    OrderState::Active(ActiveOrderState::Open(open)) => {
+                        self.arb_positions.link_order(&open.id, &order.key.cid)
+                    }
+                    OrderState::Inactive(InactiveOrderState::Cancelled(cancelled)) => {
+                        self.arb_positions.link_order(&cancelled.id, &order.key.cid)
+                    }
+                    OrderState::Inactive(InactiveOrderState::FullyFilled) => {
+                        self.arb_positions.link_fully_filled(
+                            &order.key.cid,
+                            order.key.instrument,
+                            order.quantity,
+                            Utc::now(),
+                        )
+                    }
+                    _ => {}
+                }
+
+                let success = match &snapshot.0.state {
+                    OrderState::Inactive(InactiveOrderState::OpenFailed(_)) => Some(false),
+                    OrderState::Active(ActiveOrderState::Open(_))
+                    | OrderState::Inactive(InactiveOrderState::FullyFilled) => Some(true),
+                    _ => None,
+                };
+                if let Some(success) = success {
+                    self.circuit_breaker
+                        .record_order_result(event.exchange, success, Utc::now());
+                }
+                if let (Some(metrics), Some(false), Some(&exchange)) =
+                    (&self.metrics, success, self.exchange_ids.get(&event.exchange))
+                {
+                    metrics.order_rejected(exchange);
+                }
+            }
             AccountEventKind::Trade(trade) => {
+                self.circuit_breaker
+                    .record_order_result(event.exchange, true, Utc::now());
+                self.arb_positions.apply_trade(trade);
+                if let (Some(metrics), Some(&exchange)) =
+                    (&self.metrics, self.exchange_ids.get(&event.exchange))
+                {
+                    metrics.order_filled(exchange);
+                }
                 // Buy = deploying capital, Sell = releasing capital
                 let trade_value = trade.price * trade.quantity.abs();
                 match trade.side {
@@ -271,10 +948,27 @@ impl<ExchangeKey, AssetKey, InstrumentKey> Processor<&AccountEvent<ExchangeKey,
                 }
             }
             AccountEventKind::BalanceSnapshot(balance) => {
-                // Update platform-specific balance from polling.
-                // We can't distinguish Kalshi vs Poly here since ExchangeKey is generic,
-                // but the engine tracks balances in its own asset state anyway.
-                let _ = balance;
+                let Some(&exchange) = self.exchange_ids.get(&event.exchange) else {
+                    return;
+                };
+                let snapshot = &balance.0;
+
+                // Ignore snapshots older than the one already applied
+                if self
+                    .last_balance_update
+                    .get(&exchange)
+                    .is_some_and(|updated| *updated > snapshot.time_exchange)
+                {
+                    return;
+                }
+
+                // Kalshi balances are USD and Polymarket balances USDC, one asset each
+                match exchange {
+                    ExchangeId::Kalshi => self.kalshi_balance = snapshot.balance.free,
+                    ExchangeId::Polymarket => self.polymarket_balance = snapshot.balance.free,
+                    _ => return,
+                }
+                self.last_balance_update.insert(exchange, snapshot.time_exchange);
             }
             _ => {}
         }
@@ -310,28 +1004,300 @@ impl OrderbookLookup {
     }
 }
 
+/// Map from market data stream instruments to engine instrument indices.
+///
+/// Keyed by `(exchange, subscription base)`, lowercased: the Kalshi ticker or
+/// Polymarket YES token, since streams deliver YES books.
+pub type MarketDataLookup = HashMap<(ExchangeId, String), InstrumentIndex>;
+
+/// Map each engine instrument of `pairs` to its pair's Kalshi ticker.
+///
+/// Instruments missing from `indexed` are skipped.
+pub fn pair_instruments(
+    pairs: &[CorrelatedPair],
+    indexed: &IndexedInstruments,
+) -> HashMap<InstrumentIndex, SmolStr> {
+    let name_to_index: HashMap<(ExchangeId, &str), InstrumentIndex> = indexed
+        .instruments()
+        .iter()
+        .map(|keyed| {
+            (
+                (
+                    keyed.value.exchange.value,
+                    keyed.value.name_exchange.name().as_str(),
+                ),
+                keyed.key,
+            )
+        })
+        .collect();
+
+    let mut instrument_pairs = HashMap::new();
+    for pair in pairs {
+        for key in pair.instrument_keys() {
+            let name = key.to_instrument_name();
+            if let Some(&index) = name_to_index.get(&(key.exchange, name.as_str())) {
+                instrument_pairs.insert(index, pair.kalshi_ticker.clone());
+            }
+        }
+    }
+    instrument_pairs
+}
+
+/// Build the [`MarketDataLookup`] for `pairs`, resolving instruments through
+/// [`PredictionMarketKey::to_instrument_name`].
+///
+/// Covers the YES markets and the Polymarket NO token, which has its own token id
+/// and book. Kalshi NO shares the YES ticker, so it can't be told apart by base.
+/// Pairs whose instruments are missing from `indexed` are skipped.
+pub fn market_data_lookup(
+    pairs: &[CorrelatedPair],
+    indexed: &IndexedInstruments,
+) -> MarketDataLookup {
+    let name_to_index: HashMap<(ExchangeId, &str), InstrumentIndex> = indexed
+        .instruments()
+        .iter()
+        .map(|keyed| {
+            (
+                (
+                    keyed.value.exchange.value,
+                    keyed.value.name_exchange.name().as_str(),
+                ),
+                keyed.key,
+            )
+        })
+        .collect();
+
+    let mut lookup = MarketDataLookup::new();
+    for pair in pairs {
+        for key in [
+            PredictionMarketKey::kalshi_yes(pair.kalshi_ticker.clone()),
+            PredictionMarketKey::polymarket_yes(pair.polymarket_yes_token.clone()),
+            PredictionMarketKey::polymarket_no(pair.polymarket_no_token.clone()),
+        ] {
+            let name = key.to_instrument_name();
+            if let Some(&index) = name_to_index.get(&(key.exchange, name.as_str())) {
+                lookup.insert((key.exchange, key.market_id.to_lowercase()), index);
+            }
+        }
+    }
+    lookup
+}
+
+/// Tick size of each of `exchange`'s instruments in `indexed`, by exchange name, from
+/// their instrument specs.
+///
+/// Instruments without a spec are left out, so the execution client falls back to its
+/// default tick size.
+pub fn instrument_tick_sizes(
+    indexed: &IndexedInstruments,
+    exchange: ExchangeId,
+) -> HashMap<InstrumentNameExchange, Decimal> {
+    indexed
+        .instruments()
+        .iter()
+        .map(|keyed| &keyed.value)
+        .filter(|instrument| instrument.exchange.value == exchange)
+        .filter_map(|instrument| {
+            let spec = instrument.spec.as_ref()?;
+            Some((instrument.name_exchange.clone(), spec.price.tick_size))
+        })
+        .collect()
+}
+
+/// Minimum order quantity of each of `exchange`'s instruments in `indexed`, by exchange name,
+/// from their instrument specs.
+///
+/// Instruments without a spec are left out, so the execution client only enforces its
+/// minimum order value.
+pub fn instrument_min_quantities(
+    indexed: &IndexedInstruments,
+    exchange: ExchangeId,
+) -> HashMap<InstrumentNameExchange, Decimal> {
+    indexed
+        .instruments()
+        .iter()
+        .map(|keyed| &keyed.value)
+        .filter(|instrument| instrument.exchange.value == exchange)
+        .filter_map(|instrument| {
+            let spec = instrument.spec.as_ref()?;
+            Some((instrument.name_exchange.clone(), spec.quantity.min))
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use rust_decimal_macros::dec;
 
+    #[test]
+    fn test_arb_leg_parse_cid() {
+        let yes = ArbLeg::Yes.cid("prediction-arb_7");
+        assert_eq!(yes, ClientOrderId::new("prediction-arb_7_yes"));
+        assert_eq!(ArbLeg::parse_cid(&yes), Some(("prediction-arb_7", ArbLeg::Yes)));
+        assert_eq!(
+            ArbLeg::parse_cid(&ArbLeg::No.cid("prediction-arb_7")),
+            Some(("prediction-arb_7", ArbLeg::No))
+        );
+
+        assert_eq!(ArbLeg::parse_cid(&ClientOrderId::new("manual-order")), None);
+        assert_eq!(ArbLeg::parse_cid(&ClientOrderId::new("_yes")), None);
+        assert_eq!(ArbLeg::from_cid(&ClientOrderId::new("arb_1_maybe")), None);
+
+        let flatten = ArbLeg::No.flatten_cid("prediction-arb_7", 2);
+        assert_eq!(flatten, ClientOrderId::new("prediction-arb_7_flatten2_no"));
+        assert_eq!(ArbLeg::from_cid(&flatten), Some(ArbLeg::No));
+        assert!(ArbLeg::is_flatten_cid(&flatten));
+        assert!(!ArbLeg::is_flatten_cid(&yes));
+        assert!(!ArbLeg::is_flatten_cid(&ClientOrderId::new("flatten1_yes")));
+    }
+
     #[test]
     fn test_global_data_capital_management() {
         let mut global = ArbitrageGlobalData {
             total_deployed: Decimal::ZERO,
             kalshi_balance: dec!(5000),
             polymarket_balance: dec!(5000),
+            ..Default::default()
         };
 
-        assert_eq!(global.available_capital(), dec!(10000));
+        // Unknown until both exchanges reported a balance
+        assert_eq!(global.available_capital(), None);
+        global.last_balance_update.insert(ExchangeId::Kalshi, Utc::now());
+        assert_eq!(global.available_capital(), None);
+        global.last_balance_update.insert(ExchangeId::Polymarket, Utc::now());
+        assert_eq!(global.available_capital(), Some(dec!(10000)));
 
         global.reserve_capital(dec!(2000));
         assert_eq!(global.total_deployed, dec!(2000));
-        assert_eq!(global.available_capital(), dec!(8000));
 
         global.release_capital(dec!(1000));
         assert_eq!(global.total_deployed, dec!(1000));
-        assert_eq!(global.available_capital(), dec!(9000));
+    }
+
+    #[test]
+    fn test_instrument_tick_sizes_and_min_quantities_from_specs() {
+        use barter_instrument::{
+            Underlying,
+            instrument::{
+                Instrument,
+                spec::{
+                    InstrumentSpec, InstrumentSpecNotional, InstrumentSpecPrice,
+                    InstrumentSpecQuantity, OrderQuantityUnits,
+                },
+            },
+        };
+
+        let spec = |tick_size, min_quantity| {
+            Some(InstrumentSpec::new(
+                InstrumentSpecPrice::new(tick_size, tick_size),
+                InstrumentSpecQuantity::new(OrderQuantityUnits::Contract, min_quantity, dec!(1)),
+                InstrumentSpecNotional::new(dec!(0.01)),
+            ))
+        };
+        let instrument = |exchange, name: &str, spec| {
+            Instrument::spot(exchange, name, name, Underlying::new(name, "usd"), spec)
+        };
+        let indexed = IndexedInstruments::builder()
+            .add_instrument(instrument(ExchangeId::Kalshi, "KXSUB_yes", spec(dec!(0.001), dec!(1))))
+            .add_instrument(instrument(ExchangeId::Kalshi, "KXCENT_yes", spec(dec!(0.01), dec!(1))))
+            .add_instrument(instrument(ExchangeId::Kalshi, "KXNONE_yes", None))
+            .add_instrument(instrument(ExchangeId::Polymarket, "0xtoken", spec(dec!(0.001), dec!(5))))
+            .build();
+
+        let tick_sizes = instrument_tick_sizes(&indexed, ExchangeId::Kalshi);
+        assert_eq!(
+            tick_sizes,
+            HashMap::from([
+                (InstrumentNameExchange::from("KXSUB_yes"), dec!(0.001)),
+                (InstrumentNameExchange::from("KXCENT_yes"), dec!(0.01)),
+            ])
+        );
+
+        let min_quantities = instrument_min_quantities(&indexed, ExchangeId::Polymarket);
+        assert_eq!(
+            min_quantities,
+            HashMap::from([(InstrumentNameExchange::from("0xtoken"), dec!(5))])
+        );
+    }
+
+    fn global_with_exchanges() -> ArbitrageGlobalData {
+        use barter_instrument::{Underlying, instrument::Instrument};
+
+        let indexed = IndexedInstruments::builder()
+            .add_instrument(Instrument::spot(
+                ExchangeId::Kalshi,
+                "kalshi_KXTEST_yes",
+                "KXTEST_yes",
+                Underlying::new("KXTEST_yes", "usd"),
+                None,
+            ))
+            .add_instrument(Instrument::spot(
+                ExchangeId::Polymarket,
+                "poly_0xno_tok",
+                "0xno_token",
+                Underlying::new("0xno_token", "usdc"),
+                None,
+            ))
+            .build();
+        ArbitrageGlobalData::default().with_exchanges(&indexed)
+    }
+
+    fn balance_snapshot(
+        exchange: usize,
+        free: Decimal,
+        secs: i64,
+    ) -> AccountEvent<ExchangeIndex, barter_instrument::asset::AssetIndex, InstrumentIndex> {
+        use barter_execution::balance::{AssetBalance, Balance};
+        use barter_instrument::asset::AssetIndex;
+        use barter_integration::snapshot::Snapshot;
+        use chrono::TimeZone;
+
+        AccountEvent {
+            exchange: ExchangeIndex(exchange),
+            kind: AccountEventKind::BalanceSnapshot(Snapshot(AssetBalance {
+                asset: AssetIndex(exchange),
+                balance: Balance::new(free, free),
+                time_exchange: Utc.timestamp_opt(secs, 0).unwrap(),
+            })),
+        }
+    }
+
+    #[test]
+    fn test_balance_snapshot_records_update_time() {
+        use chrono::TimeZone;
+
+        let mut global = global_with_exchanges();
+        global.process(&balance_snapshot(0, dec!(100), 100));
+        // Out of order snapshots are ignored
+        global.process(&balance_snapshot(0, dec!(50), 50));
+
+        let now = Utc.timestamp_opt(160, 0).unwrap();
+        assert_eq!(
+            global.balance_age(ExchangeId::Kalshi, now),
+            Some(chrono::TimeDelta::seconds(60))
+        );
+        assert_eq!(global.kalshi_balance, dec!(100));
+        assert_eq!(global.balance_age(ExchangeId::Polymarket, now), None);
+    }
+
+    #[test]
+    fn test_balance_snapshots_update_exchanges_independently() {
+        let mut global = global_with_exchanges();
+        assert_eq!(global.exchange_balance(ExchangeId::Kalshi), None);
+
+        global.process(&balance_snapshot(0, dec!(1200), 1));
+        global.process(&balance_snapshot(1, dec!(800), 2));
+        assert_eq!(global.exchange_balance(ExchangeId::Kalshi), Some(dec!(1200)));
+        assert_eq!(global.exchange_balance(ExchangeId::Polymarket), Some(dec!(800)));
+
+        global.process(&balance_snapshot(1, dec!(750), 3));
+        assert_eq!(global.kalshi_balance, dec!(1200));
+        assert_eq!(global.polymarket_balance, dec!(750));
+
+        // Fills spend free balance, which the next snapshots report
+        global.reserve_capital(dec!(500));
+        assert_eq!(global.available_capital(), Some(dec!(1950)));
     }
 
     #[test]
@@ -355,6 +1321,132 @@ mod tests {
         assert_eq!(data.position, 100);
     }
 
+    #[test]
+    fn test_instrument_data_realized_pnl() {
+        let mut data = ArbitrageInstrumentData::default();
+
+        data.update_position(100, dec!(0.40));
+        assert_eq!(data.realized_pnl, Decimal::ZERO);
+
+        // Close 50 at a loss of 0.10 per contract
+        data.update_position(-50, dec!(0.30));
+        assert_eq!(data.realized_pnl, dec!(-5.0));
+
+        // Close remaining 50 at a gain of 0.20 per contract
+        data.update_position(-50, dec!(0.60));
+        assert_eq!(data.realized_pnl, dec!(5.0));
+        assert_eq!(data.position, 0);
+    }
+
+    fn book(sequence: u64, bids: &[(Decimal, Decimal)], asks: &[(Decimal, Decimal)]) -> OrderBook {
+        OrderBook::new(sequence, None, bids.to_vec(), asks.to_vec())
+    }
+
+    type Levels = Vec<(Decimal, Decimal)>;
+
+    fn levels(book: &OrderBook) -> (Levels, Levels) {
+        (
+            book.bids().levels().iter().map(|l| (l.price, l.amount)).collect(),
+            book.asks().levels().iter().map(|l| (l.price, l.amount)).collect(),
+        )
+    }
+
+    #[test]
+    fn test_orderbook_update_merges_levels() {
+        let mut data = ArbitrageInstrumentData::default();
+        data.update_orderbook(&OrderBookEvent::Snapshot(book(
+            1,
+            &[(dec!(0.45), dec!(100)), (dec!(0.44), dec!(50))],
+            &[(dec!(0.47), dec!(100))],
+        )));
+
+        // Adds a bid level, keeping the others
+        data.update_orderbook(&OrderBookEvent::Update(book(2, &[(dec!(0.46), dec!(10))], &[])));
+        // Removes an ask level via zero size and replaces a bid level
+        data.update_orderbook(&OrderBookEvent::Update(book(
+            3,
+            &[(dec!(0.44), dec!(75))],
+            &[(dec!(0.47), dec!(0)), (dec!(0.48), dec!(20))],
+        )));
+
+        let (bids, asks) = levels(data.orderbook.as_ref().unwrap());
+        assert_eq!(
+            bids,
+            vec![
+                (dec!(0.46), dec!(10)),
+                (dec!(0.45), dec!(100)),
+                (dec!(0.44), dec!(75)),
+            ]
+        );
+        assert_eq!(asks, vec![(dec!(0.48), dec!(20))]);
+        assert_eq!(data.orderbook.as_ref().unwrap().sequence(), 3);
+    }
+
+    #[test]
+    fn test_orderbook_update_out_of_order_ignored() {
+        let mut data = ArbitrageInstrumentData::default();
+        data.update_orderbook(&OrderBookEvent::Snapshot(book(
+            5,
+            &[(dec!(0.45), dec!(100))],
+            &[(dec!(0.47), dec!(100))],
+        )));
+
+        data.update_orderbook(&OrderBookEvent::Update(book(4, &[(dec!(0.45), dec!(0))], &[])));
+
+        assert_eq!(data.best_bid(), Some(dec!(0.45)));
+        assert_eq!(data.orderbook.as_ref().unwrap().sequence(), 5);
+    }
+
+    #[test]
+    fn test_orderbook_update_before_snapshot_ignored() {
+        let mut data = ArbitrageInstrumentData::default();
+        data.update_orderbook(&OrderBookEvent::Update(book(1, &[(dec!(0.45), dec!(10))], &[])));
+        assert!(data.orderbook.is_none());
+        assert_eq!(data.book_version, 0);
+
+        data.update_orderbook(&OrderBookEvent::Snapshot(book(
+            2,
+            &[(dec!(0.44), dec!(50))],
+            &[(dec!(0.47), dec!(100))],
+        )));
+        assert_eq!(data.best_bid(), Some(dec!(0.44)));
+    }
+
+    #[test]
+    fn test_depth_within() {
+        let mut data = ArbitrageInstrumentData::default();
+        assert_eq!(data.ask_depth_within(dec!(0.02)), Decimal::ZERO);
+
+        data.update_orderbook(&OrderBookEvent::Snapshot(book(
+            1,
+            &[(dec!(0.45), dec!(10)), (dec!(0.44), dec!(20)), (dec!(0.40), dec!(500))],
+            &[(dec!(0.47), dec!(5)), (dec!(0.49), dec!(15)), (dec!(0.50), dec!(1000))],
+        )));
+
+        // Levels exactly on the band edge count, anything beyond does not
+        assert_eq!(data.ask_depth_within(dec!(0.02)), dec!(20));
+        assert_eq!(data.bid_depth_within(dec!(0.02)), dec!(30));
+        assert_eq!(data.ask_depth_within(Decimal::ZERO), dec!(5));
+        assert_eq!(data.bid_depth_within(dec!(0.05)), dec!(530));
+    }
+
+    #[test]
+    fn test_orderbook_snapshot_resets() {
+        let mut data = ArbitrageInstrumentData::default();
+        data.update_orderbook(&OrderBookEvent::Snapshot(book(
+            5,
+            &[(dec!(0.45), dec!(100)), (dec!(0.44), dec!(50))],
+            &[(dec!(0.47), dec!(100))],
+        )));
+
+        // Snapshots replace the book whatever their sequence
+        data.update_orderbook(&OrderBookEvent::Snapshot(book(1, &[(dec!(0.40), dec!(5))], &[])));
+
+        let (bids, asks) = levels(data.orderbook.as_ref().unwrap());
+        assert_eq!(bids, vec![(dec!(0.40), dec!(5))]);
+        assert!(asks.is_empty());
+    }
+
     #[test]
     fn test_orderbook_lookup() {
         use barter_data::books::Level;
@@ -374,4 +1466,249 @@ mod tests {
         assert!(lookup.get(&key).is_some());
         assert!(lookup.get(&PredictionMarketKey::kalshi_no("TEST-MARKET")).is_none());
     }
+
+    fn arb_positions_global() -> ArbitrageGlobalData {
+        use crate::correlation::CorrelatedPair;
+        use barter_instrument::{Underlying, instrument::Instrument};
+
+        let pair = CorrelatedPair::new(
+            "KXTEST",
+            "0xcond",
+            "0xyes",
+            "0xno",
+            "Test market",
+            Utc::now() + chrono::Duration::days(30),
+            false,
+        );
+        // Instruments are indexed in `instrument_keys` order: Kalshi YES/NO, Poly YES/NO
+        let mut builder = IndexedInstruments::builder();
+        for key in pair.instrument_keys() {
+            let name = key.to_instrument_name();
+            builder = builder.add_instrument(Instrument::spot(
+                key.exchange,
+                format!("{}_{}", key.exchange.as_str(), name),
+                name.as_str(),
+                Underlying::new(name.as_str(), "usd"),
+                None,
+            ));
+        }
+        let indexed = builder.build();
+        ArbitrageGlobalData::default()
+            .with_exchanges(&indexed)
+            .with_pairs(&[pair], &indexed)
+    }
+
+    type TestAccountEvent =
+        AccountEvent<ExchangeIndex, barter_instrument::asset::AssetIndex, InstrumentIndex>;
+
+    fn order_snapshot(instrument: usize, cid: &str, state: OrderState) -> TestAccountEvent {
+        use barter_execution::order::{Order, OrderKey, OrderKind, TimeInForce, id::StrategyId};
+        use barter_integration::snapshot::Snapshot;
+
+        AccountEvent {
+            exchange: ExchangeIndex(0),
+            kind: AccountEventKind::OrderSnapshot(Snapshot(Order {
+                key: OrderKey {
+                    exchange: ExchangeIndex(0),
+                    instrument: InstrumentIndex(instrument),
+                    strategy: StrategyId::new("test-arb"),
+                    cid: ClientOrderId::new(cid),
+                },
+                side: Side::Buy,
+                price: dec!(0.50),
+                quantity: dec!(10),
+                kind: OrderKind::Limit,
+                time_in_force: TimeInForce::ImmediateOrCancel,
+                state,
+            })),
+        }
+    }
+
+    fn order_opened(instrument: usize, cid: &str, id: &str) -> TestAccountEvent {
+        use barter_execution::order::state::Open;
+
+        let open = Open {
+            id: OrderId::new(id),
+            time_exchange: Utc::now(),
+            filled_quantity: Decimal::ZERO,
+        };
+        order_snapshot(instrument, cid, OrderState::active(open))
+    }
+
+    /// As the execution manager reports an order filled in full on submission.
+    fn order_fully_filled(instrument: usize, cid: &str) -> TestAccountEvent {
+        order_snapshot(instrument, cid, OrderState::fully_filled())
+    }
+
+    fn fill(instrument: usize, id: &str, price: Decimal, quantity: Decimal) -> TestAccountEvent {
+        use barter_execution::{
+            order::id::StrategyId,
+            trade::{AssetFees, TradeId},
+        };
+        use barter_instrument::asset::QuoteAsset;
+
+        AccountEvent {
+            exchange: ExchangeIndex(0),
+            kind: AccountEventKind::Trade(Trade {
+                id: TradeId::new(format!("{id}-fill")),
+                order_id: OrderId::new(id),
+                instrument: InstrumentIndex(instrument),
+                strategy: StrategyId::new("test-arb"),
+                time_exchange: Utc::now(),
+                side: Side::Buy,
+                price,
+                quantity,
+                fees: AssetFees {
+                    asset: QuoteAsset,
+                    fees: dec!(0.05),
+                },
+            }),
+        }
+    }
+
+    #[test]
+    fn test_matched_legs_lock_profit() {
+        let mut global = arb_positions_global();
+
+        // YES leg on Poly YES, NO leg on Kalshi NO
+        global.process(&order_opened(2, "test-arb_1_yes", "poly-1"));
+        global.process(&fill(2, "poly-1", dec!(0.40), dec!(10)));
+        // The NO fill arrives before its order snapshot
+        global.process(&fill(1, "kalshi-1", dec!(0.45), dec!(10)));
+        global.process(&order_opened(1, "test-arb_1_no", "kalshi-1"));
+
+        let position = global.arb_positions.get("KXTEST").unwrap();
+        assert_eq!(position.matched_quantity(), dec!(10));
+        assert_eq!(position.net_exposure(), Decimal::ZERO);
+        assert_eq!(position.blended_cost(), Some(dec!(0.85)));
+        assert_eq!(position.expected_payout(), dec!(10));
+        // 10 * (1 - 0.85) - 0.10 fees
+        assert_eq!(position.locked_profit(), dec!(1.40));
+        // Fills held for the late snapshot count towards the leg once linked
+        let filled = |cid: &str| global.arb_positions.filled_quantity(&ClientOrderId::new(cid));
+        assert_eq!(filled("test-arb_1_no"), dec!(10));
+    }
+
+    #[test]
+    fn test_single_leg_fill_is_unmatched() {
+        let mut global = arb_positions_global();
+
+        global.process(&order_opened(2, "test-arb_1_yes", "poly-1"));
+        global.process(&fill(2, "poly-1", dec!(0.40), dec!(10)));
+        global.process(&order_opened(1, "test-arb_1_no", "kalshi-1"));
+
+        let position = global.arb_positions.get("KXTEST").unwrap();
+        assert_eq!(position.matched_quantity(), Decimal::ZERO);
+        assert_eq!(position.unmatched_yes(), dec!(10));
+        assert_eq!(position.unmatched_no(), Decimal::ZERO);
+        assert_eq!(position.net_exposure(), dec!(10));
+        assert_eq!(position.blended_cost(), None);
+        assert_eq!(position.locked_profit(), dec!(-0.05));
+        let filled = |cid: &str| global.arb_positions.filled_quantity(&ClientOrderId::new(cid));
+        assert_eq!(filled("test-arb_1_yes"), dec!(10));
+        assert_eq!(filled("test-arb_1_no"), Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_held_to_settlement_pair_reports_locked_profit() {
+        let mut global = arb_positions_global();
+        global.process(&order_opened(2, "test-arb_1_yes", "poly-1"));
+        global.process(&fill(2, "poly-1", dec!(0.40), dec!(10)));
+        global.process(&order_opened(1, "test-arb_1_no", "kalshi-1"));
+        global.process(&fill(1, "kalshi-1", dec!(0.45), dec!(10)));
+        let position = global.arb_positions.get("KXTEST").unwrap();
+
+        let leg = |mode, mid: Decimal| {
+            let mut data = ArbitrageInstrumentData::default().with_position_mode(mode);
+            data.update_orderbook(&OrderBookEvent::Snapshot(book(
+                1,
+                &[(mid - dec!(0.01), dec!(100))],
+                &[(mid + dec!(0.01), dec!(100))],
+            )));
+            data
+        };
+
+        // Locked profit whatever the mid prices do
+        let settle = PositionMode::HeldToSettlement;
+        for (yes_mid, no_mid) in [
+            (dec!(0.40), dec!(0.45)),
+            (dec!(0.20), dec!(0.30)),
+            (dec!(0.70), dec!(0.10)),
+        ] {
+            let (yes, no) = (leg(settle, yes_mid), leg(settle, no_mid));
+            assert_eq!(position.pnl(&yes, &no), Some(dec!(1.40)));
+        }
+
+        // Marked to market, the same moves show up in the P&L
+        let mark = PositionMode::MarkToMarket;
+        let (yes, no) = (leg(mark, dec!(0.20)), leg(mark, dec!(0.30)));
+        // 10 * 0.20 - 4.00 + 10 * 0.30 - 4.50 - 0.10 fees
+        assert_eq!(position.pnl(&yes, &no), Some(dec!(-3.60)));
+    }
+
+    #[test]
+    fn test_held_to_settlement_leg_prices_at_entry() {
+        let mut data =
+            ArbitrageInstrumentData::default().with_position_mode(PositionMode::HeldToSettlement);
+        data.update_orderbook(&OrderBookEvent::Snapshot(book(
+            1,
+            &[(dec!(0.59), dec!(100))],
+            &[(dec!(0.61), dec!(100))],
+        )));
+        assert_eq!(data.price(), Some(dec!(0.60)));
+
+        data.update_position(10, dec!(0.40));
+        assert_eq!(data.price(), Some(dec!(0.40)));
+        assert_eq!(data.unrealized_pnl(), Some(Decimal::ZERO));
+    }
+
+    #[test]
+    fn test_fully_filled_legs_attributed_by_instrument() {
+        let mut global = arb_positions_global();
+
+        // YES leg fill arrives before its fully filled snapshot, NO leg fills after theirs
+        global.process(&fill(2, "poly-1", dec!(0.40), dec!(10)));
+        global.process(&order_fully_filled(2, "test-arb_1_yes"));
+        global.process(&order_fully_filled(1, "test-arb_1_no"));
+        global.process(&fill(1, "kalshi-1", dec!(0.45), dec!(4)));
+        global.process(&fill(1, "kalshi-1", dec!(0.45), dec!(6)));
+
+        let position = global.arb_positions.get("KXTEST").unwrap();
+        assert_eq!(position.matched_quantity(), dec!(10));
+        assert_eq!(position.net_exposure(), Decimal::ZERO);
+        let filled = |cid: &str| global.arb_positions.filled_quantity(&ClientOrderId::new(cid));
+        assert_eq!(filled("test-arb_1_yes"), dec!(10));
+        assert_eq!(filled("test-arb_1_no"), dec!(10));
+        assert_eq!(global.arb_positions.pending_orders(), 0);
+
+        // Once the order quantity is attributed, later fills on the instrument are not
+        global.process(&fill(1, "kalshi-2", dec!(0.45), dec!(3)));
+        let filled = |cid: &str| global.arb_positions.filled_quantity(&ClientOrderId::new(cid));
+        assert_eq!(filled("test-arb_1_no"), dec!(10));
+        assert_eq!(global.arb_positions.pending_orders(), 1);
+    }
+
+    #[test]
+    fn test_unlinked_fills_expire() {
+        let mut global = arb_positions_global();
+        let Some(AccountEventKind::Trade(trade)) =
+            Some(fill(1, "kalshi-1", dec!(0.45), dec!(10)).kind)
+        else {
+            unreachable!()
+        };
+
+        let start = Utc::now();
+        global.arb_positions.apply_trade_at(&trade, start);
+        assert_eq!(global.arb_positions.pending_orders(), 1);
+
+        // Still held within the TTL, dropped once the next trade arrives after it
+        let mut later = trade.clone();
+        later.order_id = OrderId::new("kalshi-2");
+        global.arb_positions.apply_trade_at(&later, start + PENDING_FILL_TTL);
+        assert_eq!(global.arb_positions.pending_orders(), 2);
+        global
+            .arb_positions
+            .apply_trade_at(&later, start + PENDING_FILL_TTL + TimeDelta::seconds(1));
+        assert_eq!(global.arb_positions.pending_orders(), 1);
+    }
 }
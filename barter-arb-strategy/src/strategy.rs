@@ -5,22 +5,36 @@
 
 use crate::{
     config::ArbitrageConfig,
-    correlation::{CorrelatedPair, Outcome, PredictionMarketKey},
-    fees::FeeCalculator,
-    opportunity::{ArbitrageDirection, ArbitrageOpportunity, OrderSide},
-    state::ArbitrageEngineState,
+    correlation::{CorrelatedGroup, CorrelatedPair, Outcome, PredictionMarketKey},
+    fees::{FeeCalculator, KALSHI_TAKER_FEE_BPS},
+    metrics::Metrics,
+    opportunity::{
+        ArbitrageDirection, ArbitrageOpportunity, DirectionEvaluation, OpportunityScan, OrderSide,
+        PairEvaluation, PairSkip, RejectionReason, SkipReason,
+    },
+    persistence::StatePersistence,
+    refresh::{PairUpdate, PairUpdateOutcome, same_pair},
+    risk::{DailyPnl, DailyPnlTracker, PairInstruments},
+    state::{ArbLeg, ArbPosition, ArbPositions, ArbitrageEngineState, depth_within},
 };
 use barter::engine::Engine;
+use barter::engine::action::send_requests::{SendCancelsAndOpensOutput, SendRequests};
+use barter::engine::execution_tx::ExecutionTxMap;
+use barter::engine::state::order::in_flight_recorder::InFlightRequestRecorder;
 use barter::engine::state::instrument::filter::InstrumentFilter;
 use barter::strategy::algo::AlgoStrategy;
 use barter::strategy::close_positions::ClosePositionsStrategy;
 use barter::strategy::on_disconnect::OnDisconnectStrategy;
 use barter::strategy::on_trading_disabled::OnTradingDisabled;
-use barter_data::books::{Level, OrderBook};
+use barter_data::{
+    books::{Level, OrderBook},
+    subscription::status::MarketStatus,
+};
 use barter_execution::order::{
-    OrderKey,
+    OrderKey, TimeInForce,
     id::{ClientOrderId, StrategyId},
     request::{OrderRequestCancel, OrderRequestOpen, RequestOpen},
+    state::ActiveOrderState,
 };
 use barter_instrument::{
     Side,
@@ -29,14 +43,100 @@ use barter_instrument::{
     index::IndexedInstruments,
     instrument::InstrumentIndex,
 };
-use rust_decimal::Decimal;
+use chrono::{DateTime, TimeDelta, Utc};
+use indexmap::IndexMap;
+use rust_decimal::{Decimal, MathematicalOps};
 use rust_decimal::prelude::ToPrimitive;
-use std::cell::Cell;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use smol_str::SmolStr;
+use std::cell::{Cell, Ref, RefCell};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use tokio::sync::{broadcast, mpsc};
 use tracing::{debug, info, warn};
 
+/// Channel receiving detected opportunities, paired with the filter that rejected
+/// them (`None` if the opportunity was acted on).
+pub type OpportunitySink = mpsc::UnboundedSender<(ArbitrageOpportunity, Option<&'static str>)>;
+
+/// Broadcast channel publishing each opportunity the strategy acts on, for external
+/// consumers such as dashboards and alerting.
+pub type OpportunityEvents = broadcast::Sender<ArbitrageOpportunity>;
+
+/// Channel receiving paired positions (by Kalshi ticker) whenever they change.
+pub type PositionSink = mpsc::UnboundedSender<(SmolStr, ArbPosition)>;
+
+/// Channel receiving pairs to mark invalid in the database.
+pub type PairInvalidationSink = mpsc::UnboundedSender<PairInvalidation>;
+
+/// A pair suspended at runtime that should be marked invalid in the database, or one
+/// whose pricing looks inconsistent and should be re-validated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PairInvalidation {
+    /// Market pairs table row id
+    pub pair_id: i64,
+    /// Kalshi ticker of the pair
+    pub kalshi_ticker: SmolStr,
+    /// Why the pair was suspended or flagged
+    pub reason: String,
+    /// Flag the pair for re-validation rather than marking it invalid
+    pub revalidate: bool,
+}
+
+/// How a suspension is reported to the [`PairInvalidationSink`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Invalidation {
+    /// Suspend only, the pair mapping itself is still valid (e.g. expired pairs)
+    Skip,
+    /// Mark the pair invalid
+    MarkInvalid,
+    /// Flag the pair for re-validation
+    Revalidate,
+}
+
+/// Strategy state persisted across restarts, see [`crate::persistence`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StrategySnapshot {
+    /// Client order id counter, so restarted sessions don't reuse leg ids
+    pub order_counter: u64,
+    /// Suspended pairs (by Kalshi ticker) and the reason they were suspended
+    pub suspended: HashMap<SmolStr, String>,
+    /// Risk manager's realized P&L for the day, so restarting doesn't reset the loss limit
+    #[serde(default)]
+    pub daily_pnl: DailyPnl,
+}
+
+/// Time-weighted moving average of a pair's top-of-book edge.
+#[derive(Debug, Clone, Copy)]
+struct EdgeEma {
+    ema: Decimal,
+    /// Edge observed at `updated`, assumed to hold until the next observation
+    edge: Decimal,
+    updated: DateTime<Utc>,
+}
+
+/// YES and NO leg orders of one opportunity, sent together and awaiting fills.
+#[derive(Debug, Clone)]
+struct LegGroup {
+    sent: DateTime<Utc>,
+    quantity: Decimal,
+    yes: OrderKey<ExchangeIndex, InstrumentIndex>,
+    no: OrderKey<ExchangeIndex, InstrumentIndex>,
+    /// Flattening orders sent so far, oldest first
+    flattens: Vec<OrderKey<ExchangeIndex, InstrumentIndex>>,
+    /// When the latest flattening order was sent
+    last_flatten: Option<DateTime<Utc>>,
+}
+
+/// Cancels of lagging legs and flattening opens of filled legs.
+type LegRiskOrders = (
+    Vec<OrderRequestCancel<ExchangeIndex, InstrumentIndex>>,
+    Vec<OrderRequestOpen<ExchangeIndex, InstrumentIndex>>,
+);
+
 /// Result of walking two orderbook sides simultaneously.
 struct WalkResult {
+    top_of_book_cost: Option<Decimal>,
     total_size: u32,
     total_profit: Decimal,
     avg_yes_price: Decimal,
@@ -62,19 +162,24 @@ fn derive_no_asks(yes_book: &OrderBook) -> Vec<Level> {
 /// Walk two orderbook sides simultaneously, maintaining 1:1 contract ratio.
 ///
 /// Accumulates cost per contract: yes_price + no_price + fees.
-/// Stops when cost >= $1.00 (no longer profitable).
+/// Stops when cost >= $1.00 (no longer profitable), or once the walk reaches
+/// `config.max_walk_levels` on either side or `config.max_contracts_per_opportunity`.
 fn walk_orderbook_levels(
     yes_asks: &[Level],
     no_asks: &[Level],
     yes_platform: ExchangeId,
     no_platform: ExchangeId,
     poly_fee_bps: u32,
+    config: &ArbitrageConfig,
 ) -> WalkResult {
+    let slippage_buffer = Decimal::from(config.slippage_buffer_bps) / Decimal::from(10_000);
+
     let mut total_size: u32 = 0;
     let mut total_yes_cost = Decimal::ZERO;
     let mut total_no_cost = Decimal::ZERO;
     let mut total_fees = Decimal::ZERO;
     let mut total_profit = Decimal::ZERO;
+    let mut top_of_book_cost = None;
 
     let mut yes_idx: usize = 0;
     let mut no_idx: usize = 0;
@@ -82,33 +187,44 @@ fn walk_orderbook_levels(
     let mut no_remaining = no_asks.first().map(|l| l.amount).unwrap_or(Decimal::ZERO);
 
     while yes_idx < yes_asks.len() && no_idx < no_asks.len() {
+        if config
+            .max_walk_levels
+            .is_some_and(|max_levels| yes_idx >= max_levels || no_idx >= max_levels)
+        {
+            break;
+        }
+
         let yes_price = yes_asks[yes_idx].price;
         let no_price = no_asks[no_idx].price;
 
-        let fill_amount = yes_remaining.min(no_remaining);
-        let fill_size = fill_amount.to_u32().unwrap_or(0);
+        let mut fill_amount = yes_remaining.min(no_remaining);
+        let mut fill_size = fill_amount.to_u32().unwrap_or(0);
+        if let Some(max_contracts) = config.max_contracts_per_opportunity {
+            let capacity = max_contracts.saturating_sub(total_size);
+            if fill_size > capacity {
+                fill_size = capacity;
+                fill_amount = Decimal::from(capacity);
+            }
+        }
         if fill_size == 0 {
             break;
         }
 
         // Per-fill fees
-        let yes_fee = match yes_platform {
-            ExchangeId::Kalshi => FeeCalculator::kalshi_taker_fee(yes_price, fill_size),
-            ExchangeId::Polymarket => {
-                FeeCalculator::polymarket_taker_fee(yes_price, fill_size, poly_fee_bps)
-            }
-            _ => Decimal::ZERO,
-        };
-        let no_fee = match no_platform {
-            ExchangeId::Kalshi => FeeCalculator::kalshi_taker_fee(no_price, fill_size),
-            ExchangeId::Polymarket => {
-                FeeCalculator::polymarket_taker_fee(no_price, fill_size, poly_fee_bps)
-            }
-            _ => Decimal::ZERO,
+        let fee = |exchange, price| {
+            FeeCalculator::taker_fee(exchange, price, fill_size, poly_fee_bps, KALSHI_TAKER_FEE_BPS)
         };
+        let yes_fee = fee(yes_platform, yes_price);
+        let no_fee = fee(no_platform, no_price);
+
+        // Adverse-move buffer inflates each leg's assumed cost, so only levels
+        // with margin above the buffer are taken
+        let slippage = (yes_price + no_price) * slippage_buffer;
 
         let fill_decimal = Decimal::from(fill_size);
-        let cost_per_contract = yes_price + no_price + (yes_fee + no_fee) / fill_decimal;
+        let cost_per_contract =
+            yes_price + no_price + (yes_fee + no_fee) / fill_decimal + slippage;
+        top_of_book_cost.get_or_insert(cost_per_contract);
 
         if cost_per_contract >= Decimal::ONE {
             break;
@@ -152,6 +268,7 @@ fn walk_orderbook_levels(
     };
 
     WalkResult {
+        top_of_book_cost,
         total_size,
         total_profit,
         avg_yes_price,
@@ -173,13 +290,51 @@ pub struct PredictionArbitrageStrategy {
     /// Configuration for the strategy
     pub config: ArbitrageConfig,
     /// Correlated market pairs to monitor
-    pub pairs: Vec<CorrelatedPair>,
+    pairs: RefCell<Vec<CorrelatedPair>>,
     /// Polymarket fee in basis points (default 50 = 0.5%)
     pub poly_fee_bps: u32,
     /// Map from PredictionMarketKey to (ExchangeIndex, InstrumentIndex) for order generation
-    instrument_index: HashMap<PredictionMarketKey, (ExchangeIndex, InstrumentIndex)>,
+    instrument_index: RefCell<HashMap<PredictionMarketKey, (ExchangeIndex, InstrumentIndex)>>,
+    /// All engine instruments by (exchange, exchange name), for mapping pairs added at runtime
+    known_instruments: HashMap<(ExchangeId, SmolStr), (ExchangeIndex, InstrumentIndex)>,
     /// Counter for generating unique client order IDs
     order_counter: Cell<u64>,
+    /// Optional sink for recording detected opportunities
+    opportunity_tx: Option<OpportunitySink>,
+    /// Optional broadcast of opportunities that passed all filters
+    opportunity_events: Option<OpportunityEvents>,
+    /// Optional sink for marking suspended pairs invalid in the database
+    invalidation_tx: Option<PairInvalidationSink>,
+    /// Optional source of live pair updates, applied before each scan
+    pair_updates: Option<Arc<Mutex<mpsc::UnboundedReceiver<PairUpdate>>>>,
+    /// Optional risk manager pair map, extended with pairs added at runtime
+    pair_instruments: Option<PairInstruments>,
+    /// Optional risk manager daily P&L accumulator, persisted across restarts
+    daily_pnl: Option<DailyPnlTracker>,
+    /// Suspended pairs (by Kalshi ticker) and the reason they were suspended
+    suspended: RefCell<HashMap<SmolStr, String>>,
+    /// When each pair's orderbooks were first observed missing
+    missing_books_since: RefCell<HashMap<SmolStr, DateTime<Utc>>>,
+    /// Pairs (by Kalshi ticker) with a stale book, left out of scans until it is refreshed
+    stale_books: RefCell<HashSet<SmolStr>>,
+    /// Sum of each pair's book versions at its last scan, by Kalshi ticker
+    scanned_versions: RefCell<HashMap<SmolStr, u64>>,
+    /// Number of pair evaluations run by [`Self::detect_opportunities`] and engine scans
+    pairs_checked: Cell<u64>,
+    /// Optional periodic state snapshots, written after each scan when due
+    persistence: Option<StatePersistence>,
+    /// Optional sink for paired positions
+    position_tx: Option<PositionSink>,
+    /// Positions last sent to `position_tx`
+    sent_positions: RefCell<HashMap<SmolStr, ArbPosition>>,
+    /// Leg orders awaiting fills, by shared client order id prefix
+    leg_groups: RefCell<IndexMap<SmolStr, LegGroup>>,
+    /// Exponential moving average of each pair's top-of-book edge, by Kalshi ticker
+    edge_ema: RefCell<HashMap<SmolStr, EdgeEma>>,
+    /// Consecutive scans whose prices contradicted each pair's inverse flag, by Kalshi ticker
+    anomalous_scans: RefCell<HashMap<SmolStr, u32>>,
+    /// Optional metrics updated on each scan
+    metrics: Option<Metrics>,
 }
 
 impl PredictionArbitrageStrategy {
@@ -192,10 +347,29 @@ impl PredictionArbitrageStrategy {
         Self {
             id: id.into(),
             config,
-            pairs,
+            pairs: RefCell::new(pairs),
             poly_fee_bps: 50,
-            instrument_index: HashMap::new(),
+            instrument_index: RefCell::new(HashMap::new()),
+            known_instruments: HashMap::new(),
             order_counter: Cell::new(0),
+            opportunity_tx: None,
+            opportunity_events: None,
+            invalidation_tx: None,
+            pair_updates: None,
+            pair_instruments: None,
+            daily_pnl: None,
+            suspended: RefCell::new(HashMap::new()),
+            missing_books_since: RefCell::new(HashMap::new()),
+            stale_books: RefCell::new(HashSet::new()),
+            scanned_versions: RefCell::new(HashMap::new()),
+            pairs_checked: Cell::new(0),
+            persistence: None,
+            position_tx: None,
+            sent_positions: RefCell::new(HashMap::new()),
+            leg_groups: RefCell::new(IndexMap::new()),
+            edge_ema: RefCell::new(HashMap::new()),
+            anomalous_scans: RefCell::new(HashMap::new()),
+            metrics: None,
         }
     }
 
@@ -206,740 +380,3319 @@ impl PredictionArbitrageStrategy {
         pairs: Vec<CorrelatedPair>,
         indexed: &IndexedInstruments,
     ) -> Self {
-        let mut instrument_index = HashMap::new();
-        let id = id.into();
+        let mut strategy = Self::new(id, config, pairs);
 
-        let mut name_to_index: HashMap<
-            (ExchangeId, String),
-            (ExchangeIndex, InstrumentIndex),
-        > = HashMap::new();
         for keyed_instrument in indexed.instruments() {
             let exchange_id = keyed_instrument.value.exchange.value;
-            let name = keyed_instrument.value.name_exchange.to_string();
-            name_to_index.insert(
+            let name = keyed_instrument.value.name_exchange.name().clone();
+            strategy.known_instruments.insert(
                 (exchange_id, name),
                 (keyed_instrument.value.exchange.key, keyed_instrument.key),
             );
         }
 
-        for pair in &pairs {
-            // Kalshi YES
-            let kalshi_yes_name = format!("{}_yes", pair.kalshi_ticker);
-            if let Some(&indices) = name_to_index.get(&(ExchangeId::Kalshi, kalshi_yes_name)) {
-                instrument_index.insert(
-                    PredictionMarketKey::kalshi_yes(pair.kalshi_ticker.clone()),
-                    indices,
-                );
-            }
+        for pair in strategy.pairs.borrow().iter() {
+            strategy.register_pair_instruments(pair);
+        }
 
-            // Kalshi NO
-            let kalshi_no_name = format!("{}_no", pair.kalshi_ticker);
-            if let Some(&indices) = name_to_index.get(&(ExchangeId::Kalshi, kalshi_no_name)) {
-                instrument_index.insert(
-                    PredictionMarketKey::kalshi_no(pair.kalshi_ticker.clone()),
-                    indices,
-                );
-            }
+        strategy
+    }
 
-            // Polymarket YES
-            if let Some(&indices) = name_to_index
-                .get(&(ExchangeId::Polymarket, pair.polymarket_yes_token.to_string()))
-            {
-                instrument_index.insert(
-                    PredictionMarketKey::polymarket_yes(pair.polymarket_yes_token.clone()),
-                    indices,
-                );
-            }
+    /// Correlated market pairs currently monitored.
+    pub fn pairs(&self) -> Ref<'_, Vec<CorrelatedPair>> {
+        self.pairs.borrow()
+    }
 
-            // Polymarket NO
-            if let Some(&indices) = name_to_index
-                .get(&(ExchangeId::Polymarket, pair.polymarket_no_token.to_string()))
+    /// Map a pair's instruments into `instrument_index`.
+    ///
+    /// Returns `true` if all four instruments are known to the engine.
+    fn register_pair_instruments(&self, pair: &CorrelatedPair) -> bool {
+        let mut instrument_index = self.instrument_index.borrow_mut();
+        let mut all_known = true;
+
+        for key in pair.instrument_keys() {
+            match self
+                .known_instruments
+                .get(&(key.exchange, key.to_instrument_name()))
             {
-                instrument_index.insert(
-                    PredictionMarketKey::polymarket_no(pair.polymarket_no_token.clone()),
-                    indices,
-                );
+                Some(&indices) => {
+                    instrument_index.insert(key, indices);
+                }
+                None => all_known = false,
             }
         }
 
-        Self {
-            id,
-            config,
-            pairs,
-            poly_fee_bps: 50,
-            instrument_index,
-            order_counter: Cell::new(0),
-        }
+        all_known
     }
 
-    /// Build a map of orderbooks from engine state using instrument_index.
-    fn build_book_map<'a>(
-        &self,
-        state: &'a ArbitrageEngineState,
-    ) -> HashMap<PredictionMarketKey, &'a OrderBook> {
-        let mut books = HashMap::new();
-        for (key, (_, inst_idx)) in &self.instrument_index {
-            if let Some(book) = &state.instruments.instrument_index(inst_idx).data.orderbook {
-                books.insert(key.clone(), book);
-            }
-        }
-        books
+    /// Share the risk manager's pair map, so pairs added or changed by
+    /// [`Self::apply_pair_update`] count against its `max_open_pairs`.
+    ///
+    /// Mappings of removed pairs are kept, since their positions still occupy a slot.
+    pub fn with_pair_instruments(mut self, pair_instruments: PairInstruments) -> Self {
+        self.pair_instruments = Some(pair_instruments);
+        self
     }
 
-    /// Generate a unique client order ID.
-    fn next_order_id(&self) -> ClientOrderId {
-        let id = self.order_counter.get() + 1;
-        self.order_counter.set(id);
-        ClientOrderId::new(format!("{}_{}", self.id.0.as_str(), id))
+    /// Share the risk manager's daily P&L accumulator, so it is captured by
+    /// [`Self::snapshot`] and a restart mid-day keeps the day's realized losses.
+    pub fn with_daily_pnl(mut self, daily_pnl: DailyPnlTracker) -> Self {
+        self.daily_pnl = Some(daily_pnl);
+        self
     }
 
-    /// Detect arbitrage opportunities across all monitored pairs.
-    pub fn detect_opportunities(
-        &self,
-        books: &HashMap<PredictionMarketKey, &OrderBook>,
-    ) -> Vec<ArbitrageOpportunity> {
-        self.pairs
+    /// Drop instrument mappings no current pair uses.
+    fn retain_pair_instruments(&self) {
+        let in_use: HashSet<PredictionMarketKey> = self
+            .pairs
+            .borrow()
             .iter()
-            .filter(|pair| !pair.is_expired())
-            .filter(|pair| {
-                self.config
-                    .max_days_to_expiry
-                    .map(|max| pair.days_to_expiry() <= max as i64)
-                    .unwrap_or(true)
-            })
-            .flat_map(|pair| self.check_pair_for_arbitrage(pair, books))
-            .collect()
+            .flat_map(|p| p.instrument_keys())
+            .collect();
+        self.instrument_index
+            .borrow_mut()
+            .retain(|key, _| in_use.contains(key));
     }
 
-    /// Check a single correlated pair for delta-neutral arbitrage.
+    /// Map `pair`'s instruments to it in the shared risk manager pair map, if any.
+    fn share_pair_instruments(&self, pair: &CorrelatedPair) {
+        let Some(pair_instruments) = &self.pair_instruments else {
+            return;
+        };
+        let instrument_index = self.instrument_index.borrow();
+        pair_instruments.extend(
+            pair.instrument_keys()
+                .iter()
+                .filter_map(|key| instrument_index.get(key))
+                .map(|(_, instrument)| (*instrument, pair.kalshi_ticker.clone())),
+        );
+    }
+
+    /// Apply a live pair update from a [`PairRefresher`](crate::refresh::PairRefresher).
     ///
-    /// Uses only YES orderbooks; derives NO asks from YES bids.
-    /// Checks two directions and applies inverse flag.
-    fn check_pair_for_arbitrage(
-        &self,
-        pair: &CorrelatedPair,
-        books: &HashMap<PredictionMarketKey, &OrderBook>,
-    ) -> Vec<ArbitrageOpportunity> {
-        let mut opportunities = Vec::new();
+    /// Additions whose instruments are all known to the engine start being
+    /// monitored immediately. Additions needing instruments the engine doesn't
+    /// have are not added, and are reported as [`PairUpdateOutcome::RequiresSubscription`].
+    ///
+    /// The instrument mappings of the strategy and of the shared risk manager pair map
+    /// (see [`Self::with_pair_instruments`]) are updated together.
+    pub fn apply_pair_update(&self, update: &PairUpdate) -> PairUpdateOutcome {
+        let outcome = match update {
+            PairUpdate::Added(pair) => {
+                if self.pairs.borrow().iter().any(|p| same_pair(p, pair)) {
+                    PairUpdateOutcome::Ignored
+                } else if pair.instrument_keys().iter().all(|key| {
+                    self.known_instruments
+                        .contains_key(&(key.exchange, key.to_instrument_name()))
+                }) {
+                    self.register_pair_instruments(pair);
+                    self.share_pair_instruments(pair);
+                    self.pairs.borrow_mut().push(pair.clone());
+                    PairUpdateOutcome::Monitoring
+                } else {
+                    PairUpdateOutcome::RequiresSubscription
+                }
+            }
+            PairUpdate::Removed(pair) => {
+                let mut pairs = self.pairs.borrow_mut();
+                let before = pairs.len();
+                pairs.retain(|p| !same_pair(p, pair));
+                let removed = pairs.len() != before;
+                drop(pairs);
+                if removed {
+                    self.retain_pair_instruments();
+                    self.missing_books_since.borrow_mut().remove(&pair.kalshi_ticker);
+                    self.edge_ema.borrow_mut().remove(&pair.kalshi_ticker);
+                    self.anomalous_scans.borrow_mut().remove(&pair.kalshi_ticker);
+                    PairUpdateOutcome::Removed
+                } else {
+                    PairUpdateOutcome::Ignored
+                }
+            }
+            PairUpdate::Changed { current, .. } => {
+                let mut pairs = self.pairs.borrow_mut();
+                match pairs.iter_mut().find(|p| same_pair(p, current)) {
+                    Some(existing) => {
+                        *existing = current.clone();
+                        drop(pairs);
+                        // Instruments the pair no longer trades are dropped
+                        self.retain_pair_instruments();
+                        self.register_pair_instruments(current);
+                        self.share_pair_instruments(current);
+                        PairUpdateOutcome::Updated
+                    }
+                    None => PairUpdateOutcome::Ignored,
+                }
+            }
+        };
 
-        let poly_yes_key =
-            PredictionMarketKey::polymarket_yes(pair.polymarket_yes_token.clone());
-        let kalshi_yes_key = PredictionMarketKey::kalshi_yes(pair.kalshi_ticker.clone());
+        match outcome {
+            PairUpdateOutcome::RequiresSubscription => warn!(
+                pair = %update.pair().kalshi_ticker,
+                "New pair requires market data subscriptions; restart to monitor it"
+            ),
+            PairUpdateOutcome::Ignored => debug!(
+                pair = %update.pair().kalshi_ticker,
+                "Pair update ignored"
+            ),
+            _ => info!(
+                pair = %update.pair().kalshi_ticker,
+                ?outcome,
+                "Applied pair update"
+            ),
+        }
+
+        outcome
+    }
 
-        let poly_yes_book = match books.get(&poly_yes_key) {
-            Some(book) => *book,
-            None => return opportunities,
+    /// Apply pair updates from `rx` at the start of each opportunity scan.
+    pub fn with_pair_updates(mut self, rx: mpsc::UnboundedReceiver<PairUpdate>) -> Self {
+        self.pair_updates = Some(Arc::new(Mutex::new(rx)));
+        self
+    }
+
+    /// Apply any queued pair updates.
+    fn drain_pair_updates(&self) {
+        let Some(rx) = &self.pair_updates else {
+            return;
         };
-        let kalshi_yes_book = match books.get(&kalshi_yes_key) {
-            Some(book) => *book,
-            None => return opportunities,
+        let Ok(mut rx) = rx.lock() else {
+            return;
         };
+        while let Ok(update) = rx.try_recv() {
+            self.apply_pair_update(&update);
+        }
+    }
 
-        // Derive NO asks from YES bids
-        let poly_no_asks = derive_no_asks(poly_yes_book);
-        let kalshi_no_asks = derive_no_asks(kalshi_yes_book);
+    /// Send detected opportunities to `tx` when `config.record_opportunities` is set.
+    pub fn with_opportunity_sink(mut self, tx: OpportunitySink) -> Self {
+        self.opportunity_tx = Some(tx);
+        self
+    }
 
-        if pair.inverse {
-            // Inverse: Polymarket YES = Kalshi NO
-            // The Kalshi YES book we receive actually represents the "NO" side
-            // from Polymarket's perspective.
+    /// Publish every opportunity that passes all filters on `tx`.
+    ///
+    /// Events are sent whether or not anyone is subscribed; receivers that lag
+    /// behind the channel capacity miss the oldest events.
+    pub fn with_opportunity_events(mut self, tx: OpportunityEvents) -> Self {
+        self.opportunity_events = Some(tx);
+        self
+    }
 
-            // Direction 1: Buy Poly YES + Buy "Kalshi NO"
-            // "Kalshi NO" in inverse = original Kalshi YES asks
-            let result1 = walk_orderbook_levels(
-                poly_yes_book.asks().levels(),
-                kalshi_yes_book.asks().levels(),
-                ExchangeId::Polymarket,
-                ExchangeId::Kalshi,
-                self.poly_fee_bps,
-            );
+    /// Send suspended pairs to `tx` when `config.mark_invalid_pairs` is set.
+    pub fn with_invalidation_sink(mut self, tx: PairInvalidationSink) -> Self {
+        self.invalidation_tx = Some(tx);
+        self
+    }
 
-            if result1.total_size > 0 && result1.total_profit > Decimal::ZERO {
-                opportunities.push(ArbitrageOpportunity {
-                    pair: pair.clone(),
-                    direction: ArbitrageDirection::YesPolyNoKalshi,
-                    yes_side: OrderSide::poly(
-                        pair.polymarket_yes_token.clone(),
-                        Outcome::Yes,
-                        result1.avg_yes_price,
-                        result1.total_size,
-                    ),
-                    no_side: OrderSide::kalshi(
-                        pair.kalshi_ticker.clone(),
-                        Outcome::Yes, // Buy Kalshi YES contract (= semantic NO in inverse)
-                        result1.avg_no_price,
-                        result1.total_size,
-                    ),
-                    total_cost: result1.total_cost,
-                    avg_yes_price: result1.avg_yes_price,
-                    avg_no_price: result1.avg_no_price,
-                    max_contracts: result1.total_size,
-                    expected_profit: result1.total_profit,
-                    total_fees: result1.total_fees,
-                });
-            }
-
-            // Direction 2: Buy "Kalshi YES" + Buy Poly NO
-            // "Kalshi YES" in inverse = derived from Kalshi YES bids (semantic NO bids)
-            let result2 = walk_orderbook_levels(
-                &kalshi_no_asks,
-                &poly_no_asks,
-                ExchangeId::Kalshi,
-                ExchangeId::Polymarket,
-                self.poly_fee_bps,
-            );
+    /// Send paired positions to `tx` after each scan in which they changed.
+    pub fn with_position_sink(mut self, tx: PositionSink) -> Self {
+        self.position_tx = Some(tx);
+        self
+    }
 
-            if result2.total_size > 0 && result2.total_profit > Decimal::ZERO {
-                opportunities.push(ArbitrageOpportunity {
-                    pair: pair.clone(),
-                    direction: ArbitrageDirection::YesKalshiNoPoly,
-                    yes_side: OrderSide::kalshi(
-                        pair.kalshi_ticker.clone(),
-                        Outcome::No, // Buy Kalshi NO contract (= semantic YES in inverse)
-                        result2.avg_yes_price,
-                        result2.total_size,
-                    ),
-                    no_side: OrderSide::poly(
-                        pair.polymarket_no_token.clone(),
-                        Outcome::No,
-                        result2.avg_no_price,
-                        result2.total_size,
-                    ),
-                    total_cost: result2.total_cost,
-                    avg_yes_price: result2.avg_yes_price,
-                    avg_no_price: result2.avg_no_price,
-                    max_contracts: result2.total_size,
-                    expected_profit: result2.total_profit,
-                    total_fees: result2.total_fees,
-                });
+    /// Forward positions that changed since they were last sent.
+    fn publish_positions(&self, positions: &ArbPositions) {
+        let Some(tx) = &self.position_tx else {
+            return;
+        };
+        let mut sent = self.sent_positions.borrow_mut();
+        for (ticker, position) in positions.iter() {
+            if sent.get(ticker) == Some(position) {
+                continue;
             }
-        } else {
-            // Non-inverse: standard pairing
-
-            // Direction 1: Buy Poly YES + Buy Kalshi NO
-            let result1 = walk_orderbook_levels(
-                poly_yes_book.asks().levels(),
-                &kalshi_no_asks,
-                ExchangeId::Polymarket,
-                ExchangeId::Kalshi,
-                self.poly_fee_bps,
-            );
-
-            if result1.total_size > 0 && result1.total_profit > Decimal::ZERO {
-                opportunities.push(ArbitrageOpportunity {
-                    pair: pair.clone(),
-                    direction: ArbitrageDirection::YesPolyNoKalshi,
-                    yes_side: OrderSide::poly(
-                        pair.polymarket_yes_token.clone(),
-                        Outcome::Yes,
-                        result1.avg_yes_price,
-                        result1.total_size,
-                    ),
-                    no_side: OrderSide::kalshi(
-                        pair.kalshi_ticker.clone(),
-                        Outcome::No,
-                        result1.avg_no_price,
-                        result1.total_size,
-                    ),
-                    total_cost: result1.total_cost,
-                    avg_yes_price: result1.avg_yes_price,
-                    avg_no_price: result1.avg_no_price,
-                    max_contracts: result1.total_size,
-                    expected_profit: result1.total_profit,
-                    total_fees: result1.total_fees,
-                });
+            if tx.send((ticker.clone(), position.clone())).is_err() {
+                warn!("Position sink closed, dropping position update");
+                return;
             }
+            sent.insert(ticker.clone(), position.clone());
+        }
+    }
 
-            // Direction 2: Buy Kalshi YES + Buy Poly NO
-            let result2 = walk_orderbook_levels(
-                kalshi_yes_book.asks().levels(),
-                &poly_no_asks,
-                ExchangeId::Kalshi,
-                ExchangeId::Polymarket,
-                self.poly_fee_bps,
-            );
+    /// Report opportunities, orders, latency and capital to `metrics`.
+    pub fn with_metrics(mut self, metrics: Metrics) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
 
-            if result2.total_size > 0 && result2.total_profit > Decimal::ZERO {
-                opportunities.push(ArbitrageOpportunity {
-                    pair: pair.clone(),
-                    direction: ArbitrageDirection::YesKalshiNoPoly,
-                    yes_side: OrderSide::kalshi(
-                        pair.kalshi_ticker.clone(),
-                        Outcome::Yes,
-                        result2.avg_yes_price,
-                        result2.total_size,
-                    ),
-                    no_side: OrderSide::poly(
-                        pair.polymarket_no_token.clone(),
-                        Outcome::No,
-                        result2.avg_no_price,
-                        result2.total_size,
-                    ),
-                    total_cost: result2.total_cost,
-                    avg_yes_price: result2.avg_yes_price,
-                    avg_no_price: result2.avg_no_price,
-                    max_contracts: result2.total_size,
-                    expected_profit: result2.total_profit,
-                    total_fees: result2.total_fees,
-                });
+    /// Update per-scan gauges: oldest book age per exchange, deployed capital,
+    /// and per-pair locked profit and smoothed edge.
+    fn update_metrics(&self, metrics: &Metrics, state: &ArbitrageEngineState, now: DateTime<Utc>) {
+        let mut oldest: HashMap<ExchangeId, DateTime<Utc>> = HashMap::new();
+        for instrument in state.instruments.instruments(&InstrumentFilter::None) {
+            let exchange = state.global.exchange_ids.get(&instrument.instrument.exchange);
+            if let (Some(&exchange), Some(updated)) = (exchange, instrument.data.book_updated) {
+                let entry = oldest.entry(exchange).or_insert(updated);
+                *entry = (*entry).min(updated);
             }
         }
+        for (exchange, updated) in oldest {
+            metrics.set_book_age(exchange, now - updated);
+        }
 
-        opportunities
+        metrics.set_deployed_capital(state.global.total_deployed);
+        for (ticker, position) in state.global.arb_positions.iter() {
+            metrics.set_pair_pnl(ticker, position.locked_profit());
+        }
+        for (ticker, edge) in self.edge_ema.borrow().iter() {
+            metrics.set_pair_edge(ticker, edge.ema);
+        }
     }
 
-    /// Check if an opportunity passes minimum order value requirements.
-    fn passes_min_order_values(&self, opp: &ArbitrageOpportunity) -> bool {
-        let yes_value = opp.yes_side.order_value();
-        let no_value = opp.no_side.order_value();
+    /// Latest orderbook event time across a pair's instruments.
+    fn pair_book_updated(
+        &self,
+        state: &ArbitrageEngineState,
+        pair: &CorrelatedPair,
+    ) -> Option<DateTime<Utc>> {
+        let instrument_index = self.instrument_index.borrow();
+        pair.instrument_keys()
+            .iter()
+            .filter_map(|key| instrument_index.get(key))
+            .filter_map(|(_, instrument)| {
+                state.instruments.instrument_index(instrument).data.book_updated
+            })
+            .max()
+    }
 
-        let yes_min = match opp.yes_side.exchange {
-            ExchangeId::Polymarket => self.config.min_order_value.polymarket,
-            ExchangeId::Kalshi => self.config.min_order_value.kalshi,
-            _ => Decimal::ZERO,
-        };
-        let no_min = match opp.no_side.exchange {
-            ExchangeId::Polymarket => self.config.min_order_value.polymarket,
-            ExchangeId::Kalshi => self.config.min_order_value.kalshi,
-            _ => Decimal::ZERO,
-        };
+    /// Periodically snapshot engine and strategy state with `persistence`.
+    pub fn with_persistence(mut self, persistence: StatePersistence) -> Self {
+        self.persistence = Some(persistence);
+        self
+    }
 
-        yes_value >= yes_min && no_value >= no_min
+    /// Capture the strategy state that should survive a restart.
+    pub fn snapshot(&self) -> StrategySnapshot {
+        StrategySnapshot {
+            order_counter: self.order_counter.get(),
+            suspended: self.suspended.borrow().clone(),
+            daily_pnl: self
+                .daily_pnl
+                .as_ref()
+                .map(DailyPnlTracker::get)
+                .unwrap_or_default(),
+        }
     }
 
-    /// Check if an opportunity passes position limits.
-    fn passes_position_limits(
-        &self,
-        opp: &ArbitrageOpportunity,
-        _state: &ArbitrageEngineState,
-    ) -> bool {
-        opp.max_contracts <= self.config.max_position_per_market
+    /// Restore state captured by [`Self::snapshot`] before a restart.
+    ///
+    /// Suspended pairs are not forwarded to the invalidation sink again. Missing book
+    /// timers are not persisted, so restart from scratch rather than counting the
+    /// downtime as books missing.
+    pub fn restore(&self, snapshot: &StrategySnapshot) {
+        self.order_counter
+            .set(self.order_counter.get().max(snapshot.order_counter));
+        self.suspended.borrow_mut().extend(
+            snapshot
+                .suspended
+                .iter()
+                .map(|(ticker, reason)| (ticker.clone(), reason.clone())),
+        );
+        if let Some(daily_pnl) = &self.daily_pnl {
+            daily_pnl.set(snapshot.daily_pnl);
+        }
     }
 
-    /// Generate a pair of BUY orders for a valid opportunity.
-    fn generate_order_pair(
-        &self,
-        opp: &ArbitrageOpportunity,
-    ) -> Vec<OrderRequestOpen<ExchangeIndex, InstrumentIndex>> {
-        let yes_indices = self.instrument_index.get(&opp.yes_side.instrument);
-        let no_indices = self.instrument_index.get(&opp.no_side.instrument);
-
-        let (yes_exchange, yes_instrument) = match yes_indices {
-            Some(&indices) => indices,
-            None => {
-                warn!(
-                    key = %opp.yes_side.instrument,
-                    "YES instrument not found in index"
-                );
-                return vec![];
-            }
-        };
+    /// Stop trading a pair for the rest of the session.
+    ///
+    /// If `config.mark_invalid_pairs` is set and the pair was loaded from the
+    /// database, the pair is also forwarded to the invalidation sink.
+    pub fn suspend_pair(&self, pair: &CorrelatedPair, reason: impl Into<String>) {
+        self.suspend(pair, reason.into(), Invalidation::MarkInvalid);
+    }
 
-        let (no_exchange, no_instrument) = match no_indices {
-            Some(&indices) => indices,
-            None => {
-                warn!(
-                    key = %opp.no_side.instrument,
-                    "NO instrument not found in index"
-                );
-                return vec![];
+    /// Suspend a pair, reporting it to the invalidation sink as `invalidation` describes.
+    fn suspend(&self, pair: &CorrelatedPair, reason: String, invalidation: Invalidation) {
+        let mut suspended = self.suspended.borrow_mut();
+        if suspended.contains_key(&pair.kalshi_ticker) {
+            return;
+        }
+
+        warn!(pair = %pair.kalshi_ticker, %reason, "Suspending pair");
+        suspended.insert(pair.kalshi_ticker.clone(), reason.clone());
+        drop(suspended);
+        match invalidation {
+            Invalidation::Skip => {}
+            Invalidation::MarkInvalid => self.send_invalidation(pair, reason, false),
+            Invalidation::Revalidate => self.send_invalidation(pair, reason, true),
+        }
+    }
+
+    /// Forward a pair loaded from the database to the invalidation sink, if
+    /// `config.mark_invalid_pairs` is set.
+    fn send_invalidation(&self, pair: &CorrelatedPair, reason: String, revalidate: bool) {
+        if !self.config.mark_invalid_pairs {
+            return;
+        }
+
+        if let (Some(tx), Some(pair_id)) = (&self.invalidation_tx, pair.db_id) {
+            let invalidation = PairInvalidation {
+                pair_id,
+                kalshi_ticker: pair.kalshi_ticker.clone(),
+                reason,
+                revalidate,
+            };
+            if tx.send(invalidation).is_err() {
+                warn!("Pair invalidation sink closed, dropping invalidation");
             }
+        }
+    }
+
+    /// Count consecutive scans whose prices imply the opposite of the pair's stored
+    /// inverse flag, suspending the pair and flagging it for re-validation on reaching
+    /// `config.revalidate_after_anomalous_scans`.
+    fn track_inverse_anomaly(&self, pair: &CorrelatedPair, inferred_inverse: Option<bool>) {
+        let Some(threshold) = self.config.revalidate_after_anomalous_scans else {
+            return;
         };
 
-        let quantity = Decimal::from(opp.max_contracts);
+        let mut anomalous_scans = self.anomalous_scans.borrow_mut();
+        if inferred_inverse != Some(!pair.inverse) {
+            anomalous_scans.remove(&pair.kalshi_ticker);
+            return;
+        }
 
-        let yes_order = OrderRequestOpen {
-            key: OrderKey {
-                exchange: yes_exchange,
-                instrument: yes_instrument,
-                strategy: self.id.clone(),
-                cid: self.next_order_id(),
-            },
-            state: RequestOpen {
-                side: Side::Buy,
-                price: opp.avg_yes_price,
-                quantity,
-                kind: barter_execution::order::OrderKind::Limit,
-                time_in_force: barter_execution::order::TimeInForce::ImmediateOrCancel,
-            },
+        let scans = anomalous_scans.entry(pair.kalshi_ticker.clone()).or_default();
+        *scans += 1;
+        if *scans == threshold.max(1) {
+            let reason = format!(
+                "prices implied inverse={} for {} consecutive scans",
+                !pair.inverse, scans
+            );
+            warn!(pair = %pair.kalshi_ticker, %reason, "Flagging pair for revalidation");
+            anomalous_scans.remove(&pair.kalshi_ticker);
+            self.suspend(pair, reason, Invalidation::Revalidate);
+        }
+    }
+
+    /// Reason a pair was suspended, if it is suspended.
+    pub fn suspension_reason(&self, kalshi_ticker: &str) -> Option<String> {
+        self.suspended.borrow().get(kalshi_ticker).cloned()
+    }
+
+    /// Smoothed top-of-book edge of a pair (profit per contract of its better
+    /// direction), updated each time the pair is evaluated.
+    ///
+    /// See [`ArbitrageConfig::min_edge_persistence`].
+    pub fn edge_ema(&self, kalshi_ticker: &str) -> Option<Decimal> {
+        self.edge_ema.borrow().get(kalshi_ticker).map(|edge| edge.ema)
+    }
+
+    /// Fold a pair's latest edge into its EMA, seeding it on first observation.
+    fn track_edge(&self, pair: &CorrelatedPair, evaluation: &PairEvaluation, now: DateTime<Utc>) {
+        let Some(edge) = [&evaluation.yes_poly_no_kalshi, &evaluation.yes_kalshi_no_poly]
+            .into_iter()
+            .filter_map(|direction| direction.top_of_book_cost)
+            .map(|cost| Decimal::ONE - cost)
+            .max()
+        else {
+            return;
         };
 
-        let no_order = OrderRequestOpen {
-            key: OrderKey {
-                exchange: no_exchange,
-                instrument: no_instrument,
-                strategy: self.id.clone(),
-                cid: self.next_order_id(),
-            },
-            state: RequestOpen {
-                side: Side::Buy,
-                price: opp.avg_no_price,
-                quantity,
-                kind: barter_execution::order::OrderKind::Limit,
-                time_in_force: barter_execution::order::TimeInForce::ImmediateOrCancel,
-            },
+        self.fold_edge(&pair.kalshi_ticker, edge, now);
+    }
+
+    /// Fold the previously observed edge into a pair's EMA, weighted by how long it
+    /// held, then record `edge` as the latest observation.
+    ///
+    /// Weighting by time rather than by scan keeps the smoothing independent of how
+    /// often pairs are scanned. Without persistence the EMA is the latest edge.
+    fn fold_edge(&self, kalshi_ticker: &SmolStr, edge: Decimal, now: DateTime<Utc>) {
+        let Some(persistence) = self.config.min_edge_persistence else {
+            let ema = EdgeEma { ema: edge, edge, updated: now };
+            self.edge_ema.borrow_mut().insert(kalshi_ticker.clone(), ema);
+            return;
         };
 
-        vec![yes_order, no_order]
+        let mut emas = self.edge_ema.borrow_mut();
+        let ema = emas
+            .entry(kalshi_ticker.clone())
+            .or_insert(EdgeEma { ema: edge, edge, updated: now });
+
+        // alpha = 1 - e^(-elapsed / persistence)
+        let elapsed = Decimal::from((now - ema.updated).num_milliseconds().max(0));
+        let persistence = Decimal::from(persistence.as_millis().max(1));
+        let decay = (-elapsed / persistence).checked_exp().unwrap_or(Decimal::ZERO);
+        ema.ema += (Decimal::ONE - decay) * (ema.edge - ema.ema);
+        ema.edge = edge;
+        ema.updated = now;
     }
-}
 
-impl AlgoStrategy<ExchangeIndex, InstrumentIndex> for PredictionArbitrageStrategy {
-    type State = ArbitrageEngineState;
+    /// Reject an opportunity whose pair's smoothed edge hasn't held above
+    /// `min_spread_threshold`, if `min_edge_persistence` is configured.
+    fn persistence_rejection(&self, opp: &ArbitrageOpportunity) -> Option<RejectionReason> {
+        self.config.min_edge_persistence?;
+        let persistent = self
+            .edge_ema(&opp.pair.kalshi_ticker)
+            .is_some_and(|ema| ema >= self.config.min_spread_threshold);
+        (!persistent).then_some(RejectionReason::EdgeNotPersistent)
+    }
 
-    fn generate_algo_orders(
-        &self,
-        state: &Self::State,
-    ) -> (
-        impl IntoIterator<Item = OrderRequestCancel<ExchangeIndex, InstrumentIndex>>,
-        impl IntoIterator<Item = OrderRequestOpen<ExchangeIndex, InstrumentIndex>>,
-    ) {
-        let books = self.build_book_map(state);
-        debug!(
-            books = books.len(),
-            instruments = self.instrument_index.len(),
-            "Strategy scanning for opportunities"
-        );
-        let opportunities = self.detect_opportunities(&books);
+    /// Track how long a pair's orderbooks have been missing, suspending the pair
+    /// once `config.missing_book_timeout_secs` is exceeded.
+    fn track_missing_books(&self, pair: &CorrelatedPair, books_present: bool, now: DateTime<Utc>) {
+        if books_present {
+            self.missing_books_since.borrow_mut().remove(&pair.kalshi_ticker);
+            return;
+        }
 
-        let valid_opps: Vec<_> = opportunities
-            .into_iter()
-            .filter(|opp| opp.meets_threshold(self.config.min_spread_threshold))
-            .filter(|opp| opp.is_profitable())
-            .filter(|opp| self.passes_position_limits(opp, state))
-            .filter(|opp| self.passes_min_order_values(opp))
+        let since = *self
+            .missing_books_since
+            .borrow_mut()
+            .entry(pair.kalshi_ticker.clone())
+            .or_insert(now);
+
+        let missing_secs = (now - since).num_seconds();
+        if missing_secs >= self.config.missing_book_timeout_secs as i64 {
+            self.suspend_pair(pair, format!("orderbooks missing for {}s", missing_secs));
+        }
+    }
+
+    /// Suspend pairs with an instrument whose market the exchange reports as
+    /// settled or determined, since it can never trade again.
+    fn suspend_terminal_markets(&self, state: &ArbitrageEngineState) {
+        let instrument_index = self.instrument_index.borrow();
+        for pair in self.pairs.borrow().iter() {
+            if self.suspended.borrow().contains_key(&pair.kalshi_ticker) {
+                continue;
+            }
+
+            let terminal = pair
+                .instrument_keys()
+                .iter()
+                .filter_map(|key| instrument_index.get(key))
+                .filter_map(|(_, inst_idx)| {
+                    state.instruments.instrument_index(inst_idx).data.market_status
+                })
+                .find(MarketStatus::is_terminal);
+            if let Some(status) = terminal {
+                self.suspend_pair(pair, format!("market {status}"));
+            }
+        }
+    }
+
+    /// Record pairs with an instrument whose book is stale, so scans skip them (see
+    /// [`ArbitrageInstrumentData::book_stale`](crate::state::ArbitrageInstrumentData::book_stale)).
+    ///
+    /// Pairs whose books are no longer stale are rescanned even if their levels
+    /// did not change meanwhile.
+    fn mark_stale_books(&self, state: &ArbitrageEngineState) {
+        let instrument_index = self.instrument_index.borrow();
+        let stale: HashSet<SmolStr> = self
+            .pairs
+            .borrow()
+            .iter()
+            .filter(|pair| {
+                pair.instrument_keys()
+                    .iter()
+                    .filter_map(|key| instrument_index.get(key))
+                    .any(|(_, inst_idx)| {
+                        state.instruments.instrument_index(inst_idx).data.book_stale
+                    })
+            })
+            .map(|pair| pair.kalshi_ticker.clone())
+            .collect();
+
+        let previous = self.stale_books.replace(stale);
+        let mut scanned = self.scanned_versions.borrow_mut();
+        for ticker in previous.difference(&self.stale_books.borrow()) {
+            scanned.remove(ticker);
+        }
+    }
+
+    /// Build a map of orderbooks from engine state using instrument_index.
+    fn build_book_map<'a>(
+        &self,
+        state: &'a ArbitrageEngineState,
+    ) -> HashMap<PredictionMarketKey, &'a OrderBook> {
+        let mut books = HashMap::new();
+        for (key, (_, inst_idx)) in self.instrument_index.borrow().iter() {
+            if let Some(book) = &state.instruments.instrument_index(inst_idx).data.orderbook {
+                books.insert(key.clone(), book);
+            }
+        }
+        books
+    }
+
+    /// Pairs to re-evaluate this scan, recording their current book versions.
+    ///
+    /// A pair is dirty if any of its books changed since it was last scanned, or
+    /// it is missing a YES book (so the missing-book timeout keeps advancing).
+    /// Pairs that produced an opportunity are forgotten after the scan, keeping
+    /// them dirty until the opportunity is gone.
+    fn dirty_pairs(
+        &self,
+        state: &ArbitrageEngineState,
+        books: &HashMap<PredictionMarketKey, &OrderBook>,
+    ) -> HashSet<SmolStr> {
+        let instrument_index = self.instrument_index.borrow();
+        let mut scanned = self.scanned_versions.borrow_mut();
+
+        self.pairs
+            .borrow()
+            .iter()
+            .filter(|pair| {
+                let version = pair
+                    .instrument_keys()
+                    .iter()
+                    .filter_map(|key| instrument_index.get(key))
+                    .map(|(_, inst_idx)| {
+                        state.instruments.instrument_index(inst_idx).data.book_version
+                    })
+                    .sum::<u64>();
+                let books_present = books.contains_key(&PredictionMarketKey::polymarket_yes(
+                    pair.polymarket_yes_token.clone(),
+                )) && books
+                    .contains_key(&PredictionMarketKey::kalshi_yes(pair.kalshi_ticker.clone()));
+
+                let unchanged = scanned.insert(pair.kalshi_ticker.clone(), version)
+                    == Some(version);
+                self.config.rescan_unchanged_pairs || !unchanged || !books_present
+            })
+            .map(|pair| pair.kalshi_ticker.clone())
+            .collect()
+    }
+
+    /// Number of pair evaluations performed so far.
+    pub fn pairs_checked(&self) -> u64 {
+        self.pairs_checked.get()
+    }
+
+    /// Generate client order IDs for the YES and NO legs of one opportunity.
+    ///
+    /// Both legs share a `{strategy}_{n}` group (see [`ArbLeg::cid`]) so the risk
+    /// manager can evaluate them as a unit, and fills and cancels can be traced
+    /// back to the opportunity.
+    fn next_leg_ids(&self) -> (ClientOrderId, ClientOrderId) {
+        let id = self.order_counter.get() + 1;
+        self.order_counter.set(id);
+        let group = format!("{}_{}", self.id.0.as_str(), id);
+        (ArbLeg::Yes.cid(&group), ArbLeg::No.cid(&group))
+    }
+
+    /// Detect arbitrage opportunities across all monitored pairs.
+    ///
+    /// Pairs sharing a Polymarket market are treated as one [`CorrelatedGroup`],
+    /// returning at most one opportunity per direction for the group.
+    pub fn detect_opportunities(
+        &self,
+        books: &HashMap<PredictionMarketKey, &OrderBook>,
+    ) -> Vec<ArbitrageOpportunity> {
+        self.detect_opportunities_where(books, |_| true)
+    }
+
+    /// Detect opportunities as [`Self::detect_opportunities`], alongside the reason
+    /// each pair that would not trade was skipped.
+    ///
+    /// Useful for diagnosing why a known-good pair isn't trading.
+    pub fn detect_opportunities_detailed(
+        &self,
+        books: &HashMap<PredictionMarketKey, &OrderBook>,
+    ) -> OpportunityScan {
+        self.scan_opportunities(books, |_| true)
+    }
+
+    /// Detect opportunities, only evaluating groups with at least one pair
+    /// accepted by `scan`.
+    fn detect_opportunities_where(
+        &self,
+        books: &HashMap<PredictionMarketKey, &OrderBook>,
+        scan: impl Fn(&CorrelatedPair) -> bool,
+    ) -> Vec<ArbitrageOpportunity> {
+        self.scan_opportunities(books, scan).opportunities
+    }
+
+    /// Scan groups with at least one pair accepted by `scan`, recording why each
+    /// pair that would not trade was skipped.
+    fn scan_opportunities(
+        &self,
+        books: &HashMap<PredictionMarketKey, &OrderBook>,
+        scan: impl Fn(&CorrelatedPair) -> bool,
+    ) -> OpportunityScan {
+        let pairs = self.pairs.borrow();
+        let mut skipped = Vec::new();
+        let mut skip = |pair: &CorrelatedPair, reason| {
+            skipped.push(PairSkip {
+                kalshi_ticker: pair.kalshi_ticker.clone(),
+                reason,
+            })
+        };
+
+        let mut groups: IndexMap<&SmolStr, Vec<&CorrelatedPair>> = IndexMap::new();
+        for pair in pairs.iter() {
+            if pair.is_expired() {
+                // Expiry ends the pair's life, it does not make the mapping invalid
+                self.suspend(pair, "expired".to_string(), Invalidation::Skip);
+            }
+            if let Some(reason) = self.suspension_reason(&pair.kalshi_ticker) {
+                skip(pair, SkipReason::Suspended(reason));
+                continue;
+            }
+            if self.stale_books.borrow().contains(&pair.kalshi_ticker) {
+                skip(pair, SkipReason::StaleBook);
+                continue;
+            }
+            if !pair.is_within_trading_window(self.config.max_days_to_expiry) {
+                skip(pair, SkipReason::OutsideTradingWindow);
+                continue;
+            }
+            groups
+                .entry(&pair.polymarket_yes_token)
+                .or_default()
+                .push(pair);
+        }
+
+        let opportunities = groups
+            .into_values()
+            .filter(|legs| legs.iter().any(|pair| scan(pair)))
+            .flat_map(|legs| self.scan_legs(legs, books, &mut skipped))
             .collect();
 
-        for opp in &valid_opps {
-            info!(
-                pair = %opp.pair.kalshi_ticker,
-                direction = ?opp.direction,
-                total_cost = %opp.total_cost,
-                contracts = opp.max_contracts,
-                profit = %opp.expected_profit,
-                "Arbitrage opportunity detected"
-            );
-        }
+        OpportunityScan {
+            opportunities,
+            skipped,
+        }
+    }
+
+    /// Check a many-to-one group for arbitrage, returning the best Kalshi leg
+    /// per direction.
+    pub fn check_group_for_arbitrage(
+        &self,
+        group: &CorrelatedGroup,
+        books: &HashMap<PredictionMarketKey, &OrderBook>,
+    ) -> Vec<ArbitrageOpportunity> {
+        self.scan_legs(&group.pairs, books, &mut Vec::new())
+    }
+
+    /// Check pairs sharing one Polymarket market, keeping the most profitable
+    /// opportunity per direction (ties go to the lower total cost) and recording
+    /// the legs that would not trade in `skipped`.
+    fn scan_legs<'a>(
+        &self,
+        legs: impl IntoIterator<Item = &'a CorrelatedPair>,
+        books: &HashMap<PredictionMarketKey, &OrderBook>,
+        skipped: &mut Vec<PairSkip>,
+    ) -> Vec<ArbitrageOpportunity> {
+        let mut best: Vec<ArbitrageOpportunity> = Vec::with_capacity(2);
+
+        for pair in legs {
+            let opportunities = match self.check_pair(pair, books) {
+                Ok(evaluation) => {
+                    if let Some(reason) = self.evaluation_skip(&evaluation) {
+                        skipped.push(PairSkip {
+                            kalshi_ticker: pair.kalshi_ticker.clone(),
+                            reason,
+                        });
+                    }
+                    Self::opportunities(evaluation)
+                }
+                Err(reason) => {
+                    skipped.push(PairSkip {
+                        kalshi_ticker: pair.kalshi_ticker.clone(),
+                        reason,
+                    });
+                    continue;
+                }
+            };
+
+            for opp in opportunities {
+                match best
+                    .iter_mut()
+                    .find(|current| current.direction == opp.direction)
+                {
+                    Some(current) => {
+                        let better = opp.expected_profit > current.expected_profit
+                            || (opp.expected_profit == current.expected_profit
+                                && opp.total_cost < current.total_cost);
+                        if better {
+                            *current = opp;
+                        }
+                    }
+                    None => best.push(opp),
+                }
+            }
+        }
+
+        best
+    }
+
+    /// Check a single correlated pair for delta-neutral arbitrage.
+    ///
+    /// Requires both YES books; the Kalshi and Polymarket NO books are used when present.
+    /// Returns every direction the depth walk found profitable; config filters
+    /// are applied later in [`Self::rejection_reason`].
+    #[cfg(test)]
+    fn check_pair_for_arbitrage(
+        &self,
+        pair: &CorrelatedPair,
+        books: &HashMap<PredictionMarketKey, &OrderBook>,
+    ) -> Vec<ArbitrageOpportunity> {
+        self.check_pair(pair, books)
+            .map(Self::opportunities)
+            .unwrap_or_default()
+    }
+
+    /// Opportunities the depth walk found in either direction of `evaluation`.
+    fn opportunities(evaluation: PairEvaluation) -> Vec<ArbitrageOpportunity> {
+        [evaluation.yes_poly_no_kalshi, evaluation.yes_kalshi_no_poly]
+            .into_iter()
+            .filter_map(|direction| direction.opportunity)
+            .collect()
+    }
+
+    /// Evaluate a single correlated pair against the current books, failing if
+    /// either YES book is missing.
+    fn check_pair(
+        &self,
+        pair: &CorrelatedPair,
+        books: &HashMap<PredictionMarketKey, &OrderBook>,
+    ) -> Result<PairEvaluation, SkipReason> {
+        self.pairs_checked.set(self.pairs_checked.get() + 1);
+
+        let poly_yes_key =
+            PredictionMarketKey::polymarket_yes(pair.polymarket_yes_token.clone());
+        let kalshi_yes_key = PredictionMarketKey::kalshi_yes(pair.kalshi_ticker.clone());
+
+        let now = Utc::now();
+        let (poly_yes_book, kalshi_yes_book) =
+            match (books.get(&poly_yes_key), books.get(&kalshi_yes_key)) {
+                (Some(poly), Some(kalshi)) => {
+                    self.track_missing_books(pair, true, now);
+                    (*poly, *kalshi)
+                }
+                (poly, _) => {
+                    self.track_missing_books(pair, false, now);
+                    return Err(if poly.is_none() {
+                        SkipReason::MissingPolymarketBook
+                    } else {
+                        SkipReason::MissingKalshiBook
+                    });
+                }
+            };
+
+        // Prefer the real NO books if their instruments receive market data
+        let kalshi_no_book = books
+            .get(&PredictionMarketKey::kalshi_no(pair.kalshi_ticker.clone()))
+            .copied();
+        let poly_no_book = books
+            .get(&PredictionMarketKey::polymarket_no(pair.polymarket_no_token.clone()))
+            .copied();
+
+        let inferred_inverse = pair.infer_inverse(poly_yes_book, kalshi_yes_book);
+        self.track_inverse_anomaly(pair, inferred_inverse);
+
+        // Optionally trust the inverse flag implied by current prices over the stored one
+        let inferred_pair = inferred_inverse
+            .filter(|_| self.config.prefer_inferred_inverse)
+            .filter(|&inverse| inverse != pair.inverse)
+            .map(|inverse| {
+                debug!(
+                    "{}: prices imply inverse={} (stored {}), using inferred value",
+                    pair.kalshi_ticker, inverse, pair.inverse
+                );
+                CorrelatedPair {
+                    inverse,
+                    ..pair.clone()
+                }
+            });
+        let pair = inferred_pair.as_ref().unwrap_or(pair);
+
+        let evaluation = self.evaluate_pair_with_no_books(
+            pair,
+            poly_yes_book,
+            kalshi_yes_book,
+            kalshi_no_book,
+            poly_no_book,
+        );
+        self.track_edge(pair, &evaluation, now);
+        Ok(evaluation)
+    }
+
+    /// Why an evaluated pair would not trade, if it would not.
+    ///
+    /// An actionable pair is still skipped if one of its legs has no instrument
+    /// index to order with. Otherwise the reason is taken from the closest
+    /// direction: one the depth walk found profitable, else the cheapest.
+    fn evaluation_skip(&self, evaluation: &PairEvaluation) -> Option<SkipReason> {
+        if let Some(best) = evaluation.best() {
+            let opp = best.opportunity.as_ref()?;
+            return [&opp.yes_side, &opp.no_side]
+                .into_iter()
+                .find_map(|side| self.order_indices(side).err());
+        }
+
+        let closest = evaluation.directions().into_iter().min_by_key(|direction| {
+            (
+                direction.opportunity.is_none(),
+                direction.top_of_book_cost.unwrap_or(Decimal::MAX),
+            )
+        })?;
+        closest.rejection.map(SkipReason::Rejected)
+    }
+
+    /// Evaluate both arbitrage directions for a pair against the given YES books.
+    ///
+    /// Pure: does not touch suspension tracking, order ids or sinks, so it can be
+    /// used by tooling to inspect the current edge without running the engine.
+    /// Uses only YES orderbooks; derives NO asks from YES bids and applies the
+    /// inverse flag.
+    pub fn evaluate_pair(
+        &self,
+        pair: &CorrelatedPair,
+        poly_yes_book: &OrderBook,
+        kalshi_yes_book: &OrderBook,
+    ) -> PairEvaluation {
+        self.evaluate_pair_with_no_books(pair, poly_yes_book, kalshi_yes_book, None, None)
+    }
+
+    /// Evaluate a pair, using the real Kalshi and Polymarket NO orderbooks when available.
+    ///
+    /// Both venues keep separate YES and NO order flow (Polymarket NO is its own
+    /// token with an independent book), so a subscribed NO book can diverge from
+    /// the NO asks derived from YES bids (e.g. if the YES book is stale). Falls
+    /// back to derivation for each venue whose NO book is `None`.
+    pub fn evaluate_pair_with_no_books(
+        &self,
+        pair: &CorrelatedPair,
+        poly_yes_book: &OrderBook,
+        kalshi_yes_book: &OrderBook,
+        kalshi_no_book: Option<&OrderBook>,
+        poly_no_book: Option<&OrderBook>,
+    ) -> PairEvaluation {
+        // Derive NO asks from YES bids unless the real NO book is available
+        let poly_no_asks = match poly_no_book {
+            Some(no_book) => no_book.asks().levels().to_vec(),
+            None => derive_no_asks(poly_yes_book),
+        };
+        let kalshi_no_asks = match kalshi_no_book {
+            Some(no_book) => no_book.asks().levels().to_vec(),
+            None => derive_no_asks(kalshi_yes_book),
+        };
+
+        // Inverse: Polymarket YES = Kalshi NO, so the Kalshi YES book represents
+        // the "NO" side from Polymarket's perspective and the legs swap
+        let (kalshi_semantic_yes, kalshi_semantic_no) = if pair.inverse {
+            (kalshi_no_asks.as_slice(), kalshi_yes_book.asks().levels())
+        } else {
+            (kalshi_yes_book.asks().levels(), kalshi_no_asks.as_slice())
+        };
+
+        PairEvaluation {
+            pair: pair.clone(),
+            // Direction 1: Buy Poly YES + Buy Kalshi NO
+            yes_poly_no_kalshi: self.evaluate_direction(
+                pair,
+                ArbitrageDirection::YesPolyNoKalshi,
+                poly_yes_book.asks().levels(),
+                kalshi_semantic_no,
+            ),
+            // Direction 2: Buy Kalshi YES + Buy Poly NO
+            yes_kalshi_no_poly: self.evaluate_direction(
+                pair,
+                ArbitrageDirection::YesKalshiNoPoly,
+                kalshi_semantic_yes,
+                &poly_no_asks,
+            ),
+        }
+    }
+
+    /// Walk one direction's books and apply the config filters.
+    fn evaluate_direction(
+        &self,
+        pair: &CorrelatedPair,
+        direction: ArbitrageDirection,
+        yes_asks: &[Level],
+        no_asks: &[Level],
+    ) -> DirectionEvaluation {
+        let result = walk_orderbook_levels(
+            yes_asks,
+            no_asks,
+            direction.yes_exchange(),
+            direction.no_exchange(),
+            self.poly_fee_bps,
+            &self.config,
+        );
+
+        // Kalshi contract actually bought (swapped for inverse pairs)
+        let (kalshi_yes, kalshi_no) = if pair.inverse {
+            (Outcome::No, Outcome::Yes)
+        } else {
+            (Outcome::Yes, Outcome::No)
+        };
+
+        let profitable = result.total_size > 0 && result.total_profit > Decimal::ZERO;
+        let opportunity = profitable.then(|| {
+            let (yes_side, no_side) = match direction {
+                ArbitrageDirection::YesPolyNoKalshi => (
+                    OrderSide::poly(
+                        pair.polymarket_yes_token.clone(),
+                        Outcome::Yes,
+                        result.avg_yes_price,
+                        result.total_size,
+                    ),
+                    OrderSide::kalshi(
+                        pair.kalshi_ticker.clone(),
+                        kalshi_no,
+                        result.avg_no_price,
+                        result.total_size,
+                    ),
+                ),
+                ArbitrageDirection::YesKalshiNoPoly => (
+                    OrderSide::kalshi(
+                        pair.kalshi_ticker.clone(),
+                        kalshi_yes,
+                        result.avg_yes_price,
+                        result.total_size,
+                    ),
+                    OrderSide::poly(
+                        pair.polymarket_no_token.clone(),
+                        Outcome::No,
+                        result.avg_no_price,
+                        result.total_size,
+                    ),
+                ),
+            };
+
+            ArbitrageOpportunity {
+                pair: pair.clone(),
+                direction,
+                yes_side,
+                no_side,
+                total_cost: result.total_cost,
+                avg_yes_price: result.avg_yes_price,
+                avg_no_price: result.avg_no_price,
+                max_contracts: result.total_size,
+                expected_profit: result.total_profit,
+                total_fees: result.total_fees,
+            }
+        });
+
+        let rejection = match &opportunity {
+            Some(opp) => self
+                .rejection_reason(opp)
+                .or_else(|| self.depth_rejection(yes_asks, no_asks)),
+            None if yes_asks.is_empty() || no_asks.is_empty() => Some(RejectionReason::NoLiquidity),
+            None => Some(RejectionReason::Unprofitable),
+        };
+
+        DirectionEvaluation {
+            direction,
+            top_of_book_cost: result.top_of_book_cost,
+            avg_yes_price: result.avg_yes_price,
+            avg_no_price: result.avg_no_price,
+            total_cost: result.total_cost,
+            total_fees: result.total_fees,
+            max_contracts: result.total_size,
+            expected_profit: result.total_profit,
+            opportunity,
+            rejection,
+        }
+    }
+
+    /// Return the first config filter an opportunity fails, if any.
+    fn rejection_reason(&self, opp: &ArbitrageOpportunity) -> Option<RejectionReason> {
+        if !opp.meets_threshold(self.config.min_spread_threshold) {
+            Some(RejectionReason::BelowThreshold)
+        } else if !opp.is_profitable() {
+            Some(RejectionReason::Unprofitable)
+        } else if opp.expected_profit < self.config.min_total_profit {
+            Some(RejectionReason::BelowMinProfit)
+        } else if !self.passes_position_limits(opp) {
+            Some(RejectionReason::PositionLimit)
+        } else if !self.passes_min_order_values(opp) {
+            Some(RejectionReason::MinOrderValue)
+        } else {
+            None
+        }
+    }
+
+    /// Charge an opportunity's order pair to the undeployed `capital` left under
+    /// `max_total_capital`, and within the free exchange balances once known, rejecting
+    /// it if the pair doesn't fit.
+    fn capital_rejection(
+        opp: &ArbitrageOpportunity,
+        capital: &mut Decimal,
+    ) -> Option<RejectionReason> {
+        let required = opp.capital_required();
+        if required > *capital {
+            return Some(RejectionReason::InsufficientCapital);
+        }
+        *capital -= required;
+        None
+    }
+
+    /// Reject a direction whose YES or NO asks hold fewer than `min_book_depth`
+    /// contracts within `book_depth_band` of the best ask, if configured.
+    fn depth_rejection(&self, yes_asks: &[Level], no_asks: &[Level]) -> Option<RejectionReason> {
+        let min_depth = self.config.min_book_depth?;
+        let band = self.config.book_depth_band;
+        let thin = [yes_asks, no_asks]
+            .into_iter()
+            .any(|asks| depth_within(asks, band) < min_depth);
+        thin.then_some(RejectionReason::ThinBook)
+    }
+
+    /// Forward an opportunity to the recording sink, if enabled.
+    fn record_opportunity(&self, opp: &ArbitrageOpportunity, rejection: Option<&'static str>) {
+        if !self.config.record_opportunities
+            || (rejection.is_some() && !self.config.record_rejected_opportunities)
+        {
+            return;
+        }
+
+        if let Some(tx) = &self.opportunity_tx {
+            if tx.send((opp.clone(), rejection)).is_err() {
+                warn!("Opportunity sink closed, dropping opportunity record");
+            }
+        }
+    }
+
+    /// Check if an opportunity passes minimum order value requirements.
+    fn passes_min_order_values(&self, opp: &ArbitrageOpportunity) -> bool {
+        let yes_value = opp.yes_side.order_value();
+        let no_value = opp.no_side.order_value();
+
+        let yes_min = match opp.yes_side.exchange {
+            ExchangeId::Polymarket => self.config.min_order_value.polymarket,
+            ExchangeId::Kalshi => self.config.min_order_value.kalshi,
+            _ => Decimal::ZERO,
+        };
+        let no_min = match opp.no_side.exchange {
+            ExchangeId::Polymarket => self.config.min_order_value.polymarket,
+            ExchangeId::Kalshi => self.config.min_order_value.kalshi,
+            _ => Decimal::ZERO,
+        };
+
+        yes_value >= yes_min && no_value >= no_min
+    }
+
+    /// Check if an opportunity passes position limits.
+    fn passes_position_limits(&self, opp: &ArbitrageOpportunity) -> bool {
+        opp.max_contracts <= self.config.max_position_per_market
+    }
+
+    /// Engine indices to order `side` with.
+    fn order_indices(
+        &self,
+        side: &OrderSide,
+    ) -> Result<(ExchangeIndex, InstrumentIndex), SkipReason> {
+        self.instrument_index
+            .borrow()
+            .get(&side.instrument)
+            .copied()
+            .ok_or_else(|| SkipReason::IndexNotMapped(side.instrument.clone()))
+    }
+
+    /// Time in force of an arbitrage leg on `exchange`.
+    ///
+    /// Polymarket legs are all-or-nothing fill-or-kill unless `polymarket_fill_and_kill`
+    /// opts into partial fills. Other legs are immediate-or-cancel, so a partially filled
+    /// leg leaves the other to be reconciled by [`Self::leg_risk_orders`].
+    fn leg_time_in_force(&self, exchange: ExchangeId) -> TimeInForce {
+        match exchange {
+            ExchangeId::Polymarket if !self.config.polymarket_fill_and_kill => {
+                TimeInForce::FillOrKill
+            }
+            _ => TimeInForce::ImmediateOrCancel,
+        }
+    }
+
+    /// Generate a pair of BUY orders for a valid opportunity.
+    fn generate_order_pair(
+        &self,
+        opp: &ArbitrageOpportunity,
+    ) -> Vec<OrderRequestOpen<ExchangeIndex, InstrumentIndex>> {
+        let indices = self
+            .order_indices(&opp.yes_side)
+            .and_then(|yes| Ok((yes, self.order_indices(&opp.no_side)?)));
+        let ((yes_exchange, yes_instrument), (no_exchange, no_instrument)) = match indices {
+            Ok(indices) => indices,
+            Err(reason) => {
+                warn!(pair = %opp.pair.kalshi_ticker, ?reason, "Skipping opportunity");
+                return vec![];
+            }
+        };
+
+        let quantity = Decimal::from(opp.max_contracts);
+        let (yes_cid, no_cid) = self.next_leg_ids();
+
+        let yes_order = OrderRequestOpen {
+            key: OrderKey {
+                exchange: yes_exchange,
+                instrument: yes_instrument,
+                strategy: self.id.clone(),
+                cid: yes_cid,
+            },
+            state: RequestOpen {
+                side: Side::Buy,
+                price: opp.avg_yes_price,
+                quantity,
+                kind: barter_execution::order::OrderKind::Limit,
+                time_in_force: self.leg_time_in_force(opp.yes_side.exchange),
+            },
+        };
+
+        let no_order = OrderRequestOpen {
+            key: OrderKey {
+                exchange: no_exchange,
+                instrument: no_instrument,
+                strategy: self.id.clone(),
+                cid: no_cid,
+            },
+            state: RequestOpen {
+                side: Side::Buy,
+                price: opp.avg_no_price,
+                quantity,
+                kind: barter_execution::order::OrderKind::Limit,
+                time_in_force: self.leg_time_in_force(opp.no_side.exchange),
+            },
+        };
+
+        vec![yes_order, no_order]
+    }
+
+    /// Track the YES/NO leg pairs in `opens` (as built by [`Self::generate_order_pair`])
+    /// for [`Self::leg_risk_orders`].
+    fn track_leg_groups(
+        &self,
+        opens: &[OrderRequestOpen<ExchangeIndex, InstrumentIndex>],
+        now: DateTime<Utc>,
+    ) {
+        let mut groups = self.leg_groups.borrow_mut();
+        for legs in opens.chunks_exact(2) {
+            let [yes, no] = legs else { continue };
+            let Some((group, _)) = ArbLeg::parse_cid(&yes.key.cid) else {
+                continue;
+            };
+            groups.insert(
+                SmolStr::new(group),
+                LegGroup {
+                    sent: now,
+                    quantity: yes.state.quantity,
+                    yes: yes.key.clone(),
+                    no: no.key.clone(),
+                    flattens: Vec::new(),
+                    last_flatten: None,
+                },
+            );
+        }
+    }
+
+    /// Flatten one-legged fills once `leg_confirm_timeout` has passed.
+    ///
+    /// A group whose legs have both fully filled is done. Otherwise, once the
+    /// timeout has passed, legs still working are cancelled and the excess of the
+    /// filled leg, net of earlier flattening fills, is sold at its best bid with an
+    /// IOC order. Quantities come from the fills, not the order states.
+    ///
+    /// The group is kept until it has no excess and no working legs. A flattening
+    /// order that is refused or fills short is retried once it is no longer working,
+    /// at most once per `leg_confirm_timeout`.
+    fn leg_risk_orders(
+        &self,
+        state: &ArbitrageEngineState,
+        now: DateTime<Utc>,
+    ) -> LegRiskOrders {
+        let timeout =
+            TimeDelta::from_std(self.config.leg_confirm_timeout).unwrap_or(TimeDelta::MAX);
+        self.leg_risk_orders_after(state, now, timeout)
+    }
+
+    /// Flatten every one-legged fill straight away, without waiting for
+    /// `leg_confirm_timeout`.
+    ///
+    /// Used when trading is disabled, since `generate_algo_orders` is no longer
+    /// called to flatten them once the timeout passes.
+    fn leg_risk_orders_on_disable(
+        &self,
+        state: &ArbitrageEngineState,
+        now: DateTime<Utc>,
+    ) -> LegRiskOrders {
+        self.leg_risk_orders_after(state, now, TimeDelta::zero())
+    }
+
+    fn leg_risk_orders_after(
+        &self,
+        state: &ArbitrageEngineState,
+        now: DateTime<Utc>,
+        timeout: TimeDelta,
+    ) -> LegRiskOrders {
+        let positions = &state.global.arb_positions;
+        let working = |key: &OrderKey<ExchangeIndex, InstrumentIndex>| {
+            state
+                .instruments
+                .instrument_index(&key.instrument)
+                .orders
+                .0
+                .get(&key.cid)
+        };
+        let mut cancels = Vec::new();
+        let mut opens = Vec::new();
+
+        self.leg_groups.borrow_mut().retain(|group, legs| {
+            let yes_filled = positions.filled_quantity(&legs.yes.cid);
+            let no_filled = positions.filled_quantity(&legs.no.cid);
+            if yes_filled >= legs.quantity && no_filled >= legs.quantity {
+                return false;
+            }
+            if now - legs.sent < timeout {
+                return true;
+            }
+
+            // Cancel legs still resting, once; a cancel in flight is no longer Open
+            let mut legs_working = false;
+            for key in [&legs.yes, &legs.no] {
+                let Some(order) = working(key) else { continue };
+                legs_working = true;
+                if matches!(order.state, ActiveOrderState::Open(_)) {
+                    cancels.extend(order.to_request_cancel());
+                }
+            }
+
+            let flattened = |leg| {
+                legs.flattens
+                    .iter()
+                    .filter(|key| ArbLeg::from_cid(&key.cid) == Some(leg))
+                    .map(|key| positions.filled_quantity(&key.cid))
+                    .sum::<Decimal>()
+            };
+            let excess =
+                (yes_filled - flattened(ArbLeg::Yes)) - (no_filled - flattened(ArbLeg::No));
+            let (filled, lagging, leg) = match excess.cmp(&Decimal::ZERO) {
+                std::cmp::Ordering::Greater => (&legs.yes, &legs.no, ArbLeg::Yes),
+                std::cmp::Ordering::Less => (&legs.no, &legs.yes, ArbLeg::No),
+                std::cmp::Ordering::Equal => return legs_working,
+            };
+
+            // Wait for the previous flattening order to finish before retrying
+            if legs.flattens.last().is_some_and(|key| working(key).is_some())
+                || legs.last_flatten.is_some_and(|sent| now - sent < timeout)
+            {
+                return true;
+            }
+
+            // Sell into the best bid, or the lowest tick if the book is empty
+            let price = state
+                .instruments
+                .instrument_index(&filled.instrument)
+                .data
+                .best_bid()
+                .unwrap_or(Decimal::new(1, 2));
+            let quantity = excess.abs();
+            let attempt = legs.flattens.len() + 1;
+
+            warn!(
+                group = %group,
+                filled = %filled.cid.0,
+                lagging = %lagging.cid.0,
+                %quantity,
+                %price,
+                attempt,
+                "Leg not confirmed in time, flattening the filled leg"
+            );
+
+            let key = OrderKey {
+                exchange: filled.exchange,
+                instrument: filled.instrument,
+                strategy: self.id.clone(),
+                cid: leg.flatten_cid(group, attempt),
+            };
+            opens.push(OrderRequestOpen {
+                key: key.clone(),
+                state: RequestOpen {
+                    side: Side::Sell,
+                    price,
+                    quantity,
+                    kind: barter_execution::order::OrderKind::Limit,
+                    time_in_force: barter_execution::order::TimeInForce::ImmediateOrCancel,
+                },
+            });
+            legs.flattens.push(key);
+            legs.last_flatten = Some(now);
+            true
+        });
+
+        (cancels, opens)
+    }
+}
+
+impl AlgoStrategy<ExchangeIndex, InstrumentIndex> for PredictionArbitrageStrategy {
+    type State = ArbitrageEngineState;
+
+    fn generate_algo_orders(
+        &self,
+        state: &Self::State,
+    ) -> (
+        impl IntoIterator<Item = OrderRequestCancel<ExchangeIndex, InstrumentIndex>>,
+        impl IntoIterator<Item = OrderRequestOpen<ExchangeIndex, InstrumentIndex>>,
+    ) {
+        self.drain_pair_updates();
+        self.suspend_terminal_markets(state);
+        self.mark_stale_books(state);
+
+        let books = self.build_book_map(state);
+        debug!(
+            books = books.len(),
+            instruments = self.instrument_index.borrow().len(),
+            "Strategy scanning for opportunities"
+        );
+        let dirty = self.dirty_pairs(state, &books);
+        let opportunities =
+            self.detect_opportunities_where(&books, |pair| dirty.contains(&pair.kalshi_ticker));
+        {
+            let mut scanned = self.scanned_versions.borrow_mut();
+            for opp in &opportunities {
+                scanned.remove(&opp.pair.kalshi_ticker);
+            }
+        }
+
+        // Fund the highest-edge opportunities first when capital only covers some of them
+        let mut opportunities = opportunities;
+        opportunities.sort_by(|a, b| {
+            b.expected_profit
+                .cmp(&a.expected_profit)
+                .then_with(|| a.pair.kalshi_ticker.cmp(&b.pair.kalshi_ticker))
+        });
+        let mut capital = self.config.max_total_capital - state.global.total_deployed;
+        if let Some(available) = state.global.available_capital() {
+            capital = capital.min(available);
+        }
+
+        let valid_opps: Vec<_> = opportunities
+            .into_iter()
+            .filter(|opp| {
+                let rejection = self
+                    .rejection_reason(opp)
+                    .or_else(|| self.persistence_rejection(opp))
+                    .or_else(|| Self::capital_rejection(opp, &mut capital))
+                    .map(|reason| reason.as_str());
+                self.record_opportunity(opp, rejection);
+                if let Some(metrics) = &self.metrics {
+                    metrics.opportunity(rejection);
+                }
+                rejection.is_none()
+            })
+            .collect();
+
+        for opp in &valid_opps {
+            info!(
+                pair = %opp.pair.kalshi_ticker,
+                opportunity = %opp,
+                "Arbitrage opportunity detected"
+            );
+            if let Some(tx) = &self.opportunity_events {
+                // Errors only if there are currently no subscribers
+                let _ = tx.send(opp.clone());
+            }
+        }
+
+        let now = Utc::now();
+        let (cancels, flattens) = self.leg_risk_orders(state, now);
+
+        let mut opens: Vec<OrderRequestOpen<ExchangeIndex, InstrumentIndex>> = valid_opps
+            .iter()
+            .flat_map(|opp| self.generate_order_pair(opp))
+            .collect();
+
+        if self.config.dry_run {
+            for open in opens.drain(..) {
+                info!(
+                    cid = %open.key.cid.0,
+                    instrument = ?open.key.instrument,
+                    side = ?open.state.side,
+                    price = %open.state.price,
+                    quantity = %open.state.quantity,
+                    "Dry run: would send order"
+                );
+            }
+        }
+
+        self.track_leg_groups(&opens, now);
+        opens.extend(flattens);
+
+        if let Some(metrics) = &self.metrics {
+            if !self.config.dry_run {
+                for opp in &valid_opps {
+                    if let Some(updated) = self.pair_book_updated(state, &opp.pair) {
+                        metrics.observe_event_to_order(now - updated);
+                    }
+                }
+            }
+            for open in &opens {
+                if let Some(&exchange) = state.global.exchange_ids.get(&open.key.exchange) {
+                    metrics.order_generated(exchange);
+                }
+            }
+            self.update_metrics(metrics, state, now);
+        }
+
+        self.publish_positions(&state.global.arb_positions);
+
+        if let Some(persistence) = &self.persistence {
+            persistence.save_if_due(state, self, now);
+        }
+
+        (cancels, opens)
+    }
+}
+
+/// Disabling trading stops `generate_algo_orders`, so legs still working are
+/// cancelled and one-legged fills are flattened straight away. Like
+/// `ClosePositions`, the requests bypass the risk manager.
+impl<Clock, ExecutionTxs, Risk> OnTradingDisabled<Clock, ArbitrageEngineState, ExecutionTxs, Risk>
+    for PredictionArbitrageStrategy
+where
+    ExecutionTxs: ExecutionTxMap,
+{
+    type OnTradingDisabled = SendCancelsAndOpensOutput;
+
+    fn on_trading_disabled(
+        engine: &mut Engine<Clock, ArbitrageEngineState, ExecutionTxs, Self, Risk>,
+    ) -> Self::OnTradingDisabled {
+        let (cancels, opens) = engine
+            .strategy
+            .leg_risk_orders_on_disable(&engine.state, Utc::now());
+
+        let cancels = engine.send_requests(cancels);
+        let opens = engine.send_requests(opens);
+
+        engine.state.record_in_flight_cancels(&cancels.sent);
+        engine.state.record_in_flight_opens(&opens.sent);
+
+        SendCancelsAndOpensOutput::new(cancels, opens)
+    }
+}
+
+impl<Clock, State, ExecutionTxs, Risk> OnDisconnectStrategy<Clock, State, ExecutionTxs, Risk>
+    for PredictionArbitrageStrategy
+{
+    type OnDisconnect = ();
+
+    fn on_disconnect(
+        _engine: &mut Engine<Clock, State, ExecutionTxs, Self, Risk>,
+        _exchange: ExchangeId,
+    ) -> Self::OnDisconnect {
+    }
+}
+
+impl ClosePositionsStrategy for PredictionArbitrageStrategy {
+    type State = ArbitrageEngineState;
+
+    fn close_positions_requests<'a>(
+        &'a self,
+        _state: &'a Self::State,
+        _filter: &'a InstrumentFilter,
+    ) -> (
+        impl IntoIterator<Item = OrderRequestCancel<ExchangeIndex, InstrumentIndex>> + 'a,
+        impl IntoIterator<Item = OrderRequestOpen<ExchangeIndex, InstrumentIndex>> + 'a,
+    )
+    where
+        ExchangeIndex: 'a,
+        AssetIndex: 'a,
+        InstrumentIndex: 'a,
+    {
+        (std::iter::empty(), std::iter::empty())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use barter_data::books::Level;
+    use barter_execution::order::Order;
+    use chrono::Utc;
+    use rust_decimal_macros::dec;
+
+    fn test_config() -> ArbitrageConfig {
+        ArbitrageConfig {
+            min_spread_threshold: dec!(0.02),
+            max_position_per_market: 1000,
+            max_total_capital: dec!(10000),
+            min_order_value: crate::config::MinOrderValues {
+                kalshi: Decimal::ZERO,
+                polymarket: dec!(1),
+            },
+            max_days_to_expiry: Some(90),
+            ..Default::default()
+        }
+    }
+
+    fn test_pair() -> CorrelatedPair {
+        CorrelatedPair::new(
+            "KXTEST",
+            "0xcondition",
+            "0xyes",
+            "0xno",
+            "Test market",
+            Utc::now() + chrono::Duration::days(30),
+            false,
+        )
+    }
+
+    fn inverse_pair() -> CorrelatedPair {
+        CorrelatedPair::new(
+            "KXTEST",
+            "0xcondition",
+            "0xyes",
+            "0xno",
+            "Test market (inverse)",
+            Utc::now() + chrono::Duration::days(30),
+            true,
+        )
+    }
+
+    #[test]
+    fn test_strategy_creation() {
+        let strategy = PredictionArbitrageStrategy::new(
+            StrategyId::new("test-arb"),
+            test_config(),
+            vec![test_pair()],
+        );
+
+        assert_eq!(strategy.pairs().len(), 1);
+        assert_eq!(strategy.poly_fee_bps, 50);
+    }
+
+    #[test]
+    fn test_derive_no_asks() {
+        // YES bids: [0.55x100, 0.52x200, 0.50x150] (high to low)
+        let book = OrderBook::new(
+            1,
+            None,
+            vec![
+                Level::new(dec!(0.55), dec!(100)),
+                Level::new(dec!(0.52), dec!(200)),
+                Level::new(dec!(0.50), dec!(150)),
+            ],
+            vec![Level::new(dec!(0.57), dec!(50))],
+        );
+
+        let no_asks = derive_no_asks(&book);
+        assert_eq!(no_asks.len(), 3);
+        // Should be ascending: 0.45, 0.48, 0.50
+        assert_eq!(no_asks[0].price, dec!(0.45));
+        assert_eq!(no_asks[0].amount, dec!(100));
+        assert_eq!(no_asks[1].price, dec!(0.48));
+        assert_eq!(no_asks[1].amount, dec!(200));
+        assert_eq!(no_asks[2].price, dec!(0.50));
+        assert_eq!(no_asks[2].amount, dec!(150));
+    }
+
+    #[test]
+    fn test_walk_profitable_levels() {
+        // YES asks: 40c x 100
+        let yes_asks = vec![Level::new(dec!(0.40), dec!(100))];
+        // NO asks: 54c x 100
+        let no_asks = vec![Level::new(dec!(0.54), dec!(100))];
+
+        // Cost = 0.40 + 0.54 = 0.94 before fees < 1.00
+        let result = walk_orderbook_levels(
+            &yes_asks,
+            &no_asks,
+            ExchangeId::Polymarket,
+            ExchangeId::Kalshi,
+            50,
+            &ArbitrageConfig::default(),
+        );
+
+        assert!(result.total_size > 0);
+        assert!(result.total_profit > Decimal::ZERO);
+        assert!(result.total_cost < Decimal::ONE);
+    }
+
+    #[test]
+    fn test_walk_unprofitable_levels() {
+        // YES asks: 55c x 100
+        let yes_asks = vec![Level::new(dec!(0.55), dec!(100))];
+        // NO asks: 50c x 100
+        let no_asks = vec![Level::new(dec!(0.50), dec!(100))];
+
+        // Cost = 0.55 + 0.50 = 1.05 > 1.00 (not profitable even before fees)
+        let result = walk_orderbook_levels(
+            &yes_asks,
+            &no_asks,
+            ExchangeId::Polymarket,
+            ExchangeId::Kalshi,
+            50,
+            &ArbitrageConfig::default(),
+        );
+
+        assert_eq!(result.total_size, 0);
+        assert_eq!(result.total_profit, Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_walk_depth_stops_at_unprofitable() {
+        // YES asks: 2 levels - 40c x 50, 48c x 100
+        let yes_asks = vec![
+            Level::new(dec!(0.40), dec!(50)),
+            Level::new(dec!(0.48), dec!(100)),
+        ];
+        // NO asks: 54c x 200
+        let no_asks = vec![Level::new(dec!(0.54), dec!(200))];
+
+        // Level 1: 0.40 + 0.54 = 0.94 + fees < 1.00 → fill 50
+        // Level 2: 0.48 + 0.54 = 1.02 + fees > 1.00 → stop
+        let result = walk_orderbook_levels(
+            &yes_asks,
+            &no_asks,
+            ExchangeId::Polymarket,
+            ExchangeId::Kalshi,
+            50,
+            &ArbitrageConfig::default(),
+        );
+
+        assert_eq!(result.total_size, 50);
+    }
+
+    #[test]
+    fn test_walk_halts_at_max_levels() {
+        let yes_asks = vec![
+            Level::new(dec!(0.40), dec!(10)),
+            Level::new(dec!(0.41), dec!(10)),
+            Level::new(dec!(0.42), dec!(10)),
+        ];
+        let no_asks = vec![Level::new(dec!(0.50), dec!(100))];
+        let walk = |max_walk_levels| {
+            let config = ArbitrageConfig {
+                max_walk_levels,
+                ..ArbitrageConfig::default()
+            };
+            walk_orderbook_levels(
+                &yes_asks,
+                &no_asks,
+                ExchangeId::Polymarket,
+                ExchangeId::Kalshi,
+                50,
+                &config,
+            )
+        };
+
+        assert_eq!(walk(None).total_size, 30);
+        assert_eq!(walk(Some(2)).total_size, 20);
+        assert_eq!(walk(Some(1)).total_size, 10);
+        assert_eq!(walk(Some(0)).total_size, 0);
+    }
+
+    #[test]
+    fn test_walk_halts_at_max_contracts() {
+        let yes_asks = vec![
+            Level::new(dec!(0.40), dec!(10)),
+            Level::new(dec!(0.41), dec!(10)),
+        ];
+        let no_asks = vec![Level::new(dec!(0.50), dec!(100))];
+        let config = ArbitrageConfig {
+            max_contracts_per_opportunity: Some(15),
+            ..ArbitrageConfig::default()
+        };
+
+        let result = walk_orderbook_levels(
+            &yes_asks,
+            &no_asks,
+            ExchangeId::Polymarket,
+            ExchangeId::Kalshi,
+            50,
+            &config,
+        );
+
+        // 10 at 40c, then only 5 of the 41c level
+        assert_eq!(result.total_size, 15);
+        assert_eq!(result.avg_yes_price, (dec!(4.00) + dec!(2.05)) / dec!(15));
+    }
+
+    #[test]
+    fn test_delta_neutral_detection() {
+        let strategy = PredictionArbitrageStrategy::new(
+            StrategyId::new("test-arb"),
+            test_config(),
+            vec![test_pair()],
+        );
+
+        let pair = test_pair();
+
+        // Poly YES book: ask 40c, bid 38c
+        // → Poly NO asks derived from bids: 1-0.38 = 62c
+        // Kalshi YES book: ask 48c, bid 55c
+        // → Kalshi NO asks derived from bids: 1-0.55 = 45c
+        //
+        // Direction 1 (Poly YES + Kalshi NO): 0.40 + 0.45 = 0.85 + fees < 1.00
+        // Direction 2 (Kalshi YES + Poly NO): 0.48 + 0.62 = 1.10 > 1.00
+        let poly_yes_book = OrderBook::new(
+            1,
+            None,
+            vec![Level::new(dec!(0.38), dec!(100))],
+            vec![Level::new(dec!(0.40), dec!(100))],
+        );
+        let kalshi_yes_book = OrderBook::new(
+            1,
+            None,
+            vec![Level::new(dec!(0.55), dec!(100))],
+            vec![Level::new(dec!(0.48), dec!(100))],
+        );
+
+        let mut books: HashMap<PredictionMarketKey, &OrderBook> = HashMap::new();
+        books.insert(
+            PredictionMarketKey::polymarket_yes(pair.polymarket_yes_token.clone()),
+            &poly_yes_book,
+        );
+        books.insert(
+            PredictionMarketKey::kalshi_yes(pair.kalshi_ticker.clone()),
+            &kalshi_yes_book,
+        );
+
+        let opps = strategy.detect_opportunities(&books);
+
+        assert!(!opps.is_empty());
+        let opp = &opps[0];
+        assert_eq!(opp.direction, ArbitrageDirection::YesPolyNoKalshi);
+        assert!(opp.is_profitable());
+        assert!(opp.total_cost < Decimal::ONE);
+    }
+
+    #[test]
+    fn test_inverse_flag_swaps_kalshi() {
+        let strategy = PredictionArbitrageStrategy::new(
+            StrategyId::new("test-arb"),
+            test_config(),
+            vec![inverse_pair()],
+        );
+
+        let pair = inverse_pair();
+
+        // Kalshi YES book (= semantic NO in inverse): ask 45c, bid 55c
+        // Poly YES book: ask 40c, bid 38c
+        //
+        // With inverse, Direction 1 (Poly YES + "Kalshi NO"):
+        // poly_yes_asks(40c) + kalshi_yes_asks(45c) = 0.85 + fees < 1.00 → profitable
+        let poly_yes_book = OrderBook::new(
+            1,
+            None,
+            vec![Level::new(dec!(0.38), dec!(100))],
+            vec![Level::new(dec!(0.40), dec!(100))],
+        );
+        let kalshi_yes_book = OrderBook::new(
+            1,
+            None,
+            vec![Level::new(dec!(0.55), dec!(100))],
+            vec![Level::new(dec!(0.45), dec!(100))],
+        );
+
+        let mut books: HashMap<PredictionMarketKey, &OrderBook> = HashMap::new();
+        books.insert(
+            PredictionMarketKey::polymarket_yes(pair.polymarket_yes_token.clone()),
+            &poly_yes_book,
+        );
+        books.insert(
+            PredictionMarketKey::kalshi_yes(pair.kalshi_ticker.clone()),
+            &kalshi_yes_book,
+        );
+
+        let opps = strategy.detect_opportunities(&books);
+        assert!(!opps.is_empty());
+
+        let opp = &opps[0];
+        assert_eq!(opp.direction, ArbitrageDirection::YesPolyNoKalshi);
+        // The NO side should target Kalshi YES contract (inverse)
+        assert_eq!(opp.no_side.outcome, Outcome::Yes);
+        assert_eq!(opp.no_side.exchange, ExchangeId::Kalshi);
+    }
+
+    #[test]
+    fn test_both_buy_orders_generated() {
+        let strategy = PredictionArbitrageStrategy::new(
+            StrategyId::new("test-arb"),
+            test_config(),
+            vec![test_pair()],
+        );
+
+        let pair = test_pair();
+
+        let poly_yes_book = OrderBook::new(
+            1,
+            None,
+            vec![Level::new(dec!(0.55), dec!(100))],
+            vec![Level::new(dec!(0.40), dec!(100))],
+        );
+        let kalshi_yes_book = OrderBook::new(
+            1,
+            None,
+            vec![Level::new(dec!(0.55), dec!(100))],
+            vec![Level::new(dec!(0.48), dec!(100))],
+        );
+
+        let mut books: HashMap<PredictionMarketKey, &OrderBook> = HashMap::new();
+        books.insert(
+            PredictionMarketKey::polymarket_yes(pair.polymarket_yes_token.clone()),
+            &poly_yes_book,
+        );
+        books.insert(
+            PredictionMarketKey::kalshi_yes(pair.kalshi_ticker.clone()),
+            &kalshi_yes_book,
+        );
+
+        let opps = strategy.detect_opportunities(&books);
+        assert!(!opps.is_empty());
+
+        let opp = &opps[0];
+        // YES side is Polymarket, NO side is Kalshi
+        assert_eq!(opp.yes_side.exchange, ExchangeId::Polymarket);
+        assert_eq!(opp.yes_side.outcome, Outcome::Yes);
+        assert_eq!(opp.no_side.exchange, ExchangeId::Kalshi);
+        assert_eq!(opp.no_side.outcome, Outcome::No);
+    }
+
+    #[test]
+    fn test_min_order_value_filter() {
+        let strategy = PredictionArbitrageStrategy::new(
+            StrategyId::new("test-arb"),
+            test_config(),
+            vec![test_pair()],
+        );
+
+        // YES side on Poly at 40c with 1 contract = $0.40 < $1 minimum
+        let opp = ArbitrageOpportunity {
+            pair: test_pair(),
+            direction: ArbitrageDirection::YesPolyNoKalshi,
+            yes_side: OrderSide::poly("0xyes", Outcome::Yes, dec!(0.40), 1),
+            no_side: OrderSide::kalshi("KXTEST", Outcome::No, dec!(0.45), 1),
+            total_cost: dec!(0.87),
+            avg_yes_price: dec!(0.40),
+            avg_no_price: dec!(0.45),
+            max_contracts: 1,
+            expected_profit: dec!(0.13),
+            total_fees: dec!(0.02),
+        };
+
+        assert!(!strategy.passes_min_order_values(&opp));
+    }
+
+    /// Books from `test_delta_neutral_detection`, with `size` contracts per level.
+    fn evaluation_books(size: Decimal) -> (OrderBook, OrderBook) {
+        let poly_yes_book = OrderBook::new(
+            1,
+            None,
+            vec![Level::new(dec!(0.38), size)],
+            vec![Level::new(dec!(0.40), size)],
+        );
+        let kalshi_yes_book = OrderBook::new(
+            1,
+            None,
+            vec![Level::new(dec!(0.55), size)],
+            vec![Level::new(dec!(0.48), size)],
+        );
+        (poly_yes_book, kalshi_yes_book)
+    }
+
+    fn evaluate(config: ArbitrageConfig, size: Decimal) -> PairEvaluation {
+        let strategy = PredictionArbitrageStrategy::new(
+            StrategyId::new("test-arb"),
+            config,
+            vec![test_pair()],
+        );
+        let (poly_yes_book, kalshi_yes_book) = evaluation_books(size);
+        strategy.evaluate_pair(&test_pair(), &poly_yes_book, &kalshi_yes_book)
+    }
+
+    #[test]
+    fn test_evaluate_pair_breakdown() {
+        let eval = evaluate(test_config(), dec!(100));
+
+        // Direction 1 (Poly YES 0.40 + Kalshi NO 0.45) is actionable
+        let yes_poly = &eval.yes_poly_no_kalshi;
+        assert_eq!(yes_poly.direction, ArbitrageDirection::YesPolyNoKalshi);
+        assert!(yes_poly.is_actionable());
+        assert_eq!(yes_poly.rejection, None);
+        assert_eq!(yes_poly.max_contracts, 100);
+        assert_eq!(yes_poly.avg_yes_price, dec!(0.40));
+        assert_eq!(yes_poly.avg_no_price, dec!(0.45));
+        assert!(yes_poly.total_fees > Decimal::ZERO);
+        assert!(yes_poly.expected_profit > Decimal::ZERO);
+        assert_eq!(yes_poly.top_of_book_cost, Some(yes_poly.total_cost));
+
+        // Single-level fills match fees recomputed from the opportunity
+        let opp = yes_poly.opportunity.as_ref().unwrap();
+        assert_eq!(
+            FeeCalculator::opportunity_fees(opp, 50, KALSHI_TAKER_FEE_BPS),
+            yes_poly.total_fees
+        );
+
+        // Direction 2 (Kalshi YES 0.48 + Poly NO 0.62) costs more than $1
+        let yes_kalshi = &eval.yes_kalshi_no_poly;
+        assert_eq!(yes_kalshi.rejection, Some(RejectionReason::Unprofitable));
+        assert!(yes_kalshi.opportunity.is_none());
+        assert_eq!(yes_kalshi.max_contracts, 0);
+        assert!(yes_kalshi.top_of_book_cost.unwrap() >= Decimal::ONE);
+
+        assert_eq!(
+            eval.best().map(|d| d.direction),
+            Some(ArbitrageDirection::YesPolyNoKalshi)
+        );
+    }
+
+    #[test]
+    fn test_evaluate_pair_below_threshold() {
+        let config = ArbitrageConfig {
+            min_spread_threshold: dec!(0.20),
+            ..test_config()
+        };
+        let eval = evaluate(config, dec!(100));
+
+        // Still found by the depth walk, but rejected by the threshold
+        assert!(eval.yes_poly_no_kalshi.opportunity.is_some());
+        assert_eq!(
+            eval.yes_poly_no_kalshi.rejection,
+            Some(RejectionReason::BelowThreshold)
+        );
+        assert!(eval.best().is_none());
+    }
+
+    #[test]
+    fn test_evaluate_pair_below_min_total_profit() {
+        let config = ArbitrageConfig {
+            min_total_profit: dec!(1),
+            ..test_config()
+        };
+
+        // Well above the per-contract threshold, but only 3 contracts deep
+        let eval = evaluate(config.clone(), dec!(3));
+        let yes_poly = &eval.yes_poly_no_kalshi;
+        let opp = yes_poly.opportunity.as_ref().unwrap();
+        assert!(opp.meets_threshold(config.min_spread_threshold));
+        assert!(opp.expected_profit < dec!(1));
+        assert_eq!(yes_poly.rejection, Some(RejectionReason::BelowMinProfit));
+
+        let eval = evaluate(config, dec!(100));
+        assert_eq!(eval.yes_poly_no_kalshi.rejection, None);
+    }
+
+    #[test]
+    fn test_evaluate_pair_position_limit() {
+        let config = ArbitrageConfig {
+            max_position_per_market: 50,
+            ..test_config()
+        };
+        let eval = evaluate(config, dec!(100));
+
+        assert_eq!(
+            eval.yes_poly_no_kalshi.rejection,
+            Some(RejectionReason::PositionLimit)
+        );
+    }
+
+    #[test]
+    fn test_evaluate_pair_below_min_order_value() {
+        // 1 contract on Poly at 40c is below the $1 minimum
+        let eval = evaluate(test_config(), dec!(1));
+
+        assert!(eval.yes_poly_no_kalshi.opportunity.is_some());
+        assert_eq!(
+            eval.yes_poly_no_kalshi.rejection,
+            Some(RejectionReason::MinOrderValue)
+        );
+        assert_eq!(
+            RejectionReason::MinOrderValue.as_str(),
+            "min_order_value",
+            "recorded rejection names must stay stable"
+        );
+    }
+
+    #[test]
+    fn test_evaluate_pair_thin_book() {
+        // Only 5 Poly YES contracts near the top; the rest sits 5c deeper
+        let poly_yes_book = OrderBook::new(
+            1,
+            None,
+            vec![Level::new(dec!(0.38), dec!(500))],
+            vec![Level::new(dec!(0.40), dec!(5)), Level::new(dec!(0.45), dec!(500))],
+        );
+        let kalshi_yes_book = OrderBook::new(
+            1,
+            None,
+            vec![Level::new(dec!(0.55), dec!(500))],
+            vec![Level::new(dec!(0.48), dec!(500))],
+        );
+        let evaluate = |config: ArbitrageConfig| {
+            PredictionArbitrageStrategy::new(StrategyId::new("test-arb"), config, vec![test_pair()])
+                .evaluate_pair(&test_pair(), &poly_yes_book, &kalshi_yes_book)
+                .yes_poly_no_kalshi
+        };
+
+        // Without a depth requirement the opportunity is actionable
+        assert!(evaluate(test_config()).is_actionable());
+
+        let config = ArbitrageConfig {
+            min_book_depth: Some(dec!(100)),
+            ..test_config()
+        };
+        let thin = evaluate(config.clone());
+        assert!(thin.opportunity.is_some());
+        assert_eq!(thin.rejection, Some(RejectionReason::ThinBook));
+
+        // A wider band reaches the deeper level
+        let wide = evaluate(ArbitrageConfig {
+            book_depth_band: dec!(0.05),
+            ..config
+        });
+        assert_eq!(wide.rejection, None);
+    }
+
+    #[test]
+    fn test_evaluate_pair_no_liquidity() {
+        let strategy = PredictionArbitrageStrategy::new(
+            StrategyId::new("test-arb"),
+            test_config(),
+            vec![test_pair()],
+        );
+        let (poly_yes_book, _) = evaluation_books(dec!(100));
+        // No Kalshi YES bids, so no Kalshi NO asks can be derived
+        let kalshi_yes_book =
+            OrderBook::new(1, None, vec![], vec![Level::new(dec!(0.48), dec!(100))]);
+
+        let eval = strategy.evaluate_pair(&test_pair(), &poly_yes_book, &kalshi_yes_book);
+
+        assert_eq!(
+            eval.yes_poly_no_kalshi.rejection,
+            Some(RejectionReason::NoLiquidity)
+        );
+        assert_eq!(eval.yes_poly_no_kalshi.top_of_book_cost, None);
+        assert_eq!(
+            eval.yes_kalshi_no_poly.rejection,
+            Some(RejectionReason::Unprofitable)
+        );
+    }
+
+    #[test]
+    fn test_real_kalshi_no_book_overrides_derived() {
+        let strategy = PredictionArbitrageStrategy::new(
+            StrategyId::new("test-arb"),
+            test_config(),
+            vec![test_pair()],
+        );
+        let (poly_yes_book, kalshi_yes_book) = evaluation_books(dec!(100));
+
+        // Derived NO ask from the stale YES bid of 55c is 45c, but the real NO
+        // book only offers at 50c
+        let kalshi_no_book = OrderBook::new(
+            2,
+            None,
+            vec![Level::new(dec!(0.48), dec!(100))],
+            vec![Level::new(dec!(0.50), dec!(100))],
+        );
+
+        let derived = strategy.evaluate_pair(&test_pair(), &poly_yes_book, &kalshi_yes_book);
+        let real = strategy.evaluate_pair_with_no_books(
+            &test_pair(),
+            &poly_yes_book,
+            &kalshi_yes_book,
+            Some(&kalshi_no_book),
+            None,
+        );
+
+        assert_eq!(derived.yes_poly_no_kalshi.avg_no_price, dec!(0.45));
+        assert_eq!(real.yes_poly_no_kalshi.avg_no_price, dec!(0.50));
+        assert!(
+            real.yes_poly_no_kalshi.expected_profit < derived.yes_poly_no_kalshi.expected_profit
+        );
+
+        // Detection picks up the NO book from the book map
+        let pair = test_pair();
+        let mut books: HashMap<PredictionMarketKey, &OrderBook> = HashMap::new();
+        books.insert(
+            PredictionMarketKey::polymarket_yes(pair.polymarket_yes_token.clone()),
+            &poly_yes_book,
+        );
+        books.insert(
+            PredictionMarketKey::kalshi_yes(pair.kalshi_ticker.clone()),
+            &kalshi_yes_book,
+        );
+        assert_eq!(
+            strategy.detect_opportunities(&books)[0].avg_no_price,
+            dec!(0.45)
+        );
+
+        books.insert(
+            PredictionMarketKey::kalshi_no(pair.kalshi_ticker.clone()),
+            &kalshi_no_book,
+        );
+        assert_eq!(
+            strategy.detect_opportunities(&books)[0].avg_no_price,
+            dec!(0.50)
+        );
+    }
+
+    #[test]
+    fn test_real_kalshi_no_book_inverse() {
+        let strategy = PredictionArbitrageStrategy::new(
+            StrategyId::new("test-arb"),
+            test_config(),
+            vec![inverse_pair()],
+        );
+        // Inverse: buying semantic Kalshi YES means buying the Kalshi NO contract
+        let poly_yes_book = OrderBook::new(
+            1,
+            None,
+            vec![Level::new(dec!(0.58), dec!(100))],
+            vec![Level::new(dec!(0.60), dec!(100))],
+        );
+        let kalshi_yes_book = OrderBook::new(
+            1,
+            None,
+            vec![Level::new(dec!(0.70), dec!(100))],
+            vec![Level::new(dec!(0.72), dec!(100))],
+        );
+        let kalshi_no_book = OrderBook::new(
+            2,
+            None,
+            vec![Level::new(dec!(0.20), dec!(100))],
+            vec![Level::new(dec!(0.25), dec!(100))],
+        );
+
+        let derived = strategy.evaluate_pair(&inverse_pair(), &poly_yes_book, &kalshi_yes_book);
+        let real = strategy.evaluate_pair_with_no_books(
+            &inverse_pair(),
+            &poly_yes_book,
+            &kalshi_yes_book,
+            Some(&kalshi_no_book),
+            None,
+        );
+
+        // Derived: 1 - 0.70 = 0.30; real: 0.25
+        assert_eq!(derived.yes_kalshi_no_poly.avg_yes_price, dec!(0.30));
+        assert_eq!(real.yes_kalshi_no_poly.avg_yes_price, dec!(0.25));
+        let opp = real.yes_kalshi_no_poly.opportunity.unwrap();
+        assert_eq!(opp.yes_side.outcome, Outcome::No);
+    }
+
+    #[test]
+    fn test_real_poly_no_book_overrides_derived() {
+        let strategy = PredictionArbitrageStrategy::new(
+            StrategyId::new("test-arb"),
+            test_config(),
+            vec![test_pair()],
+        );
+        let (poly_yes_book, kalshi_yes_book) = evaluation_books(dec!(100));
+
+        // Derived NO ask from the 38c YES bid is 62c, but the NO token's own book
+        // offers at 45c
+        let poly_no_book = OrderBook::new(
+            2,
+            None,
+            vec![Level::new(dec!(0.43), dec!(100))],
+            vec![Level::new(dec!(0.45), dec!(100))],
+        );
+
+        let derived = strategy.evaluate_pair(&test_pair(), &poly_yes_book, &kalshi_yes_book);
+        let real = strategy.evaluate_pair_with_no_books(
+            &test_pair(),
+            &poly_yes_book,
+            &kalshi_yes_book,
+            None,
+            Some(&poly_no_book),
+        );
 
-        let cancels: Vec<OrderRequestCancel<ExchangeIndex, InstrumentIndex>> = Vec::new();
+        // Kalshi YES 48c + derived Poly NO 62c is unprofitable, the real 45c is not
+        assert!(derived.yes_kalshi_no_poly.opportunity.is_none());
+        assert_eq!(real.yes_kalshi_no_poly.avg_no_price, dec!(0.45));
+        assert!(real.yes_kalshi_no_poly.opportunity.is_some());
 
-        let opens: Vec<OrderRequestOpen<ExchangeIndex, InstrumentIndex>> = valid_opps
+        // Detection picks up the Polymarket NO book from the book map
+        let pair = test_pair();
+        let mut books: HashMap<PredictionMarketKey, &OrderBook> = HashMap::new();
+        books.insert(
+            PredictionMarketKey::polymarket_yes(pair.polymarket_yes_token.clone()),
+            &poly_yes_book,
+        );
+        books.insert(
+            PredictionMarketKey::kalshi_yes(pair.kalshi_ticker.clone()),
+            &kalshi_yes_book,
+        );
+        assert!(strategy
+            .detect_opportunities(&books)
             .iter()
-            .flat_map(|opp| self.generate_order_pair(opp))
-            .collect();
+            .all(|opp| opp.direction != ArbitrageDirection::YesKalshiNoPoly));
 
-        (cancels, opens)
+        books.insert(
+            PredictionMarketKey::polymarket_no(pair.polymarket_no_token.clone()),
+            &poly_no_book,
+        );
+        let opps = strategy.detect_opportunities(&books);
+        let opp = opps
+            .iter()
+            .find(|opp| opp.direction == ArbitrageDirection::YesKalshiNoPoly)
+            .unwrap();
+        assert_eq!(opp.avg_no_price, dec!(0.45));
+        assert_eq!(
+            opp.no_side.instrument,
+            PredictionMarketKey::polymarket_no(pair.polymarket_no_token.clone())
+        );
     }
-}
-
-impl<Clock, State, ExecutionTxs, Risk> OnTradingDisabled<Clock, State, ExecutionTxs, Risk>
-    for PredictionArbitrageStrategy
-{
-    type OnTradingDisabled = ();
 
-    fn on_trading_disabled(
-        _engine: &mut Engine<Clock, State, ExecutionTxs, Self, Risk>,
-    ) -> Self::OnTradingDisabled {
-    }
-}
+    #[test]
+    fn test_prefer_inferred_inverse() {
+        // Stored as direct, but prices say inverse: Poly YES ~0.71, Kalshi YES ~0.27
+        let poly_yes_book = OrderBook::new(
+            1,
+            None,
+            vec![Level::new(dec!(0.70), dec!(100))],
+            vec![Level::new(dec!(0.72), dec!(100))],
+        );
+        let kalshi_yes_book = OrderBook::new(
+            1,
+            None,
+            vec![Level::new(dec!(0.26), dec!(100))],
+            vec![Level::new(dec!(0.28), dec!(100))],
+        );
+        let books = HashMap::from([
+            (PredictionMarketKey::polymarket_yes("0xyes"), &poly_yes_book),
+            (PredictionMarketKey::kalshi_yes("KXTEST"), &kalshi_yes_book),
+        ]);
 
-impl<Clock, State, ExecutionTxs, Risk> OnDisconnectStrategy<Clock, State, ExecutionTxs, Risk>
-    for PredictionArbitrageStrategy
-{
-    type OnDisconnect = ();
+        // Stored flag: spurious 42c edge from treating opposite outcomes as the same
+        let stored = PredictionArbitrageStrategy::new(
+            StrategyId::new("test-arb"),
+            test_config(),
+            vec![test_pair()],
+        );
+        let opps = stored.check_pair_for_arbitrage(&test_pair(), &books);
+        assert_eq!(opps.len(), 1);
+        assert!(!opps[0].pair.inverse);
 
-    fn on_disconnect(
-        _engine: &mut Engine<Clock, State, ExecutionTxs, Self, Risk>,
-        _exchange: ExchangeId,
-    ) -> Self::OnDisconnect {
+        // Inferred flag: costs are 1.00 and 1.04, no opportunity
+        let inferred = PredictionArbitrageStrategy::new(
+            StrategyId::new("test-arb"),
+            ArbitrageConfig {
+                prefer_inferred_inverse: true,
+                ..test_config()
+            },
+            vec![test_pair()],
+        );
+        assert!(inferred
+            .check_pair_for_arbitrage(&test_pair(), &books)
+            .is_empty());
     }
-}
 
-impl ClosePositionsStrategy for PredictionArbitrageStrategy {
-    type State = ArbitrageEngineState;
+    #[test]
+    fn test_inverse_anomaly_flags_pair_for_revalidation() {
+        // Stored as direct, but prices say inverse, as in test_prefer_inferred_inverse
+        let poly_yes_book = OrderBook::new(
+            1,
+            None,
+            vec![Level::new(dec!(0.70), dec!(100))],
+            vec![Level::new(dec!(0.72), dec!(100))],
+        );
+        let kalshi_yes_book = OrderBook::new(
+            1,
+            None,
+            vec![Level::new(dec!(0.26), dec!(100))],
+            vec![Level::new(dec!(0.28), dec!(100))],
+        );
+        let consistent_book = OrderBook::new(
+            1,
+            None,
+            vec![Level::new(dec!(0.70), dec!(100))],
+            vec![Level::new(dec!(0.72), dec!(100))],
+        );
+        let anomalous = HashMap::from([
+            (PredictionMarketKey::polymarket_yes("0xyes"), &poly_yes_book),
+            (PredictionMarketKey::kalshi_yes("KXTEST"), &kalshi_yes_book),
+        ]);
+        let consistent = HashMap::from([
+            (PredictionMarketKey::polymarket_yes("0xyes"), &poly_yes_book),
+            (PredictionMarketKey::kalshi_yes("KXTEST"), &consistent_book),
+        ]);
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let pair = test_pair().with_db_id(5);
+        let strategy = PredictionArbitrageStrategy::new(
+            StrategyId::new("test-arb"),
+            ArbitrageConfig {
+                mark_invalid_pairs: true,
+                revalidate_after_anomalous_scans: Some(3),
+                ..test_config()
+            },
+            vec![pair.clone()],
+        )
+        .with_invalidation_sink(tx);
+
+        // A consistent scan resets the count
+        strategy.check_pair_for_arbitrage(&pair, &anomalous);
+        strategy.check_pair_for_arbitrage(&pair, &anomalous);
+        strategy.check_pair_for_arbitrage(&pair, &consistent);
+        strategy.check_pair_for_arbitrage(&pair, &anomalous);
+        strategy.check_pair_for_arbitrage(&pair, &anomalous);
+        assert!(rx.try_recv().is_err());
+
+        strategy.check_pair_for_arbitrage(&pair, &anomalous);
+        assert_eq!(
+            rx.try_recv().unwrap(),
+            PairInvalidation {
+                pair_id: 5,
+                kalshi_ticker: SmolStr::new("KXTEST"),
+                reason: "prices implied inverse=true for 3 consecutive scans".to_string(),
+                revalidate: true,
+            }
+        );
 
-    fn close_positions_requests<'a>(
-        &'a self,
-        _state: &'a Self::State,
-        _filter: &'a InstrumentFilter,
-    ) -> (
-        impl IntoIterator<Item = OrderRequestCancel<ExchangeIndex, InstrumentIndex>> + 'a,
-        impl IntoIterator<Item = OrderRequestOpen<ExchangeIndex, InstrumentIndex>> + 'a,
-    )
-    where
-        ExchangeIndex: 'a,
-        AssetIndex: 'a,
-        InstrumentIndex: 'a,
-    {
-        (std::iter::empty(), std::iter::empty())
+        // The flagged pair is suspended until re-validated
+        assert_eq!(
+            strategy.suspension_reason("KXTEST").as_deref(),
+            Some("prices implied inverse=true for 3 consecutive scans")
+        );
+        assert!(strategy.detect_opportunities(&anomalous).is_empty());
+        assert!(rx.try_recv().is_err());
+        assert!(strategy.anomalous_scans.borrow().is_empty());
+
+        // Counts of removed pairs are pruned
+        strategy.check_pair_for_arbitrage(&pair, &anomalous);
+        assert!(strategy.anomalous_scans.borrow().contains_key("KXTEST"));
+        assert_eq!(
+            strategy.apply_pair_update(&PairUpdate::Removed(pair)),
+            PairUpdateOutcome::Removed
+        );
+        assert!(strategy.anomalous_scans.borrow().is_empty());
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use barter_data::books::Level;
-    use chrono::Utc;
-    use rust_decimal_macros::dec;
+    #[test]
+    fn test_group_selects_cheaper_kalshi_no() {
+        // Two bucketed Kalshi markets mapped to the same Polymarket market
+        let leg = |ticker: &str| CorrelatedPair {
+            kalshi_ticker: SmolStr::new(ticker),
+            ..test_pair()
+        };
+        let (leg_a, leg_b) = (leg("KXBTC-T100"), leg("KXBTC-T105"));
+        let strategy = PredictionArbitrageStrategy::new(
+            StrategyId::new("test-arb"),
+            test_config(),
+            vec![leg_a.clone(), leg_b.clone()],
+        );
 
-    fn test_config() -> ArbitrageConfig {
-        ArbitrageConfig {
-            min_spread_threshold: dec!(0.02),
-            max_position_per_market: 1000,
-            max_total_capital: dec!(10000),
-            min_order_value: crate::config::MinOrderValues {
-                kalshi: Decimal::ZERO,
-                polymarket: dec!(1),
-            },
-            max_days_to_expiry: Some(90),
-        }
+        let poly_yes_book = OrderBook::new(
+            1,
+            None,
+            vec![Level::new(dec!(0.38), dec!(100))],
+            vec![Level::new(dec!(0.40), dec!(100))],
+        );
+        // NO asks derived from YES bids: 0.50 for A, 0.45 for B
+        let kalshi_a_book = OrderBook::new(
+            1,
+            None,
+            vec![Level::new(dec!(0.50), dec!(100))],
+            vec![Level::new(dec!(0.56), dec!(100))],
+        );
+        let kalshi_b_book = OrderBook::new(
+            1,
+            None,
+            vec![Level::new(dec!(0.55), dec!(100))],
+            vec![Level::new(dec!(0.60), dec!(100))],
+        );
+        let books = HashMap::from([
+            (PredictionMarketKey::polymarket_yes("0xyes"), &poly_yes_book),
+            (
+                PredictionMarketKey::kalshi_yes("KXBTC-T100"),
+                &kalshi_a_book,
+            ),
+            (
+                PredictionMarketKey::kalshi_yes("KXBTC-T105"),
+                &kalshi_b_book,
+            ),
+        ]);
+
+        // Both legs are profitable on their own
+        assert_eq!(strategy.check_pair_for_arbitrage(&leg_a, &books).len(), 1);
+        assert_eq!(strategy.check_pair_for_arbitrage(&leg_b, &books).len(), 1);
+
+        // Across the group only the cheaper NO side is kept
+        let opps = strategy.detect_opportunities(&books);
+        assert_eq!(opps.len(), 1);
+        assert_eq!(opps[0].direction, ArbitrageDirection::YesPolyNoKalshi);
+        assert_eq!(opps[0].pair.kalshi_ticker, "KXBTC-T105");
+        assert_eq!(opps[0].avg_no_price, dec!(0.45));
+
+        let group = CorrelatedGroup::from_pairs([leg_a, leg_b]).remove(0);
+        let opps = strategy.check_group_for_arbitrage(&group, &books);
+        assert_eq!(opps.len(), 1);
+        assert_eq!(opps[0].pair.kalshi_ticker, "KXBTC-T105");
     }
 
-    fn test_pair() -> CorrelatedPair {
-        CorrelatedPair::new(
-            "KXTEST",
+    #[test]
+    fn test_expired_pair_suspended_without_invalidation() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let expired = CorrelatedPair::new(
+            "KXEXPIRED",
             "0xcondition",
             "0xyes",
             "0xno",
-            "Test market",
-            Utc::now() + chrono::Duration::days(30),
+            "Expired market",
+            Utc::now() - chrono::Duration::hours(1),
             false,
         )
+        .with_db_id(7);
+        let config = ArbitrageConfig {
+            mark_invalid_pairs: true,
+            ..test_config()
+        };
+        let strategy =
+            PredictionArbitrageStrategy::new(StrategyId::new("test-arb"), config, vec![expired])
+                .with_invalidation_sink(tx);
+
+        assert!(strategy.detect_opportunities(&HashMap::new()).is_empty());
+        assert_eq!(strategy.suspension_reason("KXEXPIRED").as_deref(), Some("expired"));
+
+        // Expiry is not a mapping error, so the pair is never marked invalid
+        strategy.detect_opportunities(&HashMap::new());
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_invalidation_requires_config_flag() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let strategy = PredictionArbitrageStrategy::new(
+            StrategyId::new("test-arb"),
+            test_config(),
+            vec![test_pair().with_db_id(1)],
+        )
+        .with_invalidation_sink(tx);
+
+        strategy.suspend_pair(&test_pair().with_db_id(1), "inverse mismatch");
+
+        assert!(strategy.suspension_reason("KXTEST").is_some());
+        assert!(rx.try_recv().is_err());
     }
 
-    fn inverse_pair() -> CorrelatedPair {
+    #[test]
+    fn test_missing_books_suspends_after_timeout() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let pair = test_pair().with_db_id(3);
+        let config = ArbitrageConfig {
+            mark_invalid_pairs: true,
+            missing_book_timeout_secs: 3600,
+            ..test_config()
+        };
+        let strategy =
+            PredictionArbitrageStrategy::new(StrategyId::new("test-arb"), config, vec![pair.clone()])
+                .with_invalidation_sink(tx);
+
+        let start = Utc::now();
+        strategy.track_missing_books(&pair, false, start);
+        strategy.track_missing_books(&pair, false, start + chrono::Duration::minutes(30));
+        assert!(strategy.suspension_reason("KXTEST").is_none());
+
+        // Books reappearing resets the timer
+        strategy.track_missing_books(&pair, true, start + chrono::Duration::minutes(40));
+        strategy.track_missing_books(&pair, false, start + chrono::Duration::minutes(50));
+        strategy.track_missing_books(&pair, false, start + chrono::Duration::minutes(100));
+        assert!(strategy.suspension_reason("KXTEST").is_none());
+
+        strategy.track_missing_books(&pair, false, start + chrono::Duration::minutes(111));
+        assert_eq!(
+            strategy.suspension_reason("KXTEST").as_deref(),
+            Some("orderbooks missing for 3660s")
+        );
+        assert_eq!(rx.try_recv().unwrap().pair_id, 3);
+    }
+
+    fn indexed_for(pairs: &[CorrelatedPair]) -> IndexedInstruments {
+        use barter_instrument::{Underlying, asset::Asset, instrument::Instrument};
+
+        let mut builder = IndexedInstruments::builder();
+        for pair in pairs {
+            for key in pair.instrument_keys() {
+                let (exchange, name) = (key.exchange, key.to_instrument_name());
+                builder = builder.add_instrument(Instrument::spot(
+                    exchange,
+                    format!("{}_{}", exchange.as_str(), name),
+                    name.as_str(),
+                    Underlying::new(Asset::from(name.as_str()), Asset::from("usd")),
+                    None,
+                ));
+            }
+        }
+        builder.build()
+    }
+
+    fn pair_with(ticker: &str, inverse: bool) -> CorrelatedPair {
         CorrelatedPair::new(
-            "KXTEST",
-            "0xcondition",
-            "0xyes",
-            "0xno",
-            "Test market (inverse)",
+            ticker,
+            format!("0xcond_{ticker}"),
+            format!("0xyes_{ticker}"),
+            format!("0xno_{ticker}"),
+            "Test market",
             Utc::now() + chrono::Duration::days(30),
-            true,
+            inverse,
         )
     }
 
+    /// Books for `pair` from [`evaluation_books`], omitting the Polymarket and/or
+    /// Kalshi YES book.
+    fn pair_books<'a>(
+        pair: &CorrelatedPair,
+        (poly_yes, kalshi_yes): &'a (OrderBook, OrderBook),
+        with_poly: bool,
+        with_kalshi: bool,
+    ) -> HashMap<PredictionMarketKey, &'a OrderBook> {
+        let mut books = HashMap::new();
+        if with_poly {
+            books.insert(
+                PredictionMarketKey::polymarket_yes(pair.polymarket_yes_token.clone()),
+                poly_yes,
+            );
+        }
+        if with_kalshi {
+            books.insert(PredictionMarketKey::kalshi_yes(pair.kalshi_ticker.clone()), kalshi_yes);
+        }
+        books
+    }
+
     #[test]
-    fn test_strategy_creation() {
+    fn test_detailed_scan_missing_books() {
+        let pair = pair_with("KXA", false);
         let strategy = PredictionArbitrageStrategy::new(
             StrategyId::new("test-arb"),
             test_config(),
-            vec![test_pair()],
+            vec![pair.clone()],
         );
+        let books = evaluation_books(dec!(100));
 
-        assert_eq!(strategy.pairs.len(), 1);
-        assert_eq!(strategy.poly_fee_bps, 50);
+        let scan = strategy.detect_opportunities_detailed(&pair_books(&pair, &books, false, true));
+        assert!(scan.opportunities.is_empty());
+        assert_eq!(scan.skip_reason("KXA"), Some(&SkipReason::MissingPolymarketBook));
+
+        let scan = strategy.detect_opportunities_detailed(&pair_books(&pair, &books, true, false));
+        assert_eq!(scan.skip_reason("KXA"), Some(&SkipReason::MissingKalshiBook));
     }
 
     #[test]
-    fn test_derive_no_asks() {
-        // YES bids: [0.55x100, 0.52x200, 0.50x150] (high to low)
-        let book = OrderBook::new(
-            1,
-            None,
-            vec![
-                Level::new(dec!(0.55), dec!(100)),
-                Level::new(dec!(0.52), dec!(200)),
-                Level::new(dec!(0.50), dec!(150)),
-            ],
-            vec![Level::new(dec!(0.57), dec!(50))],
+    fn test_detailed_scan_index_not_mapped() {
+        let pair = pair_with("KXA", false);
+        let books = evaluation_books(dec!(100));
+        let books = pair_books(&pair, &books, true, true);
+
+        // Profitable, but the strategy has no instrument indices to order with
+        let unmapped = PredictionArbitrageStrategy::new(
+            StrategyId::new("test-arb"),
+            test_config(),
+            vec![pair.clone()],
+        );
+        let scan = unmapped.detect_opportunities_detailed(&books);
+        assert_eq!(scan.opportunities.len(), 1);
+        assert_eq!(
+            scan.skip_reason("KXA"),
+            Some(&SkipReason::IndexNotMapped(PredictionMarketKey::polymarket_yes(
+                pair.polymarket_yes_token.clone()
+            )))
         );
 
-        let no_asks = derive_no_asks(&book);
-        assert_eq!(no_asks.len(), 3);
-        // Should be ascending: 0.45, 0.48, 0.50
-        assert_eq!(no_asks[0].price, dec!(0.45));
-        assert_eq!(no_asks[0].amount, dec!(100));
-        assert_eq!(no_asks[1].price, dec!(0.48));
-        assert_eq!(no_asks[1].amount, dec!(200));
-        assert_eq!(no_asks[2].price, dec!(0.50));
-        assert_eq!(no_asks[2].amount, dec!(150));
+        // Mapped, so the pair trades and is not skipped
+        let mapped = PredictionArbitrageStrategy::with_instruments(
+            StrategyId::new("test-arb"),
+            test_config(),
+            vec![pair.clone()],
+            &indexed_for(std::slice::from_ref(&pair)),
+        );
+        let scan = mapped.detect_opportunities_detailed(&books);
+        assert_eq!(scan.opportunities.len(), 1);
+        assert!(scan.skipped.is_empty());
     }
 
     #[test]
-    fn test_walk_profitable_levels() {
-        // YES asks: 40c x 100
-        let yes_asks = vec![Level::new(dec!(0.40), dec!(100))];
-        // NO asks: 54c x 100
-        let no_asks = vec![Level::new(dec!(0.54), dec!(100))];
+    fn test_detailed_scan_rejected() {
+        let pair = pair_with("KXA", false);
+        let books = evaluation_books(dec!(100));
+        let books = pair_books(&pair, &books, true, true);
+        let strategy = |config| {
+            PredictionArbitrageStrategy::with_instruments(
+                StrategyId::new("test-arb"),
+                config,
+                vec![pair.clone()],
+                &indexed_for(std::slice::from_ref(&pair)),
+            )
+        };
+
+        // Profitable direction reports the filter it fails
+        let below_threshold = strategy(ArbitrageConfig {
+            min_spread_threshold: dec!(0.50),
+            ..test_config()
+        });
+        assert_eq!(
+            below_threshold.detect_opportunities_detailed(&books).skip_reason("KXA"),
+            Some(&SkipReason::Rejected(RejectionReason::BelowThreshold))
+        );
+
+        // Without YES bids neither direction has NO asks to buy
+        let no_bids = (
+            OrderBook::new(1, None, vec![], vec![Level::new(dec!(0.60), dec!(100))]),
+            OrderBook::new(1, None, vec![], vec![Level::new(dec!(0.60), dec!(100))]),
+        );
+        let books = pair_books(&pair, &no_bids, true, true);
+        assert_eq!(
+            strategy(test_config()).detect_opportunities_detailed(&books).skip_reason("KXA"),
+            Some(&SkipReason::Rejected(RejectionReason::NoLiquidity))
+        );
+    }
+
+    #[test]
+    fn test_detailed_scan_suspended_and_outside_window() {
+        let near = pair_with("KXA", false);
+        let far = CorrelatedPair::new(
+            "KXFAR",
+            "0xcond_far",
+            "0xyes_far",
+            "0xno_far",
+            "Far market",
+            Utc::now() + chrono::Duration::days(365),
+            false,
+        );
+        let strategy = PredictionArbitrageStrategy::new(
+            StrategyId::new("test-arb"),
+            test_config(),
+            vec![near.clone(), far],
+        );
+        strategy.suspend_pair(&near, "manual");
+
+        let scan = strategy.detect_opportunities_detailed(&HashMap::new());
+        assert_eq!(
+            scan.skip_reason("KXA"),
+            Some(&SkipReason::Suspended("manual".to_string()))
+        );
+        assert_eq!(scan.skip_reason("KXFAR"), Some(&SkipReason::OutsideTradingWindow));
+    }
+
+    #[test]
+    fn test_apply_pair_update_added() {
+        let a = pair_with("KXA", false);
+        let b = pair_with("KXB", false);
+        let c = pair_with("KXC", false);
+        // Engine knows A and B's instruments, but the strategy starts with only A
+        let indexed = indexed_for(&[a.clone(), b.clone()]);
+        let strategy = PredictionArbitrageStrategy::with_instruments(
+            StrategyId::new("test-arb"),
+            test_config(),
+            vec![a],
+            &indexed,
+        );
+        assert_eq!(strategy.instrument_index.borrow().len(), 4);
+
+        assert_eq!(
+            strategy.apply_pair_update(&PairUpdate::Added(b.clone())),
+            PairUpdateOutcome::Monitoring
+        );
+        assert_eq!(strategy.pairs().len(), 2);
+        assert_eq!(strategy.instrument_index.borrow().len(), 8);
+
+        // Re-adding is a no-op
+        assert_eq!(
+            strategy.apply_pair_update(&PairUpdate::Added(b)),
+            PairUpdateOutcome::Ignored
+        );
+
+        // C's instruments aren't in the engine, so it needs new subscriptions
+        assert_eq!(
+            strategy.apply_pair_update(&PairUpdate::Added(c)),
+            PairUpdateOutcome::RequiresSubscription
+        );
+        assert_eq!(strategy.pairs().len(), 2);
+    }
+
+    #[test]
+    fn test_apply_pair_update_removed() {
+        let a = pair_with("KXA", false);
+        let b = pair_with("KXB", false);
+        let indexed = indexed_for(&[a.clone(), b.clone()]);
+        let strategy = PredictionArbitrageStrategy::with_instruments(
+            StrategyId::new("test-arb"),
+            test_config(),
+            vec![a.clone(), b],
+            &indexed,
+        );
+
+        assert_eq!(
+            strategy.apply_pair_update(&PairUpdate::Removed(a.clone())),
+            PairUpdateOutcome::Removed
+        );
+        assert_eq!(strategy.pairs().len(), 1);
+        assert_eq!(strategy.pairs()[0].kalshi_ticker, "KXB");
+        assert_eq!(strategy.instrument_index.borrow().len(), 4);
+        assert!(
+            !strategy
+                .instrument_index
+                .borrow()
+                .contains_key(&PredictionMarketKey::kalshi_yes("KXA"))
+        );
+
+        assert_eq!(
+            strategy.apply_pair_update(&PairUpdate::Removed(a)),
+            PairUpdateOutcome::Ignored
+        );
+    }
 
-        // Cost = 0.40 + 0.54 = 0.94 before fees < 1.00
-        let result = walk_orderbook_levels(
-            &yes_asks,
-            &no_asks,
-            ExchangeId::Polymarket,
-            ExchangeId::Kalshi,
-            50,
+    #[test]
+    fn test_apply_pair_update_inverse_changed() {
+        let a = pair_with("KXA", false);
+        let indexed = indexed_for(std::slice::from_ref(&a));
+        let strategy = PredictionArbitrageStrategy::with_instruments(
+            StrategyId::new("test-arb"),
+            test_config(),
+            vec![a.clone()],
+            &indexed,
         );
 
-        assert!(result.total_size > 0);
-        assert!(result.total_profit > Decimal::ZERO);
-        assert!(result.total_cost < Decimal::ONE);
+        let update = PairUpdate::Changed {
+            previous: a,
+            current: pair_with("KXA", true),
+        };
+        assert_eq!(strategy.apply_pair_update(&update), PairUpdateOutcome::Updated);
+        assert!(strategy.pairs()[0].inverse);
     }
 
     #[test]
-    fn test_walk_unprofitable_levels() {
-        // YES asks: 55c x 100
-        let yes_asks = vec![Level::new(dec!(0.55), dec!(100))];
-        // NO asks: 50c x 100
-        let no_asks = vec![Level::new(dec!(0.50), dec!(100))];
+    fn test_apply_pair_update_maps_instruments_for_risk() {
+        let a = pair_with("KXA", false);
+        let b = pair_with("KXB", false);
+        let c = pair_with("KXC", false);
+        let indexed = indexed_for(&[a.clone(), b.clone(), c.clone()]);
+        let pair_instruments = PairInstruments::default();
+        let strategy = PredictionArbitrageStrategy::with_instruments(
+            StrategyId::new("test-arb"),
+            test_config(),
+            vec![a.clone()],
+            &indexed,
+        )
+        .with_pair_instruments(pair_instruments.clone());
+        let instrument = |key: &PredictionMarketKey| {
+            strategy.known_instruments[&(key.exchange, key.to_instrument_name())].1
+        };
 
-        // Cost = 0.55 + 0.50 = 1.05 > 1.00 (not profitable even before fees)
-        let result = walk_orderbook_levels(
-            &yes_asks,
-            &no_asks,
-            ExchangeId::Polymarket,
-            ExchangeId::Kalshi,
-            50,
-        );
+        // Added pairs count against max_open_pairs
+        strategy.apply_pair_update(&PairUpdate::Added(b.clone()));
+        let b_yes = instrument(&PredictionMarketKey::kalshi_yes(b.kalshi_ticker.clone()));
+        assert_eq!(pair_instruments.get(&b_yes).as_deref(), Some("KXB"));
 
-        assert_eq!(result.total_size, 0);
-        assert_eq!(result.total_profit, Decimal::ZERO);
+        // A pair moved to new Polymarket tokens drops the mappings of the old ones
+        let moved = CorrelatedPair {
+            polymarket_yes_token: c.polymarket_yes_token.clone(),
+            polymarket_no_token: c.polymarket_no_token.clone(),
+            ..a.clone()
+        };
+        let update = PairUpdate::Changed {
+            previous: a.clone(),
+            current: moved,
+        };
+        assert_eq!(strategy.apply_pair_update(&update), PairUpdateOutcome::Updated);
+        let old_yes = PredictionMarketKey::polymarket_yes(a.polymarket_yes_token.clone());
+        let new_yes = PredictionMarketKey::polymarket_yes(c.polymarket_yes_token.clone());
+        assert!(!strategy.instrument_index.borrow().contains_key(&old_yes));
+        assert!(strategy.instrument_index.borrow().contains_key(&new_yes));
+        assert_eq!(strategy.instrument_index.borrow().len(), 8);
+        assert_eq!(pair_instruments.get(&instrument(&new_yes)).as_deref(), Some("KXA"));
     }
 
     #[test]
-    fn test_walk_depth_stops_at_unprofitable() {
-        // YES asks: 2 levels - 40c x 50, 48c x 100
-        let yes_asks = vec![
-            Level::new(dec!(0.40), dec!(50)),
-            Level::new(dec!(0.48), dec!(100)),
-        ];
-        // NO asks: 54c x 200
-        let no_asks = vec![Level::new(dec!(0.54), dec!(200))];
+    fn test_pair_updates_applied_from_channel() {
+        let a = pair_with("KXA", false);
+        let b = pair_with("KXB", false);
+        let indexed = indexed_for(&[a.clone(), b.clone()]);
+        let (tx, rx) = mpsc::unbounded_channel();
+        let strategy = PredictionArbitrageStrategy::with_instruments(
+            StrategyId::new("test-arb"),
+            test_config(),
+            vec![a],
+            &indexed,
+        )
+        .with_pair_updates(rx);
 
-        // Level 1: 0.40 + 0.54 = 0.94 + fees < 1.00 → fill 50
-        // Level 2: 0.48 + 0.54 = 1.02 + fees > 1.00 → stop
-        let result = walk_orderbook_levels(
-            &yes_asks,
-            &no_asks,
-            ExchangeId::Polymarket,
-            ExchangeId::Kalshi,
-            50,
-        );
+        tx.send(PairUpdate::Added(b)).unwrap();
+        strategy.drain_pair_updates();
 
-        assert_eq!(result.total_size, 50);
+        assert_eq!(strategy.pairs().len(), 2);
     }
 
     #[test]
-    fn test_delta_neutral_detection() {
-        let strategy = PredictionArbitrageStrategy::new(
+    fn test_unchanged_books_not_rescanned() {
+        use crate::state::{ArbitrageGlobalData, ArbitrageInstrumentData};
+        use barter::engine::state::builder::EngineStateBuilder;
+        use barter_data::subscription::book::OrderBookEvent;
+
+        let a = pair_with("KXA", false);
+        let b = pair_with("KXB", false);
+        let indexed = indexed_for(&[a.clone(), b.clone()]);
+        let strategy = PredictionArbitrageStrategy::with_instruments(
             StrategyId::new("test-arb"),
             test_config(),
-            vec![test_pair()],
+            vec![a.clone(), b.clone()],
+            &indexed,
         );
+        let mut state = EngineStateBuilder::new(&indexed, ArbitrageGlobalData::default(), |_| {
+            ArbitrageInstrumentData::default()
+        })
+        .build();
 
-        let pair = test_pair();
-
-        // Poly YES book: ask 40c, bid 38c
-        // → Poly NO asks derived from bids: 1-0.38 = 62c
-        // Kalshi YES book: ask 48c, bid 55c
-        // → Kalshi NO asks derived from bids: 1-0.55 = 45c
-        //
-        // Direction 1 (Poly YES + Kalshi NO): 0.40 + 0.45 = 0.85 + fees < 1.00
-        // Direction 2 (Kalshi YES + Poly NO): 0.48 + 0.62 = 1.10 > 1.00
-        let poly_yes_book = OrderBook::new(
+        // No direction is profitable, so pairs only get rescanned when books change
+        let poly_yes = OrderBook::new(
             1,
             None,
-            vec![Level::new(dec!(0.38), dec!(100))],
-            vec![Level::new(dec!(0.40), dec!(100))],
+            vec![Level::new(dec!(0.58), dec!(100))],
+            vec![Level::new(dec!(0.60), dec!(100))],
         );
-        let kalshi_yes_book = OrderBook::new(
+        let kalshi_yes = OrderBook::new(
             1,
             None,
-            vec![Level::new(dec!(0.55), dec!(100))],
-            vec![Level::new(dec!(0.48), dec!(100))],
+            vec![Level::new(dec!(0.45), dec!(100))],
+            vec![Level::new(dec!(0.60), dec!(100))],
         );
+        let apply = |state: &mut ArbitrageEngineState, key, event: OrderBookEvent| {
+            let (_, inst_idx) = strategy.instrument_index.borrow()[&key];
+            state
+                .instruments
+                .instrument_index_mut(&inst_idx)
+                .data
+                .update_orderbook(&event);
+        };
+        for pair in [&a, &b] {
+            apply(
+                &mut state,
+                PredictionMarketKey::polymarket_yes(pair.polymarket_yes_token.clone()),
+                OrderBookEvent::Snapshot(poly_yes.clone()),
+            );
+            apply(
+                &mut state,
+                PredictionMarketKey::kalshi_yes(pair.kalshi_ticker.clone()),
+                OrderBookEvent::Snapshot(kalshi_yes.clone()),
+            );
+        }
+        let scan = |state: &ArbitrageEngineState| {
+            strategy.generate_algo_orders(state);
+            strategy.pairs_checked()
+        };
 
-        let mut books: HashMap<PredictionMarketKey, &OrderBook> = HashMap::new();
-        books.insert(
-            PredictionMarketKey::polymarket_yes(pair.polymarket_yes_token.clone()),
-            &poly_yes_book,
+        assert_eq!(scan(&state), 2);
+        assert_eq!(scan(&state), 2);
+
+        // Only the pair whose book changed is re-evaluated
+        apply(
+            &mut state,
+            PredictionMarketKey::kalshi_yes(a.kalshi_ticker.clone()),
+            OrderBookEvent::Update(OrderBook::new(
+                2,
+                None,
+                vec![Level::new(dec!(0.46), dec!(50))],
+                Vec::<Level>::new(),
+            )),
         );
-        books.insert(
-            PredictionMarketKey::kalshi_yes(pair.kalshi_ticker.clone()),
-            &kalshi_yes_book,
+        assert_eq!(scan(&state), 3);
+
+        // Re-sending identical levels does not mark the pair dirty
+        apply(
+            &mut state,
+            PredictionMarketKey::polymarket_yes(b.polymarket_yes_token.clone()),
+            OrderBookEvent::Snapshot(poly_yes.clone()),
         );
+        assert_eq!(scan(&state), 3);
+    }
 
-        let opps = strategy.detect_opportunities(&books);
+    #[test]
+    fn test_terminal_market_status_suspends_pair() {
+        use crate::state::{ArbitrageGlobalData, ArbitrageInstrumentData};
+        use barter::engine::{state::builder::EngineStateBuilder, Processor};
+        use barter_data::event::{DataKind, MarketEvent};
+
+        let a = pair_with("KXA", false);
+        let b = pair_with("KXB", false);
+        let indexed = indexed_for(&[a.clone(), b.clone()]);
+        let strategy = PredictionArbitrageStrategy::with_instruments(
+            StrategyId::new("test-arb"),
+            test_config(),
+            vec![a.clone(), b.clone()],
+            &indexed,
+        );
+        let mut state = EngineStateBuilder::new(&indexed, ArbitrageGlobalData::default(), |_| {
+            ArbitrageInstrumentData::default()
+        })
+        .build();
+        let mut apply = |ticker: &SmolStr, status| {
+            let key = PredictionMarketKey::kalshi_yes(ticker.clone());
+            let (_, inst_idx) = strategy.instrument_index.borrow()[&key];
+            state.instruments.instrument_index_mut(&inst_idx).data.process(&MarketEvent {
+                time_exchange: Utc::now(),
+                time_received: Utc::now(),
+                exchange: ExchangeId::Kalshi,
+                instrument: inst_idx,
+                kind: DataKind::MarketStatus(status),
+            });
+        };
 
-        assert!(!opps.is_empty());
-        let opp = &opps[0];
-        assert_eq!(opp.direction, ArbitrageDirection::YesPolyNoKalshi);
-        assert!(opp.is_profitable());
-        assert!(opp.total_cost < Decimal::ONE);
+        // A closed market may reopen, so the pair keeps trading
+        apply(&a.kalshi_ticker, MarketStatus::Closed);
+        apply(&b.kalshi_ticker, MarketStatus::Settled);
+        strategy.generate_algo_orders(&state);
+
+        assert_eq!(strategy.suspension_reason("KXA"), None);
+        assert_eq!(strategy.suspension_reason("KXB").as_deref(), Some("market settled"));
     }
 
     #[test]
-    fn test_inverse_flag_swaps_kalshi() {
-        let strategy = PredictionArbitrageStrategy::new(
+    fn test_stale_books_skipped_until_refreshed() {
+        use crate::state::{ArbitrageGlobalData, ArbitrageInstrumentData};
+        use barter::engine::{state::builder::EngineStateBuilder, Processor};
+        use barter_data::{
+            event::{DataKind, MarketEvent},
+            subscription::book::OrderBookEvent,
+        };
+
+        let pair = pair_with("KXA", false);
+        let indexed = indexed_for(std::slice::from_ref(&pair));
+        let strategy = PredictionArbitrageStrategy::with_instruments(
             StrategyId::new("test-arb"),
             test_config(),
-            vec![inverse_pair()],
+            vec![pair.clone()],
+            &indexed,
         );
+        let mut state = EngineStateBuilder::new(&indexed, ArbitrageGlobalData::default(), |_| {
+            ArbitrageInstrumentData::default()
+        })
+        .build();
+        let apply = |state: &mut ArbitrageEngineState, key: PredictionMarketKey, kind| {
+            let (_, inst_idx) = strategy.instrument_index.borrow()[&key];
+            state.instruments.instrument_index_mut(&inst_idx).data.process(&MarketEvent {
+                time_exchange: Utc::now(),
+                time_received: Utc::now(),
+                exchange: ExchangeId::Kalshi,
+                instrument: inst_idx,
+                kind,
+            });
+        };
+        let poly_yes = PredictionMarketKey::polymarket_yes(pair.polymarket_yes_token.clone());
+        let kalshi_yes = PredictionMarketKey::kalshi_yes(pair.kalshi_ticker.clone());
+        let book = |bid| {
+            DataKind::OrderBook(OrderBookEvent::Snapshot(OrderBook::new(
+                1,
+                None,
+                vec![Level::new(bid, dec!(100))],
+                vec![Level::new(dec!(0.60), dec!(100))],
+            )))
+        };
+        apply(&mut state, poly_yes.clone(), book(dec!(0.58)));
+        apply(&mut state, kalshi_yes.clone(), book(dec!(0.45)));
+
+        // A paused market keeps its levels, but the pair is not evaluated against them
+        apply(&mut state, kalshi_yes.clone(), DataKind::MarketStatus(MarketStatus::Closed));
+        strategy.generate_algo_orders(&state);
+        assert_eq!(strategy.pairs_checked(), 0);
+        let scan = strategy.scan_opportunities(&strategy.build_book_map(&state), |_| true);
+        assert_eq!(scan.skipped[0].reason, SkipReason::StaleBook);
+
+        // Reopening refreshes the book, even though its levels did not change
+        apply(&mut state, kalshi_yes, DataKind::MarketStatus(MarketStatus::Open));
+        strategy.generate_algo_orders(&state);
+        assert_eq!(strategy.pairs_checked(), 1);
+    }
 
-        let pair = inverse_pair();
+    #[test]
+    fn test_edge_persistence_filters_spikes() {
+        use crate::state::{ArbitrageGlobalData, ArbitrageInstrumentData};
+        use barter::engine::state::builder::EngineStateBuilder;
+        use barter_data::subscription::book::OrderBookEvent;
+
+        let pair = pair_with("KXA", false);
+        let indexed = indexed_for(std::slice::from_ref(&pair));
+        let mut state = EngineStateBuilder::new(&indexed, ArbitrageGlobalData::default(), |_| {
+            ArbitrageInstrumentData::default()
+        })
+        .build();
+        let config = ArbitrageConfig {
+            min_edge_persistence: Some(std::time::Duration::from_secs(10)),
+            ..test_config()
+        };
+        let strategy = PredictionArbitrageStrategy::with_instruments(
+            StrategyId::new("test-arb"),
+            config,
+            vec![pair.clone()],
+            &indexed,
+        );
 
-        // Kalshi YES book (= semantic NO in inverse): ask 45c, bid 55c
-        // Poly YES book: ask 40c, bid 38c
-        //
-        // With inverse, Direction 1 (Poly YES + "Kalshi NO"):
-        // poly_yes_asks(40c) + kalshi_yes_asks(45c) = 0.85 + fees < 1.00 → profitable
-        let poly_yes_book = OrderBook::new(
+        let (crossed_poly, crossed_kalshi) = evaluation_books(dec!(100));
+        let flat_poly = OrderBook::new(
             1,
             None,
-            vec![Level::new(dec!(0.38), dec!(100))],
-            vec![Level::new(dec!(0.40), dec!(100))],
+            vec![Level::new(dec!(0.58), dec!(100))],
+            vec![Level::new(dec!(0.60), dec!(100))],
         );
-        let kalshi_yes_book = OrderBook::new(
+        let flat_kalshi = OrderBook::new(
             1,
             None,
-            vec![Level::new(dec!(0.55), dec!(100))],
             vec![Level::new(dec!(0.45), dec!(100))],
+            vec![Level::new(dec!(0.60), dec!(100))],
         );
+        let set_books = |state: &mut ArbitrageEngineState, poly: &OrderBook, kalshi: &OrderBook| {
+            for (key, book) in [
+                (PredictionMarketKey::polymarket_yes(pair.polymarket_yes_token.clone()), poly),
+                (PredictionMarketKey::kalshi_yes(pair.kalshi_ticker.clone()), kalshi),
+            ] {
+                let (_, instrument) = strategy.instrument_index.borrow()[&key];
+                state
+                    .instruments
+                    .instrument_index_mut(&instrument)
+                    .data
+                    .update_orderbook(&OrderBookEvent::Snapshot(book.clone()));
+            }
+        };
+        let scan = |state: &ArbitrageEngineState| {
+            let (_, opens) = strategy.generate_algo_orders(state);
+            opens.into_iter().count()
+        };
 
-        let mut books: HashMap<PredictionMarketKey, &OrderBook> = HashMap::new();
-        books.insert(
-            PredictionMarketKey::polymarket_yes(pair.polymarket_yes_token.clone()),
-            &poly_yes_book,
-        );
-        books.insert(
-            PredictionMarketKey::kalshi_yes(pair.kalshi_ticker.clone()),
-            &kalshi_yes_book,
-        );
+        // Pretend the last observation was made `secs` ago
+        let backdate = |secs: i64| {
+            let mut emas = strategy.edge_ema.borrow_mut();
+            emas.get_mut("KXA").unwrap().updated -= TimeDelta::seconds(secs);
+        };
 
-        let opps = strategy.detect_opportunities(&books);
-        assert!(!opps.is_empty());
+        set_books(&mut state, &flat_poly, &flat_kalshi);
+        assert_eq!(scan(&state), 0);
+        let flat_edge = strategy.edge_ema("KXA").unwrap();
+        assert!(flat_edge < Decimal::ZERO);
 
-        let opp = &opps[0];
-        assert_eq!(opp.direction, ArbitrageDirection::YesPolyNoKalshi);
-        // The NO side should target Kalshi YES contract (inverse)
-        assert_eq!(opp.no_side.outcome, Outcome::Yes);
-        assert_eq!(opp.no_side.exchange, ExchangeId::Kalshi);
+        // A crossed book that flickers for a second, however often it's scanned,
+        // barely moves the EMA
+        set_books(&mut state, &crossed_poly, &crossed_kalshi);
+        for _ in 0..20 {
+            assert_eq!(scan(&state), 0);
+        }
+        backdate(1);
+        set_books(&mut state, &flat_poly, &flat_kalshi);
+        assert_eq!(scan(&state), 0);
+        let ema = strategy.edge_ema("KXA").unwrap();
+        assert!(ema > flat_edge && ema < Decimal::ZERO, "{ema}");
+
+        // A crossed book that persists for several time constants fires
+        set_books(&mut state, &crossed_poly, &crossed_kalshi);
+        assert_eq!(scan(&state), 0);
+        backdate(60);
+        assert!(scan(&state) > 0);
+        assert!(strategy.edge_ema("KXA").unwrap() >= dec!(0.02));
     }
 
     #[test]
-    fn test_both_buy_orders_generated() {
-        let strategy = PredictionArbitrageStrategy::new(
+    fn test_edge_ema_independent_of_scan_frequency() {
+        let config = ArbitrageConfig {
+            min_edge_persistence: Some(std::time::Duration::from_secs(10)),
+            ..test_config()
+        };
+        let strategy =
+            PredictionArbitrageStrategy::new(StrategyId::new("test-arb"), config, vec![]);
+        let (slow, fast) = (SmolStr::new("KXSLOW"), SmolStr::new("KXFAST"));
+        let start = Utc::now();
+
+        // Both pairs see an edge of 0.05 for 10s, scanned once or every 100ms
+        for ticker in [&slow, &fast] {
+            strategy.fold_edge(ticker, Decimal::ZERO, start);
+            strategy.fold_edge(ticker, dec!(0.05), start);
+        }
+        strategy.fold_edge(&slow, dec!(0.05), start + TimeDelta::seconds(10));
+        for step in 1..=100 {
+            strategy.fold_edge(&fast, dec!(0.05), start + TimeDelta::milliseconds(step * 100));
+        }
+
+        // 0.05 * (1 - e^-1)
+        for ticker in ["KXSLOW", "KXFAST"] {
+            let ema = strategy.edge_ema(ticker).unwrap();
+            assert!((ema - dec!(0.031606)).abs() < dec!(0.000001), "{ticker}: {ema}");
+        }
+    }
+
+    #[test]
+    fn test_dry_run_records_without_sending_orders() {
+        use crate::state::{ArbitrageGlobalData, ArbitrageInstrumentData};
+        use barter::engine::state::builder::EngineStateBuilder;
+
+        let pair = pair_with("KXA", false);
+        let indexed = indexed_for(std::slice::from_ref(&pair));
+        let mut state = EngineStateBuilder::new(&indexed, ArbitrageGlobalData::default(), |_| {
+            ArbitrageInstrumentData::default()
+        })
+        .build();
+        let scan = |dry_run: bool, state: &ArbitrageEngineState| {
+            let (tx, rx) = mpsc::unbounded_channel();
+            let config = ArbitrageConfig {
+                dry_run,
+                record_opportunities: true,
+                ..test_config()
+            };
+            let strategy = PredictionArbitrageStrategy::with_instruments(
+                StrategyId::new("test-arb"),
+                config,
+                vec![pair.clone()],
+                &indexed,
+            )
+            .with_opportunity_sink(tx);
+            let (_, opens) = strategy.generate_algo_orders(state);
+            (opens.into_iter().count(), rx)
+        };
+
+        let (poly_yes, kalshi_yes) = evaluation_books(dec!(100));
+        for (key, book) in [
+            (
+                PredictionMarketKey::polymarket_yes(pair.polymarket_yes_token.clone()),
+                poly_yes,
+            ),
+            (PredictionMarketKey::kalshi_yes(pair.kalshi_ticker.clone()), kalshi_yes),
+        ] {
+            let name = key.to_instrument_name();
+            let index = indexed
+                .instruments()
+                .iter()
+                .find(|i| {
+                    i.value.exchange.value == key.exchange
+                        && i.value.name_exchange.name() == &name
+                })
+                .map(|i| i.key)
+                .unwrap();
+            state.instruments.instrument_index_mut(&index).data.orderbook = Some(book);
+        }
+
+        let (live_opens, _) = scan(false, &state);
+        assert_eq!(live_opens, 2);
+
+        let (dry_run_opens, mut rx) = scan(true, &state);
+        assert_eq!(dry_run_opens, 0);
+        let (opp, rejection) = rx.try_recv().unwrap();
+        assert_eq!(opp.pair.kalshi_ticker, "KXA");
+        assert_eq!(rejection, None);
+    }
+
+    #[test]
+    fn test_opportunity_events_published_once() {
+        use crate::state::{ArbitrageGlobalData, ArbitrageInstrumentData};
+        use barter::engine::state::builder::EngineStateBuilder;
+
+        let pair = pair_with("KXA", false);
+        let indexed = indexed_for(std::slice::from_ref(&pair));
+        let mut state = EngineStateBuilder::new(&indexed, ArbitrageGlobalData::default(), |_| {
+            ArbitrageInstrumentData::default()
+        })
+        .build();
+
+        let (poly_yes, kalshi_yes) = evaluation_books(dec!(100));
+        for (key, book) in [
+            (
+                PredictionMarketKey::polymarket_yes(pair.polymarket_yes_token.clone()),
+                poly_yes,
+            ),
+            (PredictionMarketKey::kalshi_yes(pair.kalshi_ticker.clone()), kalshi_yes),
+        ] {
+            let name = key.to_instrument_name();
+            let index = indexed
+                .instruments()
+                .iter()
+                .find(|i| {
+                    i.value.exchange.value == key.exchange
+                        && i.value.name_exchange.name() == &name
+                })
+                .map(|i| i.key)
+                .unwrap();
+            state.instruments.instrument_index_mut(&index).data.orderbook = Some(book);
+        }
+
+        let (tx, mut rx) = broadcast::channel(16);
+        let strategy = PredictionArbitrageStrategy::with_instruments(
             StrategyId::new("test-arb"),
             test_config(),
-            vec![test_pair()],
-        );
+            vec![pair.clone()],
+            &indexed,
+        )
+        .with_opportunity_events(tx);
 
-        let pair = test_pair();
+        let (_, opens) = strategy.generate_algo_orders(&state);
+        assert_eq!(opens.into_iter().count(), 2);
 
-        let poly_yes_book = OrderBook::new(
-            1,
-            None,
-            vec![Level::new(dec!(0.55), dec!(100))],
-            vec![Level::new(dec!(0.40), dec!(100))],
+        let opp = rx.try_recv().unwrap();
+        assert_eq!(opp.pair.kalshi_ticker, "KXA");
+        assert!(opp.is_profitable());
+        assert!(serde_json::to_value(&opp).is_ok());
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_capital_funds_highest_edge_pair_first() {
+        use crate::state::{ArbitrageGlobalData, ArbitrageInstrumentData};
+        use barter::engine::state::builder::EngineStateBuilder;
+
+        // KXA is shallower than KXB, so the same per-contract edge earns it less
+        let low = pair_with("KXA", false);
+        let high = pair_with("KXB", false);
+        let indexed = indexed_for(&[low.clone(), high.clone()]);
+        let mut state = EngineStateBuilder::new(&indexed, ArbitrageGlobalData::default(), |_| {
+            ArbitrageInstrumentData::default()
+        })
+        .build();
+
+        for (pair, size) in [(&low, dec!(50)), (&high, dec!(100))] {
+            let (poly_yes, kalshi_yes) = evaluation_books(size);
+            for (key, book) in [
+                (
+                    PredictionMarketKey::polymarket_yes(pair.polymarket_yes_token.clone()),
+                    poly_yes,
+                ),
+                (PredictionMarketKey::kalshi_yes(pair.kalshi_ticker.clone()), kalshi_yes),
+            ] {
+                let name = key.to_instrument_name();
+                let index = indexed
+                    .instruments()
+                    .iter()
+                    .find(|i| {
+                        i.value.exchange.value == key.exchange
+                            && i.value.name_exchange.name() == &name
+                    })
+                    .map(|i| i.key)
+                    .unwrap();
+                state.instruments.instrument_index_mut(&index).data.orderbook = Some(book);
+            }
+        }
+
+        // Capital for the 100 contract pair (~$85), but not for both
+        let strategy = PredictionArbitrageStrategy::with_instruments(
+            StrategyId::new("test-arb"),
+            ArbitrageConfig {
+                max_total_capital: dec!(100),
+                ..test_config()
+            },
+            vec![low.clone(), high.clone()],
+            &indexed,
         );
-        let kalshi_yes_book = OrderBook::new(
-            1,
-            None,
-            vec![Level::new(dec!(0.55), dec!(100))],
-            vec![Level::new(dec!(0.48), dec!(100))],
+
+        let (_, opens) = strategy.generate_algo_orders(&state);
+        let opens: Vec<_> = opens.into_iter().collect();
+        assert_eq!(opens.len(), 2);
+
+        let index = strategy.instrument_index.borrow();
+        let (_, high_yes) = index[&PredictionMarketKey::polymarket_yes(
+            high.polymarket_yes_token.clone(),
+        )];
+        assert!(opens.iter().all(|open| open.state.quantity == dec!(100)));
+        assert!(opens.iter().any(|open| open.key.instrument == high_yes));
+
+        // Free exchange balances cap capital the same way once known
+        state.global.kalshi_balance = dec!(50);
+        state.global.polymarket_balance = dec!(50);
+        for exchange in [ExchangeId::Kalshi, ExchangeId::Polymarket] {
+            state.global.last_balance_update.insert(exchange, Utc::now());
+        }
+        let strategy = PredictionArbitrageStrategy::with_instruments(
+            StrategyId::new("test-arb"),
+            test_config(),
+            vec![low.clone(), high.clone()],
+            &indexed,
         );
+        let (_, opens) = strategy.generate_algo_orders(&state);
+        let opens: Vec<_> = opens.into_iter().collect();
+        assert_eq!(opens.len(), 2);
+        assert!(opens.iter().any(|open| open.key.instrument == high_yes));
+    }
 
+    #[test]
+    fn test_polymarket_legs_fill_or_kill_by_default() {
+        let pair = pair_with("KXA", false);
+        let indexed = indexed_for(std::slice::from_ref(&pair));
+        let (poly_yes_book, kalshi_yes_book) = evaluation_books(dec!(100));
         let mut books: HashMap<PredictionMarketKey, &OrderBook> = HashMap::new();
         books.insert(
             PredictionMarketKey::polymarket_yes(pair.polymarket_yes_token.clone()),
@@ -950,39 +3703,264 @@ mod tests {
             &kalshi_yes_book,
         );
 
-        let opps = strategy.detect_opportunities(&books);
-        assert!(!opps.is_empty());
+        let legs = |polymarket_fill_and_kill| {
+            let strategy = PredictionArbitrageStrategy::with_instruments(
+                StrategyId::new("test-arb"),
+                ArbitrageConfig {
+                    polymarket_fill_and_kill,
+                    ..test_config()
+                },
+                vec![pair.clone()],
+                &indexed,
+            );
+            let opp = strategy.detect_opportunities(&books).remove(0);
+            let exchanges = [opp.yes_side.exchange, opp.no_side.exchange];
+            exchanges
+                .into_iter()
+                .zip(strategy.generate_order_pair(&opp))
+                .map(|(exchange, open)| (exchange, open.state.time_in_force))
+                .collect::<Vec<_>>()
+        };
 
-        let opp = &opps[0];
-        // YES side is Polymarket, NO side is Kalshi
-        assert_eq!(opp.yes_side.exchange, ExchangeId::Polymarket);
-        assert_eq!(opp.yes_side.outcome, Outcome::Yes);
-        assert_eq!(opp.no_side.exchange, ExchangeId::Kalshi);
-        assert_eq!(opp.no_side.outcome, Outcome::No);
+        // Only the Polymarket leg is all-or-nothing by default
+        for (exchange, time_in_force) in legs(false) {
+            let expected = match exchange {
+                ExchangeId::Polymarket => TimeInForce::FillOrKill,
+                _ => TimeInForce::ImmediateOrCancel,
+            };
+            assert_eq!(time_in_force, expected);
+        }
+
+        // Opting into fill-and-kill makes both legs immediate-or-cancel
+        let fill_and_kill = legs(true);
+        assert!(
+            fill_and_kill
+                .iter()
+                .all(|(_, tif)| *tif == TimeInForce::ImmediateOrCancel)
+        );
     }
 
     #[test]
-    fn test_min_order_value_filter() {
+    fn test_leg_ids_share_group() {
         let strategy = PredictionArbitrageStrategy::new(
             StrategyId::new("test-arb"),
             test_config(),
-            vec![test_pair()],
+            vec![pair_with("KXA", false)],
         );
 
-        // YES side on Poly at 40c with 1 contract = $0.40 < $1 minimum
-        let opp = ArbitrageOpportunity {
-            pair: test_pair(),
-            direction: ArbitrageDirection::YesPolyNoKalshi,
-            yes_side: OrderSide::poly("0xyes", Outcome::Yes, dec!(0.40), 1),
-            no_side: OrderSide::kalshi("KXTEST", Outcome::No, dec!(0.45), 1),
-            total_cost: dec!(0.87),
-            avg_yes_price: dec!(0.40),
-            avg_no_price: dec!(0.45),
-            max_contracts: 1,
-            expected_profit: dec!(0.13),
-            total_fees: dec!(0.02),
+        let (yes, no) = strategy.next_leg_ids();
+        assert_eq!(ArbLeg::parse_cid(&yes), Some(("test-arb_1", ArbLeg::Yes)));
+        assert_eq!(ArbLeg::parse_cid(&no), Some(("test-arb_1", ArbLeg::No)));
+
+        let (next_yes, _) = strategy.next_leg_ids();
+        assert_eq!(ArbLeg::parse_cid(&next_yes), Some(("test-arb_2", ArbLeg::Yes)));
+    }
+
+    /// A pair whose Polymarket YES leg filled while the Kalshi NO leg rests unfilled.
+    fn one_legged_fill() -> (
+        PredictionArbitrageStrategy,
+        ArbitrageEngineState,
+        InstrumentIndex,
+        InstrumentIndex,
+        ClientOrderId,
+        DateTime<Utc>,
+    ) {
+        use crate::state::{ArbitrageGlobalData, ArbitrageInstrumentData};
+        use barter::engine::state::builder::EngineStateBuilder;
+        use barter_execution::{
+            order::{
+                OrderKind, TimeInForce,
+                id::OrderId,
+                state::{ActiveOrderState, Open},
+            },
+            trade::{AssetFees, Trade, TradeId},
+        };
+        use barter_instrument::asset::QuoteAsset;
+
+        let pair = pair_with("KXA", false);
+        let indexed = indexed_for(std::slice::from_ref(&pair));
+        let mut state = EngineStateBuilder::new(&indexed, ArbitrageGlobalData::default(), |_| {
+            ArbitrageInstrumentData::default()
+        })
+        .build();
+        let strategy = PredictionArbitrageStrategy::with_instruments(
+            StrategyId::new("test-arb"),
+            test_config(),
+            vec![pair.clone()],
+            &indexed,
+        );
+        let indices = |key: &PredictionMarketKey| {
+            *strategy.instrument_index.borrow().get(key).unwrap()
         };
+        let (yes_exchange, yes_instrument) =
+            indices(&PredictionMarketKey::polymarket_yes(pair.polymarket_yes_token.clone()));
+        let (no_exchange, no_instrument) =
+            indices(&PredictionMarketKey::kalshi_no(pair.kalshi_ticker.clone()));
 
-        assert!(!strategy.passes_min_order_values(&opp));
+        let (yes_cid, no_cid) = strategy.next_leg_ids();
+        let leg = |exchange, instrument, cid: &ClientOrderId, price| OrderRequestOpen {
+            key: OrderKey {
+                exchange,
+                instrument,
+                strategy: strategy.id.clone(),
+                cid: cid.clone(),
+            },
+            state: RequestOpen {
+                side: Side::Buy,
+                price,
+                quantity: dec!(10),
+                kind: OrderKind::Limit,
+                time_in_force: TimeInForce::GoodUntilCancelled { post_only: false },
+            },
+        };
+        let yes = leg(yes_exchange, yes_instrument, &yes_cid, dec!(0.40));
+        let no = leg(no_exchange, no_instrument, &no_cid, dec!(0.45));
+        let sent = Utc::now();
+        strategy.track_leg_groups(&[yes, no.clone()], sent);
+
+        // YES leg fills, NO leg rests unfilled
+        let positions = &mut state.global.arb_positions;
+        positions.link_order(&OrderId::new("poly-1"), &yes_cid);
+        positions.apply_trade(&Trade {
+            id: TradeId::new("poly-1-fill"),
+            order_id: OrderId::new("poly-1"),
+            instrument: yes_instrument,
+            strategy: strategy.id.clone(),
+            time_exchange: sent,
+            side: Side::Buy,
+            price: dec!(0.40),
+            quantity: dec!(10),
+            fees: AssetFees {
+                asset: QuoteAsset,
+                fees: Decimal::ZERO,
+            },
+        });
+        state.instruments.instrument_index_mut(&no_instrument).orders.0.insert(
+            no_cid.clone(),
+            Order {
+                key: no.key.clone(),
+                side: no.state.side,
+                price: no.state.price,
+                quantity: no.state.quantity,
+                kind: no.state.kind,
+                time_in_force: no.state.time_in_force,
+                state: ActiveOrderState::Open(Open {
+                    id: OrderId::new("kalshi-1"),
+                    time_exchange: sent,
+                    filled_quantity: Decimal::ZERO,
+                }),
+            },
+        );
+        let (poly_yes_book, _) = evaluation_books(dec!(100));
+        state.instruments.instrument_index_mut(&yes_instrument).data.orderbook =
+            Some(poly_yes_book);
+
+        (strategy, state, yes_instrument, no_instrument, no_cid, sent)
+    }
+
+    #[test]
+    fn test_unconfirmed_leg_flattened_after_timeout() {
+        use barter_execution::{
+            order::{
+                id::OrderId,
+                state::{ActiveOrderState, CancelInFlight},
+            },
+            trade::{AssetFees, Trade, TradeId},
+        };
+        use barter_instrument::asset::QuoteAsset;
+
+        let (strategy, mut state, yes_instrument, no_instrument, no_cid, sent) = one_legged_fill();
+
+        // Within the window the NO leg may still fill
+        let (cancels, opens) = strategy.leg_risk_orders(&state, sent + TimeDelta::seconds(1));
+        assert!(cancels.is_empty());
+        assert!(opens.is_empty());
+
+        let (cancels, opens) = strategy.leg_risk_orders(&state, sent + TimeDelta::seconds(6));
+        assert_eq!(cancels.len(), 1);
+        assert_eq!(cancels[0].key.cid, no_cid);
+        assert_eq!(cancels[0].state.id, Some(OrderId::new("kalshi-1")));
+        assert_eq!(opens.len(), 1);
+        let flatten = &opens[0];
+        assert_eq!(flatten.key.instrument, yes_instrument);
+        assert_eq!(flatten.key.cid, ClientOrderId::new("test-arb_1_flatten1_yes"));
+        assert_eq!(flatten.state.side, Side::Sell);
+        assert_eq!(flatten.state.quantity, dec!(10));
+        // Sold into the best bid
+        assert_eq!(flatten.state.price, dec!(0.38));
+
+        // The NO cancel is in flight and the flatten is not retried straight away
+        state
+            .instruments
+            .instrument_index_mut(&no_instrument)
+            .orders
+            .0
+            .get_mut(&no_cid)
+            .unwrap()
+            .state = ActiveOrderState::CancelInFlight(CancelInFlight::default());
+        let (cancels, opens) = strategy.leg_risk_orders(&state, sent + TimeDelta::seconds(7));
+        assert!(cancels.is_empty() && opens.is_empty());
+
+        // The IOC expired unfilled, so it is retried under a new cid
+        let (cancels, opens) = strategy.leg_risk_orders(&state, sent + TimeDelta::seconds(12));
+        assert!(cancels.is_empty());
+        assert_eq!(opens.len(), 1);
+        assert_eq!(opens[0].key.cid, ClientOrderId::new("test-arb_1_flatten2_yes"));
+        assert_eq!(opens[0].state.quantity, dec!(10));
+
+        // A partial flattening fill leaves the rest to be flattened
+        let flatten_fill = |state: &mut ArbitrageEngineState, cid: &ClientOrderId, quantity| {
+            let id = OrderId::new(format!("{}-id", cid.0));
+            let positions = &mut state.global.arb_positions;
+            positions.link_order(&id, cid);
+            positions.apply_trade(&Trade {
+                id: TradeId::new(format!("{}-fill", cid.0)),
+                order_id: id,
+                instrument: yes_instrument,
+                strategy: strategy.id.clone(),
+                time_exchange: sent,
+                side: Side::Sell,
+                price: dec!(0.38),
+                quantity,
+                fees: AssetFees {
+                    asset: QuoteAsset,
+                    fees: Decimal::ZERO,
+                },
+            });
+        };
+        flatten_fill(&mut state, &opens[0].key.cid, dec!(4));
+        let (_, opens) = strategy.leg_risk_orders(&state, sent + TimeDelta::seconds(18));
+        assert_eq!(opens.len(), 1);
+        assert_eq!(opens[0].key.cid, ClientOrderId::new("test-arb_1_flatten3_yes"));
+        assert_eq!(opens[0].state.quantity, dec!(6));
+
+        // Once flat with no working legs the group is dropped
+        flatten_fill(&mut state, &opens[0].key.cid, dec!(6));
+        state
+            .instruments
+            .instrument_index_mut(&no_instrument)
+            .orders
+            .0
+            .remove(&no_cid);
+        let (cancels, opens) = strategy.leg_risk_orders(&state, sent + TimeDelta::seconds(24));
+        assert!(cancels.is_empty() && opens.is_empty());
+        assert!(strategy.leg_groups.borrow().is_empty());
+    }
+
+    #[test]
+    fn test_trading_disabled_flattens_without_waiting() {
+        let (strategy, state, yes_instrument, _, no_cid, sent) = one_legged_fill();
+
+        // Well within the window, but trading will not resume to flatten later
+        let (cancels, opens) =
+            strategy.leg_risk_orders_on_disable(&state, sent + TimeDelta::seconds(1));
+        assert_eq!(cancels.len(), 1);
+        assert_eq!(cancels[0].key.cid, no_cid);
+        assert_eq!(opens.len(), 1);
+        assert_eq!(opens[0].key.instrument, yes_instrument);
+        assert_eq!(opens[0].key.cid, ClientOrderId::new("test-arb_1_flatten1_yes"));
+        assert_eq!(opens[0].state.side, Side::Sell);
+        assert_eq!(opens[0].state.quantity, dec!(10));
+        assert!(ArbLeg::is_flatten_cid(&opens[0].key.cid));
     }
 }
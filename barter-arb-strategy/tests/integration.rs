@@ -1,17 +1,35 @@
 //! Integration tests for the delta-neutral prediction market arbitrage strategy.
 //!
-//! Tests the full opportunity detection pipeline using synthetic orderbooks.
+//! Tests the full opportunity detection pipeline using synthetic orderbooks
+//! and a replayed recording fixture.
 //! No network calls.
 
+use barter::{
+    engine::{Processor, state::builder::EngineStateBuilder},
+    risk::RiskManager,
+    strategy::algo::AlgoStrategy,
+};
 use barter_arb_strategy::{
-    ArbitrageConfig, ArbitrageDirection, CorrelatedPair, FeeCalculator,
-    MinOrderValues, PredictionArbitrageStrategy,
+    ArbitrageConfig, ArbitrageDirection, ArbitrageEngineState, ArbitrageGlobalData,
+    ArbitrageInstrumentData, ArbitrageRiskManager, CorrelatedPair, FeeCalculator,
+    MinOrderValues, PredictionArbitrageStrategy, ReplayConfig, ReplayStream,
     correlation::{Outcome, PredictionMarketKey},
+    market_data_lookup,
+};
+use barter_instrument::{
+    Underlying,
+    asset::Asset,
+    exchange::ExchangeId,
+    index::IndexedInstruments,
+    instrument::{Instrument, InstrumentIndex},
+};
+use barter_data::{
+    books::{Level, OrderBook},
+    streams::reconnect,
 };
-use barter_instrument::exchange::ExchangeId;
-use barter_data::books::{Level, OrderBook};
 use barter_execution::order::id::StrategyId;
 use chrono::{Duration, Utc};
+use futures::StreamExt;
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 use std::collections::HashMap;
@@ -54,6 +72,7 @@ fn default_config() -> ArbitrageConfig {
             polymarket: dec!(1),
         },
         max_days_to_expiry: Some(90),
+        ..Default::default()
     }
 }
 
@@ -84,6 +103,62 @@ fn insert_yes_books<'a>(
     );
 }
 
+/// Build indexed instruments for pairs (Kalshi YES/NO, Polymarket YES/NO per pair).
+fn indexed_instruments(pairs: &[CorrelatedPair]) -> IndexedInstruments {
+    let mut builder = IndexedInstruments::builder();
+    for p in pairs {
+        for key in p.instrument_keys() {
+            let name = key.to_instrument_name();
+            let (name_internal, quote) = match key.exchange {
+                ExchangeId::Kalshi => (format!("kalshi_{}", name), "usd"),
+                _ => (format!("poly_{}", name), "usdc"),
+            };
+            builder = builder.add_instrument(Instrument::spot(
+                key.exchange,
+                name_internal,
+                name.as_str(),
+                Underlying::new(Asset::from(name.as_str()), Asset::from(quote)),
+                None,
+            ));
+        }
+    }
+    builder.build()
+}
+
+/// Build engine state with YES books set for each pair.
+fn engine_state(
+    indexed: &IndexedInstruments,
+    books: &[(&CorrelatedPair, OrderBook, OrderBook)],
+) -> ArbitrageEngineState {
+    let mut state = EngineStateBuilder::new(indexed, ArbitrageGlobalData::default(), |_| {
+        ArbitrageInstrumentData::default()
+    })
+    .build();
+
+    let find = |key: PredictionMarketKey| -> InstrumentIndex {
+        let name = key.to_instrument_name();
+        indexed
+            .instruments()
+            .iter()
+            .find(|i| {
+                i.value.exchange.value == key.exchange && i.value.name_exchange.name() == &name
+            })
+            .map(|i| i.key)
+            .unwrap()
+    };
+
+    for (p, poly_yes, kalshi_yes) in books {
+        let poly_idx = find(PredictionMarketKey::polymarket_yes(
+            p.polymarket_yes_token.clone(),
+        ));
+        let kalshi_idx = find(PredictionMarketKey::kalshi_yes(p.kalshi_ticker.clone()));
+        state.instruments.instrument_index_mut(&poly_idx).data.orderbook = Some(poly_yes.clone());
+        state.instruments.instrument_index_mut(&kalshi_idx).data.orderbook = Some(kalshi_yes.clone());
+    }
+
+    state
+}
+
 // ---------------------------------------------------------------------------
 // Test 1: Delta-neutral opportunity detected (poly YES + kalshi NO < $1)
 // ---------------------------------------------------------------------------
@@ -350,11 +425,12 @@ fn test_both_directions_when_both_profitable() {
 
 #[test]
 fn test_fee_calculations_edge_cases() {
+    // 0.07 * 100 * 0.01 * 0.99 = 0.0693, billed rounded up to the cent
     let fee_low = FeeCalculator::kalshi_taker_fee(dec!(0.01), 100);
-    assert_eq!(fee_low, dec!(0.0693));
+    assert_eq!(fee_low, dec!(0.07));
 
     let fee_high = FeeCalculator::kalshi_taker_fee(dec!(0.99), 100);
-    assert_eq!(fee_high, dec!(0.0693));
+    assert_eq!(fee_high, dec!(0.07));
 
     // Symmetric around 50c
     let fee_40 = FeeCalculator::kalshi_taker_fee(dec!(0.40), 100);
@@ -526,3 +602,263 @@ fn test_all_orders_are_buy() {
     assert_eq!(opp.no_side.outcome, Outcome::No);
     // No is_buy field needed — all orders are BUY by design
 }
+
+// ---------------------------------------------------------------------------
+// Test 15: max_open_pairs caps how many pairs produce orders
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_max_open_pairs_limits_orders() {
+    let pairs = vec![
+        pair("KXA", "0xyes_a", "0xno_a", 30),
+        pair("KXB", "0xyes_b", "0xno_b", 30),
+        pair("KXC", "0xyes_c", "0xno_c", 30),
+    ];
+    let indexed = indexed_instruments(&pairs);
+
+    // Same profitable books for every pair: Poly YES 40c + Kalshi NO 45c
+    let poly_yes = book(vec![(dec!(0.38), dec!(100))], vec![(dec!(0.40), dec!(100))]);
+    let kalshi_yes = book(vec![(dec!(0.55), dec!(100))], vec![(dec!(0.48), dec!(100))]);
+    let books: Vec<_> = pairs
+        .iter()
+        .map(|p| (p, poly_yes.clone(), kalshi_yes.clone()))
+        .collect();
+    let state = engine_state(&indexed, &books);
+
+    let s = PredictionArbitrageStrategy::with_instruments(
+        StrategyId::new("test-arb"),
+        default_config(),
+        pairs.clone(),
+        &indexed,
+    );
+    // No exchange balances are reported here, so skip the balance age check
+    let risk = ArbitrageRiskManager {
+        max_open_pairs: 2,
+        max_balance_age: None,
+        ..Default::default()
+    }
+    .with_pairs(&pairs, &indexed);
+
+    let (cancels, opens) = s.generate_algo_orders(&state);
+    let opens: Vec<_> = opens.into_iter().collect();
+    assert_eq!(opens.len(), 6, "All three pairs should be profitable");
+
+    let (_, approved, _, refused) = risk.check(&state, cancels, opens);
+    let approved: Vec<_> = approved.into_iter().map(|a| a.into_item()).collect();
+    let refused: Vec<_> = refused.into_iter().collect();
+
+    let approved_pairs: std::collections::HashSet<_> = approved
+        .iter()
+        .map(|o| risk.instrument_pairs.get(&o.key.instrument).unwrap())
+        .collect();
+    assert_eq!(approved.len(), 4, "Both legs of two pairs approved");
+    assert_eq!(approved_pairs.len(), 2);
+    assert_eq!(refused.len(), 2);
+    assert!(refused.iter().all(|r| r.reason.contains("max open pairs")));
+}
+
+// ---------------------------------------------------------------------------
+// Test 16: Slippage buffer rejects thin opportunities
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_slippage_buffer_rejects_thin_opportunity() {
+    let p = pair("KXTEST", "0xyes", "0xno", 30);
+
+    // Poly YES ask 46c + Kalshi NO ask 51c (from YES bid 49c) = 0.97 + ~1.98c fees
+    // → ~1c profit per contract, wiped out by a 200 bps buffer (~1.94c)
+    let poly_yes = book(vec![(dec!(0.44), dec!(100))], vec![(dec!(0.46), dec!(100))]);
+    let kalshi_yes = book(vec![(dec!(0.49), dec!(100))], vec![(dec!(0.60), dec!(100))]);
+
+    let mut books = HashMap::new();
+    insert_yes_books(&mut books, &p, &poly_yes, &kalshi_yes);
+
+    let no_buffer = strategy(default_config(), vec![p.clone()]);
+    let opps = no_buffer.detect_opportunities(&books);
+    assert_eq!(opps.len(), 1, "Thin opportunity is profitable without a buffer");
+    assert!(opps[0].profit_per_contract() < dec!(0.02));
+
+    let buffered = strategy(
+        ArbitrageConfig {
+            slippage_buffer_bps: 200,
+            ..default_config()
+        },
+        vec![p],
+    );
+    assert!(
+        buffered.detect_opportunities(&books).is_empty(),
+        "200 bps buffer should reject the thin opportunity"
+    );
+}
+
+// ---------------------------------------------------------------------------
+// Test 17: Replayed recording surfaces the embedded opportunity
+// ---------------------------------------------------------------------------
+
+/// Replay `tests/fixtures/replay_recording.jsonl` into engine state.
+///
+/// The recording opens with no arbitrage (Poly YES ask 55c + Kalshi NO ask 50c)
+/// and a Polymarket update at 12:00:05 drops the YES ask to 40c.
+async fn replay_fixture(
+    config: ReplayConfig,
+) -> (CorrelatedPair, IndexedInstruments, ArbitrageEngineState) {
+    let p = pair("KXREPLAY", "0xreplay_yes", "0xreplay_no", 30);
+    let indexed = indexed_instruments(std::slice::from_ref(&p));
+    let lookup = market_data_lookup(std::slice::from_ref(&p), &indexed);
+
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/replay_recording.jsonl");
+    let replay = ReplayStream::from_files([path], &lookup, config).unwrap();
+
+    let mut state = engine_state(&indexed, &[]);
+    let events: Vec<_> = replay.collect().await;
+    for event in events {
+        if let reconnect::Event::Item(event) = event {
+            state
+                .instruments
+                .instrument_index_mut(&event.instrument)
+                .data
+                .process(&event);
+        }
+    }
+
+    (p, indexed, state)
+}
+
+#[tokio::test]
+async fn test_replay_detects_recorded_opportunity() {
+    let (p, indexed, state) = replay_fixture(ReplayConfig::default()).await;
+    let s = PredictionArbitrageStrategy::with_instruments(
+        StrategyId::new("test-arb"),
+        default_config(),
+        vec![p],
+        &indexed,
+    );
+
+    let (_, opens) = s.generate_algo_orders(&state);
+    let opens: Vec<_> = opens.into_iter().collect();
+    assert_eq!(opens.len(), 2, "Replayed update should open both legs");
+}
+
+#[tokio::test]
+async fn test_replay_time_range_excludes_opportunity() {
+    let end = "2025-01-10T12:00:05Z".parse().unwrap();
+    let (p, indexed, state) = replay_fixture(ReplayConfig {
+        end: Some(end),
+        ..Default::default()
+    })
+    .await;
+    let s = PredictionArbitrageStrategy::with_instruments(
+        StrategyId::new("test-arb"),
+        default_config(),
+        vec![p],
+        &indexed,
+    );
+
+    let (_, opens) = s.generate_algo_orders(&state);
+    assert_eq!(
+        opens.into_iter().count(),
+        0,
+        "Snapshots alone hold no arbitrage"
+    );
+}
+
+// ---------------------------------------------------------------------------
+// Test 18: Metrics endpoint exposes detection, order and book metrics
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn test_metrics_endpoint_after_detection() {
+    use barter_arb_strategy::Metrics;
+    use barter_data::{
+        event::{DataKind, MarketEvent},
+        subscription::book::OrderBookEvent,
+    };
+
+    let profitable = pair("KXTEST", "0xyes", "0xno", 30);
+    let thin = pair("KXTHIN", "0xthin_yes", "0xthin_no", 30);
+    let pairs = vec![profitable.clone(), thin.clone()];
+    let indexed = indexed_instruments(&pairs);
+    let metrics = Metrics::new();
+
+    let mut state = engine_state(&indexed, &[]);
+    state.global = ArbitrageGlobalData::default()
+        .with_exchanges(&indexed)
+        .with_metrics(metrics.clone());
+
+    // Feed YES books through the engine processors; the thin pair's single
+    // contract is below Polymarket's $1 minimum order value
+    let books = [
+        (&profitable, dec!(100)),
+        (&thin, dec!(1)),
+    ];
+    for (p, size) in books {
+        for (key, book) in [
+            (
+                PredictionMarketKey::polymarket_yes(p.polymarket_yes_token.clone()),
+                book(vec![(dec!(0.38), size)], vec![(dec!(0.40), size)]),
+            ),
+            (
+                PredictionMarketKey::kalshi_yes(p.kalshi_ticker.clone()),
+                book(vec![(dec!(0.55), size)], vec![(dec!(0.48), size)]),
+            ),
+        ] {
+            let name = key.to_instrument_name();
+            let instrument = indexed
+                .instruments()
+                .iter()
+                .find(|i| {
+                    i.value.exchange.value == key.exchange && i.value.name_exchange.name() == &name
+                })
+                .map(|i| i.key)
+                .unwrap();
+            let event = MarketEvent {
+                time_exchange: Utc::now(),
+                time_received: Utc::now(),
+                exchange: key.exchange,
+                instrument,
+                kind: DataKind::OrderBook(OrderBookEvent::Snapshot(book)),
+            };
+            state.global.process(&event);
+            state.instruments.instrument_index_mut(&instrument).data.process(&event);
+        }
+    }
+
+    let s = PredictionArbitrageStrategy::with_instruments(
+        StrategyId::new("test-arb"),
+        default_config(),
+        pairs,
+        &indexed,
+    )
+    .with_metrics(metrics.clone());
+    let (_, opens) = s.generate_algo_orders(&state);
+    assert_eq!(opens.into_iter().count(), 2);
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(metrics.serve(listener));
+
+    let body = reqwest::get(format!("http://{addr}/metrics"))
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+
+    for expected in [
+        r#"arb_orderbook_events_total{exchange="kalshi"} 2"#,
+        r#"arb_orderbook_events_total{exchange="polymarket"} 2"#,
+        r#"arb_book_age_seconds{exchange="kalshi"}"#,
+        "arb_opportunities_detected_total 2",
+        r#"arb_opportunities_filtered_total{reason="min_order_value"} 1"#,
+        r#"arb_orders_generated_total{exchange="kalshi"} 1"#,
+        r#"arb_orders_generated_total{exchange="polymarket"} 1"#,
+        "arb_deployed_capital 0",
+        "arb_event_to_order_latency_seconds_count 1",
+    ] {
+        assert!(body.contains(expected), "missing `{expected}` in:\n{body}");
+    }
+
+    let missing = reqwest::get(format!("http://{addr}/other")).await.unwrap();
+    assert_eq!(missing.status(), reqwest::StatusCode::NOT_FOUND);
+}
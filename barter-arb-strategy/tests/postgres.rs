@@ -0,0 +1,186 @@
+//! Integration tests for the direct Postgres pair source.
+//!
+//! Requires the `postgres` feature and a scratch database:
+//!
+//!   TEST_DATABASE_URL=postgres://... cargo test -p barter-arb-strategy --features postgres
+//!
+//! Each test creates its own schema with a stub `get_market_pairs_with_volume`
+//! function, and drops it afterwards. Tests are skipped if `TEST_DATABASE_URL`
+//! is not set.
+
+#![cfg(feature = "postgres")]
+
+use barter_arb_strategy::{MarketPairFilters, PairSource, PostgresPairSource};
+use rust_decimal_macros::dec;
+use sqlx::{postgres::PgPoolOptions, PgPool};
+
+const SETUP: &str = r#"
+CREATE TABLE {schema}.market_pairs (
+    id BIGINT PRIMARY KEY,
+    polymarket_id TEXT NOT NULL,
+    polymarket_condition_id TEXT,
+    polymarket_yes_token_id TEXT,
+    kalshi_ticker TEXT NOT NULL,
+    similarity_score NUMERIC NOT NULL,
+    confidence_score NUMERIC NOT NULL,
+    evaluation_id BIGINT,
+    llm_notes TEXT,
+    discovered_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    last_verified_at TIMESTAMPTZ,
+    verified_at TIMESTAMPTZ,
+    valid BOOLEAN,
+    validation_result TEXT,
+    inverse BOOLEAN,
+    polymarket_volume NUMERIC,
+    kalshi_volume NUMERIC,
+    polymarket_question TEXT,
+    kalshi_expiry TIMESTAMPTZ
+);
+
+CREATE FUNCTION {schema}.get_market_pairs_with_volume(
+    min_similarity NUMERIC,
+    min_confidence NUMERIC,
+    row_limit INT,
+    row_offset INT,
+    valid_only BOOLEAN
+) RETURNS SETOF {schema}.market_pairs
+LANGUAGE sql STABLE AS $$
+    SELECT * FROM {schema}.market_pairs
+    WHERE similarity_score >= min_similarity
+      AND confidence_score >= min_confidence
+      AND (NOT valid_only OR valid IS TRUE)
+    ORDER BY id
+    LIMIT row_limit OFFSET row_offset
+$$;
+
+INSERT INTO {schema}.market_pairs
+    (id, polymarket_id, polymarket_condition_id, polymarket_yes_token_id, kalshi_ticker,
+     similarity_score, confidence_score, evaluation_id, valid, inverse,
+     polymarket_volume, kalshi_expiry)
+VALUES
+    (1, 'p1', '0xa', '["yes_a", "no_a"]', 'KXA-1', 0.95, 0.95, 10, true, false,
+     50000, now() + interval '10 days'),
+    (2, 'p2', '0xb', '["yes_b", "no_b"]', 'KXB-1', 0.90, 0.90, NULL, true, true,
+     100, now() + interval '10 days'),
+    (3, 'p3', '0xc', '["yes_c", "no_c"]', 'KXC-1', 0.60, 0.95, 11, true, false,
+     50000, now() + interval '10 days'),
+    (4, 'p4', '0xd', '["yes_d", "no_d"]', 'KXD-1', 0.95, 0.95, 12, false, false,
+     50000, now() + interval '10 days'),
+    (5, 'p5', '0xe', NULL, 'KXE-1', 0.95, 0.95, 13, true, false,
+     50000, now() + interval '10 days');
+"#;
+
+/// Scratch schema holding the stub table and function; dropped on [`Self::drop_schema`].
+struct TestSchema {
+    pool: PgPool,
+    name: String,
+}
+
+impl TestSchema {
+    async fn create() -> Option<Self> {
+        let Ok(url) = std::env::var("TEST_DATABASE_URL") else {
+            eprintln!("TEST_DATABASE_URL not set, skipping Postgres test");
+            return None;
+        };
+
+        let pool = PgPoolOptions::new()
+            .max_connections(1)
+            .connect(&url)
+            .await
+            .expect("failed to connect to TEST_DATABASE_URL");
+
+        let name = format!(
+            "arb_test_{}",
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        );
+
+        sqlx::raw_sql(&format!("CREATE SCHEMA {name}"))
+            .execute(&pool)
+            .await
+            .expect("failed to create schema");
+        sqlx::raw_sql(&SETUP.replace("{schema}", &name))
+            .execute(&pool)
+            .await
+            .expect("failed to set up schema");
+
+        Some(Self { pool, name })
+    }
+
+    fn source(&self) -> PostgresPairSource {
+        PostgresPairSource::from_pool(self.pool.clone())
+            .with_function(format!("{}.get_market_pairs_with_volume", self.name))
+            .unwrap()
+    }
+
+    async fn drop_schema(self) {
+        sqlx::raw_sql(&format!("DROP SCHEMA {} CASCADE", self.name))
+            .execute(&self.pool)
+            .await
+            .expect("failed to drop schema");
+    }
+}
+
+#[tokio::test]
+async fn test_postgres_market_pairs_default_filters() {
+    let Some(schema) = TestSchema::create().await else {
+        return;
+    };
+
+    let records = schema
+        .source()
+        .get_market_pairs(MarketPairFilters::default())
+        .await
+        .unwrap();
+
+    // Below similarity threshold (3) and invalid (4) are excluded by the function
+    let ids: Vec<i64> = records.iter().map(|r| r.id).collect();
+    assert_eq!(ids, vec![1, 2, 5]);
+    assert_eq!(records[1].evaluation_id, 0);
+    assert_eq!(records[1].inverse, Some(true));
+
+    schema.drop_schema().await;
+}
+
+#[tokio::test]
+async fn test_postgres_correlated_pairs_with_client_side_filters() {
+    let Some(schema) = TestSchema::create().await else {
+        return;
+    };
+
+    let filters = MarketPairFilters {
+        min_polymarket_volume: Some(dec!(1000)),
+        ..Default::default()
+    };
+    let pairs = PairSource::get_correlated_pairs(&schema.source(), filters)
+        .await
+        .unwrap();
+
+    // 2 is below the volume filter, 5 has no token ids
+    assert_eq!(pairs.len(), 1);
+    assert_eq!(pairs[0].kalshi_ticker, "KXA-1");
+    assert_eq!(pairs[0].db_id, Some(1));
+
+    schema.drop_schema().await;
+}
+
+#[tokio::test]
+async fn test_postgres_pagination() {
+    let Some(schema) = TestSchema::create().await else {
+        return;
+    };
+
+    let filters = MarketPairFilters {
+        limit: Some(1),
+        offset: Some(1),
+        ..Default::default()
+    };
+    let records = schema.source().get_market_pairs(filters).await.unwrap();
+
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].id, 2);
+
+    schema.drop_schema().await;
+}
@@ -19,7 +19,11 @@
 //!   cargo run -p barter-data --example kalshi_mass_orderbook_stream
 
 use barter_data::{
-    exchange::kalshi::{Kalshi, auth::KalshiCredentials},
+    exchange::kalshi::{
+        Kalshi, KalshiServer,
+        auth::{KalshiAuthHeaders, KalshiCredentials},
+        status::KalshiMarketInfo,
+    },
     streams::{Streams, reconnect::stream::ReconnectingStream},
     subscription::book::OrderBooksL2,
 };
@@ -31,9 +35,6 @@ use barter_instrument::{
 };
 use chrono::{Duration, Utc};
 use futures_util::StreamExt;
-use rsa::pkcs1::DecodeRsaPrivateKey;
-use rsa::pkcs8::DecodePrivateKey;
-use rsa::signature::{RandomizedSigner, SignatureEncoding};
 use serde::Deserialize;
 use std::collections::HashSet;
 use std::time::Instant;
@@ -46,17 +47,7 @@ struct MarketsResponse {
     cursor: Option<String>,
 }
 
-/// Minimal market info from Kalshi REST API.
-#[derive(Debug, Deserialize)]
-#[allow(dead_code)]
-struct KalshiMarketInfo {
-    ticker: String,
-    status: String,
-    #[serde(default)]
-    result: Option<String>,
-}
-
-/// Fetch all active Kalshi market tickers via the REST API with pagination.
+/// Fetch all tradable Kalshi market tickers via the REST API with pagination.
 async fn fetch_all_active_tickers(demo: bool, max: usize) -> Result<Vec<String>, Box<dyn std::error::Error>> {
     let creds = KalshiCredentials::from_env()?;
 
@@ -72,36 +63,20 @@ async fn fetch_all_active_tickers(demo: bool, max: usize) -> Result<Vec<String>,
     let page_limit = 1000;
 
     loop {
-        // Generate auth headers for this request
-        let timestamp = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)?
-            .as_millis() as u64;
-        let path = "/trade-api/v2/markets";
-        let method = "GET";
-        let message = format!("{}{}{}", timestamp, method, path);
-
-        let pem_str = load_pem()?;
-        let private_key = rsa::RsaPrivateKey::from_pkcs8_pem(&pem_str)
-            .or_else(|_| rsa::RsaPrivateKey::from_pkcs1_pem(&pem_str))
-            .map_err(|e| format!("Failed to parse private key: {}", e))?;
-        let signing_key = rsa::pss::SigningKey::<sha2::Sha256>::new(private_key);
-        let mut rng = rsa::rand_core::OsRng;
-        let signature = signing_key.sign_with_rng(&mut rng, message.as_bytes());
-        let signature_b64 = base64::Engine::encode(
-            &base64::engine::general_purpose::STANDARD,
-            signature.to_bytes(),
-        );
-
         let mut url = format!("{}/trade-api/v2/markets?limit={}&status=open", base, page_limit);
         if let Some(ref c) = cursor {
             url.push_str(&format!("&cursor={}", c));
         }
+        let url = url::Url::parse(&url)?;
+
+        // Generate auth headers for this request
+        let auth = creds.generate_rest_auth("GET", &url)?;
 
         let resp = client
-            .get(&url)
-            .header("KALSHI-ACCESS-KEY", &creds.api_key)
-            .header("KALSHI-ACCESS-SIGNATURE", &signature_b64)
-            .header("KALSHI-ACCESS-TIMESTAMP", timestamp.to_string())
+            .get(url)
+            .header(KalshiAuthHeaders::KEY_HEADER, &auth.api_key)
+            .header(KalshiAuthHeaders::SIGNATURE_HEADER, &auth.signature)
+            .header(KalshiAuthHeaders::TIMESTAMP_HEADER, &auth.timestamp)
             .send()
             .await?;
 
@@ -114,12 +89,10 @@ async fn fetch_all_active_tickers(demo: bool, max: usize) -> Result<Vec<String>,
         let page: MarketsResponse = resp.json().await?;
         let count = page.markets.len();
 
-        for market in &page.markets {
-            // status=open already filters; skip settled markets with a definitive result
-            let settled = market.result.as_deref().is_some_and(|r| r == "yes" || r == "no");
-            if !settled {
-                all_tickers.push(market.ticker.clone());
-            }
+        // status=open still returns markets that aren't trading yet or are in a
+        // post-close auction, and markets with a definitive result
+        for market in page.markets.iter().filter(|market| market.is_tradable()) {
+            all_tickers.push(market.ticker.clone());
         }
 
         info!("Fetched page: {} markets ({} active so far)", count, all_tickers.len());
@@ -141,23 +114,6 @@ async fn fetch_all_active_tickers(demo: bool, max: usize) -> Result<Vec<String>,
     Ok(all_tickers)
 }
 
-fn load_pem() -> Result<String, Box<dyn std::error::Error>> {
-    if let Ok(path) = std::env::var("KALSHI_PRIVATE_KEY_PATH") {
-        // Try the path as-is, then ../path, then ../../path
-        for prefix in &["", "../", "../../"] {
-            let full = format!("{}{}", prefix, path);
-            if std::path::Path::new(&full).exists() {
-                return Ok(std::fs::read_to_string(&full)?);
-            }
-        }
-        Ok(std::fs::read_to_string(&path)?)
-    } else if let Ok(pem) = std::env::var("KALSHI_PRIVATE_KEY_PEM") {
-        Ok(pem)
-    } else {
-        Err("Neither KALSHI_PRIVATE_KEY_PATH nor KALSHI_PRIVATE_KEY_PEM set".into())
-    }
-}
-
 fn load_dotenv() {
     for path in &[".env", "../.env", "../../.env"] {
         if std::path::Path::new(path).exists() {
@@ -187,7 +143,8 @@ async fn main() {
     init_logging();
     load_dotenv();
 
-    let demo = std::env::var("KALSHI_DEMO").unwrap_or_default() == "true";
+    // REST tickers and the WebSocket stream follow the same server selection
+    let demo = Kalshi::server() == KalshiServer::Demo;
     let max_markets: usize = std::env::var("KALSHI_LIMIT")
         .ok()
         .and_then(|s| s.parse().ok())
@@ -2,7 +2,13 @@
 //!
 //! Usage: cargo run -p barter-data --example kalshi_ws_raw
 
-use barter_data::exchange::kalshi::auth::{KalshiAuthHeaders, KalshiCredentials};
+use barter_data::exchange::{
+    Connector,
+    kalshi::{
+        Kalshi,
+        auth::{KalshiAuthHeaders, KalshiCredentials},
+    },
+};
 use barter_integration::protocol::websocket::{connect_with_headers, WsMessage};
 use futures_util::{SinkExt, StreamExt};
 use serde_json::json;
@@ -34,15 +40,16 @@ fn load_dotenv() {
 async fn main() {
     load_dotenv();
 
+    // Production unless KALSHI_DEMO=true, matching the Kalshi market data streams
+    let url = Kalshi::url().expect("Failed to build Kalshi URL");
+
     let creds = KalshiCredentials::from_env().expect("Failed to load credentials");
-    let auth = creds.generate_ws_auth().expect("Failed to generate auth");
+    let auth = creds.generate_ws_auth(&url).expect("Failed to generate auth");
 
     println!("API key: {}", auth.api_key);
     println!("Timestamp: {}", auth.timestamp);
     println!("Signature len: {}", auth.signature.len());
 
-    let url = url::Url::parse("wss://api.elections.kalshi.com/trade-api/ws/v2").unwrap();
-
     let headers = [
         (KalshiAuthHeaders::KEY_HEADER, auth.api_key),
         (KalshiAuthHeaders::SIGNATURE_HEADER, auth.signature),
@@ -1,7 +1,7 @@
 //! Example: Subscribe to Polymarket public trade stream via WebSocket.
 //!
-//! Fetches active markets from the gamma API, subscribes to the live_activity
-//! channel, and streams real-time trade events.
+//! Fetches active markets from the gamma API, subscribes to the market channel,
+//! and streams its real-time `last_trade_price` events.
 //!
 //! Usage:
 //!   cargo run -p barter-data --example polymarket_trade_stream
@@ -41,6 +41,12 @@ pub enum DataError {
         prev_last_update_id: u64,
         first_update_id: u64,
     },
+
+    #[error("InvalidOrderBook: {subscription_id} failed validation: {reason}")]
+    InvalidOrderBook {
+        subscription_id: SubscriptionId,
+        reason: String,
+    },
 }
 
 impl DataError {
@@ -49,6 +55,7 @@ impl DataError {
     pub fn is_terminal(&self) -> bool {
         match self {
             DataError::InvalidSequence { .. } => true,
+            DataError::InvalidOrderBook { .. } => true,
             _ => false,
         }
     }
@@ -85,6 +92,14 @@ mod tests {
                 input: DataError::from(SocketError::Sink),
                 expected: false,
             },
+            TestCase {
+                // TC2: is terminal w/ DataError::InvalidOrderBook
+                input: DataError::InvalidOrderBook {
+                    subscription_id: SubscriptionId::from("market|1"),
+                    reason: "crossed".to_string(),
+                },
+                expected: true,
+            },
         ];
 
         for (index, test) in tests.into_iter().enumerate() {
@@ -1,25 +1,60 @@
-//! Kalshi WebSocket authentication.
+//! Kalshi WebSocket and REST authentication.
 //!
-//! Kalshi requires RSA-PSS signed authentication headers for WebSocket connections.
-//! The signature is computed over: `{timestamp}{method}{path}`
+//! Kalshi requires RSA-PSS signed authentication headers for WebSocket connections and
+//! authenticated REST requests. The signature is computed over: `{timestamp}{method}{path}`,
+//! where `path` is the path of the URL being requested (without any query string), so
+//! production and demo requests each sign for the [`KalshiServer`](super::KalshiServer) they
+//! target.
 //!
 //! Headers required:
 //! - `KALSHI-ACCESS-KEY`: API key ID
 //! - `KALSHI-ACCESS-SIGNATURE`: Base64-encoded RSA-PSS signature
 //! - `KALSHI-ACCESS-TIMESTAMP`: Unix timestamp in milliseconds
+//!
+//! Authentication happens once per connection during the handshake; Kalshi has no
+//! in-session re-auth. Long-running streams stay valid until they disconnect, and
+//! every reconnect signs a fresh timestamp.
 
-use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
-use rsa::{
-    RsaPrivateKey,
-    pkcs1::DecodeRsaPrivateKey,
-    pss::SigningKey,
-    pkcs8::DecodePrivateKey,
-    signature::{RandomizedSigner, SignatureEncoding},
+use barter_integration::protocol::http::private::rsa_pss;
+use rsa::{RsaPrivateKey, pkcs1::DecodeRsaPrivateKey, pkcs8::DecodePrivateKey};
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::{SystemTime, UNIX_EPOCH},
 };
-use sha2::Sha256;
-use std::time::{SystemTime, UNIX_EPOCH};
 use thiserror::Error;
-use tracing::debug;
+use url::Url;
+
+/// Last timestamp signed by [`KalshiCredentials::generate_auth`].
+static LAST_AUTH_TIMESTAMP: AtomicU64 = AtomicU64::new(0);
+
+/// Current Unix time in milliseconds, strictly greater than the previously returned value
+/// so back-to-back requests don't reuse a timestamp, but never more than 1ms ahead of
+/// wall-clock time.
+fn next_auth_timestamp() -> u64 {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_millis() as u64;
+
+    let previous = LAST_AUTH_TIMESTAMP
+        .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |last| {
+            Some(next_timestamp(last, now))
+        })
+        .expect("closure always returns Some");
+    next_timestamp(previous, now)
+}
+
+/// Timestamp to sign at wall-clock time `now` after signing `last`.
+///
+/// Bursts within one millisecond may reuse `now + 1`, rather than run ahead of the
+/// clock and sign future-dated requests.
+fn next_timestamp(last: u64, now: u64) -> u64 {
+    if last < now {
+        now
+    } else {
+        (last + 1).min(now + 1)
+    }
+}
 
 /// Errors that can occur during Kalshi authentication.
 #[derive(Debug, Error)]
@@ -130,43 +165,40 @@ impl KalshiCredentials {
         }
     }
 
-    /// Generate authentication headers for a WebSocket connection.
+    /// Generate authentication headers for a WebSocket connection to `url`.
     ///
-    /// Returns (api_key, signature, timestamp) tuple.
-    pub fn generate_ws_auth(&self) -> Result<KalshiAuthHeaders, KalshiAuthError> {
-        // Get current timestamp in milliseconds
-        let timestamp = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .expect("Time went backwards")
-            .as_millis() as u64;
-
-        // WebSocket auth uses GET method and /trade-api/ws/v2 path
-        let method = "GET";
-        let path = "/trade-api/ws/v2";
-
-        // Create message to sign: timestamp + method + path
-        let message = format!("{}{}{}", timestamp, method, path);
-
-        debug!("Signing Kalshi WebSocket auth message: {}", message);
+    /// Each call signs the current time, strictly later than the previous call,
+    /// so headers must be generated per connection attempt rather than reused.
+    pub fn generate_ws_auth(&self, url: &Url) -> Result<KalshiAuthHeaders, KalshiAuthError> {
+        // WebSocket auth uses GET method and the path of the server connected to
+        self.generate_auth("GET", url)
+    }
 
-        // Sign with RSA-PSS (SHA-256 + MGF1-SHA256)
-        let signing_key = SigningKey::<Sha256>::new(self.private_key.clone());
-        let mut rng = rsa::rand_core::OsRng;
-        let signature = signing_key
-            .sign_with_rng(&mut rng, message.as_bytes());
+    /// Generate authentication headers for a REST request with `method` to `url`.
+    ///
+    /// As with [`Self::generate_ws_auth`], headers are valid for a single request.
+    pub fn generate_rest_auth(
+        &self,
+        method: &str,
+        url: &Url,
+    ) -> Result<KalshiAuthHeaders, KalshiAuthError> {
+        self.generate_auth(method, url)
+    }
 
-        // Base64 encode the signature
-        let signature_b64 = BASE64.encode(signature.to_bytes());
+    fn generate_auth(&self, method: &str, url: &Url) -> Result<KalshiAuthHeaders, KalshiAuthError> {
+        // Get current timestamp in milliseconds
+        let timestamp = next_auth_timestamp();
+        let signature = rsa_pss::sign_request(&self.private_key, timestamp, method, url.path());
 
         Ok(KalshiAuthHeaders {
             api_key: self.api_key.clone(),
-            signature: signature_b64,
+            signature,
             timestamp: timestamp.to_string(),
         })
     }
 }
 
-/// Authentication headers for Kalshi WebSocket connection.
+/// Authentication headers for a Kalshi WebSocket connection or REST request.
 #[derive(Debug, Clone)]
 pub struct KalshiAuthHeaders {
     /// API key ID
@@ -189,9 +221,16 @@ impl KalshiAuthHeaders {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+    use sha2::Sha256;
 
-    // Note: Real tests would require valid RSA keys
-    // This test just verifies the structure compiles
+    fn test_credentials() -> KalshiCredentials {
+        let mut rng = rsa::rand_core::OsRng;
+        KalshiCredentials {
+            api_key: "test-key".to_string(),
+            private_key: RsaPrivateKey::new(&mut rng, 1024).unwrap(),
+        }
+    }
 
     #[test]
     fn test_auth_headers_constants() {
@@ -205,4 +244,89 @@ mod tests {
             "KALSHI-ACCESS-TIMESTAMP"
         );
     }
+
+    fn verify(credentials: &KalshiCredentials, headers: &KalshiAuthHeaders, path: &str) -> bool {
+        verify_request(credentials, headers, "GET", path)
+    }
+
+    fn verify_request(
+        credentials: &KalshiCredentials,
+        headers: &KalshiAuthHeaders,
+        method: &str,
+        path: &str,
+    ) -> bool {
+        use rsa::{pss::VerifyingKey, signature::Verifier};
+
+        let message = format!("{}{method}{path}", headers.timestamp);
+        let signature =
+            rsa::pss::Signature::try_from(BASE64.decode(&headers.signature).unwrap().as_slice())
+                .unwrap();
+        VerifyingKey::<Sha256>::new(credentials.private_key.to_public_key())
+            .verify(message.as_bytes(), &signature)
+            .is_ok()
+    }
+
+    #[test]
+    fn test_next_timestamp_stays_within_1ms_of_wall_clock() {
+        // Wall-clock time once it passes the last timestamp
+        assert_eq!(next_timestamp(0, 1_000), 1_000);
+        assert_eq!(next_timestamp(999, 1_000), 1_000);
+
+        // Repeated requests within a millisecond move 1ms ahead
+        assert_eq!(next_timestamp(1_000, 1_000), 1_001);
+
+        // But no further, even after a burst or the clock stepping back
+        assert_eq!(next_timestamp(1_001, 1_000), 1_001);
+        assert_eq!(next_timestamp(5_000, 1_000), 1_001);
+    }
+
+    #[test]
+    fn test_ws_auth_signature_verifies() {
+        use super::super::KalshiServer;
+
+        let credentials = test_credentials();
+
+        for server in [KalshiServer::Production, KalshiServer::Demo] {
+            let url = Url::parse(server.websocket_url()).unwrap();
+            let headers = credentials.generate_ws_auth(&url).unwrap();
+            assert_eq!(url.path(), "/trade-api/ws/v2");
+            assert!(verify(&credentials, &headers, url.path()), "{server:?} failed");
+        }
+
+        // The signed path is taken from the URL connected to
+        let url = Url::parse("wss://demo-api.kalshi.co/trade-api/ws/v3").unwrap();
+        let headers = credentials.generate_ws_auth(&url).unwrap();
+        assert!(verify(&credentials, &headers, "/trade-api/ws/v3"));
+        assert!(!verify(&credentials, &headers, "/trade-api/ws/v2"));
+    }
+
+    #[test]
+    fn test_rest_auth_signs_method_and_path_without_query() {
+        let credentials = test_credentials();
+        let url = Url::parse(
+            "https://api.elections.kalshi.com/trade-api/v2/markets/KXTEST/orderbook?depth=10",
+        )
+        .unwrap();
+
+        let headers = credentials.generate_rest_auth("GET", &url).unwrap();
+        assert_eq!(headers.api_key, "test-key");
+        assert!(verify(
+            &credentials,
+            &headers,
+            "/trade-api/v2/markets/KXTEST/orderbook"
+        ));
+        assert!(!verify(
+            &credentials,
+            &headers,
+            "/trade-api/v2/markets/KXTEST/orderbook?depth=10"
+        ));
+
+        let headers = credentials.generate_rest_auth("POST", &url).unwrap();
+        assert!(verify_request(
+            &credentials,
+            &headers,
+            "POST",
+            "/trade-api/v2/markets/KXTEST/orderbook"
+        ));
+    }
 }
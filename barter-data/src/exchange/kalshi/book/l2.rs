@@ -1,16 +1,40 @@
 use crate::{
+    Identifier, SnapshotFetcher,
     books::OrderBook,
     event::{MarketEvent, MarketIter},
-    exchange::kalshi::{channel::KalshiChannel, message::{KalshiMarketLifecycle, KalshiOrderbookSnapshot, KalshiOrderbookDelta, KalshiLevel}},
-    subscription::book::OrderBookEvent,
+    exchange::kalshi::{
+        Kalshi,
+        auth::{KalshiAuthHeaders, KalshiCredentials},
+        channel::KalshiChannel,
+        market::KalshiMarket,
+        message::{
+            KalshiLevel, KalshiMarketLifecycle, KalshiMarketLifecycleData, KalshiOrderbookDelta,
+            KalshiOrderbookSnapshot, KalshiOrderbookSnapshotData,
+        },
+        subscriber::get_credentials,
+    },
+    instrument::InstrumentData,
+    subscription::{
+        Subscription,
+        book::{OrderBookEvent, OrderBooksL2},
+    },
 };
-use barter_instrument::exchange::ExchangeId;
-use barter_integration::subscription::SubscriptionId;
+use barter_instrument::{
+    exchange::ExchangeId,
+    instrument::market_data::kind::Outcome,
+    kalshi::price,
+};
+use barter_integration::{error::SocketError, subscription::SubscriptionId};
 use chrono::Utc;
 use derive_more::Constructor;
+use fnv::FnvHashMap;
+use futures::StreamExt;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use smol_str::format_smolstr;
+use std::future::Future;
+use tracing::warn;
+use url::Url;
 
 /// Metadata for managing a Kalshi OrderBook L2 stream.
 #[derive(Debug, Constructor)]
@@ -20,20 +44,186 @@ pub struct KalshiOrderBookL2Meta<InstrumentKey> {
     pub sequence: u64,
 }
 
+/// Maximum number of [`Kalshi`] REST orderbook snapshots requested concurrently, keeping
+/// start-up well inside the basic tier read rate limit.
+pub const KALSHI_SNAPSHOT_CONCURRENCY: usize = 8;
+
+/// [`SnapshotFetcher`] that requests each subscribed [`Kalshi`] market's orderbook over REST,
+/// so books are populated as soon as a (re)connected stream starts.
+///
+/// Requests are signed with the same credentials as the
+/// [`KalshiAuthenticatedSubscriber`](super::super::subscriber::KalshiAuthenticatedSubscriber)
+/// and sent to the selected [`KalshiServer`](super::super::KalshiServer), at most
+/// [`KALSHI_SNAPSHOT_CONCURRENCY`] at a time. Each market is fetched once and emitted as an
+/// [`OrderBookEvent::Snapshot`] for every subscribed outcome.
+///
+/// The snapshots are emitted ahead of any WebSocket events. The WebSocket
+/// `orderbook_snapshot` that follows remains authoritative, so a market whose REST request
+/// fails is skipped with a warning rather than failing the stream.
+#[derive(Debug)]
+pub struct KalshiOrderBooksL2SnapshotFetcher;
+
+impl SnapshotFetcher<Kalshi, OrderBooksL2> for KalshiOrderBooksL2SnapshotFetcher {
+    fn fetch_snapshots<Instrument>(
+        subscriptions: &[Subscription<Kalshi, Instrument, OrderBooksL2>],
+    ) -> impl Future<Output = Result<Vec<MarketEvent<Instrument::Key, OrderBookEvent>>, SocketError>>
+    + Send
+    where
+        Instrument: InstrumentData,
+        Subscription<Kalshi, Instrument, OrderBooksL2>: Identifier<KalshiMarket>,
+    {
+        let markets = subscriptions
+            .iter()
+            .map(|sub| (sub.instrument.key().clone(), sub.id()))
+            .collect::<Vec<_>>();
+
+        async move {
+            let credentials = get_credentials()?;
+            Ok(fetch_orderbook_snapshots(Kalshi::server().rest_url(), credentials, markets).await)
+        }
+    }
+}
+
+/// Response from `GET /markets/{ticker}/orderbook`.
+///
+/// Kalshi sends `null` rather than an empty array for a side without levels.
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize)]
+pub struct KalshiOrderbookResponse {
+    pub orderbook: KalshiOrderbookResponseData,
+}
+
+/// Resting bids of a [`KalshiOrderbookResponse`]: (price_cents, quantity), and for markets
+/// quoting sub-cent prices also (price_dollars, quantity).
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize)]
+pub struct KalshiOrderbookResponseData {
+    #[serde(default)]
+    pub yes: Option<Vec<(u32, u32)>>,
+    #[serde(default)]
+    pub no: Option<Vec<(u32, u32)>>,
+    #[serde(default)]
+    pub yes_dollars: Option<Vec<(Decimal, u32)>>,
+    #[serde(default)]
+    pub no_dollars: Option<Vec<(Decimal, u32)>>,
+}
+
+/// Fetch the orderbook of every market in `markets` from the REST API at `rest_url`,
+/// returning a snapshot for each `(instrument, market)` whose market was fetched.
+async fn fetch_orderbook_snapshots<InstrumentKey>(
+    rest_url: &str,
+    credentials: &KalshiCredentials,
+    markets: Vec<(InstrumentKey, KalshiMarket)>,
+) -> Vec<MarketEvent<InstrumentKey, OrderBookEvent>>
+where
+    InstrumentKey: Clone,
+{
+    let client = reqwest::Client::new();
+
+    let mut tickers = markets
+        .iter()
+        .map(|(_, market)| market.ticker().to_uppercase())
+        .collect::<Vec<_>>();
+    tickers.sort();
+    tickers.dedup();
+
+    let books = futures::stream::iter(tickers)
+        .map(|ticker| {
+            let client = &client;
+            async move {
+                let book = fetch_orderbook(client, rest_url, credentials, &ticker).await;
+                (ticker, book)
+            }
+        })
+        .buffer_unordered(KALSHI_SNAPSHOT_CONCURRENCY)
+        .filter_map(|(ticker, book)| async move {
+            match book {
+                Ok(book) => Some((ticker, book)),
+                Err(error) => {
+                    warn!(%ticker, %error, "failed to fetch Kalshi orderbook snapshot, skipping");
+                    None
+                }
+            }
+        })
+        .collect::<FnvHashMap<_, _>>()
+        .await;
+
+    markets
+        .into_iter()
+        .filter_map(|(instrument, market)| {
+            let book = books.get(&market.ticker().to_uppercase())?;
+            let snapshot = KalshiOrderbookSnapshot {
+                sid: 0,
+                seq: 0,
+                msg: book.clone(),
+            };
+            let is_yes = market.outcome() == Outcome::Yes;
+            Some(MarketEvent::from((ExchangeId::Kalshi, instrument, snapshot, is_yes)))
+        })
+        .collect()
+}
+
+/// Fetch the orderbook of one market `ticker`.
+async fn fetch_orderbook(
+    client: &reqwest::Client,
+    rest_url: &str,
+    credentials: &KalshiCredentials,
+    ticker: &str,
+) -> Result<KalshiOrderbookSnapshotData, SocketError> {
+    let url = Url::parse(&format!("{rest_url}/markets/{ticker}/orderbook"))?;
+    let auth = credentials
+        .generate_rest_auth("GET", &url)
+        .map_err(|error| SocketError::Subscribe(format!("Failed to generate auth: {error}")))?;
+
+    let response = client
+        .get(url)
+        .header(KalshiAuthHeaders::KEY_HEADER, auth.api_key)
+        .header(KalshiAuthHeaders::SIGNATURE_HEADER, auth.signature)
+        .header(KalshiAuthHeaders::TIMESTAMP_HEADER, auth.timestamp)
+        .send()
+        .await
+        .map_err(SocketError::Http)?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(SocketError::HttpResponse(status, body));
+    }
+
+    let KalshiOrderbookResponse { orderbook } = response
+        .json::<KalshiOrderbookResponse>()
+        .await
+        .map_err(SocketError::Http)?;
+
+    Ok(KalshiOrderbookSnapshotData {
+        market_ticker: ticker.to_string(),
+        yes: orderbook.yes.unwrap_or_default(),
+        no: orderbook.no.unwrap_or_default(),
+        yes_dollars: orderbook.yes_dollars.unwrap_or_default(),
+        no_dollars: orderbook.no_dollars.unwrap_or_default(),
+        event_type: None,
+    })
+}
+
 /// Internal representation of a Kalshi orderbook for a single market.
 ///
 /// This tracks both YES and NO sides. For arbitrage, we typically treat
 /// YES and NO as separate instruments.
+///
+/// Levels are keyed by price in ticks (see [`price::TICKS_PER_DOLLAR`]) rather than cents,
+/// so sub-cent prices are kept exactly.
 #[derive(Clone, PartialEq, Eq, Debug, Default, Deserialize, Serialize)]
 pub struct KalshiOrderBook {
-    /// YES side levels (price_cents -> quantity)
+    /// YES side levels (price_ticks -> quantity)
     pub yes: std::collections::BTreeMap<u32, u32>,
-    /// NO side levels (price_cents -> quantity)
+    /// NO side levels (price_ticks -> quantity)
     pub no: std::collections::BTreeMap<u32, u32>,
     /// Current sequence number
     pub seq: u64,
     /// Last update timestamp
     pub last_update: Option<String>,
+    /// Set by a transient lifecycle event (eg/ a paused market); levels are kept
+    /// but may no longer be tradable. Cleared by the next snapshot or delta.
+    #[serde(default)]
+    pub stale: bool,
 }
 
 impl KalshiOrderBook {
@@ -42,14 +232,14 @@ impl KalshiOrderBook {
         let mut yes = std::collections::BTreeMap::new();
         let mut no = std::collections::BTreeMap::new();
 
-        for (price, amount) in &snapshot.msg.yes {
-            if *amount > 0 {
-                yes.insert(*price, *amount);
+        for (price, amount) in snapshot.msg.yes_ticks() {
+            if amount > 0 {
+                yes.insert(price, amount);
             }
         }
-        for (price, amount) in &snapshot.msg.no {
-            if *amount > 0 {
-                no.insert(*price, *amount);
+        for (price, amount) in snapshot.msg.no_ticks() {
+            if amount > 0 {
+                no.insert(price, amount);
             }
         }
 
@@ -58,6 +248,7 @@ impl KalshiOrderBook {
             no,
             seq: snapshot.seq,
             last_update: None,
+            stale: false,
         }
     }
 
@@ -69,35 +260,49 @@ impl KalshiOrderBook {
             _ => return,
         };
 
-        let current_size = side.get(&delta.msg.price).copied().unwrap_or(0);
+        let price = delta.msg.price_ticks();
+        let current_size = side.get(&price).copied().unwrap_or(0);
         let new_size = (current_size as i32 + delta.msg.delta).max(0) as u32;
 
         if new_size == 0 {
             // Remove price level when size reaches zero
-            side.remove(&delta.msg.price);
+            side.remove(&price);
         } else {
-            side.insert(delta.msg.price, new_size);
+            side.insert(price, new_size);
         }
 
         self.seq = delta.seq;
+        self.stale = false;
     }
 
-    /// Clear all levels from the orderbook (used on market lifecycle events).
+    /// Clear all levels from the orderbook (used on terminal market lifecycle events).
     pub fn clear(&mut self) {
         self.yes.clear();
         self.no.clear();
     }
 
+    /// Apply a market lifecycle event.
+    ///
+    /// Terminal events clear the book, transient events mark it stale but keep levels.
+    pub fn apply_lifecycle(&mut self, lifecycle: &KalshiMarketLifecycleData) {
+        if lifecycle.is_terminal() {
+            self.clear();
+            self.stale = false;
+        } else {
+            self.stale = true;
+        }
+    }
+
     /// Get the best YES bid (highest price with quantity).
     pub fn best_yes_bid(&self) -> Option<KalshiLevel> {
         self.yes.iter().next_back().map(|(&price, &amount)| KalshiLevel { price, amount })
     }
 
-    /// Get the best YES ask (lowest price to buy YES = 100 - best NO bid).
+    /// Get the best YES ask (lowest price to buy YES = $1 - best NO bid).
     pub fn best_yes_ask(&self) -> Option<KalshiLevel> {
-        // YES ask = 100 - NO bid (inverse relationship)
+        // YES ask = $1 - NO bid (inverse relationship)
         self.no.iter().next_back().map(|(&no_bid_price, &amount)| {
-            KalshiLevel { price: 100 - no_bid_price, amount }
+            KalshiLevel { price: price::TICKS_PER_DOLLAR - no_bid_price, amount }
         })
     }
 
@@ -106,11 +311,11 @@ impl KalshiOrderBook {
         self.no.iter().next_back().map(|(&price, &amount)| KalshiLevel { price, amount })
     }
 
-    /// Get the best NO ask (lowest price to buy NO = 100 - best YES bid).
+    /// Get the best NO ask (lowest price to buy NO = $1 - best YES bid).
     pub fn best_no_ask(&self) -> Option<KalshiLevel> {
-        // NO ask = 100 - YES bid (inverse relationship)
+        // NO ask = $1 - YES bid (inverse relationship)
         self.yes.iter().next_back().map(|(&yes_bid_price, &amount)| {
-            KalshiLevel { price: 100 - yes_bid_price, amount }
+            KalshiLevel { price: price::TICKS_PER_DOLLAR - yes_bid_price, amount }
         })
     }
 
@@ -118,34 +323,67 @@ impl KalshiOrderBook {
     pub fn to_yes_orderbook(&self) -> OrderBook {
         let bids: Vec<_> = self.yes.iter()
             .map(|(&price, &amount)| {
-                (Decimal::from(price) / Decimal::from(100), Decimal::from(amount))
+                (price::from_ticks(price), Decimal::from(amount))
             })
             .collect();
 
         // YES asks = inverse of NO bids
         let asks: Vec<_> = self.no.iter()
             .map(|(&no_bid_price, &amount)| {
-                let yes_ask_price = 100 - no_bid_price;
-                (Decimal::from(yes_ask_price) / Decimal::from(100), Decimal::from(amount))
+                let yes_ask_price = price::TICKS_PER_DOLLAR - no_bid_price;
+                (price::from_ticks(yes_ask_price), Decimal::from(amount))
             })
             .collect();
 
         OrderBook::new(self.seq, None, bids, asks)
     }
 
+    /// Convert the level changed by `delta` to a barter OrderBook update for the
+    /// YES side, holding the level's current quantity (zero once removed).
+    ///
+    /// Must be called after [`Self::apply_delta`].
+    pub fn to_yes_update(&self, delta: &KalshiOrderbookDelta) -> OrderBook {
+        self.to_update(delta, "yes")
+    }
+
+    /// Convert the level changed by `delta` to a barter OrderBook update for the
+    /// NO side, see [`Self::to_yes_update`].
+    pub fn to_no_update(&self, delta: &KalshiOrderbookDelta) -> OrderBook {
+        self.to_update(delta, "no")
+    }
+
+    fn to_update(&self, delta: &KalshiOrderbookDelta, outcome: &str) -> OrderBook {
+        let price = delta.msg.price_ticks();
+        let amount = match delta.msg.side.as_str() {
+            "yes" => self.yes.get(&price),
+            "no" => self.no.get(&price),
+            _ => return OrderBook::new(self.seq, None, Vec::<(Decimal, Decimal)>::new(), vec![]),
+        };
+        let amount = Decimal::from(amount.copied().unwrap_or(0));
+
+        if delta.msg.side == outcome {
+            let bid = (price::from_ticks(price), amount);
+            OrderBook::new(self.seq, None, vec![bid], vec![])
+        } else {
+            // Bids on the other outcome are asks at the inverse price
+            let ask = (price::from_ticks(price::TICKS_PER_DOLLAR - price), amount);
+            OrderBook::new(self.seq, None, vec![], vec![ask])
+        }
+    }
+
     /// Convert to barter OrderBook for the NO side.
     pub fn to_no_orderbook(&self) -> OrderBook {
         let bids: Vec<_> = self.no.iter()
             .map(|(&price, &amount)| {
-                (Decimal::from(price) / Decimal::from(100), Decimal::from(amount))
+                (price::from_ticks(price), Decimal::from(amount))
             })
             .collect();
 
         // NO asks = inverse of YES bids
         let asks: Vec<_> = self.yes.iter()
             .map(|(&yes_bid_price, &amount)| {
-                let no_ask_price = 100 - yes_bid_price;
-                (Decimal::from(no_ask_price) / Decimal::from(100), Decimal::from(amount))
+                let no_ask_price = price::TICKS_PER_DOLLAR - yes_bid_price;
+                (price::from_ticks(no_ask_price), Decimal::from(amount))
             })
             .collect();
 
@@ -159,6 +397,10 @@ impl<InstrumentKey> From<(ExchangeId, InstrumentKey, KalshiOrderbookSnapshot)>
     fn from(
         (exchange, instrument, snapshot): (ExchangeId, InstrumentKey, KalshiOrderbookSnapshot),
     ) -> Self {
+        // Lifecycle messages decode as snapshots without levels
+        if let Some(lifecycle) = snapshot.lifecycle() {
+            return Self::from((exchange, instrument, lifecycle));
+        }
         Self(vec![Ok(MarketEvent::from((exchange, instrument, snapshot)))])
     }
 }
@@ -212,32 +454,32 @@ impl<InstrumentKey> From<(ExchangeId, InstrumentKey, KalshiMarketLifecycle)>
     fn from(
         (exchange, instrument, lifecycle): (ExchangeId, InstrumentKey, KalshiMarketLifecycle),
     ) -> Self {
-        Self(vec![Ok(MarketEvent::from((exchange, instrument, lifecycle)))])
-    }
-}
+        // Transient events (eg/ "closed" during a pause) keep the book: the market
+        // may reopen, and downstream levels remain until the next snapshot or delta.
+        if !lifecycle.msg.is_terminal() {
+            tracing::info!(
+                ticker = %lifecycle.msg.market_ticker,
+                event_type = %lifecycle.msg.event_type,
+                "Market lifecycle event — retaining orderbook as stale"
+            );
+            return Self(vec![]);
+        }
 
-impl<InstrumentKey> From<(ExchangeId, InstrumentKey, KalshiMarketLifecycle)>
-    for MarketEvent<InstrumentKey, OrderBookEvent>
-{
-    fn from(
-        (exchange, instrument, lifecycle): (ExchangeId, InstrumentKey, KalshiMarketLifecycle),
-    ) -> Self {
         // Emit an empty orderbook snapshot to clear the book for this market.
-        // The strategy skips pairs without orderbook data, so this effectively
-        // removes the market from arbitrage monitoring.
+        // Consumers learn the market's status itself from a MarketStatuses stream.
         tracing::warn!(
             ticker = %lifecycle.msg.market_ticker,
             event_type = %lifecycle.msg.event_type,
-            "Market lifecycle event — clearing orderbook"
+            "Terminal market lifecycle event — clearing orderbook"
         );
         let empty_book = OrderBook::new(lifecycle.seq, None, Vec::<(Decimal, Decimal)>::new(), Vec::<(Decimal, Decimal)>::new());
-        Self {
+        Self(vec![Ok(MarketEvent {
             time_exchange: Utc::now(),
             time_received: Utc::now(),
             exchange,
             instrument,
             kind: OrderBookEvent::Snapshot(empty_book),
-        }
+        })])
     }
 }
 
@@ -253,7 +495,8 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::exchange::kalshi::message::{KalshiOrderbookSnapshotData, KalshiOrderbookDeltaData};
+    use crate::exchange::kalshi::message::KalshiOrderbookDeltaData;
+    use rust_decimal_macros::dec;
 
     fn test_snapshot(yes: Vec<(u32, u32)>, no: Vec<(u32, u32)>, seq: u64) -> KalshiOrderbookSnapshot {
         KalshiOrderbookSnapshot {
@@ -263,6 +506,9 @@ mod tests {
                 market_ticker: "TEST".to_string(),
                 yes,
                 no,
+                yes_dollars: vec![],
+                no_dollars: vec![],
+                event_type: None,
             },
         }
     }
@@ -274,6 +520,7 @@ mod tests {
             msg: KalshiOrderbookDeltaData {
                 market_ticker: "TEST".to_string(),
                 price,
+                price_dollars: None,
                 delta,
                 side: side.to_string(),
             },
@@ -290,10 +537,10 @@ mod tests {
 
         let book = KalshiOrderBook::from_snapshot(&snapshot);
 
-        assert_eq!(book.yes.get(&40), Some(&100));
-        assert_eq!(book.yes.get(&39), Some(&200));
-        assert_eq!(book.no.get(&60), Some(&150));
-        assert_eq!(book.no.get(&61), Some(&250));
+        assert_eq!(book.yes.get(&4000), Some(&100));
+        assert_eq!(book.yes.get(&3900), Some(&200));
+        assert_eq!(book.no.get(&6000), Some(&150));
+        assert_eq!(book.no.get(&6100), Some(&250));
         assert_eq!(book.seq, 1);
     }
 
@@ -304,15 +551,15 @@ mod tests {
 
         // Add to existing level
         book.apply_delta(&test_delta(40, 50, "yes", 2));
-        assert_eq!(book.yes.get(&40), Some(&150));
+        assert_eq!(book.yes.get(&4000), Some(&150));
 
         // Remove from existing level
         book.apply_delta(&test_delta(40, -150, "yes", 3));
-        assert_eq!(book.yes.get(&40), None);
+        assert_eq!(book.yes.get(&4000), None);
 
         // Add new level
         book.apply_delta(&test_delta(45, 75, "yes", 4));
-        assert_eq!(book.yes.get(&45), Some(&75));
+        assert_eq!(book.yes.get(&4500), Some(&75));
     }
 
     #[test]
@@ -327,19 +574,43 @@ mod tests {
 
         // Best YES bid = 40c
         let yes_bid = book.best_yes_bid().unwrap();
-        assert_eq!(yes_bid.price, 40);
+        assert_eq!(yes_bid.price, 4000);
 
         // Best YES ask = 100 - 60 = 40c (inverse of best NO bid)
         let yes_ask = book.best_yes_ask().unwrap();
-        assert_eq!(yes_ask.price, 40);
+        assert_eq!(yes_ask.price, 4000);
 
         // Best NO bid = 60c
         let no_bid = book.best_no_bid().unwrap();
-        assert_eq!(no_bid.price, 60);
+        assert_eq!(no_bid.price, 6000);
 
         // Best NO ask = 100 - 40 = 60c (inverse of best YES bid)
         let no_ask = book.best_no_ask().unwrap();
-        assert_eq!(no_ask.price, 60);
+        assert_eq!(no_ask.price, 6000);
+    }
+
+    #[test]
+    fn test_kalshi_orderbook_subcent_levels() {
+        let mut snapshot = test_snapshot(vec![(51, 100)], vec![(49, 150)], 1);
+        snapshot.msg.market_ticker = "KXSUBCENT-25".to_string();
+        snapshot.msg.yes_dollars = vec![(dec!(0.505), 100)];
+        snapshot.msg.no_dollars = vec![(dec!(0.4905), 150)];
+
+        let mut book = KalshiOrderBook::from_snapshot(&snapshot);
+        assert_eq!(book.yes.get(&5050), Some(&100));
+        assert_eq!(book.best_yes_ask().unwrap().price_decimal(), dec!(0.5095));
+
+        // Sub-cent deltas update their own level rather than the rounded cent
+        let mut delta = test_delta(51, 20, "yes", 2);
+        delta.msg.price_dollars = Some(dec!(0.505));
+        book.apply_delta(&delta);
+        assert_eq!(book.yes.get(&5050), Some(&120));
+        assert_eq!(book.yes.get(&5100), None);
+
+        let yes = book.to_yes_orderbook();
+        assert_eq!(yes.bids().best().unwrap().price, dec!(0.505));
+        assert_eq!(yes.asks().best().unwrap().price, dec!(0.5095));
+        assert_eq!(book.to_yes_update(&delta).bids().best().unwrap().price, dec!(0.505));
     }
 
     #[test]
@@ -360,7 +631,7 @@ mod tests {
     fn test_lifecycle_json_deserializes_as_empty_snapshot() {
         // Lifecycle messages on the same WS connection deserialize as
         // KalshiOrderbookSnapshot with empty yes/no (via #[serde(default)]).
-        // This effectively clears the orderbook for that market.
+        // Terminal events like "settled" clear the orderbook for that market.
         let lifecycle_json = r#"{
             "type": "market_lifecycle_v2",
             "sid": 1,
@@ -386,4 +657,222 @@ mod tests {
         assert!(yes_book.bids().best().is_none());
         assert!(yes_book.asks().best().is_none());
     }
+
+    fn lifecycle_json(event_type: &str) -> String {
+        format!(
+            r#"{{
+                "type": "market_lifecycle_v2",
+                "sid": 1,
+                "seq": 4,
+                "msg": {{
+                    "market_ticker": "KXBTC-25JAN31-T100000",
+                    "event_type": "{event_type}"
+                }}
+            }}"#
+        )
+    }
+
+    #[test]
+    fn test_kalshi_orderbook_apply_lifecycle() {
+        let snapshot = test_snapshot(vec![(40, 100)], vec![(60, 150)], 1);
+        let mut book = KalshiOrderBook::from_snapshot(&snapshot);
+        let lifecycle = |event_type: &str| KalshiMarketLifecycleData {
+            market_ticker: "TEST".to_string(),
+            event_type: event_type.to_string(),
+        };
+
+        // Transient pause keeps levels but marks the book stale
+        book.apply_lifecycle(&lifecycle("closed"));
+        assert!(book.stale);
+        assert_eq!(book.yes.get(&4000), Some(&100));
+        assert_eq!(book.no.get(&6000), Some(&150));
+
+        // Activity resumes
+        book.apply_delta(&test_delta(41, 10, "yes", 2));
+        assert!(!book.stale);
+
+        // Settlement discards levels
+        book.apply_lifecycle(&lifecycle("settled"));
+        assert!(book.yes.is_empty());
+        assert!(book.no.is_empty());
+    }
+
+    #[test]
+    fn test_lifecycle_closed_retains_orderbook() {
+        let snapshot: KalshiOrderbookSnapshot =
+            serde_json::from_str(&lifecycle_json("closed")).unwrap();
+        assert_eq!(snapshot.msg.event_type.as_deref(), Some("closed"));
+
+        let events = MarketIter::<&str, OrderBookEvent>::from((ExchangeId::Kalshi, "kxbtc", snapshot));
+        assert!(events.0.is_empty(), "Transient event must not emit an empty book");
+    }
+
+    #[test]
+    fn test_lifecycle_settled_clears_orderbook() {
+        let snapshot: KalshiOrderbookSnapshot =
+            serde_json::from_str(&lifecycle_json("settled")).unwrap();
+
+        let events = MarketIter::<&str, OrderBookEvent>::from((ExchangeId::Kalshi, "kxbtc", snapshot));
+        assert_eq!(events.0.len(), 1);
+        match &events.0[0] {
+            Ok(MarketEvent { kind: OrderBookEvent::Snapshot(book), .. }) => {
+                assert!(book.bids().best().is_none());
+                assert!(book.asks().best().is_none());
+            }
+            other => panic!("Expected empty snapshot, got {other:?}"),
+        }
+    }
+
+    mod snapshot_fetcher {
+        use super::*;
+        use crate::{
+            exchange::kalshi::transformer::KalshiOrderBookTransformer, subscription::Map,
+        };
+        use barter_integration::{
+            protocol::websocket::{WebSocketParser, WsMessage},
+            stream::ExchangeStream,
+        };
+        use rsa::pkcs8::{EncodePrivateKey, LineEnding};
+        use rust_decimal_macros::dec;
+        use std::sync::{Arc, Mutex};
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        /// Requests received by [`mock_rest_server`]: (path, signed).
+        type Requests = Arc<Mutex<Vec<(String, bool)>>>;
+
+        fn credentials() -> KalshiCredentials {
+            let key = rsa::RsaPrivateKey::new(&mut rsa::rand_core::OsRng, 1024).unwrap();
+            let pem = key.to_pkcs8_pem(LineEnding::LF).unwrap();
+            KalshiCredentials::from_pem("test-key", &pem).unwrap()
+        }
+
+        /// Serve `GET /trade-api/v2/markets/{ticker}/orderbook` for `KXTEST` (and 404
+        /// otherwise), returning the REST base URL and the requests received.
+        async fn mock_rest_server() -> (String, Requests) {
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let rest_url = format!("http://{}/trade-api/v2", listener.local_addr().unwrap());
+            let requests = Requests::default();
+
+            let received = Arc::clone(&requests);
+            tokio::spawn(async move {
+                loop {
+                    let (mut socket, _) = listener.accept().await.unwrap();
+                    let mut buffer = vec![0; 4096];
+                    let mut read = 0;
+                    while !buffer[..read].windows(4).any(|window| window == b"\r\n\r\n") {
+                        read += socket.read(&mut buffer[read..]).await.unwrap();
+                    }
+
+                    let request = String::from_utf8_lossy(&buffer[..read]);
+                    let path = request.split_whitespace().nth(1).unwrap().to_string();
+                    let headers = request.to_lowercase();
+                    let signed = headers.contains("kalshi-access-key: test-key")
+                        && headers.contains("kalshi-access-signature: ")
+                        && headers.contains("kalshi-access-timestamp: ");
+                    received.lock().unwrap().push((path.clone(), signed));
+
+                    let (status, body) = if path == "/trade-api/v2/markets/KXTEST/orderbook" {
+                        ("200 OK", r#"{"orderbook": {"yes": [[40, 100]], "no": [[55, 150]]}}"#)
+                    } else {
+                        ("404 Not Found", r#"{"error": {"code": "not_found"}}"#)
+                    };
+                    let response = format!(
+                        "HTTP/1.1 {status}\r\ncontent-type: application/json\r\n\
+                         content-length: {}\r\nconnection: close\r\n\r\n{body}",
+                        body.len()
+                    );
+                    socket.write_all(response.as_bytes()).await.unwrap();
+                }
+            });
+
+            (rest_url, requests)
+        }
+
+        #[test]
+        fn test_de_orderbook_response_null_sides() {
+            let response: KalshiOrderbookResponse =
+                serde_json::from_str(r#"{"orderbook": {"yes": [[40, 100]], "no": null}}"#)
+                    .unwrap();
+            assert_eq!(response.orderbook.yes, Some(vec![(40, 100)]));
+            assert_eq!(response.orderbook.no, None);
+        }
+
+        #[tokio::test]
+        async fn test_fetch_orderbook_snapshots() {
+            let (rest_url, requests) = mock_rest_server().await;
+            let markets = vec![
+                ("yes", KalshiMarket::new("kxtest")),
+                ("no", KalshiMarket::with_outcome("kxtest", Outcome::No)),
+                ("missing", KalshiMarket::new("kxmissing")),
+            ];
+
+            let events = fetch_orderbook_snapshots(&rest_url, &credentials(), markets).await;
+
+            // Failed markets are skipped, and each outcome gets its own perspective
+            assert_eq!(events.len(), 2);
+            let book = |index: usize| match &events[index] {
+                MarketEvent { kind: OrderBookEvent::Snapshot(book), .. } => book,
+                other => panic!("Expected snapshot, got {other:?}"),
+            };
+            assert_eq!(events[0].instrument, "yes");
+            assert_eq!(book(0).bids().best().unwrap().price, dec!(0.40));
+            assert_eq!(book(0).asks().best().unwrap().price, dec!(0.45));
+            assert_eq!(events[1].instrument, "no");
+            assert_eq!(book(1).bids().best().unwrap().price, dec!(0.55));
+            assert_eq!(book(1).asks().best().unwrap().price, dec!(0.60));
+
+            // One signed request per market ticker, shared across outcomes
+            let mut requests = requests.lock().unwrap().clone();
+            requests.sort();
+            assert_eq!(
+                requests,
+                vec![
+                    ("/trade-api/v2/markets/KXMISSING/orderbook".to_string(), true),
+                    ("/trade-api/v2/markets/KXTEST/orderbook".to_string(), true),
+                ]
+            );
+        }
+
+        #[tokio::test]
+        async fn test_rest_snapshot_emitted_before_ws_events() {
+            let (rest_url, _) = mock_rest_server().await;
+            let markets = vec![("kxtest", KalshiMarket::new("kxtest"))];
+            let snapshots = fetch_orderbook_snapshots(&rest_url, &credentials(), markets).await;
+
+            let ws_messages = [
+                r#"{"type": "orderbook_snapshot", "sid": 1, "seq": 1,
+                    "msg": {"market_ticker": "KXTEST", "yes": [[41, 10]], "no": [[55, 150]]}}"#,
+                r#"{"type": "orderbook_delta", "sid": 1, "seq": 2,
+                    "msg": {"market_ticker": "KXTEST", "price": 42, "delta": 5, "side": "yes"}}"#,
+            ]
+            .map(WsMessage::text);
+
+            let transformer = KalshiOrderBookTransformer::new(Map::from_iter([(
+                SubscriptionId::from("orderbook_delta|kxtest"),
+                "kxtest",
+            )]));
+            let stream = ExchangeStream::<WebSocketParser, _, _>::new(
+                futures::stream::iter(ws_messages).map(Ok),
+                transformer,
+                snapshots.into_iter().map(Ok).collect(),
+            );
+            let events = stream
+                .map(|event| event.unwrap().kind)
+                .collect::<Vec<_>>()
+                .await;
+
+            let best_bid = |event: &OrderBookEvent| match event {
+                OrderBookEvent::Snapshot(book) | OrderBookEvent::Update(book) => {
+                    book.bids().best().unwrap().price
+                }
+            };
+            assert_eq!(events.len(), 3);
+            assert!(matches!(events[0], OrderBookEvent::Snapshot(_)));
+            assert_eq!(best_bid(&events[0]), dec!(0.40));
+            assert!(matches!(events[1], OrderBookEvent::Snapshot(_)));
+            assert_eq!(best_bid(&events[1]), dec!(0.41));
+            assert!(matches!(events[2], OrderBookEvent::Update(_)));
+            assert_eq!(best_bid(&events[2]), dec!(0.42));
+        }
+    }
 }
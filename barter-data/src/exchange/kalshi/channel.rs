@@ -1,7 +1,12 @@
 use super::Kalshi;
 use crate::{
     Identifier,
-    subscription::{Subscription, book::OrderBooksL2, trade::PublicTrades},
+    subscription::{
+        Subscription,
+        book::{OrderBooksL1, OrderBooksL2},
+        status::MarketStatuses,
+        trade::PublicTrades,
+    },
 };
 use serde::Serialize;
 
@@ -23,6 +28,11 @@ impl KalshiChannel {
     /// See docs: <https://trading-api.readme.io/reference/websocket-public-channels>
     pub const TRADES: Self = Self("trade");
 
+    /// [`Kalshi`] ticker channel - lightweight best bid/ask and last price updates.
+    ///
+    /// See docs: <https://trading-api.readme.io/reference/websocket-public-channels>
+    pub const TICKER_V2: Self = Self("ticker_v2");
+
     /// [`Kalshi`] market lifecycle channel - detect when markets close/settle/determine.
     ///
     /// See docs: <https://trading-api.readme.io/reference/websocket-public-channels>
@@ -41,6 +51,18 @@ impl<Instrument> Identifier<KalshiChannel> for Subscription<Kalshi, Instrument,
     }
 }
 
+impl<Instrument> Identifier<KalshiChannel> for Subscription<Kalshi, Instrument, OrderBooksL1> {
+    fn id(&self) -> KalshiChannel {
+        KalshiChannel::TICKER_V2
+    }
+}
+
+impl<Instrument> Identifier<KalshiChannel> for Subscription<Kalshi, Instrument, MarketStatuses> {
+    fn id(&self) -> KalshiChannel {
+        KalshiChannel::MARKET_LIFECYCLE
+    }
+}
+
 impl AsRef<str> for KalshiChannel {
     fn as_ref(&self) -> &str {
         self.0
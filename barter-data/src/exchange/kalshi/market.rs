@@ -1,31 +1,71 @@
 use super::Kalshi;
 use crate::{Identifier, instrument::MarketInstrumentData, subscription::Subscription};
 use barter_instrument::{
-    Keyed, instrument::market_data::MarketDataInstrument,
+    Keyed,
+    instrument::market_data::{
+        MarketDataInstrument,
+        kind::{MarketDataInstrumentKind, Outcome},
+    },
 };
 use serde::{Deserialize, Serialize};
-use smol_str::SmolStr;
+use smol_str::{SmolStr, format_smolstr};
 
 /// Type that defines how to translate a Barter [`Subscription`] into a
 /// [`Kalshi`] market that can be subscribed to.
 ///
-/// For Kalshi, this is the market ticker (e.g., "KXBTC-25JAN31-T100000").
+/// For Kalshi, this is the market ticker (e.g., "KXBTC-25JAN31-T100000"). Both
+/// outcomes trade on one ticker, so instruments declared with [`Outcome::No`] are
+/// identified as `{ticker}_no` to keep their subscriptions distinct from YES.
 ///
 /// See docs: <https://trading-api.readme.io/reference/getmarket>
 #[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Deserialize, Serialize)]
 pub struct KalshiMarket(pub SmolStr);
 
 impl KalshiMarket {
+    /// Suffix identifying a NO outcome subscription.
+    const NO_SUFFIX: &'static str = "_no";
+
     /// Create a new [`KalshiMarket`] from a ticker string.
     pub fn new<S: Into<SmolStr>>(ticker: S) -> Self {
         Self(ticker.into())
     }
+
+    /// Create the [`KalshiMarket`] for one `outcome` of `ticker`.
+    pub fn with_outcome(ticker: &str, outcome: Outcome) -> Self {
+        match outcome {
+            Outcome::Yes => Self::new(ticker),
+            Outcome::No => Self(format_smolstr!("{ticker}{}", Self::NO_SUFFIX)),
+        }
+    }
+
+    /// Market ticker sent to Kalshi, without any outcome suffix.
+    pub fn ticker(&self) -> &str {
+        self.0.strip_suffix(Self::NO_SUFFIX).unwrap_or(&self.0)
+    }
+
+    /// Outcome whose orderbook this market streams.
+    pub fn outcome(&self) -> Outcome {
+        if self.0.ends_with(Self::NO_SUFFIX) {
+            Outcome::No
+        } else {
+            Outcome::Yes
+        }
+    }
+}
+
+/// Subscribed outcome of a [`MarketDataInstrumentKind`], YES unless it's a NO
+/// prediction contract.
+fn outcome(kind: &MarketDataInstrumentKind) -> Outcome {
+    match kind {
+        MarketDataInstrumentKind::Prediction(contract) => contract.outcome,
+        _ => Outcome::Yes,
+    }
 }
 
 impl<Kind> Identifier<KalshiMarket> for Subscription<Kalshi, MarketDataInstrument, Kind> {
     fn id(&self) -> KalshiMarket {
         // For prediction markets, the "base" field holds the market ticker
-        KalshiMarket(self.instrument.base.name().clone())
+        KalshiMarket::with_outcome(self.instrument.base.name(), outcome(&self.instrument.kind))
     }
 }
 
@@ -34,7 +74,10 @@ impl<InstrumentKey, Kind> Identifier<KalshiMarket>
 {
     fn id(&self) -> KalshiMarket {
         // For prediction markets, the "base" field holds the market ticker
-        KalshiMarket(self.instrument.value.base.name().clone())
+        KalshiMarket::with_outcome(
+            self.instrument.value.base.name(),
+            outcome(&self.instrument.value.kind),
+        )
     }
 }
 
@@ -42,7 +85,10 @@ impl<InstrumentKey, Kind> Identifier<KalshiMarket>
     for Subscription<Kalshi, MarketInstrumentData<InstrumentKey>, Kind>
 {
     fn id(&self) -> KalshiMarket {
-        KalshiMarket(self.instrument.name_exchange.name().clone())
+        KalshiMarket::with_outcome(
+            self.instrument.name_exchange.name(),
+            outcome(&self.instrument.kind),
+        )
     }
 }
 
@@ -51,3 +97,21 @@ impl AsRef<str> for KalshiMarket {
         &self.0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kalshi_market_outcome() {
+        let yes = KalshiMarket::with_outcome("kxbtc-25jan31", Outcome::Yes);
+        assert_eq!(yes.as_ref(), "kxbtc-25jan31");
+        assert_eq!(yes.ticker(), "kxbtc-25jan31");
+        assert_eq!(yes.outcome(), Outcome::Yes);
+
+        let no = KalshiMarket::with_outcome("kxbtc-25jan31", Outcome::No);
+        assert_eq!(no.as_ref(), "kxbtc-25jan31_no");
+        assert_eq!(no.ticker(), "kxbtc-25jan31");
+        assert_eq!(no.outcome(), Outcome::No);
+    }
+}
@@ -1,4 +1,5 @@
-use crate::Identifier;
+use crate::{Identifier, subscription::status::MarketStatus};
+use barter_instrument::kalshi::price;
 use barter_integration::subscription::SubscriptionId;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
@@ -21,6 +22,9 @@ pub enum KalshiMessage<T> {
     OrderbookDelta(KalshiOrderbookDelta),
     /// Trade execution message
     Trade(KalshiTrade),
+    /// Top-of-book ticker update
+    #[serde(rename = "ticker_v2")]
+    TickerV2(KalshiTicker),
     /// Market lifecycle event
     #[serde(rename = "market_lifecycle_v2")]
     MarketLifecycle(KalshiMarketLifecycle),
@@ -38,6 +42,7 @@ where
             Self::OrderbookSnapshot(snapshot) => snapshot.id(),
             Self::OrderbookDelta(delta) => delta.id(),
             Self::Trade(trade) => trade.id(),
+            Self::TickerV2(ticker) => ticker.id(),
             Self::MarketLifecycle(lifecycle) => lifecycle.id(),
             Self::Data(data) => data.id(),
         }
@@ -72,11 +77,20 @@ impl Identifier<Option<SubscriptionId>> for KalshiTrade {
     }
 }
 
+impl Identifier<Option<SubscriptionId>> for KalshiTicker {
+    fn id(&self) -> Option<SubscriptionId> {
+        Some(SubscriptionId(format_smolstr!(
+            "ticker_v2|{}",
+            self.msg.market_ticker.to_lowercase()
+        )))
+    }
+}
+
 impl Identifier<Option<SubscriptionId>> for KalshiMarketLifecycle {
     fn id(&self) -> Option<SubscriptionId> {
         // Route lifecycle events to the orderbook_delta subscription ID so the
         // transformer can find the instrument and emit an empty-book snapshot,
-        // effectively clearing the orderbook when a market closes/settles.
+        // effectively clearing the orderbook when a market settles.
         Some(SubscriptionId(format_smolstr!(
             "orderbook_delta|{}",
             self.msg.market_ticker.to_lowercase()
@@ -118,6 +132,44 @@ pub struct KalshiOrderbookSnapshotData {
     /// NO side orderbook levels: (price_cents, quantity)
     #[serde(default)]
     pub no: Vec<(u32, u32)>,
+    /// YES side levels priced in dollars: (price, quantity), sent by markets quoting
+    /// sub-cent prices, whose `yes` levels are rounded to whole cents
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub yes_dollars: Vec<(Decimal, u32)>,
+    /// NO side levels priced in dollars, see `yes_dollars`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub no_dollars: Vec<(Decimal, u32)>,
+    /// Lifecycle event type, present when a `market_lifecycle_v2` message is
+    /// decoded as a snapshot by the stateless transformer.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub event_type: Option<String>,
+}
+
+impl KalshiOrderbookSnapshotData {
+    /// YES side levels: (price_ticks, quantity), see [`price::TICKS_PER_DOLLAR`].
+    pub fn yes_ticks(&self) -> Vec<(u32, u32)> {
+        levels_in_ticks(&self.yes, &self.yes_dollars)
+    }
+
+    /// NO side levels: (price_ticks, quantity), see [`price::TICKS_PER_DOLLAR`].
+    pub fn no_ticks(&self) -> Vec<(u32, u32)> {
+        levels_in_ticks(&self.no, &self.no_dollars)
+    }
+}
+
+/// Convert levels to ticks, preferring dollar prices (when sent) over whole cents.
+fn levels_in_ticks(cents: &[(u32, u32)], dollars: &[(Decimal, u32)]) -> Vec<(u32, u32)> {
+    if dollars.is_empty() {
+        cents
+            .iter()
+            .map(|&(price, amount)| (price * price::TICKS_PER_CENT, amount))
+            .collect()
+    } else {
+        dollars
+            .iter()
+            .map(|&(price, amount)| (price::to_ticks(price), amount))
+            .collect()
+    }
 }
 
 impl KalshiOrderbookSnapshot {
@@ -125,6 +177,19 @@ impl KalshiOrderbookSnapshot {
     pub fn market_ticker(&self) -> &str {
         &self.msg.market_ticker
     }
+
+    /// Lifecycle event carried by this message, if it is a decoded
+    /// `market_lifecycle_v2` message rather than a real snapshot.
+    pub fn lifecycle(&self) -> Option<KalshiMarketLifecycle> {
+        self.msg.event_type.as_ref().map(|event_type| KalshiMarketLifecycle {
+            sid: self.sid,
+            seq: self.seq,
+            msg: KalshiMarketLifecycleData {
+                market_ticker: self.msg.market_ticker.clone(),
+                event_type: event_type.clone(),
+            },
+        })
+    }
 }
 
 /// Kalshi orderbook delta wrapper.
@@ -157,6 +222,10 @@ pub struct KalshiOrderbookDeltaData {
     pub market_ticker: String,
     /// Price level in cents (1-99)
     pub price: u32,
+    /// Price level in dollars, sent by markets quoting sub-cent prices, whose `price` is
+    /// rounded to whole cents
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub price_dollars: Option<Decimal>,
     /// Quantity change (positive = add, negative = remove)
     pub delta: i32,
     /// Side: "yes" or "no"
@@ -170,6 +239,15 @@ impl KalshiOrderbookDelta {
     }
 }
 
+impl KalshiOrderbookDeltaData {
+    /// Price level in ticks, see [`price::TICKS_PER_DOLLAR`].
+    pub fn price_ticks(&self) -> u32 {
+        self.price_dollars
+            .map(price::to_ticks)
+            .unwrap_or(self.price * price::TICKS_PER_CENT)
+    }
+}
+
 /// Kalshi trade wrapper.
 ///
 /// ### Raw Payload
@@ -209,6 +287,60 @@ pub struct KalshiTradeData {
     pub taker_side: String,
 }
 
+/// Kalshi `ticker_v2` wrapper.
+///
+/// Only fields that changed since the previous update are sent, so every field
+/// other than the market ticker is optional.
+///
+/// ### Raw Payload
+/// ```json
+/// {
+///   "type": "ticker_v2",
+///   "sid": 2,
+///   "seq": 5,
+///   "msg": {
+///     "market_ticker": "KXBTC-25JAN31-T100000",
+///     "price": 48,
+///     "yes_bid": 47,
+///     "yes_ask": 49,
+///     "volume": 2410,
+///     "open_interest": 1280,
+///     "ts": 1738281600
+///   }
+/// }
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct KalshiTicker {
+    pub sid: u64,
+    pub seq: u64,
+    pub msg: KalshiTickerData,
+}
+
+/// Inner data for `ticker_v2` message.
+#[derive(Clone, PartialEq, Eq, Debug, Default, Deserialize, Serialize)]
+pub struct KalshiTickerData {
+    /// Market ticker identifier
+    pub market_ticker: String,
+    /// Last traded YES price in cents
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub price: Option<u32>,
+    /// Best YES bid in cents
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub yes_bid: Option<u32>,
+    /// Best YES ask in cents
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub yes_ask: Option<u32>,
+    /// Number of contracts traded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub volume: Option<i64>,
+    /// Number of contracts outstanding
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub open_interest: Option<i64>,
+    /// Unix timestamp (seconds) of the update
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ts: Option<i64>,
+}
+
 /// Kalshi market lifecycle event wrapper.
 ///
 /// ### Raw Payload
@@ -235,10 +367,33 @@ pub struct KalshiMarketLifecycle {
 pub struct KalshiMarketLifecycleData {
     /// Market ticker identifier
     pub market_ticker: String,
-    /// Event type: "closed", "settled", "determined", "deactivated"
+    /// Event type: "created", "activated", "closed", "deactivated", "determined", "settled"
     pub event_type: String,
 }
 
+impl KalshiMarketLifecycleData {
+    /// [`MarketStatus`] this event moves the market to, or `None` for events that
+    /// don't change it (eg/ `"close_date_updated"`).
+    pub fn status(&self) -> Option<MarketStatus> {
+        match self.event_type.as_str() {
+            "created" | "activated" => Some(MarketStatus::Open),
+            "closed" => Some(MarketStatus::Closed),
+            "deactivated" => Some(MarketStatus::Deactivated),
+            "determined" => Some(MarketStatus::Determined),
+            "settled" => Some(MarketStatus::Settled),
+            _ => None,
+        }
+    }
+
+    /// Whether the market can never trade again.
+    ///
+    /// `"settled"` and `"determined"` are final. Other events, such as `"closed"`
+    /// or `"deactivated"`, may be followed by the market reopening.
+    pub fn is_terminal(&self) -> bool {
+        self.status().is_some_and(|status| status.is_terminal())
+    }
+}
+
 /// Kalshi generic error message.
 #[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Deserialize, Serialize)]
 pub struct KalshiError {
@@ -246,19 +401,19 @@ pub struct KalshiError {
     pub message: String,
 }
 
-/// Level in a Kalshi orderbook (price in cents, quantity in contracts).
+/// Level in a Kalshi orderbook (price in ticks, quantity in contracts).
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Ord, PartialOrd, Hash, Default, Deserialize, Serialize)]
 pub struct KalshiLevel {
-    /// Price in cents (1-99)
+    /// Price in ticks (100-9900 for whole cents), see [`price::TICKS_PER_DOLLAR`]
     pub price: u32,
     /// Quantity in contracts
     pub amount: u32,
 }
 
 impl KalshiLevel {
-    /// Convert price from cents (1-99) to decimal (0.01-0.99).
+    /// Convert price from ticks to decimal (0.0001-0.9999).
     pub fn price_decimal(&self) -> Decimal {
-        Decimal::from(self.price) / Decimal::from(100)
+        price::from_ticks(self.price)
     }
 
     /// Convert amount to decimal.
@@ -363,6 +518,34 @@ mod tests {
             }
         }
 
+        #[test]
+        fn test_kalshi_orderbook_subcent_levels() {
+            let snapshot: KalshiMessage<()> = serde_json::from_str(
+                r#"{"type": "orderbook_snapshot", "sid": 1, "seq": 1,
+                    "msg": {"market_ticker": "KXTEST", "yes": [[51, 100]], "no": [[49, 150]],
+                            "yes_dollars": [["0.5050", 100]], "no_dollars": [["0.4900", 150]]}}"#,
+            )
+            .unwrap();
+            match snapshot {
+                KalshiMessage::OrderbookSnapshot(snapshot) => {
+                    assert_eq!(snapshot.msg.yes_ticks(), vec![(5050, 100)]);
+                    assert_eq!(snapshot.msg.no_ticks(), vec![(4900, 150)]);
+                }
+                _ => panic!("Expected OrderbookSnapshot"),
+            }
+
+            let delta: KalshiMessage<()> = serde_json::from_str(
+                r#"{"type": "orderbook_delta", "sid": 1, "seq": 2,
+                    "msg": {"market_ticker": "KXTEST", "price": 51, "price_dollars": "0.5050",
+                            "delta": 5, "side": "yes"}}"#,
+            )
+            .unwrap();
+            match delta {
+                KalshiMessage::OrderbookDelta(delta) => assert_eq!(delta.msg.price_ticks(), 5050),
+                _ => panic!("Expected OrderbookDelta"),
+            }
+        }
+
         #[test]
         fn test_kalshi_trade() {
             let input = r#"
@@ -393,6 +576,65 @@ mod tests {
             }
         }
 
+        #[test]
+        fn test_kalshi_ticker_v2() {
+            let input = r#"
+            {
+                "type": "ticker_v2",
+                "sid": 2,
+                "seq": 5,
+                "msg": {
+                    "market_ticker": "KXBTC-25JAN31-T100000",
+                    "price": 48,
+                    "yes_bid": 47,
+                    "yes_ask": 49,
+                    "volume": 2410,
+                    "open_interest": 1280,
+                    "ts": 1738281600
+                }
+            }
+            "#;
+
+            let msg: KalshiMessage<()> = serde_json::from_str(input).unwrap();
+            match msg {
+                KalshiMessage::TickerV2(ticker) => {
+                    assert_eq!(
+                        ticker.id(),
+                        Some(SubscriptionId::from("ticker_v2|kxbtc-25jan31-t100000"))
+                    );
+                    assert_eq!(ticker.msg.price, Some(48));
+                    assert_eq!(ticker.msg.yes_bid, Some(47));
+                    assert_eq!(ticker.msg.yes_ask, Some(49));
+                    assert_eq!(ticker.msg.volume, Some(2410));
+                    assert_eq!(ticker.msg.open_interest, Some(1280));
+                    assert_eq!(ticker.msg.ts, Some(1738281600));
+                }
+                _ => panic!("Expected TickerV2"),
+            }
+        }
+
+        #[test]
+        fn test_kalshi_ticker_v2_partial() {
+            let input = r#"
+            {
+                "type": "ticker_v2",
+                "sid": 2,
+                "seq": 6,
+                "msg": {"market_ticker": "KXBTC-25JAN31-T100000", "yes_bid": 46}
+            }
+            "#;
+
+            let msg: KalshiMessage<()> = serde_json::from_str(input).unwrap();
+            match msg {
+                KalshiMessage::TickerV2(ticker) => {
+                    assert_eq!(ticker.msg.yes_bid, Some(46));
+                    assert_eq!(ticker.msg.yes_ask, None);
+                    assert_eq!(ticker.msg.price, None);
+                }
+                _ => panic!("Expected TickerV2"),
+            }
+        }
+
         #[test]
         fn test_kalshi_market_lifecycle() {
             let input = r#"
@@ -412,9 +654,61 @@ mod tests {
                 KalshiMessage::MarketLifecycle(lifecycle) => {
                     assert_eq!(lifecycle.msg.market_ticker, "KXBTC-25JAN31-T100000");
                     assert_eq!(lifecycle.msg.event_type, "settled");
+                    assert!(lifecycle.msg.is_terminal());
                 }
                 _ => panic!("Expected MarketLifecycle"),
             }
         }
+
+        #[test]
+        fn test_kalshi_market_lifecycle_status() {
+            struct TestCase {
+                event_type: &'static str,
+                expected: Option<MarketStatus>,
+            }
+
+            let tests = vec![
+                TestCase { event_type: "created", expected: Some(MarketStatus::Open) },
+                TestCase { event_type: "activated", expected: Some(MarketStatus::Open) },
+                TestCase { event_type: "closed", expected: Some(MarketStatus::Closed) },
+                TestCase {
+                    event_type: "deactivated",
+                    expected: Some(MarketStatus::Deactivated),
+                },
+                TestCase {
+                    event_type: "determined",
+                    expected: Some(MarketStatus::Determined),
+                },
+                TestCase { event_type: "settled", expected: Some(MarketStatus::Settled) },
+                TestCase { event_type: "close_date_updated", expected: None },
+            ];
+
+            for (index, test) in tests.into_iter().enumerate() {
+                let input = serde_json::json!({
+                    "type": "market_lifecycle_v2",
+                    "sid": 1,
+                    "seq": 4,
+                    "msg": { "market_ticker": "KXTEST", "event_type": test.event_type }
+                });
+                let msg: KalshiMessage<()> = serde_json::from_value(input).unwrap();
+                let KalshiMessage::MarketLifecycle(lifecycle) = msg else {
+                    panic!("TC{index} expected MarketLifecycle");
+                };
+                assert_eq!(lifecycle.msg.status(), test.expected, "TC{index} failed");
+            }
+        }
+
+        #[test]
+        fn test_kalshi_market_lifecycle_is_terminal() {
+            let data = |event_type: &str| KalshiMarketLifecycleData {
+                market_ticker: "KXTEST".to_string(),
+                event_type: event_type.to_string(),
+            };
+
+            assert!(data("settled").is_terminal());
+            assert!(data("determined").is_terminal());
+            assert!(!data("closed").is_terminal());
+            assert!(!data("deactivated").is_terminal());
+        }
     }
 }
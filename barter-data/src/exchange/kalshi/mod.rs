@@ -1,23 +1,39 @@
 use self::{
+    book::l2::KalshiOrderBooksL2SnapshotFetcher,
     channel::KalshiChannel,
     market::KalshiMarket,
-    message::{KalshiOrderbookSnapshot, KalshiTrade},
     subscriber::KalshiAuthenticatedSubscriber,
     subscription::KalshiSubResponse,
+    transformer::{
+        KalshiMarketStatusTransformer, KalshiOrderBookTransformer, KalshiTickerTransformer,
+        KalshiTradesTransformer,
+    },
 };
 use crate::{
     ExchangeWsStream, NoInitialSnapshots,
     exchange::{Connector, ExchangeSub, StreamSelector},
     instrument::InstrumentData,
     subscriber::validator::WebSocketSubValidator,
-    subscription::{book::OrderBooksL2, trade::PublicTrades},
-    transformer::stateless::StatelessTransformer,
+    subscription::{
+        book::{OrderBooksL1, OrderBooksL2},
+        status::MarketStatuses,
+        trade::PublicTrades,
+    },
 };
 use barter_instrument::exchange::ExchangeId;
 use barter_integration::{error::SocketError, protocol::websocket::WsMessage};
 use barter_macro::{DeExchange, SerExchange};
 use derive_more::Display;
+use fnv::FnvHashSet;
 use serde_json::json;
+use std::{
+    sync::{
+        LazyLock,
+        atomic::{AtomicU8, AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+use tokio::sync::watch;
 use url::Url;
 
 /// Authentication for Kalshi WebSocket connections.
@@ -29,6 +45,9 @@ pub mod book;
 /// Public trade types for [`Kalshi`].
 pub mod trade;
 
+/// Top-of-book ticker types for [`Kalshi`].
+pub mod ticker;
+
 /// Defines the type that translates a Barter [`Subscription`](crate::subscription::Subscription)
 /// into an exchange [`Connector`] specific channel used for generating [`Connector::requests`].
 pub mod channel;
@@ -43,10 +62,18 @@ pub mod message;
 /// Authenticated WebSocket subscriber for Kalshi.
 pub mod subscriber;
 
+/// REST market lifecycle status types for [`Kalshi`], used to filter out markets that
+/// aren't tradable.
+pub mod status;
+
 /// [`Subscription`](crate::subscription::Subscription) response type and response
 /// [`Validator`](barter_integration) for [`Kalshi`].
 pub mod subscription;
 
+/// [`ExchangeTransformer`](crate::transformer::ExchangeTransformer)s for Kalshi
+/// orderbooks, applying deltas, tickers and public trades.
+pub mod transformer;
+
 /// [`Kalshi`] WebSocket base URL.
 ///
 /// See docs: <https://trading-api.readme.io/reference/websocket-overview>
@@ -55,6 +82,72 @@ pub const BASE_URL_KALSHI: &str = "wss://api.elections.kalshi.com/trade-api/ws/v
 /// [`Kalshi`] demo/sandbox WebSocket base URL.
 pub const BASE_URL_KALSHI_DEMO: &str = "wss://demo-api.kalshi.co/trade-api/ws/v2";
 
+/// [`Kalshi`] production REST API base URL.
+///
+/// See docs: <https://trading-api.readme.io/reference/getmarketorderbook>
+pub const HTTP_BASE_URL_KALSHI: &str = "https://api.elections.kalshi.com/trade-api/v2";
+
+/// [`Kalshi`] demo/sandbox REST API base URL.
+pub const HTTP_BASE_URL_KALSHI_DEMO: &str = "https://demo-api.kalshi.co/trade-api/v2";
+
+/// [`Kalshi`] WebSocket server that market data subscriptions connect to.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub enum KalshiServer {
+    /// Production server ([`BASE_URL_KALSHI`])
+    #[default]
+    Production,
+    /// Demo/sandbox server ([`BASE_URL_KALSHI_DEMO`])
+    Demo,
+}
+
+impl KalshiServer {
+    /// WebSocket base URL of this server.
+    pub fn websocket_url(&self) -> &'static str {
+        match self {
+            Self::Production => BASE_URL_KALSHI,
+            Self::Demo => BASE_URL_KALSHI_DEMO,
+        }
+    }
+
+    /// REST API base URL of this server.
+    pub fn rest_url(&self) -> &'static str {
+        match self {
+            Self::Production => HTTP_BASE_URL_KALSHI,
+            Self::Demo => HTTP_BASE_URL_KALSHI_DEMO,
+        }
+    }
+
+    /// Select the server from the same `KALSHI_DEMO` (or `KALSHI_USE_DEMO`) flag
+    /// used by the Kalshi execution client.
+    pub fn from_env() -> Self {
+        let demo = std::env::var("KALSHI_DEMO")
+            .or_else(|_| std::env::var("KALSHI_USE_DEMO"))
+            .is_ok_and(|value| value == "true");
+
+        if demo { Self::Demo } else { Self::Production }
+    }
+}
+
+/// [`Kalshi`] market stream idle timeout, covering several of the pings Kalshi sends every 10s.
+pub const KALSHI_IDLE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Default maximum number of market tickers in one [`Kalshi`] subscribe command.
+pub const DEFAULT_SUBSCRIPTION_BATCH_SIZE: usize = 250;
+
+/// Process-wide batch size set by [`Kalshi::set_subscription_batch_size`].
+///
+/// 0 = unset (use [`DEFAULT_SUBSCRIPTION_BATCH_SIZE`]).
+static KALSHI_SUBSCRIPTION_BATCH_SIZE: AtomicUsize = AtomicUsize::new(0);
+
+/// Process-wide [`KalshiServer`] override set by [`Kalshi::set_server`].
+///
+/// 0 = unset (use [`KalshiServer::from_env`]), 1 = production, 2 = demo.
+static KALSHI_SERVER: AtomicU8 = AtomicU8::new(0);
+
+/// Process-wide uppercase market tickers dropped by [`Kalshi::unsubscribe_markets`].
+static KALSHI_UNSUBSCRIBED: LazyLock<watch::Sender<FnvHashSet<String>>> =
+    LazyLock::new(|| watch::Sender::new(FnvHashSet::default()));
+
 /// [`Kalshi`] prediction market exchange.
 ///
 /// Kalshi is a CFTC-regulated prediction market offering binary event contracts.
@@ -76,6 +169,136 @@ pub const BASE_URL_KALSHI_DEMO: &str = "wss://demo-api.kalshi.co/trade-api/ws/v2
 )]
 pub struct Kalshi;
 
+impl Kalshi {
+    /// Select the server all subsequent [`Kalshi`] connections use, including
+    /// reconnects of existing streams.
+    ///
+    /// [`Connector::url`] has no instance to carry this, so the selection is
+    /// process-wide. Defaults to [`KalshiServer::from_env`] when never set.
+    pub fn set_server(server: KalshiServer) {
+        let value = match server {
+            KalshiServer::Production => 1,
+            KalshiServer::Demo => 2,
+        };
+        KALSHI_SERVER.store(value, Ordering::Relaxed);
+    }
+
+    /// Server that [`Kalshi`] connections currently use.
+    pub fn server() -> KalshiServer {
+        match KALSHI_SERVER.load(Ordering::Relaxed) {
+            1 => KalshiServer::Production,
+            2 => KalshiServer::Demo,
+            _ => KalshiServer::from_env(),
+        }
+    }
+
+    /// Set the maximum number of market tickers sent in one subscribe command.
+    ///
+    /// Larger subscriptions are split into several commands per channel. Like
+    /// [`Self::set_server`], the setting is process-wide.
+    pub fn set_subscription_batch_size(batch_size: usize) {
+        KALSHI_SUBSCRIPTION_BATCH_SIZE.store(batch_size.max(1), Ordering::Relaxed);
+    }
+
+    /// Maximum number of market tickers sent in one subscribe command.
+    pub fn subscription_batch_size() -> usize {
+        match KALSHI_SUBSCRIPTION_BATCH_SIZE.load(Ordering::Relaxed) {
+            0 => DEFAULT_SUBSCRIPTION_BATCH_SIZE,
+            batch_size => batch_size,
+        }
+    }
+
+    /// Stop streaming `tickers` (eg/ once their markets settle) on every live
+    /// [`Kalshi`] orderbook and market status stream.
+    ///
+    /// Each stream drops the markets from its subscription map, so later messages
+    /// for them are ignored, and sends Kalshi an `update_subscription` command
+    /// deleting them from its subscriptions on the existing connection. Like
+    /// [`Self::set_server`], this is process-wide, and streams that reconnect
+    /// drop the markets again. Outcome suffixes are ignored, so both outcomes of
+    /// a ticker are dropped together.
+    pub fn unsubscribe_markets<I, S>(tickers: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let tickers = tickers
+            .into_iter()
+            .map(|ticker| KalshiMarket::new(ticker.as_ref()).ticker().to_uppercase())
+            .collect::<Vec<_>>();
+
+        KALSHI_UNSUBSCRIBED.send_if_modified(|unsubscribed| {
+            tickers
+                .into_iter()
+                .fold(false, |modified, ticker| unsubscribed.insert(ticker) | modified)
+        });
+    }
+
+    /// Receiver of the tickers dropped by [`Self::unsubscribe_markets`], which
+    /// sees the current set as changed.
+    pub(crate) fn unsubscribed_markets() -> watch::Receiver<FnvHashSet<String>> {
+        let mut rx = KALSHI_UNSUBSCRIBED.subscribe();
+        rx.mark_changed();
+        rx
+    }
+}
+
+/// Group `(channel, market)` subscriptions into the uppercase market tickers to
+/// subscribe to on each channel.
+///
+/// YES and NO instruments of one ticker share a single subscription, and
+/// tickers on `orderbook_delta` or `ticker_v2` are also subscribed to
+/// `market_lifecycle_v2`, so settlement/close events can clear stale books.
+fn channel_tickers<'a>(
+    subs: impl IntoIterator<Item = (&'a str, &'a str)>,
+) -> Vec<(&'a str, Vec<String>)> {
+    fn insert<'a>(channels: &mut Vec<(&'a str, Vec<String>)>, channel: &'a str, ticker: String) {
+        let index = channels
+            .iter()
+            .position(|(existing, _)| *existing == channel)
+            .unwrap_or_else(|| {
+                channels.push((channel, Vec::new()));
+                channels.len() - 1
+            });
+        let tickers = &mut channels[index].1;
+        if !tickers.contains(&ticker) {
+            tickers.push(ticker);
+        }
+    }
+
+    let mut channels = Vec::new();
+    let mut lifecycle_tickers = Vec::new();
+    for (channel, market) in subs {
+        // Kalshi requires uppercase tickers in subscription requests,
+        // but AssetNameInternal lowercases everything internally
+        let ticker = KalshiMarket::new(market).ticker().to_uppercase();
+        if [KalshiChannel::ORDER_BOOK_DELTA, KalshiChannel::TICKER_V2]
+            .iter()
+            .any(|lifecycle| lifecycle.as_ref() == channel)
+        {
+            lifecycle_tickers.push(ticker.clone());
+        }
+        insert(&mut channels, channel, ticker);
+    }
+    for ticker in lifecycle_tickers {
+        insert(&mut channels, KalshiChannel::MARKET_LIFECYCLE.as_ref(), ticker);
+    }
+
+    channels
+}
+
+/// Split each channel's tickers into subscribe commands of at most `batch_size`.
+fn subscription_batches<'a>(
+    channel_tickers: &'a [(&'a str, Vec<String>)],
+    batch_size: usize,
+) -> impl Iterator<Item = (&'a str, &'a [String])> {
+    channel_tickers.iter().flat_map(move |(channel, tickers)| {
+        tickers
+            .chunks(batch_size.max(1))
+            .map(move |batch| (*channel, batch))
+    })
+}
+
 impl Connector for Kalshi {
     const ID: ExchangeId = ExchangeId::Kalshi;
     type Channel = KalshiChannel;
@@ -85,37 +308,22 @@ impl Connector for Kalshi {
     type SubResponse = KalshiSubResponse;
 
     fn url() -> Result<Url, SocketError> {
-        Url::parse(BASE_URL_KALSHI).map_err(SocketError::UrlParse)
+        Url::parse(Self::server().websocket_url()).map_err(SocketError::UrlParse)
     }
 
-    fn requests(exchange_subs: Vec<ExchangeSub<Self::Channel, Self::Market>>) -> Vec<WsMessage> {
-        // Group subscriptions by channel
-        let mut channels_to_markets: std::collections::HashMap<&str, Vec<String>> =
-            std::collections::HashMap::new();
-
-        for ExchangeSub { channel, market } in &exchange_subs {
-            // Kalshi requires uppercase tickers in subscription requests,
-            // but AssetNameInternal lowercases everything internally
-            channels_to_markets
-                .entry(channel.as_ref())
-                .or_default()
-                .push(market.as_ref().to_uppercase());
-        }
+    fn idle_timeout() -> Option<Duration> {
+        Some(KALSHI_IDLE_TIMEOUT)
+    }
 
-        // Auto-subscribe to market_lifecycle_v2 for tickers on orderbook_delta,
-        // so we receive settlement/close events and can clear stale orderbooks.
-        if let Some(ob_tickers) = channels_to_markets.get(KalshiChannel::ORDER_BOOK_DELTA.as_ref())
-        {
-            let lifecycle_tickers = ob_tickers.clone();
-            channels_to_markets
-                .entry(KalshiChannel::MARKET_LIFECYCLE.as_ref())
-                .or_default()
-                .extend(lifecycle_tickers);
-        }
+    fn requests(exchange_subs: Vec<ExchangeSub<Self::Channel, Self::Market>>) -> Vec<WsMessage> {
+        let channel_tickers = channel_tickers(
+            exchange_subs
+                .iter()
+                .map(|ExchangeSub { channel, market }| (channel.as_ref(), market.0.as_str())),
+        );
 
-        // Create one subscription request per channel with all markets
-        channels_to_markets
-            .into_iter()
+        // One subscribe command per batch of market tickers, each with its own id
+        subscription_batches(&channel_tickers, Self::subscription_batch_size())
             .enumerate()
             .map(|(id, (channel, market_tickers))| {
                 WsMessage::text(
@@ -133,10 +341,14 @@ impl Connector for Kalshi {
             .collect()
     }
 
-    fn expected_responses<InstrumentKey>(_map: &crate::subscription::Map<InstrumentKey>) -> usize {
-        // Kalshi returns one response per channel subscription, not per market
-        // For simplicity, we expect one response per unique channel
-        1
+    fn expected_responses<InstrumentKey>(map: &crate::subscription::Map<InstrumentKey>) -> usize {
+        // Kalshi confirms each subscribe command once, so batch the subscriptions
+        // exactly as Self::requests does
+        let channel_tickers = channel_tickers(map.0.keys().filter_map(|sub_id| {
+            sub_id.0.split_once('|')
+        }));
+
+        subscription_batches(&channel_tickers, Self::subscription_batch_size()).count()
     }
 
     fn subscription_timeout() -> std::time::Duration {
@@ -146,14 +358,20 @@ impl Connector for Kalshi {
     }
 }
 
-impl<Instrument> StreamSelector<Instrument, OrderBooksL2> for Kalshi
+impl<Instrument> StreamSelector<Instrument, OrderBooksL1> for Kalshi
 where
     Instrument: InstrumentData,
 {
     type SnapFetcher = NoInitialSnapshots;
-    type Stream = ExchangeWsStream<
-        StatelessTransformer<Self, Instrument::Key, OrderBooksL2, KalshiOrderbookSnapshot>,
-    >;
+    type Stream = ExchangeWsStream<KalshiTickerTransformer<Instrument::Key>>;
+}
+
+impl<Instrument> StreamSelector<Instrument, OrderBooksL2> for Kalshi
+where
+    Instrument: InstrumentData,
+{
+    type SnapFetcher = KalshiOrderBooksL2SnapshotFetcher;
+    type Stream = ExchangeWsStream<KalshiOrderBookTransformer<Instrument::Key>>;
 }
 
 impl<Instrument> StreamSelector<Instrument, PublicTrades> for Kalshi
@@ -161,19 +379,34 @@ where
     Instrument: InstrumentData,
 {
     type SnapFetcher = NoInitialSnapshots;
-    type Stream = ExchangeWsStream<
-        StatelessTransformer<Self, Instrument::Key, PublicTrades, KalshiTrade>,
-    >;
+    type Stream = ExchangeWsStream<KalshiTradesTransformer<Instrument::Key>>;
+}
+
+impl<Instrument> StreamSelector<Instrument, MarketStatuses> for Kalshi
+where
+    Instrument: InstrumentData,
+{
+    type SnapFetcher = NoInitialSnapshots;
+    type Stream = ExchangeWsStream<KalshiMarketStatusTransformer<Instrument::Key>>;
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    // Single test since the server selection is process-wide
     #[test]
     fn test_kalshi_url() {
-        let url = Kalshi::url().unwrap();
-        assert_eq!(url.as_str(), BASE_URL_KALSHI);
+        Kalshi::set_server(KalshiServer::Production);
+        assert_eq!(Kalshi::url().unwrap().as_str(), BASE_URL_KALSHI);
+
+        Kalshi::set_server(KalshiServer::Demo);
+        assert_eq!(Kalshi::server(), KalshiServer::Demo);
+        assert_eq!(Kalshi::url().unwrap().as_str(), BASE_URL_KALSHI_DEMO);
+        assert_eq!(Kalshi::server().rest_url(), HTTP_BASE_URL_KALSHI_DEMO);
+
+        Kalshi::set_server(KalshiServer::Production);
+        assert_eq!(Kalshi::server().rest_url(), HTTP_BASE_URL_KALSHI);
     }
 
     #[test]
@@ -199,4 +432,156 @@ mod tests {
         // (lifecycle auto-subscribed for orderbook_delta tickers)
         assert_eq!(requests.len(), 3);
     }
+
+    #[test]
+    fn test_kalshi_requests_trades_and_books_on_one_connection() {
+        let subs = vec![
+            ExchangeSub {
+                channel: KalshiChannel::ORDER_BOOK_DELTA,
+                market: KalshiMarket::new("kxbtc-25jan31"),
+            },
+            ExchangeSub {
+                channel: KalshiChannel::TRADES,
+                market: KalshiMarket::new("kxbtc-25jan31"),
+            },
+        ];
+
+        let mut channels: Vec<(String, serde_json::Value)> = Kalshi::requests(subs)
+            .into_iter()
+            .map(|request| {
+                let WsMessage::Text(text) = request else {
+                    panic!("expected text request");
+                };
+                let request: serde_json::Value = serde_json::from_str(text.as_str()).unwrap();
+                let params = &request["params"];
+                (
+                    params["channels"][0].as_str().unwrap().to_string(),
+                    params["market_tickers"].clone(),
+                )
+            })
+            .collect();
+        channels.sort_by(|a, b| a.0.cmp(&b.0));
+
+        assert_eq!(
+            channels,
+            vec![
+                ("market_lifecycle_v2".to_string(), json!(["KXBTC-25JAN31"])),
+                ("orderbook_delta".to_string(), json!(["KXBTC-25JAN31"])),
+                ("trade".to_string(), json!(["KXBTC-25JAN31"])),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_kalshi_requests_ticker_v2_subscribes_lifecycle() {
+        let subs = vec![
+            ExchangeSub {
+                channel: KalshiChannel::TICKER_V2,
+                market: KalshiMarket::new("kxbtc-25jan31"),
+            },
+            ExchangeSub {
+                channel: KalshiChannel::ORDER_BOOK_DELTA,
+                market: KalshiMarket::new("kxbtc-25jan31"),
+            },
+        ];
+
+        let lifecycle: Vec<serde_json::Value> = Kalshi::requests(subs)
+            .into_iter()
+            .map(|request| {
+                let WsMessage::Text(text) = request else {
+                    panic!("expected text request");
+                };
+                serde_json::from_str::<serde_json::Value>(text.as_str()).unwrap()["params"].clone()
+            })
+            .filter(|params| params["channels"][0] == "market_lifecycle_v2")
+            .collect();
+
+        // Tickers on both channels share one lifecycle subscription
+        assert_eq!(lifecycle.len(), 1);
+        assert_eq!(lifecycle[0]["market_tickers"], json!(["KXBTC-25JAN31"]));
+    }
+
+    #[test]
+    fn test_kalshi_requests_share_ticker_across_outcomes() {
+        use barter_instrument::instrument::market_data::kind::Outcome;
+
+        let subs = vec![
+            ExchangeSub {
+                channel: KalshiChannel::ORDER_BOOK_DELTA,
+                market: KalshiMarket::with_outcome("kxbtc-25jan31", Outcome::Yes),
+            },
+            ExchangeSub {
+                channel: KalshiChannel::ORDER_BOOK_DELTA,
+                market: KalshiMarket::with_outcome("kxbtc-25jan31", Outcome::No),
+            },
+        ];
+
+        for request in Kalshi::requests(subs) {
+            let WsMessage::Text(text) = request else {
+                panic!("expected text request");
+            };
+            let request: serde_json::Value = serde_json::from_str(text.as_str()).unwrap();
+            assert_eq!(request["params"]["market_tickers"], json!(["KXBTC-25JAN31"]));
+        }
+    }
+
+    #[test]
+    fn test_kalshi_requests_batched() {
+        use crate::subscription::Map;
+        use barter_integration::subscription::SubscriptionId;
+
+        let tickers = (0..600).map(|i| format!("kxtest-{i}")).collect::<Vec<_>>();
+        let subs = tickers
+            .iter()
+            .map(|ticker| ExchangeSub {
+                channel: KalshiChannel::ORDER_BOOK_DELTA,
+                market: KalshiMarket::new(ticker.as_str()),
+            })
+            .collect();
+
+        let requests = Kalshi::requests(subs)
+            .into_iter()
+            .map(|request| {
+                let WsMessage::Text(text) = request else {
+                    panic!("expected text request");
+                };
+                serde_json::from_str::<serde_json::Value>(text.as_str()).unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        // 3 orderbook_delta batches of at most 250 tickers, plus 3 for lifecycle
+        let batch_sizes = |channel: &str| {
+            requests
+                .iter()
+                .filter(|request| request["params"]["channels"][0] == channel)
+                .map(|request| request["params"]["market_tickers"].as_array().unwrap().len())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(batch_sizes("orderbook_delta"), vec![250, 250, 100]);
+        assert_eq!(batch_sizes("market_lifecycle_v2"), vec![250, 250, 100]);
+
+        let mut ids = requests
+            .iter()
+            .map(|request| request["id"].as_u64().unwrap())
+            .collect::<Vec<_>>();
+        ids.sort_unstable();
+        assert_eq!(ids, (1..=6).collect::<Vec<_>>());
+
+        let map = Map(tickers
+            .iter()
+            .map(|ticker| (SubscriptionId::from(format!("orderbook_delta|{ticker}")), ()))
+            .collect());
+        assert_eq!(Kalshi::expected_responses(&map), 6);
+    }
+
+    #[test]
+    fn test_kalshi_unsubscribe_markets_normalised() {
+        let mut unsubscribed = Kalshi::unsubscribed_markets();
+        assert!(unsubscribed.has_changed().unwrap());
+
+        Kalshi::unsubscribe_markets(["kxunsubtest-26jan01_no", "KXUNSUBTEST-26JAN01"]);
+        let unsubscribed = unsubscribed.borrow_and_update();
+        assert!(unsubscribed.contains("KXUNSUBTEST-26JAN01"));
+        assert!(!unsubscribed.contains("KXUNSUBTEST-26JAN01_NO"));
+    }
 }
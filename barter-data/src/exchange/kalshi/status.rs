@@ -0,0 +1,221 @@
+use crate::subscription::status::MarketStatus;
+use serde::{Deserialize, Serialize};
+
+/// Lifecycle status of a Kalshi market, as reported by the REST market endpoints.
+///
+/// Kalshi lists markets that aren't trading yet (`initialized`) and markets in a
+/// post-close auction or awaiting settlement (`closed`) alongside tradable ones. Their
+/// orderbooks are empty or stale, so use [`KalshiMarketInfo::is_tradable`] to keep them out
+/// of a trading universe.
+///
+/// Both the current (`active`, `finalized`, ...) and legacy (`open`, `settled`, ...) status
+/// names are accepted.
+///
+/// See docs: <https://trading-api.readme.io/reference/getmarkets>
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KalshiMarketStatus {
+    /// Created but not yet open for trading.
+    #[serde(alias = "unopened")]
+    Initialized,
+    /// Open for trading.
+    #[serde(alias = "active")]
+    Open,
+    /// Trading paused, but may resume.
+    Inactive,
+    /// Trading has stopped (including any post-close auction), outcome pending.
+    Closed,
+    /// Outcome has been determined and awaits settlement.
+    #[serde(alias = "disputed", alias = "amended")]
+    Determined,
+    /// Market has settled and paid out.
+    #[serde(alias = "finalized")]
+    Settled,
+    /// Status not recognised by this version of barter-data.
+    #[serde(other)]
+    Unknown,
+}
+
+impl KalshiMarketStatus {
+    /// Whether orders can currently be placed on the market.
+    pub fn is_tradable(&self) -> bool {
+        matches!(self, Self::Open)
+    }
+
+    /// Normalised [`MarketStatus`], or `None` for markets not yet open or with an unknown
+    /// status.
+    pub fn market_status(&self) -> Option<MarketStatus> {
+        match self {
+            Self::Open => Some(MarketStatus::Open),
+            Self::Inactive => Some(MarketStatus::Deactivated),
+            Self::Closed => Some(MarketStatus::Closed),
+            Self::Determined => Some(MarketStatus::Determined),
+            Self::Settled => Some(MarketStatus::Settled),
+            Self::Initialized | Self::Unknown => None,
+        }
+    }
+}
+
+/// Minimal Kalshi market listing entry, enough to decide whether to subscribe to it.
+///
+/// ### Raw Payload Examples
+/// See docs: <https://trading-api.readme.io/reference/getmarkets>
+/// ```json
+/// {
+///   "ticker": "KXBTC-25JAN31-T100000",
+///   "status": "active",
+///   "result": ""
+/// }
+/// ```
+#[derive(Clone, Eq, PartialEq, Debug, Deserialize, Serialize)]
+pub struct KalshiMarketInfo {
+    /// Market ticker identifier
+    pub ticker: String,
+    /// Market lifecycle status
+    pub status: KalshiMarketStatus,
+    /// Settlement result (`"yes"`/`"no"`), empty or absent until determined
+    #[serde(default)]
+    pub result: Option<String>,
+}
+
+impl KalshiMarketInfo {
+    /// Whether the market is open for trading and has no settlement result yet.
+    ///
+    /// Kalshi can report a result for a market whose status hasn't caught up, so a
+    /// definitive `"yes"`/`"no"` result excludes the market whatever its status.
+    pub fn is_tradable(&self) -> bool {
+        let settled = matches!(self.result.as_deref(), Some("yes" | "no"));
+        self.status.is_tradable() && !settled
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_de_kalshi_market_status() {
+        struct TestCase {
+            input: &'static str,
+            expected: KalshiMarketStatus,
+            tradable: bool,
+        }
+
+        let tests = vec![
+            TestCase {
+                // TC0: legacy open
+                input: r#""open""#,
+                expected: KalshiMarketStatus::Open,
+                tradable: true,
+            },
+            TestCase {
+                // TC1: current open
+                input: r#""active""#,
+                expected: KalshiMarketStatus::Open,
+                tradable: true,
+            },
+            TestCase {
+                // TC2: closed, eg/ post-close auction
+                input: r#""closed""#,
+                expected: KalshiMarketStatus::Closed,
+                tradable: false,
+            },
+            TestCase {
+                // TC3: legacy settled
+                input: r#""settled""#,
+                expected: KalshiMarketStatus::Settled,
+                tradable: false,
+            },
+            TestCase {
+                // TC4: current settled
+                input: r#""finalized""#,
+                expected: KalshiMarketStatus::Settled,
+                tradable: false,
+            },
+            TestCase {
+                // TC5: not yet trading
+                input: r#""initialized""#,
+                expected: KalshiMarketStatus::Initialized,
+                tradable: false,
+            },
+            TestCase {
+                // TC6: paused
+                input: r#""inactive""#,
+                expected: KalshiMarketStatus::Inactive,
+                tradable: false,
+            },
+            TestCase {
+                // TC7: determined
+                input: r#""determined""#,
+                expected: KalshiMarketStatus::Determined,
+                tradable: false,
+            },
+            TestCase {
+                // TC8: unrecognised status
+                input: r#""something_new""#,
+                expected: KalshiMarketStatus::Unknown,
+                tradable: false,
+            },
+        ];
+
+        for (index, test) in tests.into_iter().enumerate() {
+            let actual = serde_json::from_str::<KalshiMarketStatus>(test.input).unwrap();
+            assert_eq!(actual, test.expected, "TC{index} failed");
+            assert_eq!(actual.is_tradable(), test.tradable, "TC{index} failed");
+        }
+    }
+
+    #[test]
+    fn test_kalshi_market_status_to_market_status() {
+        assert_eq!(KalshiMarketStatus::Open.market_status(), Some(MarketStatus::Open));
+        assert_eq!(KalshiMarketStatus::Closed.market_status(), Some(MarketStatus::Closed));
+        assert_eq!(KalshiMarketStatus::Settled.market_status(), Some(MarketStatus::Settled));
+        assert_eq!(KalshiMarketStatus::Initialized.market_status(), None);
+    }
+
+    #[test]
+    fn test_kalshi_market_info_is_tradable() {
+        struct TestCase {
+            input: &'static str,
+            expected: bool,
+        }
+
+        let tests = vec![
+            TestCase {
+                // TC0: open without result
+                input: r#"{"ticker": "KXTEST", "status": "active", "result": ""}"#,
+                expected: true,
+            },
+            TestCase {
+                // TC1: open with missing result
+                input: r#"{"ticker": "KXTEST", "status": "open"}"#,
+                expected: true,
+            },
+            TestCase {
+                // TC2: open but already resolved
+                input: r#"{"ticker": "KXTEST", "status": "open", "result": "yes"}"#,
+                expected: false,
+            },
+            TestCase {
+                // TC3: not yet trading
+                input: r#"{"ticker": "KXTEST", "status": "initialized", "result": ""}"#,
+                expected: false,
+            },
+            TestCase {
+                // TC4: closed
+                input: r#"{"ticker": "KXTEST", "status": "closed", "result": ""}"#,
+                expected: false,
+            },
+            TestCase {
+                // TC5: settled
+                input: r#"{"ticker": "KXTEST", "status": "settled", "result": "no"}"#,
+                expected: false,
+            },
+        ];
+
+        for (index, test) in tests.into_iter().enumerate() {
+            let actual = serde_json::from_str::<KalshiMarketInfo>(test.input).unwrap();
+            assert_eq!(actual.is_tradable(), test.expected, "TC{index} failed");
+        }
+    }
+}
@@ -16,6 +16,7 @@ use crate::{
     subscription::{Subscription, SubscriptionKind, SubscriptionMeta},
 };
 use async_trait::async_trait;
+use barter_instrument::exchange::ExchangeId;
 use barter_integration::{
     error::SocketError,
     protocol::websocket::connect_with_headers,
@@ -23,13 +24,14 @@ use barter_integration::{
 use futures::SinkExt;
 use std::sync::OnceLock;
 use tracing::debug;
+use url::Url;
 
 /// Global storage for Kalshi credentials.
 /// Initialized on first use from environment variables.
 static KALSHI_CREDENTIALS: OnceLock<Result<KalshiCredentials, String>> = OnceLock::new();
 
 /// Initialize Kalshi credentials from environment.
-fn get_credentials() -> Result<&'static KalshiCredentials, SocketError> {
+pub(super) fn get_credentials() -> Result<&'static KalshiCredentials, SocketError> {
     let result = KALSHI_CREDENTIALS.get_or_init(|| {
         KalshiCredentials::from_env()
             .map_err(|e| e.to_string())
@@ -44,10 +46,34 @@ fn get_credentials() -> Result<&'static KalshiCredentials, SocketError> {
     }
 }
 
+/// Generate signed handshake headers for one connection attempt to `url`.
+fn auth_headers(
+    credentials: &KalshiCredentials,
+    url: &Url,
+) -> Result<[(&'static str, String); 3], SocketError> {
+    let auth_headers = credentials
+        .generate_ws_auth(url)
+        .map_err(|e| SocketError::Subscribe(format!("Failed to generate auth: {}", e)))?;
+
+    debug!(
+        exchange = %ExchangeId::Kalshi,
+        api_key = %auth_headers.api_key,
+        timestamp = %auth_headers.timestamp,
+        "Generated Kalshi authentication headers"
+    );
+
+    Ok([
+        (KalshiAuthHeaders::KEY_HEADER, auth_headers.api_key),
+        (KalshiAuthHeaders::SIGNATURE_HEADER, auth_headers.signature),
+        (KalshiAuthHeaders::TIMESTAMP_HEADER, auth_headers.timestamp),
+    ])
+}
+
 /// Authenticated WebSocket subscriber for Kalshi.
 ///
 /// This subscriber generates RSA-signed authentication headers
-/// before connecting to Kalshi's WebSocket API.
+/// before connecting to Kalshi's WebSocket API. Headers are re-signed on every
+/// connection attempt, including reconnects.
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
 pub struct KalshiAuthenticatedSubscriber;
 
@@ -70,25 +96,9 @@ impl crate::subscriber::Subscriber for KalshiAuthenticatedSubscriber {
         let url = Exchange::url()?;
         debug!(%exchange, %url, ?subscriptions, "subscribing to Kalshi WebSocket with authentication");
 
-        // Get credentials and generate auth headers
-        let credentials = get_credentials()?;
-        let auth_headers = credentials
-            .generate_ws_auth()
-            .map_err(|e| SocketError::Subscribe(format!("Failed to generate auth: {}", e)))?;
-
-        debug!(
-            %exchange,
-            api_key = %auth_headers.api_key,
-            timestamp = %auth_headers.timestamp,
-            "Generated Kalshi authentication headers"
-        );
-
-        // Build headers iterator
-        let headers = [
-            (KalshiAuthHeaders::KEY_HEADER, auth_headers.api_key),
-            (KalshiAuthHeaders::SIGNATURE_HEADER, auth_headers.signature),
-            (KalshiAuthHeaders::TIMESTAMP_HEADER, auth_headers.timestamp),
-        ];
+        // Sign fresh auth headers: subscribe runs again on every reconnect, so a
+        // long-running stream never reconnects with a stale timestamp
+        let headers = auth_headers(get_credentials()?, &url)?;
 
         // Connect with authentication headers
         let mut websocket = connect_with_headers(url.clone(), headers).await?;
@@ -125,3 +135,26 @@ impl crate::subscriber::Subscriber for KalshiAuthenticatedSubscriber {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exchange::kalshi::BASE_URL_KALSHI;
+
+    #[test]
+    fn test_sequential_auth_headers_are_distinct() {
+        let mut rng = rsa::rand_core::OsRng;
+        let private_key = rsa::RsaPrivateKey::new(&mut rng, 1024).unwrap();
+        let pem =
+            rsa::pkcs8::EncodePrivateKey::to_pkcs8_pem(&private_key, Default::default()).unwrap();
+        let credentials = KalshiCredentials::from_pem("test-key", &pem).unwrap();
+
+        let url = Url::parse(BASE_URL_KALSHI).unwrap();
+        let first = auth_headers(&credentials, &url).unwrap();
+        let second = auth_headers(&credentials, &url).unwrap();
+
+        assert_eq!(first[0], second[0]);
+        assert_ne!(first[1].1, second[1].1, "signatures must differ");
+        assert_ne!(first[2].1, second[2].1, "timestamps must differ");
+    }
+}
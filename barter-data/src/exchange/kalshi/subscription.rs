@@ -29,6 +29,10 @@ use serde::{Deserialize, Serialize};
 ///   }
 /// }
 /// ```
+///
+/// Large subscriptions are split into several commands with distinct ids (see
+/// [`Kalshi::requests`](crate::exchange::Connector::requests)). Each command is
+/// confirmed separately, so a confirmation is valid whichever issued id it carries.
 #[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Deserialize, Serialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum KalshiSubResponse {
@@ -54,6 +58,15 @@ pub struct KalshiSubscribedMsg {
     pub market_tickers: Option<Vec<String>>,
 }
 
+impl KalshiSubResponse {
+    /// Id of the subscribe command this message responds to.
+    pub fn id(&self) -> u64 {
+        match self {
+            Self::Subscribed { id, .. } | Self::Error { id, .. } => *id,
+        }
+    }
+}
+
 impl Validator for KalshiSubResponse {
     fn validate(self) -> Result<Self, SocketError>
     where
@@ -61,8 +74,8 @@ impl Validator for KalshiSubResponse {
     {
         match &self {
             KalshiSubResponse::Subscribed { .. } => Ok(self),
-            KalshiSubResponse::Error { msg, .. } => Err(SocketError::Subscribe(format!(
-                "received failure subscription response: {}",
+            KalshiSubResponse::Error { id, msg } => Err(SocketError::Subscribe(format!(
+                "received failure subscription response to command {id}: {}",
                 msg.message
             ))),
         }
@@ -153,6 +166,18 @@ mod tests {
                 },
                 is_valid: false,
             },
+            TestCase {
+                // TC2: Confirmation of a later batch of the same channel
+                input_response: KalshiSubResponse::Subscribed {
+                    id: 3,
+                    msg: KalshiSubscribedMsg {
+                        channel: "orderbook_delta".to_string(),
+                        sid: Some(3),
+                        market_tickers: None,
+                    },
+                },
+                is_valid: true,
+            },
         ];
 
         for (index, test) in cases.into_iter().enumerate() {
@@ -0,0 +1,110 @@
+use crate::{books::Level, subscription::book::OrderBookL1};
+use barter_instrument::{instrument::market_data::kind::Outcome, kalshi::price};
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+
+use super::message::KalshiTickerData;
+
+impl KalshiTickerData {
+    /// Apply a partial `ticker_v2` update, keeping any fields it doesn't carry.
+    pub fn merge(&mut self, update: &KalshiTickerData) {
+        self.price = update.price.or(self.price);
+        self.yes_bid = update.yes_bid.or(self.yes_bid);
+        self.yes_ask = update.yes_ask.or(self.yes_ask);
+        self.volume = update.volume.or(self.volume);
+        self.open_interest = update.open_interest.or(self.open_interest);
+        self.ts = update.ts.or(self.ts);
+    }
+
+    /// Convert to a barter [`OrderBookL1`] from the perspective of `outcome`.
+    ///
+    /// Prices are converted from cents to decimals. NO prices mirror YES prices, so
+    /// the NO bid is `1 - yes_ask` and the NO ask is `1 - yes_bid`. The ticker
+    /// carries no sizes, so each [`Level`] has a zero amount.
+    pub fn to_order_book_l1(&self, outcome: Outcome) -> OrderBookL1 {
+        let (bid, ask) = match outcome {
+            Outcome::Yes => (self.yes_bid, self.yes_ask),
+            Outcome::No => (
+                self.yes_ask.map(|cents| 100 - cents.min(100)),
+                self.yes_bid.map(|cents| 100 - cents.min(100)),
+            ),
+        };
+
+        OrderBookL1 {
+            last_update_time: self
+                .ts
+                .and_then(|ts| DateTime::from_timestamp(ts, 0))
+                .unwrap_or_else(Utc::now),
+            best_bid: level(bid),
+            best_ask: level(ask),
+        }
+    }
+}
+
+/// [`Level`] at `cents`, or `None` if there is no resting order on that side
+/// (Kalshi reports an empty side as 0 or 100).
+fn level(cents: Option<u32>) -> Option<Level> {
+    cents
+        .filter(|cents| (1..100).contains(cents))
+        .map(|cents| Level::new(price::from_cents(cents), Decimal::ZERO))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn data(yes_bid: Option<u32>, yes_ask: Option<u32>) -> KalshiTickerData {
+        KalshiTickerData {
+            market_ticker: "KXBTC-25JAN31-T100000".to_string(),
+            price: Some(48),
+            yes_bid,
+            yes_ask,
+            volume: Some(2410),
+            open_interest: Some(1280),
+            ts: Some(1738281600),
+        }
+    }
+
+    #[test]
+    fn test_kalshi_ticker_to_order_book_l1() {
+        let ticker = data(Some(47), Some(49));
+
+        let yes = ticker.to_order_book_l1(Outcome::Yes);
+        assert_eq!(yes.best_bid, Some(Level::new(dec!(0.47), dec!(0))));
+        assert_eq!(yes.best_ask, Some(Level::new(dec!(0.49), dec!(0))));
+        assert_eq!(yes.last_update_time.timestamp(), 1738281600);
+
+        let no = ticker.to_order_book_l1(Outcome::No);
+        assert_eq!(no.best_bid, Some(Level::new(dec!(0.51), dec!(0))));
+        assert_eq!(no.best_ask, Some(Level::new(dec!(0.53), dec!(0))));
+    }
+
+    #[test]
+    fn test_kalshi_ticker_empty_sides() {
+        let ticker = data(Some(0), Some(100));
+
+        let yes = ticker.to_order_book_l1(Outcome::Yes);
+        assert_eq!(yes.best_bid, None);
+        assert_eq!(yes.best_ask, None);
+
+        let no = ticker.to_order_book_l1(Outcome::No);
+        assert_eq!(no.best_bid, None);
+        assert_eq!(no.best_ask, None);
+    }
+
+    #[test]
+    fn test_kalshi_ticker_merge() {
+        let mut ticker = data(Some(47), Some(49));
+        ticker.merge(&KalshiTickerData {
+            market_ticker: "KXBTC-25JAN31-T100000".to_string(),
+            yes_bid: Some(48),
+            ..Default::default()
+        });
+
+        assert_eq!(ticker.yes_bid, Some(48));
+        assert_eq!(ticker.yes_ask, Some(49));
+        assert_eq!(ticker.price, Some(48));
+        assert_eq!(ticker.volume, Some(2410));
+    }
+}
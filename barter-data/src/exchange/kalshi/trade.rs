@@ -2,11 +2,38 @@ use crate::{
     event::{MarketEvent, MarketIter},
     subscription::trade::PublicTrade,
 };
-use barter_instrument::{Side, exchange::ExchangeId};
+use barter_instrument::{
+    Side, exchange::ExchangeId, instrument::market_data::kind::Outcome, kalshi::price,
+};
 use chrono::Utc;
+use rust_decimal::prelude::ToPrimitive;
 
 use super::message::KalshiTrade;
 
+impl KalshiTrade {
+    /// Convert to a barter [`PublicTrade`] from the perspective of `outcome`.
+    ///
+    /// The price is the outcome's price, and a taker buying the other outcome is
+    /// selling this one.
+    pub fn to_public_trade(&self, outcome: Outcome) -> PublicTrade {
+        let (price, taker_side) = match outcome {
+            Outcome::Yes => (self.msg.yes_price, "yes"),
+            Outcome::No => (self.msg.no_price, "no"),
+        };
+
+        PublicTrade {
+            id: format!("{}-{}", self.sid, self.seq),
+            price: price::from_cents(price).to_f64().unwrap_or_default(),
+            amount: self.msg.count as f64,
+            side: if self.msg.taker_side == taker_side {
+                Side::Buy
+            } else {
+                Side::Sell
+            },
+        }
+    }
+}
+
 impl<InstrumentKey> From<(ExchangeId, InstrumentKey, KalshiTrade)>
     for MarketIter<InstrumentKey, PublicTrade>
 {
@@ -16,15 +43,7 @@ impl<InstrumentKey> From<(ExchangeId, InstrumentKey, KalshiTrade)>
             time_received: Utc::now(),
             exchange,
             instrument,
-            kind: PublicTrade {
-                id: format!("{}-{}", trade.sid, trade.seq),
-                price: trade.msg.yes_price as f64 / 100.0,
-                amount: trade.msg.count as f64,
-                side: match trade.msg.taker_side.as_str() {
-                    "yes" => Side::Buy,
-                    _ => Side::Sell,
-                },
-            },
+            kind: trade.to_public_trade(Outcome::Yes),
         })])
     }
 }
@@ -80,4 +99,24 @@ mod tests {
         assert!((event.kind.amount - 50.0).abs() < f64::EPSILON);
         assert_eq!(event.kind.side, Side::Sell);
     }
+
+    #[test]
+    fn test_kalshi_trade_to_public_trade_no_outcome() {
+        let trade = KalshiTrade {
+            sid: 2,
+            seq: 7,
+            msg: KalshiTradeData {
+                market_ticker: "KXETH-25JAN31-T5000".to_string(),
+                yes_price: 25,
+                no_price: 75,
+                count: 50,
+                taker_side: "no".to_string(),
+            },
+        };
+
+        let trade = trade.to_public_trade(Outcome::No);
+        assert!((trade.price - 0.75).abs() < f64::EPSILON);
+        assert!((trade.amount - 50.0).abs() < f64::EPSILON);
+        assert_eq!(trade.side, Side::Buy);
+    }
 }
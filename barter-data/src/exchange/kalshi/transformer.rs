@@ -0,0 +1,1319 @@
+use crate::{
+    Identifier,
+    error::DataError,
+    event::{MarketEvent, MarketIter},
+    exchange::kalshi::{
+        book::KalshiOrderBook,
+        channel::KalshiChannel,
+        market::KalshiMarket,
+        message::{KalshiMessage, KalshiOrderbookDelta, KalshiOrderbookSnapshot, KalshiTickerData},
+    },
+    subscription::{
+        Map,
+        book::{OrderBookEvent, OrderBookL1, OrderBooksL1, OrderBooksL2},
+        status::{MarketStatus, MarketStatuses},
+        trade::{PublicTrade, PublicTrades},
+    },
+    transformer::ExchangeTransformer,
+};
+use async_trait::async_trait;
+use barter_instrument::{exchange::ExchangeId, instrument::market_data::kind::Outcome};
+use barter_integration::{
+    Transformer, protocol::websocket::WsMessage, subscription::SubscriptionId,
+};
+use chrono::Utc;
+use fnv::{FnvHashMap, FnvHashSet};
+use serde_json::json;
+use smol_str::format_smolstr;
+use std::collections::BTreeMap;
+use tokio::sync::{mpsc, watch};
+use tracing::{debug, info, warn};
+
+/// Stateful transformer for Kalshi OrderBook L2 streams.
+///
+/// Kalshi sends one `orderbook_snapshot` per market followed by `orderbook_delta`
+/// messages. A [`KalshiOrderBook`] is kept per market [`SubscriptionId`] so deltas
+/// can be sequence checked and applied:
+/// - Snapshots replace the local book and emit an [`OrderBookEvent::Snapshot`]
+/// - Deltas newer than the local book emit an [`OrderBookEvent::Update`] of the
+///   changed level; stale deltas and deltas received before a snapshot are dropped
+/// - `market_lifecycle_v2` events clear the book when terminal
+///
+/// Every market on one Kalshi subscription (`sid`) shares a single `seq` counter.
+/// A skipped `seq` means some book on the subscription missed a delta, so every
+/// book on it is discarded and withheld, a [`DataError::InvalidSequence`] is
+/// emitted, and the subscription's markets are resubscribed over the same
+/// WebSocket. Emission resumes with the fresh snapshots.
+///
+/// Markets dropped by [`Kalshi::unsubscribe_markets`](super::Kalshi::unsubscribe_markets)
+/// are deleted from their `orderbook_delta` and `market_lifecycle_v2` subscriptions,
+/// and any of their messages still in flight are ignored.
+///
+/// Each message is emitted once per instrument subscribed to the market, from the
+/// perspective of the instrument's [`Outcome`] (see [`KalshiMarket`]).
+#[derive(Debug)]
+pub struct KalshiOrderBookTransformer<InstrumentKey> {
+    markets: Map<KalshiMarketBook<InstrumentKey>>,
+    /// Market ids dropped by [`Self::unsubscribe`], whose messages are ignored.
+    unsubscribed: FnvHashSet<SubscriptionId>,
+    /// Source of tickers to [`Self::unsubscribe`] (`None` if not connected).
+    unsubscribed_rx: Option<watch::Receiver<FnvHashSet<String>>>,
+    /// Subscription carrying this connection's `market_lifecycle_v2` messages.
+    lifecycle_sid: Option<u64>,
+    /// Last `seq` received on each subscription, by `sid`.
+    sequences: FnvHashMap<u64, u64>,
+    /// Subscriptions unsubscribed after a sequence gap, whose messages are ignored.
+    retired: FnvHashSet<u64>,
+    /// Sink for resubscribe commands (`None` if the transformer isn't connected).
+    ws_sink_tx: Option<mpsc::UnboundedSender<WsMessage>>,
+    /// Id of the next command sent over `ws_sink_tx`.
+    next_request_id: u64,
+}
+
+/// First command id used for resubscribes and unsubscribes, clear of the initial
+/// [`Connector::requests`](crate::exchange::Connector::requests) ids.
+const RESUBSCRIBE_REQUEST_ID: u64 = 1000;
+
+/// Instruments streaming one Kalshi market's orderbook, and its local book.
+#[derive(Debug)]
+struct KalshiMarketBook<InstrumentKey> {
+    instruments: Vec<(Outcome, InstrumentKey)>,
+    book: Option<KalshiOrderBook>,
+    /// Subscription the current book was snapshotted on.
+    sid: Option<u64>,
+}
+
+impl<InstrumentKey> KalshiMarketBook<InstrumentKey>
+where
+    InstrumentKey: Clone,
+{
+    /// Emit `kind(book, outcome)` for every subscribed instrument.
+    fn events(
+        &self,
+        book: &KalshiOrderBook,
+        kind: impl Fn(&KalshiOrderBook, Outcome) -> OrderBookEvent,
+    ) -> Vec<Result<MarketEvent<InstrumentKey, OrderBookEvent>, DataError>> {
+        let now = Utc::now();
+        self.instruments
+            .iter()
+            .map(|(outcome, instrument)| {
+                Ok(MarketEvent {
+                    time_exchange: now,
+                    time_received: now,
+                    exchange: ExchangeId::Kalshi,
+                    instrument: instrument.clone(),
+                    kind: kind(book, *outcome),
+                })
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl<InstrumentKey> ExchangeTransformer<super::Kalshi, InstrumentKey, OrderBooksL2>
+    for KalshiOrderBookTransformer<InstrumentKey>
+where
+    InstrumentKey: Clone + Send,
+{
+    async fn init(
+        instrument_map: Map<InstrumentKey>,
+        _initial_snapshots: &[MarketEvent<InstrumentKey, OrderBookEvent>],
+        ws_sink_tx: mpsc::UnboundedSender<WsMessage>,
+    ) -> Result<Self, DataError> {
+        Ok(Self::new(instrument_map)
+            .with_ws_sink(ws_sink_tx)
+            .with_unsubscribes(super::Kalshi::unsubscribed_markets()))
+    }
+}
+
+impl<InstrumentKey> Transformer for KalshiOrderBookTransformer<InstrumentKey>
+where
+    InstrumentKey: Clone,
+{
+    type Error = DataError;
+    type Input = KalshiMessage<serde_json::Value>;
+    type Output = MarketEvent<InstrumentKey, OrderBookEvent>;
+    type OutputIter = Vec<Result<Self::Output, Self::Error>>;
+
+    fn transform(&mut self, input: Self::Input) -> Self::OutputIter {
+        if let Some(tickers) = poll_unsubscribed(&mut self.unsubscribed_rx) {
+            self.unsubscribe(&tickers);
+        }
+
+        match input {
+            KalshiMessage::OrderbookSnapshot(snapshot) => {
+                let Some(mut events) = self.check_sequence(snapshot.sid, snapshot.seq) else {
+                    return vec![];
+                };
+                events.extend(self.transform_snapshot(snapshot));
+                events
+            }
+            KalshiMessage::OrderbookDelta(delta) => {
+                let Some(mut events) = self.check_sequence(delta.sid, delta.seq) else {
+                    return vec![];
+                };
+                events.extend(self.transform_delta(delta));
+                events
+            }
+            KalshiMessage::MarketLifecycle(lifecycle) => {
+                self.lifecycle_sid = Some(lifecycle.sid);
+                let Some(sub_id) = lifecycle.id() else {
+                    return vec![];
+                };
+                if self.unsubscribed.contains(&sub_id) {
+                    return vec![];
+                }
+                let market = match self.markets.find_mut(&sub_id) {
+                    Ok(market) => market,
+                    Err(unidentifiable) => return vec![Err(DataError::from(unidentifiable))],
+                };
+                if let Some(book) = &mut market.book {
+                    book.apply_lifecycle(&lifecycle.msg);
+                }
+                market
+                    .instruments
+                    .iter()
+                    .flat_map(|(_, instrument)| {
+                        MarketIter::<InstrumentKey, OrderBookEvent>::from((
+                            ExchangeId::Kalshi,
+                            instrument.clone(),
+                            lifecycle.clone(),
+                        ))
+                        .0
+                    })
+                    .collect()
+            }
+            KalshiMessage::Trade(_) | KalshiMessage::TickerV2(_) | KalshiMessage::Data(_) => {
+                vec![]
+            }
+        }
+    }
+}
+
+impl<InstrumentKey> KalshiOrderBookTransformer<InstrumentKey>
+where
+    InstrumentKey: Clone,
+{
+    /// Construct a transformer without any local books.
+    ///
+    /// Subscriptions to each outcome of one ticker are grouped under the ticker's
+    /// [`SubscriptionId`], which is how Kalshi identifies its messages.
+    pub fn new(instrument_map: Map<InstrumentKey>) -> Self {
+        let markets = group_by_market(instrument_map)
+            .0
+            .into_iter()
+            .map(|(market_id, instruments)| {
+                let book = KalshiMarketBook {
+                    instruments,
+                    book: None,
+                    sid: None,
+                };
+                (market_id, book)
+            })
+            .collect();
+
+        Self {
+            markets,
+            unsubscribed: FnvHashSet::default(),
+            unsubscribed_rx: None,
+            lifecycle_sid: None,
+            sequences: FnvHashMap::default(),
+            retired: FnvHashSet::default(),
+            ws_sink_tx: None,
+            next_request_id: RESUBSCRIBE_REQUEST_ID,
+        }
+    }
+
+    /// Resubscribe markets after a sequence gap, and unsubscribe dropped
+    /// markets, over `ws_sink_tx`.
+    pub fn with_ws_sink(mut self, ws_sink_tx: mpsc::UnboundedSender<WsMessage>) -> Self {
+        self.ws_sink_tx = Some(ws_sink_tx);
+        self
+    }
+
+    /// [`Self::unsubscribe`] the tickers received on `unsubscribed_rx` whenever
+    /// it changes.
+    pub fn with_unsubscribes(
+        mut self,
+        unsubscribed_rx: watch::Receiver<FnvHashSet<String>>,
+    ) -> Self {
+        self.unsubscribed_rx = Some(unsubscribed_rx);
+        self
+    }
+
+    /// Drop the markets of `tickers` from this stream, so their messages are
+    /// ignored, and delete them from their Kalshi subscriptions.
+    ///
+    /// Markets without a book yet have no known `orderbook_delta` subscription,
+    /// so are only deleted from the `market_lifecycle_v2` one.
+    pub fn unsubscribe(&mut self, tickers: &[String]) {
+        let tickers = normalise_tickers(tickers);
+        let mut by_sid: BTreeMap<u64, Vec<String>> = BTreeMap::new();
+        let mut removed = Vec::new();
+
+        self.markets.0.retain(|market_id, market| {
+            let Some(ticker) = market_ticker(market_id).filter(|t| tickers.contains(t)) else {
+                return true;
+            };
+            if let Some(sid) = market.sid {
+                by_sid.entry(sid).or_default().push(ticker.clone());
+            }
+            self.unsubscribed.insert(market_id.clone());
+            removed.push(ticker);
+            false
+        });
+
+        if removed.is_empty() {
+            return;
+        }
+        info!(tickers = ?removed, "Kalshi orderbooks unsubscribed");
+
+        if let Some(sid) = self.lifecycle_sid {
+            by_sid.entry(sid).or_default().extend(removed);
+        }
+        for (sid, tickers) in by_sid {
+            self.send_command(delete_markets_command(self.next_request_id, sid, tickers));
+            self.next_request_id += 1;
+        }
+    }
+
+    fn send_command(&self, command: WsMessage) {
+        let Some(ws_sink_tx) = &self.ws_sink_tx else {
+            warn!("Kalshi transformer has no WebSocket sink to send commands with");
+            return;
+        };
+        if ws_sink_tx.send(command).is_err() {
+            warn!("Kalshi WebSocket sink closed, cannot send command");
+        }
+    }
+
+    /// Track `seq` on subscription `sid`, returning the events to emit ahead of
+    /// the message, or `None` if the message is from a retired subscription.
+    ///
+    /// On a gap, every book on `sid` is discarded and its markets resubscribed.
+    fn check_sequence(
+        &mut self,
+        sid: u64,
+        seq: u64,
+    ) -> Option<Vec<Result<MarketEvent<InstrumentKey, OrderBookEvent>, DataError>>> {
+        if self.retired.contains(&sid) {
+            return None;
+        }
+
+        let prev = match self.sequences.get(&sid) {
+            Some(&prev) if seq > prev + 1 => prev,
+            // Duplicate or reordered message, left to the per-book stale check
+            Some(&prev) if seq <= prev => return Some(vec![]),
+            _ => {
+                self.sequences.insert(sid, seq);
+                return Some(vec![]);
+            }
+        };
+
+        warn!(sid, prev, seq, "Kalshi sequence gap, resubscribing");
+        self.sequences.remove(&sid);
+        self.retired.insert(sid);
+
+        let mut tickers = Vec::new();
+        for (market_id, market) in self.markets.0.iter_mut() {
+            if market.sid == Some(sid) {
+                market.book = None;
+                market.sid = None;
+                if let Some((_, ticker)) = market_id.0.split_once('|') {
+                    tickers.push(ticker.to_uppercase());
+                }
+            }
+        }
+        self.resubscribe(sid, tickers);
+
+        Some(vec![Err(DataError::InvalidSequence {
+            prev_last_update_id: prev,
+            first_update_id: seq,
+        })])
+    }
+
+    /// Replace subscription `sid` with a fresh one for `tickers`, which starts
+    /// with new snapshots.
+    fn resubscribe(&mut self, sid: u64, tickers: Vec<String>) {
+        let Some(ws_sink_tx) = &self.ws_sink_tx else {
+            warn!(sid, "Kalshi transformer has no WebSocket sink to resubscribe with");
+            return;
+        };
+
+        let unsubscribe = json!({
+            "id": self.next_request_id,
+            "cmd": "unsubscribe",
+            "params": { "sids": [sid] }
+        });
+        let subscribe = json!({
+            "id": self.next_request_id + 1,
+            "cmd": "subscribe",
+            "params": {
+                "channels": [KalshiChannel::ORDER_BOOK_DELTA.as_ref()],
+                "market_tickers": tickers
+            }
+        });
+        self.next_request_id += 2;
+
+        for command in [unsubscribe, subscribe] {
+            if ws_sink_tx.send(WsMessage::text(command.to_string())).is_err() {
+                warn!(sid, "Kalshi WebSocket sink closed, cannot resubscribe");
+                return;
+            }
+        }
+    }
+
+    fn transform_snapshot(
+        &mut self,
+        snapshot: KalshiOrderbookSnapshot,
+    ) -> Vec<Result<MarketEvent<InstrumentKey, OrderBookEvent>, DataError>> {
+        let Some(sub_id) = snapshot.id() else {
+            return vec![];
+        };
+        if self.unsubscribed.contains(&sub_id) {
+            return vec![];
+        }
+        let market = match self.markets.find_mut(&sub_id) {
+            Ok(market) => market,
+            Err(unidentifiable) => return vec![Err(DataError::from(unidentifiable))],
+        };
+
+        let book = KalshiOrderBook::from_snapshot(&snapshot);
+        let events = market.events(&book, |book, outcome| {
+            OrderBookEvent::Snapshot(match outcome {
+                Outcome::Yes => book.to_yes_orderbook(),
+                Outcome::No => book.to_no_orderbook(),
+            })
+        });
+        market.book = Some(book);
+        market.sid = Some(snapshot.sid);
+        events
+    }
+
+    fn transform_delta(
+        &mut self,
+        delta: KalshiOrderbookDelta,
+    ) -> Vec<Result<MarketEvent<InstrumentKey, OrderBookEvent>, DataError>> {
+        let Some(sub_id) = delta.id() else {
+            return vec![];
+        };
+        if self.unsubscribed.contains(&sub_id) {
+            return vec![];
+        }
+        let market = match self.markets.find_mut(&sub_id) {
+            Ok(market) => market,
+            Err(unidentifiable) => return vec![Err(DataError::from(unidentifiable))],
+        };
+
+        let Some(mut book) = market.book.take() else {
+            debug!(
+                ticker = %delta.market_ticker(),
+                seq = delta.seq,
+                "Kalshi delta received before snapshot, dropping"
+            );
+            return vec![];
+        };
+
+        // Sequence numbers are shared by every market on a subscription (gaps are
+        // caught in check_sequence), so only deltas at or behind the book are stale
+        let events = if delta.seq <= book.seq {
+            debug!(
+                ticker = %delta.market_ticker(),
+                seq = delta.seq,
+                book_seq = book.seq,
+                "Kalshi stale delta, dropping"
+            );
+            vec![]
+        } else {
+            book.apply_delta(&delta);
+            market.events(&book, |book, outcome| {
+                OrderBookEvent::Update(match outcome {
+                    Outcome::Yes => book.to_yes_update(&delta),
+                    Outcome::No => book.to_no_update(&delta),
+                })
+            })
+        };
+        market.book = Some(book);
+        events
+    }
+}
+
+/// Stateless transformer for Kalshi PublicTrades streams.
+///
+/// Each `trade` message is emitted once per instrument subscribed to the market,
+/// priced from the perspective of the instrument's [`Outcome`].
+#[derive(Debug)]
+pub struct KalshiTradesTransformer<InstrumentKey> {
+    markets: Map<Vec<(Outcome, InstrumentKey)>>,
+}
+
+#[async_trait]
+impl<InstrumentKey> ExchangeTransformer<super::Kalshi, InstrumentKey, PublicTrades>
+    for KalshiTradesTransformer<InstrumentKey>
+where
+    InstrumentKey: Clone + Send,
+{
+    async fn init(
+        instrument_map: Map<InstrumentKey>,
+        _initial_snapshots: &[MarketEvent<InstrumentKey, PublicTrade>],
+        _ws_sink_tx: mpsc::UnboundedSender<WsMessage>,
+    ) -> Result<Self, DataError> {
+        Ok(Self::new(instrument_map))
+    }
+}
+
+impl<InstrumentKey> KalshiTradesTransformer<InstrumentKey> {
+    /// Construct a transformer, grouping subscriptions to each outcome of one
+    /// ticker as [`KalshiOrderBookTransformer::new`] does.
+    pub fn new(instrument_map: Map<InstrumentKey>) -> Self {
+        Self {
+            markets: group_by_market(instrument_map),
+        }
+    }
+}
+
+impl<InstrumentKey> Transformer for KalshiTradesTransformer<InstrumentKey>
+where
+    InstrumentKey: Clone,
+{
+    type Error = DataError;
+    type Input = KalshiMessage<serde_json::Value>;
+    type Output = MarketEvent<InstrumentKey, PublicTrade>;
+    type OutputIter = Vec<Result<Self::Output, Self::Error>>;
+
+    fn transform(&mut self, input: Self::Input) -> Self::OutputIter {
+        let KalshiMessage::Trade(trade) = input else {
+            return vec![];
+        };
+        let Some(sub_id) = trade.id() else {
+            return vec![];
+        };
+        let instruments = match self.markets.find(&sub_id) {
+            Ok(instruments) => instruments,
+            Err(unidentifiable) => return vec![Err(DataError::from(unidentifiable))],
+        };
+
+        let now = Utc::now();
+        instruments
+            .iter()
+            .map(|(outcome, instrument)| {
+                Ok(MarketEvent {
+                    time_exchange: now,
+                    time_received: now,
+                    exchange: ExchangeId::Kalshi,
+                    instrument: instrument.clone(),
+                    kind: trade.to_public_trade(*outcome),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Stateful transformer for Kalshi OrderBook L1 streams, sourced from `ticker_v2`.
+///
+/// `ticker_v2` messages only carry the fields that changed, so the latest
+/// [`KalshiTickerData`] is kept per market [`SubscriptionId`] and each update is
+/// merged into it before emitting an [`OrderBookL1`] per subscribed instrument.
+/// Terminal `market_lifecycle_v2` events clear the ticker and emit an empty
+/// [`OrderBookL1`].
+#[derive(Debug)]
+pub struct KalshiTickerTransformer<InstrumentKey> {
+    markets: Map<KalshiMarketTicker<InstrumentKey>>,
+}
+
+/// Instruments streaming one Kalshi market's ticker, and its latest state.
+#[derive(Debug)]
+struct KalshiMarketTicker<InstrumentKey> {
+    instruments: Vec<(Outcome, InstrumentKey)>,
+    ticker: Option<KalshiTickerData>,
+}
+
+#[async_trait]
+impl<InstrumentKey> ExchangeTransformer<super::Kalshi, InstrumentKey, OrderBooksL1>
+    for KalshiTickerTransformer<InstrumentKey>
+where
+    InstrumentKey: Clone + Send,
+{
+    async fn init(
+        instrument_map: Map<InstrumentKey>,
+        _initial_snapshots: &[MarketEvent<InstrumentKey, OrderBookL1>],
+        _ws_sink_tx: mpsc::UnboundedSender<WsMessage>,
+    ) -> Result<Self, DataError> {
+        Ok(Self::new(instrument_map))
+    }
+}
+
+impl<InstrumentKey> KalshiTickerTransformer<InstrumentKey> {
+    /// Construct a transformer without any ticker state, grouping subscriptions
+    /// to each outcome of one ticker as [`KalshiOrderBookTransformer::new`] does.
+    pub fn new(instrument_map: Map<InstrumentKey>) -> Self {
+        let markets = group_by_market(instrument_map)
+            .0
+            .into_iter()
+            .map(|(market_id, instruments)| {
+                let ticker = KalshiMarketTicker {
+                    instruments,
+                    ticker: None,
+                };
+                (market_id, ticker)
+            })
+            .collect();
+
+        Self { markets }
+    }
+}
+
+impl<InstrumentKey> Transformer for KalshiTickerTransformer<InstrumentKey>
+where
+    InstrumentKey: Clone,
+{
+    type Error = DataError;
+    type Input = KalshiMessage<serde_json::Value>;
+    type Output = MarketEvent<InstrumentKey, OrderBookL1>;
+    type OutputIter = Vec<Result<Self::Output, Self::Error>>;
+
+    fn transform(&mut self, input: Self::Input) -> Self::OutputIter {
+        let (sub_id, update) = match input {
+            KalshiMessage::TickerV2(ticker) => match ticker.id() {
+                Some(sub_id) => (sub_id, Some(ticker.msg)),
+                None => return vec![],
+            },
+            KalshiMessage::MarketLifecycle(lifecycle) if lifecycle.msg.is_terminal() => {
+                // Lifecycle messages are identified by their orderbook_delta channel
+                let sub_id = SubscriptionId(format_smolstr!(
+                    "ticker_v2|{}",
+                    lifecycle.msg.market_ticker.to_lowercase()
+                ));
+                (sub_id, None)
+            }
+            _ => return vec![],
+        };
+
+        let market = match self.markets.find_mut(&sub_id) {
+            Ok(market) => market,
+            Err(unidentifiable) => return vec![Err(DataError::from(unidentifiable))],
+        };
+
+        let ticker = match update {
+            Some(update) => {
+                let ticker = market.ticker.get_or_insert_with(|| KalshiTickerData {
+                    market_ticker: update.market_ticker.clone(),
+                    ..Default::default()
+                });
+                ticker.merge(&update);
+                Some(&*ticker)
+            }
+            None => {
+                market.ticker = None;
+                None
+            }
+        };
+
+        let now = Utc::now();
+        market
+            .instruments
+            .iter()
+            .map(|(outcome, instrument)| {
+                Ok(MarketEvent {
+                    time_exchange: now,
+                    time_received: now,
+                    exchange: ExchangeId::Kalshi,
+                    instrument: instrument.clone(),
+                    kind: ticker.map_or_else(
+                        || OrderBookL1::new(now, None, None),
+                        |ticker| ticker.to_order_book_l1(*outcome),
+                    ),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Stateless transformer for Kalshi MarketStatuses streams, sourced from
+/// `market_lifecycle_v2`.
+///
+/// Each lifecycle event that changes a market's status is emitted as a
+/// [`MarketStatus`] once per instrument subscribed to the market. Both outcomes
+/// of a ticker share its status. Markets dropped by
+/// [`Kalshi::unsubscribe_markets`](super::Kalshi::unsubscribe_markets) are
+/// deleted from the subscription and their statuses ignored.
+#[derive(Debug)]
+pub struct KalshiMarketStatusTransformer<InstrumentKey> {
+    markets: Map<Vec<(Outcome, InstrumentKey)>>,
+    /// Market ids dropped by [`Self::unsubscribe`], whose messages are ignored.
+    unsubscribed: FnvHashSet<SubscriptionId>,
+    /// Source of tickers to [`Self::unsubscribe`] (`None` if not connected).
+    unsubscribed_rx: Option<watch::Receiver<FnvHashSet<String>>>,
+    /// Subscription carrying this connection's `market_lifecycle_v2` messages.
+    lifecycle_sid: Option<u64>,
+    /// Sink for unsubscribe commands (`None` if the transformer isn't connected).
+    ws_sink_tx: Option<mpsc::UnboundedSender<WsMessage>>,
+    /// Id of the next command sent over `ws_sink_tx`.
+    next_request_id: u64,
+}
+
+#[async_trait]
+impl<InstrumentKey> ExchangeTransformer<super::Kalshi, InstrumentKey, MarketStatuses>
+    for KalshiMarketStatusTransformer<InstrumentKey>
+where
+    InstrumentKey: Clone + Send,
+{
+    async fn init(
+        instrument_map: Map<InstrumentKey>,
+        _initial_snapshots: &[MarketEvent<InstrumentKey, MarketStatus>],
+        ws_sink_tx: mpsc::UnboundedSender<WsMessage>,
+    ) -> Result<Self, DataError> {
+        Ok(Self::new(instrument_map)
+            .with_ws_sink(ws_sink_tx)
+            .with_unsubscribes(super::Kalshi::unsubscribed_markets()))
+    }
+}
+
+impl<InstrumentKey> KalshiMarketStatusTransformer<InstrumentKey> {
+    /// Construct a transformer, grouping subscriptions to each outcome of one
+    /// ticker as [`KalshiOrderBookTransformer::new`] does.
+    pub fn new(instrument_map: Map<InstrumentKey>) -> Self {
+        Self {
+            markets: group_by_market(instrument_map),
+            unsubscribed: FnvHashSet::default(),
+            unsubscribed_rx: None,
+            lifecycle_sid: None,
+            ws_sink_tx: None,
+            next_request_id: RESUBSCRIBE_REQUEST_ID,
+        }
+    }
+
+    /// Unsubscribe dropped markets over `ws_sink_tx`.
+    pub fn with_ws_sink(mut self, ws_sink_tx: mpsc::UnboundedSender<WsMessage>) -> Self {
+        self.ws_sink_tx = Some(ws_sink_tx);
+        self
+    }
+
+    /// [`Self::unsubscribe`] the tickers received on `unsubscribed_rx` whenever
+    /// it changes.
+    pub fn with_unsubscribes(
+        mut self,
+        unsubscribed_rx: watch::Receiver<FnvHashSet<String>>,
+    ) -> Self {
+        self.unsubscribed_rx = Some(unsubscribed_rx);
+        self
+    }
+
+    /// Drop the markets of `tickers` from this stream, so their statuses are
+    /// ignored, and delete them from the `market_lifecycle_v2` subscription.
+    pub fn unsubscribe(&mut self, tickers: &[String]) {
+        let tickers = normalise_tickers(tickers);
+        let mut removed = Vec::new();
+
+        self.markets.0.retain(|market_id, _| {
+            let Some(ticker) = market_ticker(market_id).filter(|t| tickers.contains(t)) else {
+                return true;
+            };
+            self.unsubscribed.insert(market_id.clone());
+            removed.push(ticker);
+            false
+        });
+
+        if removed.is_empty() {
+            return;
+        }
+        info!(tickers = ?removed, "Kalshi market statuses unsubscribed");
+
+        let (Some(sid), Some(ws_sink_tx)) = (self.lifecycle_sid, &self.ws_sink_tx) else {
+            return;
+        };
+        let command = delete_markets_command(self.next_request_id, sid, removed);
+        self.next_request_id += 1;
+        if ws_sink_tx.send(command).is_err() {
+            warn!(sid, "Kalshi WebSocket sink closed, cannot unsubscribe");
+        }
+    }
+}
+
+impl<InstrumentKey> Transformer for KalshiMarketStatusTransformer<InstrumentKey>
+where
+    InstrumentKey: Clone,
+{
+    type Error = DataError;
+    type Input = KalshiMessage<serde_json::Value>;
+    type Output = MarketEvent<InstrumentKey, MarketStatus>;
+    type OutputIter = Vec<Result<Self::Output, Self::Error>>;
+
+    fn transform(&mut self, input: Self::Input) -> Self::OutputIter {
+        if let Some(tickers) = poll_unsubscribed(&mut self.unsubscribed_rx) {
+            self.unsubscribe(&tickers);
+        }
+
+        let KalshiMessage::MarketLifecycle(lifecycle) = input else {
+            return vec![];
+        };
+        self.lifecycle_sid = Some(lifecycle.sid);
+        let Some(status) = lifecycle.msg.status() else {
+            return vec![];
+        };
+
+        // Lifecycle message ids route to orderbook_delta, so build this channel's id
+        let sub_id = SubscriptionId(format_smolstr!(
+            "{}|{}",
+            KalshiChannel::MARKET_LIFECYCLE.as_ref(),
+            lifecycle.msg.market_ticker.to_lowercase()
+        ));
+        if self.unsubscribed.contains(&sub_id) {
+            return vec![];
+        }
+        let instruments = match self.markets.find(&sub_id) {
+            Ok(instruments) => instruments,
+            Err(unidentifiable) => return vec![Err(DataError::from(unidentifiable))],
+        };
+
+        let now = Utc::now();
+        instruments
+            .iter()
+            .map(|(_, instrument)| {
+                Ok(MarketEvent {
+                    time_exchange: now,
+                    time_received: now,
+                    exchange: ExchangeId::Kalshi,
+                    instrument: instrument.clone(),
+                    kind: status,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Tickers most recently published on `unsubscribed_rx`, if they changed since
+/// the last poll.
+fn poll_unsubscribed(
+    unsubscribed_rx: &mut Option<watch::Receiver<FnvHashSet<String>>>,
+) -> Option<Vec<String>> {
+    let unsubscribed_rx = unsubscribed_rx.as_mut()?;
+    if !unsubscribed_rx.has_changed().unwrap_or(false) {
+        return None;
+    }
+    Some(unsubscribed_rx.borrow_and_update().iter().cloned().collect())
+}
+
+/// Uppercase `tickers` without any [`KalshiMarket`] outcome suffix.
+fn normalise_tickers(tickers: &[String]) -> FnvHashSet<String> {
+    tickers
+        .iter()
+        .map(|ticker| KalshiMarket::new(ticker.as_str()).ticker().to_uppercase())
+        .collect()
+}
+
+/// Uppercase ticker of a market [`SubscriptionId`] built by [`group_by_market`].
+fn market_ticker(market_id: &SubscriptionId) -> Option<String> {
+    market_id
+        .0
+        .split_once('|')
+        .map(|(_, ticker)| ticker.to_uppercase())
+}
+
+/// Kalshi command deleting `tickers` from subscription `sid`.
+fn delete_markets_command(id: u64, sid: u64, tickers: Vec<String>) -> WsMessage {
+    let command = json!({
+        "id": id,
+        "cmd": "update_subscription",
+        "params": {
+            "sids": [sid],
+            "market_tickers": tickers,
+            "action": "delete_markets"
+        }
+    });
+    WsMessage::text(command.to_string())
+}
+
+/// Group subscribed instruments by the market [`SubscriptionId`] Kalshi identifies
+/// its messages with, ie/ without the [`KalshiMarket`] outcome suffix.
+fn group_by_market<InstrumentKey>(
+    instrument_map: Map<InstrumentKey>,
+) -> Map<Vec<(Outcome, InstrumentKey)>> {
+    let mut markets: Map<Vec<(Outcome, InstrumentKey)>> = Map(FnvHashMap::default());
+    for (sub_id, instrument) in instrument_map.0 {
+        let (market_id, outcome) = match sub_id.0.split_once('|') {
+            Some((channel, market)) => {
+                let market = KalshiMarket::new(market);
+                let market_id = SubscriptionId::from(format!("{channel}|{}", market.ticker()));
+                (market_id, market.outcome())
+            }
+            None => (sub_id, Outcome::Yes),
+        };
+
+        markets
+            .0
+            .entry(market_id)
+            .or_default()
+            .push((outcome, instrument));
+    }
+
+    for instruments in markets.0.values_mut() {
+        instruments.sort_by_key(|(outcome, _)| *outcome);
+    }
+
+    markets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::books::{Level, OrderBook};
+    use rust_decimal_macros::dec;
+    use smol_str::SmolStr;
+
+    fn transformer() -> KalshiOrderBookTransformer<SmolStr> {
+        KalshiOrderBookTransformer::new(Map::from_iter([(
+            SubscriptionId::from("orderbook_delta|kxtest"),
+            SmolStr::new("kxtest"),
+        )]))
+    }
+
+    fn message(json: &str) -> KalshiMessage<serde_json::Value> {
+        serde_json::from_str(json).unwrap()
+    }
+
+    fn snapshot(seq: u64) -> KalshiMessage<serde_json::Value> {
+        message(&format!(
+            r#"{{"type": "orderbook_snapshot", "sid": 1, "seq": {seq},
+                "msg": {{"market_ticker": "KXTEST", "yes": [[40, 100]], "no": [[55, 150]]}}}}"#
+        ))
+    }
+
+    fn delta(seq: u64, price: u32, delta: i32, side: &str) -> KalshiMessage<serde_json::Value> {
+        message(&format!(
+            r#"{{"type": "orderbook_delta", "sid": 1, "seq": {seq},
+                "msg": {{"market_ticker": "KXTEST", "price": {price}, "delta": {delta},
+                "side": "{side}"}}}}"#
+        ))
+    }
+
+    fn apply(
+        transformer: &mut KalshiOrderBookTransformer<SmolStr>,
+        book: &mut OrderBook,
+        input: KalshiMessage<serde_json::Value>,
+    ) -> usize {
+        let events = transformer.transform(input);
+        for event in &events {
+            book.update(&event.as_ref().unwrap().kind);
+        }
+        events.len()
+    }
+
+    #[test]
+    fn test_snapshot_then_deltas() {
+        let mut transformer = transformer();
+        let mut book = OrderBook::default();
+
+        let events = transformer.transform(snapshot(1));
+        assert!(matches!(
+            events.as_slice(),
+            [Ok(MarketEvent { kind: OrderBookEvent::Snapshot(_), .. })]
+        ));
+        book.update(&events[0].as_ref().unwrap().kind);
+        assert_eq!(book.bids().best(), Some(&Level::new(dec!(0.40), dec!(100))));
+        assert_eq!(book.asks().best(), Some(&Level::new(dec!(0.45), dec!(150))));
+
+        // New better YES bid
+        let events = transformer.transform(delta(2, 42, 30, "yes"));
+        assert!(matches!(
+            events.as_slice(),
+            [Ok(MarketEvent { kind: OrderBookEvent::Update(_), .. })]
+        ));
+        book.update(&events[0].as_ref().unwrap().kind);
+        assert_eq!(book.bids().best(), Some(&Level::new(dec!(0.42), dec!(30))));
+
+        // Partially consume the NO bid, ie/ the YES ask
+        assert_eq!(apply(&mut transformer, &mut book, delta(3, 55, -50, "no")), 1);
+        assert_eq!(book.asks().best(), Some(&Level::new(dec!(0.45), dec!(100))));
+
+        // Remove the new YES bid entirely
+        assert_eq!(apply(&mut transformer, &mut book, delta(4, 42, -30, "yes")), 1);
+        assert_eq!(book.bids().best(), Some(&Level::new(dec!(0.40), dec!(100))));
+        assert_eq!(book.sequence(), 4);
+    }
+
+    #[test]
+    fn test_stale_delta_ignored() {
+        let mut transformer = transformer();
+        let mut book = OrderBook::default();
+
+        assert_eq!(apply(&mut transformer, &mut book, snapshot(5)), 1);
+
+        // At and behind the snapshot sequence
+        assert!(transformer.transform(delta(5, 40, -100, "yes")).is_empty());
+        assert!(transformer.transform(delta(3, 40, -100, "yes")).is_empty());
+        assert_eq!(book.bids().best(), Some(&Level::new(dec!(0.40), dec!(100))));
+
+        // Next delta still applies against the untouched book
+        assert_eq!(apply(&mut transformer, &mut book, delta(6, 40, -60, "yes")), 1);
+        assert_eq!(book.bids().best(), Some(&Level::new(dec!(0.40), dec!(40))));
+    }
+
+    #[test]
+    fn test_sequence_gap_resubscribes() {
+        let (ws_sink_tx, mut ws_sink_rx) = mpsc::unbounded_channel();
+        let mut transformer = transformer().with_ws_sink(ws_sink_tx);
+        let mut book = OrderBook::default();
+
+        assert_eq!(apply(&mut transformer, &mut book, snapshot(1)), 1);
+        assert_eq!(apply(&mut transformer, &mut book, delta(2, 42, 30, "yes")), 1);
+
+        // seq 3 was missed: the gap is reported and the delta is not applied
+        let events = transformer.transform(delta(4, 42, -30, "yes"));
+        assert!(matches!(
+            events.as_slice(),
+            [Err(DataError::InvalidSequence {
+                prev_last_update_id: 2,
+                first_update_id: 4
+            })]
+        ));
+
+        // The old subscription is replaced by a fresh one for the same ticker
+        let command = |message: Option<WsMessage>| -> serde_json::Value {
+            let Some(WsMessage::Text(text)) = message else {
+                panic!("expected text command, got {message:?}");
+            };
+            serde_json::from_str(text.as_str()).unwrap()
+        };
+        let unsubscribe = command(ws_sink_rx.try_recv().ok());
+        assert_eq!(unsubscribe["cmd"], "unsubscribe");
+        assert_eq!(unsubscribe["params"]["sids"], json!([1]));
+        let subscribe = command(ws_sink_rx.try_recv().ok());
+        assert_eq!(subscribe["cmd"], "subscribe");
+        assert_eq!(subscribe["params"]["channels"], json!(["orderbook_delta"]));
+        assert_eq!(subscribe["params"]["market_tickers"], json!(["KXTEST"]));
+
+        // The book is withheld until the new subscription's snapshot arrives
+        assert!(transformer.transform(delta(5, 40, -10, "yes")).is_empty());
+        let fresh = message(
+            r#"{"type": "orderbook_snapshot", "sid": 2, "seq": 1,
+                "msg": {"market_ticker": "KXTEST", "yes": [[38, 10]], "no": []}}"#,
+        );
+        assert_eq!(apply(&mut transformer, &mut book, fresh), 1);
+        assert_eq!(book.bids().best(), Some(&Level::new(dec!(0.38), dec!(10))));
+
+        let next = message(
+            r#"{"type": "orderbook_delta", "sid": 2, "seq": 2,
+                "msg": {"market_ticker": "KXTEST", "price": 39, "delta": 5, "side": "yes"}}"#,
+        );
+        assert_eq!(apply(&mut transformer, &mut book, next), 1);
+        assert_eq!(book.bids().best(), Some(&Level::new(dec!(0.39), dec!(5))));
+    }
+
+    #[test]
+    fn test_unsubscribe_deletes_markets_and_ignores_messages() {
+        let (ws_sink_tx, mut ws_sink_rx) = mpsc::unbounded_channel();
+        let (unsubscribed_tx, unsubscribed_rx) = watch::channel(FnvHashSet::default());
+        let mut transformer = transformer()
+            .with_ws_sink(ws_sink_tx)
+            .with_unsubscribes(unsubscribed_rx);
+        let mut book = OrderBook::default();
+
+        assert_eq!(apply(&mut transformer, &mut book, snapshot(1)), 1);
+        let lifecycle = message(
+            r#"{"type": "market_lifecycle_v2", "sid": 2, "seq": 1,
+                "msg": {"market_ticker": "KXTEST", "event_type": "close_date_updated"}}"#,
+        );
+        transformer.transform(lifecycle);
+
+        // Dropped tickers are picked up with the next message, which is ignored
+        unsubscribed_tx.send_modify(|tickers| {
+            tickers.insert("KXTEST".to_string());
+        });
+        assert!(transformer.transform(delta(2, 42, 30, "yes")).is_empty());
+
+        // The market is deleted from both its orderbook and lifecycle subscriptions
+        let command = |message: Option<WsMessage>| -> serde_json::Value {
+            let Some(WsMessage::Text(text)) = message else {
+                panic!("expected text command, got {message:?}");
+            };
+            serde_json::from_str(text.as_str()).unwrap()
+        };
+        assert_eq!(
+            command(ws_sink_rx.try_recv().ok()),
+            json!({
+                "id": 1000,
+                "cmd": "update_subscription",
+                "params": {"sids": [1], "market_tickers": ["KXTEST"], "action": "delete_markets"}
+            })
+        );
+        assert_eq!(
+            command(ws_sink_rx.try_recv().ok()),
+            json!({
+                "id": 1001,
+                "cmd": "update_subscription",
+                "params": {"sids": [2], "market_tickers": ["KXTEST"], "action": "delete_markets"}
+            })
+        );
+        assert!(ws_sink_rx.try_recv().is_err());
+
+        // Messages still in flight produce neither events nor errors
+        let settled = message(
+            r#"{"type": "market_lifecycle_v2", "sid": 2, "seq": 2,
+                "msg": {"market_ticker": "KXTEST", "event_type": "settled"}}"#,
+        );
+        assert!(transformer.transform(settled).is_empty());
+        assert!(transformer.transform(snapshot(3)).is_empty());
+        assert!(transformer.transform(delta(4, 40, -10, "yes")).is_empty());
+
+        // Unsubscribing again is a no-op
+        transformer.unsubscribe(&["KXTEST".to_string()]);
+        assert!(ws_sink_rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_delta_before_snapshot_ignored() {
+        let mut transformer = transformer();
+        assert!(transformer.transform(delta(1, 40, 10, "yes")).is_empty());
+    }
+
+    #[test]
+    fn test_outcomes_of_one_ticker_mirror() {
+        let mut transformer = KalshiOrderBookTransformer::new(Map::from_iter([
+            (
+                SubscriptionId::from("orderbook_delta|kxtest"),
+                SmolStr::new("kxtest_yes"),
+            ),
+            (
+                SubscriptionId::from("orderbook_delta|kxtest_no"),
+                SmolStr::new("kxtest_no"),
+            ),
+        ]));
+
+        let books = |events: Vec<Result<MarketEvent<SmolStr, OrderBookEvent>, DataError>>| {
+            events
+                .into_iter()
+                .map(|event| {
+                    let event = event.unwrap();
+                    let mut book = OrderBook::default();
+                    book.update(&event.kind);
+                    (event.instrument, book)
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let emitted = books(transformer.transform(snapshot(1)));
+        let [(yes_key, yes), (no_key, no)] = emitted.as_slice() else {
+            panic!("expected one book per outcome, got {emitted:?}");
+        };
+        assert_eq!(yes_key, "kxtest_yes");
+        assert_eq!(no_key, "kxtest_no");
+
+        // YES bid 40c / ask 45c mirrors NO bid 55c / ask 60c
+        assert_eq!(yes.bids().best(), Some(&Level::new(dec!(0.40), dec!(100))));
+        assert_eq!(yes.asks().best(), Some(&Level::new(dec!(0.45), dec!(150))));
+        assert_eq!(no.bids().best(), Some(&Level::new(dec!(0.55), dec!(150))));
+        assert_eq!(no.asks().best(), Some(&Level::new(dec!(0.60), dec!(100))));
+
+        // A YES delta is a YES bid and a NO ask
+        let events = transformer.transform(delta(2, 42, 30, "yes"));
+        let [Ok(yes_update), Ok(no_update)] = events.as_slice() else {
+            panic!("expected one update per outcome");
+        };
+        let (OrderBookEvent::Update(yes), OrderBookEvent::Update(no)) =
+            (&yes_update.kind, &no_update.kind)
+        else {
+            panic!("expected updates");
+        };
+        assert_eq!(yes.bids().levels(), &[Level::new(dec!(0.42), dec!(30))]);
+        assert!(yes.asks().levels().is_empty());
+        assert!(no.bids().levels().is_empty());
+        assert_eq!(no.asks().levels(), &[Level::new(dec!(0.58), dec!(30))]);
+    }
+
+    #[test]
+    fn test_trades_per_outcome() {
+        use barter_instrument::Side;
+
+        let mut transformer = KalshiTradesTransformer::new(Map::from_iter([
+            (SubscriptionId::from("trade|kxtest"), SmolStr::new("kxtest_yes")),
+            (SubscriptionId::from("trade|kxtest_no"), SmolStr::new("kxtest_no")),
+        ]));
+
+        let events = transformer.transform(message(
+            r#"{"type": "trade", "sid": 3, "seq": 9,
+                "msg": {"market_ticker": "KXTEST", "yes_price": 36, "no_price": 64,
+                "count": 25, "taker_side": "yes"}}"#,
+        ));
+        let [Ok(yes), Ok(no)] = events.as_slice() else {
+            panic!("expected one trade per outcome, got {events:?}");
+        };
+
+        assert_eq!(yes.instrument, "kxtest_yes");
+        assert_eq!(yes.kind.id, "3-9");
+        assert!((yes.kind.price - 0.36).abs() < f64::EPSILON);
+        assert!((yes.kind.amount - 25.0).abs() < f64::EPSILON);
+        assert_eq!(yes.kind.side, Side::Buy);
+
+        assert_eq!(no.instrument, "kxtest_no");
+        assert!((no.kind.price - 0.64).abs() < f64::EPSILON);
+        assert_eq!(no.kind.side, Side::Sell);
+
+        // Orderbook messages on the same connection are ignored
+        assert!(transformer.transform(snapshot(1)).is_empty());
+    }
+
+    #[test]
+    fn test_ticker_v2_to_l1_per_outcome() {
+        use crate::books::Level;
+        use rust_decimal_macros::dec;
+
+        let mut transformer = KalshiTickerTransformer::new(Map::from_iter([
+            (
+                SubscriptionId::from("ticker_v2|kxtest"),
+                SmolStr::new("kxtest_yes"),
+            ),
+            (
+                SubscriptionId::from("ticker_v2|kxtest_no"),
+                SmolStr::new("kxtest_no"),
+            ),
+        ]));
+
+        let events = transformer.transform(message(
+            r#"{"type": "ticker_v2", "sid": 2, "seq": 1,
+                "msg": {"market_ticker": "KXTEST", "price": 48, "yes_bid": 47,
+                "yes_ask": 49, "volume": 10, "open_interest": 5, "ts": 1738281600}}"#,
+        ));
+        let [Ok(yes), Ok(no)] = events.as_slice() else {
+            panic!("expected one L1 per outcome, got {events:?}");
+        };
+        assert_eq!(yes.instrument, "kxtest_yes");
+        assert_eq!(yes.kind.best_bid, Some(Level::new(dec!(0.47), dec!(0))));
+        assert_eq!(yes.kind.best_ask, Some(Level::new(dec!(0.49), dec!(0))));
+        assert_eq!(no.instrument, "kxtest_no");
+        assert_eq!(no.kind.best_bid, Some(Level::new(dec!(0.51), dec!(0))));
+        assert_eq!(no.kind.best_ask, Some(Level::new(dec!(0.53), dec!(0))));
+
+        // Partial updates keep the unchanged side
+        let events = transformer.transform(message(
+            r#"{"type": "ticker_v2", "sid": 2, "seq": 2,
+                "msg": {"market_ticker": "KXTEST", "yes_bid": 48}}"#,
+        ));
+        let Some(Ok(yes)) = events.first() else {
+            panic!("expected L1 update, got {events:?}");
+        };
+        assert_eq!(yes.kind.best_bid, Some(Level::new(dec!(0.48), dec!(0))));
+        assert_eq!(yes.kind.best_ask, Some(Level::new(dec!(0.49), dec!(0))));
+
+        // Terminal lifecycle events clear the top of book
+        let events = transformer.transform(message(
+            r#"{"type": "market_lifecycle_v2", "sid": 3, "seq": 1,
+                "msg": {"market_ticker": "KXTEST", "event_type": "settled"}}"#,
+        ));
+        assert_eq!(events.len(), 2);
+        for event in events {
+            let event = event.unwrap();
+            assert_eq!(event.kind.best_bid, None);
+            assert_eq!(event.kind.best_ask, None);
+        }
+    }
+
+    #[test]
+    fn test_terminal_lifecycle_clears_book() {
+        let mut transformer = transformer();
+        let mut book = OrderBook::default();
+        assert_eq!(apply(&mut transformer, &mut book, snapshot(1)), 1);
+
+        let lifecycle = message(
+            r#"{"type": "market_lifecycle_v2", "sid": 2, "seq": 1,
+                "msg": {"market_ticker": "KXTEST", "event_type": "settled"}}"#,
+        );
+        assert_eq!(apply(&mut transformer, &mut book, lifecycle), 1);
+        assert!(book.bids().best().is_none());
+        assert!(book.asks().best().is_none());
+    }
+
+    #[test]
+    fn test_market_status_per_instrument() {
+        let mut transformer = KalshiMarketStatusTransformer::new(Map::from_iter([
+            (
+                SubscriptionId::from("market_lifecycle_v2|kxtest"),
+                SmolStr::new("yes"),
+            ),
+            (
+                SubscriptionId::from("market_lifecycle_v2|kxtest_no"),
+                SmolStr::new("no"),
+            ),
+        ]));
+
+        let settled = message(
+            r#"{"type": "market_lifecycle_v2", "sid": 2, "seq": 1,
+                "msg": {"market_ticker": "KXTEST", "event_type": "settled"}}"#,
+        );
+        let mut events = transformer
+            .transform(settled)
+            .into_iter()
+            .map(|event| event.unwrap())
+            .map(|event| (event.instrument, event.kind))
+            .collect::<Vec<_>>();
+        events.sort();
+        assert_eq!(
+            events,
+            vec![
+                (SmolStr::new("no"), MarketStatus::Settled),
+                (SmolStr::new("yes"), MarketStatus::Settled),
+            ]
+        );
+
+        // Events that don't change the status, and other channels, are ignored
+        let updated = message(
+            r#"{"type": "market_lifecycle_v2", "sid": 2, "seq": 2,
+                "msg": {"market_ticker": "KXTEST", "event_type": "close_date_updated"}}"#,
+        );
+        assert!(transformer.transform(updated).is_empty());
+        assert!(transformer.transform(snapshot(3)).is_empty());
+    }
+
+    #[test]
+    fn test_market_status_unsubscribe() {
+        let (ws_sink_tx, mut ws_sink_rx) = mpsc::unbounded_channel();
+        let mut transformer = KalshiMarketStatusTransformer::new(Map::from_iter([
+            (
+                SubscriptionId::from("market_lifecycle_v2|kxtest"),
+                SmolStr::new("yes"),
+            ),
+            (
+                SubscriptionId::from("market_lifecycle_v2|kxtest_no"),
+                SmolStr::new("no"),
+            ),
+            (
+                SubscriptionId::from("market_lifecycle_v2|kxother"),
+                SmolStr::new("other"),
+            ),
+        ]))
+        .with_ws_sink(ws_sink_tx);
+
+        let determined = message(
+            r#"{"type": "market_lifecycle_v2", "sid": 2, "seq": 1,
+                "msg": {"market_ticker": "KXTEST", "event_type": "determined"}}"#,
+        );
+        assert_eq!(transformer.transform(determined).len(), 2);
+
+        // Either outcome's name drops the whole ticker
+        transformer.unsubscribe(&["kxtest_no".to_string()]);
+        let Ok(WsMessage::Text(text)) = ws_sink_rx.try_recv() else {
+            panic!("expected unsubscribe command");
+        };
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(text.as_str()).unwrap(),
+            json!({
+                "id": 1000,
+                "cmd": "update_subscription",
+                "params": {"sids": [2], "market_tickers": ["KXTEST"], "action": "delete_markets"}
+            })
+        );
+
+        let settled = message(
+            r#"{"type": "market_lifecycle_v2", "sid": 2, "seq": 2,
+                "msg": {"market_ticker": "KXTEST", "event_type": "settled"}}"#,
+        );
+        assert!(transformer.transform(settled).is_empty());
+
+        // Other markets on the subscription are unaffected
+        let other = message(
+            r#"{"type": "market_lifecycle_v2", "sid": 2, "seq": 3,
+                "msg": {"market_ticker": "KXOTHER", "event_type": "settled"}}"#,
+        );
+        assert_eq!(transformer.transform(other).len(), 1);
+    }
+}
@@ -123,6 +123,14 @@ where
         None
     }
 
+    /// Maximum [`Duration`] to wait for any inbound frame (including pings and pongs) before
+    /// the connection is considered dead and the [`MarketStream`] is re-initialised.
+    ///
+    /// Defaults to `None`, meaning that silent connections are never timed out.
+    fn idle_timeout() -> Option<Duration> {
+        None
+    }
+
     /// Defines how to translate a collection of [`ExchangeSub`]s into the [`WsMessage`]
     /// subscription payloads sent to the exchange server.
     fn requests(exchange_subs: Vec<ExchangeSub<Self::Channel, Self::Market>>) -> Vec<WsMessage>;
@@ -139,6 +147,17 @@ where
     fn subscription_timeout() -> Duration {
         DEFAULT_SUBSCRIPTION_TIMEOUT
     }
+
+    /// Maximum number of distinct markets subscribed to over one
+    /// [`WebSocket`](barter_integration::protocol::websocket::WebSocket) connection.
+    ///
+    /// Larger subscription batches are split across several connections, see
+    /// [`connection_batches`](crate::streams::builder::connection_batches).
+    ///
+    /// Defaults to `None`, meaning that all `Subscription`s share one connection.
+    fn max_markets_per_connection() -> Option<usize> {
+        None
+    }
 }
 
 /// Used when an exchange has servers different
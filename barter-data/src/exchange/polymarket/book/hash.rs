@@ -0,0 +1,129 @@
+use crate::exchange::polymarket::{
+    message::{PolymarketLevel, PolymarketPriceBook},
+    transformer::PolymarketBookError,
+};
+use serde::Serialize;
+use sha1::{Digest, Sha1};
+
+/// Fields of a [`PolymarketPriceBook`] covered by its hash, in the order Polymarket
+/// serialises them.
+#[derive(Serialize)]
+struct HashedBook<'a> {
+    market: Option<&'a str>,
+    asset_id: &'a str,
+    timestamp: Option<String>,
+    bids: &'a [PolymarketLevel],
+    asks: &'a [PolymarketLevel],
+    min_order_size: Option<&'a str>,
+    neg_risk: Option<bool>,
+    tick_size: Option<String>,
+    hash: &'static str,
+}
+
+/// Compute the hash Polymarket attaches to `book`.
+///
+/// The hash is the hex SHA-1 of the book serialised as compact JSON with an empty `hash`
+/// field, with levels in the order they were received. Fields missing from `book`
+/// serialise as `null`.
+///
+/// See docs: <https://github.com/Polymarket/py-clob-client/blob/main/py_clob_client/utilities.py>
+pub fn book_hash(book: &PolymarketPriceBook) -> String {
+    let hashed = HashedBook {
+        market: book.market.as_deref(),
+        asset_id: &book.asset_id,
+        timestamp: book.timestamp.map(|timestamp| timestamp.to_string()),
+        bids: &book.bids,
+        asks: &book.asks,
+        min_order_size: book.min_order_size.as_deref(),
+        neg_risk: book.neg_risk,
+        tick_size: book.tick_size.map(|tick_size| tick_size.to_string()),
+        hash: "",
+    };
+    let json = serde_json::to_vec(&hashed).expect("HashedBook serialisation is infallible");
+    format!("{:x}", Sha1::digest(json))
+}
+
+/// Check `book` against the hash Polymarket sent with it, if any.
+pub fn verify_book_hash(book: &PolymarketPriceBook) -> Result<(), PolymarketBookError> {
+    let Some(expected) = &book.hash else {
+        return Ok(());
+    };
+
+    let actual = book_hash(book);
+    if actual == *expected {
+        Ok(())
+    } else {
+        Err(PolymarketBookError::HashMismatch {
+            expected: expected.clone(),
+            actual,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // REST book payload, hashed with the py-clob-client scheme
+    const BOOK: &str = r#"{
+        "market": "0x5f65177b394277fd294cd75650044e32ba009a95022d88a0c1d565897d72f8f1",
+        "asset_id": "71321045679252212594626385532706912750332728571942532289631379312455583992563",
+        "timestamp": "1757908892351",
+        "hash": "27af3cdec23d9e8061fe2100c8b941b76751e159",
+        "bids": [
+            {"price": "0.48", "size": "30"},
+            {"price": "0.49", "size": "20"},
+            {"price": "0.5", "size": "15"}
+        ],
+        "asks": [
+            {"price": "0.52", "size": "25"},
+            {"price": "0.53", "size": "60"}
+        ],
+        "min_order_size": "5",
+        "neg_risk": false,
+        "tick_size": "0.01"
+    }"#;
+
+    fn book() -> PolymarketPriceBook {
+        serde_json::from_str(BOOK).unwrap()
+    }
+
+    #[test]
+    fn test_book_hash() {
+        let book = book();
+        assert_eq!(book_hash(&book), "27af3cdec23d9e8061fe2100c8b941b76751e159");
+        assert_eq!(verify_book_hash(&book), Ok(()));
+    }
+
+    #[test]
+    fn test_book_hash_websocket_fields() {
+        // WebSocket book events omit the REST-only fields
+        let mut book = book();
+        book.min_order_size = None;
+        book.neg_risk = None;
+        book.tick_size = None;
+        book.hash = Some("07f329bbd7fee9295b87805420c5c22885f6fb24".to_string());
+        assert_eq!(verify_book_hash(&book), Ok(()));
+    }
+
+    #[test]
+    fn test_verify_book_hash_mismatch() {
+        // Mutated level size
+        let mut book = book();
+        book.asks[1].size = "61".to_string();
+        assert!(matches!(
+            verify_book_hash(&book),
+            Err(PolymarketBookError::HashMismatch { expected, .. })
+                if expected == "27af3cdec23d9e8061fe2100c8b941b76751e159"
+        ));
+
+        // Missing level
+        let mut book = self::book();
+        book.bids.pop();
+        assert!(verify_book_hash(&book).is_err());
+
+        // Books without a hash are not checked
+        book.hash = None;
+        assert_eq!(verify_book_hash(&book), Ok(()));
+    }
+}
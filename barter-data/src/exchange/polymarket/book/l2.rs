@@ -1,15 +1,38 @@
 use crate::{
+    Identifier, SnapshotFetcher,
     books::OrderBook,
     event::{MarketEvent, MarketIter},
-    exchange::polymarket::{channel::PolymarketChannel, message::{PolymarketMessage, PolymarketPriceBook}},
-    subscription::book::OrderBookEvent,
+    exchange::polymarket::{
+        Polymarket,
+        channel::PolymarketChannel,
+        market::PolymarketMarket,
+        book::hash::verify_book_hash,
+        message::{PolymarketMessage, PolymarketPriceBook},
+    },
+    instrument::InstrumentData,
+    subscription::{
+        Subscription,
+        book::{OrderBookEvent, OrderBooksL2},
+    },
 };
 use barter_instrument::exchange::ExchangeId;
-use barter_integration::subscription::SubscriptionId;
+use barter_integration::{error::SocketError, subscription::SubscriptionId};
 use chrono::{DateTime, Utc};
 use derive_more::Constructor;
+use fnv::FnvHashMap;
 use serde::Deserialize;
+use serde_json::json;
 use smol_str::format_smolstr;
+use std::future::Future;
+use tracing::warn;
+
+/// [`Polymarket`] CLOB HTTP batch OrderBook L2 snapshot url.
+///
+/// See docs: <https://docs.polymarket.com/api-reference/orderbook/get-multiple-order-books-summaries-by-request>
+pub const HTTP_BOOK_L2_SNAPSHOT_URL_POLYMARKET: &str = "https://clob.polymarket.com/books";
+
+/// Maximum number of token ids in one [`HTTP_BOOK_L2_SNAPSHOT_URL_POLYMARKET`] request.
+pub const POLYMARKET_BOOKS_BATCH_LIMIT: usize = 500;
 
 /// Metadata for managing a Polymarket OrderBook L2 stream.
 #[derive(Debug, Constructor)]
@@ -17,6 +40,129 @@ pub struct PolymarketOrderBookL2Meta<InstrumentKey> {
     pub key: InstrumentKey,
 }
 
+/// [`SnapshotFetcher`] that requests every subscribed [`Polymarket`] token's book from the
+/// CLOB batch `/books` endpoint, in chunks of at most [`POLYMARKET_BOOKS_BATCH_LIMIT`] tokens.
+///
+/// The snapshots seed the
+/// [`PolymarketOrderBookTransformer`](super::super::transformer::PolymarketOrderBookTransformer)
+/// and are emitted ahead of any WebSocket events, so `price_change` deltas received after a
+/// (re)connect apply to a populated book. A chunk whose request fails is skipped with a
+/// warning, leaving its tokens to the WebSocket snapshot sent on subscription.
+#[derive(Debug)]
+pub struct PolymarketOrderBooksL2SnapshotFetcher;
+
+impl SnapshotFetcher<Polymarket, OrderBooksL2> for PolymarketOrderBooksL2SnapshotFetcher {
+    fn fetch_snapshots<Instrument>(
+        subscriptions: &[Subscription<Polymarket, Instrument, OrderBooksL2>],
+    ) -> impl Future<Output = Result<Vec<MarketEvent<Instrument::Key, OrderBookEvent>>, SocketError>>
+    + Send
+    where
+        Instrument: InstrumentData,
+        Subscription<Polymarket, Instrument, OrderBooksL2>: Identifier<PolymarketMarket>,
+    {
+        let markets = subscriptions
+            .iter()
+            .map(|sub| (sub.instrument.key().clone(), sub.id()))
+            .collect::<Vec<_>>();
+
+        async move {
+            Ok(fetch_book_snapshots(HTTP_BOOK_L2_SNAPSHOT_URL_POLYMARKET, markets).await)
+        }
+    }
+}
+
+/// Fetch the book of every token in `markets` from the batch books endpoint at `url`,
+/// returning a snapshot for each `(instrument, market)` whose book was fetched.
+async fn fetch_book_snapshots<InstrumentKey>(
+    url: &str,
+    markets: Vec<(InstrumentKey, PolymarketMarket)>,
+) -> Vec<MarketEvent<InstrumentKey, OrderBookEvent>>
+where
+    InstrumentKey: Clone,
+{
+    let client = reqwest::Client::new();
+
+    let mut token_ids = markets
+        .iter()
+        .map(|(_, market)| market.token_id())
+        .collect::<Vec<_>>();
+    token_ids.sort();
+    token_ids.dedup();
+
+    let mut books = FnvHashMap::default();
+    for chunk in token_ids.chunks(POLYMARKET_BOOKS_BATCH_LIMIT) {
+        match fetch_books(&client, url, chunk).await {
+            Ok(fetched) => books.extend(
+                fetched
+                    .into_iter()
+                    .filter(hash_verified)
+                    .map(|book| (book.asset_id.clone(), book)),
+            ),
+            Err(error) => {
+                warn!(
+                    tokens = chunk.len(),
+                    %error,
+                    "failed to fetch Polymarket book snapshots, skipping"
+                );
+            }
+        }
+    }
+
+    markets
+        .into_iter()
+        .filter_map(|(instrument, market)| {
+            let book = books.get(market.token_id())?.clone();
+            Some(MarketEvent::from((ExchangeId::Polymarket, instrument, book)))
+        })
+        .collect()
+}
+
+/// Whether `book` passes hash verification, if enabled, logging any mismatch.
+fn hash_verified(book: &PolymarketPriceBook) -> bool {
+    if !Polymarket::verify_book_hashes() {
+        return true;
+    }
+    verify_book_hash(book)
+        .inspect_err(|error| {
+            warn!(
+                asset_id = %book.asset_id,
+                %error,
+                "Polymarket book snapshot failed hash check, skipping"
+            )
+        })
+        .is_ok()
+}
+
+/// Fetch the books of one chunk of `token_ids`.
+async fn fetch_books(
+    client: &reqwest::Client,
+    url: &str,
+    token_ids: &[&str],
+) -> Result<Vec<PolymarketPriceBook>, SocketError> {
+    let body = token_ids
+        .iter()
+        .map(|token_id| json!({ "token_id": token_id }))
+        .collect::<Vec<_>>();
+
+    let response = client
+        .post(url)
+        .json(&body)
+        .send()
+        .await
+        .map_err(SocketError::Http)?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(SocketError::HttpResponse(status, body));
+    }
+
+    response
+        .json::<Vec<PolymarketPriceBook>>()
+        .await
+        .map_err(SocketError::Http)
+}
+
 impl<InstrumentKey> From<(ExchangeId, InstrumentKey, PolymarketPriceBook)>
     for MarketIter<InstrumentKey, OrderBookEvent>
 {
@@ -116,6 +262,10 @@ mod tests {
                 PolymarketLevel { price: "0.46".to_string(), size: "150.0".to_string() },
                 PolymarketLevel { price: "0.47".to_string(), size: "250.0".to_string() },
             ],
+            tick_size: None,
+            min_order_size: None,
+            neg_risk: None,
+            hash: None,
         };
 
         let event: MarketEvent<String, OrderBookEvent> =
@@ -140,4 +290,150 @@ mod tests {
             _ => panic!("Expected Snapshot"),
         }
     }
+
+    mod snapshot_fetcher {
+        use super::*;
+        use std::sync::{Arc, Mutex};
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        /// Token ids of each request received by [`mock_books_server`].
+        type Requests = Arc<Mutex<Vec<Vec<String>>>>;
+
+        /// Serve `POST /books`, returning a book for every requested token except `missing`,
+        /// and return the endpoint url and the requests received.
+        async fn mock_books_server() -> (String, Requests) {
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let url = format!("http://{}/books", listener.local_addr().unwrap());
+            let requests = Requests::default();
+
+            let received = Arc::clone(&requests);
+            tokio::spawn(async move {
+                loop {
+                    let (mut socket, _) = listener.accept().await.unwrap();
+                    let mut request = Vec::new();
+                    let mut buffer = [0; 8192];
+                    let header_end = loop {
+                        let read = socket.read(&mut buffer).await.unwrap();
+                        request.extend_from_slice(&buffer[..read]);
+                        if let Some(end) = request.windows(4).position(|w| w == b"\r\n\r\n") {
+                            break end + 4;
+                        }
+                    };
+
+                    let headers = String::from_utf8_lossy(&request[..header_end]).to_lowercase();
+                    let content_length = headers
+                        .lines()
+                        .find_map(|line| line.strip_prefix("content-length: "))
+                        .map(|length| length.trim().parse::<usize>().unwrap())
+                        .unwrap();
+                    while request.len() < header_end + content_length {
+                        let read = socket.read(&mut buffer).await.unwrap();
+                        request.extend_from_slice(&buffer[..read]);
+                    }
+
+                    let body: Vec<serde_json::Value> =
+                        serde_json::from_slice(&request[header_end..]).unwrap();
+                    let token_ids = body
+                        .iter()
+                        .map(|token| token["token_id"].as_str().unwrap().to_string())
+                        .collect::<Vec<_>>();
+
+                    let books = token_ids
+                        .iter()
+                        .filter(|token_id| *token_id != "missing")
+                        .map(|token_id| {
+                            // Only the corrupt book carries a (wrong) hash
+                            let hash = (token_id == "corrupt").then_some("0xabc");
+                            json!({
+                                "market": "0x5678efgh",
+                                "asset_id": token_id,
+                                "timestamp": "1706313600000",
+                                "hash": hash,
+                                "bids": [
+                                    {"price": "0.44", "size": "200"},
+                                    {"price": "0.455", "size": "1234.56"}
+                                ],
+                                "asks": [
+                                    {"price": "0.47", "size": "250"},
+                                    {"price": "0.46", "size": "0.5"}
+                                ],
+                                "min_order_size": "5",
+                                "tick_size": "0.001"
+                            })
+                        })
+                        .collect::<Vec<_>>();
+                    received.lock().unwrap().push(token_ids);
+
+                    let body = serde_json::to_string(&books).unwrap();
+                    let response = format!(
+                        "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\n\
+                         content-length: {}\r\nconnection: close\r\n\r\n{body}",
+                        body.len()
+                    );
+                    socket.write_all(response.as_bytes()).await.unwrap();
+                }
+            });
+
+            (url, requests)
+        }
+
+        #[tokio::test]
+        async fn test_fetch_book_snapshots() {
+            Polymarket::set_verify_book_hashes(true);
+            let (url, requests) = mock_books_server().await;
+            let markets = vec![
+                ("yes", PolymarketMarket::new("111")),
+                ("no", PolymarketMarket::new("222")),
+                ("missing", PolymarketMarket::new("missing")),
+                ("corrupt", PolymarketMarket::new("corrupt")),
+            ];
+
+            let events = fetch_book_snapshots(&url, markets).await;
+
+            assert_eq!(
+                *requests.lock().unwrap(),
+                vec![vec!["111", "222", "corrupt", "missing"]]
+            );
+            assert_eq!(events.len(), 2);
+            assert_eq!(events[0].instrument, "yes");
+            assert_eq!(events[1].instrument, "no");
+
+            let OrderBookEvent::Snapshot(book) = &events[0].kind else {
+                panic!("Expected Snapshot");
+            };
+            assert_eq!(book.sequence(), 1706313600000);
+            assert_eq!(book.bids().levels()[0].price, dec!(0.455));
+            assert_eq!(book.bids().levels()[0].amount, dec!(1234.56));
+            assert_eq!(book.bids().levels()[1].price, dec!(0.44));
+            assert_eq!(book.asks().levels()[0].price, dec!(0.46));
+            assert_eq!(book.asks().levels()[0].amount, dec!(0.5));
+            assert_eq!(
+                events[0].time_exchange,
+                DateTime::from_timestamp_millis(1706313600000).unwrap()
+            );
+        }
+
+        #[tokio::test]
+        async fn test_fetch_book_snapshots_chunked() {
+            let (url, requests) = mock_books_server().await;
+            let markets = (0..POLYMARKET_BOOKS_BATCH_LIMIT * 2 + 1)
+                .map(|index| (index, PolymarketMarket::new(format!("{index:04}"))))
+                .collect::<Vec<_>>();
+
+            let events = fetch_book_snapshots(&url, markets).await;
+
+            let chunks = requests
+                .lock()
+                .unwrap()
+                .iter()
+                .map(Vec::len)
+                .collect::<Vec<_>>();
+            assert_eq!(
+                chunks,
+                vec![POLYMARKET_BOOKS_BATCH_LIMIT, POLYMARKET_BOOKS_BATCH_LIMIT, 1]
+            );
+            assert_eq!(events.len(), POLYMARKET_BOOKS_BATCH_LIMIT * 2 + 1);
+            assert!(events.iter().enumerate().all(|(index, event)| event.instrument == index));
+        }
+    }
 }
@@ -3,4 +3,7 @@
 /// L2 OrderBook implementation for Polymarket prediction markets.
 pub mod l2;
 
+/// Book hash verification, detecting corrupted or missed book updates.
+pub mod hash;
+
 pub use l2::PolymarketOrderBookL2Meta;
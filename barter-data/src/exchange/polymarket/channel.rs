@@ -15,7 +15,7 @@ pub struct PolymarketChannel(pub &'static str);
 impl PolymarketChannel {
     /// [`Polymarket`] market channel for orderbook price level updates.
     ///
-    /// The "market" type provides book snapshots and price changes.
+    /// The "market" type provides book snapshots, price changes and last trade prices.
     /// See docs: <https://docs.polymarket.com/#websocket-api>
     pub const MARKET: Self = Self("market");
 
@@ -36,7 +36,8 @@ impl PolymarketChannel {
 
 impl<Instrument> Identifier<PolymarketChannel> for Subscription<Polymarket, Instrument, PublicTrades> {
     fn id(&self) -> PolymarketChannel {
-        PolymarketChannel::LIVE_ACTIVITY
+        // Trade prints are `last_trade_price` events of the market channel
+        PolymarketChannel::MARKET
     }
 }
 
@@ -12,6 +12,10 @@ use smol_str::format_smolstr;
 pub enum PolymarketMessage<T> {
     /// Price book snapshot/update message
     PriceBook(PolymarketPriceBook),
+    /// Tick size change message
+    TickSizeChange(PolymarketTickSizeChange),
+    /// Last trade price message
+    LastTradePrice(PolymarketLastTradePrice),
     /// Live activity (trades) message
     LiveActivity(PolymarketLiveActivity),
     /// Generic data message
@@ -27,6 +31,8 @@ where
     fn id(&self) -> Option<SubscriptionId> {
         match self {
             Self::PriceBook(book) => book.id(),
+            Self::TickSizeChange(change) => change.id(),
+            Self::LastTradePrice(trade) => trade.id(),
             Self::LiveActivity(activity) => activity.id(),
             Self::Data(data) => data.id(),
             Self::Other(_) => None, // Heartbeats/unknown messages have no subscription ID
@@ -43,6 +49,19 @@ impl Identifier<Option<SubscriptionId>> for PolymarketPriceBook {
     }
 }
 
+impl Identifier<Option<SubscriptionId>> for PolymarketTickSizeChange {
+    fn id(&self) -> Option<SubscriptionId> {
+        Some(SubscriptionId(format_smolstr!("market|{}", self.asset_id)))
+    }
+}
+
+impl Identifier<Option<SubscriptionId>> for PolymarketLastTradePrice {
+    fn id(&self) -> Option<SubscriptionId> {
+        // Trade prints are sent on the market channel PublicTrades subscribes to
+        Some(SubscriptionId(format_smolstr!("market|{}", self.asset_id)))
+    }
+}
+
 impl Identifier<Option<SubscriptionId>> for PolymarketLiveActivity {
     fn id(&self) -> Option<SubscriptionId> {
         Some(SubscriptionId(format_smolstr!(
@@ -82,6 +101,18 @@ pub struct PolymarketPriceBook {
     pub bids: Vec<PolymarketLevel>,
     /// Ask levels
     pub asks: Vec<PolymarketLevel>,
+    /// Tick size, included in REST book snapshots
+    #[serde(default)]
+    pub tick_size: Option<Decimal>,
+    /// Minimum order size, included in REST book snapshots
+    #[serde(default)]
+    pub min_order_size: Option<String>,
+    /// Whether the market is a negative risk market, included in REST book snapshots
+    #[serde(default)]
+    pub neg_risk: Option<bool>,
+    /// Hash of the book, see [`book_hash`](super::book::hash::book_hash)
+    #[serde(default)]
+    pub hash: Option<String>,
 }
 
 /// Polymarket live activity message for trades.
@@ -121,6 +152,84 @@ pub struct PolymarketLiveActivity {
     pub timestamp: Option<u64>,
 }
 
+/// Polymarket tick_size_change message, sent when a market's minimum price increment
+/// changes (eg/ from 0.01 to 0.001 as the price nears 0 or 1).
+///
+/// ### Payload Example
+/// ```json
+/// {
+///   "event_type": "tick_size_change",
+///   "asset_id": "65818619657568813474341868652308942079804919287380422192892211131408793125422",
+///   "market": "0xbd31dc8a20211944f6b70f31557f1001557b59905b7738480ca09bd4532f84af",
+///   "old_tick_size": "0.01",
+///   "new_tick_size": "0.001",
+///   "timestamp": "100000000"
+/// }
+/// ```
+#[derive(Clone, PartialEq, Debug, Deserialize, Serialize)]
+pub struct PolymarketTickSizeChange {
+    /// Event type: "tick_size_change"
+    pub event_type: String,
+    /// Token ID
+    pub asset_id: String,
+    /// Condition ID (the overall market)
+    #[serde(default)]
+    pub market: Option<String>,
+    /// Previous tick size
+    pub old_tick_size: Decimal,
+    /// Tick size now in effect
+    pub new_tick_size: Decimal,
+    /// Timestamp in milliseconds (Polymarket sends as string or number)
+    #[serde(default, deserialize_with = "de_string_or_u64")]
+    pub timestamp: Option<u64>,
+}
+
+/// `event_type` of a [`PolymarketLastTradePrice`], which otherwise has the same shape as a
+/// [`PolymarketLiveActivity`].
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Deserialize, Serialize)]
+pub enum PolymarketLastTradePriceType {
+    #[serde(rename = "last_trade_price")]
+    LastTradePrice,
+}
+
+/// Polymarket last_trade_price message, sent on the market channel when a trade executes.
+///
+/// ### Payload Example
+/// ```json
+/// {
+///   "event_type": "last_trade_price",
+///   "asset_id": "114122071509644379678018727908709560226618148003371446110114509806601493071694",
+///   "market": "0x6a67b9d828d53862160e470329ffea5246f338ecfffdf2cab45211ec578b0347",
+///   "fee_rate_bps": "0",
+///   "price": "0.456",
+///   "side": "BUY",
+///   "size": "219.217767",
+///   "timestamp": "1750428146322"
+/// }
+/// ```
+#[derive(Clone, PartialEq, Debug, Deserialize, Serialize)]
+pub struct PolymarketLastTradePrice {
+    /// Event type: "last_trade_price"
+    pub event_type: PolymarketLastTradePriceType,
+    /// Token ID
+    pub asset_id: String,
+    /// Condition ID (the overall market)
+    #[serde(default)]
+    pub market: Option<String>,
+    /// Trade price
+    pub price: Decimal,
+    /// Trade size
+    pub size: Decimal,
+    /// Taker side: "BUY" or "SELL"
+    pub side: String,
+    /// Fee rate charged on the trade, in basis points
+    #[serde(default)]
+    pub fee_rate_bps: Option<String>,
+    /// Timestamp in milliseconds (Polymarket sends as string or number)
+    #[serde(default, deserialize_with = "de_string_or_u64")]
+    pub timestamp: Option<u64>,
+}
+
 /// Polymarket orderbook level.
 #[derive(Clone, PartialEq, Debug, Deserialize, Serialize)]
 pub struct PolymarketLevel {
@@ -184,7 +293,7 @@ pub struct PolymarketPriceChangeEntry {
 }
 
 /// Deserialize a timestamp that may be either a number or a string.
-fn de_string_or_u64<'de, D>(deserializer: D) -> Result<Option<u64>, D::Error>
+pub(super) fn de_string_or_u64<'de, D>(deserializer: D) -> Result<Option<u64>, D::Error>
 where
     D: Deserializer<'de>,
 {
@@ -288,6 +397,61 @@ mod tests {
             }
         }
 
+        #[test]
+        fn test_polymarket_tick_size_change() {
+            let input = r#"
+            {
+                "event_type": "tick_size_change",
+                "asset_id": "65818619657568813474341868652308942079804919287380422192892211131408793125422",
+                "market": "0xbd31dc8a20211944f6b70f31557f1001557b59905b7738480ca09bd4532f84af",
+                "old_tick_size": "0.01",
+                "new_tick_size": "0.001",
+                "timestamp": "100000000"
+            }
+            "#;
+
+            let msg: PolymarketMessage<()> = serde_json::from_str(input).unwrap();
+            let PolymarketMessage::TickSizeChange(change) = &msg else {
+                panic!("Expected TickSizeChange, got {msg:?}");
+            };
+            assert_eq!(change.old_tick_size, Decimal::from_str_exact("0.01").unwrap());
+            assert_eq!(change.new_tick_size, Decimal::from_str_exact("0.001").unwrap());
+            assert_eq!(change.timestamp, Some(100000000));
+            assert_eq!(
+                change.id(),
+                Some(SubscriptionId(format_smolstr!("market|{}", change.asset_id)))
+            );
+        }
+
+        #[test]
+        fn test_polymarket_last_trade_price() {
+            let input = r#"
+            {
+                "asset_id": "114122071509644379678018727908709560226618148003371446110114509806601493071694",
+                "event_type": "last_trade_price",
+                "fee_rate_bps": "0",
+                "market": "0x6a67b9d828d53862160e470329ffea5246f338ecfffdf2cab45211ec578b0347",
+                "price": "0.456",
+                "side": "BUY",
+                "size": "219.217767",
+                "timestamp": "1750428146322"
+            }
+            "#;
+
+            let msg: PolymarketMessage<()> = serde_json::from_str(input).unwrap();
+            let PolymarketMessage::LastTradePrice(trade) = &msg else {
+                panic!("Expected LastTradePrice, got {msg:?}");
+            };
+            assert_eq!(trade.price, Decimal::from_str_exact("0.456").unwrap());
+            assert_eq!(trade.size, Decimal::from_str_exact("219.217767").unwrap());
+            assert_eq!(trade.side, "BUY");
+            assert_eq!(trade.timestamp, Some(1750428146322));
+            assert_eq!(
+                trade.id(),
+                Some(SubscriptionId(format_smolstr!("market|{}", trade.asset_id)))
+            );
+        }
+
         #[test]
         fn test_polymarket_level_parsing() {
             let level = PolymarketLevel {
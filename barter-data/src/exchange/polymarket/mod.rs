@@ -1,4 +1,5 @@
 use self::{
+    book::l2::PolymarketOrderBooksL2SnapshotFetcher,
     channel::PolymarketChannel,
     market::PolymarketMarket,
     subscription::PolymarketSubResponse,
@@ -18,6 +19,7 @@ use barter_integration::{error::SocketError, protocol::websocket::WsMessage};
 use barter_macro::{DeExchange, SerExchange};
 use derive_more::Display;
 use serde_json::json;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use url::Url;
 
 /// OrderBook types for [`Polymarket`].
@@ -45,6 +47,10 @@ pub mod subscription;
 /// that handles JSON array snapshots.
 pub mod transformer;
 
+/// Authenticated [`PolymarketUser`](user::PolymarketUser) connector streaming the account's
+/// own order and trade events.
+pub mod user;
+
 /// [`Polymarket`] WebSocket base URL.
 ///
 /// See docs: <https://docs.polymarket.com/#websocket-api>
@@ -53,6 +59,23 @@ pub const BASE_URL_POLYMARKET: &str = "wss://ws-subscriptions-clob.polymarket.co
 /// [`Polymarket`] user channel WebSocket base URL (requires authentication).
 pub const BASE_URL_POLYMARKET_USER: &str = "wss://ws-subscriptions-clob.polymarket.com/ws/user";
 
+/// [`Polymarket`] market stream idle timeout, covering at least one `PING` / `PONG` exchange
+/// at the 10s [`Connector::ping_interval`] cadence.
+pub const POLYMARKET_IDLE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(15);
+
+/// Default maximum number of asset ids subscribed to over one [`Polymarket`] market connection.
+///
+/// The market channel silently drops assets beyond several hundred per connection.
+pub const DEFAULT_MAX_ASSETS_PER_CONNECTION: usize = 500;
+
+/// Process-wide limit set by [`Polymarket::set_max_assets_per_connection`].
+///
+/// 0 = unset (use [`DEFAULT_MAX_ASSETS_PER_CONNECTION`]).
+static POLYMARKET_MAX_ASSETS_PER_CONNECTION: AtomicUsize = AtomicUsize::new(0);
+
+/// Process-wide book hash verification toggle set by [`Polymarket::set_verify_book_hashes`].
+static POLYMARKET_VERIFY_BOOK_HASHES: AtomicBool = AtomicBool::new(false);
+
 /// [`Polymarket`] prediction market exchange.
 ///
 /// Polymarket is a decentralized prediction market built on Polygon.
@@ -74,6 +97,40 @@ pub const BASE_URL_POLYMARKET_USER: &str = "wss://ws-subscriptions-clob.polymark
 )]
 pub struct Polymarket;
 
+impl Polymarket {
+    /// Enable or disable verifying the `hash` of every received book against its levels
+    /// (see [`book::hash`]), for all current and future [`Polymarket`] streams.
+    ///
+    /// Disabled by default: [`book::hash::book_hash`] follows the py-clob-client scheme but
+    /// is not yet verified against captured production payloads, and a scheme mismatch
+    /// would reject every book. Each check also re-serialises and hashes the book.
+    pub fn set_verify_book_hashes(verify: bool) {
+        POLYMARKET_VERIFY_BOOK_HASHES.store(verify, Ordering::Relaxed);
+    }
+
+    /// Whether received book hashes are verified.
+    pub fn verify_book_hashes() -> bool {
+        POLYMARKET_VERIFY_BOOK_HASHES.load(Ordering::Relaxed)
+    }
+
+    /// Set the maximum number of asset ids subscribed to over one connection.
+    ///
+    /// Larger subscriptions are split across several connections, each with its own pings
+    /// and subscription validation. Like [`Self::set_verify_book_hashes`], the setting is
+    /// process-wide.
+    pub fn set_max_assets_per_connection(max_assets: usize) {
+        POLYMARKET_MAX_ASSETS_PER_CONNECTION.store(max_assets.max(1), Ordering::Relaxed);
+    }
+
+    /// Maximum number of asset ids subscribed to over one connection.
+    pub fn max_assets_per_connection() -> usize {
+        match POLYMARKET_MAX_ASSETS_PER_CONNECTION.load(Ordering::Relaxed) {
+            0 => DEFAULT_MAX_ASSETS_PER_CONNECTION,
+            max_assets => max_assets,
+        }
+    }
+}
+
 impl Connector for Polymarket {
     const ID: ExchangeId = ExchangeId::Polymarket;
     type Channel = PolymarketChannel;
@@ -93,6 +150,10 @@ impl Connector for Polymarket {
         })
     }
 
+    fn idle_timeout() -> Option<std::time::Duration> {
+        Some(POLYMARKET_IDLE_TIMEOUT)
+    }
+
     fn requests(exchange_subs: Vec<ExchangeSub<Self::Channel, Self::Market>>) -> Vec<WsMessage> {
         // Group subscriptions by channel type
         let mut channels_to_assets: std::collections::HashMap<&str, Vec<String>> =
@@ -126,13 +187,17 @@ impl Connector for Polymarket {
         // book data immediately after subscription request
         0
     }
+
+    fn max_markets_per_connection() -> Option<usize> {
+        Some(Self::max_assets_per_connection())
+    }
 }
 
 impl<Instrument> StreamSelector<Instrument, OrderBooksL2> for Polymarket
 where
     Instrument: InstrumentData,
 {
-    type SnapFetcher = NoInitialSnapshots;
+    type SnapFetcher = PolymarketOrderBooksL2SnapshotFetcher;
     type Stream = ExchangeWsStream<PolymarketOrderBookTransformer<Instrument::Key>>;
 }
 
@@ -149,6 +214,10 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{streams::builder::connection_batches, subscription::Subscription};
+    use barter_instrument::instrument::market_data::{
+        MarketDataInstrument, kind::MarketDataInstrumentKind,
+    };
 
     #[test]
     fn test_polymarket_url() {
@@ -178,4 +247,65 @@ mod tests {
         // Should have 2 requests: one for market, one for live_activity
         assert_eq!(requests.len(), 2);
     }
+
+    fn book_subscriptions(
+        assets: usize,
+    ) -> Vec<Subscription<Polymarket, MarketDataInstrument, OrderBooksL2>> {
+        (0..assets)
+            .map(|n| {
+                Subscription::from((
+                    Polymarket,
+                    format!("token{n}"),
+                    "usdc".to_string(),
+                    MarketDataInstrumentKind::Spot,
+                    OrderBooksL2,
+                ))
+            })
+            .collect()
+    }
+
+    fn subscribed_assets(request: &WsMessage) -> Vec<String> {
+        let WsMessage::Text(text) = request else {
+            panic!("expected a text request, got {request:?}");
+        };
+        let payload: serde_json::Value = serde_json::from_str(text.as_str()).unwrap();
+        payload["assets_ids"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|asset| asset.as_str().unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_polymarket_subscriptions_split_across_connections() {
+        let subscriptions = book_subscriptions(1200);
+
+        let connections = connection_batches(subscriptions, Some(500))
+            .into_iter()
+            .map(|batch| {
+                let requests = Polymarket::requests(batch.iter().map(ExchangeSub::new).collect());
+                assert_eq!(requests.len(), 1);
+                subscribed_assets(&requests[0])
+            })
+            .collect::<Vec<_>>();
+
+        let sizes = connections.iter().map(Vec::len).collect::<Vec<_>>();
+        assert_eq!(sizes, vec![500, 500, 200]);
+
+        let all = connections
+            .iter()
+            .flatten()
+            .collect::<std::collections::HashSet<_>>();
+        assert_eq!(all.len(), 1200);
+    }
+
+    #[test]
+    fn test_polymarket_subscriptions_under_limit_share_connection() {
+        let subscriptions = book_subscriptions(500);
+
+        let batches = connection_batches(subscriptions.clone(), Some(500));
+        assert_eq!(batches, vec![subscriptions.clone()]);
+        assert_eq!(connection_batches(subscriptions.clone(), None), vec![subscriptions]);
+    }
 }
@@ -6,9 +6,10 @@ use crate::{
 use barter_instrument::{Side, exchange::ExchangeId};
 use barter_integration::subscription::SubscriptionId;
 use chrono::{DateTime, Utc};
+use rust_decimal::prelude::ToPrimitive;
 use serde::Deserialize;
 
-use super::message::{PolymarketLiveActivity, PolymarketMessage};
+use super::message::{PolymarketLastTradePrice, PolymarketLiveActivity, PolymarketMessage};
 
 impl<InstrumentKey> From<(ExchangeId, InstrumentKey, PolymarketLiveActivity)>
     for MarketIter<InstrumentKey, PublicTrade>
@@ -60,7 +61,37 @@ impl<InstrumentKey> From<(ExchangeId, InstrumentKey, PolymarketLiveActivity)>
     }
 }
 
-/// Wrapper around `PolymarketMessage` that only routes `LiveActivity` variants
+impl<InstrumentKey> From<(ExchangeId, InstrumentKey, PolymarketLastTradePrice)>
+    for MarketIter<InstrumentKey, PublicTrade>
+{
+    fn from(
+        (exchange, instrument, trade): (ExchangeId, InstrumentKey, PolymarketLastTradePrice),
+    ) -> Self {
+        let side = match trade.side.as_str() {
+            "BUY" => Side::Buy,
+            _ => Side::Sell,
+        };
+        let time = trade
+            .timestamp
+            .and_then(|ms| DateTime::from_timestamp_millis(ms as i64))
+            .unwrap_or_else(Utc::now);
+
+        Self(vec![Ok(MarketEvent {
+            time_exchange: time,
+            time_received: Utc::now(),
+            exchange,
+            instrument,
+            kind: PublicTrade {
+                id: format!("{}-{}", trade.asset_id, trade.timestamp.unwrap_or(0)),
+                price: trade.price.to_f64().unwrap_or(0.0),
+                amount: trade.size.to_f64().unwrap_or(0.0),
+                side,
+            },
+        })])
+    }
+}
+
+/// Wrapper around `PolymarketMessage` that only routes `LiveActivity` and `LastTradePrice` variants
 /// to the subscription map. All other message types (book snapshots, price_change
 /// deltas, etc.) that Polymarket sends on the same WS connection return `None`
 /// from `id()`, causing the `StatelessTransformer` to silently drop them.
@@ -72,6 +103,7 @@ impl Identifier<Option<SubscriptionId>> for PolymarketTradeMessage {
     fn id(&self) -> Option<SubscriptionId> {
         match &self.0 {
             PolymarketMessage::LiveActivity(activity) => activity.id(),
+            PolymarketMessage::LastTradePrice(trade) => trade.id(),
             _ => None,
         }
     }
@@ -88,6 +120,7 @@ impl<InstrumentKey> From<(ExchangeId, InstrumentKey, PolymarketTradeMessage)>
             | PolymarketMessage::Data(activity) => {
                 Self::from((exchange, instrument, activity))
             }
+            PolymarketMessage::LastTradePrice(trade) => Self::from((exchange, instrument, trade)),
             _ => Self(vec![]),
         }
     }
@@ -96,6 +129,14 @@ impl<InstrumentKey> From<(ExchangeId, InstrumentKey, PolymarketTradeMessage)>
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{
+        exchange::{
+            ExchangeSub,
+            polymarket::{Polymarket, channel::PolymarketChannel, market::PolymarketMarket},
+        },
+        subscription::{Subscription, trade::PublicTrades},
+    };
+    use barter_instrument::instrument::market_data::kind::MarketDataInstrumentKind;
 
     fn make_activity(event_type: &str) -> PolymarketLiveActivity {
         PolymarketLiveActivity {
@@ -173,6 +214,59 @@ mod tests {
         assert!(iter.0[0].is_ok());
     }
 
+    #[test]
+    fn test_trade_message_routes_last_trade_price() {
+        let msg: PolymarketTradeMessage = serde_json::from_value(serde_json::json!({
+            "asset_id": "0xabc123",
+            "event_type": "last_trade_price",
+            "fee_rate_bps": "0",
+            "market": "0xmarket",
+            "price": "0.456",
+            "side": "SELL",
+            "size": "219.5",
+            "timestamp": "1750428146322"
+        }))
+        .unwrap();
+        assert_eq!(
+            msg.id(),
+            Some(SubscriptionId(smol_str::format_smolstr!("market|0xabc123")))
+        );
+
+        let iter = MarketIter::<&str, PublicTrade>::from((ExchangeId::Polymarket, "test", msg));
+        let event = iter.0[0].as_ref().unwrap();
+        assert_eq!(event.kind.id, "0xabc123-1750428146322");
+        assert!((event.kind.price - 0.456).abs() < f64::EPSILON);
+        assert!((event.kind.amount - 219.5).abs() < f64::EPSILON);
+        assert_eq!(event.kind.side, Side::Sell);
+        assert_eq!(event.time_exchange.timestamp_millis(), 1750428146322);
+    }
+
+    #[test]
+    fn test_public_trades_subscription_matches_last_trade_price() {
+        // last_trade_price events are only sent on the market channel
+        let sub = Subscription::from((
+            Polymarket,
+            "0xabc123",
+            "usdc",
+            MarketDataInstrumentKind::Spot,
+            PublicTrades,
+        ));
+        let exchange_sub = ExchangeSub::<PolymarketChannel, PolymarketMarket>::new(&sub);
+        assert_eq!(exchange_sub.channel, PolymarketChannel::MARKET);
+
+        let msg: PolymarketTradeMessage = serde_json::from_value(serde_json::json!({
+            "asset_id": "0xabc123",
+            "event_type": "last_trade_price",
+            "market": "0xmarket",
+            "price": "0.456",
+            "side": "BUY",
+            "size": "10",
+            "timestamp": "1750428146322"
+        }))
+        .unwrap();
+        assert_eq!(msg.id(), Some(exchange_sub.id()));
+    }
+
     #[test]
     fn test_trade_message_drops_price_book() {
         // PriceBook messages should return None from id() and empty from From
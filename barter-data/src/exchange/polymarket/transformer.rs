@@ -2,8 +2,13 @@ use crate::{
     books::OrderBook,
     error::DataError,
     event::{MarketEvent, MarketIter},
-    exchange::polymarket::message::{
-        PolymarketMessage, PolymarketPriceBook, PolymarketPriceChangeEvent,
+    exchange::polymarket::{
+        Polymarket,
+        book::hash::verify_book_hash,
+        message::{
+            PolymarketMessage, PolymarketPriceBook, PolymarketPriceChangeEntry,
+            PolymarketPriceChangeEvent, PolymarketTickSizeChange,
+        },
     },
     subscription::{Map, book::OrderBooksL2},
     transformer::ExchangeTransformer,
@@ -16,10 +21,12 @@ use barter_integration::{
 use crate::Identifier;
 use crate::subscription::book::OrderBookEvent;
 use chrono::Utc;
+use fnv::FnvHashMap;
 use rust_decimal::Decimal;
 use smol_str::format_smolstr;
+use thiserror::Error;
 use tokio::sync::mpsc;
-use tracing::debug;
+use tracing::{debug, warn};
 
 /// Custom transformer for Polymarket OrderBook L2 streams.
 ///
@@ -27,24 +34,82 @@ use tracing::debug;
 /// - Initial snapshots as JSON arrays `[{...}, {...}]`
 /// - Individual book updates as JSON objects with `asset_id`, `bids`, `asks`
 /// - Price change deltas: `{"event_type": "price_change", "price_changes": [...]}`
+/// - Tick size changes, recorded per asset (see [`Self::tick_size`]) and in
+///   [`barter_instrument::polymarket::tick`] for order submission to read
+/// - Last trade prices, which are routed to PublicTrades streams and ignored here
 /// - PONG text replies (skipped upstream as parse errors)
+///
+/// A local [`OrderBook`] is maintained per asset, seeded from any initial REST snapshots, and
+/// checked with [`Self::validate`] after every snapshot and update, and against the
+/// `best_bid`/`best_ask` hints carried by `price_change` entries. Snapshots are also checked
+/// against their `hash` if enabled with [`Polymarket::set_verify_book_hashes`].
+/// Polymarket occasionally
+/// sends crossed or out-of-order levels, so rather than emitting an inconsistent book (and a
+/// phantom edge downstream), the asset's book is discarded and a terminal
+/// [`DataError::InvalidOrderBook`] is emitted. The stream then reconnects, fetching fresh
+/// snapshots.
 #[derive(Debug)]
 pub struct PolymarketOrderBookTransformer<InstrumentKey> {
     instrument_map: Map<InstrumentKey>,
+    /// Local book per asset [`SubscriptionId`], present once a snapshot is received.
+    books: FnvHashMap<SubscriptionId, OrderBook>,
+    /// Latest tick size per asset [`SubscriptionId`], present once a change is received.
+    tick_sizes: FnvHashMap<SubscriptionId, Decimal>,
+}
+
+/// Consistency violation found by [`PolymarketOrderBookTransformer::validate`].
+#[derive(Debug, Clone, Eq, PartialEq, Error)]
+pub enum PolymarketBookError {
+    #[error("bids are not in descending price order")]
+    BidsNotDescending,
+
+    #[error("asks are not in ascending price order")]
+    AsksNotAscending,
+
+    #[error("crossed book: best bid {best_bid} >= best ask {best_ask}")]
+    Crossed { best_bid: Decimal, best_ask: Decimal },
+
+    #[error("book hash {actual} does not match hash {expected} sent with it")]
+    HashMismatch { expected: String, actual: String },
+
+    #[error("best {side} {actual} does not match price_change hint {hint}")]
+    HintMismatch {
+        side: &'static str,
+        hint: Decimal,
+        actual: Decimal,
+    },
 }
 
 #[async_trait]
-impl<InstrumentKey> ExchangeTransformer<super::Polymarket, InstrumentKey, OrderBooksL2>
+impl<InstrumentKey> ExchangeTransformer<Polymarket, InstrumentKey, OrderBooksL2>
     for PolymarketOrderBookTransformer<InstrumentKey>
 where
-    InstrumentKey: Clone + Send,
+    InstrumentKey: Clone + PartialEq + Send + Sync,
 {
     async fn init(
         instrument_map: Map<InstrumentKey>,
-        _initial_snapshots: &[MarketEvent<InstrumentKey, OrderBookEvent>],
+        initial_snapshots: &[MarketEvent<InstrumentKey, OrderBookEvent>],
         _ws_sink_tx: mpsc::UnboundedSender<WsMessage>,
     ) -> Result<Self, DataError> {
-        Ok(Self { instrument_map })
+        let books = initial_snapshots
+            .iter()
+            .filter_map(|snapshot| {
+                let OrderBookEvent::Snapshot(book) = &snapshot.kind else {
+                    return None;
+                };
+                let (sub_id, _) = instrument_map
+                    .0
+                    .iter()
+                    .find(|(_, instrument)| **instrument == snapshot.instrument)?;
+                Some((sub_id.clone(), book.clone()))
+            })
+            .collect();
+
+        Ok(Self {
+            instrument_map,
+            books,
+            tick_sizes: FnvHashMap::default(),
+        })
     }
 }
 
@@ -87,22 +152,35 @@ where
 
         // Try to parse as a PolymarketMessage (individual book updates, live activity, etc.)
         match serde_json::from_value::<PolymarketMessage<PolymarketPriceBook>>(input) {
+            Ok(PolymarketMessage::TickSizeChange(change)) => {
+                self.record_tick_size(change);
+                vec![]
+            }
+            Ok(PolymarketMessage::LastTradePrice(_)) => vec![],
             Ok(msg) => {
                 let sub_id = match msg.id() {
                     Some(id) => id,
                     None => return vec![],
                 };
-                match self.instrument_map.find(&sub_id) {
-                    Ok(instrument) => {
-                        MarketIter::<InstrumentKey, OrderBookEvent>::from((
-                            ExchangeId::Polymarket,
-                            instrument.clone(),
-                            msg,
-                        ))
-                        .0
-                    }
-                    Err(unidentifiable) => vec![Err(DataError::from(unidentifiable))],
+                let instrument = match self.instrument_map.find(&sub_id) {
+                    Ok(instrument) => instrument.clone(),
+                    Err(unidentifiable) => return vec![Err(DataError::from(unidentifiable))],
+                };
+                if let PolymarketMessage::PriceBook(book) = &msg
+                    && let Err(error) = self.verify_hash(&sub_id, book)
+                {
+                    return vec![Err(error)];
                 }
+
+                MarketIter::<InstrumentKey, OrderBookEvent>::from((
+                    ExchangeId::Polymarket,
+                    instrument,
+                    msg,
+                ))
+                .0
+                .into_iter()
+                .map(|event| self.maintain(&sub_id, event))
+                .collect()
             }
             Err(_) => vec![],
         }
@@ -113,28 +191,153 @@ impl<InstrumentKey> PolymarketOrderBookTransformer<InstrumentKey>
 where
     InstrumentKey: Clone,
 {
+    /// Validate the local book of asset `sub_id`: bids strictly descending, asks strictly
+    /// ascending, and best bid below best ask.
+    ///
+    /// An asset without a local book (no snapshot received yet) is valid.
+    pub fn validate(&self, sub_id: &SubscriptionId) -> Result<(), PolymarketBookError> {
+        self.books.get(sub_id).map_or(Ok(()), validate_book)
+    }
+
+    /// Latest tick size of asset `sub_id`, or `None` if no tick_size_change has been
+    /// received for it.
+    pub fn tick_size(&self, sub_id: &SubscriptionId) -> Option<Decimal> {
+        self.tick_sizes.get(sub_id).copied()
+    }
+
+    /// Record a tick_size_change for a subscribed asset.
+    fn record_tick_size(&mut self, change: PolymarketTickSizeChange) {
+        let Some(sub_id) = change.id() else {
+            return;
+        };
+        if self.instrument_map.find(&sub_id).is_err() {
+            return;
+        }
+
+        debug!(
+            %sub_id,
+            old = %change.old_tick_size,
+            new = %change.new_tick_size,
+            "Polymarket tick size changed"
+        );
+        self.tick_sizes.insert(sub_id, change.new_tick_size);
+    }
+
+    /// Apply `event` to the local book of asset `sub_id` and validate the result.
+    ///
+    /// Updates received before the asset's first snapshot are passed through unchecked.
+    fn maintain(
+        &mut self,
+        sub_id: &SubscriptionId,
+        event: Result<MarketEvent<InstrumentKey, OrderBookEvent>, DataError>,
+    ) -> Result<MarketEvent<InstrumentKey, OrderBookEvent>, DataError> {
+        let event = event?;
+        match &event.kind {
+            OrderBookEvent::Snapshot(snapshot) => {
+                self.books.insert(sub_id.clone(), snapshot.clone());
+            }
+            OrderBookEvent::Update(_) => match self.books.get_mut(sub_id) {
+                Some(book) => book.update(&event.kind),
+                None => return Ok(event),
+            },
+        }
+
+        match self.validate(sub_id) {
+            Ok(()) => Ok(event),
+            Err(error) => Err(self.invalidate(sub_id, error)),
+        }
+    }
+
+    /// Check the local book of asset `sub_id` against the best prices Polymarket reports
+    /// alongside a `price_change` entry, catching books that drifted between snapshots.
+    ///
+    /// Polymarket reports an empty side as a best bid of 0 or a best ask of 1. Missing or
+    /// unparsable hints, and assets without a local book, are not checked.
+    fn check_hints(
+        &mut self,
+        sub_id: &SubscriptionId,
+        hints: &PolymarketPriceChangeEntry,
+    ) -> Result<(), DataError> {
+        let Some(book) = self.books.get(sub_id) else {
+            return Ok(());
+        };
+
+        let best_bid = book.bids().best().map_or(Decimal::ZERO, |level| level.price);
+        let best_ask = book.asks().best().map_or(Decimal::ONE, |level| level.price);
+        let parse = |hint: &Option<String>| hint.as_deref().and_then(|h| h.parse().ok());
+
+        let mismatch = [
+            ("bid", parse(&hints.best_bid), best_bid),
+            ("ask", parse(&hints.best_ask), best_ask),
+        ]
+        .into_iter()
+        .find_map(|(side, hint, actual)| {
+            hint.filter(|hint| *hint != actual)
+                .map(|hint| PolymarketBookError::HintMismatch { side, hint, actual })
+        });
+
+        match mismatch {
+            None => Ok(()),
+            Some(error) => Err(self.invalidate(sub_id, error)),
+        }
+    }
+
+    /// Check a received snapshot of asset `sub_id` against its hash, if enabled.
+    ///
+    /// On mismatch the asset's local book is discarded, so the stream reconnects and
+    /// fetches a fresh snapshot.
+    fn verify_hash(
+        &mut self,
+        sub_id: &SubscriptionId,
+        book: &PolymarketPriceBook,
+    ) -> Result<(), DataError> {
+        if !Polymarket::verify_book_hashes() {
+            return Ok(());
+        }
+        verify_book_hash(book).map_err(|error| self.invalidate(sub_id, error))
+    }
+
+    /// Discard the local book of asset `sub_id`, returning the terminal error to emit.
+    fn invalidate(&mut self, sub_id: &SubscriptionId, error: PolymarketBookError) -> DataError {
+        warn!(%sub_id, %error, "Polymarket OrderBook failed validation, discarding");
+        self.books.remove(sub_id);
+        DataError::InvalidOrderBook {
+            subscription_id: sub_id.clone(),
+            reason: error.to_string(),
+        }
+    }
+
     fn transform_price_book(
-        &self,
+        &mut self,
         book: PolymarketPriceBook,
     ) -> Option<Vec<Result<MarketEvent<InstrumentKey, OrderBookEvent>, DataError>>> {
         let sub_id = SubscriptionId(format_smolstr!("market|{}", book.asset_id));
-        let instrument = self.instrument_map.find(&sub_id).ok()?;
+        let instrument = self.instrument_map.find(&sub_id).ok()?.clone();
+        if let Err(error) = self.verify_hash(&sub_id, &book) {
+            return Some(vec![Err(error)]);
+        }
         Some(
             MarketIter::<InstrumentKey, OrderBookEvent>::from((
                 ExchangeId::Polymarket,
-                instrument.clone(),
+                instrument,
                 book,
             ))
-            .0,
+            .0
+            .into_iter()
+            .map(|event| self.maintain(&sub_id, event))
+            .collect(),
         )
     }
 
     /// Convert a price_change event into OrderBookEvent::Update events.
     ///
     /// Each entry in `price_changes` contains a single level change for a specific
-    /// asset_id. We group by asset_id and emit one Update per asset.
+    /// asset_id, where a size of 0 removes the level. Entries are grouped by asset_id,
+    /// emitting one Update per asset in the order assets first appear, and the resulting
+    /// book is checked against the asset's last `best_bid`/`best_ask` hints. Entries for
+    /// assets we are not subscribed to are ignored.
     fn transform_price_change(
-        &self,
+        &mut self,
         input: serde_json::Value,
     ) -> Vec<Result<MarketEvent<InstrumentKey, OrderBookEvent>, DataError>> {
         let event: PolymarketPriceChangeEvent = match serde_json::from_value(input) {
@@ -146,50 +349,453 @@ where
         };
 
         let now = Utc::now();
+        let seq = event.timestamp.unwrap_or(0);
         let mut results = Vec::new();
 
-        // Group changes by asset_id
-        let mut grouped: std::collections::HashMap<&str, (Vec<(Decimal, Decimal)>, Vec<(Decimal, Decimal)>)> =
-            std::collections::HashMap::new();
-
+        // Group changes by asset_id, keeping each asset's latest entry for its hints
+        let mut grouped: Vec<AssetChanges<'_>> = Vec::new();
         for change in &event.price_changes {
-            let price: Decimal = match change.price.parse() {
-                Ok(p) => p,
-                Err(_) => continue,
-            };
-            let size: Decimal = match change.size.parse() {
-                Ok(s) => s,
-                Err(_) => continue,
+            let (Ok(price), Ok(size)) = (change.price.parse(), change.size.parse()) else {
+                continue;
             };
 
-            let (bids, asks) = grouped.entry(&change.asset_id).or_default();
+            let existing = grouped
+                .iter()
+                .position(|group| group.latest.asset_id == change.asset_id);
+            let index = match existing {
+                Some(index) => index,
+                None => {
+                    grouped.push(AssetChanges {
+                        latest: change,
+                        bids: Vec::new(),
+                        asks: Vec::new(),
+                    });
+                    grouped.len() - 1
+                }
+            };
+            let group = &mut grouped[index];
+            group.latest = change;
             match change.side.as_str() {
-                "BUY" => bids.push((price, size)),
-                "SELL" => asks.push((price, size)),
+                "BUY" => group.bids.push((price, size)),
+                "SELL" => group.asks.push((price, size)),
                 _ => {}
             }
         }
 
-        // Emit one Update event per asset_id
-        for (asset_id, (bids, asks)) in grouped {
-            let sub_id = SubscriptionId(format_smolstr!("market|{}", asset_id));
-            let instrument = match self.instrument_map.find(&sub_id) {
-                Ok(i) => i.clone(),
-                Err(_) => continue,
+        // Emit one Update event per subscribed asset_id
+        for AssetChanges { latest, bids, asks } in grouped {
+            let sub_id = SubscriptionId(format_smolstr!("market|{}", latest.asset_id));
+            let Ok(instrument) = self.instrument_map.find(&sub_id) else {
+                continue;
             };
 
-            let seq = event.timestamp.unwrap_or(0);
-            let orderbook = OrderBook::new(seq, Some(now), bids, asks);
-
-            results.push(Ok(MarketEvent {
+            let event = Ok(MarketEvent {
                 time_exchange: now,
                 time_received: now,
                 exchange: ExchangeId::Polymarket,
-                instrument,
-                kind: OrderBookEvent::Update(orderbook),
-            }));
+                instrument: instrument.clone(),
+                kind: OrderBookEvent::Update(OrderBook::new(seq, Some(now), bids, asks)),
+            });
+            let result = self
+                .maintain(&sub_id, event)
+                .and_then(|event| self.check_hints(&sub_id, latest).map(|()| event));
+            results.push(result);
         }
 
         results
     }
 }
+
+/// Level changes for one asset within a price_change event.
+struct AssetChanges<'a> {
+    /// Latest entry for the asset, carrying its `best_bid`/`best_ask` hints
+    latest: &'a PolymarketPriceChangeEntry,
+    bids: Vec<(Decimal, Decimal)>,
+    asks: Vec<(Decimal, Decimal)>,
+}
+
+/// Check `book` is consistent, see [`PolymarketOrderBookTransformer::validate`].
+fn validate_book(book: &OrderBook) -> Result<(), PolymarketBookError> {
+    let bids = book.bids().levels();
+    if bids.windows(2).any(|pair| pair[0].price <= pair[1].price) {
+        return Err(PolymarketBookError::BidsNotDescending);
+    }
+
+    let asks = book.asks().levels();
+    if asks.windows(2).any(|pair| pair[0].price >= pair[1].price) {
+        return Err(PolymarketBookError::AsksNotAscending);
+    }
+
+    match (bids.first(), asks.first()) {
+        (Some(best_bid), Some(best_ask)) if best_bid.price >= best_ask.price => {
+            Err(PolymarketBookError::Crossed {
+                best_bid: best_bid.price,
+                best_ask: best_ask.price,
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const ASSET: &str = "0x1234abcd";
+
+    fn transformer() -> PolymarketOrderBookTransformer<&'static str> {
+        let mut instrument_map = Map(FnvHashMap::default());
+        instrument_map
+            .0
+            .insert(SubscriptionId(format_smolstr!("market|{ASSET}")), "instrument");
+        PolymarketOrderBookTransformer {
+            instrument_map,
+            books: FnvHashMap::default(),
+            tick_sizes: FnvHashMap::default(),
+        }
+    }
+
+    fn sub_id() -> SubscriptionId {
+        SubscriptionId(format_smolstr!("market|{ASSET}"))
+    }
+
+    fn book(bids: &[(&str, &str)], asks: &[(&str, &str)]) -> serde_json::Value {
+        let levels = |levels: &[(&str, &str)]| {
+            levels
+                .iter()
+                .map(|(price, size)| json!({"price": price, "size": size}))
+                .collect::<Vec<_>>()
+        };
+        json!({
+            "event_type": "book",
+            "asset_id": ASSET,
+            "timestamp": "1706313600000",
+            "bids": levels(bids),
+            "asks": levels(asks),
+        })
+    }
+
+    fn price_change(price: &str, size: &str, side: &str) -> serde_json::Value {
+        json!({
+            "event_type": "price_change",
+            "timestamp": "1706313601000",
+            "price_changes": [
+                {"asset_id": ASSET, "price": price, "size": size, "side": side}
+            ],
+        })
+    }
+
+    fn price_changes(changes: serde_json::Value) -> serde_json::Value {
+        json!({
+            "event_type": "price_change",
+            "timestamp": "1706313601000",
+            "price_changes": changes,
+        })
+    }
+
+    fn levels(levels: &[crate::books::Level]) -> Vec<(Decimal, Decimal)> {
+        levels.iter().map(|level| (level.price, level.amount)).collect()
+    }
+
+    fn is_invalid(output: &[Result<MarketEvent<&str, OrderBookEvent>, DataError>]) -> bool {
+        matches!(
+            output,
+            [Err(DataError::InvalidOrderBook { subscription_id, .. })]
+                if *subscription_id == sub_id()
+        )
+    }
+
+    #[test]
+    fn test_crossed_snapshot_rejected() {
+        let mut transformer = transformer();
+
+        // Snapshot array with the best bid above the best ask
+        let snapshot = json!([book(&[("0.60", "10")], &[("0.55", "10")])]);
+        let output = transformer.transform(snapshot);
+        assert!(is_invalid(&output), "{output:?}");
+        assert!(output[0].as_ref().unwrap_err().is_terminal());
+        assert!(transformer.books.is_empty());
+
+        // Locked book (best bid == best ask) is also rejected
+        let output = transformer.transform(book(&[("0.55", "10")], &[("0.55", "10")]));
+        assert!(is_invalid(&output), "{output:?}");
+    }
+
+    #[test]
+    fn test_valid_snapshot_then_crossing_update_rejected() {
+        let mut transformer = transformer();
+
+        let output = transformer.transform(book(
+            &[("0.45", "100"), ("0.44", "50")],
+            &[("0.46", "200"), ("0.47", "20")],
+        ));
+        assert!(matches!(
+            output.as_slice(),
+            [Ok(MarketEvent { kind: OrderBookEvent::Snapshot(_), .. })]
+        ));
+        assert_eq!(transformer.validate(&sub_id()), Ok(()));
+
+        // Non-crossing update is applied to the local book
+        let output = transformer.transform(price_change("0.455", "10", "BUY"));
+        assert!(matches!(
+            output.as_slice(),
+            [Ok(MarketEvent { kind: OrderBookEvent::Update(_), .. })]
+        ));
+        let best_bid = transformer.books[&sub_id()].bids().best().unwrap().price;
+        assert_eq!(best_bid, Decimal::from_str_exact("0.455").unwrap());
+
+        // Bid through the best ask crosses the book
+        let output = transformer.transform(price_change("0.47", "10", "BUY"));
+        assert!(is_invalid(&output), "{output:?}");
+        assert!(output[0].as_ref().unwrap_err().is_terminal());
+        assert!(!transformer.books.contains_key(&sub_id()));
+    }
+
+    #[test]
+    fn test_multi_entry_price_change_applied() {
+        let mut transformer = transformer();
+        transformer.transform(book(
+            &[("0.45", "100"), ("0.44", "50")],
+            &[("0.46", "200"), ("0.47", "20")],
+        ));
+
+        let output = transformer.transform(price_changes(json!([
+            {"asset_id": ASSET, "price": "0.44", "size": "0", "side": "BUY"},
+            {"asset_id": "0xunsubscribed", "price": "0.50", "size": "10", "side": "SELL"},
+            {"asset_id": ASSET, "price": "0.43", "size": "30", "side": "BUY"},
+            {
+                "asset_id": ASSET, "price": "0.46", "size": "150", "side": "SELL",
+                "best_bid": "0.45", "best_ask": "0.46"
+            },
+        ])));
+
+        // One Update for the subscribed asset; the unsubscribed asset is ignored
+        let [Ok(MarketEvent { kind: OrderBookEvent::Update(update), .. })] = output.as_slice()
+        else {
+            panic!("expected a single update, got {output:?}");
+        };
+        assert_eq!(
+            levels(update.bids().levels()),
+            [(dec("0.44"), dec("0")), (dec("0.43"), dec("30"))]
+        );
+        assert_eq!(levels(update.asks().levels()), [(dec("0.46"), dec("150"))]);
+
+        // Zero size removed the 0.44 bid from the maintained book
+        let book = &transformer.books[&sub_id()];
+        assert_eq!(
+            levels(book.bids().levels()),
+            [(dec("0.45"), dec("100")), (dec("0.43"), dec("30"))]
+        );
+        assert_eq!(
+            levels(book.asks().levels()),
+            [(dec("0.46"), dec("150")), (dec("0.47"), dec("20"))]
+        );
+    }
+
+    #[test]
+    fn test_price_change_for_unsubscribed_asset_ignored() {
+        let mut transformer = transformer();
+        let output = transformer.transform(price_changes(json!([
+            {"asset_id": "0xunsubscribed", "price": "0.50", "size": "10", "side": "SELL"},
+        ])));
+        assert!(output.is_empty(), "{output:?}");
+    }
+
+    #[test]
+    fn test_price_change_hint_mismatch_rejected() {
+        let mut transformer = transformer();
+
+        // Hints are only checked once a local book exists
+        let output = transformer.transform(price_changes(json!([
+            {
+                "asset_id": ASSET, "price": "0.44", "size": "5", "side": "BUY",
+                "best_bid": "0.40", "best_ask": "0.60"
+            },
+        ])));
+        assert!(matches!(output.as_slice(), [Ok(_)]), "{output:?}");
+
+        transformer.transform(book(&[("0.45", "100")], &[("0.46", "200"), ("0.47", "20")]));
+
+        // Removing the 0.46 ask moves the best ask to 0.47, matching the hint
+        let output = transformer.transform(price_changes(json!([
+            {
+                "asset_id": ASSET, "price": "0.46", "size": "0", "side": "SELL",
+                "best_bid": "0.45", "best_ask": "0.47"
+            },
+        ])));
+        assert!(matches!(output.as_slice(), [Ok(_)]), "{output:?}");
+
+        // Our book says 0.47 but Polymarket reports a best ask of 0.48: the book drifted
+        let output = transformer.transform(price_changes(json!([
+            {
+                "asset_id": ASSET, "price": "0.44", "size": "10", "side": "BUY",
+                "best_bid": "0.45", "best_ask": "0.48"
+            },
+        ])));
+        assert!(is_invalid(&output), "{output:?}");
+        assert!(!transformer.books.contains_key(&sub_id()));
+    }
+
+    #[test]
+    fn test_book_hash_mismatch_rejected() {
+        Polymarket::set_verify_book_hashes(true);
+        let mut transformer = transformer();
+        let mut payload = book(&[("0.45", "100")], &[("0.46", "200")]);
+
+        // Correct hash, as for an uncorrupted book
+        let hash = super::super::book::hash::book_hash(
+            &serde_json::from_value::<PolymarketPriceBook>(payload.clone()).unwrap(),
+        );
+        payload["hash"] = json!(hash);
+        let output = transformer.transform(payload.clone());
+        assert!(matches!(output.as_slice(), [Ok(_)]), "{output:?}");
+
+        // Levels no longer match the hash sent with them
+        payload["bids"][0]["size"] = json!("99");
+        let output = transformer.transform(payload.clone());
+        assert!(is_invalid(&output), "{output:?}");
+        assert!(output[0].as_ref().unwrap_err().is_terminal());
+        assert!(!transformer.books.contains_key(&sub_id()));
+
+        // Snapshot arrays are checked too
+        let output = transformer.transform(json!([payload]));
+        assert!(is_invalid(&output), "{output:?}");
+    }
+
+    #[test]
+    fn test_tick_size_change_recorded() {
+        let mut transformer = transformer();
+        assert_eq!(transformer.tick_size(&sub_id()), None);
+
+        let output = transformer.transform(json!({
+            "event_type": "tick_size_change",
+            "asset_id": ASSET,
+            "market": "0xbd31dc8a20211944f6b70f31557f1001557b59905b7738480ca09bd4532f84af",
+            "old_tick_size": "0.01",
+            "new_tick_size": "0.001",
+            "timestamp": "100000000"
+        }));
+        assert!(output.is_empty(), "{output:?}");
+        assert_eq!(transformer.tick_size(&sub_id()), Some(dec("0.001")));
+
+        // Unsubscribed assets are not recorded
+        transformer.transform(json!({
+            "event_type": "tick_size_change",
+            "asset_id": "0xunsubscribed",
+            "old_tick_size": "0.01",
+            "new_tick_size": "0.001"
+        }));
+        assert_eq!(transformer.tick_sizes.len(), 1);
+
+        // Trade prints on the market channel produce no book events or errors
+        let output = transformer.transform(json!({
+            "event_type": "last_trade_price",
+            "asset_id": ASSET,
+            "price": "0.456",
+            "side": "BUY",
+            "size": "10",
+            "timestamp": "1750428146322"
+        }));
+        assert!(output.is_empty(), "{output:?}");
+    }
+
+    fn dec(value: &str) -> Decimal {
+        Decimal::from_str_exact(value).unwrap()
+    }
+
+    #[test]
+    fn test_validate_book() {
+        struct TestCase {
+            input: OrderBook,
+            expected: Result<(), PolymarketBookError>,
+        }
+
+        let tests = vec![
+            TestCase {
+                // TC0: valid book
+                input: OrderBook::new(
+                    0,
+                    None,
+                    [(dec("0.45"), dec("1")), (dec("0.44"), dec("1"))],
+                    [(dec("0.46"), dec("1")), (dec("0.47"), dec("1"))],
+                ),
+                expected: Ok(()),
+            },
+            TestCase {
+                // TC1: one-sided book is valid
+                input: OrderBook::new(0, None, [(dec("0.45"), dec("1"))], []),
+                expected: Ok(()),
+            },
+            TestCase {
+                // TC2: duplicate bid levels are out of order
+                input: OrderBook::new(
+                    0,
+                    None,
+                    [(dec("0.45"), dec("1")), (dec("0.45"), dec("2"))],
+                    [(dec("0.46"), dec("1"))],
+                ),
+                expected: Err(PolymarketBookError::BidsNotDescending),
+            },
+            TestCase {
+                // TC3: duplicate ask levels are out of order
+                input: OrderBook::new(
+                    0,
+                    None,
+                    [(dec("0.45"), dec("1"))],
+                    [(dec("0.46"), dec("1")), (dec("0.46"), dec("2"))],
+                ),
+                expected: Err(PolymarketBookError::AsksNotAscending),
+            },
+            TestCase {
+                // TC4: crossed book
+                input: OrderBook::new(
+                    0,
+                    None,
+                    [(dec("0.50"), dec("1"))],
+                    [(dec("0.49"), dec("1"))],
+                ),
+                expected: Err(PolymarketBookError::Crossed {
+                    best_bid: dec("0.50"),
+                    best_ask: dec("0.49"),
+                }),
+            },
+        ];
+
+        for (index, test) in tests.into_iter().enumerate() {
+            let actual = validate_book(&test.input);
+            assert_eq!(actual, test.expected, "TC{index} failed");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_initial_snapshot_seeds_book() {
+        let instrument_map = transformer().instrument_map;
+        let snapshot = MarketEvent {
+            time_exchange: Utc::now(),
+            time_received: Utc::now(),
+            exchange: ExchangeId::Polymarket,
+            instrument: "instrument",
+            kind: OrderBookEvent::Snapshot(OrderBook::new(
+                0,
+                None,
+                [(dec("0.45"), dec("100"))],
+                [(dec("0.46"), dec("200"))],
+            )),
+        };
+        let (ws_sink_tx, _ws_sink_rx) = mpsc::unbounded_channel();
+
+        let mut transformer = <PolymarketOrderBookTransformer<_> as ExchangeTransformer<
+            super::super::Polymarket,
+            _,
+            OrderBooksL2,
+        >>::init(instrument_map, &[snapshot], ws_sink_tx)
+        .await
+        .unwrap();
+        assert!(transformer.books.contains_key(&sub_id()));
+
+        // Deltas after a reconnect are checked against the seeded book
+        let output = transformer.transform(price_change("0.47", "10", "BUY"));
+        assert!(is_invalid(&output), "{output:?}");
+    }
+}
@@ -0,0 +1,303 @@
+use super::super::message::de_string_or_u64;
+use crate::{
+    Identifier,
+    event::{MarketEvent, MarketIter},
+};
+use barter_instrument::{Side, exchange::ExchangeId};
+use barter_integration::subscription::SubscriptionId;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use smol_str::{SmolStr, format_smolstr};
+
+/// [`PolymarketUser`](super::PolymarketUser) message variants that can be received over
+/// WebSocket.
+///
+/// See docs: <https://docs.polymarket.com/developers/CLOB/websocket/user-channel>
+#[derive(Clone, PartialEq, Debug, Deserialize)]
+#[serde(tag = "event_type", rename_all = "snake_case")]
+pub enum PolymarketUserMessage {
+    Trade(PolymarketUserTrade),
+    Order(PolymarketUserOrder),
+    /// Any other event type, which has no subscription
+    #[serde(other)]
+    Other,
+}
+
+impl Identifier<Option<SubscriptionId>> for PolymarketUserMessage {
+    fn id(&self) -> Option<SubscriptionId> {
+        let market = match self {
+            Self::Trade(trade) => &trade.market,
+            Self::Order(order) => &order.market,
+            Self::Other => return None,
+        };
+        Some(SubscriptionId(format_smolstr!("user|{market}")))
+    }
+}
+
+/// Normalised [`PolymarketUser`](super::PolymarketUser) event, yielded by
+/// [`PolymarketUserEvents`](super::PolymarketUserEvents) subscriptions.
+#[derive(Clone, PartialEq, Debug, Deserialize, Serialize)]
+pub enum PolymarketUserEvent {
+    Trade(PolymarketUserTrade),
+    Order(PolymarketUserOrder),
+}
+
+/// Status of a [`PolymarketUserTrade`] as it settles on-chain.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum PolymarketTradeStatus {
+    /// Matched by the operator, the first and fastest fill signal
+    Matched,
+    Mined,
+    Confirmed,
+    Retrying,
+    Failed,
+}
+
+/// Polymarket user channel trade, sent when one of the account's orders is matched and
+/// again as the match settles.
+///
+/// ### Payload Example
+/// ```json
+/// {
+///   "event_type": "trade",
+///   "type": "TRADE",
+///   "id": "28c4d2eb-bbea-40e7-a9f0-b2fdb56b2c2e",
+///   "market": "0xbd31dc8a20211944f6b70f31557f1001557b59905b7738480ca09bd4532f84af",
+///   "asset_id": "52114319501245915516055106046884209969926127482827954674443846427813813222426",
+///   "side": "BUY",
+///   "price": "0.57",
+///   "size": "10",
+///   "status": "MATCHED",
+///   "taker_order_id": "0x06bc63e346ed4ceddce9efd6b3af37c8f8f440c92fe7da6b2d0f9e4ccbc50c42",
+///   "maker_orders": [
+///     {
+///       "order_id": "0xff354cd7ca7539dfa9c28d90943ab5779a4eac34b9b37a757d7b32bdfb11790b",
+///       "asset_id": "52114319501245915516055106046884209969926127482827954674443846427813813222426",
+///       "matched_amount": "10",
+///       "price": "0.57"
+///     }
+///   ],
+///   "timestamp": "1672290701"
+/// }
+/// ```
+#[derive(Clone, PartialEq, Debug, Deserialize, Serialize)]
+pub struct PolymarketUserTrade {
+    pub id: SmolStr,
+    /// Condition id of the market
+    pub market: SmolStr,
+    /// Outcome token traded
+    pub asset_id: SmolStr,
+    pub side: Side,
+    pub price: Decimal,
+    pub size: Decimal,
+    pub status: PolymarketTradeStatus,
+    pub taker_order_id: SmolStr,
+    #[serde(default)]
+    pub maker_orders: Vec<PolymarketMakerOrder>,
+    /// Unix timestamp in seconds
+    #[serde(default, deserialize_with = "de_string_or_u64")]
+    pub timestamp: Option<u64>,
+}
+
+/// Resting order filled by a [`PolymarketUserTrade`].
+#[derive(Clone, PartialEq, Debug, Deserialize, Serialize)]
+pub struct PolymarketMakerOrder {
+    pub order_id: SmolStr,
+    pub asset_id: SmolStr,
+    pub matched_amount: Decimal,
+    pub price: Decimal,
+}
+
+/// Kind of change to a [`PolymarketUserOrder`].
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum PolymarketOrderEventKind {
+    /// Order placed on the book
+    Placement,
+    /// Order partially matched
+    Update,
+    /// Order cancelled
+    Cancellation,
+}
+
+/// Polymarket user channel order update, sent when one of the account's orders is
+/// placed, matched or cancelled.
+///
+/// ### Payload Example
+/// ```json
+/// {
+///   "event_type": "order",
+///   "type": "PLACEMENT",
+///   "id": "0xff354cd7ca7539dfa9c28d90943ab5779a4eac34b9b37a757d7b32bdfb11790b",
+///   "market": "0xbd31dc8a20211944f6b70f31557f1001557b59905b7738480ca09bd4532f84af",
+///   "asset_id": "52114319501245915516055106046884209969926127482827954674443846427813813222426",
+///   "side": "SELL",
+///   "price": "0.57",
+///   "original_size": "10",
+///   "size_matched": "0",
+///   "timestamp": "1672290687"
+/// }
+/// ```
+#[derive(Clone, PartialEq, Debug, Deserialize, Serialize)]
+pub struct PolymarketUserOrder {
+    /// Order id
+    pub id: SmolStr,
+    /// Condition id of the market
+    pub market: SmolStr,
+    /// Outcome token ordered
+    pub asset_id: SmolStr,
+    #[serde(rename = "type")]
+    pub kind: PolymarketOrderEventKind,
+    pub side: Side,
+    pub price: Decimal,
+    pub original_size: Decimal,
+    pub size_matched: Decimal,
+    /// Unix timestamp in seconds
+    #[serde(default, deserialize_with = "de_string_or_u64")]
+    pub timestamp: Option<u64>,
+}
+
+impl<InstrumentKey> From<(ExchangeId, InstrumentKey, PolymarketUserMessage)>
+    for MarketIter<InstrumentKey, PolymarketUserEvent>
+{
+    fn from(
+        (exchange, instrument, message): (ExchangeId, InstrumentKey, PolymarketUserMessage),
+    ) -> Self {
+        let (timestamp, kind) = match message {
+            PolymarketUserMessage::Trade(trade) => {
+                (trade.timestamp, PolymarketUserEvent::Trade(trade))
+            }
+            PolymarketUserMessage::Order(order) => {
+                (order.timestamp, PolymarketUserEvent::Order(order))
+            }
+            PolymarketUserMessage::Other => return Self(vec![]),
+        };
+
+        let time_received = Utc::now();
+        let time_exchange = timestamp
+            .and_then(|secs| DateTime::from_timestamp(i64::try_from(secs).ok()?, 0))
+            .unwrap_or(time_received);
+
+        Self(vec![Ok(MarketEvent {
+            time_exchange,
+            time_received,
+            exchange,
+            instrument,
+            kind,
+        })])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    const MARKET: &str = "0xbd31dc8a20211944f6b70f31557f1001557b59905b7738480ca09bd4532f84af";
+
+    #[test]
+    fn test_de_polymarket_user_trade() {
+        let input = r#"{
+            "asset_id": "52114319501245915516055106046884209969926127482827954674443846427813813222426",
+            "event_type": "trade",
+            "id": "28c4d2eb-bbea-40e7-a9f0-b2fdb56b2c2e",
+            "last_update": "1672290701",
+            "maker_orders": [
+                {
+                    "asset_id": "52114319501245915516055106046884209969926127482827954674443846427813813222426",
+                    "matched_amount": "10",
+                    "order_id": "0xff354cd7ca7539dfa9c28d90943ab5779a4eac34b9b37a757d7b32bdfb11790b",
+                    "outcome": "YES",
+                    "owner": "9180014b-33c8-9240-a14b-bdca11c0a465",
+                    "price": "0.57"
+                }
+            ],
+            "market": "0xbd31dc8a20211944f6b70f31557f1001557b59905b7738480ca09bd4532f84af",
+            "matchtime": "1672290701",
+            "outcome": "YES",
+            "owner": "9180014b-33c8-9240-a14b-bdca11c0a465",
+            "price": "0.57",
+            "side": "BUY",
+            "size": "10",
+            "status": "MATCHED",
+            "taker_order_id": "0x06bc63e346ed4ceddce9efd6b3af37c8f8f440c92fe7da6b2d0f9e4ccbc50c42",
+            "timestamp": "1672290701",
+            "trade_owner": "9180014b-33c8-9240-a14b-bdca11c0a465",
+            "type": "TRADE"
+        }"#;
+
+        let message: PolymarketUserMessage = serde_json::from_str(input).unwrap();
+        assert_eq!(
+            message.id(),
+            Some(SubscriptionId(format_smolstr!("user|{MARKET}")))
+        );
+
+        let PolymarketUserMessage::Trade(trade) = message else {
+            panic!("expected trade, got {message:?}");
+        };
+        assert_eq!(trade.side, Side::Buy);
+        assert_eq!(trade.price, dec!(0.57));
+        assert_eq!(trade.size, dec!(10));
+        assert_eq!(trade.status, PolymarketTradeStatus::Matched);
+        assert_eq!(
+            trade.taker_order_id,
+            "0x06bc63e346ed4ceddce9efd6b3af37c8f8f440c92fe7da6b2d0f9e4ccbc50c42"
+        );
+        assert_eq!(trade.maker_orders.len(), 1);
+        assert_eq!(trade.maker_orders[0].matched_amount, dec!(10));
+        assert_eq!(trade.timestamp, Some(1672290701));
+    }
+
+    #[test]
+    fn test_de_polymarket_user_order() {
+        let input = r#"{
+            "asset_id": "52114319501245915516055106046884209969926127482827954674443846427813813222426",
+            "associate_trades": null,
+            "event_type": "order",
+            "id": "0xff354cd7ca7539dfa9c28d90943ab5779a4eac34b9b37a757d7b32bdfb11790b",
+            "market": "0xbd31dc8a20211944f6b70f31557f1001557b59905b7738480ca09bd4532f84af",
+            "order_owner": "9180014b-33c8-9240-a14b-bdca11c0a465",
+            "original_size": "10",
+            "outcome": "YES",
+            "owner": "9180014b-33c8-9240-a14b-bdca11c0a465",
+            "price": "0.57",
+            "side": "SELL",
+            "size_matched": "0",
+            "timestamp": "1672290687",
+            "type": "PLACEMENT"
+        }"#;
+
+        let message: PolymarketUserMessage = serde_json::from_str(input).unwrap();
+        let PolymarketUserMessage::Order(order) = &message else {
+            panic!("expected order, got {message:?}");
+        };
+        assert_eq!(order.kind, PolymarketOrderEventKind::Placement);
+        assert_eq!(order.side, Side::Sell);
+        assert_eq!(order.original_size, dec!(10));
+        assert_eq!(order.size_matched, Decimal::ZERO);
+
+        let events = MarketIter::<&str, PolymarketUserEvent>::from((
+            ExchangeId::Polymarket,
+            "instrument",
+            message,
+        ))
+        .0;
+        let event = events[0].as_ref().unwrap();
+        assert_eq!(event.time_exchange.timestamp(), 1672290687);
+        assert!(matches!(
+            &event.kind,
+            PolymarketUserEvent::Order(order) if order.price == dec!(0.57)
+        ));
+    }
+
+    #[test]
+    fn test_de_polymarket_user_unknown_event() {
+        let message: PolymarketUserMessage =
+            serde_json::from_str(r#"{"event_type": "heartbeat", "market": "0xabc"}"#).unwrap();
+
+        assert_eq!(message, PolymarketUserMessage::Other);
+        assert_eq!(message.id(), None);
+    }
+}
@@ -0,0 +1,249 @@
+use self::{
+    message::{PolymarketUserEvent, PolymarketUserMessage},
+    subscriber::PolymarketUserSubscriber,
+};
+use super::{
+    BASE_URL_POLYMARKET_USER, POLYMARKET_IDLE_TIMEOUT, channel::PolymarketChannel,
+    market::PolymarketMarket, subscription::PolymarketSubResponse,
+};
+use crate::{
+    ExchangeWsStream, Identifier, NoInitialSnapshots,
+    exchange::{Connector, ExchangeSub, PingInterval, StreamSelector},
+    instrument::{InstrumentData, MarketInstrumentData},
+    subscriber::validator::WebSocketSubValidator,
+    subscription::{Subscription, SubscriptionKind},
+    transformer::stateless::StatelessTransformer,
+};
+use barter_instrument::{
+    Keyed, exchange::ExchangeId, instrument::market_data::MarketDataInstrument,
+};
+use barter_integration::{error::SocketError, protocol::websocket::WsMessage};
+use barter_macro::{DeExchange, SerExchange};
+use derive_more::Display;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::RwLock;
+use url::Url;
+
+/// Order and trade message types for [`PolymarketUser`].
+pub mod message;
+
+/// Authenticated [`Subscriber`](crate::subscriber::Subscriber) for [`PolymarketUser`].
+pub mod subscriber;
+
+/// Process-wide credentials set by [`PolymarketUser::set_credentials`].
+static POLYMARKET_USER_CREDENTIALS: RwLock<Option<PolymarketUserCredentials>> = RwLock::new(None);
+
+/// Polymarket CLOB API credentials authenticating a [`PolymarketUser`] subscription.
+#[derive(Clone, PartialEq, Eq)]
+pub struct PolymarketUserCredentials {
+    pub api_key: String,
+    pub secret: String,
+    pub passphrase: String,
+}
+
+impl std::fmt::Debug for PolymarketUserCredentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PolymarketUserCredentials")
+            .field("api_key", &self.api_key)
+            .field("secret", &"[REDACTED]")
+            .field("passphrase", &"[REDACTED]")
+            .finish()
+    }
+}
+
+/// Polymarket authenticated user channel, streaming the account's own order and trade
+/// events for the subscribed markets.
+///
+/// Subscriptions are per market, identified by the condition id held in the instrument
+/// name, so subscribe once per condition id rather than once per outcome token.
+///
+/// See docs: <https://docs.polymarket.com/developers/CLOB/websocket/user-channel>
+#[derive(
+    Copy,
+    Clone,
+    Eq,
+    PartialEq,
+    Ord,
+    PartialOrd,
+    Hash,
+    Debug,
+    Default,
+    Display,
+    DeExchange,
+    SerExchange,
+)]
+pub struct PolymarketUser;
+
+impl PolymarketUser {
+    /// Set the API credentials sent on every current and future [`PolymarketUser`]
+    /// connection, including reconnects.
+    ///
+    /// [`Connector`]s have no instance to carry credentials, so like the other
+    /// [`Polymarket`](super::Polymarket) settings this is process-wide.
+    pub fn set_credentials(credentials: PolymarketUserCredentials) {
+        *POLYMARKET_USER_CREDENTIALS
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(credentials);
+    }
+
+    /// Credentials set by [`Self::set_credentials`], if any.
+    pub fn credentials() -> Option<PolymarketUserCredentials> {
+        POLYMARKET_USER_CREDENTIALS
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+}
+
+/// Barter [`Subscription`] [`SubscriptionKind`] that yields [`PolymarketUserEvent`]
+/// [`MarketEvent<T>`](crate::event::MarketEvent) events.
+#[derive(
+    Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default, Deserialize, Serialize,
+)]
+pub struct PolymarketUserEvents;
+
+impl SubscriptionKind for PolymarketUserEvents {
+    type Event = PolymarketUserEvent;
+
+    fn as_str(&self) -> &'static str {
+        "polymarket_user_events"
+    }
+}
+
+impl std::fmt::Display for PolymarketUserEvents {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl<Instrument> Identifier<PolymarketChannel>
+    for Subscription<PolymarketUser, Instrument, PolymarketUserEvents>
+{
+    fn id(&self) -> PolymarketChannel {
+        PolymarketChannel::USER
+    }
+}
+
+impl<Kind> Identifier<PolymarketMarket>
+    for Subscription<PolymarketUser, MarketDataInstrument, Kind>
+{
+    fn id(&self) -> PolymarketMarket {
+        // The "base" field holds the condition id
+        PolymarketMarket(self.instrument.base.name().clone())
+    }
+}
+
+impl<InstrumentKey, Kind> Identifier<PolymarketMarket>
+    for Subscription<PolymarketUser, Keyed<InstrumentKey, MarketDataInstrument>, Kind>
+{
+    fn id(&self) -> PolymarketMarket {
+        PolymarketMarket(self.instrument.value.base.name().clone())
+    }
+}
+
+impl<InstrumentKey, Kind> Identifier<PolymarketMarket>
+    for Subscription<PolymarketUser, MarketInstrumentData<InstrumentKey>, Kind>
+{
+    fn id(&self) -> PolymarketMarket {
+        PolymarketMarket(self.instrument.name_exchange.name().clone())
+    }
+}
+
+impl Connector for PolymarketUser {
+    const ID: ExchangeId = ExchangeId::Polymarket;
+    type Channel = PolymarketChannel;
+    type Market = PolymarketMarket;
+    type Subscriber = PolymarketUserSubscriber;
+    type SubValidator = WebSocketSubValidator;
+    type SubResponse = PolymarketSubResponse;
+
+    fn url() -> Result<Url, SocketError> {
+        Url::parse(BASE_URL_POLYMARKET_USER).map_err(SocketError::UrlParse)
+    }
+
+    fn ping_interval() -> Option<PingInterval> {
+        Some(PingInterval {
+            interval: tokio::time::interval(std::time::Duration::from_secs(10)),
+            ping: || WsMessage::text("PING"),
+        })
+    }
+
+    fn idle_timeout() -> Option<std::time::Duration> {
+        Some(POLYMARKET_IDLE_TIMEOUT)
+    }
+
+    fn requests(exchange_subs: Vec<ExchangeSub<Self::Channel, Self::Market>>) -> Vec<WsMessage> {
+        let mut markets = exchange_subs
+            .iter()
+            .map(|ExchangeSub { market, .. }| market.as_ref())
+            .collect::<Vec<_>>();
+        markets.sort_unstable();
+        markets.dedup();
+
+        // Polymarket format: {"type": "user", "markets": ["condition1", ...]}, with the
+        // credentials attached by the PolymarketUserSubscriber
+        vec![WsMessage::text(
+            json!({
+                "type": PolymarketChannel::USER.as_ref(),
+                "markets": markets
+            })
+            .to_string(),
+        )]
+    }
+
+    fn expected_responses<InstrumentKey>(_map: &crate::subscription::Map<InstrumentKey>) -> usize {
+        // Like the market channel, the user channel sends no subscription confirmation
+        0
+    }
+}
+
+impl<Instrument> StreamSelector<Instrument, PolymarketUserEvents> for PolymarketUser
+where
+    Instrument: InstrumentData,
+{
+    type SnapFetcher = NoInitialSnapshots;
+    type Stream = ExchangeWsStream<
+        StatelessTransformer<Self, Instrument::Key, PolymarketUserEvents, PolymarketUserMessage>,
+    >;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_polymarket_user_requests() {
+        let subs = ["0xcondition_b", "0xcondition_a", "0xcondition_b"]
+            .into_iter()
+            .map(|market| ExchangeSub {
+                channel: PolymarketChannel::USER,
+                market: PolymarketMarket::new(market),
+            })
+            .collect();
+
+        let requests = PolymarketUser::requests(subs);
+
+        // A single request listing each market once
+        assert_eq!(
+            requests,
+            vec![WsMessage::text(
+                r#"{"markets":["0xcondition_a","0xcondition_b"],"type":"user"}"#
+            )]
+        );
+    }
+
+    #[test]
+    fn test_polymarket_user_credentials_debug_redacted() {
+        let credentials = PolymarketUserCredentials {
+            api_key: "test-key".to_string(),
+            secret: "s3cr3t".to_string(),
+            passphrase: "p4ss".to_string(),
+        };
+
+        let debug = format!("{credentials:?}");
+        assert!(debug.contains("test-key"));
+        assert!(!debug.contains("s3cr3t"));
+        assert!(!debug.contains("p4ss"));
+    }
+}
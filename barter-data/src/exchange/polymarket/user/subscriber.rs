@@ -0,0 +1,264 @@
+use super::{PolymarketUser, PolymarketUserCredentials};
+use crate::{
+    Identifier,
+    exchange::Connector,
+    instrument::InstrumentData,
+    subscriber::{
+        Subscribed, Subscriber,
+        mapper::{SubscriptionMapper, WebSocketSubMapper},
+        validator::SubscriptionValidator,
+    },
+    subscription::{Subscription, SubscriptionKind, SubscriptionMeta},
+};
+use async_trait::async_trait;
+use barter_integration::{
+    error::SocketError,
+    protocol::websocket::{WsMessage, connect},
+};
+use futures::SinkExt;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::debug;
+use url::Url;
+
+/// [`Subscriber`] for the [`PolymarketUser`] channel, which attaches the API credentials set
+/// via [`PolymarketUser::set_credentials`] to every subscription request.
+///
+/// Since [`Subscriber::subscribe`] runs on every (re)connection, the authenticated
+/// subscription is re-sent each time the stream reconnects.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Deserialize, Serialize)]
+pub struct PolymarketUserSubscriber;
+
+#[async_trait]
+impl Subscriber for PolymarketUserSubscriber {
+    type SubMapper = WebSocketSubMapper;
+
+    async fn subscribe<Exchange, Instrument, Kind>(
+        subscriptions: &[Subscription<Exchange, Instrument, Kind>],
+    ) -> Result<Subscribed<Instrument::Key>, SocketError>
+    where
+        Exchange: Connector + Send + Sync,
+        Kind: SubscriptionKind + Send + Sync,
+        Instrument: InstrumentData,
+        Subscription<Exchange, Instrument, Kind>:
+            Identifier<Exchange::Channel> + Identifier<Exchange::Market>,
+    {
+        let credentials = PolymarketUser::credentials().ok_or_else(|| {
+            SocketError::Subscribe(
+                "PolymarketUser credentials not set, see PolymarketUser::set_credentials"
+                    .to_string(),
+            )
+        })?;
+
+        subscribe_authenticated(Exchange::url()?, &credentials, subscriptions).await
+    }
+}
+
+/// Connect to the provided `url` and send each [`Connector::requests`] subscription with the
+/// provided [`PolymarketUserCredentials`] attached, before validating the responses.
+pub async fn subscribe_authenticated<Exchange, Instrument, Kind>(
+    url: Url,
+    credentials: &PolymarketUserCredentials,
+    subscriptions: &[Subscription<Exchange, Instrument, Kind>],
+) -> Result<Subscribed<Instrument::Key>, SocketError>
+where
+    Exchange: Connector + Send + Sync,
+    Kind: SubscriptionKind + Send + Sync,
+    Instrument: InstrumentData,
+    Subscription<Exchange, Instrument, Kind>:
+        Identifier<Exchange::Channel> + Identifier<Exchange::Market>,
+{
+    // Define variables for logging ergonomics
+    let exchange = Exchange::ID;
+    debug!(%exchange, %url, ?subscriptions, "subscribing to authenticated WebSocket");
+
+    // Connect to exchange
+    let mut websocket = connect(url).await?;
+    debug!(%exchange, ?subscriptions, "connected to authenticated WebSocket");
+
+    // Map &[Subscription<Exchange, Kind>] to SubscriptionMeta
+    let SubscriptionMeta {
+        instrument_map,
+        ws_subscriptions,
+    } = WebSocketSubMapper::map::<Exchange, Instrument, Kind>(subscriptions);
+
+    // Send authenticated Subscriptions over WebSocket, omitting credentials from the logs
+    for subscription in ws_subscriptions {
+        debug!(%exchange, payload = ?subscription, "sending authenticated exchange subscription");
+        websocket
+            .send(authenticate(subscription, credentials)?)
+            .await
+            .map_err(|error| SocketError::WebSocket(Box::new(error)))?;
+    }
+
+    // Validate Subscription responses
+    let (map, buffered_websocket_events) = Exchange::SubValidator::validate::<
+        Exchange,
+        Instrument::Key,
+        Kind,
+    >(instrument_map, &mut websocket)
+    .await?;
+
+    debug!(%exchange, "successfully initialised authenticated WebSocket stream");
+    Ok(Subscribed {
+        websocket,
+        map,
+        buffered_websocket_events,
+    })
+}
+
+/// Attach the `auth` object expected by the Polymarket user channel to a JSON subscription
+/// request.
+///
+/// See docs: <https://docs.polymarket.com/developers/CLOB/websocket/wss-auth>
+pub fn authenticate(
+    request: WsMessage,
+    credentials: &PolymarketUserCredentials,
+) -> Result<WsMessage, SocketError> {
+    let WsMessage::Text(payload) = request else {
+        return Err(SocketError::Subscribe(format!(
+            "expected text subscription request, got: {request:?}"
+        )));
+    };
+
+    let mut payload = serde_json::from_str::<serde_json::Value>(payload.as_str())
+        .map_err(|error| SocketError::Deserialise {
+            error,
+            payload: payload.to_string(),
+        })?;
+
+    let serde_json::Value::Object(fields) = &mut payload else {
+        return Err(SocketError::Subscribe(format!(
+            "expected JSON object subscription request, got: {payload}"
+        )));
+    };
+
+    fields.insert(
+        "auth".to_string(),
+        json!({
+            "apiKey": credentials.api_key,
+            "secret": credentials.secret,
+            "passphrase": credentials.passphrase,
+        }),
+    );
+
+    Ok(WsMessage::text(payload.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        exchange::polymarket::user::PolymarketUserEvents,
+        streams::{
+            consumer::StreamKey,
+            reconnect::stream::{
+                ReconnectingStream, ReconnectionBackoffPolicy, init_reconnecting_stream,
+            },
+        },
+    };
+    use barter_instrument::{
+        exchange::ExchangeId,
+        instrument::market_data::{MarketDataInstrument, kind::MarketDataInstrumentKind},
+    };
+    use futures::StreamExt;
+    use std::sync::{Arc, Mutex};
+
+    fn credentials() -> PolymarketUserCredentials {
+        PolymarketUserCredentials {
+            api_key: "key".to_string(),
+            secret: "secret".to_string(),
+            passphrase: "passphrase".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_authenticate() {
+        let request = WsMessage::text(r#"{"markets":["0xcondition"],"type":"user"}"#);
+
+        let actual = authenticate(request, &credentials()).unwrap();
+
+        let actual = serde_json::from_str::<serde_json::Value>(actual.to_text().unwrap()).unwrap();
+        assert_eq!(
+            actual,
+            json!({
+                "type": "user",
+                "markets": ["0xcondition"],
+                "auth": {
+                    "apiKey": "key",
+                    "secret": "secret",
+                    "passphrase": "passphrase",
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn test_authenticate_rejects_non_json_object() {
+        assert!(authenticate(WsMessage::text("PING"), &credentials()).is_err());
+        assert!(authenticate(WsMessage::text("[]"), &credentials()).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_resubscribe_on_reconnect() {
+        // Mock server recording the first message of each connection, then sending one
+        // event and closing the connection to force a reconnect
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = Url::parse(&format!("ws://{}", listener.local_addr().unwrap())).unwrap();
+        let received = Arc::new(Mutex::new(Vec::new()));
+
+        tokio::spawn({
+            let received = Arc::clone(&received);
+            async move {
+                while let Ok((stream, _)) = listener.accept().await {
+                    let mut websocket = tokio_tungstenite::accept_async(stream).await.unwrap();
+                    if let Some(Ok(message)) = websocket.next().await {
+                        received.lock().unwrap().push(message.into_text().unwrap().to_string());
+                    }
+                    let _ = websocket.send(WsMessage::text("{}")).await;
+                    let _ = websocket.close(None).await;
+                }
+            }
+        });
+
+        let subscriptions: Vec<Subscription<_, MarketDataInstrument, _>> =
+            vec![Subscription::from((
+                PolymarketUser,
+                "0xcondition",
+                "yes",
+                MarketDataInstrumentKind::Spot,
+                PolymarketUserEvents,
+            ))];
+
+        let stream = init_reconnecting_stream(move || {
+            let url = url.clone();
+            let subscriptions = subscriptions.clone();
+            async move {
+                subscribe_authenticated(url, &credentials(), &subscriptions)
+                    .await
+                    .map(|subscribed| subscribed.websocket)
+            }
+        })
+        .await
+        .unwrap()
+        .with_reconnect_backoff(
+            ReconnectionBackoffPolicy::new(1, 1, 1),
+            StreamKey::new("user_stream", ExchangeId::Polymarket, None),
+        );
+
+        // Drain two connections, the second of which is a reconnection
+        let connections = stream.take(2).flat_map(|websocket| websocket).collect::<Vec<_>>();
+        tokio::time::timeout(std::time::Duration::from_secs(5), connections)
+            .await
+            .unwrap();
+
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 2);
+        for message in received.iter() {
+            let message = serde_json::from_str::<serde_json::Value>(message).unwrap();
+            assert_eq!(message["type"], "user");
+            assert_eq!(message["markets"], json!(["0xcondition"]));
+            assert_eq!(message["auth"]["apiKey"], "key");
+        }
+    }
+}
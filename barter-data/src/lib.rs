@@ -108,7 +108,7 @@ use barter_integration::{
         StreamParser,
         websocket::{WebSocketParser, WsMessage, WsSink, WsStream},
     },
-    stream::ExchangeStream,
+    stream::{ExchangeStream, idle::IdleTimeout},
 };
 use futures::{SinkExt, Stream, StreamExt};
 use std::{collections::VecDeque, future::Future};
@@ -163,7 +163,12 @@ pub mod transformer;
 
 /// Convenient type alias for an [`ExchangeStream`] utilising a tungstenite
 /// [`WebSocket`](barter_integration::protocol::websocket::WebSocket).
-pub type ExchangeWsStream<Transformer> = ExchangeStream<WebSocketParser, WsStream, Transformer>;
+///
+/// The [`WsStream`] is guarded by the
+/// [`Connector::idle_timeout`](exchange::Connector::idle_timeout) watchdog, which ends the stream
+/// if the exchange goes silent.
+pub type ExchangeWsStream<Transformer> =
+    ExchangeStream<WebSocketParser, IdleTimeout<WsStream>, Transformer>;
 
 /// Defines a generic identification type for the implementor.
 pub trait Identifier<T> {
@@ -242,6 +247,9 @@ where
         // Split WebSocket into WsStream & WsSink components
         let (ws_sink, ws_stream) = websocket.split();
 
+        // Error & end the WsStream if the exchange stops sending, triggering a reconnect
+        let ws_stream = IdleTimeout::new(ws_stream, Exchange::idle_timeout());
+
         // Spawn task to distribute Transformer messages (eg/ custom pongs) to the exchange
         let (ws_sink_tx, ws_sink_rx) = mpsc::unbounded_channel();
         tokio::spawn(distribute_messages_to_exchange(
@@ -263,14 +271,17 @@ where
         let mut transformer =
             Transformer::init(instrument_map, &initial_snapshots, ws_sink_tx).await?;
 
+        // Emit any initial snapshot events first, so buffered updates apply on top of them
+        let mut processed = initial_snapshots
+            .into_iter()
+            .map(Ok)
+            .collect::<VecDeque<_>>();
+
         // Process any buffered active subscription events received during Subscription validation
-        let mut processed = process_buffered_events::<WebSocketParser, _>(
+        processed.extend(process_buffered_events::<WebSocketParser, _>(
             &mut transformer,
             buffered_websocket_events,
-        );
-
-        // Extend buffered events with any initial snapshot events
-        processed.extend(initial_snapshots.into_iter().map(Ok));
+        ));
 
         Ok(ExchangeWsStream::new(ws_stream, transformer, processed))
     }
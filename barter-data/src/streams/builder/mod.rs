@@ -2,7 +2,7 @@ use super::Streams;
 use crate::{
     Identifier,
     error::DataError,
-    exchange::StreamSelector,
+    exchange::{Connector, StreamSelector},
     instrument::InstrumentData,
     streams::{
         consumer::{MarketStreamResult, STREAM_RECONNECTION_POLICY, init_market_stream},
@@ -13,7 +13,7 @@ use crate::{
 use barter_instrument::exchange::ExchangeId;
 use barter_integration::{Validator, channel::Channel};
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     fmt::{Debug, Display},
     future::Future,
     pin::Pin,
@@ -72,6 +72,10 @@ where
     /// Add a collection of [`Subscription`]s to the [`StreamBuilder`] that will be actioned on
     /// a distinct [`WebSocket`](barter_integration::protocol::websocket::WebSocket) connection.
     ///
+    /// If the collection exceeds the [`Connector::max_markets_per_connection`] of the exchange,
+    /// it is split across several connections (see [`connection_batches`]) whose events are
+    /// merged into the same [`Streams`] entry.
+    ///
     /// Note that [`Subscription`]s are not actioned until the
     /// [`init()`](StreamBuilder::init()) method is invoked.
     pub fn subscribe<SubIter, Sub, Exchange, Instrument>(mut self, subscriptions: SubIter) -> Self
@@ -105,11 +109,15 @@ where
             subscriptions.sort();
             subscriptions.dedup();
 
-            // Initialise a MarketEvent `ReconnectingStream`
-            let stream = init_market_stream(STREAM_RECONNECTION_POLICY, subscriptions).await?;
+            // Initialise a MarketEvent `ReconnectingStream` per connection
+            let batches =
+                connection_batches(subscriptions, Exchange::max_markets_per_connection());
+            for batch in batches {
+                let stream = init_market_stream(STREAM_RECONNECTION_POLICY, batch).await?;
 
-            // Forward MarketEvents to ExchangeTx
-            tokio::spawn(stream.forward_to(exchange_tx));
+                // Forward MarketEvents to ExchangeTx
+                tokio::spawn(stream.forward_to(exchange_tx.clone()));
+            }
 
             Ok(())
         }));
@@ -139,3 +147,41 @@ where
         })
     }
 }
+
+/// Split `subscriptions` into the batches actioned on distinct connections, each covering at most
+/// `max_markets` distinct exchange markets.
+///
+/// Subscriptions to the same market stay on the same connection where possible. With no
+/// `max_markets`, all `subscriptions` form a single batch.
+pub fn connection_batches<Exchange, Instrument, Kind>(
+    subscriptions: Vec<Subscription<Exchange, Instrument, Kind>>,
+    max_markets: Option<usize>,
+) -> Vec<Vec<Subscription<Exchange, Instrument, Kind>>>
+where
+    Exchange: Connector,
+    Subscription<Exchange, Instrument, Kind>: Identifier<Exchange::Market>,
+{
+    let Some(max_markets) = max_markets else {
+        return vec![subscriptions];
+    };
+
+    let mut batches = Vec::new();
+    let mut batch = Vec::new();
+    let mut markets = HashSet::new();
+    for subscription in subscriptions {
+        let market = Identifier::<Exchange::Market>::id(&subscription)
+            .as_ref()
+            .to_string();
+        if !markets.contains(&market) && markets.len() >= max_markets.max(1) {
+            batches.push(std::mem::take(&mut batch));
+            markets.clear();
+        }
+        markets.insert(market);
+        batch.push(subscription);
+    }
+    if !batch.is_empty() {
+        batches.push(batch);
+    }
+
+    batches
+}
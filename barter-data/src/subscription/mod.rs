@@ -23,6 +23,9 @@ pub mod candle;
 /// Liquidation [`SubscriptionKind`] and the associated Barter output data model.
 pub mod liquidation;
 
+/// Market status [`SubscriptionKind`] and the associated Barter output data model.
+pub mod status;
+
 /// Public trade [`SubscriptionKind`] and the associated Barter output data model.
 pub mod trade;
 
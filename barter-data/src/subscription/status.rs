@@ -0,0 +1,62 @@
+use super::SubscriptionKind;
+use serde::{Deserialize, Serialize};
+
+/// Barter [`Subscription`](super::Subscription) [`SubscriptionKind`] that yields [`MarketStatus`]
+/// [`MarketEvent<T>`](crate::event::MarketEvent) events.
+#[derive(
+    Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default, Deserialize, Serialize,
+)]
+pub struct MarketStatuses;
+
+impl SubscriptionKind for MarketStatuses {
+    type Event = MarketStatus;
+
+    fn as_str(&self) -> &'static str {
+        "market_statuses"
+    }
+}
+
+impl std::fmt::Display for MarketStatuses {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Normalised Barter [`MarketStatus`] model, describing whether a market is trading.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MarketStatus {
+    /// Market is open for trading.
+    Open,
+    /// Market has stopped trading, but may reopen.
+    Closed,
+    /// Market trading is paused, but may resume.
+    Deactivated,
+    /// Market outcome has been determined and awaits settlement.
+    Determined,
+    /// Market has settled and paid out.
+    Settled,
+}
+
+impl MarketStatus {
+    /// Whether the market can never trade again.
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, Self::Determined | Self::Settled)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Open => "open",
+            Self::Closed => "closed",
+            Self::Deactivated => "deactivated",
+            Self::Determined => "determined",
+            Self::Settled => "settled",
+        }
+    }
+}
+
+impl std::fmt::Display for MarketStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
@@ -0,0 +1,16 @@
+//! Kalshi request timestamps, signed by the REST client and the fill WebSocket handshake.
+//!
+//! Kalshi authenticates with an RSA-PSS signature over `{timestamp}{method}{path}`, where
+//! `timestamp` is the Unix time in milliseconds sent in the `KALSHI-ACCESS-TIMESTAMP` header
+//! and `path` excludes any query string. Signing itself is shared with barter-data, see
+//! [`rsa_pss::sign_request`](barter_integration::protocol::http::private::rsa_pss::sign_request).
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Current Unix time in milliseconds, as signed with each request.
+pub fn timestamp_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_millis() as u64
+}
@@ -1,7 +1,9 @@
 //! Kalshi API request/response models for Trade API v2.
 
+use barter_instrument::kalshi::{fee, price};
+use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Serialize, Serializer};
 
 /// Request body for POST /portfolio/orders.
 #[derive(Debug, Clone, Serialize)]
@@ -12,15 +14,73 @@ pub struct KalshiCreateOrder {
     #[serde(rename = "type")]
     pub order_type: String, // "limit" or "market"
     pub count: u32,
-    /// Price in cents (1-99)
-    pub yes_price: Option<u32>,
-    pub no_price: Option<u32>,
+    /// Price in dollars (0-1), see [`KalshiOrderPrice`]
+    #[serde(flatten)]
+    pub price: KalshiOrderPrice,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub expiration_ts: Option<i64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub sell_position_floor: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub buy_max_cost: Option<u32>,
+    /// UUID echoed back on the order, see [`client_order_uuid`](super::client_order_uuid)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_order_id: Option<String>,
+}
+
+/// Limit price of a [`KalshiCreateOrder`], in dollars (0-1), on the YES or NO outcome.
+///
+/// Whole cent prices are sent in cents as `yes_price` / `no_price`. Markets quoting
+/// sub-cent prices take prices off the cent grid in dollars, as `yes_price_dollars` /
+/// `no_price_dollars`, which would otherwise be clamped to a whole cent.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum KalshiOrderPrice {
+    Yes(Decimal),
+    No(Decimal),
+}
+
+impl KalshiOrderPrice {
+    /// Price in dollars (0-1) of the outcome ordered.
+    pub fn price(&self) -> Decimal {
+        match self {
+            Self::Yes(price) | Self::No(price) => *price,
+        }
+    }
+}
+
+impl Serialize for KalshiOrderPrice {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        #[derive(Default, Serialize)]
+        struct Wire {
+            yes_price: Option<u32>,
+            no_price: Option<u32>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            yes_price_dollars: Option<String>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            no_price_dollars: Option<String>,
+        }
+
+        let price = self.price();
+        let cents = (price * Decimal::ONE_HUNDRED).is_integer().then(|| price::to_cents(price));
+        let dollars = cents.is_none().then(|| format!("{price:.4}"));
+
+        let wire = match self {
+            Self::Yes(_) => Wire {
+                yes_price: cents,
+                yes_price_dollars: dollars,
+                ..Wire::default()
+            },
+            Self::No(_) => Wire {
+                no_price: cents,
+                no_price_dollars: dollars,
+                ..Wire::default()
+            },
+        };
+        wire.serialize(serializer)
+    }
 }
 
 /// Response from POST /portfolio/orders.
@@ -41,10 +101,26 @@ pub struct KalshiOrder {
     pub order_type: String,
     pub yes_price: Option<u32>,
     pub no_price: Option<u32>,
+    /// Exact prices of markets quoting sub-cent prices, whose cent prices are rounded
+    #[serde(default)]
+    pub yes_price_dollars: Option<Decimal>,
+    #[serde(default)]
+    pub no_price_dollars: Option<Decimal>,
     pub count: Option<u32>,
     pub remaining_count: Option<u32>,
     pub created_time: Option<String>,
     pub expiration_time: Option<String>,
+    #[serde(default)]
+    pub client_order_id: Option<String>,
+    /// Cost and fees of the fills so far, split by whether they took liquidity
+    #[serde(default)]
+    pub taker_fill_cost_dollars: Option<Decimal>,
+    #[serde(default)]
+    pub maker_fill_cost_dollars: Option<Decimal>,
+    #[serde(default)]
+    pub taker_fees_dollars: Option<Decimal>,
+    #[serde(default)]
+    pub maker_fees_dollars: Option<Decimal>,
 }
 
 /// Response from GET /portfolio/orders.
@@ -78,7 +154,38 @@ pub struct KalshiFill {
     pub count: u32,
     pub yes_price: u32,
     pub no_price: u32,
+    /// Exact prices of markets quoting sub-cent prices, whose cent prices are rounded
+    #[serde(default)]
+    pub yes_price_dollars: Option<Decimal>,
+    #[serde(default)]
+    pub no_price_dollars: Option<Decimal>,
+    /// Whether the fill took liquidity, paying the taker fee
+    #[serde(default)]
+    pub is_taker: Option<bool>,
+    /// Fee billed for the fill in dollars, sent by some API versions
+    #[serde(default)]
+    pub fee_cost: Option<Decimal>,
     pub created_time: String,
+    #[serde(default)]
+    pub client_order_id: Option<String>,
+}
+
+/// Net position in one market from GET /portfolio/positions.
+#[derive(Debug, Clone, Deserialize)]
+pub struct KalshiMarketPosition {
+    pub ticker: String,
+    /// Contracts held: positive for YES, negative for NO
+    pub position: i64,
+    /// Cost of the position, in cents
+    pub market_exposure: i64,
+}
+
+/// Response from GET /portfolio/positions.
+#[derive(Debug, Clone, Deserialize)]
+pub struct KalshiPositionsResponse {
+    #[serde(default)]
+    pub market_positions: Vec<KalshiMarketPosition>,
+    pub cursor: Option<String>,
 }
 
 /// Response from DELETE /portfolio/orders/{id}.
@@ -88,10 +195,84 @@ pub struct KalshiCancelResponse {
     pub reduced_by: Option<u32>,
 }
 
+/// Request body for POST /portfolio/orders/batched.
+#[derive(Debug, Clone, Serialize)]
+pub struct KalshiBatchCreateOrders<'a> {
+    pub orders: &'a [KalshiCreateOrder],
+}
+
+/// Response from POST /portfolio/orders/batched, one result per order in request order.
+#[derive(Debug, Clone, Deserialize)]
+pub struct KalshiBatchCreateResponse {
+    pub orders: Vec<KalshiBatchOrderResult>,
+}
+
+/// Outcome of one order in a batch, holding either the order or why it failed.
+#[derive(Debug, Clone, Deserialize)]
+pub struct KalshiBatchOrderResult {
+    #[serde(default)]
+    pub order: Option<KalshiOrder>,
+    #[serde(default)]
+    pub error: Option<KalshiErrorDetail>,
+}
+
+/// Request body for DELETE /portfolio/orders/batched.
+#[derive(Debug, Clone, Serialize)]
+pub struct KalshiBatchCancelOrders<'a> {
+    pub ids: &'a [String],
+}
+
+/// Response from DELETE /portfolio/orders/batched, one result per id in request order.
+#[derive(Debug, Clone, Deserialize)]
+pub struct KalshiBatchCancelResponse {
+    pub orders: Vec<KalshiBatchCancelResult>,
+}
+
+/// Outcome of one cancel in a batch, holding either the cancelled order or why it failed.
+#[derive(Debug, Clone, Deserialize)]
+pub struct KalshiBatchCancelResult {
+    pub order_id: String,
+    #[serde(default)]
+    pub order: Option<KalshiOrder>,
+    pub reduced_by: Option<u32>,
+    #[serde(default)]
+    pub error: Option<KalshiErrorDetail>,
+}
+
+/// Error response body, `{"error": {"code": "...", "message": "..."}}`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct KalshiErrorResponse {
+    pub error: KalshiErrorDetail,
+}
+
+/// Detail of a [`KalshiErrorResponse`].
+#[derive(Debug, Clone, Deserialize)]
+pub struct KalshiErrorDetail {
+    /// Machine readable code (eg/ "insufficient_balance")
+    #[serde(default)]
+    pub code: String,
+    #[serde(default)]
+    pub message: String,
+}
+
 impl KalshiOrder {
-    /// Price in decimal (0-1) from the yes_price cents field.
+    /// YES price in decimal (0-1), exact for markets quoting sub-cent prices.
     pub fn price_decimal(&self) -> Option<Decimal> {
-        self.yes_price.map(|p| Decimal::from(p) / Decimal::from(100))
+        self.yes_price_dollars.or(self.yes_price.map(price::from_cents))
+    }
+
+    /// NO price in decimal (0-1), exact for markets quoting sub-cent prices.
+    pub fn no_price_decimal(&self) -> Option<Decimal> {
+        self.no_price_dollars.or(self.no_price.map(price::from_cents))
+    }
+
+    /// Price of the outcome the order is for, ie/ the NO price of a NO order.
+    pub fn side_price_decimal(&self) -> Option<Decimal> {
+        if self.side.eq_ignore_ascii_case("no") {
+            self.no_price_decimal()
+        } else {
+            self.price_decimal()
+        }
     }
 
     /// Filled count = original count - remaining count.
@@ -101,7 +282,66 @@ impl KalshiOrder {
         total.saturating_sub(remaining)
     }
 
+    /// Total cost of the fills so far, if reported.
+    pub fn fill_cost(&self) -> Option<Decimal> {
+        sum_reported(self.taker_fill_cost_dollars, self.maker_fill_cost_dollars)
+    }
+
+    /// Total fees paid on the fills so far, if reported.
+    pub fn fees(&self) -> Option<Decimal> {
+        sum_reported(self.taker_fees_dollars, self.maker_fees_dollars)
+    }
+
     pub fn is_open(&self) -> bool {
         self.status == "resting"
     }
 }
+
+/// Sum of the taker and maker amounts, if either is reported.
+fn sum_reported(taker: Option<Decimal>, maker: Option<Decimal>) -> Option<Decimal> {
+    match (taker, maker) {
+        (None, None) => None,
+        (taker, maker) => Some(taker.unwrap_or_default() + maker.unwrap_or_default()),
+    }
+}
+
+impl KalshiFill {
+    /// Fill price of the outcome bought or sold (0-1), exact for markets quoting sub-cent
+    /// prices.
+    pub fn price_decimal(&self) -> Decimal {
+        if self.side.eq_ignore_ascii_case("no") {
+            self.no_price_dollars.unwrap_or(price::from_cents(self.no_price))
+        } else {
+            self.yes_price_dollars.unwrap_or(price::from_cents(self.yes_price))
+        }
+    }
+
+    /// Fee billed for the fill, see [`fill_fee`].
+    pub fn fee(&self) -> Decimal {
+        fill_fee(self.fee_cost, self.is_taker, self.price_decimal(), self.count)
+    }
+
+    /// Time the fill executed, or now if `created_time` is not RFC 3339.
+    pub fn time_exchange(&self) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(&self.created_time)
+            .map(|time| time.with_timezone(&Utc))
+            .unwrap_or_else(|_| Utc::now())
+    }
+}
+
+/// Fee in dollars billed for a fill of `count` contracts at `price`.
+///
+/// Uses the `fee_cost` reported by Kalshi if present, otherwise the billed taker fee. Maker
+/// fills are fee free, and fills not known to be maker fills are charged as taker fills.
+pub fn fill_fee(
+    fee_cost: Option<Decimal>,
+    is_taker: Option<bool>,
+    price: Decimal,
+    count: u32,
+) -> Decimal {
+    match (fee_cost, is_taker) {
+        (Some(fee_cost), _) => fee_cost,
+        (None, Some(false)) => Decimal::ZERO,
+        (None, _) => fee::taker_fee(price, Decimal::from(count)),
+    }
+}
@@ -2,34 +2,48 @@
 //!
 //! Connects to the authenticated Kalshi WS endpoint and subscribes to the
 //! `fill` channel for real-time trade fill notifications.
+//!
+//! As with the Polymarket user WS, the fill stream ends when the server closes
+//! the socket, ending the account stream so the execution manager reconnects,
+//! re-signs and re-subscribes with backoff.
 
 use crate::{
     AccountEvent, AccountEventKind, UnindexedAccountEvent,
     order::id::{OrderId, StrategyId},
     trade::{AssetFees, Trade, TradeId},
 };
-use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+use super::{auth, model::fill_fee};
 use barter_instrument::{
     Side,
     asset::QuoteAsset,
     exchange::ExchangeId,
     instrument::name::InstrumentNameExchange,
+    kalshi::price,
+};
+use barter_integration::protocol::{
+    http::private::rsa_pss::sign_request,
+    websocket::{WebSocket, WsError, WsMessage, connect_with_headers},
 };
-use barter_integration::protocol::websocket::{WebSocket, WsMessage, connect_with_headers};
-use chrono::Utc;
-use futures::{SinkExt, StreamExt, stream::BoxStream};
-use rsa::{RsaPrivateKey, pss::SigningKey, signature::{RandomizedSigner, SignatureEncoding}};
+use chrono::{DateTime, Utc};
+use futures::{SinkExt, Stream, StreamExt, stream::BoxStream};
+use rsa::RsaPrivateKey;
 use rust_decimal::Decimal;
 use serde::Deserialize;
-use sha2::Sha256;
 use smol_str::SmolStr;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::{
+    future::ready,
+    time::Duration,
+};
 use tracing::{debug, info, warn};
 
 const KALSHI_WS_URL: &str = "wss://api.elections.kalshi.com/trade-api/ws/v2";
 const KALSHI_DEMO_WS_URL: &str = "wss://demo-api.kalshi.co/trade-api/ws/v2";
 const KALSHI_WS_SIGN_PATH: &str = "/trade-api/ws/v2";
 
+/// How long the account stream polls balances only after the fill WS fails to
+/// connect, before ending so the connection is retried.
+pub const FILL_WS_RETRY_INTERVAL: Duration = Duration::from_secs(60);
+
 // ---------------------------------------------------------------------------
 // WS message types
 // ---------------------------------------------------------------------------
@@ -59,23 +73,89 @@ pub struct KalshiWsFill {
     #[allow(dead_code)]
     pub sid: u64,
     #[allow(dead_code)]
-    pub seq: u64,
+    #[serde(default)]
+    pub seq: Option<u64>,
     pub msg: KalshiWsFillData,
 }
 
+/// Fill on one of the account's orders.
+///
+/// See docs: <https://docs.kalshi.com/websockets/user-fills>
 #[derive(Debug, Clone, Deserialize)]
 pub struct KalshiWsFillData {
     pub trade_id: String,
     pub order_id: String,
+    #[serde(alias = "market_ticker")]
     pub ticker: String,
     pub side: String,   // "yes" or "no"
     pub action: String, // "buy" or "sell"
     pub count: u32,
     pub yes_price: u32, // cents
-    #[allow(dead_code)]
-    pub no_price: u32,  // cents
-    #[allow(dead_code)]
-    pub created_time: Option<String>,
+    /// Sent by some API versions, otherwise implied by `yes_price`
+    #[serde(default)]
+    pub no_price: Option<u32>, // cents
+    /// Exact prices of markets quoting sub-cent prices, whose cent prices are rounded
+    #[serde(default)]
+    pub yes_price_dollars: Option<Decimal>,
+    #[serde(default)]
+    pub no_price_dollars: Option<Decimal>,
+    /// Whether the fill took liquidity, paying the taker fee
+    #[serde(default)]
+    pub is_taker: Option<bool>,
+    /// Fee billed for the fill in dollars, sent by some API versions
+    #[serde(default)]
+    pub fee_cost: Option<Decimal>,
+    /// Unix timestamp in seconds
+    #[serde(default)]
+    pub ts: Option<i64>,
+}
+
+impl KalshiWsFillData {
+    /// Outcome instrument filled, named `"{ticker}_{yes|no}"`.
+    pub fn instrument(&self) -> InstrumentNameExchange {
+        InstrumentNameExchange::from(
+            format!("{}_{}", self.ticker, self.side.to_ascii_lowercase()).as_str(),
+        )
+    }
+
+    /// Fill price of the outcome bought or sold, in dollars.
+    pub fn price(&self) -> Decimal {
+        let yes_price = self.yes_price_dollars.unwrap_or(price::from_cents(self.yes_price));
+        if self.side.eq_ignore_ascii_case("no") {
+            self.no_price_dollars
+                .or(self.no_price.map(price::from_cents))
+                .unwrap_or(Decimal::ONE - yes_price.min(Decimal::ONE))
+        } else {
+            yes_price
+        }
+    }
+
+    /// Convert the fill into a [`Trade`] on the filled outcome instrument.
+    pub fn to_trade(&self) -> Trade<QuoteAsset, InstrumentNameExchange> {
+        let side = match self.action.as_str() {
+            "buy" => Side::Buy,
+            _ => Side::Sell,
+        };
+        let price = self.price();
+
+        Trade {
+            id: TradeId(SmolStr::new(&self.trade_id)),
+            order_id: OrderId(SmolStr::new(&self.order_id)),
+            instrument: self.instrument(),
+            strategy: StrategyId::new("unknown"),
+            time_exchange: self
+                .ts
+                .and_then(|ts| DateTime::from_timestamp(ts, 0))
+                .unwrap_or_else(Utc::now),
+            side,
+            price,
+            quantity: Decimal::from(self.count),
+            fees: AssetFees::new(
+                QuoteAsset,
+                fill_fee(self.fee_cost, self.is_taker, price, self.count),
+            ),
+        }
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -89,18 +169,8 @@ pub fn generate_ws_auth_headers(
     api_key: &str,
     private_key: &RsaPrivateKey,
 ) -> Vec<(&'static str, String)> {
-    let timestamp = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .expect("Time went backwards")
-        .as_millis() as u64;
-
-    let message = format!("{}GET{}", timestamp, KALSHI_WS_SIGN_PATH);
-    debug!(message = %message, "Signing Kalshi WS handshake");
-
-    let signing_key = SigningKey::<Sha256>::new(private_key.clone());
-    let mut rng = rsa::rand_core::OsRng;
-    let signature = signing_key.sign_with_rng(&mut rng, message.as_bytes());
-    let signature_b64 = BASE64.encode(signature.to_bytes());
+    let timestamp = auth::timestamp_ms();
+    let signature_b64 = sign_request(private_key, timestamp, "GET", KALSHI_WS_SIGN_PATH);
 
     vec![
         ("KALSHI-ACCESS-KEY", api_key.to_string()),
@@ -151,82 +221,191 @@ pub async fn connect_kalshi_fills(
 // ---------------------------------------------------------------------------
 
 /// Convert a Kalshi WebSocket into a stream of `AccountEvent::Trade` events.
+///
+/// The stream ends when the server closes the socket or a read fails.
 pub fn kalshi_fill_stream(ws: WebSocket) -> BoxStream<'static, UnindexedAccountEvent> {
     let (_sink, stream) = ws.split();
 
-    let mapped = stream.filter_map(|result| async move {
-        let msg = match result {
-            Ok(m) => m,
-            Err(e) => {
-                warn!(error = %e, "Kalshi fill WS read error");
-                return None;
-            }
-        };
+    Box::pin(fill_events(stream))
+}
 
-        let text = match msg {
-            WsMessage::Text(t) => t,
-            WsMessage::Ping(_) | WsMessage::Pong(_) => return None,
-            WsMessage::Close(_) => {
-                warn!("Kalshi fill WS closed by server");
-                return None;
-            }
-            _ => return None,
-        };
+/// Map fill WS messages to `AccountEvent::Trade` events, ending at the first
+/// close frame or read error.
+pub fn fill_events<S>(messages: S) -> impl Stream<Item = UnindexedAccountEvent> + Send + 'static
+where
+    S: Stream<Item = Result<WsMessage, WsError>> + Send + 'static,
+{
+    messages
+        .take_while(|result| {
+            ready(match result {
+                Ok(WsMessage::Close(_)) => {
+                    warn!("Kalshi fill WS closed by server");
+                    false
+                }
+                Err(e) => {
+                    warn!(error = %e, "Kalshi fill WS read error");
+                    false
+                }
+                Ok(_) => true,
+            })
+        })
+        .filter_map(|result| ready(result.ok().and_then(parse_fill)))
+}
 
-        let parsed: KalshiWsMessage = match serde_json::from_str(&text) {
-            Ok(m) => m,
-            Err(e) => {
-                debug!(error = %e, payload = %text, "Failed to parse Kalshi WS message");
-                return None;
-            }
-        };
+/// Parse a fill WS message into an `AccountEvent::Trade`, if it is a fill.
+fn parse_fill(msg: WsMessage) -> Option<UnindexedAccountEvent> {
+    let text = match msg {
+        WsMessage::Text(t) => t,
+        _ => return None,
+    };
 
-        match parsed {
-            KalshiWsMessage::Fill(fill) => {
-                let data = &fill.msg;
-                let instrument_name = format!("{}_{}", data.ticker, data.side);
-                let side = match data.action.as_str() {
-                    "buy" => Side::Buy,
-                    _ => Side::Sell,
-                };
-                let price = Decimal::from(data.yes_price) / Decimal::from(100);
-
-                info!(
-                    trade_id = %data.trade_id,
-                    ticker = %data.ticker,
-                    side = %data.side,
-                    action = %data.action,
-                    count = data.count,
-                    price = %price,
-                    "Kalshi trade fill received via WS"
-                );
-
-                Some(AccountEvent {
-                    exchange: ExchangeId::Kalshi,
-                    kind: AccountEventKind::Trade(Trade {
-                        id: TradeId(SmolStr::new(&data.trade_id)),
-                        order_id: OrderId(SmolStr::new(&data.order_id)),
-                        instrument: InstrumentNameExchange::from(instrument_name.as_str()),
-                        strategy: StrategyId::new("unknown"),
-                        time_exchange: Utc::now(),
-                        side,
-                        price,
-                        quantity: Decimal::from(data.count),
-                        fees: AssetFees::new(QuoteAsset, Decimal::ZERO),
-                    }),
-                })
-            }
-            KalshiWsMessage::Subscribed { .. } => {
-                debug!("Kalshi fill WS subscription confirmed");
-                None
-            }
-            KalshiWsMessage::Error { msg, .. } => {
-                warn!(error = ?msg, "Kalshi fill WS error message");
-                None
-            }
-            KalshiWsMessage::Unknown => None,
+    let parsed: KalshiWsMessage = match serde_json::from_str(&text) {
+        Ok(m) => m,
+        Err(e) => {
+            debug!(error = %e, payload = %text, "Failed to parse Kalshi WS message");
+            return None;
         }
-    });
+    };
+
+    match parsed {
+        KalshiWsMessage::Fill(fill) => {
+            let trade = fill.msg.to_trade();
+
+            info!(
+                trade_id = %trade.id.0,
+                instrument = %trade.instrument,
+                side = ?trade.side,
+                quantity = %trade.quantity,
+                price = %trade.price,
+                "Kalshi trade fill received via WS"
+            );
+
+            Some(AccountEvent {
+                exchange: ExchangeId::Kalshi,
+                kind: AccountEventKind::Trade(trade),
+            })
+        }
+        KalshiWsMessage::Subscribed { .. } => {
+            debug!("Kalshi fill WS subscription confirmed");
+            None
+        }
+        KalshiWsMessage::Error { msg, .. } => {
+            warn!(error = ?msg, "Kalshi fill WS error message");
+            None
+        }
+        KalshiWsMessage::Unknown => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::stream;
+
+    fn fill_msg(side: &str, action: &str) -> WsMessage {
+        WsMessage::text(format!(
+            r#"{{
+                "type": "fill",
+                "sid": 13,
+                "msg": {{
+                    "trade_id": "d91bc706-ee49-470d-82d8-11418bda6fed",
+                    "order_id": "ee587a1c-8b87-4dcf-b721-9f6f790619fa",
+                    "market_ticker": "HIGHNY-22DEC23-B53.5",
+                    "is_taker": true,
+                    "side": "{side}",
+                    "yes_price": 75,
+                    "yes_price_dollars": "0.750",
+                    "count": 278,
+                    "action": "{action}",
+                    "ts": 1671899397,
+                    "post_position": 500
+                }}
+            }}"#
+        ))
+    }
+
+    fn parse_trade(msg: WsMessage) -> Trade<QuoteAsset, InstrumentNameExchange> {
+        match parse_fill(msg).map(|event| event.kind) {
+            Some(AccountEventKind::Trade(trade)) => trade,
+            other => panic!("expected trade, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_yes_fill() {
+        let trade = parse_trade(fill_msg("yes", "buy"));
+
+        assert_eq!(trade.id.0, "d91bc706-ee49-470d-82d8-11418bda6fed");
+        assert_eq!(trade.order_id.0, "ee587a1c-8b87-4dcf-b721-9f6f790619fa");
+        assert_eq!(
+            trade.instrument,
+            InstrumentNameExchange::from("HIGHNY-22DEC23-B53.5_yes")
+        );
+        assert_eq!(trade.side, Side::Buy);
+        assert_eq!(trade.price, Decimal::new(75, 2));
+        assert_eq!(trade.quantity, Decimal::from(278));
+        assert_eq!(trade.time_exchange.timestamp(), 1671899397);
+        // Taker fee of 0.07 * 278 * 0.75 * 0.25 = 3.64875, rounded up to the cent
+        assert_eq!(trade.fees.fees, Decimal::new(365, 2));
+    }
+
+    #[test]
+    fn test_parse_no_fill() {
+        // NO fills are priced at the NO price, implied by the YES price if absent
+        let trade = parse_trade(fill_msg("no", "sell"));
+
+        assert_eq!(
+            trade.instrument,
+            InstrumentNameExchange::from("HIGHNY-22DEC23-B53.5_no")
+        );
+        assert_eq!(trade.side, Side::Sell);
+        assert_eq!(trade.price, Decimal::new(25, 2));
+        assert_eq!(trade.fees.fees, Decimal::new(365, 2));
+
+        let explicit = WsMessage::text(
+            r#"{"type":"fill","sid":1,"seq":2,"msg":{"trade_id":"t","order_id":"o",
+            "ticker":"KXTEST","side":"no","action":"buy","count":5,"yes_price":40,
+            "no_price":61}}"#,
+        );
+        let trade = parse_trade(explicit);
+        assert_eq!(trade.instrument, InstrumentNameExchange::from("KXTEST_no"));
+        assert_eq!(trade.price, Decimal::new(61, 2));
+    }
+
+    #[test]
+    fn test_parse_fill_fees() {
+        // Maker fills are fee free
+        let maker = WsMessage::text(
+            r#"{"type":"fill","sid":1,"msg":{"trade_id":"t","order_id":"o","ticker":"KXTEST",
+            "side":"yes","action":"buy","count":5,"yes_price":40,"is_taker":false}}"#,
+        );
+        assert_eq!(parse_trade(maker).fees.fees, Decimal::ZERO);
+
+        // A reported fee is used as billed
+        let billed = WsMessage::text(
+            r#"{"type":"fill","sid":1,"msg":{"trade_id":"t","order_id":"o","ticker":"KXTEST",
+            "side":"no","action":"buy","count":5,"yes_price":40,"is_taker":true,
+            "fee_cost":"0.0300"}}"#,
+        );
+        assert_eq!(parse_trade(billed).fees.fees, Decimal::new(3, 2));
+    }
+
+    #[test]
+    fn test_parse_non_fill_messages() {
+        let subscribed = r#"{"type":"subscribed","id":1,"msg":{"channel":"fill","sid":13}}"#;
+        assert!(parse_fill(WsMessage::text(subscribed)).is_none());
+        assert!(parse_fill(WsMessage::text(r#"{"type":"ticker","sid":1}"#)).is_none());
+        assert!(parse_fill(WsMessage::Ping(Default::default())).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_fill_events_end_on_close() {
+        let messages = stream::iter(vec![
+            Ok(fill_msg("yes", "buy")),
+            Ok(WsMessage::Close(None)),
+            Ok(fill_msg("no", "buy")),
+        ]);
 
-    Box::pin(mapped)
+        assert_eq!(fill_events(messages).count().await, 1);
+    }
 }
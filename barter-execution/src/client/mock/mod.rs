@@ -6,8 +6,9 @@ use crate::{
     exchange::mock::request::MockExchangeRequest,
     order::{
         Order, OrderEvent, OrderKey,
+        id::OrderId,
         request::{OrderRequestCancel, OrderRequestOpen, UnindexedOrderResponseCancel},
-        state::Open,
+        state::{Open, UnindexedOrderState},
     },
     trade::Trade,
 };
@@ -306,6 +307,24 @@ where
             ))
         })
     }
+
+    async fn fetch_order(
+        &self,
+        order_id: &OrderId,
+    ) -> Result<
+        Option<(Order<ExchangeId, InstrumentNameExchange, UnindexedOrderState>, Decimal)>,
+        UnindexedClientError,
+    > {
+        // The MockExchange only keeps open orders, so any other order is unknown
+        let orders = self.fetch_open_orders().await?;
+        Ok(orders
+            .into_iter()
+            .find(|order| &order.state.id == order_id)
+            .map(|order| {
+                let filled = order.state.filled_quantity;
+                (Order::from(order), filled)
+            }))
+    }
 }
 
 fn into_owned_request<Kind>(
@@ -4,8 +4,9 @@ use crate::{
     error::{UnindexedClientError, UnindexedOrderError},
     order::{
         Order,
+        id::OrderId,
         request::{OrderRequestCancel, OrderRequestOpen, UnindexedOrderResponseCancel},
-        state::Open,
+        state::{Open, UnindexedOrderState},
     },
     trade::Trade,
 };
@@ -15,14 +16,91 @@ use barter_instrument::{
     instrument::name::InstrumentNameExchange,
 };
 use chrono::{DateTime, Utc};
-use futures::Stream;
-use std::future::Future;
+use futures::{Stream, StreamExt, stream::BoxStream};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::{
+    future::{Future, ready},
+    time::Duration,
+};
+use tokio_stream::wrappers::IntervalStream;
+use tracing::warn;
 
 mod binance;
 pub mod kalshi;
 pub mod mock;
+pub mod order_poll;
 pub mod polymarket;
 
+/// Merge balance polling with a user WS fill stream, ending when the fill stream ends.
+///
+/// Balance polling never ends on its own, so without this a closed user WS would
+/// silently stop fills without the account stream ever reconnecting.
+pub fn merge_until_fills_end<B, F>(
+    exchange: ExchangeId,
+    balances: B,
+    fills: F,
+) -> BoxStream<'static, UnindexedAccountEvent>
+where
+    B: Stream<Item = UnindexedAccountEvent> + Send + 'static,
+    F: Stream<Item = UnindexedAccountEvent> + Send + 'static,
+{
+    let fills = fills.map(Some).chain(futures::stream::once(async move {
+        warn!(%exchange, "user WS disconnected, ending account stream to reconnect");
+        None
+    }));
+
+    futures::stream::select(balances.map(Some), fills)
+        .take_while(|event| ready(event.is_some()))
+        .filter_map(ready)
+        .boxed()
+}
+
+/// Merge independent balance and order status polls, each run on its own interval.
+///
+/// Balances change slowly while order state drives the engine, so the two are polled
+/// at separate rates and a slow poll of one never delays the other.
+pub fn merge_polls<T, PollBalance, BalanceFut, PollOrders, OrdersFut>(
+    balance_interval: Duration,
+    mut poll_balance: PollBalance,
+    order_interval: Duration,
+    mut poll_orders: PollOrders,
+) -> impl Stream<Item = T> + Send + 'static
+where
+    T: Send + 'static,
+    PollBalance: FnMut() -> BalanceFut + Send + 'static,
+    BalanceFut: Future<Output = Option<T>> + Send + 'static,
+    PollOrders: FnMut() -> OrdersFut + Send + 'static,
+    OrdersFut: Future<Output = Vec<T>> + Send + 'static,
+{
+    let balances = IntervalStream::new(tokio::time::interval(balance_interval))
+        .then(move |_| poll_balance())
+        .filter_map(ready);
+
+    let orders = IntervalStream::new(tokio::time::interval(order_interval))
+        .then(move |_| poll_orders())
+        .flat_map(futures::stream::iter);
+
+    futures::stream::select(balances, orders)
+}
+
+/// Net holding of one outcome instrument as reported by an exchange.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+pub struct InstrumentPosition<InstrumentKey = InstrumentNameExchange> {
+    pub instrument: InstrumentKey,
+    /// Contracts held
+    pub quantity: Decimal,
+    /// Total amount paid for the contracts held
+    pub cost_basis: Decimal,
+}
+
+impl<InstrumentKey> InstrumentPosition<InstrumentKey> {
+    /// Average price paid per contract, `None` if no contracts are held.
+    pub fn average_price(&self) -> Option<Decimal> {
+        (self.quantity > Decimal::ZERO).then(|| self.cost_basis / self.quantity)
+    }
+}
+
 pub trait ExecutionClient
 where
     Self: Clone,
@@ -96,4 +174,46 @@ where
         &self,
         time_since: DateTime<Utc>,
     ) -> impl Future<Output = Result<Vec<Trade<QuoteAsset, InstrumentNameExchange>>, UnindexedClientError>>;
+
+    /// Fetch the current state of a single order by its exchange [`OrderId`], whether it
+    /// is open, filled or cancelled, or `None` if the exchange does not know the order.
+    ///
+    /// The order is returned with its filled quantity, which a cancelled
+    /// [`OrderState`](crate::order::state::OrderState) does not carry.
+    fn fetch_order(
+        &self,
+        order_id: &OrderId,
+    ) -> impl Future<
+        Output = Result<
+            Option<(Order<ExchangeId, InstrumentNameExchange, UnindexedOrderState>, Decimal)>,
+            UnindexedClientError,
+        >,
+    >;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::time::Instant;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_merge_polls_ticks_at_configured_rates() {
+        let stream = merge_polls(
+            Duration::from_millis(300),
+            || async { Some("balance") },
+            Duration::from_millis(100),
+            || async { vec!["orders"] },
+        );
+
+        let started = Instant::now();
+        let polls = stream
+            .take_while(|_| ready(started.elapsed() <= Duration::from_millis(1000)))
+            .collect::<Vec<_>>()
+            .await;
+
+        // Both poll immediately, then balances at 300ms and orders at 100ms intervals
+        let count = |kind| polls.iter().filter(|poll| **poll == kind).count();
+        assert_eq!(count("balance"), 4);
+        assert_eq!(count("orders"), 11);
+    }
 }
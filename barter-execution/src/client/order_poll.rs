@@ -0,0 +1,751 @@
+//! Order status polling for exchanges whose user streams do not report order state.
+//!
+//! Fill streams report trades but not order state, so resting orders going from
+//! partially filled to fully filled or canceled never reach the engine. An
+//! [`OrderPollTracker`] tracks orders by exchange order id, both those opened by the
+//! client and any found resting, and diffs successive polls to synthesise order
+//! snapshots, cancels and any fills the fill stream has not already delivered.
+
+use crate::{
+    AccountEvent, AccountEventKind, UnindexedAccountEvent,
+    error::UnindexedClientError,
+    order::{
+        Order,
+        id::OrderId,
+        request::UnindexedOrderResponseCancel,
+        state::{Cancelled, Open, OrderState, UnindexedOrderState},
+    },
+    trade::{AssetFees, Trade, TradeId},
+};
+use barter_instrument::{
+    asset::{QuoteAsset, name::AssetNameExchange},
+    exchange::ExchangeId,
+    instrument::name::InstrumentNameExchange,
+};
+use barter_integration::snapshot::Snapshot;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use smol_str::format_smolstr;
+use std::{
+    collections::{HashMap, HashSet},
+    future::Future,
+    sync::{Arc, Mutex, MutexGuard},
+    time::Duration,
+};
+use tracing::{debug, warn};
+
+/// How long an inactive order is kept after its final poll, so fills the fill stream
+/// delivers late are still recognised as already reported.
+pub const INACTIVE_ORDER_RETENTION: Duration = Duration::from_secs(300);
+
+/// Normalised status of a [`PolledOrder`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum PolledOrderStatus {
+    /// Resting or pending, with quantity left to fill
+    Open,
+    FullyFilled,
+    Cancelled,
+}
+
+/// Order state as reported by an exchange poll.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PolledOrder {
+    pub id: OrderId,
+    /// Order as reported by the exchange, used to track resting orders this client
+    /// did not open
+    pub order: Order<ExchangeId, InstrumentNameExchange, ()>,
+    pub status: PolledOrderStatus,
+    /// Quantity filled so far
+    pub filled: Decimal,
+    /// Total cost of the fills so far, if reported
+    pub fill_cost: Option<Decimal>,
+    /// Total fees paid on the fills so far, if reported
+    pub fees: Option<Decimal>,
+}
+
+impl PolledOrder {
+    /// Quantity left to fill.
+    pub fn remaining(&self) -> Decimal {
+        (self.order.quantity - self.filled).max(Decimal::ZERO)
+    }
+
+    /// Average price of the fills so far, if their cost is reported.
+    pub fn average_price(&self) -> Option<Decimal> {
+        self.fill_cost
+            .filter(|_| !self.filled.is_zero())
+            .map(|cost| cost / self.filled)
+    }
+
+    /// Share of the fees paid for `quantity` of the fills so far, if reported.
+    pub fn fees_for(&self, quantity: Decimal) -> Option<Decimal> {
+        self.fees
+            .filter(|_| !self.filled.is_zero())
+            .map(|fees| fees * quantity / self.filled)
+    }
+
+    /// Current [`OrderState`] of the order, treating an open order with nothing left to
+    /// fill as fully filled.
+    pub fn state(&self, time_exchange: DateTime<Utc>) -> UnindexedOrderState {
+        match self.status {
+            PolledOrderStatus::Open if self.remaining().is_zero() => OrderState::fully_filled(),
+            PolledOrderStatus::Open => OrderState::active(Open {
+                id: self.id.clone(),
+                time_exchange,
+                filled_quantity: self.filled,
+            }),
+            PolledOrderStatus::FullyFilled => OrderState::fully_filled(),
+            PolledOrderStatus::Cancelled => OrderState::inactive(Cancelled {
+                id: self.id.clone(),
+                time_exchange,
+            }),
+        }
+    }
+
+    /// Convert into an [`Order`] holding its current [`OrderState`], see [`Self::state`].
+    pub fn into_order(
+        self,
+        time_exchange: DateTime<Utc>,
+    ) -> Order<ExchangeId, InstrumentNameExchange, UnindexedOrderState> {
+        let state = self.state(time_exchange);
+        let Order {
+            key,
+            side,
+            price,
+            quantity,
+            kind,
+            time_in_force,
+            state: (),
+        } = self.order;
+
+        Order {
+            key,
+            side,
+            price,
+            quantity,
+            kind,
+            time_in_force,
+            state,
+        }
+    }
+}
+
+/// Source of exchange order status polled by an [`OrderPollTracker`].
+pub trait OrderStatusSource: Sync {
+    /// Fetch every resting order.
+    fn fetch_resting_orders(
+        &self,
+    ) -> impl Future<Output = Result<Vec<PolledOrder>, UnindexedClientError>> + Send;
+
+    /// Fetch a single order by id, whatever its status.
+    fn fetch_order(
+        &self,
+        order_id: &OrderId,
+    ) -> impl Future<Output = Result<PolledOrder, UnindexedClientError>> + Send;
+}
+
+/// Tracked order, and what has been reported to the engine about it.
+#[derive(Debug, Clone)]
+struct TrackedOrder {
+    order: Order<ExchangeId, InstrumentNameExchange, ()>,
+    /// Quantity left to fill as last reported
+    remaining: Decimal,
+    /// Quantity reported as trades, via either the fill stream or polling
+    reported: Decimal,
+    /// Quantity seen on the fill stream
+    streamed: Decimal,
+    /// Time the order was last reported inactive, after which it is no longer polled
+    inactive_since: Option<DateTime<Utc>>,
+}
+
+/// Fill stream quantity of an order not yet tracked, such as one filled before its
+/// open acknowledgement arrived.
+#[derive(Debug, Copy, Clone)]
+struct UntrackedFills {
+    quantity: Decimal,
+    last_seen: DateTime<Utc>,
+}
+
+impl TrackedOrder {
+    fn expired(&self, now: DateTime<Utc>) -> bool {
+        self.inactive_since.is_some_and(|since| {
+            self.streamed >= self.reported
+                || now
+                    .signed_duration_since(since)
+                    .to_std()
+                    .unwrap_or_default()
+                    >= INACTIVE_ORDER_RETENTION
+        })
+    }
+}
+
+/// Orders tracked for status polling, shared between an execution client's
+/// `open_order` and its account stream.
+#[derive(Debug, Clone)]
+pub struct OrderPollTracker {
+    exchange: ExchangeId,
+    orders: Arc<Mutex<HashMap<OrderId, TrackedOrder>>>,
+    /// Always locked after `orders`
+    untracked: Arc<Mutex<HashMap<OrderId, UntrackedFills>>>,
+}
+
+impl OrderPollTracker {
+    pub fn new(exchange: ExchangeId) -> Self {
+        Self {
+            exchange,
+            orders: Arc::default(),
+            untracked: Arc::default(),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<OrderId, TrackedOrder>> {
+        self.orders
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn lock_untracked(&self) -> MutexGuard<'_, HashMap<OrderId, UntrackedFills>> {
+        self.untracked
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Track a newly opened order, acknowledged with `filled` quantity already filled.
+    ///
+    /// The engine only knows the order as open, so even an order acknowledged as
+    /// fully filled is polled once to report its final state. Fills the fill stream
+    /// delivered before the order was tracked count as already reported.
+    pub fn track(
+        &self,
+        id: OrderId,
+        order: Order<ExchangeId, InstrumentNameExchange, ()>,
+        filled: Decimal,
+    ) {
+        let remaining = (order.quantity - filled).max(Decimal::ZERO);
+        let mut orders = self.lock();
+        let streamed = self
+            .lock_untracked()
+            .remove(&id)
+            .map(|fills| fills.quantity)
+            .unwrap_or_default();
+        orders.insert(
+            id,
+            TrackedOrder {
+                order,
+                remaining,
+                reported: streamed,
+                streamed,
+                inactive_since: None,
+            },
+        );
+    }
+
+    /// Stop polling an order the engine cancelled itself, which it already knows is
+    /// inactive.
+    pub fn cancelled(&self, id: &OrderId) {
+        if let Some(tracked) = self.lock().get_mut(id) {
+            tracked.inactive_since.get_or_insert_with(Utc::now);
+        }
+    }
+
+    /// Ids of tracked orders that are still active, pruning expired inactive orders.
+    pub fn active_order_ids(&self) -> Vec<OrderId> {
+        let now = Utc::now();
+        let mut orders = self.lock();
+        orders.retain(|_, tracked| !tracked.expired(now));
+        self.lock_untracked().retain(|_, fills| {
+            now.signed_duration_since(fills.last_seen)
+                .to_std()
+                .unwrap_or_default()
+                < INACTIVE_ORDER_RETENTION
+        });
+        orders
+            .iter()
+            .filter(|(_, tracked)| tracked.inactive_since.is_none())
+            .map(|(id, _)| id.clone())
+            .collect()
+    }
+
+    /// Poll resting orders, then individually fetch tracked orders that are no longer
+    /// resting, returning the events for any changes.
+    ///
+    /// If resting orders cannot be fetched the poll is skipped until next time.
+    pub async fn poll<Source>(&self, source: &Source) -> Vec<UnindexedAccountEvent>
+    where
+        Source: OrderStatusSource,
+    {
+        let exchange = self.exchange;
+        let resting = match source.fetch_resting_orders().await {
+            Ok(resting) => resting,
+            Err(error) => {
+                warn!(%exchange, %error, "resting order status poll failed");
+                return vec![];
+            }
+        };
+
+        let mut seen = HashSet::with_capacity(resting.len());
+        let mut events = Vec::new();
+        for polled in resting {
+            events.extend(self.update(&polled));
+            seen.insert(polled.id);
+        }
+
+        // Tracked orders that stopped resting have since filled or been cancelled
+        for order_id in self.active_order_ids() {
+            if seen.contains(&order_id) {
+                continue;
+            }
+            match source.fetch_order(&order_id).await {
+                Ok(polled) => events.extend(self.update(&polled)),
+                Err(error) => warn!(%exchange, %order_id, %error, "order status poll failed"),
+            }
+        }
+
+        events
+    }
+
+    /// Apply a polled order status, returning the events for what changed since the
+    /// last report.
+    ///
+    /// Filled quantity not already seen on the fill stream is reported as a
+    /// synthesised trade, priced at the average fill price with its share of the fees
+    /// where the exchange reports them, followed by a cancel or order snapshot if the order status
+    /// or remaining quantity changed. Once inactive, an order yields no further
+    /// events. Untracked open orders start being tracked from their current state.
+    pub fn update(&self, polled: &PolledOrder) -> Vec<UnindexedAccountEvent> {
+        let mut orders = self.lock();
+        let Some(tracked) = orders.get_mut(&polled.id) else {
+            if polled.status == PolledOrderStatus::Open {
+                debug!(
+                    exchange = %self.exchange,
+                    order_id = %polled.id,
+                    "tracking resting order not opened by this client"
+                );
+                self.lock_untracked().remove(&polled.id);
+                orders.insert(
+                    polled.id.clone(),
+                    TrackedOrder {
+                        order: polled.order.clone(),
+                        remaining: polled.remaining(),
+                        reported: polled.filled,
+                        streamed: polled.filled,
+                        inactive_since: None,
+                    },
+                );
+            }
+            return vec![];
+        };
+        if tracked.inactive_since.is_some() {
+            return vec![];
+        }
+
+        let now = Utc::now();
+        let mut kinds = Vec::new();
+
+        if polled.filled > tracked.reported {
+            let quantity = polled.filled - tracked.reported;
+            kinds.push(AccountEventKind::Trade(Trade {
+                id: TradeId(format_smolstr!("{}:{}", polled.id, polled.filled)),
+                order_id: polled.id.clone(),
+                instrument: tracked.order.key.instrument.clone(),
+                strategy: tracked.order.key.strategy.clone(),
+                time_exchange: now,
+                side: tracked.order.side,
+                price: polled.average_price().unwrap_or(tracked.order.price),
+                quantity,
+                fees: AssetFees::new(
+                    QuoteAsset,
+                    polled.fees_for(quantity).unwrap_or(Decimal::ZERO),
+                ),
+            }));
+            tracked.reported = polled.filled;
+        }
+
+        let remaining = polled.remaining();
+        let status = match polled.status {
+            PolledOrderStatus::Open if remaining.is_zero() => PolledOrderStatus::FullyFilled,
+            status => status,
+        };
+        if status == PolledOrderStatus::Open && remaining == tracked.remaining {
+            return self.events(kinds);
+        }
+
+        tracked.remaining = remaining;
+        if status != PolledOrderStatus::Open {
+            tracked.inactive_since = Some(now);
+        }
+
+        let order = &tracked.order;
+        let state = match status {
+            PolledOrderStatus::Cancelled => None,
+            PolledOrderStatus::FullyFilled => Some(OrderState::fully_filled()),
+            PolledOrderStatus::Open => Some(OrderState::active(Open {
+                id: polled.id.clone(),
+                time_exchange: now,
+                filled_quantity: polled.filled,
+            })),
+        };
+        kinds.push(match state {
+            None => AccountEventKind::OrderCancelled(UnindexedOrderResponseCancel {
+                key: order.key.clone(),
+                state: Ok(Cancelled {
+                    id: polled.id.clone(),
+                    time_exchange: now,
+                }),
+            }),
+            Some(state) => AccountEventKind::OrderSnapshot(Snapshot(Order {
+                key: order.key.clone(),
+                side: order.side,
+                price: order.price,
+                quantity: order.quantity,
+                kind: order.kind,
+                time_in_force: order.time_in_force,
+                state,
+            })),
+        });
+
+        debug!(
+            exchange = %self.exchange,
+            order_id = %polled.id,
+            ?status,
+            %remaining,
+            "order status changed"
+        );
+        self.events(kinds)
+    }
+
+    /// Drop or shrink a fill stream trade for a tracked order whose fills polling has
+    /// already reported, passing every other event through unchanged.
+    ///
+    /// Trades for untracked orders are remembered, so an order tracked after its
+    /// first fills were streamed does not have them reported again by polling.
+    pub fn dedup_fill(&self, event: UnindexedAccountEvent) -> Option<UnindexedAccountEvent> {
+        let AccountEvent {
+            exchange,
+            kind: AccountEventKind::Trade(mut trade),
+        } = event
+        else {
+            return Some(event);
+        };
+
+        let mut orders = self.lock();
+        if let Some(tracked) = orders.get_mut(&trade.order_id) {
+            let seen = tracked.streamed.max(tracked.reported);
+            tracked.streamed += trade.quantity;
+            if tracked.streamed <= seen {
+                debug!(%exchange, trade_id = %trade.id.0, "fill already reported by polling");
+                return None;
+            }
+            trade.quantity = tracked.streamed - seen;
+            trade.strategy = tracked.order.key.strategy.clone();
+            tracked.reported = tracked.streamed;
+        } else {
+            let mut untracked = self.lock_untracked();
+            let fills = untracked
+                .entry(trade.order_id.clone())
+                .or_insert(UntrackedFills {
+                    quantity: Decimal::ZERO,
+                    last_seen: Utc::now(),
+                });
+            fills.quantity += trade.quantity;
+            fills.last_seen = Utc::now();
+        }
+
+        Some(AccountEvent {
+            exchange,
+            kind: AccountEventKind::Trade(trade),
+        })
+    }
+
+    fn events(
+        &self,
+        kinds: Vec<AccountEventKind<ExchangeId, AssetNameExchange, InstrumentNameExchange>>,
+    ) -> Vec<UnindexedAccountEvent> {
+        kinds
+            .into_iter()
+            .map(|kind| AccountEvent {
+                exchange: self.exchange,
+                kind,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        error::ConnectivityError,
+        order::{
+            OrderKey, OrderKind, TimeInForce,
+            id::{ClientOrderId, StrategyId},
+            state::InactiveOrderState,
+        },
+    };
+    use barter_instrument::Side;
+
+    const ORDER_ID: &str = "ee8aa3b4-0bd2-4b45-8b2b-1a7d3d6e5a1c";
+
+    /// Order source replying with the latest statuses set by the test, counting
+    /// single order fetches.
+    #[derive(Default)]
+    struct MockOrderSource {
+        orders: Mutex<Vec<PolledOrder>>,
+        fetches: Mutex<usize>,
+    }
+
+    impl MockOrderSource {
+        fn set(&self, orders: Vec<PolledOrder>) {
+            *self.orders.lock().unwrap() = orders;
+        }
+
+        fn fetches(&self) -> usize {
+            *self.fetches.lock().unwrap()
+        }
+    }
+
+    impl OrderStatusSource for MockOrderSource {
+        async fn fetch_resting_orders(&self) -> Result<Vec<PolledOrder>, UnindexedClientError> {
+            Ok(self
+                .orders
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|order| order.status == PolledOrderStatus::Open)
+                .cloned()
+                .collect())
+        }
+
+        async fn fetch_order(
+            &self,
+            order_id: &OrderId,
+        ) -> Result<PolledOrder, UnindexedClientError> {
+            *self.fetches.lock().unwrap() += 1;
+            self.orders
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|order| &order.id == order_id)
+                .cloned()
+                .ok_or_else(|| {
+                    UnindexedClientError::Connectivity(ConnectivityError::Socket(
+                        "Status 404: not found".to_string(),
+                    ))
+                })
+        }
+    }
+
+    fn order(strategy: &str) -> Order<ExchangeId, InstrumentNameExchange, ()> {
+        Order {
+            key: OrderKey {
+                exchange: ExchangeId::Kalshi,
+                instrument: InstrumentNameExchange::from("KXBTC-25_yes"),
+                strategy: StrategyId::new(strategy),
+                cid: ClientOrderId::new("cid"),
+            },
+            side: Side::Buy,
+            price: Decimal::new(45, 2),
+            quantity: Decimal::from(10),
+            kind: OrderKind::Limit,
+            time_in_force: TimeInForce::GoodUntilCancelled { post_only: false },
+            state: (),
+        }
+    }
+
+    fn polled(status: PolledOrderStatus, filled: i64) -> PolledOrder {
+        PolledOrder {
+            id: OrderId::new(ORDER_ID),
+            order: order("unknown"),
+            status,
+            filled: Decimal::from(filled),
+            fill_cost: None,
+            fees: None,
+        }
+    }
+
+    fn tracker() -> OrderPollTracker {
+        let tracker = OrderPollTracker::new(ExchangeId::Kalshi);
+        tracker.track(OrderId::new(ORDER_ID), order("arb"), Decimal::ZERO);
+        tracker
+    }
+
+    fn fill(quantity: i64) -> UnindexedAccountEvent {
+        AccountEvent {
+            exchange: ExchangeId::Kalshi,
+            kind: AccountEventKind::Trade(Trade {
+                id: TradeId::new("ws-trade"),
+                order_id: OrderId::new(ORDER_ID),
+                instrument: InstrumentNameExchange::from("KXBTC-25_yes"),
+                strategy: StrategyId::new("unknown"),
+                time_exchange: Utc::now(),
+                side: Side::Buy,
+                price: Decimal::new(45, 2),
+                quantity: Decimal::from(quantity),
+                fees: AssetFees::new(QuoteAsset, Decimal::ZERO),
+            }),
+        }
+    }
+
+    fn trade_quantity(event: &UnindexedAccountEvent) -> Option<Decimal> {
+        match &event.kind {
+            AccountEventKind::Trade(trade) => Some(trade.quantity),
+            _ => None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fully_filled_transition_emits_events_once() {
+        let tracker = tracker();
+        let source = MockOrderSource::default();
+
+        // Unchanged while resting
+        source.set(vec![polled(PolledOrderStatus::Open, 0)]);
+        assert!(tracker.poll(&source).await.is_empty());
+        assert_eq!(source.fetches(), 0);
+
+        // 10 remaining -> 0 yields the synthesised fill followed by the final snapshot
+        source.set(vec![polled(PolledOrderStatus::FullyFilled, 10)]);
+        let events = tracker.poll(&source).await;
+        assert_eq!(events.len(), 2);
+
+        let AccountEventKind::Trade(trade) = &events[0].kind else {
+            panic!("expected trade, got {:?}", events[0]);
+        };
+        assert_eq!(trade.order_id, OrderId::new(ORDER_ID));
+        assert_eq!(trade.strategy, StrategyId::new("arb"));
+        assert_eq!(trade.side, Side::Buy);
+        assert_eq!(trade.price, Decimal::new(45, 2));
+        assert_eq!(trade.quantity, Decimal::from(10));
+
+        let AccountEventKind::OrderSnapshot(Snapshot(snapshot)) = &events[1].kind else {
+            panic!("expected order snapshot, got {:?}", events[1]);
+        };
+        assert_eq!(snapshot.key, order("arb").key);
+        assert_eq!(
+            snapshot.state,
+            OrderState::Inactive(InactiveOrderState::FullyFilled)
+        );
+
+        // Inactive orders are no longer polled
+        let fetches = source.fetches();
+        assert!(tracker.poll(&source).await.is_empty());
+        assert_eq!(source.fetches(), fetches);
+        assert!(
+            tracker
+                .update(&polled(PolledOrderStatus::FullyFilled, 10))
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fills_seen_on_stream_are_not_reported_twice() {
+        let tracker = tracker();
+        let source = MockOrderSource::default();
+
+        // Fill streamed before polling passes through, attributed to the tracked strategy
+        let streamed = tracker.dedup_fill(fill(4)).unwrap();
+        let AccountEventKind::Trade(trade) = &streamed.kind else {
+            panic!("expected trade, got {streamed:?}");
+        };
+        assert_eq!(trade.quantity, Decimal::from(4));
+        assert_eq!(trade.strategy, StrategyId::new("arb"));
+
+        // Polling only reports the partially filled order state
+        source.set(vec![polled(PolledOrderStatus::Open, 4)]);
+        let events = tracker.poll(&source).await;
+        assert_eq!(events.len(), 1);
+        let AccountEventKind::OrderSnapshot(Snapshot(snapshot)) = &events[0].kind else {
+            panic!("expected order snapshot, got {:?}", events[0]);
+        };
+        assert_eq!(
+            snapshot.state,
+            OrderState::active(Open {
+                id: OrderId::new(ORDER_ID),
+                time_exchange: snapshot.state.time_exchange().unwrap(),
+                filled_quantity: Decimal::from(4),
+            })
+        );
+
+        // Polling sees the remaining fills before the fill stream does
+        source.set(vec![polled(PolledOrderStatus::FullyFilled, 10)]);
+        let events = tracker.poll(&source).await;
+        assert_eq!(
+            events.iter().filter_map(trade_quantity).collect::<Vec<_>>(),
+            vec![Decimal::from(6)]
+        );
+
+        // Late streamed fills are dropped, or shrunk to the part not yet reported
+        assert!(tracker.dedup_fill(fill(5)).is_none());
+        let streamed = tracker.dedup_fill(fill(2)).unwrap();
+        assert_eq!(trade_quantity(&streamed), Some(Decimal::from(1)));
+    }
+
+    #[tokio::test]
+    async fn test_fills_streamed_before_tracking_are_not_reported_twice() {
+        let tracker = OrderPollTracker::new(ExchangeId::Kalshi);
+        let source = MockOrderSource::default();
+
+        // The fill stream beats the open acknowledgement
+        let streamed = tracker.dedup_fill(fill(4)).unwrap();
+        assert_eq!(trade_quantity(&streamed), Some(Decimal::from(4)));
+        tracker.track(OrderId::new(ORDER_ID), order("arb"), Decimal::ZERO);
+
+        // Polling reports only the fills beyond those streamed
+        source.set(vec![polled(PolledOrderStatus::FullyFilled, 10)]);
+        let events = tracker.poll(&source).await;
+        assert_eq!(
+            events.iter().filter_map(trade_quantity).collect::<Vec<_>>(),
+            vec![Decimal::from(6)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_partially_filled_cancel() {
+        let tracker = tracker();
+        let source = MockOrderSource::default();
+
+        source.set(vec![polled(PolledOrderStatus::Cancelled, 3)]);
+        let events = tracker.poll(&source).await;
+        assert_eq!(events.len(), 2);
+        assert_eq!(trade_quantity(&events[0]), Some(Decimal::from(3)));
+
+        let AccountEventKind::OrderCancelled(cancelled) = &events[1].kind else {
+            panic!("expected order cancelled, got {:?}", events[1]);
+        };
+        assert_eq!(cancelled.key, order("arb").key);
+        assert_eq!(cancelled.state.as_ref().unwrap().id, OrderId::new(ORDER_ID));
+
+        assert!(tracker.poll(&source).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_orders_cancelled_by_engine_are_not_polled() {
+        let tracker = tracker();
+        let source = MockOrderSource::default();
+
+        tracker.cancelled(&OrderId::new(ORDER_ID));
+        source.set(vec![polled(PolledOrderStatus::Cancelled, 0)]);
+
+        assert!(tracker.poll(&source).await.is_empty());
+        assert_eq!(source.fetches(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_resting_orders_not_opened_by_client_are_diffed() {
+        let tracker = OrderPollTracker::new(ExchangeId::Kalshi);
+        let source = MockOrderSource::default();
+
+        // First sighting only starts tracking, without re-reporting earlier fills
+        source.set(vec![polled(PolledOrderStatus::Open, 2)]);
+        assert!(tracker.poll(&source).await.is_empty());
+
+        // Only the fill since the previous poll is reported
+        source.set(vec![polled(PolledOrderStatus::Open, 5)]);
+        let events = tracker.poll(&source).await;
+        assert_eq!(trade_quantity(&events[0]), Some(Decimal::from(3)));
+        assert!(matches!(
+            &events[1].kind,
+            AccountEventKind::OrderSnapshot(Snapshot(snapshot))
+                if snapshot.key.strategy == StrategyId::new("unknown")
+        ));
+    }
+}
@@ -1,11 +1,17 @@
 //! Polymarket CLOB REST HTTP client with HMAC authentication.
 
 use super::model::*;
-use super::signing::{PolymarketApiCredentials, build_auth_headers, build_l1_auth_headers};
+use super::signing::{
+    PolymarketApiCredentials, build_auth_headers, build_l1_auth_headers, exchange_address,
+};
+use alloy_primitives::U256;
 use reqwest::Client;
+use rust_decimal::Decimal;
 use tracing::{debug, error, info};
 
 const POLYMARKET_CLOB_BASE: &str = "https://clob.polymarket.com";
+const POLYMARKET_DATA_API_BASE: &str = "https://data-api.polymarket.com";
+const POLYMARKET_GAMMA_API_BASE: &str = "https://gamma-api.polymarket.com";
 
 /// Polymarket CLOB REST client.
 #[derive(Debug, Clone)]
@@ -85,6 +91,12 @@ impl PolymarketHttpClient {
         if !status.is_success() {
             let body = resp.text().await.unwrap_or_default();
             error!(status = %status, body = %body, "Polymarket order submission failed");
+            if status.is_client_error() {
+                return Err(PolymarketHttpError::Rejected {
+                    status: status.as_u16(),
+                    body,
+                });
+            }
             return Err(PolymarketHttpError::Api(format!(
                 "Status {}: {}",
                 status, body
@@ -149,6 +161,104 @@ impl PolymarketHttpClient {
         Ok(orders)
     }
 
+    /// Fetch a single order by ID, whatever its status.
+    pub async fn fetch_order(
+        &self,
+        order_id: &str,
+    ) -> Result<PolymarketOrder, PolymarketHttpError> {
+        let path = format!("/data/order/{}", order_id);
+
+        let resp = self
+            .authenticated_request("GET", &path, &path, "")
+            .send()
+            .await
+            .map_err(|e| PolymarketHttpError::Request(e.to_string()))?;
+
+        let status = resp.status();
+        if !status.is_success() {
+            let body = resp.text().await.unwrap_or_default();
+            if status.is_client_error() {
+                return Err(PolymarketHttpError::Rejected {
+                    status: status.as_u16(),
+                    body,
+                });
+            }
+            return Err(PolymarketHttpError::Api(format!(
+                "Status {}: {}",
+                status, body
+            )));
+        }
+
+        resp.json()
+            .await
+            .map_err(|e| PolymarketHttpError::Parse(e.to_string()))
+    }
+
+    /// Fetch outcome token positions held by `user` from the public data API.
+    pub async fn fetch_positions(
+        &self,
+        user: &str,
+    ) -> Result<Vec<PolymarketPosition>, PolymarketHttpError> {
+        let resp = self
+            .client
+            .get(format!("{}/positions", POLYMARKET_DATA_API_BASE))
+            .query(&[("user", user), ("sizeThreshold", "0")])
+            .send()
+            .await
+            .map_err(|e| PolymarketHttpError::Request(e.to_string()))?;
+
+        let status = resp.status();
+        if !status.is_success() {
+            let body = resp.text().await.unwrap_or_default();
+            return Err(PolymarketHttpError::Api(format!(
+                "Status {}: {}",
+                status, body
+            )));
+        }
+
+        resp.json()
+            .await
+            .map_err(|e| PolymarketHttpError::Parse(e.to_string()))
+    }
+
+    /// Fetch the token ids, neg-risk status and tick size of the market with
+    /// `condition_id` from the public gamma API.
+    pub async fn fetch_market(
+        &self,
+        condition_id: &str,
+    ) -> Result<PolymarketMarketMeta, PolymarketHttpError> {
+        let resp = self
+            .client
+            .get(format!("{}/markets", POLYMARKET_GAMMA_API_BASE))
+            .query(&[("condition_ids", condition_id)])
+            .send()
+            .await
+            .map_err(|e| PolymarketHttpError::Request(e.to_string()))?;
+
+        let status = resp.status();
+        if !status.is_success() {
+            let body = resp.text().await.unwrap_or_default();
+            return Err(PolymarketHttpError::Api(format!(
+                "Status {}: {}",
+                status, body
+            )));
+        }
+
+        let markets: Vec<PolymarketGammaMarket> = resp
+            .json()
+            .await
+            .map_err(|e| PolymarketHttpError::Parse(e.to_string()))?;
+
+        markets
+            .iter()
+            .find(|market| market.condition_id.eq_ignore_ascii_case(condition_id))
+            .ok_or_else(|| {
+                PolymarketHttpError::Api(format!("No market for condition {condition_id}"))
+            })?
+            .meta()
+            .map_err(PolymarketHttpError::Parse)
+    }
+
     /// Fetch USDC balance and allowance.
     pub async fn fetch_balance(&self) -> Result<PolymarketBalanceResponse, PolymarketHttpError> {
         let sign_path = "/balance-allowance";
@@ -174,6 +284,37 @@ impl PolymarketHttpClient {
             .map_err(|e| PolymarketHttpError::Parse(e.to_string()))
     }
 
+    /// Check the exchange contract is approved to spend at least `needed` USDC,
+    /// returning the current allowance in USDC base units.
+    ///
+    /// Without an approval every order fails on settlement. Approvals are made
+    /// on-chain from the wallet (eg/ via the Polymarket UI); they can't be
+    /// granted through the CLOB API.
+    pub async fn ensure_allowance(
+        &self,
+        needed: Decimal,
+        neg_risk: bool,
+    ) -> Result<U256, PolymarketHttpError> {
+        let resp = self.fetch_balance().await?;
+        let spender = exchange_address(neg_risk);
+
+        match resp.check_allowance(spender, needed) {
+            Ok(()) => {
+                debug!(spender, "Polymarket USDC allowance sufficient");
+                Ok(resp.allowance(spender).unwrap_or(U256::ZERO))
+            }
+            Err(shortfall) => {
+                error!(
+                    spender = %shortfall.spender,
+                    allowance = %shortfall.allowance,
+                    needed = %shortfall.needed,
+                    "Polymarket USDC allowance insufficient, approve the exchange from the wallet"
+                );
+                Err(PolymarketHttpError::InsufficientAllowance(shortfall))
+            }
+        }
+    }
+
     /// Derive or create API credentials from a private key.
     ///
     /// First attempts POST /auth/api-key (create). If that fails (key already
@@ -283,6 +424,14 @@ pub enum PolymarketHttpError {
     Request(String),
     #[error("API error: {0}")]
     Api(String),
+    /// Request refused by the exchange with a 4xx status (eg/ insufficient balance).
+    #[error("Rejected with status {status}: {body}")]
+    Rejected { status: u16, body: String },
+    #[error(
+        "Insufficient USDC allowance for {}: allowance={} < needed={} (base units)",
+        .0.spender, .0.allowance, .0.needed
+    )]
+    InsufficientAllowance(AllowanceShortfall),
     #[error("Parse error: {0}")]
     Parse(String),
 }
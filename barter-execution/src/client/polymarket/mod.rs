@@ -3,6 +3,12 @@
 //! Instrument naming convention: `InstrumentNameExchange` = token_id directly.
 //! Prices are already in decimal 0-1.
 //!
+//! Time in force maps to the Polymarket order type, see [`PolymarketExecution::order_type`].
+//! `FillOrKill` orders are sent as all-or-nothing FOK, which arbitrage legs use by
+//! default. `ImmediateOrCancel` orders are sent as FAK, so thin books fill partially
+//! rather than not at all, with the partial fill reported for the other leg to be
+//! sized against.
+//!
 //! Two-layer auth:
 //! 1. API auth (all requests): HMAC-SHA256 headers
 //! 2. Order signing (order submission): EIP-712 typed data signature
@@ -18,22 +24,26 @@ use self::signing::{OrderParams, PolymarketApiCredentials, sign_order};
 use crate::{
     AccountEvent, AccountEventKind, UnindexedAccountEvent, UnindexedAccountSnapshot,
     balance::{AssetBalance, Balance},
-    error::{ConnectivityError, UnindexedClientError, UnindexedOrderError},
+    error::{ApiError, ConnectivityError, UnindexedClientError, UnindexedOrderError},
     order::{
         Order, OrderKey, OrderKind, TimeInForce,
         id::{ClientOrderId, OrderId, StrategyId},
         request::{OrderRequestCancel, OrderRequestOpen, UnindexedOrderResponseCancel},
-        state::Open,
+        state::{Open, UnindexedOrderState},
     },
     trade::Trade,
 };
-use super::ExecutionClient;
+use super::{
+    ExecutionClient, InstrumentPosition, merge_polls, merge_until_fills_end,
+    order_poll::{OrderPollTracker, OrderStatusSource, PolledOrder, PolledOrderStatus},
+};
 use alloy_primitives::{Address, U256};
 use barter_instrument::{
     Side,
     asset::{QuoteAsset, name::AssetNameExchange},
     exchange::ExchangeId,
     instrument::name::InstrumentNameExchange,
+    polymarket::tick,
 };
 use barter_integration::snapshot::Snapshot;
 use chrono::{DateTime, Utc};
@@ -41,9 +51,25 @@ use crate::order::state::Cancelled;
 use futures::{stream::BoxStream, StreamExt};
 use rust_decimal::Decimal;
 use smol_str::SmolStr;
-use tokio_stream::wrappers::IntervalStream;
+use std::{
+    collections::HashMap,
+    future::ready,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
 use tracing::{error, info, warn};
 
+/// Asset Polymarket balances are denominated in, unless configured otherwise.
+pub const DEFAULT_QUOTE_ASSET: &str = "usdc";
+
+/// Minimum order value, in the quote asset, Polymarket accepts.
+pub const DEFAULT_MIN_ORDER_VALUE: Decimal = Decimal::ONE;
+
+/// How long a confirmed USDC allowance is trusted before it's re-fetched.
+///
+/// Approvals can be revoked or spent down from the wallet at any time.
+pub const ALLOWANCE_RECHECK_INTERVAL: Duration = Duration::from_secs(60);
+
 /// Configuration for the Polymarket execution client.
 #[derive(Debug, Clone)]
 pub struct PolymarketExecutionConfig {
@@ -55,11 +81,24 @@ pub struct PolymarketExecutionConfig {
     pub private_key_hex: String,
     /// Maker (wallet) address.
     pub maker_address: String,
-    /// Polling interval for account stream in milliseconds.
-    pub poll_interval_ms: u64,
+    /// Balance polling interval for the account stream in milliseconds.
+    pub balance_poll_interval_ms: u64,
+    /// Order status polling interval for the account stream in milliseconds.
+    pub order_poll_interval_ms: u64,
+    /// Asset that balances are reported in (eg/ [`DEFAULT_QUOTE_ASSET`]).
+    pub quote_asset: AssetNameExchange,
     /// Whether markets are neg risk (uses different exchange contract).
     /// Default: false.
     pub neg_risk: bool,
+    /// Orders worth less than this, in the quote asset, are rejected before submission.
+    /// Default: [`DEFAULT_MIN_ORDER_VALUE`].
+    pub min_order_value: Decimal,
+    /// Tick size of each token, from its instrument spec, submitted with its orders.
+    /// Tokens without one use [`tick::DEFAULT_TICK_SIZE`].
+    pub tick_sizes: HashMap<InstrumentNameExchange, Decimal>,
+    /// Minimum order size, in shares, of each token, from its instrument spec.
+    /// Tokens without one only enforce [`Self::min_order_value`].
+    pub min_order_sizes: HashMap<InstrumentNameExchange, Decimal>,
 }
 
 /// Polymarket execution client implementing the barter ExecutionClient trait.
@@ -68,18 +107,177 @@ pub struct PolymarketExecution {
     http: PolymarketHttpClient,
     private_key_hex: String,
     maker_address: String,
-    poll_interval_ms: u64,
+    balance_poll_interval_ms: u64,
+    order_poll_interval_ms: u64,
+    quote_asset: AssetNameExchange,
     neg_risk: bool,
+    min_order_value: Decimal,
+    /// Tick size of each token, see [`PolymarketExecutionConfig::tick_sizes`]
+    tick_sizes: Arc<HashMap<InstrumentNameExchange, Decimal>>,
+    /// Minimum order size of each token, see [`PolymarketExecutionConfig::min_order_sizes`]
+    min_order_sizes: Arc<HashMap<InstrumentNameExchange, Decimal>>,
+    /// USDC allowance last confirmed sufficient, re-checked before buys once stale
+    allowance: Arc<Mutex<Option<AllowanceCheck>>>,
+    /// Orders polled for status changes, shared with the account stream
+    orders: OrderPollTracker,
 }
 
 impl PolymarketExecution {
+    /// Translate a data API position into an outcome instrument position.
+    ///
+    /// Empty positions return `None`.
+    pub fn instrument_position(position: &PolymarketPosition) -> Option<InstrumentPosition> {
+        (position.size > Decimal::ZERO).then(|| InstrumentPosition {
+            instrument: InstrumentNameExchange::from(position.asset.as_str()),
+            quantity: position.size,
+            cost_basis: position.size * position.avg_price,
+        })
+    }
+
+    /// Fetch non-empty positions held by the maker address, keyed by token ID.
+    pub async fn fetch_positions(&self) -> Result<Vec<InstrumentPosition>, UnindexedClientError> {
+        let positions = self
+            .http
+            .fetch_positions(&self.maker_address)
+            .await
+            .map_err(Self::map_http_error)?;
+
+        Ok(positions.iter().filter_map(Self::instrument_position).collect())
+    }
+
+    /// Balance of `total` in the configured quote asset, which Polymarket reports as
+    /// entirely free.
+    fn quote_balance(
+        quote_asset: &AssetNameExchange,
+        total: Decimal,
+    ) -> AssetBalance<AssetNameExchange> {
+        AssetBalance {
+            asset: quote_asset.clone(),
+            balance: Balance {
+                total,
+                free: total,
+            },
+            time_exchange: Utc::now(),
+        }
+    }
+
     fn map_http_error(e: PolymarketHttpError) -> UnindexedClientError {
         UnindexedClientError::Connectivity(ConnectivityError::Socket(e.to_string()))
     }
 
+    /// Classify an order rejection message from Polymarket.
+    ///
+    /// Rejections are order-logic failures (eg/ balance, tick size), distinct from
+    /// connectivity failures.
+    fn rejection_error(quote_asset: &AssetNameExchange, msg: String) -> UnindexedOrderError {
+        let lower = msg.to_lowercase();
+        let api = if lower.contains("balance") || lower.contains("allowance") {
+            ApiError::BalanceInsufficient(quote_asset.clone(), msg)
+        } else if lower.contains("rate limit") || lower.contains("too many requests") {
+            ApiError::RateLimit
+        } else {
+            ApiError::OrderRejected(msg)
+        };
+        UnindexedOrderError::Rejected(api)
+    }
+
+    /// Deduct a buy costing `needed` USDC from the confirmed allowance, returning `false`
+    /// if the allowance must be re-checked first.
+    fn spend_allowance(&self, needed: Decimal) -> bool {
+        let needed = usdc_base_units(needed);
+        let mut allowance = self.allowance.lock().unwrap();
+        match *allowance {
+            Some(check) if check.covers(needed, Instant::now()) => {
+                *allowance = Some(check.spend(needed));
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Map an order submission failure to an [`UnindexedOrderError`].
+    fn open_order_error(
+        quote_asset: &AssetNameExchange,
+        e: PolymarketHttpError,
+    ) -> UnindexedOrderError {
+        match e {
+            PolymarketHttpError::Rejected { status: 429, .. } => {
+                UnindexedOrderError::Rejected(ApiError::RateLimit)
+            }
+            PolymarketHttpError::Rejected { body, .. } => {
+                Self::rejection_error(quote_asset, body)
+            }
+            e @ PolymarketHttpError::InsufficientAllowance(_) => {
+                Self::rejection_error(quote_asset, e.to_string())
+            }
+            other => {
+                UnindexedOrderError::Connectivity(ConnectivityError::Socket(other.to_string()))
+            }
+        }
+    }
+
+    /// Tick size of `instrument`, see [`PolymarketExecutionConfig::tick_sizes`].
+    fn tick_size(&self, instrument: &InstrumentNameExchange) -> Decimal {
+        self.tick_sizes
+            .get(instrument)
+            .copied()
+            .unwrap_or(tick::DEFAULT_TICK_SIZE)
+    }
+
+    /// Reject an order Polymarket would refuse for being below its minimum value, or the
+    /// token's minimum size in shares when known, saving the round trip.
+    fn min_order_error(
+        &self,
+        instrument: &InstrumentNameExchange,
+        price: Decimal,
+        quantity: Decimal,
+    ) -> Option<UnindexedOrderError> {
+        let value = price * quantity;
+        let reason = if value < self.min_order_value {
+            format!("order value {value} below minimum {}", self.min_order_value)
+        } else if let Some(min_size) = self
+            .min_order_sizes
+            .get(instrument)
+            .filter(|min| quantity < **min)
+        {
+            format!("order size {quantity} below minimum {min_size} shares")
+        } else {
+            return None;
+        };
+
+        Some(UnindexedOrderError::Rejected(ApiError::OrderRejected(reason)))
+    }
+
+    /// Polymarket order type honouring `time_in_force`.
+    ///
+    /// Time in force without a Polymarket equivalent is rejected with
+    /// [`ApiError::TimeInForceUnsupported`].
+    pub fn order_type(
+        time_in_force: TimeInForce,
+    ) -> Result<PolymarketOrderType, UnindexedOrderError> {
+        match time_in_force {
+            TimeInForce::ImmediateOrCancel => Ok(PolymarketOrderType::Fak),
+            TimeInForce::FillOrKill => Ok(PolymarketOrderType::Fok),
+            TimeInForce::GoodUntilCancelled { post_only: false } => Ok(PolymarketOrderType::Gtc),
+            unsupported => Err(UnindexedOrderError::Rejected(
+                ApiError::TimeInForceUnsupported(unsupported),
+            )),
+        }
+    }
+
     fn order_error(
         request: &OrderRequestOpen<ExchangeId, &InstrumentNameExchange>,
         msg: String,
+    ) -> Order<ExchangeId, InstrumentNameExchange, Result<Open, UnindexedOrderError>> {
+        Self::order_failed(
+            request,
+            UnindexedOrderError::Connectivity(ConnectivityError::Socket(msg)),
+        )
+    }
+
+    fn order_failed(
+        request: &OrderRequestOpen<ExchangeId, &InstrumentNameExchange>,
+        error: UnindexedOrderError,
     ) -> Order<ExchangeId, InstrumentNameExchange, Result<Open, UnindexedOrderError>> {
         Order {
             key: OrderKey {
@@ -93,13 +291,75 @@ impl PolymarketExecution {
             quantity: request.state.quantity,
             kind: request.state.kind,
             time_in_force: request.state.time_in_force,
-            state: Err(UnindexedOrderError::Connectivity(
-                ConnectivityError::Socket(msg),
-            )),
+            state: Err(error),
+        }
+    }
+
+    /// Normalise a Polymarket order for status polling.
+    ///
+    /// Orders not opened by this client are keyed like [`Self::fetch_open_orders`],
+    /// with the order id as client order id.
+    pub fn polled_order(order: &PolymarketOrder) -> PolledOrder {
+        let status = match order.status.to_ascii_lowercase().as_str() {
+            "matched" => PolledOrderStatus::FullyFilled,
+            status if status.starts_with("cancel") || status == "invalid" => {
+                PolledOrderStatus::Cancelled
+            }
+            _ => PolledOrderStatus::Open,
+        };
+        let side = match order.side.as_str() {
+            "BUY" => Side::Buy,
+            _ => Side::Sell,
+        };
+
+        PolledOrder {
+            id: OrderId(SmolStr::new(&order.id)),
+            order: Order {
+                key: OrderKey {
+                    exchange: ExchangeId::Polymarket,
+                    instrument: InstrumentNameExchange::from(
+                        order.asset_id.as_deref().unwrap_or_default(),
+                    ),
+                    strategy: StrategyId::new("unknown"),
+                    cid: ClientOrderId::new(&order.id),
+                },
+                side,
+                price: order.price_decimal(),
+                quantity: order.original_size_decimal(),
+                kind: OrderKind::Limit,
+                time_in_force: TimeInForce::GoodUntilCancelled { post_only: false },
+                state: (),
+            },
+            status,
+            filled: order.size_matched_decimal(),
+            fill_cost: None,
+            fees: None,
         }
     }
 }
 
+impl OrderStatusSource for PolymarketExecution {
+    async fn fetch_resting_orders(&self) -> Result<Vec<PolledOrder>, UnindexedClientError> {
+        let orders = self
+            .http
+            .fetch_open_orders()
+            .await
+            .map_err(Self::map_http_error)?;
+
+        Ok(orders.iter().map(Self::polled_order).collect())
+    }
+
+    async fn fetch_order(&self, order_id: &OrderId) -> Result<PolledOrder, UnindexedClientError> {
+        let order = self
+            .http
+            .fetch_order(order_id.0.as_str())
+            .await
+            .map_err(Self::map_http_error)?;
+
+        Ok(Self::polled_order(&order))
+    }
+}
+
 impl ExecutionClient for PolymarketExecution {
     const EXCHANGE: ExchangeId = ExchangeId::Polymarket;
 
@@ -120,8 +380,15 @@ impl ExecutionClient for PolymarketExecution {
             http,
             private_key_hex: config.private_key_hex,
             maker_address: config.maker_address,
-            poll_interval_ms: config.poll_interval_ms,
+            balance_poll_interval_ms: config.balance_poll_interval_ms,
+            order_poll_interval_ms: config.order_poll_interval_ms,
+            quote_asset: config.quote_asset,
             neg_risk: config.neg_risk,
+            min_order_value: config.min_order_value,
+            tick_sizes: Arc::new(config.tick_sizes),
+            min_order_sizes: Arc::new(config.min_order_sizes),
+            allowance: Arc::new(Mutex::new(None)),
+            orders: OrderPollTracker::new(ExchangeId::Polymarket),
         }
     }
 
@@ -138,14 +405,7 @@ impl ExecutionClient for PolymarketExecution {
             }
         };
 
-        let balances = vec![AssetBalance {
-            asset: AssetNameExchange::from("usdc"),
-            balance: Balance {
-                total: balance_decimal,
-                free: balance_decimal,
-            },
-            time_exchange: Utc::now(),
-        }];
+        let balances = vec![Self::quote_balance(&self.quote_asset, balance_decimal)];
 
         Ok(UnindexedAccountSnapshot {
             exchange: ExchangeId::Polymarket,
@@ -159,42 +419,46 @@ impl ExecutionClient for PolymarketExecution {
         _assets: &[AssetNameExchange],
         _instruments: &[InstrumentNameExchange],
     ) -> Result<Self::AccountStream, UnindexedClientError> {
-        // Balance polling stream (existing behavior)
-        let interval = tokio::time::interval(
-            std::time::Duration::from_millis(self.poll_interval_ms),
-        );
+        // Balance and order status polling on independent intervals, the latter reporting
+        // state changes of resting and opened orders
         let http = self.http.clone();
-
-        let balance_stream = IntervalStream::new(interval).filter_map(move |_| {
-            let http = http.clone();
-            async move {
-                match http.fetch_balance().await {
-                    Ok(resp) => {
-                        let balance_decimal = resp
-                            .balance
-                            .parse::<Decimal>()
-                            .unwrap_or(Decimal::ZERO);
-                        Some(AccountEvent {
-                            exchange: ExchangeId::Polymarket,
-                            kind: AccountEventKind::BalanceSnapshot(
-                                Snapshot(AssetBalance {
-                                    asset: AssetNameExchange::from("usdc"),
-                                    balance: Balance {
-                                        total: balance_decimal,
-                                        free: balance_decimal,
-                                    },
-                                    time_exchange: Utc::now(),
-                                }),
-                            ),
-                        })
-                    }
-                    Err(e) => {
-                        warn!(error = %e, "Polymarket balance poll failed");
-                        None
+        let quote_asset = self.quote_asset.clone();
+        let client = self.clone();
+        let balance_stream = merge_polls(
+            std::time::Duration::from_millis(self.balance_poll_interval_ms),
+            move || {
+                let http = http.clone();
+                let quote_asset = quote_asset.clone();
+                async move {
+                    match http.fetch_balance().await {
+                        Ok(resp) => {
+                            let balance_decimal = resp
+                                .balance
+                                .parse::<Decimal>()
+                                .unwrap_or(Decimal::ZERO);
+                            Some(AccountEvent {
+                                exchange: ExchangeId::Polymarket,
+                                kind: AccountEventKind::BalanceSnapshot(
+                                    Snapshot(Self::quote_balance(
+                                        &quote_asset,
+                                        balance_decimal,
+                                    )),
+                                ),
+                            })
+                        }
+                        Err(e) => {
+                            warn!(error = %e, "Polymarket balance poll failed");
+                            None
+                        }
                     }
                 }
-            }
-        });
+            },
+            std::time::Duration::from_millis(self.order_poll_interval_ms),
+            move || {
+                let client = client.clone();
+                async move { client.orders.poll(&client).await }
+            },
+        );
 
         // Polymarket instrument names are token IDs (asset_ids).
         // The user WS subscribes by condition ID (market), but passing
@@ -215,16 +479,20 @@ impl ExecutionClient for PolymarketExecution {
         .await
         {
             Ok(websocket) => {
-                info!("Polymarket user WS connected, merging with balance polling");
-                let (fill_stream, _ping_handle) = ws::polymarket_fill_stream(websocket);
-                let merged = tokio_stream::StreamExt::merge(
-                    tokio_stream::StreamExt::fuse(balance_stream),
-                    tokio_stream::StreamExt::fuse(fill_stream),
+                info!(
+                    "Polymarket user WS connected, merging with balance and order status polling"
                 );
-                Ok(Box::pin(merged))
+                let (fill_stream, _ping_handle) = ws::polymarket_fill_stream(websocket);
+                let orders = self.orders.clone();
+                let fill_stream =
+                    fill_stream.filter_map(move |event| ready(orders.dedup_fill(event)));
+                Ok(merge_until_fills_end(ExchangeId::Polymarket, balance_stream, fill_stream))
             }
             Err(e) => {
-                warn!(error = %e, "Polymarket user WS connection failed, using balance-only polling");
+                warn!(
+                    error = %e,
+                    "Polymarket user WS connection failed, using balance and order status polling"
+                );
                 Ok(Box::pin(balance_stream))
             }
         }
@@ -260,13 +528,17 @@ impl ExecutionClient for PolymarketExecution {
         };
 
         Some(match result {
-            Ok(()) => UnindexedOrderResponseCancel {
-                key,
-                state: Ok(Cancelled {
-                    id: OrderId(SmolStr::new(&order_id)),
-                    time_exchange: Utc::now(),
-                }),
-            },
+            Ok(()) => {
+                let id = OrderId(SmolStr::new(&order_id));
+                self.orders.cancelled(&id);
+                UnindexedOrderResponseCancel {
+                    key,
+                    state: Ok(Cancelled {
+                        id,
+                        time_exchange: Utc::now(),
+                    }),
+                }
+            }
             Err(e) => {
                 error!(error = %e, "Polymarket cancel order failed");
                 UnindexedOrderResponseCancel {
@@ -285,6 +557,20 @@ impl ExecutionClient for PolymarketExecution {
     ) -> Option<Order<ExchangeId, InstrumentNameExchange, Result<Open, UnindexedOrderError>>> {
         let token_id = request.key.instrument.to_string();
 
+        if let Some(error) = self.min_order_error(
+            request.key.instrument,
+            request.state.price,
+            request.state.quantity,
+        ) {
+            warn!(token_id = %token_id, %error, "Polymarket order below minimum, not submitted");
+            return Some(Self::order_failed(&request, error));
+        }
+
+        let order_type = match Self::order_type(request.state.time_in_force) {
+            Ok(order_type) => order_type,
+            Err(error) => return Some(Self::order_failed(&request, error)),
+        };
+
         let side_num: u8 = match request.state.side {
             Side::Buy => 0,
             Side::Sell => 1,
@@ -300,6 +586,27 @@ impl ExecutionClient for PolymarketExecution {
             .round()
             .to_string();
 
+        // Check the USDC allowance before buys, re-fetching it once the last check is stale
+        // or no longer covers the order
+        let needed = request.state.price * request.state.quantity;
+        if request.state.side == Side::Buy && !self.spend_allowance(needed) {
+            match self.http.ensure_allowance(needed, self.neg_risk).await {
+                Ok(allowance) => {
+                    let check = AllowanceCheck::new(allowance, Instant::now());
+                    let check = check.spend(usdc_base_units(needed));
+                    *self.allowance.lock().unwrap() = Some(check);
+                }
+                Err(e @ PolymarketHttpError::InsufficientAllowance(_)) => {
+                    *self.allowance.lock().unwrap() = None;
+                    let error = Self::open_order_error(&self.quote_asset, e);
+                    return Some(Self::order_failed(&request, error));
+                }
+                Err(e) => {
+                    warn!(error = %e, "Polymarket allowance check failed, submitting anyway")
+                }
+            }
+        }
+
         let (maker_amount_str, taker_amount_str) = match request.state.side {
             Side::Buy => (cost_raw.clone(), quantity_raw.clone()),
             Side::Sell => (quantity_raw.clone(), cost_raw.clone()),
@@ -378,8 +685,8 @@ impl ExecutionClient for PolymarketExecution {
                 signature,
                 signature_type: 0,
             },
-            order_type: "FOK".to_string(),
-            tick_size: Some("0.01".to_string()),
+            order_type,
+            tick_size: Some(self.tick_size(request.key.instrument).to_string()),
             neg_risk: if self.neg_risk { Some(true) } else { None },
         };
 
@@ -393,22 +700,42 @@ impl ExecutionClient for PolymarketExecution {
 
         Some(match result {
             Ok(resp) => {
+                let filled_quantity = resp.filled_quantity(
+                    request.state.side,
+                    request.state.quantity,
+                    order_type,
+                );
+                let tracked = resp.order_id.is_some();
                 let order_id = resp
                     .order_id
                     .unwrap_or_else(|| "unknown".to_string());
 
                 if resp.success.unwrap_or(false) {
-                    Order {
+                    let order = Order {
                         key,
                         side: request.state.side,
                         price: request.state.price,
                         quantity: request.state.quantity,
                         kind: request.state.kind,
                         time_in_force: request.state.time_in_force,
+                        state: (),
+                    };
+                    let order_id = OrderId(SmolStr::new(&order_id));
+                    if tracked {
+                        self.orders.track(order_id.clone(), order.clone(), filled_quantity);
+                    }
+
+                    Order {
+                        key: order.key,
+                        side: order.side,
+                        price: order.price,
+                        quantity: order.quantity,
+                        kind: order.kind,
+                        time_in_force: order.time_in_force,
                         state: Ok(Open {
-                            id: OrderId(SmolStr::new(&order_id)),
+                            id: order_id,
                             time_exchange: Utc::now(),
-                            filled_quantity: Decimal::ZERO,
+                            filled_quantity,
                         }),
                     }
                 } else {
@@ -422,9 +749,7 @@ impl ExecutionClient for PolymarketExecution {
                         quantity: request.state.quantity,
                         kind: request.state.kind,
                         time_in_force: request.state.time_in_force,
-                        state: Err(UnindexedOrderError::Connectivity(
-                            ConnectivityError::Socket(err_msg),
-                        )),
+                        state: Err(Self::rejection_error(&self.quote_asset, err_msg)),
                     }
                 }
             }
@@ -437,9 +762,7 @@ impl ExecutionClient for PolymarketExecution {
                     quantity: request.state.quantity,
                     kind: request.state.kind,
                     time_in_force: request.state.time_in_force,
-                    state: Err(UnindexedOrderError::Connectivity(
-                        ConnectivityError::Socket(e.to_string()),
-                    )),
+                    state: Err(Self::open_order_error(&self.quote_asset, e)),
                 }
             }
         })
@@ -459,14 +782,7 @@ impl ExecutionClient for PolymarketExecution {
             .parse::<Decimal>()
             .unwrap_or(Decimal::ZERO);
 
-        Ok(vec![AssetBalance {
-            asset: AssetNameExchange::from("usdc"),
-            balance: Balance {
-                total: balance_decimal,
-                free: balance_decimal,
-            },
-            time_exchange: Utc::now(),
-        }])
+        Ok(vec![Self::quote_balance(&self.quote_asset, balance_decimal)])
     }
 
     async fn fetch_open_orders(
@@ -521,4 +837,393 @@ impl ExecutionClient for PolymarketExecution {
         // Trades are tracked via order updates. Return empty for now.
         Ok(vec![])
     }
+
+    async fn fetch_order(
+        &self,
+        order_id: &OrderId,
+    ) -> Result<
+        Option<(Order<ExchangeId, InstrumentNameExchange, UnindexedOrderState>, Decimal)>,
+        UnindexedClientError,
+    > {
+        match self.http.fetch_order(order_id.0.as_str()).await {
+            Ok(order) => {
+                let polled = Self::polled_order(&order);
+                let filled = polled.filled;
+                Ok(Some((polled.into_order(Utc::now()), filled)))
+            }
+            Err(PolymarketHttpError::Rejected { status: 404, .. }) => Ok(None),
+            Err(error) => Err(Self::map_http_error(error)),
+        }
+    }
+}
+
+/// USDC allowance confirmed for the exchange contract, less the buys submitted since.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct AllowanceCheck {
+    checked_at: Instant,
+    remaining: U256,
+}
+
+impl AllowanceCheck {
+    fn new(allowance: U256, checked_at: Instant) -> Self {
+        Self {
+            checked_at,
+            remaining: allowance,
+        }
+    }
+
+    /// Whether the check is recent enough and still covers `needed` base units.
+    fn covers(&self, needed: U256, now: Instant) -> bool {
+        now.duration_since(self.checked_at) < ALLOWANCE_RECHECK_INTERVAL
+            && self.remaining >= needed
+    }
+
+    /// Deduct an order's cost, in base units, from the remaining allowance.
+    fn spend(self, needed: U256) -> Self {
+        Self {
+            remaining: self.remaining.saturating_sub(needed),
+            ..self
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::order::{
+        request::RequestOpen,
+        state::{ActiveOrderState, InactiveOrderState, OrderState},
+    };
+
+    #[test]
+    fn test_balance_rejection_is_typed() {
+        let usdc = AssetNameExchange::from(DEFAULT_QUOTE_ASSET);
+        let error = PolymarketExecution::rejection_error(
+            &usdc,
+            "not enough balance / allowance".to_string(),
+        );
+        assert_eq!(
+            error,
+            UnindexedOrderError::Rejected(ApiError::BalanceInsufficient(
+                usdc,
+                "not enough balance / allowance".to_string(),
+            ))
+        );
+    }
+
+    #[test]
+    fn test_tick_size_rejection_is_typed() {
+        let msg = "invalid order: price (0.555), breaks minimum tick size rule: 0.01";
+        let usdc = AssetNameExchange::from(DEFAULT_QUOTE_ASSET);
+        let error = PolymarketExecution::rejection_error(&usdc, msg.to_string());
+        assert_eq!(
+            error,
+            UnindexedOrderError::Rejected(ApiError::OrderRejected(msg.to_string()))
+        );
+    }
+
+    #[test]
+    fn test_open_order_http_errors() {
+        let usdc = AssetNameExchange::from(DEFAULT_QUOTE_ASSET);
+        let rejected = PolymarketExecution::open_order_error(&usdc, PolymarketHttpError::Rejected {
+            status: 400,
+            body: r#"{"error":"not enough balance / allowance"}"#.to_string(),
+        });
+        assert!(matches!(
+            rejected,
+            UnindexedOrderError::Rejected(ApiError::BalanceInsufficient(..))
+        ));
+
+        let throttled = PolymarketExecution::open_order_error(&usdc, PolymarketHttpError::Rejected {
+            status: 429,
+            body: String::new(),
+        });
+        assert_eq!(throttled, UnindexedOrderError::Rejected(ApiError::RateLimit));
+
+        let disconnected = PolymarketExecution::open_order_error(
+            &usdc,
+            PolymarketHttpError::Request("timed out".into()),
+        );
+        assert!(matches!(disconnected, UnindexedOrderError::Connectivity(_)));
+    }
+
+    #[test]
+    fn test_custom_quote_asset_propagates_to_balances() {
+        let client = PolymarketExecution::new(PolymarketExecutionConfig {
+            api_key: "key".to_string(),
+            api_secret: "secret".to_string(),
+            api_passphrase: "passphrase".to_string(),
+            private_key_hex: String::new(),
+            maker_address: "0xabc".to_string(),
+            balance_poll_interval_ms: 1000,
+            order_poll_interval_ms: 1000,
+            quote_asset: AssetNameExchange::from("pusd"),
+            neg_risk: false,
+            min_order_value: DEFAULT_MIN_ORDER_VALUE,
+            tick_sizes: HashMap::new(),
+            min_order_sizes: HashMap::new(),
+        });
+
+        let balance = PolymarketExecution::quote_balance(&client.quote_asset, Decimal::new(255, 1));
+        assert_eq!(balance.asset, AssetNameExchange::from("pusd"));
+        assert_eq!(balance.balance, Balance::new(Decimal::new(255, 1), Decimal::new(255, 1)));
+
+        let rejected = PolymarketExecution::open_order_error(
+            &client.quote_asset,
+            PolymarketHttpError::Rejected {
+                status: 400,
+                body: "not enough balance / allowance".to_string(),
+            },
+        );
+        assert!(matches!(
+            rejected,
+            UnindexedOrderError::Rejected(ApiError::BalanceInsufficient(asset, _))
+                if asset == AssetNameExchange::from("pusd")
+        ));
+    }
+
+    fn client_config() -> PolymarketExecutionConfig {
+        PolymarketExecutionConfig {
+            api_key: "key".to_string(),
+            api_secret: "secret".to_string(),
+            api_passphrase: "passphrase".to_string(),
+            private_key_hex: String::new(),
+            maker_address: "0xabc".to_string(),
+            balance_poll_interval_ms: 1000,
+            order_poll_interval_ms: 1000,
+            quote_asset: AssetNameExchange::from(DEFAULT_QUOTE_ASSET),
+            neg_risk: false,
+            min_order_value: DEFAULT_MIN_ORDER_VALUE,
+            tick_sizes: HashMap::new(),
+            min_order_sizes: HashMap::new(),
+        }
+    }
+
+    fn client() -> PolymarketExecution {
+        PolymarketExecution::new(client_config())
+    }
+
+    fn open_request(
+        instrument: &InstrumentNameExchange,
+        price: Decimal,
+        quantity: Decimal,
+    ) -> OrderRequestOpen<ExchangeId, &InstrumentNameExchange> {
+        OrderRequestOpen {
+            key: OrderKey {
+                exchange: ExchangeId::Polymarket,
+                instrument,
+                strategy: StrategyId::new("arb"),
+                cid: ClientOrderId::new("cid-1"),
+            },
+            state: RequestOpen {
+                side: Side::Buy,
+                price,
+                quantity,
+                kind: OrderKind::Limit,
+                time_in_force: TimeInForce::ImmediateOrCancel,
+            },
+        }
+    }
+
+    #[test]
+    fn test_time_in_force_maps_to_order_type() {
+        assert_eq!(
+            PolymarketExecution::order_type(TimeInForce::ImmediateOrCancel),
+            Ok(PolymarketOrderType::Fak)
+        );
+        assert_eq!(
+            PolymarketExecution::order_type(TimeInForce::FillOrKill),
+            Ok(PolymarketOrderType::Fok)
+        );
+        assert_eq!(
+            PolymarketExecution::order_type(TimeInForce::GoodUntilCancelled { post_only: false }),
+            Ok(PolymarketOrderType::Gtc)
+        );
+        assert_eq!(
+            PolymarketExecution::order_type(TimeInForce::GoodUntilEndOfDay),
+            Err(UnindexedOrderError::Rejected(ApiError::TimeInForceUnsupported(
+                TimeInForce::GoodUntilEndOfDay
+            )))
+        );
+
+        assert_eq!(serde_json::to_value(PolymarketOrderType::Fak).unwrap(), "FAK");
+        assert_eq!(serde_json::to_value(PolymarketOrderType::Fok).unwrap(), "FOK");
+    }
+
+    #[tokio::test]
+    async fn test_sub_minimum_value_order_rejected_locally() {
+        let instrument = InstrumentNameExchange::from("1234");
+
+        // $0.40 of shares, rejected without reaching the (unreachable) API
+        let order = client()
+            .open_order(open_request(&instrument, Decimal::new(40, 2), Decimal::ONE))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            order.state,
+            Err(UnindexedOrderError::Rejected(ApiError::OrderRejected(
+                "order value 0.40 below minimum 1".to_string()
+            )))
+        );
+    }
+
+    #[test]
+    fn test_allowance_rechecked_once_stale_or_spent() {
+        let client = client();
+        let usdc = |units: u64| U256::from(units * 1_000_000);
+
+        // Nothing confirmed yet
+        assert!(!client.spend_allowance(Decimal::ONE));
+
+        // Buys are deducted from the confirmed allowance until it no longer covers one
+        let check = AllowanceCheck::new(usdc(10), Instant::now());
+        *client.allowance.lock().unwrap() = Some(check);
+        assert!(client.spend_allowance(Decimal::from(6)));
+        assert!(!client.spend_allowance(Decimal::from(6)));
+        assert!(client.spend_allowance(Decimal::from(4)));
+        assert_eq!(client.allowance.lock().unwrap().unwrap().remaining, U256::ZERO);
+
+        // A check older than the interval is re-fetched, however much it covers
+        let now = Instant::now();
+        let check = AllowanceCheck::new(U256::MAX, now);
+        assert!(check.covers(usdc(1), now + ALLOWANCE_RECHECK_INTERVAL / 2));
+        assert!(!check.covers(usdc(1), now + ALLOWANCE_RECHECK_INTERVAL));
+    }
+
+    #[test]
+    fn test_tick_size_from_instrument_spec() {
+        let token = InstrumentNameExchange::from("5678");
+        let client = PolymarketExecution::new(PolymarketExecutionConfig {
+            tick_sizes: HashMap::from([(token.clone(), Decimal::new(1, 3))]),
+            ..client_config()
+        });
+
+        assert_eq!(client.tick_size(&token), Decimal::new(1, 3));
+        assert_eq!(
+            client.tick_size(&InstrumentNameExchange::from("unknown")),
+            tick::DEFAULT_TICK_SIZE
+        );
+    }
+
+    #[test]
+    fn test_min_order_size_from_instrument_spec() {
+        let token = InstrumentNameExchange::from("5678");
+        let client = PolymarketExecution::new(PolymarketExecutionConfig {
+            min_order_sizes: HashMap::from([(token.clone(), Decimal::from(5))]),
+            ..client_config()
+        });
+
+        assert_eq!(
+            client.min_order_error(&token, Decimal::new(50, 2), Decimal::from(4)),
+            Some(UnindexedOrderError::Rejected(ApiError::OrderRejected(
+                "order size 4 below minimum 5 shares".to_string()
+            )))
+        );
+        assert_eq!(
+            client.min_order_error(&token, Decimal::new(50, 2), Decimal::from(5)),
+            None
+        );
+        // Tokens without known metadata only enforce the minimum value
+        assert_eq!(
+            client.min_order_error(
+                &InstrumentNameExchange::from("unknown"),
+                Decimal::new(50, 2),
+                Decimal::from(2)
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_data_api_positions_map_to_token_instruments() {
+        let json = r#"[
+            {"asset": "1234", "conditionId": "0xabc", "size": 25.5, "avgPrice": 0.4},
+            {"asset": "5678", "conditionId": "0xdef", "size": 0, "avgPrice": 0.6}
+        ]"#;
+        let positions: Vec<PolymarketPosition> = serde_json::from_str(json).unwrap();
+        let positions: Vec<_> = positions
+            .iter()
+            .filter_map(PolymarketExecution::instrument_position)
+            .collect();
+
+        assert_eq!(
+            positions,
+            vec![InstrumentPosition {
+                instrument: InstrumentNameExchange::from("1234"),
+                quantity: Decimal::new(255, 1),
+                cost_basis: Decimal::new(1020, 2),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_polled_order_status() {
+        let order = |status: &str, size_matched: &str| -> PolymarketOrder {
+            serde_json::from_value(serde_json::json!({
+                "id": "0xorder",
+                "status": status,
+                "side": "SELL",
+                "price": "0.57",
+                "original_size": "10",
+                "size_matched": size_matched,
+                "asset_id": "1234",
+            }))
+            .unwrap()
+        };
+
+        let polled = PolymarketExecution::polled_order(&order("LIVE", "4"));
+        assert_eq!(polled.status, PolledOrderStatus::Open);
+        assert_eq!(polled.order.key.instrument, InstrumentNameExchange::from("1234"));
+        assert_eq!(polled.order.side, Side::Sell);
+        assert_eq!(polled.remaining(), Decimal::from(6));
+
+        let polled = PolymarketExecution::polled_order(&order("MATCHED", "10"));
+        assert_eq!(polled.status, PolledOrderStatus::FullyFilled);
+
+        let polled = PolymarketExecution::polled_order(&order("CANCELED", "4"));
+        assert_eq!(polled.status, PolledOrderStatus::Cancelled);
+        assert_eq!(polled.filled, Decimal::from(4));
+    }
+
+    #[test]
+    fn test_fetched_order_state() {
+        let order = |status: &str, size_matched: &str| -> PolymarketOrder {
+            serde_json::from_value(serde_json::json!({
+                "id": "0xorder",
+                "status": status,
+                "side": "BUY",
+                "price": "0.57",
+                "original_size": "10",
+                "size_matched": size_matched,
+                "asset_id": "1234",
+            }))
+            .unwrap()
+        };
+        let fetched = |order: PolymarketOrder| {
+            PolymarketExecution::polled_order(&order).into_order(Utc::now())
+        };
+
+        let filled = fetched(order("MATCHED", "10"));
+        assert_eq!(filled.key.instrument, InstrumentNameExchange::from("1234"));
+        assert_eq!(filled.side, Side::Buy);
+        assert_eq!(filled.price, Decimal::new(57, 2));
+        assert_eq!(filled.quantity, Decimal::from(10));
+        assert_eq!(filled.state, OrderState::fully_filled());
+
+        let cancelled = fetched(order("CANCELED", "4"));
+        assert!(matches!(
+            cancelled.state,
+            OrderState::Inactive(InactiveOrderState::Cancelled(Cancelled { ref id, .. }))
+                if id == &OrderId::new("0xorder")
+        ));
+
+        // Live orders report how much has filled so far
+        let open = fetched(order("LIVE", "4"));
+        assert!(matches!(
+            open.state,
+            OrderState::Active(ActiveOrderState::Open(Open { filled_quantity, .. }))
+                if filled_quantity == Decimal::from(4)
+        ));
+    }
 }
@@ -1,5 +1,7 @@
 //! Polymarket CLOB API request/response models.
 
+use alloy_primitives::U256;
+use barter_instrument::Side;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 
@@ -8,13 +10,25 @@ use serde::{Deserialize, Serialize};
 pub struct PolymarketOrderPayload {
     pub order: SignedOrderPayload,
     #[serde(rename = "orderType")]
-    pub order_type: String, // "GTC", "FOK", "GTD"
+    pub order_type: PolymarketOrderType,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tick_size: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub neg_risk: Option<bool>,
 }
 
+/// How long a Polymarket order may rest on the book.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum PolymarketOrderType {
+    /// Good-til-cancelled
+    Gtc,
+    /// Fill-or-kill: fill entirely on submission, or not at all
+    Fok,
+    /// Fill-and-kill: fill what the book offers on submission, killing the remainder
+    Fak,
+}
+
 /// The signed order structure sent to the CLOB.
 #[derive(Debug, Clone, Serialize)]
 pub struct SignedOrderPayload {
@@ -52,7 +66,53 @@ pub struct PolymarketOrderResponse {
     pub success: Option<bool>,
     #[serde(rename = "errorMsg")]
     pub error_msg: Option<String>,
+    /// "matched", "live", "delayed" or "unmatched"
     pub status: Option<String>,
+    /// Amount given up by the order (USDC for a buy, shares for a sell)
+    #[serde(rename = "makingAmount", default)]
+    pub making_amount: Option<String>,
+    /// Amount received by the order (shares for a buy, USDC for a sell)
+    #[serde(rename = "takingAmount", default)]
+    pub taking_amount: Option<String>,
+}
+
+impl PolymarketOrderResponse {
+    /// Whether the order was matched on submission.
+    pub fn is_matched(&self) -> bool {
+        self.status.as_deref() == Some("matched")
+    }
+
+    /// Shares filled on submission for an order of `quantity` shares on `side`.
+    ///
+    /// A matched FOK response without a parseable amount is treated as fully filled, since
+    /// FOK orders either fill completely or not at all. Other order types may have filled
+    /// partially, so are reported unfilled, leaving status polling to report the fill.
+    pub fn filled_quantity(
+        &self,
+        side: Side,
+        quantity: Decimal,
+        order_type: PolymarketOrderType,
+    ) -> Decimal {
+        if !self.is_matched() {
+            return Decimal::ZERO;
+        }
+
+        let shares = match side {
+            Side::Buy => self.taking_amount.as_deref(),
+            Side::Sell => self.making_amount.as_deref(),
+        };
+
+        shares
+            .and_then(|amount| amount.parse::<Decimal>().ok())
+            .filter(|amount| *amount > Decimal::ZERO)
+            .map_or_else(
+                || match order_type {
+                    PolymarketOrderType::Fok => quantity,
+                    _ => Decimal::ZERO,
+                },
+                |amount| amount.min(quantity),
+            )
+    }
 }
 
 /// Response from GET /orders.
@@ -82,6 +142,146 @@ pub struct PolymarketBalanceResponse {
     pub allowances: std::collections::HashMap<String, String>,
 }
 
+impl PolymarketBalanceResponse {
+    /// Allowance granted to `spender` in USDC base units (6 decimals), if reported.
+    ///
+    /// Parsed as a `U256` since approvals are commonly `uint256::MAX`.
+    pub fn allowance(&self, spender: &str) -> Option<U256> {
+        self.allowances
+            .iter()
+            .find(|(address, _)| address.eq_ignore_ascii_case(spender))
+            .and_then(|(_, allowance)| U256::from_str_radix(allowance, 10).ok())
+    }
+
+    /// Check `spender` may spend at least `needed` USDC.
+    pub fn check_allowance(
+        &self,
+        spender: &str,
+        needed: Decimal,
+    ) -> Result<(), AllowanceShortfall> {
+        let needed_raw = usdc_base_units(needed);
+        let allowance = self.allowance(spender).unwrap_or(U256::ZERO);
+
+        if allowance >= needed_raw {
+            Ok(())
+        } else {
+            Err(AllowanceShortfall {
+                spender: spender.to_string(),
+                allowance: allowance.to_string(),
+                needed: needed_raw.to_string(),
+            })
+        }
+    }
+}
+
+/// Convert a USDC amount to base units (6 decimals), rounding up.
+pub fn usdc_base_units(amount: Decimal) -> U256 {
+    let raw = (amount * Decimal::from(1_000_000)).ceil().to_string();
+    U256::from_str_radix(&raw, 10).unwrap_or(U256::MAX)
+}
+
+/// USDC allowance below what an order needs, in USDC base units.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AllowanceShortfall {
+    pub spender: String,
+    pub allowance: String,
+    pub needed: String,
+}
+
+/// A position from the data API's GET /positions.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PolymarketPosition {
+    /// Outcome token ID
+    pub asset: String,
+    pub condition_id: String,
+    /// Tokens held
+    pub size: Decimal,
+    /// Average entry price
+    pub avg_price: Decimal,
+}
+
+/// A market from the gamma API's GET /markets.
+///
+/// Gamma encodes `outcomes` and `clobTokenIds` as JSON arrays inside strings.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PolymarketGammaMarket {
+    pub condition_id: String,
+    #[serde(default)]
+    pub question: String,
+    /// eg/ `"[\"Yes\", \"No\"]"`
+    pub outcomes: String,
+    /// Outcome token ids, in `outcomes` order
+    pub clob_token_ids: String,
+    #[serde(default)]
+    pub neg_risk: bool,
+    pub order_price_min_tick_size: Option<Decimal>,
+    pub order_min_size: Option<Decimal>,
+    pub end_date: Option<String>,
+    #[serde(default)]
+    pub active: bool,
+    #[serde(default)]
+    pub closed: bool,
+}
+
+impl PolymarketGammaMarket {
+    /// Resolve the YES/NO token ids, neg-risk status, tick size and minimum order size of this
+    /// binary market.
+    ///
+    /// The YES token is the outcome named "Yes", or the first outcome of markets with
+    /// other outcome names (eg/ "Over"/"Under").
+    pub fn meta(&self) -> Result<PolymarketMarketMeta, String> {
+        let outcomes: Vec<String> = serde_json::from_str(&self.outcomes)
+            .map_err(|e| format!("invalid outcomes {:?}: {e}", self.outcomes))?;
+        let tokens: Vec<String> = serde_json::from_str(&self.clob_token_ids)
+            .map_err(|e| format!("invalid clobTokenIds {:?}: {e}", self.clob_token_ids))?;
+
+        if outcomes.len() != 2 || tokens.len() != 2 {
+            return Err(format!(
+                "market {} is not binary: {} outcomes, {} tokens",
+                self.condition_id,
+                outcomes.len(),
+                tokens.len()
+            ));
+        }
+
+        let yes = outcomes
+            .iter()
+            .position(|outcome| outcome.eq_ignore_ascii_case("yes"))
+            .unwrap_or(0);
+
+        Ok(PolymarketMarketMeta {
+            condition_id: self.condition_id.clone(),
+            question: self.question.clone(),
+            yes_token: tokens[yes].clone(),
+            no_token: tokens[1 - yes].clone(),
+            neg_risk: self.neg_risk,
+            tick_size: self.order_price_min_tick_size,
+            min_order_size: self.order_min_size,
+            closed: self.closed || !self.active,
+        })
+    }
+}
+
+/// Tradable metadata of a binary Polymarket market, see
+/// [`PolymarketHttpClient::fetch_market`](super::http::PolymarketHttpClient::fetch_market).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PolymarketMarketMeta {
+    pub condition_id: String,
+    pub question: String,
+    pub yes_token: String,
+    pub no_token: String,
+    /// Whether orders go through the neg-risk exchange
+    pub neg_risk: bool,
+    /// Minimum price increment, if reported
+    pub tick_size: Option<Decimal>,
+    /// Minimum order size in shares, if reported
+    pub min_order_size: Option<Decimal>,
+    /// Whether the market is closed or inactive
+    pub closed: bool,
+}
+
 /// Response from POST /auth/api-key or GET /auth/derive-api-key.
 #[derive(Debug, Clone, Deserialize)]
 pub struct PolymarketApiKeyResponse {
@@ -112,3 +312,176 @@ impl PolymarketOrder {
         self.status == "live" || self.status == "open"
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matched_fok_response_fully_filled() {
+        let json = r#"{
+            "success": true,
+            "errorMsg": "",
+            "orderID": "0xabc",
+            "transactionsHashes": ["0xdef"],
+            "status": "matched",
+            "makingAmount": "55",
+            "takingAmount": "100"
+        }"#;
+        let resp: PolymarketOrderResponse = serde_json::from_str(json).unwrap();
+
+        assert!(resp.is_matched());
+        assert_eq!(resp.making_amount.as_deref(), Some("55"));
+        assert_eq!(
+            resp.filled_quantity(Side::Buy, Decimal::from(100), PolymarketOrderType::Fok),
+            Decimal::from(100)
+        );
+        assert_eq!(
+            resp.filled_quantity(Side::Sell, Decimal::from(55), PolymarketOrderType::Fok),
+            Decimal::from(55)
+        );
+    }
+
+    #[test]
+    fn test_unmatched_response_not_filled() {
+        let json = r#"{"success": true, "orderID": "0xabc", "status": "unmatched"}"#;
+        let resp: PolymarketOrderResponse = serde_json::from_str(json).unwrap();
+
+        assert!(!resp.is_matched());
+        assert_eq!(
+            resp.filled_quantity(Side::Buy, Decimal::from(100), PolymarketOrderType::Fak),
+            Decimal::ZERO
+        );
+    }
+
+    #[test]
+    fn test_allowance_check() {
+        let json = r#"{
+            "balance": "25000000",
+            "allowances": {
+                "0x4bfb41d5b3570defd03c39a9a4d8de6bd8b8982e": "10000000",
+                "0xC5d563A36AE78145C45a50134d48A1215220f80a": "115792089237316195423570985008687907853269984665640564039457584007913129639935"
+            }
+        }"#;
+        let resp: PolymarketBalanceResponse = serde_json::from_str(json).unwrap();
+        let ctf = "0x4bFb41d5B3570DeFd03C39a9A4D8dE6Bd8B8982E";
+        let neg_risk = "0xC5d563A36AE78145C45a50134d48A1215220f80a";
+
+        // Addresses match case-insensitively
+        assert_eq!(resp.allowance(ctf), Some(U256::from(10_000_000u64)));
+        assert_eq!(resp.check_allowance(ctf, Decimal::from(10)), Ok(()));
+        assert_eq!(
+            resp.check_allowance(ctf, Decimal::new(1001, 2)),
+            Err(AllowanceShortfall {
+                spender: ctf.to_string(),
+                allowance: "10000000".to_string(),
+                needed: "10010000".to_string(),
+            })
+        );
+
+        // Max approvals don't overflow
+        assert_eq!(resp.allowance(neg_risk), Some(U256::MAX));
+        assert_eq!(resp.check_allowance(neg_risk, Decimal::from(1_000_000)), Ok(()));
+
+        // Missing spender means no allowance
+        assert!(resp.check_allowance("0xdead", Decimal::ONE).is_err());
+    }
+
+    #[test]
+    fn test_gamma_market_meta() {
+        let json = r#"[{
+            "id": "516710",
+            "question": "Will BTC be above $100k on Jan 31?",
+            "conditionId": "0x9b2c6e1f7a3d4c5b8e0f1a2b3c4d5e6f7a8b9c0d1e2f3a4b5c6d7e8f9a0b1c2d",
+            "slug": "btc-above-100k-jan-31",
+            "endDate": "2025-01-31T12:00:00Z",
+            "outcomes": "[\"Yes\", \"No\"]",
+            "outcomePrices": "[\"0.55\", \"0.45\"]",
+            "clobTokenIds": "[\"71321045679252212594626385532706912750332728571942532289631379312455583992563\", \"52114319501245915516055106046884209969926127482827954674443846427813813222426\"]",
+            "active": true,
+            "closed": false,
+            "negRisk": true,
+            "orderPriceMinTickSize": 0.001,
+            "orderMinSize": 5
+        }]"#;
+        let markets: Vec<PolymarketGammaMarket> = serde_json::from_str(json).unwrap();
+        let meta = markets[0].meta().unwrap();
+
+        assert_eq!(
+            meta.condition_id,
+            "0x9b2c6e1f7a3d4c5b8e0f1a2b3c4d5e6f7a8b9c0d1e2f3a4b5c6d7e8f9a0b1c2d"
+        );
+        assert_eq!(
+            meta.yes_token,
+            "71321045679252212594626385532706912750332728571942532289631379312455583992563"
+        );
+        assert_eq!(
+            meta.no_token,
+            "52114319501245915516055106046884209969926127482827954674443846427813813222426"
+        );
+        assert!(meta.neg_risk);
+        assert_eq!(meta.tick_size, Some(Decimal::new(1, 3)));
+        assert_eq!(meta.min_order_size, Some(Decimal::from(5)));
+        assert!(!meta.closed);
+    }
+
+    #[test]
+    fn test_gamma_market_meta_outcome_order() {
+        let json = r#"{
+            "conditionId": "0xabc",
+            "outcomes": "[\"No\", \"Yes\"]",
+            "clobTokenIds": "[\"111\", \"222\"]",
+            "active": true,
+            "closed": true
+        }"#;
+        let market: PolymarketGammaMarket = serde_json::from_str(json).unwrap();
+        let meta = market.meta().unwrap();
+
+        assert_eq!((meta.yes_token.as_str(), meta.no_token.as_str()), ("222", "111"));
+        assert!(!meta.neg_risk);
+        assert_eq!(meta.tick_size, None);
+        assert_eq!(meta.min_order_size, None);
+        assert!(meta.closed);
+
+        let json = r#"{
+            "conditionId": "0xabc",
+            "outcomes": "[\"A\", \"B\", \"C\"]",
+            "clobTokenIds": "[\"1\", \"2\", \"3\"]"
+        }"#;
+        let market: PolymarketGammaMarket = serde_json::from_str(json).unwrap();
+        assert!(market.meta().is_err());
+    }
+
+    #[test]
+    fn test_matched_response_without_amounts_fully_filled() {
+        let json = r#"{"success": true, "orderID": "0xabc", "status": "matched"}"#;
+        let resp: PolymarketOrderResponse = serde_json::from_str(json).unwrap();
+
+        assert_eq!(
+            resp.filled_quantity(Side::Buy, Decimal::from(100), PolymarketOrderType::Fok),
+            Decimal::from(100)
+        );
+        // FAK orders may have filled partially, so wait for status polling
+        assert_eq!(
+            resp.filled_quantity(Side::Buy, Decimal::from(100), PolymarketOrderType::Fak),
+            Decimal::ZERO
+        );
+    }
+
+    #[test]
+    fn test_partially_matched_fak_response() {
+        let json = r#"{
+            "success": true,
+            "orderID": "0xabc",
+            "status": "matched",
+            "makingAmount": "16.5",
+            "takingAmount": "30"
+        }"#;
+        let resp: PolymarketOrderResponse = serde_json::from_str(json).unwrap();
+
+        assert_eq!(
+            resp.filled_quantity(Side::Buy, Decimal::from(100), PolymarketOrderType::Fak),
+            Decimal::from(30)
+        );
+    }
+}
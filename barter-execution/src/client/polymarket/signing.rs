@@ -49,13 +49,18 @@ sol! {
     }
 }
 
+/// Exchange contract that settles (and so spends USDC for) orders.
+pub(crate) fn exchange_address(neg_risk: bool) -> &'static str {
+    if neg_risk {
+        NEG_RISK_CTF_EXCHANGE
+    } else {
+        CTF_EXCHANGE
+    }
+}
+
 /// EIP-712 domain separator for Polymarket CTF Exchange.
 fn eip712_domain(neg_risk: bool) -> alloy_sol_types::Eip712Domain {
-    let contract: Address = if neg_risk {
-        NEG_RISK_CTF_EXCHANGE.parse().unwrap()
-    } else {
-        CTF_EXCHANGE.parse().unwrap()
-    };
+    let contract: Address = exchange_address(neg_risk).parse().unwrap();
 
     alloy_sol_types::Eip712Domain {
         name: Some("Polymarket CTF Exchange".into()),
@@ -2,6 +2,11 @@
 //!
 //! Connects to the authenticated Polymarket user WS channel for real-time
 //! trade fill notifications. Requires periodic PING keepalive (10s).
+//!
+//! The fill stream ends when the server closes the socket. The account stream
+//! ends with it, so the execution manager's reconnecting stream re-auths and
+//! re-subscribes with backoff, and the engine is notified that fills may have
+//! been missed.
 
 use crate::{
     AccountEvent, AccountEventKind, UnindexedAccountEvent,
@@ -14,13 +19,13 @@ use barter_instrument::{
     exchange::ExchangeId,
     instrument::name::InstrumentNameExchange,
 };
-use barter_integration::protocol::websocket::{WebSocket, WsMessage, connect};
+use barter_integration::protocol::websocket::{WebSocket, WsError, WsMessage, connect};
 use chrono::Utc;
-use futures::{SinkExt, StreamExt, stream::BoxStream};
+use futures::{SinkExt, Stream, StreamExt, stream::BoxStream};
 use rust_decimal::Decimal;
 use serde::Deserialize;
 use smol_str::SmolStr;
-use std::str::FromStr;
+use std::{future::ready, str::FromStr};
 use tokio::task::JoinHandle;
 use tracing::{debug, info, warn};
 
@@ -78,6 +83,18 @@ pub async fn connect_polymarket_user(
         .parse()
         .map_err(|e: url::ParseError| barter_integration::error::SocketError::UrlParse(e))?;
 
+    connect_polymarket_user_url(url, api_key, api_secret, api_passphrase, markets).await
+}
+
+/// Connect to a Polymarket user WebSocket at `url` and authenticate, see
+/// [`connect_polymarket_user`].
+pub async fn connect_polymarket_user_url(
+    url: url::Url,
+    api_key: &str,
+    api_secret: &str,
+    api_passphrase: &str,
+    markets: &[String],
+) -> Result<WebSocket, barter_integration::error::SocketError> {
     let mut ws = connect(url).await?;
     info!("Connected to Polymarket user WebSocket");
 
@@ -132,9 +149,7 @@ pub fn spawn_ping_task(
 /// Convert a Polymarket user WebSocket into a stream of `AccountEvent::Trade`
 /// events plus a ping keepalive handle.
 ///
-/// Filters for trade events with `status == "MATCHED"` (fastest fill signal).
-/// Sums `maker_orders[].matched_amount` for total quantity, computes weighted
-/// average price.
+/// The stream ends when the server closes the socket or a read fails.
 pub fn polymarket_fill_stream(
     ws: WebSocket,
 ) -> (BoxStream<'static, UnindexedAccountEvent>, JoinHandle<()>) {
@@ -142,119 +157,282 @@ pub fn polymarket_fill_stream(
 
     let ping_handle = spawn_ping_task(sink);
 
-    let mapped = stream.filter_map(|result| async move {
-        let msg = match result {
-            Ok(m) => m,
-            Err(e) => {
-                warn!(error = %e, "Polymarket user WS read error");
-                return None;
-            }
-        };
+    (Box::pin(fill_events(stream)), ping_handle)
+}
 
-        let text = match msg {
-            WsMessage::Text(t) => t,
-            WsMessage::Ping(_) | WsMessage::Pong(_) => return None,
-            WsMessage::Close(_) => {
-                warn!("Polymarket user WS closed by server");
-                return None;
-            }
-            _ => return None,
-        };
+/// Map user WS messages to `AccountEvent::Trade` events, ending at the first
+/// close frame or read error.
+pub fn fill_events<S>(messages: S) -> impl Stream<Item = UnindexedAccountEvent> + Send + 'static
+where
+    S: Stream<Item = Result<WsMessage, WsError>> + Send + 'static,
+{
+    messages
+        .take_while(|result| {
+            ready(match result {
+                Ok(WsMessage::Close(_)) => {
+                    warn!("Polymarket user WS closed by server");
+                    false
+                }
+                Err(e) => {
+                    warn!(error = %e, "Polymarket user WS read error");
+                    false
+                }
+                Ok(_) => true,
+            })
+        })
+        .filter_map(|result| ready(result.ok().and_then(parse_fill)))
+}
 
-        let trimmed = text.trim();
-        if trimmed.eq_ignore_ascii_case("PONG") || trimmed.eq_ignore_ascii_case("PING") {
-            return None;
-        }
+/// Parse a user WS message into a fill, if it is a MATCHED trade.
+///
+/// Sums `maker_orders[].matched_amount` for total quantity, computes weighted
+/// average price.
+fn parse_fill(msg: WsMessage) -> Option<UnindexedAccountEvent> {
+    let text = match msg {
+        WsMessage::Text(t) => t,
+        _ => return None,
+    };
 
-        let envelope: PolymarketUserEnvelope = match serde_json::from_str(trimmed) {
-            Ok(e) => e,
-            Err(e) => {
-                debug!(error = %e, payload = %trimmed, "Failed to parse Polymarket user WS message");
-                return None;
-            }
-        };
+    let trimmed = text.trim();
+    if trimmed.eq_ignore_ascii_case("PONG") || trimmed.eq_ignore_ascii_case("PING") {
+        return None;
+    }
 
-        let event_type = envelope.event_type.as_deref().unwrap_or("");
-        if event_type != "trade" {
-            debug!(event_type = %event_type, "Ignoring non-trade Polymarket user WS event");
+    let envelope: PolymarketUserEnvelope = match serde_json::from_str(trimmed) {
+        Ok(e) => e,
+        Err(e) => {
+            debug!(error = %e, payload = %trimmed, "Failed to parse Polymarket user WS message");
             return None;
         }
+    };
 
-        let trade: PolymarketWsTrade = match serde_json::from_value(envelope.data) {
-            Ok(t) => t,
-            Err(e) => {
-                debug!(error = %e, "Failed to parse Polymarket trade payload");
-                return None;
-            }
-        };
+    let event_type = envelope.event_type.as_deref().unwrap_or("");
+    if event_type != "trade" {
+        debug!(event_type = %event_type, "Ignoring non-trade Polymarket user WS event");
+        return None;
+    }
 
-        // Only process MATCHED fills (fastest signal)
-        let status = trade.status.as_deref().unwrap_or("");
-        if status != "MATCHED" {
-            debug!(status = %status, "Skipping non-MATCHED Polymarket trade");
+    let trade: PolymarketWsTrade = match serde_json::from_value(envelope.data) {
+        Ok(t) => t,
+        Err(e) => {
+            debug!(error = %e, "Failed to parse Polymarket trade payload");
             return None;
         }
+    };
 
-        let asset_id = trade.asset_id.as_deref().unwrap_or("unknown");
-        let trade_id = trade.id.as_deref().unwrap_or("unknown");
+    // Only process MATCHED fills (fastest signal)
+    let status = trade.status.as_deref().unwrap_or("");
+    if status != "MATCHED" {
+        debug!(status = %status, "Skipping non-MATCHED Polymarket trade");
+        return None;
+    }
 
-        let side = match trade.side.as_deref() {
-            Some("BUY") => Side::Buy,
-            _ => Side::Sell,
-        };
+    let asset_id = trade.asset_id.as_deref().unwrap_or("unknown");
+    let trade_id = trade.id.as_deref().unwrap_or("unknown");
 
-        // Sum maker_orders matched amounts and compute weighted average price
-        let maker_orders = trade.maker_orders.as_deref().unwrap_or(&[]);
-        let mut total_quantity = Decimal::ZERO;
-        let mut total_cost = Decimal::ZERO;
-
-        for mo in maker_orders {
-            let qty = mo
-                .matched_amount
-                .as_deref()
-                .and_then(|s| Decimal::from_str(s).ok())
-                .unwrap_or(Decimal::ZERO);
-            let px = mo
-                .price
-                .as_deref()
-                .and_then(|s| Decimal::from_str(s).ok())
-                .unwrap_or(Decimal::ZERO);
-            total_quantity += qty;
-            total_cost += qty * px;
-        }
+    let side = match trade.side.as_deref() {
+        Some("BUY") => Side::Buy,
+        _ => Side::Sell,
+    };
+
+    // Sum maker_orders matched amounts and compute weighted average price
+    let maker_orders = trade.maker_orders.as_deref().unwrap_or(&[]);
+    let mut total_quantity = Decimal::ZERO;
+    let mut total_cost = Decimal::ZERO;
+
+    for mo in maker_orders {
+        let qty = mo
+            .matched_amount
+            .as_deref()
+            .and_then(|s| Decimal::from_str(s).ok())
+            .unwrap_or(Decimal::ZERO);
+        let px = mo
+            .price
+            .as_deref()
+            .and_then(|s| Decimal::from_str(s).ok())
+            .unwrap_or(Decimal::ZERO);
+        total_quantity += qty;
+        total_cost += qty * px;
+    }
+
+    let avg_price = if total_quantity > Decimal::ZERO {
+        total_cost / total_quantity
+    } else {
+        Decimal::ZERO
+    };
+
+    info!(
+        trade_id = %trade_id,
+        asset_id = %asset_id,
+        side = ?side,
+        quantity = %total_quantity,
+        avg_price = %avg_price,
+        "Polymarket trade fill received via WS"
+    );
+
+    Some(AccountEvent {
+        exchange: ExchangeId::Polymarket,
+        kind: AccountEventKind::Trade(Trade {
+            id: TradeId(SmolStr::new(trade_id)),
+            order_id: OrderId(SmolStr::new(
+                trade.taker_order_id.as_deref().unwrap_or("unknown"),
+            )),
+            instrument: InstrumentNameExchange::from(asset_id),
+            strategy: StrategyId::new("unknown"),
+            time_exchange: Utc::now(),
+            side,
+            price: avg_price,
+            quantity: total_quantity,
+            fees: AssetFees::new(QuoteAsset, Decimal::ZERO),
+        }),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::merge_until_fills_end;
+    use barter_data::streams::{
+        consumer::StreamKey,
+        reconnect::{
+            Event,
+            stream::{ReconnectingStream, ReconnectionBackoffPolicy, init_reconnecting_stream},
+        },
+    };
+    use futures::stream;
+    use std::sync::{Arc, Mutex};
+
+    const TRADE: &str = r#"{
+        "event_type": "trade",
+        "id": "trade-1",
+        "status": "MATCHED",
+        "side": "BUY",
+        "asset_id": "123",
+        "taker_order_id": "0xorder",
+        "maker_orders": [{"order_id": "0xmaker", "matched_amount": "10", "price": "0.55"}]
+    }"#;
 
-        let avg_price = if total_quantity > Decimal::ZERO {
-            total_cost / total_quantity
-        } else {
-            Decimal::ZERO
+    fn trade_msg() -> WsMessage {
+        WsMessage::text(TRADE.to_string())
+    }
+
+    #[tokio::test]
+    async fn test_fill_events_end_on_close() {
+        let messages = stream::iter(vec![
+            Ok(trade_msg()),
+            Ok(WsMessage::text("PONG".to_string())),
+            Ok(WsMessage::Close(None)),
+            Ok(trade_msg()),
+        ]);
+
+        let events: Vec<_> = fill_events(messages).collect().await;
+        assert_eq!(events.len(), 1);
+        let AccountEventKind::Trade(trade) = &events[0].kind else {
+            panic!("expected trade, got {:?}", events[0].kind);
         };
+        assert_eq!(trade.quantity, Decimal::from(10));
+        assert_eq!(trade.price, Decimal::new(55, 2));
+    }
+
+    #[tokio::test]
+    async fn test_fill_events_end_on_read_error() {
+        let messages = stream::iter(vec![Err(WsError::ConnectionClosed), Ok(trade_msg())]);
+        assert_eq!(fill_events(messages).count().await, 0);
+    }
 
-        info!(
-            trade_id = %trade_id,
-            asset_id = %asset_id,
-            side = ?side,
-            quantity = %total_quantity,
-            avg_price = %avg_price,
-            "Polymarket trade fill received via WS"
-        );
-
-        Some(AccountEvent {
-            exchange: ExchangeId::Polymarket,
-            kind: AccountEventKind::Trade(Trade {
-                id: TradeId(SmolStr::new(trade_id)),
-                order_id: OrderId(SmolStr::new(
-                    trade.taker_order_id.as_deref().unwrap_or("unknown"),
-                )),
-                instrument: InstrumentNameExchange::from(asset_id),
-                strategy: StrategyId::new("unknown"),
-                time_exchange: Utc::now(),
-                side,
-                price: avg_price,
-                quantity: total_quantity,
-                fees: AssetFees::new(QuoteAsset, Decimal::ZERO),
-            }),
+    #[tokio::test]
+    async fn test_closed_user_ws_ends_account_stream() {
+        // Balance polling never ends, so only the closed fill stream can end the
+        // account stream and trigger a reconnect
+        let balances = stream::pending();
+        let fills = fill_events(stream::iter(vec![Ok(trade_msg()), Ok(WsMessage::Close(None))]));
+
+        let events = tokio::time::timeout(
+            std::time::Duration::from_secs(1),
+            merge_until_fills_end(ExchangeId::Polymarket, balances, fills).collect::<Vec<_>>(),
+        )
+        .await
+        .expect("account stream should end when the user WS closes");
+        assert_eq!(events.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_closed_user_ws_reconnects_and_resubscribes() {
+        // Mock server recording the auth message of each connection, then sending one
+        // fill and closing the first connection
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = url::Url::parse(&format!("ws://{}", listener.local_addr().unwrap())).unwrap();
+        let received = Arc::new(Mutex::new(Vec::new()));
+
+        tokio::spawn({
+            let received = Arc::clone(&received);
+            async move {
+                let mut connections = Vec::new();
+                while let Ok((stream, _)) = listener.accept().await {
+                    let mut websocket = tokio_tungstenite::accept_async(stream).await.unwrap();
+                    if let Some(Ok(message)) = websocket.next().await {
+                        received.lock().unwrap().push(message.into_text().unwrap().to_string());
+                    }
+                    websocket.send(trade_msg()).await.unwrap();
+                    if connections.is_empty() {
+                        let _ = websocket.close(None).await;
+                    }
+                    connections.push(websocket);
+                }
+            }
+        });
+
+        // Initialised like the execution manager initialises the account stream
+        let account_stream = init_reconnecting_stream(move || {
+            let url = url.clone();
+            async move {
+                let markets = ["0xcondition".to_string()];
+                let websocket =
+                    connect_polymarket_user_url(url, "key", "secret", "passphrase", &markets)
+                        .await?;
+                let (fills, _ping_handle) = polymarket_fill_stream(websocket);
+                Ok::<_, barter_integration::error::SocketError>(merge_until_fills_end(
+                    ExchangeId::Polymarket,
+                    stream::pending(),
+                    fills,
+                ))
+            }
         })
-    });
+        .await
+        .unwrap()
+        .with_reconnect_backoff(
+            ReconnectionBackoffPolicy::new(1, 1, 1),
+            StreamKey::new_general("account_stream", ExchangeId::Polymarket),
+        )
+        .with_reconnection_events(ExchangeId::Polymarket);
+
+        let events = tokio::time::timeout(
+            std::time::Duration::from_secs(5),
+            account_stream.take(3).collect::<Vec<_>>(),
+        )
+        .await
+        .expect("account stream should reconnect after the user WS closes");
+
+        // A fill, the reconnect signal telling the engine fills may have been missed,
+        // then a fill received over the new connection
+        assert!(matches!(
+            &events[..],
+            [
+                Event::Item(first),
+                Event::Reconnecting(ExchangeId::Polymarket),
+                Event::Item(second),
+            ] if matches!(first.kind, AccountEventKind::Trade(_))
+                && matches!(second.kind, AccountEventKind::Trade(_))
+        ), "{events:?}");
 
-    (Box::pin(mapped), ping_handle)
+        // Each connection re-authenticated and re-subscribed to the same markets
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 2);
+        for message in received.iter() {
+            let message = serde_json::from_str::<serde_json::Value>(message).unwrap();
+            assert_eq!(message["type"], "user");
+            assert_eq!(message["markets"], serde_json::json!(["0xcondition"]));
+            assert_eq!(message["auth"]["apiKey"], "key");
+        }
+    }
 }
@@ -1,9 +1,11 @@
+use crate::order::TimeInForce;
 use barter_instrument::{
     asset::{AssetIndex, name::AssetNameExchange},
     exchange::ExchangeId,
     instrument::{InstrumentIndex, name::InstrumentNameExchange},
 };
 use barter_integration::error::SocketError;
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
@@ -97,6 +99,24 @@ pub enum ApiError<AssetKey = AssetIndex, InstrumentKey = InstrumentIndex> {
     OrderAlreadyCancelled,
     #[error("order already fully filled")]
     OrderAlreadyFullyFilled,
+    /// The order does not exist, or is no longer open, on the exchange.
+    #[error("order not found")]
+    OrderNotFound,
+    /// The exchange cannot honour the requested [`TimeInForce`].
+    #[error("time in force {0} unsupported")]
+    TimeInForceUnsupported(TimeInForce),
+    /// The order price cannot be represented on the exchange.
+    ///
+    /// For example:
+    /// - The price lies outside the exchange's tradeable range.
+    #[error("price {0} invalid: {1}")]
+    PriceInvalid(Decimal, String),
+    /// The order quantity cannot be represented on the exchange.
+    ///
+    /// For example:
+    /// - The quantity is zero, or a fraction of a whole contract.
+    #[error("quantity {0} invalid: {1}")]
+    QuantityInvalid(Decimal, String),
 }
 
 /// Represents all errors that can be generated when cancelling or opening orders.
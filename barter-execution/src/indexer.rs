@@ -2,6 +2,7 @@ use crate::{
     AccountEvent, AccountEventKind, AccountSnapshot, InstrumentAccountSnapshot,
     UnindexedAccountEvent, UnindexedAccountSnapshot,
     balance::AssetBalance,
+    client::InstrumentPosition,
     error::{
         ApiError, ClientError, KeyError, OrderError, UnindexedApiError, UnindexedClientError,
         UnindexedOrderError,
@@ -63,6 +64,9 @@ impl AccountEventIndexer {
                 AccountEventKind::OrderCancelled(self.order_response_cancel(response)?)
             }
             AccountEventKind::Trade(trade) => AccountEventKind::Trade(self.trade(trade)?),
+            AccountEventKind::PositionSnapshot(snapshot) => {
+                AccountEventKind::PositionSnapshot(self.position(snapshot.0).map(Snapshot)?)
+            }
         };
 
         Ok(AccountEvent { exchange, kind })
@@ -88,7 +92,11 @@ impl AccountEventIndexer {
         let instruments = instruments
             .into_iter()
             .map(|snapshot| {
-                let InstrumentAccountSnapshot { instrument, orders } = snapshot;
+                let InstrumentAccountSnapshot {
+                    instrument,
+                    orders,
+                    position,
+                } = snapshot;
 
                 let instrument = self.map.find_instrument_index(&instrument)?;
 
@@ -97,7 +105,15 @@ impl AccountEventIndexer {
                     .map(|order| self.order_snapshot(order))
                     .collect::<Result<Vec<_>, _>>()?;
 
-                Ok(InstrumentAccountSnapshot { instrument, orders })
+                let position = position
+                    .map(|position| self.position(position))
+                    .transpose()?;
+
+                Ok(InstrumentAccountSnapshot {
+                    instrument,
+                    orders,
+                    position,
+                })
             })
             .collect::<Result<Vec<_>, _>>()?;
 
@@ -216,6 +232,14 @@ impl AccountEventIndexer {
             UnindexedApiError::OrderRejected(reason) => ApiError::OrderRejected(reason),
             UnindexedApiError::OrderAlreadyCancelled => ApiError::OrderAlreadyCancelled,
             UnindexedApiError::OrderAlreadyFullyFilled => ApiError::OrderAlreadyFullyFilled,
+            UnindexedApiError::OrderNotFound => ApiError::OrderNotFound,
+            UnindexedApiError::TimeInForceUnsupported(time_in_force) => {
+                ApiError::TimeInForceUnsupported(time_in_force)
+            }
+            UnindexedApiError::PriceInvalid(price, reason) => ApiError::PriceInvalid(price, reason),
+            UnindexedApiError::QuantityInvalid(quantity, reason) => {
+                ApiError::QuantityInvalid(quantity, reason)
+            }
         })
     }
 
@@ -267,6 +291,23 @@ impl AccountEventIndexer {
         })
     }
 
+    pub fn position(
+        &self,
+        position: InstrumentPosition,
+    ) -> Result<InstrumentPosition<InstrumentIndex>, IndexError> {
+        let InstrumentPosition {
+            instrument,
+            quantity,
+            cost_basis,
+        } = position;
+
+        Ok(InstrumentPosition {
+            instrument: self.map.find_instrument_index(&instrument)?,
+            quantity,
+            cost_basis,
+        })
+    }
+
     pub fn trade(
         &self,
         trade: Trade<QuoteAsset, InstrumentNameExchange>,
@@ -25,6 +25,7 @@
 
 use crate::{
     balance::AssetBalance,
+    client::InstrumentPosition,
     order::{Order, OrderSnapshot, request::OrderResponseCancel},
     trade::Trade,
 };
@@ -98,6 +99,9 @@ pub enum AccountEventKind<ExchangeKey, AssetKey, InstrumentKey> {
 
     /// [`Order<ExchangeKey, InstrumentKey, Open>`] partial or full-fill.
     Trade(Trade<QuoteAsset, InstrumentKey>),
+
+    /// Single [`InstrumentPosition`] snapshot - replaces the locally tracked position.
+    PositionSnapshot(Snapshot<InstrumentPosition<InstrumentKey>>),
 }
 
 impl<ExchangeKey, AssetKey, InstrumentKey> AccountEvent<ExchangeKey, AssetKey, InstrumentKey>
@@ -137,6 +141,8 @@ pub struct InstrumentAccountSnapshot<
     pub instrument: InstrumentKey,
     #[serde(default = "Vec::new")]
     pub orders: Vec<OrderSnapshot<ExchangeKey, AssetKey, InstrumentKey>>,
+    /// Net holding reported by the exchange, if it reports positions
+    pub position: Option<InstrumentPosition<InstrumentKey>>,
 }
 
 impl<ExchangeKey, AssetKey, InstrumentKey> AccountSnapshot<ExchangeKey, AssetKey, InstrumentKey> {
@@ -9,6 +9,7 @@ use barter_instrument::{
     exchange::{ExchangeId, ExchangeIndex},
     instrument::{InstrumentIndex, name::InstrumentNameExchange},
 };
+use chrono::{DateTime, Utc};
 use derive_more::{Constructor, Display};
 use id::ClientOrderId;
 use rust_decimal::Decimal;
@@ -168,6 +169,8 @@ pub enum TimeInForce {
     GoodUntilEndOfDay,
     FillOrKill,
     ImmediateOrCancel,
+    /// Rest until filled, cancelled, or the `expiry` elapses.
+    GoodUntilDate { expiry: DateTime<Utc> },
 }
 
 impl<ExchangeKey, InstrumentKey> From<&OrderRequestOpen<ExchangeKey, InstrumentKey>>
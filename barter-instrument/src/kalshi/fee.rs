@@ -0,0 +1,68 @@
+use rust_decimal::{Decimal, RoundingStrategy};
+
+/// Kalshi taker fee rate, applied to the expected earnings `price * (1 - price)` of each
+/// contract.
+pub const TAKER_FEE_RATE: Decimal = Decimal::from_parts(7, 0, 0, false, 2);
+
+/// Fee Kalshi bills for a taker fill of `contracts` at a decimal `price` (0-1), in dollars.
+///
+/// Kalshi bills `0.07 * contracts * price * (1 - price)` rounded up to the next cent, so
+/// unlike an estimate this is the exact amount debited for the fill.
+///
+/// See docs: <https://kalshi.com/docs/kalshi-fee-schedule.pdf>
+pub fn taker_fee(price: Decimal, contracts: Decimal) -> Decimal {
+    taker_fee_at_rate(TAKER_FEE_RATE, price, contracts)
+}
+
+/// Fee for a taker fill as [`taker_fee`], billed at `rate` rather than [`TAKER_FEE_RATE`].
+pub fn taker_fee_at_rate(rate: Decimal, price: Decimal, contracts: Decimal) -> Decimal {
+    (rate * contracts * price * (Decimal::ONE - price))
+        .round_dp_with_strategy(2, RoundingStrategy::AwayFromZero)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_taker_fee() {
+        struct TestCase {
+            price: Decimal,
+            contracts: Decimal,
+            expected: Decimal,
+        }
+
+        let tests = vec![
+            TestCase {
+                // TC0: exact cent amount is not rounded
+                price: dec!(0.50),
+                contracts: dec!(100),
+                expected: dec!(1.75),
+            },
+            TestCase {
+                // TC1: fraction of a cent rounds up
+                price: dec!(0.45),
+                contracts: dec!(10),
+                expected: dec!(0.18),
+            },
+            TestCase {
+                // TC2: a single cheap contract still costs a cent
+                price: dec!(0.01),
+                contracts: dec!(1),
+                expected: dec!(0.01),
+            },
+            TestCase {
+                // TC3: no contracts, no fee
+                price: dec!(0.45),
+                contracts: dec!(0),
+                expected: dec!(0),
+            },
+        ];
+
+        for (index, test) in tests.into_iter().enumerate() {
+            let actual = taker_fee(test.price, test.contracts);
+            assert_eq!(actual, test.expected, "TC{index} failed");
+        }
+    }
+}
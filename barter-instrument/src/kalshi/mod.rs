@@ -0,0 +1,8 @@
+/// Conversions between Kalshi cent prices and Barter decimal prices.
+pub mod price;
+
+/// Kalshi tick sizes.
+pub mod tick;
+
+/// Fees Kalshi bills on fills.
+pub mod fee;
@@ -0,0 +1,285 @@
+use crate::Side;
+use rust_decimal::{Decimal, RoundingStrategy, prelude::ToPrimitive};
+
+/// Lowest price, in cents, a Kalshi contract can trade at.
+pub const MIN_PRICE_CENTS: u32 = 1;
+
+/// Highest price, in cents, a Kalshi contract can trade at.
+pub const MAX_PRICE_CENTS: u32 = 99;
+
+/// Ticks per dollar of a Kalshi price held in ticks (hundredths of a cent), the
+/// finest resolution of sub-cent prices.
+pub const TICKS_PER_DOLLAR: u32 = 10_000;
+
+/// Ticks per cent, see [`TICKS_PER_DOLLAR`].
+pub const TICKS_PER_CENT: u32 = 100;
+
+/// Convert a decimal price (0-1) to the nearest tradeable Kalshi price in cents (1-99).
+///
+/// Half-cent prices round to the nearest even cent (banker's rounding), and prices outside the
+/// tradeable range clamp to [`MIN_PRICE_CENTS`] or [`MAX_PRICE_CENTS`].
+pub fn to_cents(price: Decimal) -> u32 {
+    (price * Decimal::ONE_HUNDRED)
+        .round_dp_with_strategy(0, RoundingStrategy::MidpointNearestEven)
+        .clamp(Decimal::from(MIN_PRICE_CENTS), Decimal::from(MAX_PRICE_CENTS))
+        .to_u32()
+        .unwrap_or(MIN_PRICE_CENTS)
+}
+
+/// Convert a Kalshi amount in cents (eg/ a price, balance or exposure) to dollars.
+pub fn from_cents(cents: impl Into<Decimal>) -> Decimal {
+    cents.into() / Decimal::ONE_HUNDRED
+}
+
+/// Round a decimal price (0-1) of an order on `side` to a multiple of `tick_size` tradeable
+/// on Kalshi.
+///
+/// Prices round in the direction that never worsens the order: buys round down (never pay
+/// more) and sells round up (never receive less). Returns `None` if the rounded price lies
+/// outside the tradeable range of one tick above 0 to one tick below 1, rather than clamping
+/// it to a price the caller did not ask for.
+pub fn round_to_tick(price: Decimal, tick_size: Decimal, side: Side) -> Option<Decimal> {
+    let strategy = match side {
+        Side::Buy => RoundingStrategy::ToNegativeInfinity,
+        Side::Sell => RoundingStrategy::ToPositiveInfinity,
+    };
+    let rounded = (price / tick_size).round_dp_with_strategy(0, strategy) * tick_size;
+
+    (tick_size..=Decimal::ONE - tick_size)
+        .contains(&rounded)
+        .then(|| rounded.normalize())
+}
+
+/// Convert a decimal price (0-1) to ticks, see [`TICKS_PER_DOLLAR`].
+///
+/// Prices finer than a tick round to the nearest even tick, and negative prices map to 0.
+pub fn to_ticks(price: Decimal) -> u32 {
+    (price * Decimal::from(TICKS_PER_DOLLAR))
+        .round_dp_with_strategy(0, RoundingStrategy::MidpointNearestEven)
+        .to_u32()
+        .unwrap_or(0)
+}
+
+/// Convert a price in ticks to a decimal price (0-1), see [`TICKS_PER_DOLLAR`].
+pub fn from_ticks(ticks: u32) -> Decimal {
+    (Decimal::from(ticks) / Decimal::from(TICKS_PER_DOLLAR)).normalize()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_to_cents() {
+        struct TestCase {
+            input: Decimal,
+            expected: u32,
+        }
+
+        let tests = vec![
+            TestCase {
+                // TC0: whole cent
+                input: dec!(0.42),
+                expected: 42,
+            },
+            TestCase {
+                // TC1: below half a cent rounds down
+                input: dec!(0.424),
+                expected: 42,
+            },
+            TestCase {
+                // TC2: above half a cent rounds up
+                input: dec!(0.426),
+                expected: 43,
+            },
+            TestCase {
+                // TC3: half cent rounds to even, down
+                input: dec!(0.125),
+                expected: 12,
+            },
+            TestCase {
+                // TC4: half cent rounds to even, up
+                input: dec!(0.135),
+                expected: 14,
+            },
+            TestCase {
+                // TC5: half a cent rounds to 0, clamped to the minimum
+                input: dec!(0.005),
+                expected: 1,
+            },
+            TestCase {
+                // TC6: one and a half cents rounds to even
+                input: dec!(0.015),
+                expected: 2,
+            },
+            TestCase {
+                // TC7: 99.5 cents rounds to 100, clamped to the maximum
+                input: dec!(0.995),
+                expected: 99,
+            },
+            TestCase {
+                // TC8: 98.5 cents rounds to even
+                input: dec!(0.985),
+                expected: 98,
+            },
+            TestCase {
+                // TC9: zero clamps to the minimum
+                input: dec!(0),
+                expected: 1,
+            },
+            TestCase {
+                // TC10: one dollar clamps to the maximum
+                input: dec!(1),
+                expected: 99,
+            },
+            TestCase {
+                // TC11: negative prices clamp to the minimum
+                input: dec!(-0.2),
+                expected: 1,
+            },
+            TestCase {
+                // TC12: prices above one dollar clamp to the maximum
+                input: dec!(1.5),
+                expected: 99,
+            },
+        ];
+
+        for (index, test) in tests.into_iter().enumerate() {
+            assert_eq!(to_cents(test.input), test.expected, "TC{index} failed");
+        }
+    }
+
+    #[test]
+    fn test_from_cents() {
+        assert_eq!(from_cents(1u32), dec!(0.01));
+        assert_eq!(from_cents(99u32), dec!(0.99));
+        assert_eq!(from_cents(12345i64), dec!(123.45));
+        assert_eq!(from_cents(-50i64), dec!(-0.5));
+    }
+
+    #[test]
+    fn test_cents_round_trip() {
+        for cents in MIN_PRICE_CENTS..=MAX_PRICE_CENTS {
+            assert_eq!(to_cents(from_cents(cents)), cents);
+        }
+    }
+
+    #[test]
+    fn test_round_to_tick() {
+        struct TestCase {
+            price: Decimal,
+            tick_size: Decimal,
+            side: Side,
+            expected: Option<Decimal>,
+        }
+
+        let tests = vec![
+            TestCase {
+                // TC0: sub-cent price on a sub-cent tick is kept
+                price: dec!(0.505),
+                tick_size: dec!(0.001),
+                side: Side::Buy,
+                expected: Some(dec!(0.505)),
+            },
+            TestCase {
+                // TC1: buy between cents rounds down
+                price: dec!(0.505),
+                tick_size: dec!(0.01),
+                side: Side::Buy,
+                expected: Some(dec!(0.50)),
+            },
+            TestCase {
+                // TC2: sell between cents rounds up
+                price: dec!(0.505),
+                tick_size: dec!(0.01),
+                side: Side::Sell,
+                expected: Some(dec!(0.51)),
+            },
+            TestCase {
+                // TC3: buy just below a cent still rounds down
+                price: dec!(0.4299),
+                tick_size: dec!(0.01),
+                side: Side::Buy,
+                expected: Some(dec!(0.42)),
+            },
+            TestCase {
+                // TC4: sell just above a cent still rounds up
+                price: dec!(0.4201),
+                tick_size: dec!(0.01),
+                side: Side::Sell,
+                expected: Some(dec!(0.43)),
+            },
+            TestCase {
+                // TC5: whole cent is kept on either side
+                price: dec!(0.42),
+                tick_size: dec!(0.01),
+                side: Side::Sell,
+                expected: Some(dec!(0.42)),
+            },
+            TestCase {
+                // TC6: buy below one cent rounds to 0, outside the tradeable range
+                price: dec!(0.004),
+                tick_size: dec!(0.01),
+                side: Side::Buy,
+                expected: None,
+            },
+            TestCase {
+                // TC7: sell above 99 cents rounds to 1, outside the tradeable range
+                price: dec!(0.991),
+                tick_size: dec!(0.01),
+                side: Side::Sell,
+                expected: None,
+            },
+            TestCase {
+                // TC8: buy above 99 cents rounds down to 99 cents
+                price: dec!(0.999),
+                tick_size: dec!(0.01),
+                side: Side::Buy,
+                expected: Some(dec!(0.99)),
+            },
+            TestCase {
+                // TC9: one dollar is outside the tradeable range
+                price: dec!(1),
+                tick_size: dec!(0.01),
+                side: Side::Buy,
+                expected: None,
+            },
+            TestCase {
+                // TC10: negative prices are outside the tradeable range
+                price: dec!(-0.2),
+                tick_size: dec!(0.01),
+                side: Side::Sell,
+                expected: None,
+            },
+            TestCase {
+                // TC11: sub-cent tick widens the tradeable range
+                price: dec!(0.999),
+                tick_size: dec!(0.001),
+                side: Side::Sell,
+                expected: Some(dec!(0.999)),
+            },
+        ];
+
+        for (index, test) in tests.into_iter().enumerate() {
+            assert_eq!(
+                round_to_tick(test.price, test.tick_size, test.side),
+                test.expected,
+                "TC{index} failed"
+            );
+        }
+    }
+
+    #[test]
+    fn test_ticks() {
+        assert_eq!(to_ticks(dec!(0.505)), 5050);
+        assert_eq!(to_ticks(dec!(0.42)), 4200);
+        assert_eq!(to_ticks(dec!(-0.1)), 0);
+        assert_eq!(from_ticks(5050), dec!(0.505));
+        assert_eq!(from_ticks(TICKS_PER_CENT), dec!(0.01));
+
+        for cents in MIN_PRICE_CENTS..=MAX_PRICE_CENTS {
+            assert_eq!(to_ticks(from_cents(cents)), cents * TICKS_PER_CENT);
+        }
+    }
+}
@@ -0,0 +1,23 @@
+use rust_decimal::Decimal;
+
+/// Tick size of a Kalshi market with no known tick size, one cent.
+pub const DEFAULT_TICK_SIZE: Decimal = Decimal::from_parts(1, 0, 0, false, 2);
+
+/// Tick size of a Kalshi market supporting sub-cent prices, a tenth of a cent.
+///
+/// High-liquidity Kalshi markets quote sub-cent prices, and orders on them may be priced off
+/// the cent grid. Such markets carry this tick size in their instrument spec, so order
+/// submission knows not to round to whole cents.
+pub const SUBCENT_TICK_SIZE: Decimal = Decimal::from_parts(1, 0, 0, false, 3);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_tick_sizes() {
+        assert_eq!(DEFAULT_TICK_SIZE, dec!(0.01));
+        assert_eq!(SUBCENT_TICK_SIZE, dec!(0.001));
+    }
+}
@@ -39,6 +39,16 @@ pub mod instrument;
 /// indexing non-indexed collections.
 pub mod index;
 
+/// Kalshi conventions shared by the market data and execution integrations.
+///
+/// eg/ cent price conversions.
+pub mod kalshi;
+
+/// Polymarket conventions shared by the market data and execution integrations.
+///
+/// eg/ per-token tick sizes.
+pub mod polymarket;
+
 /// A keyed value.
 ///
 /// eg/ Keyed<InstrumentIndex, Instrument>
@@ -0,0 +1,2 @@
+/// Polymarket tick sizes.
+pub mod tick;
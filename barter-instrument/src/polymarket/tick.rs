@@ -0,0 +1,18 @@
+use rust_decimal::Decimal;
+
+/// Tick size of a Polymarket token with no known tick size.
+///
+/// Polymarket tightens a market's tick (eg/ from 0.01 to 0.001) as its price nears 0 or 1.
+/// Orders are submitted with the tick size carried by the token's instrument spec.
+pub const DEFAULT_TICK_SIZE: Decimal = Decimal::from_parts(1, 0, 0, false, 2);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_default_tick_size() {
+        assert_eq!(DEFAULT_TICK_SIZE, dec!(0.01));
+    }
+}
@@ -6,6 +6,9 @@ use hmac::Mac;
 /// Implementations for encoding signatures generated by a [`RequestSigner`].
 pub mod encoder;
 
+/// RSA-PSS request signing for APIs authenticated with an RSA private key.
+pub mod rsa_pss;
+
 /// API specific signing logic used by a [`RequestSigner`].
 #[allow(clippy::needless_lifetimes)]
 pub trait Signer {
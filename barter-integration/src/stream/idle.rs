@@ -0,0 +1,128 @@
+use futures::Stream;
+use pin_project::pin_project;
+use std::{
+    future::Future,
+    io,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::time::{Instant, Sleep};
+
+/// [`Stream`] wrapper that errors and ends if the inner [`Stream`] yields nothing within the
+/// idle `timeout`.
+///
+/// Guards against half-open connections, where the remote silently stops sending and the
+/// inner [`Stream`] would otherwise stay pending forever. Every item (including keep-alive
+/// frames) resets the deadline. On timeout an [`io::ErrorKind::TimedOut`] error is yielded,
+/// followed by `None`.
+///
+/// A `timeout` of `None` disables the watchdog.
+#[derive(Debug)]
+#[pin_project]
+pub struct IdleTimeout<St> {
+    #[pin]
+    stream: St,
+    timeout: Option<Duration>,
+    deadline: Option<Pin<Box<Sleep>>>,
+    timed_out: bool,
+}
+
+impl<St> IdleTimeout<St> {
+    pub fn new(stream: St, timeout: Option<Duration>) -> Self {
+        Self {
+            stream,
+            timeout,
+            deadline: timeout.map(|timeout| Box::pin(tokio::time::sleep(timeout))),
+            timed_out: false,
+        }
+    }
+}
+
+impl<St, T, E> Stream for IdleTimeout<St>
+where
+    St: Stream<Item = Result<T, E>>,
+    E: From<io::Error>,
+{
+    type Item = St::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+        if *this.timed_out {
+            return Poll::Ready(None);
+        }
+
+        if let Poll::Ready(item) = this.stream.poll_next(cx) {
+            if let (Some(deadline), Some(timeout)) = (this.deadline.as_mut(), *this.timeout) {
+                deadline.as_mut().reset(Instant::now() + timeout);
+            }
+            return Poll::Ready(item);
+        }
+
+        let (Some(deadline), Some(timeout)) = (this.deadline.as_mut(), *this.timeout) else {
+            return Poll::Pending;
+        };
+
+        match deadline.as_mut().poll(cx) {
+            Poll::Ready(()) => {
+                *this.timed_out = true;
+                let error = io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    format!("no message received within idle timeout of {timeout:?}"),
+                );
+                Poll::Ready(Some(Err(E::from(error))))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+
+    const TIMEOUT: Duration = Duration::from_secs(15);
+
+    #[tokio::test(start_paused = true)]
+    async fn test_idle_timeout_fires_on_silent_stream() {
+        let mut stream = IdleTimeout::new(
+            futures::stream::pending::<Result<(), io::Error>>(),
+            Some(TIMEOUT),
+        );
+
+        let started = Instant::now();
+        let error = stream.next().await.unwrap().unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::TimedOut);
+        assert_eq!(started.elapsed(), TIMEOUT);
+
+        // The stream ends after timing out, so it can be re-initialised
+        assert!(stream.next().await.is_none());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_idle_timeout_reset_by_regular_messages() {
+        // Messages every 10s, like a venue PING cadence, inside a 15s timeout
+        let messages = futures::stream::iter(0..5)
+            .then(|message| async move {
+                tokio::time::sleep(Duration::from_secs(10)).await;
+                Ok::<_, io::Error>(message)
+            })
+            .boxed();
+        let mut stream = IdleTimeout::new(messages, Some(TIMEOUT));
+
+        for expected in 0..5 {
+            assert_eq!(stream.next().await.unwrap().unwrap(), expected);
+        }
+        assert!(stream.next().await.is_none());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_idle_timeout_disabled() {
+        let mut stream =
+            IdleTimeout::new(futures::stream::pending::<Result<(), io::Error>>(), None);
+
+        let next = tokio::time::timeout(Duration::from_secs(3600), stream.next()).await;
+        assert!(next.is_err());
+    }
+}
@@ -8,6 +8,7 @@ use std::{
     task::{Context, Poll},
 };
 
+pub mod idle;
 pub mod indexed;
 pub mod merge;
 
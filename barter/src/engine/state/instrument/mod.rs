@@ -413,6 +413,7 @@ where
                 })
             })
             .collect(),
+        position: None,
     }
 }
 
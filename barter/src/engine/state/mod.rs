@@ -126,6 +126,14 @@ impl<GlobalData, InstrumentData> EngineState<GlobalData, InstrumentData> {
 
                     instrument_state.update_from_account_snapshot(instrument);
                     instrument_state.data.process(event);
+
+                    // Exchange reported positions reach InstrumentData like a live snapshot
+                    if let Some(position) = &instrument.position {
+                        instrument_state.data.process(&AccountEvent::new(
+                            event.exchange,
+                            AccountEventKind::PositionSnapshot(Snapshot(position.clone())),
+                        ));
+                    }
                 }
                 None
             }
@@ -159,6 +167,13 @@ impl<GlobalData, InstrumentData> EngineState<GlobalData, InstrumentData> {
                 instrument_state.data.process(event);
                 instrument_state.update_from_trade(trade)
             }
+            AccountEventKind::PositionSnapshot(position) => {
+                self.instruments
+                    .instrument_index_mut(&position.0.instrument)
+                    .data
+                    .process(event);
+                None
+            }
         };
 
         // Update any user provided GlobalData State